@@ -3,28 +3,38 @@ use std::collections::HashSet;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, ensure, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply,
-    ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+    attr, ensure, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+    Reply, ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
 use cw_utils::parse_instantiate_response_data;
 use itertools::Itertools;
 
 use astroport::asset::{addr_opt_validate, AssetInfo, PairInfo};
-use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::common::{
+    claim_ownership, drop_ownership_proposal, init_ownership_timelock, propose_new_owner,
+    update_ownership_timelock, MAX_PROPOSAL_TTL,
+};
 use astroport::factory::{
     Config, ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, MigrateMsg, PairConfig,
-    PairType, PairsResponse, QueryMsg, TrackerConfig,
+    PairCreator, PairMetadata, PairMigration, PairMigrationInfo, PairType, PairWithMetadata,
+    PairsResponse, PairsWithMetadataResponse, PauseStateResponse, QueryMsg, TrackerConfig,
 };
 use astroport::incentives::ExecuteMsg::DeactivatePool;
-use astroport::pair::InstantiateMsg as PairInstantiateMsg;
+use astroport::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg, MAX_ASSET_DECIMALS,
+};
 
 use crate::error::ContractError;
 use crate::migration::migrate_pair_configs;
 use crate::querier::query_pair_info;
 use crate::state::{
-    check_asset_infos, pair_key, read_pairs, TmpPairInfo, CONFIG, OWNERSHIP_PROPOSAL, PAIRS,
-    PAIR_CONFIGS, TMP_PAIR_INFO, TRACKER_CONFIG,
+    check_asset_infos, is_pair_creator, pair_key, read_pairs, unschedule_pair_migration,
+    OwnerPropagation, TmpPairInfo, CONFIG, DECIMALS_OVERRIDES, DEFAULT_LIMIT, FLASH_LOANS_ENABLED,
+    MAX_LIMIT, OWNERSHIP_PROPOSAL, OWNER_PROPAGATION, PAIRS, PAIRS_PAUSED, PAIRS_TO_MIGRATE,
+    PAIR_CONFIGS, PAIR_CREATION_PAUSED, PAIR_CREATORS, PAIR_CREATORS_GUARDIAN, PAIR_METADATA,
+    PAIR_MIGRATIONS, PAUSED_PAIRS, PAUSE_GUARDIAN, TMP_PAIR_INFO, TRACKER_CONFIG,
 };
 
 /// Contract name that is used for migration.
@@ -33,6 +43,8 @@ const CONTRACT_NAME: &str = "astroport-factory";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID used in a sub-message.
 const INSTANTIATE_PAIR_REPLY_ID: u64 = 1;
+/// A `reply` call code ID used for ownership proposals sent to pairs.
+const PROPAGATE_OWNERSHIP_REPLY_ID: u64 = 2;
 
 /// Creates a new contract with the specified parameters packed in the `msg` variable.
 ///
@@ -51,6 +63,7 @@ pub fn instantiate(
         token_code_id: msg.token_code_id,
         fee_address: None,
         generator_address: None,
+        auto_stake_target: None,
         whitelist_code_id: msg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(&msg.coin_registry_address)?,
     };
@@ -77,6 +90,7 @@ pub fn instantiate(
         PAIR_CONFIGS.save(deps.storage, pc.pair_type.to_string(), pc)?;
     }
     CONFIG.save(deps.storage, &config)?;
+    init_ownership_timelock(deps.storage, msg.ownership_timelock)?;
 
     if let Some(tracker_config) = msg.tracker_config {
         TRACKER_CONFIG.save(
@@ -134,6 +148,27 @@ pub struct UpdateConfig {
 /// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
 ///
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+///
+/// * **ExecuteMsg::PropagateOwnership { expires_in, limit }** Proposes the factory owner on registered pairs.
+///
+/// * **ExecuteMsg::UpdatePairMetadata { pair_addr, metadata }** Sets or removes pair display metadata.
+///
+/// * **ExecuteMsg::SchedulePairMigrations { pairs, new_code_id, migrate_msg }** Schedules code migrations of pairs.
+///
+/// * **ExecuteMsg::CancelPairMigrations { pairs }** Removes pairs from the migration schedule.
+///
+/// * **ExecuteMsg::MigratePairs { limit }** Migrates the next batch of scheduled pairs.
+///
+/// * **ExecuteMsg::UpdateFlashLoans { enabled }** Enables or disables flash loans in pairs.
+///
+/// * **ExecuteMsg::SetDecimalsOverride { asset_info, decimals }** Sets or removes the decimals override of an asset.
+///
+/// * **ExecuteMsg::UpdatePauseGuardian { guardian }** Sets or removes the pause guardian.
+///
+/// * **ExecuteMsg::UpdateAutoStakeTarget { auto_stake_target }** Sets or removes the auto-stake target.
+///
+/// * **ExecuteMsg::UpdatePauseState { pair_creation, all_pairs, pause, unpause }** Pauses or unpauses
+/// pair creation and pairs.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -197,13 +232,278 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::UpdateOwnershipTimelock { timelock } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            update_ownership_timelock(deps, info, env, timelock, config.owner).map_err(Into::into)
+        }
+        ExecuteMsg::PropagateOwnership { expires_in, limit } => {
+            propagate_ownership(deps, info, expires_in, limit)
+        }
         ExecuteMsg::UpdateTrackerConfig {
             tracker_code_id,
             token_factory_addr,
         } => update_tracker_config(deps, info, tracker_code_id, token_factory_addr),
+        ExecuteMsg::UpdatePairMetadata {
+            pair_addr,
+            metadata,
+        } => update_pair_metadata(deps, info, pair_addr, metadata),
+        ExecuteMsg::SchedulePairMigrations {
+            pairs,
+            new_code_id,
+            migrate_msg,
+        } => schedule_pair_migrations(
+            deps,
+            env,
+            info,
+            pairs,
+            PairMigration {
+                new_code_id,
+                migrate_msg,
+            },
+        ),
+        ExecuteMsg::CancelPairMigrations { pairs } => cancel_pair_migrations(deps, info, pairs),
+        ExecuteMsg::MigratePairs { limit } => migrate_pairs(deps, info, limit),
+        ExecuteMsg::UpdateFlashLoans { enabled } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+            FLASH_LOANS_ENABLED.save(deps.storage, &enabled)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "update_flash_loans"),
+                attr("enabled", enabled.to_string()),
+            ]))
+        }
+        ExecuteMsg::SetDecimalsOverride {
+            asset_info,
+            decimals,
+        } => set_decimals_override(deps, info, asset_info, decimals),
+        ExecuteMsg::UpdatePairCreators {
+            pair_type,
+            add,
+            remove,
+        } => update_pair_creators(deps, env, info, pair_type, add, remove),
+        ExecuteMsg::UpdatePairCreatorsGuardian { guardian } => {
+            let config = CONFIG.load(deps.storage)?;
+            ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+            let guardian_attr = if let Some(guardian) = addr_opt_validate(deps.api, &guardian)? {
+                PAIR_CREATORS_GUARDIAN.save(deps.storage, &guardian)?;
+                guardian.to_string()
+            } else {
+                PAIR_CREATORS_GUARDIAN.remove(deps.storage);
+                "none".to_string()
+            };
+
+            Ok(Response::new().add_attributes([
+                attr("action", "update_pair_creators_guardian"),
+                attr("guardian", guardian_attr),
+            ]))
+        }
+        ExecuteMsg::UpdatePauseGuardian { guardian } => {
+            let config = CONFIG.load(deps.storage)?;
+            ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+            let guardian_attr = if let Some(guardian) = addr_opt_validate(deps.api, &guardian)? {
+                PAUSE_GUARDIAN.save(deps.storage, &guardian)?;
+                guardian.to_string()
+            } else {
+                PAUSE_GUARDIAN.remove(deps.storage);
+                "none".to_string()
+            };
+
+            Ok(Response::new().add_attributes([
+                attr("action", "update_pause_guardian"),
+                attr("guardian", guardian_attr),
+            ]))
+        }
+        ExecuteMsg::UpdateAutoStakeTarget { auto_stake_target } => {
+            let mut config = CONFIG.load(deps.storage)?;
+            ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+            config.auto_stake_target = addr_opt_validate(deps.api, &auto_stake_target)?;
+            CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "update_auto_stake_target"),
+                attr(
+                    "auto_stake_target",
+                    auto_stake_target.unwrap_or_else(|| "none".to_string()),
+                ),
+            ]))
+        }
+        ExecuteMsg::UpdatePauseState {
+            pair_creation,
+            all_pairs,
+            pause,
+            unpause,
+        } => update_pause_state(deps, info, pair_creation, all_pairs, pause, unpause),
     }
 }
 
+/// Pauses or unpauses pair creation, all pairs at once or specific registered pairs.
+/// Pairs read the pause state from the factory with raw queries and reject swaps and liquidity provision
+/// while paused. Withdrawals are always allowed.
+///
+/// * **pair_creation** sets whether pair creation is paused.
+///
+/// * **all_pairs** sets whether all pairs are paused regardless of individually paused pairs.
+///
+/// * **pause** is a list of registered pair contract addresses to pause.
+///
+/// * **unpause** is a list of pair contract addresses to unpause.
+///
+/// ## Executor
+/// Only the owner or the pause guardian can execute this.
+pub fn update_pause_state(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_creation: Option<bool>,
+    all_pairs: Option<bool>,
+    pause: Option<Vec<String>>,
+    unpause: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let guardian = PAUSE_GUARDIAN.may_load(deps.storage)?;
+    ensure!(
+        info.sender == config.owner || Some(&info.sender) == guardian.as_ref(),
+        ContractError::Unauthorized {}
+    );
+
+    let mut attrs = vec![attr("action", "update_pause_state")];
+
+    if let Some(pair_creation) = pair_creation {
+        PAIR_CREATION_PAUSED.save(deps.storage, &pair_creation)?;
+        attrs.push(attr("pair_creation_paused", pair_creation.to_string()));
+    }
+
+    if let Some(all_pairs) = all_pairs {
+        PAIRS_PAUSED.save(deps.storage, &all_pairs)?;
+        attrs.push(attr("all_pairs_paused", all_pairs.to_string()));
+    }
+
+    for pair_addr in unpause.unwrap_or_default() {
+        let pair_addr = deps.api.addr_validate(&pair_addr)?;
+        PAUSED_PAIRS.remove(deps.storage, &pair_addr);
+        attrs.push(attr("unpaused_pair", pair_addr));
+    }
+
+    for pair_addr in pause.unwrap_or_default() {
+        let pair_addr = deps.api.addr_validate(&pair_addr)?;
+        assert_pair_registered(deps.as_ref(), &pair_addr)?;
+        PAUSED_PAIRS.save(deps.storage, &pair_addr, &())?;
+        attrs.push(attr("paused_pair", pair_addr));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Adds or removes addresses allowed to create pairs of a permissioned pair type.
+///
+/// * **pair_type** is the pair type the allowlist belongs to.
+///
+/// * **add** is a list of addresses to allow along with optional expiry timestamps.
+///
+/// * **remove** is a list of addresses to remove from the allowlist.
+///
+/// ## Executor
+/// Only the owner or the pair creators guardian can execute this.
+pub fn update_pair_creators(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair_type: PairType,
+    add: Option<Vec<(String, Option<u64>)>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let guardian = PAIR_CREATORS_GUARDIAN.may_load(deps.storage)?;
+    ensure!(
+        info.sender == config.owner || Some(&info.sender) == guardian.as_ref(),
+        ContractError::Unauthorized {}
+    );
+
+    ensure!(
+        PAIR_CONFIGS.has(deps.storage, pair_type.to_string()),
+        ContractError::PairConfigNotFound {}
+    );
+
+    let mut attrs = vec![
+        attr("action", "update_pair_creators"),
+        attr("pair_type", pair_type.to_string()),
+    ];
+
+    for addr in remove.unwrap_or_default() {
+        let addr = deps.api.addr_validate(&addr)?;
+        PAIR_CREATORS.remove(deps.storage, (pair_type.to_string(), &addr));
+        attrs.push(attr("removed_creator", addr));
+    }
+
+    for (addr, expires_at) in add.unwrap_or_default() {
+        let address = deps.api.addr_validate(&addr)?;
+        if let Some(expires_at) = expires_at {
+            ensure!(
+                expires_at > env.block.time.seconds(),
+                ContractError::PairCreatorExpiryInPast { expires_at }
+            );
+        }
+
+        PAIR_CREATORS.save(
+            deps.storage,
+            (pair_type.to_string(), &address),
+            &PairCreator {
+                address: address.clone(),
+                expires_at,
+            },
+        )?;
+        attrs.push(attr("added_creator", address));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sets or removes the decimals override of an asset.
+///
+/// * **asset_info** is the asset to override decimals for.
+///
+/// * **decimals** is the number of decimals pairs must use. The override is removed if it is None.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_decimals_override(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    decimals: Option<u8>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+    asset_info.check(deps.api)?;
+
+    let decimals_attr = if let Some(decimals) = decimals {
+        ensure!(
+            decimals <= MAX_ASSET_DECIMALS,
+            ContractError::DecimalsOverrideTooHigh {
+                max: MAX_ASSET_DECIMALS
+            }
+        );
+        DECIMALS_OVERRIDES.save(deps.storage, asset_info.to_string(), &decimals)?;
+        decimals.to_string()
+    } else {
+        DECIMALS_OVERRIDES.remove(deps.storage, asset_info.to_string());
+        "none".to_string()
+    };
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_decimals_override"),
+        attr("asset_info", asset_info.to_string()),
+        attr("decimals", decimals_attr),
+    ]))
+}
+
 /// Updates general contract settings.
 ///
 /// * **param** is an object of type [`UpdateConfig`] that contains the parameters to update.
@@ -300,6 +600,13 @@ pub fn execute_create_pair(
 
     let config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !PAIR_CREATION_PAUSED
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        ContractError::PairCreationPaused {}
+    );
+
     if PAIRS.has(deps.storage, &pair_key(&asset_infos)) {
         return Err(ContractError::PairWasCreated {});
     }
@@ -309,7 +616,15 @@ pub fn execute_create_pair(
         .load(deps.storage, pair_type.to_string())
         .map_err(|_| ContractError::PairConfigNotFound {})?;
 
-    if pair_config.permissioned && info.sender != config.owner {
+    if pair_config.permissioned
+        && info.sender != config.owner
+        && !is_pair_creator(
+            deps.storage,
+            &pair_type,
+            &info.sender,
+            env.block.time.seconds(),
+        )?
+    {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -378,10 +693,103 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 attr("pair_contract_addr", pair_contract),
             ]))
         }
+        // Pairs which don't keep their own owner or don't support ownership transfer are skipped
+        Reply {
+            id: PROPAGATE_OWNERSHIP_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new().add_attributes(vec![
+            attr("action", "skip_ownership_propagation"),
+            attr("error", err),
+        ])),
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
 
+/// Proposes the current factory owner as a new owner on registered pairs.
+/// Pairs are processed in batches of `limit` pairs starting from the last processed pair.
+/// The propagation starts from the first pair if the factory owner has changed since the last batch.
+///
+/// * **expires_in** is the time during which pair ownership proposals are still valid.
+///
+/// * **limit** is the number of pairs to process.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn propagate_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    expires_in: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ensure!(
+        expires_in <= MAX_PROPOSAL_TTL,
+        ContractError::ExpiresInTooHigh {
+            max: MAX_PROPOSAL_TTL
+        }
+    );
+
+    let last_pair_key = OWNER_PROPAGATION
+        .may_load(deps.storage)?
+        .filter(|propagation| propagation.owner == config.owner)
+        .and_then(|propagation| propagation.last_pair_key);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let pairs = PAIRS
+        .range(
+            deps.storage,
+            last_pair_key.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let messages = pairs
+        .iter()
+        .map(|(_, pair_addr)| {
+            Ok(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: pair_addr.to_string(),
+                    msg: to_json_binary(&PairExecuteMsg::ProposeNewOwner {
+                        owner: config.owner.to_string(),
+                        expires_in,
+                    })?,
+                    funds: vec![],
+                },
+                PROPAGATE_OWNERSHIP_REPLY_ID,
+            ))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let finished = pairs.len() < limit;
+    if finished {
+        OWNER_PROPAGATION.remove(deps.storage);
+    } else {
+        OWNER_PROPAGATION.save(
+            deps.storage,
+            &OwnerPropagation {
+                owner: config.owner.clone(),
+                last_pair_key: pairs.last().map(|(key, _)| key.clone()),
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "propagate_ownership"),
+            attr("owner", config.owner),
+            attr("pairs_processed", pairs.len().to_string()),
+            attr("finished", finished.to_string()),
+        ]))
+}
+
 /// Removes an existing pair from the factory.
 ///
 /// * **asset_infos** is a vector with assets for which we deregister the pair.
@@ -403,6 +811,8 @@ pub fn deregister(
 
     let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
     PAIRS.remove(deps.storage, &pair_key(&asset_infos));
+    PAIR_METADATA.remove(deps.storage, &pair_addr);
+    unschedule_pair_migration(deps.storage, &pair_addr)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(generator) = config.generator_address {
@@ -453,6 +863,171 @@ pub fn update_tracker_config(
         .add_attribute("code_id", tracker_code_id.to_string()))
 }
 
+/// Sets or removes display metadata of a pair registered in the factory.
+///
+/// * **pair_addr** pair contract address.
+///
+/// * **metadata** new pair metadata. If None, the existing metadata is removed.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn update_pair_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_addr: String,
+    metadata: Option<PairMetadata>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+    assert_pair_registered(deps.as_ref(), &pair_addr)?;
+
+    if let Some(metadata) = metadata {
+        metadata.validate()?;
+        PAIR_METADATA.save(deps.storage, &pair_addr, &metadata)?;
+    } else {
+        PAIR_METADATA.remove(deps.storage, &pair_addr);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_pair_metadata"),
+        attr("pair_contract_addr", pair_addr),
+    ]))
+}
+
+/// Checks that the pair is registered in the factory.
+fn assert_pair_registered(deps: Deps, pair_addr: &Addr) -> Result<(), ContractError> {
+    let pair_info = query_pair_info(&deps.querier, pair_addr)?;
+    let registered_addr = PAIRS.may_load(deps.storage, &pair_key(&pair_info.asset_infos))?;
+    ensure!(
+        registered_addr.as_ref() == Some(pair_addr),
+        ContractError::PairNotRegistered {
+            pair_addr: pair_addr.to_string()
+        }
+    );
+
+    Ok(())
+}
+
+/// Schedules code migrations of registered pairs. Scheduled pairs report the upcoming migration
+/// via their `migration_status` query until they are migrated with [`migrate_pairs`].
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn schedule_pair_migrations(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pairs: Vec<String>,
+    migration: PairMigration,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+    let mut pairs_to_migrate = PAIRS_TO_MIGRATE.may_load(deps.storage)?.unwrap_or_default();
+
+    for pair_addr in &pairs {
+        let pair_addr = deps.api.addr_validate(pair_addr)?;
+        assert_pair_registered(deps.as_ref(), &pair_addr)?;
+
+        let contract_info = deps.querier.query_wasm_contract_info(&pair_addr)?;
+        ensure!(
+            contract_info.admin.as_deref() == Some(env.contract.address.as_str()),
+            ContractError::FactoryIsNotPairAdmin {
+                pair_addr: pair_addr.to_string()
+            }
+        );
+
+        PAIR_MIGRATIONS.save(deps.storage, &pair_addr, &migration)?;
+        if !pairs_to_migrate.contains(&pair_addr) {
+            pairs_to_migrate.push(pair_addr);
+        }
+    }
+
+    PAIRS_TO_MIGRATE.save(deps.storage, &pairs_to_migrate)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "schedule_pair_migrations"),
+        attr("new_code_id", migration.new_code_id.to_string()),
+        attr("pairs", pairs.join(",")),
+    ]))
+}
+
+/// Removes pairs from the migration schedule.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn cancel_pair_migrations(
+    deps: DepsMut,
+    info: MessageInfo,
+    pairs: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+    for pair_addr in &pairs {
+        let pair_addr = deps.api.addr_validate(pair_addr)?;
+        ensure!(
+            unschedule_pair_migration(deps.storage, &pair_addr)?,
+            ContractError::PairMigrationNotScheduled {
+                pair_addr: pair_addr.to_string()
+            }
+        );
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_pair_migrations"),
+        attr("pairs", pairs.join(",")),
+    ]))
+}
+
+/// Migrates the next batch of scheduled pairs in the scheduling order
+/// and removes them from the schedule.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn migrate_pairs(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut pairs_to_migrate = PAIRS_TO_MIGRATE.may_load(deps.storage)?.unwrap_or_default();
+    let batch = pairs_to_migrate
+        .drain(..limit.min(pairs_to_migrate.len()))
+        .collect::<Vec<_>>();
+
+    let messages = batch
+        .iter()
+        .map(|pair_addr| {
+            let migration = PAIR_MIGRATIONS.load(deps.storage, pair_addr)?;
+            PAIR_MIGRATIONS.remove(deps.storage, pair_addr);
+
+            Ok(WasmMsg::Migrate {
+                contract_addr: pair_addr.to_string(),
+                new_code_id: migration.new_code_id,
+                msg: migration.migrate_msg,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    PAIRS_TO_MIGRATE.save(deps.storage, &pairs_to_migrate)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "migrate_pairs"),
+        attr("pairs_migrated", batch.len().to_string()),
+        attr("pairs_left", pairs_to_migrate.len().to_string()),
+    ]))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -466,6 +1041,20 @@ pub fn update_tracker_config(
 /// * **QueryMsg::FeeInfo { pair_type }** Returns the fee structure (total and maker fees) for a specific pair type.
 ///
 /// * **QueryMsg::BlacklistedPairTypes {}** Returns a vector that contains blacklisted pair types (pair types that cannot get ASTRO emissions).
+///
+/// * **QueryMsg::PairMetadata { pair_addr }** Returns display metadata of a specific pair.
+///
+/// * **QueryMsg::PairsWithMetadata { start_after, limit }** Returns an array of pairs accompanied with their metadata.
+///
+/// * **QueryMsg::PairMigrations { start_after, limit }** Returns pairs scheduled for migration along with their code IDs.
+///
+/// * **QueryMsg::FlashLoansEnabled {}** Returns whether flash loans are enabled in pairs.
+///
+/// * **QueryMsg::DecimalsOverride { asset_info }** Returns the decimals override of an asset.
+///
+/// * **QueryMsg::PauseState {}** Returns the pause guardian and whether pair creation and all pairs are paused.
+///
+/// * **QueryMsg::PausedPairs { start_after, limit }** Returns individually paused pairs.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -477,9 +1066,131 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::FeeInfo { pair_type } => to_json_binary(&query_fee_info(deps, pair_type)?),
         QueryMsg::BlacklistedPairTypes {} => to_json_binary(&query_blacklisted_pair_types(deps)?),
         QueryMsg::TrackerConfig {} => to_json_binary(&query_tracker_config(deps)?),
+        QueryMsg::PairMetadata { pair_addr } => {
+            let pair_addr = deps.api.addr_validate(&pair_addr)?;
+            to_json_binary(&PAIR_METADATA.may_load(deps.storage, &pair_addr)?)
+        }
+        QueryMsg::PairsWithMetadata { start_after, limit } => {
+            to_json_binary(&query_pairs_with_metadata(deps, start_after, limit)?)
+        }
+        QueryMsg::PairMigrations { start_after, limit } => {
+            to_json_binary(&query_pair_migrations(deps, start_after, limit)?)
+        }
+        QueryMsg::FlashLoansEnabled {} => to_json_binary(
+            &FLASH_LOANS_ENABLED
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::DecimalsOverride { asset_info } => {
+            to_json_binary(&DECIMALS_OVERRIDES.may_load(deps.storage, asset_info.to_string())?)
+        }
+        QueryMsg::PairCreators {
+            pair_type,
+            start_after,
+            limit,
+        } => to_json_binary(&query_pair_creators(deps, pair_type, start_after, limit)?),
+        QueryMsg::PairCreatorsGuardian {} => {
+            to_json_binary(&PAIR_CREATORS_GUARDIAN.may_load(deps.storage)?)
+        }
+        QueryMsg::PauseState {} => to_json_binary(&PauseStateResponse {
+            guardian: PAUSE_GUARDIAN.may_load(deps.storage)?,
+            pair_creation_paused: PAIR_CREATION_PAUSED
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+            all_pairs_paused: PAIRS_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::PausedPairs { start_after, limit } => {
+            to_json_binary(&query_paused_pairs(deps, start_after, limit)?)
+        }
     }
 }
 
+/// Returns individually paused pairs.
+/// * **start_after** is the pair contract address to start reading from.
+///
+/// * **limit** sets the number of pairs to be retrieved.
+pub fn query_paused_pairs(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = addr_opt_validate(deps.api, &start_after)?;
+
+    PAUSED_PAIRS
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+/// Returns addresses allowed to create pairs of a pair type including expired ones.
+/// * **start_after** is the address to start reading from.
+///
+/// * **limit** sets the number of addresses to be retrieved.
+pub fn query_pair_creators(
+    deps: Deps,
+    pair_type: PairType,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairCreator>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = addr_opt_validate(deps.api, &start_after)?;
+
+    PAIR_CREATORS
+        .prefix(pair_type.to_string())
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, creator)| creator))
+        .collect()
+}
+
+/// Returns pairs scheduled for migration in the scheduling order along with their current code IDs.
+/// * **start_after** is the pair contract address to start reading from.
+///
+/// * **limit** sets the number of pairs to be retrieved.
+pub fn query_pair_migrations(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairMigrationInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let pairs_to_migrate = PAIRS_TO_MIGRATE.may_load(deps.storage)?.unwrap_or_default();
+    let start = start_after
+        .and_then(|start_after| {
+            pairs_to_migrate
+                .iter()
+                .position(|pair| pair.as_str() == start_after)
+        })
+        .map(|index| index + 1)
+        .unwrap_or_default();
+
+    pairs_to_migrate
+        .into_iter()
+        .skip(start)
+        .take(limit)
+        .map(|pair_addr| {
+            let current_code_id = deps.querier.query_wasm_contract_info(&pair_addr)?.code_id;
+            let migration = PAIR_MIGRATIONS.load(deps.storage, &pair_addr)?;
+
+            Ok(PairMigrationInfo {
+                pair_addr,
+                current_code_id,
+                new_code_id: migration.new_code_id,
+            })
+        })
+        .collect()
+}
+
 /// Returns a vector that contains blacklisted pair types
 pub fn query_blacklisted_pair_types(deps: Deps) -> StdResult<Vec<PairType>> {
     PAIR_CONFIGS
@@ -509,6 +1220,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .collect::<StdResult<Vec<_>>>()?,
         fee_address: config.fee_address,
         generator_address: config.generator_address,
+        auto_stake_target: config.auto_stake_target,
         whitelist_code_id: config.whitelist_code_id,
         coin_registry_address: config.coin_registry_address,
     };
@@ -541,6 +1253,25 @@ pub fn query_pairs(
     Ok(PairsResponse { pairs })
 }
 
+/// Same as [`query_pairs`] but each pair is accompanied with its metadata.
+pub fn query_pairs_with_metadata(
+    deps: Deps,
+    start_after: Option<Vec<AssetInfo>>,
+    limit: Option<u32>,
+) -> StdResult<PairsWithMetadataResponse> {
+    let pairs = read_pairs(deps, start_after, limit)?
+        .into_iter()
+        .map(|pair_addr| {
+            Ok(PairWithMetadata {
+                pair_info: query_pair_info(&deps.querier, &pair_addr)?,
+                metadata: PAIR_METADATA.may_load(deps.storage, &pair_addr)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PairsWithMetadataResponse { pairs })
+}
+
 /// Returns the fee setup for a specific pair type using a [`FeeInfoResponse`] struct.
 /// * **pair_type** is a struct that represents the fee information (total and maker fees) for a specific pair type.
 pub fn query_fee_info(deps: Deps, pair_type: PairType) -> StdResult<FeeInfoResponse> {
@@ -604,7 +1335,7 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
                     )?;
                 }
             }
-            "1.8.0" | "1.8.1" => {}
+            "1.8.0" | "1.8.1" | "1.9.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -34,6 +34,27 @@ pub enum ContractError {
     #[error("Contract can't be migrated!")]
     MigrationError {},
 
+    #[error("Pair {pair_addr} is not registered in the factory")]
+    PairNotRegistered { pair_addr: String },
+
+    #[error("Factory must be the admin of pair {pair_addr} to migrate it")]
+    FactoryIsNotPairAdmin { pair_addr: String },
+
+    #[error("Pair {pair_addr} is not scheduled for migration")]
+    PairMigrationNotScheduled { pair_addr: String },
+
+    #[error("Parameter expires_in cannot be higher than {max}")]
+    ExpiresInTooHigh { max: u64 },
+
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("Decimals override cannot be higher than {max}")]
+    DecimalsOverrideTooHigh { max: u8 },
+
+    #[error("Pair creator expiry {expires_at} must be in the future")]
+    PairCreatorExpiryInPast { expires_at: u64 },
+
+    #[error("Pair creation is paused")]
+    PairCreationPaused {},
 }
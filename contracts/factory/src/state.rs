@@ -1,12 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Deps, Order, StdResult};
+use cosmwasm_std::{Addr, Api, Deps, Order, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 use itertools::Itertools;
 
 use crate::error::ContractError;
 use astroport::asset::AssetInfo;
 use astroport::common::OwnershipProposal;
-use astroport::factory::{Config, PairConfig, TrackerConfig};
+use astroport::factory::{
+    Config, PairConfig, PairCreator, PairMetadata, PairMigration, PairType, TrackerConfig,
+    PAIRS_PAUSED_KEY, PAUSED_PAIRS_NAMESPACE,
+};
 /// This is an intermediate structure for storing a pair's key. It is used in a submessage response.
 #[cw_serde]
 pub struct TmpPairInfo {
@@ -22,6 +25,9 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Saves created pairs (from olders to latest)
 pub const PAIRS: Map<&[u8], Addr> = Map::new("pair_info");
 
+/// Pair display metadata. key: pair contract address
+pub const PAIR_METADATA: Map<&Addr, PairMetadata> = Map::new("pair_metadata");
+
 /// Track config for tracking contract
 pub const TRACKER_CONFIG: Item<TrackerConfig> = Item::new("tracker_config");
 
@@ -43,9 +49,9 @@ pub const PAIR_CONFIGS: Map<String, PairConfig> = Map::new("pair_configs");
 
 /// ## Pagination settings
 /// The maximum limit for reading pairs from [`PAIRS`]
-const MAX_LIMIT: u32 = 30;
+pub(crate) const MAX_LIMIT: u32 = 30;
 /// The default limit for reading pairs from [`PAIRS`]
-const DEFAULT_LIMIT: u32 = 10;
+pub(crate) const DEFAULT_LIMIT: u32 = 10;
 
 /// Reads pairs from the [`PAIRS`] vector according to the `start_after` and `limit` variables.
 /// Otherwise, it returns the default number of pairs, starting from the oldest one.
@@ -115,9 +121,83 @@ pub(crate) fn check_asset_infos(
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
-/// This state key isn't used anymore but left for backward compatability with old pairs
+/// This structure tracks the progress of the factory owner propagation to registered pairs.
+#[cw_serde]
+pub struct OwnerPropagation {
+    /// The factory owner being proposed on pairs
+    pub owner: Addr,
+    /// The key of the last processed pair
+    pub last_pair_key: Option<Vec<u8>>,
+}
+
+/// Stores the progress of the ongoing owner propagation
+pub const OWNER_PROPAGATION: Item<OwnerPropagation> = Item::new("owner_propagation");
+
+/// Pairs scheduled for migration in the scheduling order.
+/// Pairs read this key with a raw query, thus its name and format must not change.
 pub const PAIRS_TO_MIGRATE: Item<Vec<Addr>> = Item::new("pairs_to_migrate");
 
+/// Whether flash loans are enabled in pairs.
+/// Pairs read this key with a raw query, thus its name and format must not change.
+pub const FLASH_LOANS_ENABLED: Item<bool> = Item::new("flash_loans_enabled");
+
+/// Decimals overrides of exotic assets. key: asset info as string
+/// Pairs read this map with a raw query, thus its name and format must not change.
+pub const DECIMALS_OVERRIDES: Map<String, u8> = Map::new("decimals_overrides");
+
+/// Scheduled pair migrations. key: pair contract address
+pub const PAIR_MIGRATIONS: Map<&Addr, PairMigration> = Map::new("pair_migrations");
+
+/// Addresses allowed to create pairs of permissioned pair types. key: (pair type, creator address)
+pub const PAIR_CREATORS: Map<(String, &Addr), PairCreator> = Map::new("pair_creators");
+
+/// The address which manages [`PAIR_CREATORS`] along with the owner
+pub const PAIR_CREATORS_GUARDIAN: Item<Addr> = Item::new("pair_creators_guardian");
+
+/// The address which can pause pair creation and pairs along with the owner
+pub const PAUSE_GUARDIAN: Item<Addr> = Item::new("pause_guardian");
+
+/// Whether pair creation is paused
+pub const PAIR_CREATION_PAUSED: Item<bool> = Item::new("pair_creation_paused");
+
+/// Whether all pairs are paused.
+/// Pairs and the router read this key with a raw query, thus its format must not change.
+pub const PAIRS_PAUSED: Item<bool> = Item::new(PAIRS_PAUSED_KEY);
+
+/// Individually paused pairs. key: pair contract address
+/// Pairs and the router read this map with a raw query, thus its format must not change.
+pub const PAUSED_PAIRS: Map<&Addr, ()> = Map::new(PAUSED_PAIRS_NAMESPACE);
+
+/// Returns whether the address is allowed to create pairs of the pair type at the specified time.
+pub(crate) fn is_pair_creator(
+    storage: &dyn Storage,
+    pair_type: &PairType,
+    sender: &Addr,
+    block_ts: u64,
+) -> StdResult<bool> {
+    let creator = PAIR_CREATORS.may_load(storage, (pair_type.to_string(), sender))?;
+
+    Ok(matches!(creator, Some(creator) if creator.is_active(block_ts)))
+}
+
+/// Removes the pair from the migration schedule. Returns false if the pair wasn't scheduled.
+pub(crate) fn unschedule_pair_migration(
+    storage: &mut dyn Storage,
+    pair_addr: &Addr,
+) -> StdResult<bool> {
+    let mut pairs_to_migrate = PAIRS_TO_MIGRATE.may_load(storage)?.unwrap_or_default();
+
+    if let Some(index) = pairs_to_migrate.iter().position(|pair| pair == pair_addr) {
+        pairs_to_migrate.remove(index);
+        PAIRS_TO_MIGRATE.save(storage, &pairs_to_migrate)?;
+        PAIR_MIGRATIONS.remove(storage, pair_addr);
+
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use astroport::asset::{native_asset_info, token_asset_info};
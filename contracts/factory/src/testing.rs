@@ -11,6 +11,7 @@ use crate::{
 };
 
 use astroport::asset::{AssetInfo, PairInfo};
+use astroport::common::{MAX_OWNERSHIP_TIMELOCK, OWNERSHIP_TIMELOCK};
 use astroport::factory::{
     ConfigResponse, ExecuteMsg, InstantiateMsg, PairConfig, PairType, PairsResponse, QueryMsg,
 };
@@ -51,6 +52,7 @@ fn proper_initialization() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
             PairConfig {
                 code_id: 325u64,
@@ -60,6 +62,7 @@ fn proper_initialization() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
         ],
         token_code_id: 123u64,
@@ -69,6 +72,7 @@ fn proper_initialization() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -86,6 +90,7 @@ fn proper_initialization() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: 123u64,
         fee_address: None,
@@ -94,6 +99,7 @@ fn proper_initialization() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -104,7 +110,7 @@ fn proper_initialization() {
 
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
+    let mut msg = InstantiateMsg {
         pair_configs: vec![
             PairConfig {
                 code_id: 325u64,
@@ -114,6 +120,7 @@ fn proper_initialization() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
             PairConfig {
                 code_id: 123u64,
@@ -123,6 +130,7 @@ fn proper_initialization() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
         ],
         token_code_id: 123u64,
@@ -132,11 +140,19 @@ fn proper_initialization() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: Some(MAX_OWNERSHIP_TIMELOCK + 1),
     };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
 
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        res.to_string(),
+        format!("Generic error: Ownership timelock cannot be higher than {MAX_OWNERSHIP_TIMELOCK}")
+    );
+
+    msg.ownership_timelock = Some(86400);
     instantiate(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
 
     let query_res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
@@ -144,6 +160,7 @@ fn proper_initialization() {
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(msg.pair_configs, config_res.pair_configs);
     assert_eq!(Addr::unchecked(owner), config_res.owner);
+    assert_eq!(OWNERSHIP_TIMELOCK.load(&deps.storage).unwrap(), 86400);
 }
 
 #[test]
@@ -159,6 +176,7 @@ fn update_config() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     }];
 
     let msg = InstantiateMsg {
@@ -170,6 +188,7 @@ fn update_config() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -193,7 +212,7 @@ fn update_config() {
     assert_eq!(0, res.messages.len());
 
     // It worked, let's query the state
-    let query_res = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+    let query_res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
     let config_res: ConfigResponse = from_json(&query_res).unwrap();
     assert_eq!(200u64, config_res.token_code_id);
     assert_eq!(owner, config_res.owner);
@@ -205,6 +224,36 @@ fn update_config() {
         String::from("new_generator_addr"),
         config_res.generator_address.unwrap()
     );
+    assert_eq!(config_res.auto_stake_target, None);
+
+    // Set and remove the auto-stake target
+    let msg = ExecuteMsg::UpdateAutoStakeTarget {
+        auto_stake_target: Some(String::from("new_incentives_addr")),
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    let config_res: ConfigResponse =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config_res.auto_stake_target,
+        Some(Addr::unchecked("new_incentives_addr"))
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let msg = ExecuteMsg::UpdateAutoStakeTarget {
+        auto_stake_target: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+    let config_res: ConfigResponse =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config_res.auto_stake_target, None);
 
     // Unauthorized err
     let env = mock_env();
@@ -235,6 +284,7 @@ fn update_owner() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -313,6 +363,7 @@ fn update_pair_config() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     }];
 
     let msg = InstantiateMsg {
@@ -324,6 +375,7 @@ fn update_pair_config() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -346,6 +398,7 @@ fn update_pair_config() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     };
 
     // Unauthorized err
@@ -370,6 +423,7 @@ fn update_pair_config() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         },
     };
 
@@ -398,6 +452,7 @@ fn update_pair_config() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     };
 
     let info = mock_info(owner, &[]);
@@ -428,6 +483,7 @@ fn create_pair() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     };
 
     let msg = InstantiateMsg {
@@ -439,6 +495,7 @@ fn create_pair() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -532,6 +589,7 @@ fn register() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: 123u64,
         fee_address: None,
@@ -540,6 +598,7 @@ fn register() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -81,6 +81,7 @@ impl FactoryHelper {
                     is_disabled: false,
                     is_generator_disabled: false,
                     permissioned: false,
+                    min_trade_notional: None,
                 },
                 PairConfig {
                     code_id: pair_code_id,
@@ -90,6 +91,7 @@ impl FactoryHelper {
                     is_disabled: false,
                     is_generator_disabled: false,
                     permissioned: true,
+                    min_trade_notional: None,
                 },
             ],
             token_code_id: cw20_token_code_id,
@@ -99,6 +101,7 @@ impl FactoryHelper {
             whitelist_code_id: 0,
             coin_registry_address: "coin_registry".to_string(),
             tracker_config: None,
+            ownership_timelock: None,
         };
 
         let factory = router
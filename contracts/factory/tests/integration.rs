@@ -2,13 +2,19 @@
 
 mod factory_helper;
 
-use cosmwasm_std::{attr, Addr, StdError};
+use cosmwasm_std::{
+    attr, coins, to_json_binary, Addr, DepsMut, Empty, Env, Event, Response, StdError, StdResult,
+    WasmMsg,
+};
 
-use astroport::asset::{AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::common::MAX_OWNERSHIP_TIMELOCK;
 use astroport::factory::{
-    ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, PairConfig, PairType, QueryMsg,
-    TrackerConfig,
+    ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, PairConfig, PairCreator,
+    PairMetadata, PairMigrationInfo, PairType, PairsWithMetadataResponse, PauseStateResponse,
+    QueryMsg, TrackerConfig,
 };
+use astroport::pair::MigrationStatusResponse;
 
 use crate::factory_helper::{instantiate_token, FactoryHelper};
 use astroport_factory::error::ContractError;
@@ -50,6 +56,7 @@ fn proper_initialization() {
         is_disabled: false,
         is_generator_disabled: false,
         permissioned: false,
+        min_trade_notional: None,
     }];
 
     let msg = InstantiateMsg {
@@ -61,6 +68,7 @@ fn proper_initialization() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -213,6 +221,7 @@ fn test_create_pair() {
                 is_disabled: true,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
         },
         &[],
@@ -366,6 +375,137 @@ fn check_update_owner() {
     assert_eq!(res.owner, new_owner)
 }
 
+#[test]
+fn check_ownership_timelock() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+    let helper = FactoryHelper::init(&mut app, &owner);
+
+    let err = app
+        .execute_contract(
+            new_owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdateOwnershipTimelock { timelock: 86400 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: Unauthorized");
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdateOwnershipTimelock {
+                timelock: MAX_OWNERSHIP_TIMELOCK + 1,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("Generic error: Ownership timelock cannot be higher than {MAX_OWNERSHIP_TIMELOCK}")
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdateOwnershipTimelock { timelock: 86400 },
+        &[],
+    )
+    .unwrap();
+
+    let claimable_from = app.block_info().time.seconds() + 86400;
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::ProposeNewOwner {
+                owner: new_owner.to_string(),
+                expires_in: 100,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "propose_new_owner")
+            .add_attribute("claimable_from", claimable_from.to_string())
+    ));
+
+    // The proposal can't be claimed during the timelock
+    app.update_block(|block| block.time = block.time.plus_seconds(86399));
+    let err = app
+        .execute_contract(
+            new_owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::ClaimOwnership {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("Generic error: Ownership proposal can't be claimed until {claimable_from}")
+    );
+
+    // The proposal stays valid for expires_in seconds after the timelock
+    app.update_block(|block| block.time = block.time.plus_seconds(101));
+    app.execute_contract(
+        new_owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::ClaimOwnership {},
+        &[],
+    )
+    .unwrap();
+
+    let res: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.owner, new_owner);
+
+    // A decrease applies only once the current timelock has passed
+    let effective_from = app.block_info().time.seconds() + 86400;
+    let res = app
+        .execute_contract(
+            new_owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdateOwnershipTimelock { timelock: 0 },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "update_ownership_timelock")
+            .add_attribute("timelock", "0")
+            .add_attribute("effective_from", effective_from.to_string())
+    ));
+
+    let propose_msg = ExecuteMsg::ProposeNewOwner {
+        owner: owner.to_string(),
+        expires_in: 100,
+    };
+    let res = app
+        .execute_contract(new_owner.clone(), helper.factory.clone(), &propose_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "propose_new_owner")
+            .add_attribute("claimable_from", effective_from.to_string())
+    ));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(86400));
+    let claimable_from = app.block_info().time.seconds();
+    let res = app
+        .execute_contract(new_owner.clone(), helper.factory.clone(), &propose_msg, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "propose_new_owner")
+            .add_attribute("claimable_from", claimable_from.to_string())
+    ));
+}
+
 #[test]
 fn test_create_permissioned_pair() {
     let mut app = mock_app();
@@ -400,6 +540,212 @@ fn test_create_permissioned_pair() {
         .unwrap();
 }
 
+#[test]
+fn test_pair_creators_allowlist() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let guardian = Addr::unchecked("guardian");
+    let creator = Addr::unchecked("creator");
+    let temporary_creator = Addr::unchecked("temporary_creator");
+    let pair_type = PairType::Custom("transmuter".to_string());
+    let tokens = ["tokenA", "tokenB", "tokenC", "tokenD"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+
+    let update_creators =
+        |add: Vec<(&Addr, Option<u64>)>, remove: Vec<&Addr>| ExecuteMsg::UpdatePairCreators {
+            pair_type: pair_type.clone(),
+            add: Some(
+                add.into_iter()
+                    .map(|(addr, expires_at)| (addr.to_string(), expires_at))
+                    .collect(),
+            ),
+            remove: Some(remove.into_iter().map(Addr::to_string).collect()),
+        };
+
+    // Only the owner manages allowlists until the guardian is set
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            helper.factory.clone(),
+            &update_creators(vec![(&creator, None)], vec![]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePairCreatorsGuardian {
+                guardian: Some(guardian.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairCreatorsGuardian {
+            guardian: Some(guardian.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+    let stored_guardian: Option<Addr> = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::PairCreatorsGuardian {})
+        .unwrap();
+    assert_eq!(stored_guardian, Some(guardian.clone()));
+
+    let block_ts = app.block_info().time.seconds();
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            helper.factory.clone(),
+            &update_creators(vec![(&temporary_creator, Some(block_ts))], vec![]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairCreatorExpiryInPast {
+            expires_at: block_ts
+        }
+    );
+
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_creators(
+            vec![(&creator, None), (&temporary_creator, Some(block_ts + 100))],
+            vec![],
+        ),
+        &[],
+    )
+    .unwrap();
+
+    let creators: Vec<PairCreator> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairCreators {
+                pair_type: pair_type.clone(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        creators,
+        vec![
+            PairCreator {
+                address: creator.clone(),
+                expires_at: None,
+            },
+            PairCreator {
+                address: temporary_creator.clone(),
+                expires_at: Some(block_ts + 100),
+            },
+        ]
+    );
+
+    let creators: Vec<PairCreator> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairCreators {
+                pair_type: pair_type.clone(),
+                start_after: Some(creator.to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(creators[0].address, temporary_creator);
+
+    // Allowlists are kept per pair type
+    let err = helper
+        .create_pair(
+            &mut app,
+            &Addr::unchecked("random_stranger"),
+            pair_type.clone(),
+            [&tokens[0], &tokens[1]],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    helper
+        .create_pair(
+            &mut app,
+            &temporary_creator,
+            pair_type.clone(),
+            [&tokens[0], &tokens[1]],
+            None,
+        )
+        .unwrap();
+
+    // The entry expires
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    let err = helper
+        .create_pair(
+            &mut app,
+            &temporary_creator,
+            pair_type.clone(),
+            [&tokens[1], &tokens[2]],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    helper
+        .create_pair(
+            &mut app,
+            &creator,
+            pair_type.clone(),
+            [&tokens[1], &tokens[2]],
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &update_creators(vec![], vec![&creator]),
+        &[],
+    )
+    .unwrap();
+    let err = helper
+        .create_pair(
+            &mut app,
+            &creator,
+            pair_type.clone(),
+            [&tokens[2], &tokens[3]],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+}
+
 #[test]
 fn tracker_config() {
     let mut app = mock_app();
@@ -457,6 +803,7 @@ fn tracker_config() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: 0,
         generator_address: None,
@@ -467,6 +814,7 @@ fn tracker_config() {
             code_id: 64,
             token_factory_addr: "token_factory_addr".to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory = app
@@ -484,3 +832,788 @@ fn tracker_config() {
     assert_eq!(tracker_config.token_factory_addr, "token_factory_addr");
     assert_eq!(tracker_config.code_id, 64);
 }
+
+#[test]
+fn test_pair_metadata() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token1 = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "tokenX", None);
+    let token2 = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "tokenY", None);
+    helper
+        .create_pair(&mut app, &owner, PairType::Xyk {}, [&token1, &token2], None)
+        .unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: token1.clone(),
+        },
+        AssetInfo::Token {
+            contract_addr: token2.clone(),
+        },
+    ];
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap();
+    let pair_addr = pair_info.contract_addr.to_string();
+
+    let metadata = PairMetadata {
+        display_name: Some("X/Y".to_string()),
+        description: Some("Test pair".to_string()),
+        logo_uri: Some("https://example.com/xy.png".to_string()),
+        tags: vec!["stable".to_string(), "bluechip".to_string()],
+    };
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePairMetadata {
+                pair_addr: pair_addr.clone(),
+                metadata: Some(metadata.clone()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePairMetadata {
+                pair_addr: pair_addr.clone(),
+                metadata: Some(PairMetadata {
+                    tags: vec!["Blue Chip".to_string()],
+                    ..metadata.clone()
+                }),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Invalid tag Blue Chip. Tag must be 1-32 characters long and contain only lowercase alphanumeric characters or '-'"
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePairMetadata {
+            pair_addr: pair_addr.clone(),
+            metadata: Some(metadata.clone()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let resp: Option<PairMetadata> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairMetadata {
+                pair_addr: pair_addr.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(resp, Some(metadata.clone()));
+
+    let resp: PairsWithMetadataResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairsWithMetadata {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(resp.pairs.len(), 1);
+    assert_eq!(resp.pairs[0].pair_info, pair_info);
+    assert_eq!(resp.pairs[0].metadata, Some(metadata));
+
+    // Metadata is removed along with the pair
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::Deregister { asset_infos },
+        &[],
+    )
+    .unwrap();
+    let resp: Option<PairMetadata> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairMetadata {
+                pair_addr: pair_addr.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(resp, None);
+
+    // Deregistered pair can't get metadata anymore
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePairMetadata {
+                pair_addr: pair_addr.clone(),
+                metadata: Some(PairMetadata::default()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairNotRegistered { pair_addr }
+    );
+}
+
+#[test]
+fn test_propagate_ownership() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let tokens = ["tokenX", "tokenY", "tokenZ"]
+        .into_iter()
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None))
+        .collect::<Vec<_>>();
+    for (token1, token2) in [(0, 1), (0, 2), (1, 2)] {
+        helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [&tokens[token1], &tokens[token2]],
+                None,
+            )
+            .unwrap();
+    }
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &ExecuteMsg::PropagateOwnership {
+                expires_in: 100,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::PropagateOwnership {
+                expires_in: 1209601,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ExpiresInTooHigh { max: 1209600 }
+    );
+
+    // XYK pairs don't keep their own owner thus they are skipped
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::PropagateOwnership {
+                expires_in: 100,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("pairs_processed", "2")
+            .add_attribute("finished", "false")
+    ));
+    assert!(
+        res.has_event(&Event::new("wasm").add_attribute("action", "skip_ownership_propagation"))
+    );
+
+    // The next batch continues from the last processed pair
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::PropagateOwnership {
+                expires_in: 100,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("pairs_processed", "1")
+            .add_attribute("finished", "true")
+    ));
+}
+
+fn noop_migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn migration_scheduled(app: &TestApp, pair: &Addr) -> bool {
+    app.wrap()
+        .query_wasm_smart::<MigrationStatusResponse>(
+            pair,
+            &astroport::pair::QueryMsg::MigrationStatus {},
+        )
+        .unwrap()
+        .migration_scheduled
+}
+
+#[test]
+fn test_pair_migrations() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let new_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            astroport_pair::contract::query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply)
+        .with_migrate_empty(noop_migrate),
+    ));
+
+    let tokens = ["tokenX", "tokenY", "tokenZ"]
+        .into_iter()
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None))
+        .collect::<Vec<_>>();
+    let pairs = [(0, 1), (0, 2)]
+        .into_iter()
+        .map(|(token1, token2)| {
+            helper
+                .create_pair(
+                    &mut app,
+                    &owner,
+                    PairType::Xyk {},
+                    [&tokens[token1], &tokens[token2]],
+                    None,
+                )
+                .unwrap();
+            app.wrap()
+                .query_wasm_smart::<PairInfo>(
+                    &helper.factory,
+                    &QueryMsg::Pair {
+                        asset_infos: vec![
+                            AssetInfo::Token {
+                                contract_addr: tokens[token1].clone(),
+                            },
+                            AssetInfo::Token {
+                                contract_addr: tokens[token2].clone(),
+                            },
+                        ],
+                    },
+                )
+                .unwrap()
+                .contract_addr
+        })
+        .collect::<Vec<_>>();
+    let old_code_id = app
+        .wrap()
+        .query_wasm_contract_info(&pairs[0])
+        .unwrap()
+        .code_id;
+
+    let schedule_msg = |pairs: &[Addr]| ExecuteMsg::SchedulePairMigrations {
+        pairs: pairs.iter().map(Addr::to_string).collect(),
+        new_code_id,
+        migrate_msg: to_json_binary(&Empty {}).unwrap(),
+    };
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &schedule_msg(&pairs),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // Pairs are instantiated with the factory owner as the admin
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &schedule_msg(&pairs),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FactoryIsNotPairAdmin {
+            pair_addr: pairs[0].to_string()
+        }
+    );
+
+    for pair in &pairs {
+        app.execute(
+            owner.clone(),
+            WasmMsg::UpdateAdmin {
+                contract_addr: pair.to_string(),
+                admin: helper.factory.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+
+    // Step 1: pairs show the upcoming migration
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &schedule_msg(&pairs),
+        &[],
+    )
+    .unwrap();
+    assert!(migration_scheduled(&app, &pairs[0]));
+    assert!(migration_scheduled(&app, &pairs[1]));
+
+    let migrations: Vec<PairMigrationInfo> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairMigrations {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        migrations,
+        pairs
+            .iter()
+            .map(|pair| PairMigrationInfo {
+                pair_addr: pair.clone(),
+                current_code_id: old_code_id,
+                new_code_id,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::CancelPairMigrations {
+            pairs: vec![pairs[1].to_string()],
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(!migration_scheduled(&app, &pairs[1]));
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::CancelPairMigrations {
+                pairs: vec![pairs[1].to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairMigrationNotScheduled {
+            pair_addr: pairs[1].to_string()
+        }
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &schedule_msg(&pairs[1..]),
+        &[],
+    )
+    .unwrap();
+
+    // Step 2: pairs are migrated in batches in the scheduling order
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &ExecuteMsg::MigratePairs { limit: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::MigratePairs { limit: Some(1) },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("pairs_migrated", "1")
+            .add_attribute("pairs_left", "1")
+    ));
+    assert_eq!(
+        app.wrap()
+            .query_wasm_contract_info(&pairs[0])
+            .unwrap()
+            .code_id,
+        new_code_id
+    );
+    assert!(!migration_scheduled(&app, &pairs[0]));
+    assert!(migration_scheduled(&app, &pairs[1]));
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::MigratePairs { limit: None },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        app.wrap()
+            .query_wasm_contract_info(&pairs[1])
+            .unwrap()
+            .code_id,
+        new_code_id
+    );
+
+    let migrations: Vec<PairMigrationInfo> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PairMigrations {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(migrations.is_empty());
+}
+
+#[test]
+fn test_pause_pairs() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let guardian = Addr::unchecked("guardian");
+    let user = Addr::unchecked("user");
+    let tokens = ["tokenA", "tokenB", "tokenC"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+
+    helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [&tokens[0], &tokens[1]],
+            None,
+        )
+        .unwrap();
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::Pair {
+                asset_infos: vec![
+                    AssetInfo::cw20(tokens[0].clone()),
+                    AssetInfo::cw20(tokens[1].clone()),
+                ],
+            },
+        )
+        .unwrap();
+    let pair = pair_info.contract_addr.clone();
+
+    for token in &tokens[..2] {
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &cw20::Cw20ExecuteMsg::Mint {
+                recipient: user.to_string(),
+                amount: 1_000_000u128.into(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            user.clone(),
+            token.clone(),
+            &cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.to_string(),
+                amount: 1_000_000u128.into(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let provide_msg = astroport::pair::ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            Asset::cw20(tokens[0].clone(), 100_000u128),
+            Asset::cw20(tokens[1].clone(), 100_000u128),
+        ],
+        slippage_tolerance: None,
+        auto_stake: None,
+        receiver: None,
+        min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
+    };
+    let swap_msg = cw20::Cw20ExecuteMsg::Send {
+        contract: pair.to_string(),
+        amount: 1_000u128.into(),
+        msg: to_json_binary(&astroport::pair::Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            deadline: None,
+            to_commitment: None,
+        })
+        .unwrap(),
+    };
+    app.execute_contract(user.clone(), pair.clone(), &provide_msg, &[])
+        .unwrap();
+
+    let update_pause_state =
+        |pair_creation: Option<bool>,
+         all_pairs: Option<bool>,
+         pause: Vec<&Addr>,
+         unpause: Vec<&Addr>| ExecuteMsg::UpdatePauseState {
+            pair_creation,
+            all_pairs,
+            pause: Some(pause.into_iter().map(Addr::to_string).collect()),
+            unpause: Some(unpause.into_iter().map(Addr::to_string).collect()),
+        };
+
+    // Only the owner can pause until the guardian is set
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            helper.factory.clone(),
+            &update_pause_state(Some(true), None, vec![], vec![]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            helper.factory.clone(),
+            &ExecuteMsg::UpdatePauseGuardian {
+                guardian: Some(guardian.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &ExecuteMsg::UpdatePauseGuardian {
+            guardian: Some(guardian.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Pair creation pause
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_pause_state(Some(true), None, vec![], vec![]),
+        &[],
+    )
+    .unwrap();
+    let pause_state: PauseStateResponse = app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &QueryMsg::PauseState {})
+        .unwrap();
+    assert_eq!(
+        pause_state,
+        PauseStateResponse {
+            guardian: Some(guardian.clone()),
+            pair_creation_paused: true,
+            all_pairs_paused: false,
+        }
+    );
+
+    let err = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [&tokens[1], &tokens[2]],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairCreationPaused {}
+    );
+
+    // Pausing pair creation doesn't affect existing pairs
+    app.execute_contract(user.clone(), tokens[0].clone(), &swap_msg, &[])
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &update_pause_state(Some(false), None, vec![], vec![]),
+        &[],
+    )
+    .unwrap();
+    helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [&tokens[1], &tokens[2]],
+            None,
+        )
+        .unwrap();
+
+    // Only registered pairs can be paused
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_pause_state(None, None, vec![&tokens[0]], vec![]),
+        &[],
+    )
+    .unwrap_err();
+
+    // Individual pause
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_pause_state(None, None, vec![&pair], vec![]),
+        &[],
+    )
+    .unwrap();
+    let paused_pairs: Vec<Addr> = app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &QueryMsg::PausedPairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(paused_pairs, vec![pair.clone()]);
+
+    let err = app
+        .execute_contract(user.clone(), tokens[0].clone(), &swap_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<astroport_pair::error::ContractError>()
+            .unwrap(),
+        astroport_pair::error::ContractError::PairPaused {}
+    );
+    let err = app
+        .execute_contract(user.clone(), pair.clone(), &provide_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<astroport_pair::error::ContractError>()
+            .unwrap(),
+        astroport_pair::error::ContractError::PairPaused {}
+    );
+
+    // Withdrawals are still allowed
+    let lp_amount = app
+        .wrap()
+        .query_balance(&user, &pair_info.liquidity_token)
+        .unwrap()
+        .amount;
+    app.execute_contract(
+        user.clone(),
+        pair.clone(),
+        &astroport::pair::ExecuteMsg::WithdrawLiquidity {
+            assets: vec![],
+            min_assets_to_receive: None,
+            deadline: None,
+        },
+        &coins(lp_amount.u128() / 2, &pair_info.liquidity_token),
+    )
+    .unwrap();
+
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_pause_state(None, None, vec![], vec![&pair]),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(user.clone(), tokens[0].clone(), &swap_msg, &[])
+        .unwrap();
+
+    // Global pause takes precedence over individual pauses
+    app.execute_contract(
+        guardian.clone(),
+        helper.factory.clone(),
+        &update_pause_state(None, Some(true), vec![], vec![]),
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(user.clone(), pair.clone(), &provide_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<astroport_pair::error::ContractError>()
+            .unwrap(),
+        astroport_pair::error::ContractError::PairPaused {}
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &update_pause_state(None, Some(false), vec![], vec![]),
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(user.clone(), pair.clone(), &provide_msg, &[])
+        .unwrap();
+}
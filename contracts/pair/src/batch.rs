@@ -0,0 +1,579 @@
+use cosmwasm_std::{
+    attr, Addr, Decimal, Decimal256, Deps, DepsMut, Env, Event, MessageInfo, Order, QuerierWrapper,
+    Response, StdResult, Storage, SubMsg, SubMsgResult, Uint128, Uint256,
+};
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::events::SwapEvent;
+use astroport::observation::{accumulate_swap_sizes, store_fee_observation, PrecommitObservation};
+use astroport::pair::{load_lp_fees, BatchedSwap, ReplyIds, SwapBatch};
+use astroport::querier::query_fee_info;
+
+use crate::contract::{accumulate_prices, assert_max_spread, calculate_maker_fee, save_checkpoint};
+use crate::error::ContractError;
+use crate::state::{
+    Config, ACCRUED_FEES, BALANCES, BATCH_MIN_OFFER, CIRCUIT_BREAKER, CONFIG, FEE_OBSERVATIONS,
+    MAX_BATCH_SIZE, MAX_BATCH_SWAPS_PER_SENDER, OBSERVATIONS, PENDING_BATCH, PENDING_PAYOUTS,
+    UNCLAIMED_PAYOUTS, UNCLAIMED_TOTAL, VOLUME_OBSERVATIONS,
+};
+
+/// Returns the pool reserves. Offers of queued swaps and unclaimed batch payouts are held by the pair
+/// but don't belong to the pool.
+pub fn query_reserves(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+) -> StdResult<Vec<Asset>> {
+    let batch = PENDING_BATCH.may_load(storage)?;
+    config
+        .pair_info
+        .query_pools(querier, &config.pair_info.contract_addr)?
+        .into_iter()
+        .map(|pool| {
+            let held = held_amount(storage, batch.as_ref(), &pool.info)?;
+            Ok(pool.info.with_balance(pool.amount.checked_sub(held)?))
+        })
+        .collect()
+}
+
+/// Returns the amount of the given asset which is held by the pair on behalf of batch swappers.
+fn held_amount(
+    storage: &dyn Storage,
+    batch: Option<&SwapBatch>,
+    asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    let unclaimed = UNCLAIMED_TOTAL
+        .may_load(storage, asset_info)?
+        .unwrap_or_default();
+    let escrow = batch
+        .map(|batch| batch.escrow(asset_info))
+        .unwrap_or_default();
+
+    Ok(unclaimed + escrow)
+}
+
+/// Queues a swap into the batch of the current block.
+/// The batch collected in a previous block is settled first.
+///
+/// * **sender** is the swap initiator which receives the refund if the swap can't be filled.
+///
+/// * **to** is the receiver of the swapped asset.
+pub fn queue_swap(
+    mut deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.pair_info.asset_infos.contains(&offer_asset.info) {
+        return Err(ContractError::AssetMismatch {});
+    }
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if let Some(min_offer) = BATCH_MIN_OFFER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|min_offer| min_offer.info == offer_asset.info)
+    {
+        if offer_asset.amount < min_offer.amount {
+            return Err(ContractError::BatchOfferTooSmall {
+                min_offer: min_offer.to_string(),
+            });
+        }
+    }
+    if let Some(circuit_breaker) = CIRCUIT_BREAKER.may_load(deps.storage)? {
+        if circuit_breaker.tripped_at.is_some() {
+            return Err(ContractError::PairHalted {});
+        }
+    }
+
+    let mut response = match PENDING_BATCH.may_load(deps.storage)? {
+        Some(batch) if batch.height < env.block.height => {
+            settle(deps.branch(), &env, batch, Some(&offer_asset))?
+        }
+        _ => Response::new(),
+    };
+
+    let mut batch = PENDING_BATCH
+        .may_load(deps.storage)?
+        .unwrap_or_else(|| SwapBatch {
+            height: env.block.height,
+            swaps: vec![],
+        });
+    if batch.swaps.len() >= MAX_BATCH_SIZE {
+        return Err(ContractError::BatchIsFull {});
+    }
+    let sender_swaps = batch
+        .swaps
+        .iter()
+        .filter(|swap| swap.sender == sender)
+        .count();
+    if sender_swaps >= MAX_BATCH_SWAPS_PER_SENDER {
+        return Err(ContractError::TooManyBatchedSwaps {});
+    }
+
+    let receiver = to.unwrap_or_else(|| sender.clone());
+    response = response.add_attributes([
+        attr("action", "queue_swap"),
+        attr("sender", &sender),
+        attr("receiver", &receiver),
+        attr("offer_asset", offer_asset.info.to_string()),
+        attr("offer_amount", offer_asset.amount),
+        attr("batch_height", batch.height.to_string()),
+    ]);
+
+    batch.swaps.push(BatchedSwap {
+        sender,
+        receiver,
+        offer_asset,
+        belief_price,
+        max_spread,
+    });
+    PENDING_BATCH.save(deps.storage, &batch)?;
+
+    Ok(response)
+}
+
+/// Settles the batch collected in a previous block.
+/// Swaps queued in the current block wait until the block is over.
+pub fn settle_batch(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    match PENDING_BATCH.may_load(deps.storage)? {
+        Some(batch) if batch.height < env.block.height => settle(deps, &env, batch, None),
+        _ => Err(ContractError::NothingToSettle {}),
+    }
+}
+
+/// Fills all swaps of the batch at a single clearing price. Opposite swaps are matched against
+/// each other and only the surplus goes through the pool curve.
+/// Swaps which fail their spread check at the clearing price are refunded and the price is recomputed without them.
+/// All swaps are refunded if the pair is halted or if the settlement would shift the reserve ratio
+/// by more than the circuit breaker allows.
+///
+/// Outputs and refunds are sent as submessages. A payout which can't reach its receiver
+/// doesn't revert the settlement and can be claimed later with [`claim_batch_payouts`].
+///
+/// * **incoming** is the offer of the ongoing swap which is already in the pair balance but not in the batch.
+pub fn settle(
+    deps: DepsMut,
+    env: &Env,
+    batch: SwapBatch,
+    incoming: Option<&Asset>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?
+        .into_iter()
+        .map(|pool| {
+            let mut held = held_amount(deps.storage, Some(&batch), &pool.info)?;
+            if let Some(offer) = incoming.filter(|offer| offer.info.equal(&pool.info)) {
+                held += offer.amount;
+            }
+            Ok(pool.info.with_balance(pool.amount.checked_sub(held)?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    PENDING_BATCH.remove(deps.storage);
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+
+    let circuit_breaker = CIRCUIT_BREAKER.may_load(deps.storage)?;
+    let halted = circuit_breaker
+        .as_ref()
+        .map(|circuit_breaker| circuit_breaker.tripped_at.is_some())
+        .unwrap_or_default();
+
+    let mut swaps = batch.swaps;
+    let mut refunds = vec![];
+    let mut price = None;
+    // Every iteration either settles the batch or drops at least one swap
+    while !swaps.is_empty() {
+        if halted || pools[0].amount.is_zero() || pools[1].amount.is_zero() {
+            refunds.append(&mut swaps);
+            break;
+        }
+
+        let clearing_price = clearing_price(&pools, &swaps)?;
+        let (filled, rejected): (Vec<_>, Vec<_>) = swaps.into_iter().partition(|swap| {
+            fill(&pools, clearing_price, swap, fee_info.total_fee_rate)
+                .map(|(return_amount, _, _)| !return_amount.is_zero())
+                .unwrap_or(false)
+        });
+        swaps = filled;
+
+        if rejected.is_empty() {
+            price = Some(clearing_price);
+            break;
+        }
+        refunds.extend(rejected);
+    }
+
+    let mut messages = vec![];
+    let mut payouts = vec![];
+    let mut events = vec![];
+    // Amounts coming into and leaving the pool in the pair assets order
+    let mut inflows = [Uint128::zero(); 2];
+    let mut outflows = [Uint128::zero(); 2];
+    let mut fees = [(Uint128::zero(), Uint128::zero(), Uint128::zero()); 2];
+    // Traded amounts in the pair assets order
+    let mut volumes = [Uint128::zero(); 2];
+
+    if let Some(clearing_price) = price {
+        for swap in swaps.iter().cloned() {
+            let (offer_ind, ask_ind) = if swap.offer_asset.info.equal(&pools[0].info) {
+                (0, 1)
+            } else {
+                (1, 0)
+            };
+            let ask_info = &pools[ask_ind].info;
+            let (return_amount, spread_amount, commission_amount) =
+                fill(&pools, clearing_price, &swap, fee_info.total_fee_rate)?;
+
+            let mut fees_commission_amount = commission_amount;
+            let mut fee_share_amount = Uint128::zero();
+            if let Some(fee_share) = &config.fee_share {
+                fee_share_amount = commission_amount * Decimal::from_ratio(fee_share.bps, 10000u16);
+                fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
+            }
+            let maker_fee_amount = match &fee_info.fee_address {
+                Some(_) => {
+                    calculate_maker_fee(ask_info, fees_commission_amount, fee_info.maker_fee_rate)
+                        .map(|fee| fee.amount)
+                        .unwrap_or_default()
+                }
+                None => Uint128::zero(),
+            };
+
+            payouts.push((swap.receiver.clone(), ask_info.with_balance(return_amount)));
+            inflows[offer_ind] += swap.offer_asset.amount;
+            outflows[ask_ind] += return_amount + maker_fee_amount + fee_share_amount;
+            fees[ask_ind].0 += commission_amount;
+            fees[ask_ind].1 += maker_fee_amount;
+            fees[ask_ind].2 += fee_share_amount;
+            volumes[offer_ind] += swap.offer_asset.amount;
+            volumes[ask_ind] += return_amount;
+
+            events.push(
+                Event::new("batch_swap").add_attributes(
+                    SwapEvent {
+                        pool: config.pair_info.contract_addr.clone(),
+                        sender: swap.sender,
+                        receiver: swap.receiver,
+                        offer_asset: swap.offer_asset,
+                        return_asset: ask_info.with_balance(return_amount),
+                        spread_amount,
+                        commission_amount,
+                        maker_fee_amount,
+                        fee_share_amount,
+                    }
+                    .into_attributes(),
+                ),
+            );
+        }
+    }
+
+    // A batch which would shift the reserve ratio too much is refunded as a whole
+    let mut new_reserves = reserves_after(&pools, &inflows, &outflows)?;
+    if let Some(circuit_breaker) = &circuit_breaker {
+        if circuit_breaker.check(&pools, &new_reserves)?.is_some() {
+            price = None;
+            payouts.clear();
+            events.clear();
+            fees = Default::default();
+            volumes = Default::default();
+            refunds.append(&mut swaps);
+            new_reserves = pools.clone();
+        }
+    }
+
+    store_fee_observation(
+        deps.storage,
+        env,
+        FEE_OBSERVATIONS,
+        load_lp_fees(deps.storage, ACCRUED_FEES, &config.pair_info.asset_infos)?,
+    )?;
+    for (pool, (commission_amount, maker_fee_amount, fee_share_amount)) in pools.iter().zip(fees) {
+        if commission_amount.is_zero() {
+            continue;
+        }
+
+        if let Some(fee_share) = &config.fee_share {
+            if !fee_share_amount.is_zero() {
+                messages.push(
+                    pool.info
+                        .with_balance(fee_share_amount)
+                        .into_msg(&fee_share.recipient)?,
+                );
+            }
+        }
+        if let Some(fee_address) = &fee_info.fee_address {
+            if !maker_fee_amount.is_zero() {
+                messages.push(
+                    pool.info
+                        .with_balance(maker_fee_amount)
+                        .into_msg(fee_address)?,
+                );
+            }
+        }
+
+        let mut accrued_fees = ACCRUED_FEES
+            .may_load(deps.storage, &pool.info)?
+            .unwrap_or_default();
+        accrued_fees.add(commission_amount, maker_fee_amount, fee_share_amount);
+        ACCRUED_FEES.save(deps.storage, &pool.info, &accrued_fees)?;
+    }
+
+    let refunded = refunds.len();
+    for swap in refunds {
+        payouts.push((swap.sender, swap.offer_asset));
+    }
+
+    if config.track_asset_balances {
+        for reserve in &new_reserves {
+            BALANCES.save(
+                deps.storage,
+                &reserve.info,
+                &reserve.amount,
+                env.block.height,
+            )?;
+        }
+    }
+
+    let checkpoint_attrs = save_checkpoint(deps.storage, env, new_reserves)?;
+
+    // Store observation from precommit data
+    accumulate_swap_sizes(deps.storage, env, OBSERVATIONS, VOLUME_OBSERVATIONS)?;
+
+    // Store time series data in precommit observation.
+    // This data will be reflected in observations on the next action.
+    if !volumes[0].is_zero() && !volumes[1].is_zero() {
+        PrecommitObservation::save(deps.storage, env, volumes[0], volumes[1])?;
+    }
+
+    // Accumulate prices for the assets in the pool
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env.clone(), &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let clearing_price = price
+        .map(|(numerator, denominator)| {
+            Decimal256::checked_from_ratio(numerator, denominator)
+                .map(|price| price.to_string())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    let mut payout_msgs = vec![];
+    for (ind, (receiver, asset)) in payouts.into_iter().enumerate() {
+        payout_msgs.push(SubMsg::reply_always(
+            asset.clone().into_msg(&receiver)?,
+            ReplyIds::BatchPayout as u64,
+        ));
+        PENDING_PAYOUTS.save(deps.storage, ind as u64, &(receiver, asset))?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(payout_msgs)
+        .add_events(events)
+        .add_attributes([
+            attr("action", "settle_batch"),
+            attr("batch_height", batch.height.to_string()),
+            attr("clearing_price", clearing_price),
+            attr("refunded_swaps", refunded.to_string()),
+        ])
+        .add_attributes(checkpoint_attrs))
+}
+
+/// Returns the pool reserves after the given amounts came into and left the pool.
+fn reserves_after(
+    pools: &[Asset],
+    inflows: &[Uint128],
+    outflows: &[Uint128],
+) -> StdResult<Vec<Asset>> {
+    pools
+        .iter()
+        .zip(inflows.iter().zip(outflows))
+        .map(|(pool, (inflow, outflow))| {
+            Ok(pool
+                .info
+                .with_balance((pool.amount + inflow).checked_sub(*outflow)?))
+        })
+        .collect()
+}
+
+/// Handles the reply of a batch payout. Replies arrive in the order the payouts were sent.
+/// A failed payout is kept in the pair until the receiver claims it.
+pub fn batch_payout_reply(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let (ind, (receiver, asset)) = PENDING_PAYOUTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .ok_or(ContractError::FailedToParseReply {})?;
+    PENDING_PAYOUTS.remove(deps.storage, ind);
+
+    match result {
+        SubMsgResult::Ok(_) => Ok(Response::new()),
+        SubMsgResult::Err(err) => {
+            UNCLAIMED_PAYOUTS.update(
+                deps.storage,
+                (&receiver, &asset.info),
+                |amount| -> StdResult<_> { Ok(amount.unwrap_or_default() + asset.amount) },
+            )?;
+            UNCLAIMED_TOTAL.update(deps.storage, &asset.info, |amount| -> StdResult<_> {
+                Ok(amount.unwrap_or_default() + asset.amount)
+            })?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "batch_payout_failed"),
+                attr("receiver", receiver),
+                attr("payout", asset.to_string()),
+                attr("error", err),
+            ]))
+        }
+    }
+}
+
+/// Sends the sender the batch payouts which failed to reach them during the settlement.
+pub fn claim_batch_payouts(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let payouts = query_unclaimed_payouts(deps.as_ref(), &config, &info.sender)?;
+    if payouts.is_empty() {
+        return Err(ContractError::NoUnclaimedPayouts {});
+    }
+
+    let mut messages = vec![];
+    for payout in &payouts {
+        UNCLAIMED_PAYOUTS.remove(deps.storage, (&info.sender, &payout.info));
+        UNCLAIMED_TOTAL.update(deps.storage, &payout.info, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default().checked_sub(payout.amount)?)
+        })?;
+        messages.push(payout.clone().into_msg(&info.sender)?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "claim_batch_payouts"),
+        attr("receiver", info.sender),
+        attr(
+            "payouts",
+            payouts
+                .iter()
+                .map(Asset::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    ]))
+}
+
+/// Returns the batch payouts which failed to reach the given address.
+pub fn query_unclaimed_payouts(
+    deps: Deps,
+    config: &Config,
+    address: &Addr,
+) -> StdResult<Vec<Asset>> {
+    config
+        .pair_info
+        .asset_infos
+        .iter()
+        .filter_map(|asset_info| {
+            UNCLAIMED_PAYOUTS
+                .may_load(deps.storage, (address, asset_info))
+                .transpose()
+                .map(|amount| amount.map(|amount| asset_info.with_balance(amount)))
+        })
+        .collect()
+}
+
+/// Returns the uniform price of asset\[0\] denominated in asset\[1\] as a (numerator, denominator) pair.
+///
+/// Let `a` and `b` be the total amounts offered in asset\[0\] and asset\[1\].
+/// If `a` is worth more than `b` at the spot price, the pool buys the surplus `dx` of asset\[0\]
+/// and the clearing price equals the average price of that pool swap: `p = y / (x + dx)`.
+/// Everything offered must be paid out at `p`, which gives `dx = (a * y - b * x) / (y + b)`.
+/// The opposite case is symmetric. The surplus is rounded up so the pool never pays more than the curve allows.
+fn clearing_price(pools: &[Asset], swaps: &[BatchedSwap]) -> StdResult<(Uint256, Uint256)> {
+    let x = Uint256::from(pools[0].amount);
+    let y = Uint256::from(pools[1].amount);
+    let (mut a, mut b) = (Uint256::zero(), Uint256::zero());
+    for swap in swaps {
+        if swap.offer_asset.info.equal(&pools[0].info) {
+            a += Uint256::from(swap.offer_asset.amount);
+        } else {
+            b += Uint256::from(swap.offer_asset.amount);
+        }
+    }
+
+    let ay = a.checked_mul(y)?;
+    let bx = b.checked_mul(x)?;
+    if ay >= bx {
+        let dx = div_ceil(ay - bx, y + b)?;
+        Ok((y, x + dx))
+    } else {
+        let dy = div_ceil(bx - ay, x + a)?;
+        Ok((y + dy, x))
+    }
+}
+
+fn div_ceil(numerator: Uint256, denominator: Uint256) -> StdResult<Uint256> {
+    Ok(numerator
+        .checked_add(denominator - Uint256::one())?
+        .checked_div(denominator)?)
+}
+
+fn mul_div(amount: Uint128, numerator: Uint256, denominator: Uint256) -> StdResult<Uint128> {
+    Ok(Uint256::from(amount)
+        .checked_mul(numerator)?
+        .checked_div(denominator)?
+        .try_into()?)
+}
+
+/// Fills a queued swap at the clearing price.
+/// Returns the return amount, the spread against the spot price and the commission,
+/// or an error if the swap doesn't pass its spread check.
+fn fill(
+    pools: &[Asset],
+    (numerator, denominator): (Uint256, Uint256),
+    swap: &BatchedSwap,
+    commission_rate: Decimal,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    let offer_amount = swap.offer_asset.amount;
+    let (gross_return, spot_return) = if swap.offer_asset.info.equal(&pools[0].info) {
+        (
+            mul_div(offer_amount, numerator, denominator)?,
+            offer_amount.multiply_ratio(pools[1].amount, pools[0].amount),
+        )
+    } else {
+        (
+            mul_div(offer_amount, denominator, numerator)?,
+            offer_amount.multiply_ratio(pools[0].amount, pools[1].amount),
+        )
+    };
+    let spread_amount = spot_return.saturating_sub(gross_return);
+    let commission_amount = gross_return * commission_rate;
+
+    assert_max_spread(
+        swap.belief_price,
+        swap.max_spread,
+        offer_amount,
+        gross_return,
+        spread_amount,
+    )?;
+
+    Ok((
+        gross_return - commission_amount,
+        spread_amount,
+        commission_amount,
+    ))
+}
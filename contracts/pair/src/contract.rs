@@ -5,41 +5,67 @@ use std::vec;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg,
-    CustomMsg, CustomQuery, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Fraction, MessageInfo,
-    QuerierWrapper, Reply, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult,
-    Uint128, Uint256, Uint64, WasmMsg,
+    attr, coin, ensure, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, Attribute,
+    Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Fraction, MessageInfo,
+    Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+    Uint256, Uint64, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw_utils::{
     one_coin, parse_reply_instantiate_data, MsgInstantiateContractResponse, PaymentError,
 };
 
 use astroport::asset::{
-    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, CoinsExt, PairInfo,
+    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, AssetInfoExt, CoinsExt, PairInfo,
     MINIMUM_LIQUIDITY_AMOUNT,
 };
 use astroport::common::LP_SUBDENOM;
-use astroport::incentives::ExecuteMsg as IncentiveExecuteMsg;
+use astroport::events::{
+    FlashLoanEvent, FlashSwapEvent, ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent,
+};
+use astroport::observation::{
+    accumulate_swap_sizes, query_fee_apr, query_observation, query_observations_info,
+    query_safe_price, query_volume_sma, store_fee_observation, PrecommitObservation,
+    OBSERVATIONS_SIZE, VOLUME_OBSERVATIONS_SIZE,
+};
 use astroport::pair::{
-    ConfigResponse, FeeShareConfig, ReplyIds, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
+    check_balance_caps, flash_loan_fee, load_lp_fees, update_balance_caps, AccruedFeesResponse,
+    CircuitBreaker, ConfigResponse, FeeShareConfig, FlashLoanReceiverMsg, InvariantCheckpoint,
+    MigrationStatusResponse, ReplyIds, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
+    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS, MAX_FLASH_LOAN_FEE_BPS,
 };
 use astroport::pair::{
-    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
+    check_deadline, check_recipient_commitment, initial_share_after_lockup,
+    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MinimumLiquidityResponse,
+    PairCreationInfo, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    COMMISSION_ASSET, TWAP_PRECISION,
 };
 use astroport::querier::{
-    query_factory_config, query_fee_info, query_native_supply, query_tracker_config,
+    migration_check, query_factory_config, query_fee_info, query_flash_loans_enabled,
+    query_native_supply, query_pair_paused, query_tracker_config, FeeInfo,
 };
 use astroport::token_factory::{
-    tf_before_send_hook_msg, tf_burn_msg, tf_create_denom_msg, tf_mint_msg, MsgCreateDenomResponse,
+    tf_before_send_hook_msg, tf_burn_msg, tf_create_denom_msg, MsgCreateDenomResponse,
 };
 use astroport::{tokenfactory_tracker, U256};
+use astroport_circular_buffer::BufferManager;
+use astroport_pair_common::liquidity::{
+    ensure_min_assets_to_receive, get_share_in_assets, mint_liquidity_token_message,
+    resolve_receiver,
+};
 
+use crate::batch::{
+    batch_payout_reply, claim_batch_payouts, query_reserves, query_unclaimed_payouts, queue_swap,
+    settle, settle_batch,
+};
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::state::{
+    Config, FlashLoanState, ACCRUED_FEES, BALANCES, BALANCE_CAPS, BATCH_MIN_OFFER, BATCH_SWAPS,
+    CHECKPOINTS, CHECKPOINTS_SIZE, CIRCUIT_BREAKER, CONFIG, CREATION_INFO, FEE_OBSERVATIONS,
+    FLASH_LOAN, FLASH_LOAN_FEE_BPS, LP_TOKEN_METADATA, OBSERVATIONS, PENDING_BATCH,
+    VOLUME_OBSERVATIONS,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-pair";
@@ -70,6 +96,16 @@ pub fn instantiate(
     if let Some(init_params) = msg.init_params {
         let params: XYKPoolParams = from_json(init_params)?;
         track_asset_balances = params.track_asset_balances.unwrap_or_default();
+
+        if let Some(lp_token_metadata) = params.lp_token_metadata {
+            lp_token_metadata.validate()?;
+            LP_TOKEN_METADATA.save(deps.storage, &lp_token_metadata)?;
+        }
+
+        if let Some(caps) = params.balance_caps {
+            let caps = update_balance_caps(vec![], caps, &msg.asset_infos)?;
+            BALANCE_CAPS.save(deps.storage, &caps)?;
+        }
     }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -97,6 +133,11 @@ pub fn instantiate(
     }
 
     CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
+
+    BufferManager::init(deps.storage, CHECKPOINTS, CHECKPOINTS_SIZE)?;
+    BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+    BufferManager::init(deps.storage, VOLUME_OBSERVATIONS, VOLUME_OBSERVATIONS_SIZE)?;
 
     // Create LP token
     let sub_msg: SubMsg<_> = SubMsg::reply_on_success(
@@ -144,6 +185,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                                     .to_string(),
                                 tracked_denom: new_token_denom.clone(),
                                 track_over_seconds: false,
+                                leaderboard_min_balance: None,
                             })?,
                             funds: vec![],
                             label: format!("{new_token_denom} tracking contract"),
@@ -154,6 +196,14 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                     sub_msgs.extend(sub_msg);
                 }
 
+                if let Some(lp_token_metadata) = LP_TOKEN_METADATA.may_load(deps.storage)? {
+                    LP_TOKEN_METADATA.remove(deps.storage);
+                    sub_msgs.push(SubMsg::new(lp_token_metadata.into_denom_metadata_msg(
+                        env.contract.address.clone(),
+                        &new_token_denom,
+                    )));
+                }
+
                 CONFIG.update(deps.storage, |mut config| {
                     if !config.pair_info.liquidity_token.is_empty() {
                         return Err(StdError::generic_err(
@@ -192,6 +242,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                 .add_message(set_hook_msg)
                 .add_attribute("tracker_contract", contract_address))
         }
+        ReplyIds::BatchPayout => batch_payout_reply(deps, msg.result),
     }
 }
 
@@ -209,6 +260,8 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             auto_stake,
 ///             receiver,
 ///            min_lp_to_receive,
+///            staking_target,
+///            deadline,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
@@ -216,11 +269,28 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             belief_price,
 ///             max_spread,
 ///             to,
+///             deadline,
 ///         }** Performs a swap operation with the specified parameters.
 /// * **ExecuteMsg::WithdrawLiquidity {
 ///            assets,
 ///           min_assets_to_receive,
+///           deadline,
 ///       }** Withdraws liquidity from the pool.
+///
+/// * **ExecuteMsg::FlashLoan { asset, msg }** Lends a pool asset to the sender within a single transaction.
+///
+/// * **ExecuteMsg::FlashSwap { ask_asset, offer_asset_info, msg }** Sends the ask asset to the sender
+/// which pays for it within a single transaction.
+///
+/// * **ExecuteMsg::FinalizeFlashLoan {}** Verifies the flash loan repayment. Callable only by the pair itself.
+///
+/// * **ExecuteMsg::HaltPair {}** Halts swaps and liquidity provision. Callable by the circuit breaker guardian.
+///
+/// * **ExecuteMsg::ResumePair {}** Resumes the pair after it was halted.
+///
+/// * **ExecuteMsg::SettleBatch {}** Settles swaps queued in previous blocks at a uniform clearing price.
+///
+/// * **ExecuteMsg::ClaimBatchPayouts {}** Sends the sender the batch payouts which failed to reach them.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -228,6 +298,11 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Pool reserves are not consistent until the ongoing flash loan is repaid
+    if FLASH_LOAN.exists(deps.storage) && !matches!(msg, ExecuteMsg::FinalizeFlashLoan {}) {
+        return Err(ContractError::FlashLoanInProgress {});
+    }
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ProvideLiquidity {
@@ -236,23 +311,35 @@ pub fn execute(
             auto_stake,
             receiver,
             min_lp_to_receive,
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-            min_lp_to_receive,
-        ),
+            staking_target,
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+                staking_target,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -271,11 +358,27 @@ pub fn execute(
                 to_addr,
             )
         }
-        ExecuteMsg::UpdateConfig { params } => update_config(deps, info, params),
+        ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
         ExecuteMsg::WithdrawLiquidity {
             assets,
             min_assets_to_receive,
-        } => withdraw_liquidity(deps, env, info, assets, min_assets_to_receive),
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets, min_assets_to_receive)
+        }
+        ExecuteMsg::FlashLoan { asset, msg } => flash_loan(deps, env, info, asset, msg),
+        ExecuteMsg::FlashSwap {
+            ask_asset,
+            offer_asset_info,
+            msg,
+        } => flash_swap(deps, env, info, ask_asset, offer_asset_info, msg),
+        ExecuteMsg::FinalizeFlashLoan {} => finalize_flash_loan(deps, env, info),
+        ExecuteMsg::HaltPair {} => halt_pair(deps, env, info),
+        ExecuteMsg::ResumePair {} => resume_pair(deps, info),
+        ExecuteMsg::SettleBatch {} => settle_batch(deps, env),
+        ExecuteMsg::ClaimBatchPayouts {} => claim_batch_payouts(deps, info),
         _ => Err(ContractError::NonSupported {}),
     }
 }
@@ -294,8 +397,13 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             // Only asset contract can execute this message
             let mut authorized = false;
             let config = CONFIG.load(deps.storage)?;
@@ -346,6 +454,9 @@ pub fn receive_cw20(
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
 /// * **min_lp_to_receive** is an optional parameter which specifies the minimum amount of LP tokens to receive.
+///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+/// It must be registered in the factory; the factory default is used if not set.
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
 #[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
@@ -357,12 +468,16 @@ pub fn provide_liquidity(
     auto_stake: Option<bool>,
     receiver: Option<String>,
     min_lp_to_receive: Option<Uint128>,
+    staking_target: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
-    let mut pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
+    let mut pools = query_reserves(&deps.querier, deps.storage, &config)?;
 
     let deposits = get_deposits_from_assets(deps.as_ref(), &assets, &pools)?;
 
@@ -397,11 +512,13 @@ pub fn provide_liquidity(
     if total_share.is_zero() {
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.factory_addr,
+            &config.pair_info.liquidity_token,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
             false,
+            None,
         )?);
     }
 
@@ -415,27 +532,44 @@ pub fn provide_liquidity(
     }
 
     // Mint LP tokens for the sender or for the receiver (if set)
-    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let receiver = resolve_receiver(deps.api, &receiver, &info.sender)?;
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.factory_addr,
+        &config.pair_info.liquidity_token,
         &env.contract.address,
         &receiver,
         share,
         auto_stake,
+        staking_target.as_deref(),
     )?);
 
+    let new_reserves = pools
+        .iter()
+        .zip(deposits)
+        .map(|(pool, deposit)| Ok(pool.info.with_balance(pool.amount.checked_add(deposit)?)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    check_balance_caps(
+        &BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+        &new_reserves,
+    )?;
+
+    check_circuit_breaker(deps.storage, &pools, &new_reserves)?;
+
     if config.track_asset_balances {
-        for (i, pool) in pools.iter().enumerate() {
+        for reserve in &new_reserves {
             BALANCES.save(
                 deps.storage,
-                &pool.info,
-                &pool.amount.checked_add(deposits[i])?,
+                &reserve.info,
+                &reserve.amount,
                 env.block.height,
             )?;
         }
     }
 
+    let checkpoint_attrs = save_checkpoint(deps.storage, &env, new_reserves)?;
+
     // Accumulate prices for the assets in the pool
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
         accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
@@ -446,61 +580,19 @@ pub fn provide_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", format!("{}, {}", assets[0], assets[1])),
-        attr("share", share),
-    ]))
-}
-
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Incentive contract (if auto staking is specified).
-///
-/// * **recipient** LP token recipient.
-///
-/// * **coin** denom and amount of LP tokens that will be minted for the recipient.
-///
-/// * **auto_stake** determines whether the newly minted LP tokens will
-/// be automatically staked in the Incentives contract on behalf of the recipient.
-pub fn mint_liquidity_token_message<T, C>(
-    querier: QuerierWrapper<C>,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg<T>>, ContractError>
-where
-    C: CustomQuery,
-    T: CustomMsg,
-{
-    let coin = coin(amount.into(), config.pair_info.liquidity_token.to_string());
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(tf_mint_msg(contract_address, coin, recipient));
-    }
-
-    // Mint for the pair contract and stake into the Incentives contract
-    let incentives_addr = query_factory_config(&querier, &config.factory_addr)?.generator_address;
-
-    if let Some(address) = incentives_addr {
-        let mut msgs = tf_mint_msg(contract_address, coin.clone(), contract_address);
-        msgs.push(
-            wasm_execute(
-                address,
-                &IncentiveExecuteMsg::Deposit {
-                    recipient: Some(recipient.to_string()),
-                },
-                vec![coin],
-            )?
-            .into(),
-        );
-        Ok(msgs)
-    } else {
-        Err(ContractError::AutoStakeError {})
-    }
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(
+            ProvideLiquidityEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                receiver,
+                assets,
+                share,
+            }
+            .into_attributes(),
+        )
+        .add_attributes(checkpoint_attrs))
 }
 
 /// Withdraw liquidity from the pool.
@@ -521,7 +613,8 @@ pub fn withdraw_liquidity(
         PaymentError::MissingDenom(config.pair_info.liquidity_token.to_string())
     );
 
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
+    let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
     let refund_assets = if assets.is_empty() {
         // Usual withdraw (balanced)
@@ -530,19 +623,31 @@ pub fn withdraw_liquidity(
         return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
     };
 
-    ensure_min_assets_to_receive(&config, refund_assets.clone(), min_assets_to_receive)?;
+    ensure_min_assets_to_receive(
+        &config.pair_info.asset_infos,
+        refund_assets.clone(),
+        min_assets_to_receive,
+    )?;
+
+    let new_reserves = pools
+        .iter()
+        .zip(&refund_assets)
+        .map(|(pool, refund)| pool.checked_sub(refund))
+        .collect::<StdResult<Vec<_>>>()?;
 
     if config.track_asset_balances {
-        for (i, pool) in pools.iter().enumerate() {
+        for reserve in &new_reserves {
             BALANCES.save(
                 deps.storage,
-                &pool.info,
-                &(pool.amount - refund_assets[i].amount),
+                &reserve.info,
+                &reserve.amount,
                 env.block.height,
             )?;
         }
     }
 
+    let checkpoint_attrs = save_checkpoint(deps.storage, &env, new_reserves)?;
+
     // Accumulate prices for the pair assets
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
         accumulate_prices(env.clone(), &config, pools[0].amount, pools[1].amount)?
@@ -565,37 +670,18 @@ pub fn withdraw_liquidity(
         coin(amount.u128(), config.pair_info.liquidity_token.to_string()),
     ));
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("sender", &info.sender),
-        attr("withdrawn_share", amount),
-        attr(
-            "refund_assets",
-            format!("{}, {}", refund_assets[0], refund_assets[1]),
-        ),
-    ]))
-}
-
-/// Returns the amount of pool assets that correspond to an amount of LP tokens.
-///
-/// * **pools** is the array with assets in the pool.
-///
-/// * **amount** is amount of LP tokens to compute a corresponding amount of assets for.
-///
-/// * **total_share** is the total amount of LP tokens currently minted.
-pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint128) -> Vec<Asset> {
-    let mut share_ratio = Decimal::zero();
-    if !total_share.is_zero() {
-        share_ratio = Decimal::from_ratio(amount, total_share);
-    }
-
-    pools
-        .iter()
-        .map(|a| Asset {
-            info: a.info.clone(),
-            amount: a.amount * share_ratio,
-        })
-        .collect()
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(
+            WithdrawLiquidityEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                withdrawn_share: amount,
+                refund_assets,
+            }
+            .into_attributes(),
+        )
+        .add_attributes(checkpoint_attrs))
 }
 
 /// Performs an swap operation with the specified parameters. The trader must approve the
@@ -627,16 +713,24 @@ pub fn swap(
 
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
+    if BATCH_SWAPS.may_load(deps.storage)?.unwrap_or_default() {
+        return queue_swap(deps, env, sender, offer_asset, belief_price, max_spread, to);
+    }
+
     // If the asset balance is already increased, we should subtract the user deposit from the pool amount
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?
         .into_iter()
-        .map(|mut p| {
+        .map(|p| {
             if p.info.equal(&offer_asset.info) {
-                p.amount = p.amount.checked_sub(offer_asset.amount)?;
+                p.checked_sub(&offer_asset)
+            } else {
+                Ok(p)
             }
-            Ok(p)
         })
         .collect::<StdResult<Vec<_>>>()?;
 
@@ -689,59 +783,61 @@ pub fn swap(
         messages.push(return_asset.into_msg(receiver.clone())?)
     }
 
-    // If this pool is configured to share fees, calculate the amount to send
-    // to the receiver and add the transfer message
-    // The calculation works as follows: We take the share percentage first,
-    // and the remainder is then split between LPs and maker
-    let mut fees_commission_amount = commission_amount;
-    let mut fee_share_amount = Uint128::zero();
-    if let Some(fee_share) = config.fee_share.clone() {
-        // Calculate the fee share amount from the full commission amount
-        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
-        fee_share_amount = fees_commission_amount * share_fee_rate;
-
-        if !fee_share_amount.is_zero() {
-            // Subtract the fee share amount from the commission
-            fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
-
-            // Build send message for the shared amount
-            let fee_share_msg = Asset {
-                info: ask_pool.info.clone(),
-                amount: fee_share_amount,
-            }
-            .into_msg(fee_share.recipient)?;
-            messages.push(fee_share_msg);
-        }
-    }
+    let (fee_share_amount, maker_fee_amount, fee_messages) = charge_commission(
+        deps.storage,
+        &env,
+        &config,
+        &fee_info,
+        &ask_pool.info,
+        commission_amount,
+    )?;
+    messages.extend(fee_messages);
 
-    // Compute the Maker fee
-    let mut maker_fee_amount = Uint128::zero();
-    if let Some(fee_address) = fee_info.fee_address {
-        if let Some(f) = calculate_maker_fee(
-            &ask_pool.info,
-            fees_commission_amount,
-            fee_info.maker_fee_rate,
-        ) {
-            maker_fee_amount = f.amount;
-            messages.push(f.into_msg(fee_address)?);
-        }
-    }
+    let new_offer_pool = offer_pool.amount + offer_amount;
+    let new_ask_pool = ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount;
 
     if config.track_asset_balances {
         BALANCES.save(
             deps.storage,
             &offer_pool.info,
-            &(offer_pool.amount + offer_amount),
+            &new_offer_pool,
             env.block.height,
         )?;
         BALANCES.save(
             deps.storage,
             &ask_pool.info,
-            &(ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount),
+            &new_ask_pool,
             env.block.height,
         )?;
     }
 
+    let new_reserves: Vec<_> = pools
+        .iter()
+        .map(|pool| {
+            if pool.info == offer_pool.info {
+                pool.info.with_balance(new_offer_pool)
+            } else {
+                pool.info.with_balance(new_ask_pool)
+            }
+        })
+        .collect();
+    check_circuit_breaker(deps.storage, &pools, &new_reserves)?;
+    let checkpoint_attrs = save_checkpoint(deps.storage, &env, new_reserves)?;
+
+    // Store observation from precommit data
+    accumulate_swap_sizes(deps.storage, &env, OBSERVATIONS, VOLUME_OBSERVATIONS)?;
+
+    // Store time series data in precommit observation.
+    // This data will be reflected in observations on the next action.
+    if !offer_amount.is_zero() && !return_amount.is_zero() {
+        let (base_amount, quote_amount) = if offer_pool.info.equal(&pools[0].info) {
+            (offer_amount, return_amount)
+        } else {
+            (return_amount, offer_amount)
+        };
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
     // Accumulate prices for the assets in the pool
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
         accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
@@ -758,19 +854,86 @@ pub fn swap(
             // 2. send inactive commission fees to the Maker contract
             messages,
         )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
-            attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("fee_share_amount", fee_share_amount),
-        ]))
+        .add_attributes(
+            SwapEvent {
+                pool: config.pair_info.contract_addr,
+                sender,
+                receiver,
+                offer_asset,
+                return_asset: ask_pool.info.with_balance(return_amount),
+                spread_amount,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount,
+            }
+            .into_attributes(),
+        )
+        .add_attributes(checkpoint_attrs))
+}
+
+/// Splits the swap commission between the fee share recipient, the Maker and liquidity providers
+/// and records it in the accrued fees.
+/// Returns the fee share and Maker fee amounts along with the messages sending them out.
+///
+/// * **ask_info** is the asset in which the commission is charged.
+fn charge_commission(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    fee_info: &FeeInfo,
+    ask_info: &AssetInfo,
+    commission_amount: Uint128,
+) -> Result<(Uint128, Uint128, Vec<CosmosMsg>), ContractError> {
+    let mut messages = vec![];
+
+    // If this pool is configured to share fees, calculate the amount to send
+    // to the receiver and add the transfer message
+    // The calculation works as follows: We take the share percentage first,
+    // and the remainder is then split between LPs and maker
+    let mut fees_commission_amount = commission_amount;
+    let mut fee_share_amount = Uint128::zero();
+    if let Some(fee_share) = &config.fee_share {
+        // Calculate the fee share amount from the full commission amount
+        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
+        fee_share_amount = fees_commission_amount * share_fee_rate;
+
+        if !fee_share_amount.is_zero() {
+            // Subtract the fee share amount from the commission
+            fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
+
+            // Build send message for the shared amount
+            messages.push(
+                ask_info
+                    .with_balance(fee_share_amount)
+                    .into_msg(&fee_share.recipient)?,
+            );
+        }
+    }
+
+    // Compute the Maker fee
+    let mut maker_fee_amount = Uint128::zero();
+    if let Some(fee_address) = &fee_info.fee_address {
+        if let Some(f) =
+            calculate_maker_fee(ask_info, fees_commission_amount, fee_info.maker_fee_rate)
+        {
+            maker_fee_amount = f.amount;
+            messages.push(f.into_msg(fee_address)?);
+        }
+    }
+
+    store_fee_observation(
+        storage,
+        env,
+        FEE_OBSERVATIONS,
+        load_lp_fees(storage, ACCRUED_FEES, &config.pair_info.asset_infos)?,
+    )?;
+    let mut accrued_fees = ACCRUED_FEES
+        .may_load(storage, ask_info)?
+        .unwrap_or_default();
+    accrued_fees.add(commission_amount, maker_fee_amount, fee_share_amount);
+    ACCRUED_FEES.save(storage, ask_info, &accrued_fees)?;
+
+    Ok((fee_share_amount, maker_fee_amount, messages))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -778,6 +941,7 @@ pub fn swap(
 /// * **params** new parameter values.
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     params: Binary,
 ) -> Result<Response, ContractError> {
@@ -808,6 +972,7 @@ pub fn update_config(
             config.fee_share = Some(FeeShareConfig {
                 bps: fee_share_bps,
                 recipient: deps.api.addr_validate(&fee_share_address)?,
+                callback: false,
             });
 
             CONFIG.save(deps.storage, &config)?;
@@ -829,11 +994,474 @@ pub fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        XYKPoolUpdateParams::EnableFlashLoans { fee_bps } => {
+            if fee_bps == 0 || fee_bps > MAX_FLASH_LOAN_FEE_BPS {
+                return Err(ContractError::FlashLoanFeeOutOfBounds {});
+            }
+
+            FLASH_LOAN_FEE_BPS.save(deps.storage, &fee_bps)?;
+
+            response
+                .attributes
+                .push(attr("action", "enable_flash_loans"));
+            response
+                .attributes
+                .push(attr("fee_bps", fee_bps.to_string()));
+        }
+        XYKPoolUpdateParams::DisableFlashLoans => {
+            FLASH_LOAN_FEE_BPS.remove(deps.storage);
+            response
+                .attributes
+                .push(attr("action", "disable_flash_loans"));
+        }
+        XYKPoolUpdateParams::UpdateBalanceCaps { caps } => {
+            let caps = update_balance_caps(
+                BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+                caps,
+                &config.pair_info.asset_infos,
+            )?;
+            BALANCE_CAPS.save(deps.storage, &caps)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_balance_caps"));
+            response.attributes.push(attr(
+                "balance_caps",
+                caps.iter()
+                    .map(Asset::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
+        XYKPoolUpdateParams::SetCircuitBreaker {
+            guardian,
+            max_ratio_change,
+        } => {
+            if max_ratio_change.is_zero() {
+                return Err(ContractError::InvalidMaxRatioChange {});
+            }
+
+            let circuit_breaker = CircuitBreaker {
+                guardian: deps.api.addr_validate(&guardian)?,
+                max_ratio_change,
+                tripped_at: CIRCUIT_BREAKER
+                    .may_load(deps.storage)?
+                    .and_then(|circuit_breaker| circuit_breaker.tripped_at),
+            };
+            CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+            response
+                .attributes
+                .push(attr("action", "set_circuit_breaker"));
+            response.attributes.push(attr("guardian", guardian));
+            response
+                .attributes
+                .push(attr("max_ratio_change", max_ratio_change.to_string()));
+        }
+        XYKPoolUpdateParams::RemoveCircuitBreaker => {
+            CIRCUIT_BREAKER.remove(deps.storage);
+            response
+                .attributes
+                .push(attr("action", "remove_circuit_breaker"));
+        }
+        XYKPoolUpdateParams::EnableBatchSwaps => {
+            BATCH_SWAPS.save(deps.storage, &true)?;
+            response
+                .attributes
+                .push(attr("action", "enable_batch_swaps"));
+        }
+        XYKPoolUpdateParams::DisableBatchSwaps => {
+            BATCH_SWAPS.remove(deps.storage);
+            // Queued swaps must not wait for a settlement which will never be triggered
+            if let Some(batch) = PENDING_BATCH.may_load(deps.storage)? {
+                response = settle(deps, &env, batch, None)?;
+            }
+            response
+                .attributes
+                .push(attr("action", "disable_batch_swaps"));
+        }
+        XYKPoolUpdateParams::UpdateBatchMinOffer { min_offer } => {
+            let min_offer = update_balance_caps(
+                BATCH_MIN_OFFER.may_load(deps.storage)?.unwrap_or_default(),
+                min_offer,
+                &config.pair_info.asset_infos,
+            )?;
+            BATCH_MIN_OFFER.save(deps.storage, &min_offer)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_batch_min_offer"));
+            response.attributes.push(attr(
+                "batch_min_offer",
+                min_offer
+                    .iter()
+                    .map(Asset::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
     }
 
     Ok(response)
 }
 
+/// Halts swaps and liquidity provision until the pair is resumed. Withdrawals are still allowed.
+/// Only the circuit breaker guardian or the factory owner can execute this.
+pub fn halt_pair(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut circuit_breaker = CIRCUIT_BREAKER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::CircuitBreakerNotSet {})?;
+    ensure!(
+        circuit_breaker.tripped_at.is_none(),
+        ContractError::PairHalted {}
+    );
+    assert_guardian(deps.as_ref(), &info, &circuit_breaker)?;
+
+    circuit_breaker.tripped_at = Some(env.block.time.seconds());
+    CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+    Ok(Response::new().add_attribute("action", "halt_pair"))
+}
+
+/// Resumes swaps and liquidity provision after the pair was halted.
+/// Only the circuit breaker guardian or the factory owner can execute this.
+pub fn resume_pair(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut circuit_breaker = CIRCUIT_BREAKER
+        .may_load(deps.storage)?
+        .filter(|circuit_breaker| circuit_breaker.tripped_at.is_some())
+        .ok_or(ContractError::PairNotHalted {})?;
+    assert_guardian(deps.as_ref(), &info, &circuit_breaker)?;
+
+    circuit_breaker.tripped_at = None;
+    CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+    Ok(Response::new().add_attribute("action", "resume_pair"))
+}
+
+/// Checks that the sender is the circuit breaker guardian or the factory owner.
+fn assert_guardian(
+    deps: Deps,
+    info: &MessageInfo,
+    circuit_breaker: &CircuitBreaker,
+) -> Result<(), ContractError> {
+    if info.sender != circuit_breaker.guardian {
+        let config = CONFIG.load(deps.storage)?;
+        let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+        if info.sender != factory_config.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails if the pair is halted or if the operation would shift the reserve ratio
+/// by more than the circuit breaker allows.
+pub(crate) fn check_circuit_breaker(
+    storage: &dyn Storage,
+    old_reserves: &[Asset],
+    new_reserves: &[Asset],
+) -> Result<(), ContractError> {
+    let Some(circuit_breaker) = CIRCUIT_BREAKER.may_load(storage)? else {
+        return Ok(());
+    };
+
+    ensure!(
+        circuit_breaker.tripped_at.is_none(),
+        ContractError::PairHalted {}
+    );
+
+    match circuit_breaker.check(old_reserves, new_reserves)? {
+        Some(change) => Err(ContractError::ReserveRatioChangeTooLarge {
+            change,
+            max: circuit_breaker.max_ratio_change,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Lends a pool asset to the sender and schedules the callback into the borrower
+/// followed by the repayment check.
+///
+/// * **asset** is the borrowed asset.
+///
+/// * **msg** is passed to the borrower's callback as is.
+pub fn flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let fee_bps = FLASH_LOAN_FEE_BPS
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FlashLoansDisabled {})?;
+    if !query_flash_loans_enabled(&deps.querier, &config.factory_addr)? {
+        return Err(ContractError::FlashLoansDisabled {});
+    }
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+    // Loans are repaid to the same reserves thus the circuit breaker isn't checked on finalization
+    ensure!(
+        CIRCUIT_BREAKER
+            .may_load(deps.storage)?
+            .and_then(|circuit_breaker| circuit_breaker.tripped_at)
+            .is_none(),
+        ContractError::PairHalted {}
+    );
+
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
+    let reserve = pools
+        .iter()
+        .find(|pool| pool.info.equal(&asset.info))
+        .ok_or(ContractError::AssetMismatch {})?;
+    if asset.amount.is_zero() || asset.amount >= reserve.amount {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let fee = flash_loan_fee(asset.amount, fee_bps)?;
+    if fee.is_zero() {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    // Prices are accumulated with the reserves before the loan
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env.clone(), &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    FLASH_LOAN.save(
+        deps.storage,
+        &FlashLoanState {
+            loan: asset.clone(),
+            repayment: asset.info.with_balance(fee),
+            balance_before: reserve.amount,
+            fee,
+            reserves_before: vec![],
+        },
+    )?;
+
+    let callback_msg = wasm_execute(
+        &info.sender,
+        &FlashLoanReceiverMsg::FlashLoanCallback {
+            loan: asset.clone(),
+            fee: asset.info.with_balance(fee),
+            msg,
+        },
+        vec![],
+    )?;
+    let finalize_msg = wasm_execute(
+        &env.contract.address,
+        &ExecuteMsg::FinalizeFlashLoan {},
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(asset.clone().into_msg(&info.sender)?)
+        .add_message(callback_msg)
+        .add_message(finalize_msg)
+        .add_attributes(
+            FlashLoanEvent {
+                pool: config.pair_info.contract_addr,
+                borrower: info.sender,
+                loan: asset,
+                fee_amount: fee,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Sends the ask asset to the sender and schedules the callback into the borrower
+/// followed by the payment check. The swap is priced as a regular one plus the flash loan fee.
+///
+/// * **ask_asset** is the asset received before paying for it.
+///
+/// * **offer_asset_info** is the asset used to pay for the swap.
+///
+/// * **msg** is passed to the borrower's callback as is.
+pub fn flash_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset: Asset,
+    offer_asset_info: AssetInfo,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let fee_bps = FLASH_LOAN_FEE_BPS
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FlashLoansDisabled {})?;
+    if !query_flash_loans_enabled(&deps.querier, &config.factory_addr)? {
+        return Err(ContractError::FlashLoansDisabled {});
+    }
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+    // Swaps are settled at the batch clearing price only
+    if BATCH_SWAPS.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::NonSupported {});
+    }
+
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
+    let (offer_pool, ask_pool) =
+        if offer_asset_info.equal(&pools[0].info) && ask_asset.info.equal(&pools[1].info) {
+            (pools[0].clone(), pools[1].clone())
+        } else if offer_asset_info.equal(&pools[1].info) && ask_asset.info.equal(&pools[0].info) {
+            (pools[1].clone(), pools[0].clone())
+        } else {
+            return Err(ContractError::AssetMismatch {});
+        };
+    if ask_asset.amount.is_zero() || ask_asset.amount >= ask_pool.amount {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let (offer_amount, _, commission_amount) = compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        fee_info.total_fee_rate,
+    )?;
+    let fee = flash_loan_fee(offer_amount, fee_bps)?;
+    if fee.is_zero() {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let (fee_share_amount, maker_fee_amount, fee_messages) = charge_commission(
+        deps.storage,
+        &env,
+        &config,
+        &fee_info,
+        &ask_pool.info,
+        commission_amount,
+    )?;
+
+    // Prices are accumulated with the reserves before the swap
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env.clone(), &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let repayment = offer_asset_info.with_balance(offer_amount + fee);
+    FLASH_LOAN.save(
+        deps.storage,
+        &FlashLoanState {
+            loan: ask_asset.clone(),
+            repayment: repayment.clone(),
+            balance_before: offer_pool.amount,
+            fee,
+            reserves_before: pools,
+        },
+    )?;
+
+    let callback_msg = wasm_execute(
+        &info.sender,
+        &FlashLoanReceiverMsg::FlashSwapCallback {
+            ask: ask_asset.clone(),
+            offer: repayment.clone(),
+            msg,
+        },
+        vec![],
+    )?;
+    let finalize_msg = wasm_execute(
+        &env.contract.address,
+        &ExecuteMsg::FinalizeFlashLoan {},
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(ask_asset.clone().into_msg(&info.sender)?)
+        .add_messages(fee_messages)
+        .add_message(callback_msg)
+        .add_message(finalize_msg)
+        .add_attributes(
+            FlashSwapEvent {
+                pool: config.pair_info.contract_addr,
+                borrower: info.sender,
+                ask_asset,
+                offer_asset: repayment,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount,
+                fee_amount: fee,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Verifies that the ongoing flash loan or flash swap was repaid together with the fee.
+pub fn finalize_flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let flash_loan = FLASH_LOAN.load(deps.storage)?;
+    FLASH_LOAN.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
+
+    let expected = flash_loan.balance_before + flash_loan.repayment.amount;
+    let actual = pools
+        .iter()
+        .find(|pool| pool.info.equal(&flash_loan.repayment.info))
+        .map(|pool| pool.amount)
+        .unwrap_or_default();
+    if actual < expected {
+        return Err(ContractError::FlashLoanNotRepaid { expected, actual });
+    }
+
+    // A flash swap moves the reserves as a regular swap does
+    if !flash_loan.reserves_before.is_empty() {
+        check_circuit_breaker(deps.storage, &flash_loan.reserves_before, &pools)?;
+
+        accumulate_swap_sizes(deps.storage, &env, OBSERVATIONS, VOLUME_OBSERVATIONS)?;
+        let (base_amount, quote_amount) = if flash_loan.loan.info.equal(&pools[0].info) {
+            (flash_loan.loan.amount, flash_loan.repayment.amount)
+        } else {
+            (flash_loan.repayment.amount, flash_loan.loan.amount)
+        };
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
+    if config.track_asset_balances {
+        for pool in &pools {
+            BALANCES.save(deps.storage, &pool.info, &pool.amount, env.block.height)?;
+        }
+    }
+
+    let checkpoint_attrs = save_checkpoint(deps.storage, &env, pools)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "finalize_flash_loan"),
+            attr("fee_amount", flash_loan.fee),
+        ])
+        .add_attributes(checkpoint_attrs))
+}
+
 /// Accumulate token prices for the assets in the pool.
 /// Note that this function shifts **block_time** when any of the token prices is zero in order to not
 /// fill an accumulator with a null price for that period.
@@ -925,8 +1553,27 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::SimulateWithdraw { lp_amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 /// * **QueryMsg::SimulateProvide { msg }** Simulates the liquidity provision in the pair contract.
+/// * **QueryMsg::MinimumLiquidity {}** Returns the amount of LP tokens locked in the pair on the first provision.
+/// * **QueryMsg::CommissionAsset {}** Returns the asset in which the pair charges its commission.
+/// * **QueryMsg::InvariantCheckpoints { limit }** Returns the most recent pool invariant checkpoints, newest first.
+/// * **QueryMsg::MigrationStatus {}** Returns whether the factory has scheduled a code migration of the pair.
+/// * **QueryMsg::AccruedFees {}** Returns swap fees collected in each pool asset since inception.
+/// * **QueryMsg::PendingBatch {}** Returns the swaps waiting for the batch settlement.
+/// * **QueryMsg::UnclaimedBatchPayouts { address }** Returns the batch payouts which failed to reach the address.
+/// * **QueryMsg::FeeApr { window }** Returns the LP fee APR estimated over the last `window` seconds.
+/// * **QueryMsg::Observe { seconds_ago }** Returns the price observed `seconds_ago` seconds ago.
+/// * **QueryMsg::SafePrice { window }** Returns the median of the prices observed within the last `window` seconds.
+/// * **QueryMsg::ObservationsInfo {}** Returns the observations buffer metadata.
+/// * **QueryMsg::VolumeSma { window }** Returns the swap volumes observed within the last `window` seconds.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    // The borrowed amount is out of the pool until the flash loan is repaid
+    if FLASH_LOAN.exists(deps.storage) && msg.depends_on_reserves() {
+        return Err(StdError::generic_err(
+            ContractError::FlashLoanInProgress {}.to_string(),
+        ));
+    }
+
     match msg {
         QueryMsg::Pair {} => to_json_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Pool {} => to_json_binary(&query_pool(deps)?),
@@ -938,6 +1585,18 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query_reverse_simulation(deps, ask_asset)?)
         }
         QueryMsg::CumulativePrices {} => to_json_binary(&query_cumulative_prices(deps, env)?),
+        QueryMsg::Observe { seconds_ago } => {
+            to_json_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
+        }
+        QueryMsg::SafePrice { window } => {
+            to_json_binary(&query_safe_price(deps, env, OBSERVATIONS, window)?)
+        }
+        QueryMsg::ObservationsInfo {} => {
+            to_json_binary(&query_observations_info(deps, env, OBSERVATIONS)?)
+        }
+        QueryMsg::VolumeSma { window } => {
+            to_json_binary(&query_volume_sma(deps, env, VOLUME_OBSERVATIONS, window)?)
+        }
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::AssetBalanceAt {
             asset_info,
@@ -948,15 +1607,58 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             assets,
             slippage_tolerance,
         } => to_json_binary(&query_simulate_provide(deps, assets, slippage_tolerance)?),
+        QueryMsg::MinimumLiquidity {} => {
+            to_json_binary(&MinimumLiquidityResponse::new(env.contract.address))
+        }
+        QueryMsg::CommissionAsset {} => to_json_binary(&COMMISSION_ASSET),
+        QueryMsg::InvariantCheckpoints { limit } => {
+            to_json_binary(&query_invariant_checkpoints(deps, limit)?)
+        }
+        QueryMsg::MigrationStatus {} => to_json_binary(&query_migration_status(deps, env)?),
+        QueryMsg::AccruedFees {} => to_json_binary(&query_accrued_fees(deps)?),
+        QueryMsg::PendingBatch {} => to_json_binary(&PENDING_BATCH.may_load(deps.storage)?),
+        QueryMsg::UnclaimedBatchPayouts { address } => {
+            let config = CONFIG.load(deps.storage)?;
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&query_unclaimed_payouts(deps, &config, &address)?)
+        }
+        QueryMsg::FeeApr { window } => {
+            let config = CONFIG.load(deps.storage)?;
+            let lp_fees = load_lp_fees(deps.storage, ACCRUED_FEES, &config.pair_info.asset_infos)?;
+            let reserves = query_reserves(&deps.querier, deps.storage, &config)?;
+
+            to_json_binary(&query_fee_apr(
+                deps,
+                env,
+                FEE_OBSERVATIONS,
+                lp_fees,
+                reserves,
+                window,
+            )?)
+        }
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
 
+/// Returns whether the factory has scheduled a code migration of the pair.
+pub fn query_migration_status(deps: Deps, env: Env) -> StdResult<MigrationStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(MigrationStatusResponse {
+        migration_scheduled: migration_check(
+            &deps.querier,
+            &config.factory_addr,
+            &env.contract.address,
+        )?,
+    })
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let assets = query_reserves(&deps.querier, deps.storage, &config)?;
+    let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
     let resp = PoolResponse {
         assets,
@@ -972,7 +1674,8 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
 /// * **amount** is the amount of LP tokens for which we calculate associated amounts of assets.
 pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     let config = CONFIG.load(deps.storage)?;
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
+    let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
     let refund_assets = get_share_in_assets(&pools, amount, total_share);
 
     Ok(refund_assets)
@@ -984,9 +1687,7 @@ pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
 pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -1033,9 +1734,7 @@ pub fn query_reverse_simulation(
 ) -> StdResult<ReverseSimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
 
     let offer_pool: Asset;
     let ask_pool: Asset;
@@ -1075,7 +1774,8 @@ pub fn query_reverse_simulation(
 /// Returns information about cumulative prices for the assets in the pool using a [`CumulativePricesResponse`] object.
 pub fn query_cumulative_prices(deps: Deps, env: Env) -> StdResult<CumulativePricesResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let assets = query_reserves(&deps.querier, deps.storage, &config)?;
+    let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
     let mut price0_cumulative_last = config.price0_cumulative_last;
     let mut price1_cumulative_last = config.price1_cumulative_last;
@@ -1120,10 +1820,17 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         params: Some(to_json_binary(&XYKPoolConfig {
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            flash_loan_fee_bps: FLASH_LOAN_FEE_BPS.may_load(deps.storage)?,
+            balance_caps: BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+            circuit_breaker: CIRCUIT_BREAKER.may_load(deps.storage)?,
+            batch_swaps: BATCH_SWAPS.may_load(deps.storage)?.unwrap_or_default(),
+            batch_min_offer: BATCH_MIN_OFFER.may_load(deps.storage)?.unwrap_or_default(),
         })?),
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
         tracker_addr: config.tracker_addr,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
     })
 }
 
@@ -1141,9 +1848,7 @@ fn query_simulate_provide(
 ) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
 
-    let pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let pools = query_reserves(&deps.querier, deps.storage, &config)?;
 
     let deposits = get_deposits_from_assets(deps, &assets, &pools)
         .map_err(|e| StdError::generic_err(e.to_string()))?;
@@ -1155,6 +1860,44 @@ fn query_simulate_provide(
     Ok(share)
 }
 
+/// Returns up to `limit` most recent invariant checkpoints, newest first.
+/// All stored checkpoints are returned if the limit is not specified.
+pub fn query_invariant_checkpoints(
+    deps: Deps,
+    limit: Option<u32>,
+) -> StdResult<Vec<InvariantCheckpoint>> {
+    let buffer = BufferManager::new(deps.storage, CHECKPOINTS)?;
+    let capacity = buffer.capacity();
+    let limit = limit.unwrap_or(capacity).min(capacity);
+
+    let mut checkpoints = vec![];
+    for i in 1..=limit {
+        match buffer.read_single(deps.storage, buffer.head() + capacity - i)? {
+            Some(checkpoint) => checkpoints.push(checkpoint),
+            None => break,
+        }
+    }
+
+    Ok(checkpoints)
+}
+
+/// Returns swap fees collected in each pool asset. Assets which were never swapped to have zero fees.
+pub fn query_accrued_fees(deps: Deps) -> StdResult<Vec<AccruedFeesResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    config
+        .pair_info
+        .asset_infos
+        .into_iter()
+        .map(|asset_info| {
+            let fees = ACCRUED_FEES
+                .may_load(deps.storage, &asset_info)?
+                .unwrap_or_default();
+            Ok(AccruedFeesResponse { asset_info, fees })
+        })
+        .collect()
+}
+
 /// Returns the balance of the specified asset that was in the pool
 /// just preceeding the moment of the specified block height creation.
 /// It will return None (null) if the balance was not tracked up to the specified block height
@@ -1273,16 +2016,10 @@ pub fn calculate_shares(
             (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
                 .integer_sqrt()
                 .as_u128(),
-        )
-        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
-        .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+        );
 
         // share cannot become zero after minimum liquidity subtraction
-        if share.is_zero() {
-            return Err(ContractError::MinimumLiquidityAmountError {});
-        }
-
-        share
+        initial_share_after_lockup(share).ok_or(ContractError::MinimumLiquidityAmountError {})?
     } else {
         // Assert slippage tolerance
         assert_slippage_tolerance(slippage_tolerance, deposits, pools)?;
@@ -1292,10 +2029,16 @@ pub fn calculate_shares(
         // == deposit_0 * total_share / pool_0
         // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
         // == deposit_1 * total_share / pool_1
-        std::cmp::min(
+        let share = std::cmp::min(
             deposits[0].multiply_ratio(total_share, pools[0].amount),
             deposits[1].multiply_ratio(total_share, pools[1].amount),
-        )
+        );
+
+        if share.is_zero() {
+            return Err(ContractError::LiquidityAmountTooSmall {});
+        }
+
+        share
     };
     Ok(share)
 }
@@ -1421,61 +2164,66 @@ pub fn assert_slippage_tolerance(
 
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    unimplemented!("No safe path available for migration from cw20 to tokenfactory LP tokens")
-}
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    // There is no safe path for migration from cw20 to tokenfactory LP tokens
+    match contract_version.contract.as_ref() {
+        "astroport-pair" => match contract_version.version.as_ref() {
+            "2.1.0" => {
+                BufferManager::init(deps.storage, CHECKPOINTS, CHECKPOINTS_SIZE)?;
+                BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+                BufferManager::init(deps.storage, VOLUME_OBSERVATIONS, VOLUME_OBSERVATIONS_SIZE)?;
+            }
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub fn pool_info(querier: QuerierWrapper, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
-    let pools = config
-        .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
-    let total_share = query_native_supply(&querier, &config.pair_info.liquidity_token)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    Ok((pools, total_share))
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
 }
 
-fn ensure_min_assets_to_receive(
-    config: &Config,
-    mut refund_assets: Vec<Asset>,
-    min_assets_to_receive: Option<Vec<Asset>>,
-) -> Result<(), ContractError> {
-    if let Some(min_assets_to_receive) = min_assets_to_receive {
-        if refund_assets.len() != min_assets_to_receive.len() {
-            return Err(ContractError::WrongAssetLength {
-                expected: refund_assets.len(),
-                actual: min_assets_to_receive.len(),
-            });
-        }
-
-        for asset in &min_assets_to_receive {
-            if !config.pair_info.asset_infos.contains(&asset.info) {
-                return Err(ContractError::AssetMismatch {});
-            }
-        }
-
-        if refund_assets[0].info.ne(&min_assets_to_receive[0].info) {
-            refund_assets.swap(0, 1)
-        }
+/// Records the pool reserves and the constant product invariant after an operation.
+/// Returns the attributes describing the checkpoint.
+///
+/// * **reserves** pool reserves after the operation in the pair assets order.
+pub fn save_checkpoint(
+    storage: &mut dyn Storage,
+    env: &Env,
+    reserves: Vec<Asset>,
+) -> Result<Vec<Attribute>, ContractError> {
+    let k = reserves.iter().fold(Uint256::one(), |acc, reserve| {
+        acc * Uint256::from(reserve.amount)
+    });
+    let checkpoint = InvariantCheckpoint {
+        block_height: env.block.height,
+        block_time: env.block.time.seconds(),
+        reserves,
+        k,
+    };
 
-        if refund_assets[0].amount < min_assets_to_receive[0].amount {
-            return Err(ContractError::WithdrawSlippageViolation {
-                asset_name: refund_assets[0].info.to_string(),
-                received: refund_assets[0].amount,
-                expected: min_assets_to_receive[0].amount,
-            });
-        }
+    let attrs = vec![
+        attr(
+            "reserves",
+            checkpoint
+                .reserves
+                .iter()
+                .map(|reserve| reserve.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        attr("invariant_k", k),
+    ];
 
-        if refund_assets[1].amount < min_assets_to_receive[1].amount {
-            return Err(ContractError::WithdrawSlippageViolation {
-                asset_name: refund_assets[1].info.to_string(),
-                received: refund_assets[1].amount,
-                expected: min_assets_to_receive[1].amount,
-            });
-        }
-    }
+    BufferManager::new(storage, CHECKPOINTS)?.instant_push(storage, &checkpoint)?;
 
-    Ok(())
+    Ok(attrs)
 }
 
 #[cfg(test)]
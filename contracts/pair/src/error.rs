@@ -1,8 +1,15 @@
-use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use astroport::{
+    asset::MINIMUM_LIQUIDITY_AMOUNT,
+    pair::{MAX_FEE_SHARE_BPS, MAX_FLASH_LOAN_FEE_BPS},
+};
+use astroport_circular_buffer::error::BufferError;
+use astroport_pair_common::error::PairCommonError;
+use cosmwasm_std::{Decimal, Decimal256, OverflowError, StdError, Uint128};
 use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
+use crate::state::{MAX_BATCH_SIZE, MAX_BATCH_SWAPS_PER_SENDER};
+
 /// This enum describes pair contract errors
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -15,6 +22,9 @@ pub enum ContractError {
     #[error("{0}")]
     ParseReplyError(#[from] ParseReplyError),
 
+    #[error("{0}")]
+    CircularBuffer(#[from] BufferError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -64,6 +74,9 @@ pub enum ContractError {
     #[error("Initial liquidity must be more than {}", MINIMUM_LIQUIDITY_AMOUNT)]
     MinimumLiquidityAmountError {},
 
+    #[error("Insufficient amount of liquidity")]
+    LiquidityAmountTooSmall {},
+
     #[error("Failed to migrate the contract")]
     MigrationError {},
 
@@ -75,6 +88,63 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error(
+        "Flash loan fee is 0 or exceeds maximum allowed value of {} bps",
+        MAX_FLASH_LOAN_FEE_BPS
+    )]
+    FlashLoanFeeOutOfBounds {},
+
+    #[error("Flash loans are disabled")]
+    FlashLoansDisabled {},
+
+    #[error("Operation is not allowed while a flash loan is in progress")]
+    FlashLoanInProgress {},
+
+    #[error("Flash loan amount must be positive and less than the pool reserve")]
+    InvalidFlashLoanAmount {},
+
+    #[error("Flash loan is not repaid: pool balance is {actual}, expected at least {expected}")]
+    FlashLoanNotRepaid { expected: Uint128, actual: Uint128 },
+
+    #[error("Pair is halted by the circuit breaker guardian, only withdrawals are allowed")]
+    PairHalted {},
+
+    #[error("Pair is not halted")]
+    PairNotHalted {},
+
+    #[error("Circuit breaker is not set")]
+    CircuitBreakerNotSet {},
+
+    #[error("Operation would shift the reserve ratio by {change}, the circuit breaker allows at most {max}")]
+    ReserveRatioChangeTooLarge { change: Decimal256, max: Decimal },
+
+    #[error("Circuit breaker max ratio change must be positive")]
+    InvalidMaxRatioChange {},
+
+    #[error(
+        "Batch is full: at most {} swaps can be queued per block",
+        MAX_BATCH_SIZE
+    )]
+    BatchIsFull {},
+
+    #[error(
+        "At most {} swaps can be queued per sender in a single batch",
+        MAX_BATCH_SWAPS_PER_SENDER
+    )]
+    TooManyBatchedSwaps {},
+
+    #[error("Batched swap offer must be at least {min_offer}")]
+    BatchOfferTooSmall { min_offer: String },
+
+    #[error("No unclaimed batch payouts")]
+    NoUnclaimedPayouts {},
+
+    #[error("No swaps queued in previous blocks to settle")]
+    NothingToSettle {},
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -82,3 +152,26 @@ impl From<OverflowError> for ContractError {
         StdError::from(o).into()
     }
 }
+
+impl From<PairCommonError> for ContractError {
+    fn from(err: PairCommonError) -> Self {
+        match err {
+            PairCommonError::Std(err) => ContractError::Std(err),
+            PairCommonError::DoublingAssets {} => ContractError::DoublingAssets {},
+            PairCommonError::AutoStakeError {} => ContractError::AutoStakeError {},
+            PairCommonError::WrongAssetLength { expected, actual } => {
+                ContractError::WrongAssetLength { expected, actual }
+            }
+            PairCommonError::AssetMismatch {} => ContractError::AssetMismatch {},
+            PairCommonError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            } => ContractError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            },
+        }
+    }
+}
@@ -1,10 +1,15 @@
 use astroport::{
-    asset::{AssetInfo, PairInfo},
-    pair::FeeShareConfig,
+    asset::{Asset, AssetInfo, PairInfo},
+    observation::{FeeObservation, Observation, VolumeObservation},
+    pair::{
+        AccruedFees, CircuitBreaker, FeeShareConfig, InvariantCheckpoint, LpTokenMetadata,
+        PairCreationInfo, SwapBatch,
+    },
 };
+use astroport_circular_buffer::CircularBuffer;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, SnapshotMap};
+use cw_storage_plus::{Item, Map, SnapshotMap};
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -30,6 +35,12 @@ pub struct Config {
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
+
+/// Temporarily stores custom LP token metadata until the LP denom is created
+pub const LP_TOKEN_METADATA: Item<LpTokenMetadata> = Item::new("lp_token_metadata");
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
@@ -37,3 +48,78 @@ pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances_change",
     cw_storage_plus::Strategy::EveryBlock,
 );
+
+/// Number of invariant checkpoints kept in the buffer
+pub const CHECKPOINTS_SIZE: u32 = 30;
+
+/// Stores pool invariant checkpoints recorded after every swap, provide and withdraw
+pub const CHECKPOINTS: CircularBuffer<InvariantCheckpoint> =
+    CircularBuffer::new("checkpoints_state", "checkpoints_buffer");
+
+/// Cumulative swap fees collected in each pool asset. key: ask asset info
+pub const ACCRUED_FEES: Map<&AssetInfo, AccruedFees> = Map::new("accrued_fees");
+
+/// Circular buffer to store cumulative LP fee snapshots used to estimate the fee APR
+pub const FEE_OBSERVATIONS: CircularBuffer<FeeObservation> =
+    CircularBuffer::new("fee_observations_state", "fee_observations_buffer");
+
+/// Circular buffer to store trade size observations
+pub const OBSERVATIONS: CircularBuffer<Observation> =
+    CircularBuffer::new("observations_state", "observations_buffer");
+
+/// Circular buffer to store swap volumes traded within a block
+pub const VOLUME_OBSERVATIONS: CircularBuffer<VolumeObservation> =
+    CircularBuffer::new("volume_observations_state", "volume_observations_buffer");
+
+/// Flash loan fee in bps. Flash loans are disabled in the pair if not set
+pub const FLASH_LOAN_FEE_BPS: Item<u16> = Item::new("flash_loan_fee_bps");
+
+/// Caps on pool balances. Liquidity provision is not capped if not set
+pub const BALANCE_CAPS: Item<Vec<Asset>> = Item::new("balance_caps");
+
+/// Halts swaps and liquidity provision on large reserve ratio shifts. Disabled if not set
+pub const CIRCUIT_BREAKER: Item<CircuitBreaker> = Item::new("circuit_breaker");
+
+/// Whether swaps are queued and settled in batches. Swaps are executed right away if not set
+pub const BATCH_SWAPS: Item<bool> = Item::new("batch_swaps");
+
+/// Max number of swaps which can be queued within a single block
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Max number of swaps a single sender can queue within a single block
+pub const MAX_BATCH_SWAPS_PER_SENDER: usize = 5;
+
+/// Swaps queued in the batch mode which are not settled yet
+pub const PENDING_BATCH: Item<SwapBatch> = Item::new("pending_batch");
+
+/// Minimum amounts which can be offered in a batched swap. Offers are not limited if not set
+pub const BATCH_MIN_OFFER: Item<Vec<Asset>> = Item::new("batch_min_offer");
+
+/// Batch outputs and refunds sent as submessages and waiting for their replies. key: send order
+pub const PENDING_PAYOUTS: Map<u64, (Addr, Asset)> = Map::new("pending_payouts");
+
+/// Batch outputs and refunds which failed to reach their receivers. key: receiver, asset info
+pub const UNCLAIMED_PAYOUTS: Map<(&Addr, &AssetInfo), Uint128> = Map::new("unclaimed_payouts");
+
+/// Total unclaimed batch payouts. They are held by the pair but don't belong to the pool. key: asset info
+pub const UNCLAIMED_TOTAL: Map<&AssetInfo, Uint128> = Map::new("unclaimed_total");
+
+/// Stores the ongoing flash loan until it is finalized in the same transaction
+pub const FLASH_LOAN: Item<FlashLoanState> = Item::new("flash_loan");
+
+/// The state needed to verify the flash loan or flash swap repayment.
+#[cw_serde]
+pub struct FlashLoanState {
+    /// The borrowed asset. For flash swaps it is the ask asset which is not returned
+    pub loan: Asset,
+    /// The repayment asset and the amount by which its pool balance must grow.
+    /// For flash loans it is the fee in the borrowed asset, for flash swaps it is the whole offer
+    pub repayment: Asset,
+    /// The pool balance of the repayment asset before the loan
+    pub balance_before: Uint128,
+    /// The flash loan fee included in the repayment
+    pub fee: Uint128,
+    /// The pool reserves before a flash swap. Empty for flash loans
+    #[serde(default)]
+    pub reserves_before: Vec<Asset>,
+}
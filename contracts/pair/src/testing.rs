@@ -1,31 +1,37 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     attr, coin, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal,
-    DepsMut, Env, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult,
-    Timestamp, Uint128, WasmMsg,
+    DepsMut, Empty, Env, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult,
+    Timestamp, Uint128, Uint256, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use proptest::prelude::*;
 use prost::Message;
+use sha2::{Digest, Sha256};
 
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::common::LP_SUBDENOM;
+use astroport::events::EVENTS_SCHEMA_VERSION;
 use astroport::factory::PairType;
 use astroport::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse, TWAP_PRECISION,
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LpTokenMetadata, PoolResponse, RecipientCommitment,
+    ReverseSimulationResponse, SimulationResponse, XYKPoolParams, TWAP_PRECISION,
 };
-use astroport::token_factory::{MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint};
+use astroport::token_factory::{
+    MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint, MsgSetDenomMetadata,
+};
+use astroport_circular_buffer::BufferManager;
 
 use crate::contract::compute_offer_amount;
 use crate::contract::reply;
 use crate::contract::{
-    accumulate_prices, assert_max_spread, compute_swap, execute, instantiate, query_pool,
-    query_reverse_simulation, query_share, query_simulation,
+    accumulate_prices, assert_max_spread, compute_swap, execute, instantiate, migrate,
+    query_invariant_checkpoints, query_pool, query_reverse_simulation, query_share,
+    query_simulation, save_checkpoint,
 };
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CHECKPOINTS, CHECKPOINTS_SIZE, CONFIG};
 
 #[derive(Clone, PartialEq, Message)]
 struct MsgInstantiateContractResponse {
@@ -122,6 +128,101 @@ fn proper_initialization() {
     );
 }
 
+#[test]
+fn custom_lp_token_metadata() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+
+    let mut msg = InstantiateMsg {
+        pair_type: PairType::Xyk {},
+        factory_addr: String::from("factory"),
+        asset_infos: vec![AssetInfo::native("uusd"), AssetInfo::native("uluna")],
+        token_code_id: 10u64,
+        init_params: Some(
+            to_json_binary(&XYKPoolParams {
+                track_asset_balances: None,
+                lp_token_metadata: Some(LpTokenMetadata {
+                    name: "Index fund LP".to_string(),
+                    symbol: "?!".to_string(),
+                }),
+                balance_caps: None,
+            })
+            .unwrap(),
+        ),
+    };
+
+    let err = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "LP token symbol is not in expected format [a-zA-Z0-9\\-]{3,12}"
+        ))
+    );
+
+    msg.init_params = Some(
+        to_json_binary(&XYKPoolParams {
+            track_asset_balances: None,
+            lp_token_metadata: Some(LpTokenMetadata {
+                name: "Index fund LP".to_string(),
+                symbol: "IDX-LP".to_string(),
+            }),
+            balance_caps: None,
+        })
+        .unwrap(),
+    );
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let denom = format!("factory/{}/{}", env.contract.address, LP_SUBDENOM);
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(
+                    MsgCreateDenomResponse {
+                        new_token_denom: denom.clone(),
+                    }
+                    .into(),
+                ),
+            }),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 1);
+    let (type_url, value) = match &res.messages[0].msg {
+        CosmosMsg::Stargate { type_url, value } => (type_url, value),
+        msg => panic!("Unexpected message {msg:?}"),
+    };
+    assert_eq!(type_url, MsgSetDenomMetadata::TYPE_URL);
+    let metadata = MsgSetDenomMetadata::try_from(value.clone())
+        .unwrap()
+        .metadata
+        .unwrap();
+    assert_eq!(metadata.base, denom);
+    assert_eq!(metadata.name, "Index fund LP");
+    assert_eq!(metadata.symbol, "IDX-LP");
+    assert_eq!(metadata.display, "IDX-LP");
+    assert_eq!(metadata.denom_units[1].exponent, 6);
+
+    assert_eq!(
+        CONFIG
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .pair_info
+            .liquidity_token,
+        denom
+    );
+}
+
 #[test]
 fn provide_liquidity() {
     let mut deps = mock_dependencies(&[Coin {
@@ -184,6 +285,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -309,6 +412,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -389,6 +494,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -453,6 +560,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -495,6 +604,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -537,6 +648,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -578,6 +691,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -609,6 +724,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0001",
@@ -639,6 +756,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0001",
@@ -713,6 +832,7 @@ fn withdraw_liquidity() {
     let msg = ExecuteMsg::WithdrawLiquidity {
         assets: vec![],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -849,6 +969,8 @@ fn try_native_to_token() {
         belief_price: None,
         max_spread: Some(Decimal::percent(50)),
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info(
@@ -960,6 +1082,8 @@ fn try_native_to_token() {
         true
     );
 
+    let new_collateral_pool = collateral_pool_amount + offer_amount;
+    let new_asset_pool = asset_pool_amount - expected_return_amount - expected_maker_fee_amount;
     assert_eq!(
         res.attributes,
         vec![
@@ -974,6 +1098,24 @@ fn try_native_to_token() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    expected_spread_amount,
+                    expected_return_amount + expected_spread_amount + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+            attr(
+                "reserves",
+                format!("{new_collateral_pool}uusd, {new_asset_pool}asset0000")
+            ),
+            attr(
+                "invariant_k",
+                (Uint256::from(new_collateral_pool) * Uint256::from(new_asset_pool)).to_string()
+            ),
         ]
     );
 
@@ -1057,6 +1199,8 @@ fn try_token_to_native() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info("addr0000", &[]);
@@ -1072,6 +1216,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: Some(Decimal::percent(50)),
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -1152,6 +1298,9 @@ fn try_token_to_native() {
         true
     );
 
+    let new_collateral_pool =
+        collateral_pool_amount - expected_return_amount - expected_maker_fee_amount;
+    let new_asset_pool = asset_pool_amount + offer_amount;
     assert_eq!(
         res.attributes,
         vec![
@@ -1166,6 +1315,24 @@ fn try_token_to_native() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    expected_spread_amount,
+                    expected_return_amount + expected_spread_amount + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+            attr(
+                "reserves",
+                format!("{new_collateral_pool}uusd, {new_asset_pool}asset0000")
+            ),
+            attr(
+                "invariant_k",
+                (Uint256::from(new_collateral_pool) * Uint256::from(new_asset_pool)).to_string()
+            ),
         ]
     );
 
@@ -1195,6 +1362,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -1374,6 +1543,73 @@ fn test_query_share() {
     assert_eq!(res[1].amount, Uint128::new(500));
 }
 
+#[test]
+fn test_invariant_checkpoints() {
+    let mut deps = mock_dependencies(&[]);
+    let mut env = mock_env();
+
+    BufferManager::init(&mut deps.storage, CHECKPOINTS, CHECKPOINTS_SIZE).unwrap();
+
+    let res = query_invariant_checkpoints(deps.as_ref(), None).unwrap();
+    assert!(res.is_empty());
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let asset = AssetInfo::Token {
+        contract_addr: Addr::unchecked("asset0000"),
+    };
+
+    let first_height = env.block.height;
+    for i in 1..=CHECKPOINTS_SIZE as u128 + 5 {
+        let attrs = save_checkpoint(
+            &mut deps.storage,
+            &env,
+            vec![uusd.with_balance(i * 1000), asset.with_balance(i * 2000)],
+        )
+        .unwrap();
+        assert_eq!(
+            attrs,
+            vec![
+                attr(
+                    "reserves",
+                    format!("{}uusd, {}asset0000", i * 1000, i * 2000)
+                ),
+                attr("invariant_k", (i * i * 2_000_000).to_string()),
+            ]
+        );
+
+        env.block.height += 1;
+    }
+    let last_height = env.block.height - 1;
+
+    let res = query_invariant_checkpoints(deps.as_ref(), Some(3)).unwrap();
+    assert_eq!(
+        res.iter().map(|c| c.block_height).collect::<Vec<_>>(),
+        vec![last_height, last_height - 1, last_height - 2]
+    );
+    assert_eq!(
+        res[0].reserves,
+        vec![
+            uusd.with_balance(1000 * (CHECKPOINTS_SIZE as u128 + 5)),
+            asset.with_balance(2000 * (CHECKPOINTS_SIZE as u128 + 5))
+        ]
+    );
+    assert_eq!(
+        res[0].k,
+        Uint256::from(res[0].reserves[0].amount) * Uint256::from(res[0].reserves[1].amount)
+    );
+
+    // Old checkpoints are overwritten
+    let res = query_invariant_checkpoints(deps.as_ref(), None).unwrap();
+    assert_eq!(res.len(), CHECKPOINTS_SIZE as usize);
+    assert_eq!(res.last().unwrap().block_height, first_height + 5);
+
+    // The limit is capped by the buffer capacity
+    let res = query_invariant_checkpoints(deps.as_ref(), Some(CHECKPOINTS_SIZE * 2)).unwrap();
+    assert_eq!(res.len(), CHECKPOINTS_SIZE as usize);
+}
+
 #[test]
 fn test_accumulate_prices() {
     struct Case {
@@ -1613,3 +1849,217 @@ fn ensure_useful_error_messages_are_given_on_swaps() {
     );
     compute_offer_amount(OFFER, ASK, AMOUNT, DZERO).unwrap();
 }
+
+#[test]
+fn expired_deadline() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let env = mock_env_with_block_time(1000);
+    let expected_err = ContractError::Std(StdError::generic_err(
+        "Deadline 999 has passed. Current block time: 1000",
+    ));
+
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(100u128),
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset0000"),
+                },
+                amount: Uint128::from(100u128),
+            },
+        ],
+        slippage_tolerance: None,
+        auto_stake: None,
+        receiver: None,
+        min_lp_to_receive: None,
+        staking_target: None,
+        deadline: Some(999),
+    };
+    let info = mock_info("addr0000", &[coin(100, "uusd")]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: Some(999),
+        to_commitment: None,
+    };
+    let info = mock_info("addr0000", &[coin(100, "uusd")]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: String::from("addr0000"),
+        amount: Uint128::from(100u128),
+        msg: to_json_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            deadline: Some(999),
+            to_commitment: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        assets: vec![],
+        min_assets_to_receive: None,
+        deadline: Some(999),
+    };
+    let info = mock_info("addr0000", &[coin(100, "liquidity0000")]);
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    // The deadline itself is still valid
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        deadline: Some(1000),
+        to_commitment: None,
+    };
+    let info = mock_info("addr0000", &[coin(100, "uusd")]);
+    let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_ne!(err, expected_err);
+}
+
+#[test]
+fn swap_recipient_commitment() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let salt = Binary::from(b"salt".to_vec());
+    let to_commitment = RecipientCommitment {
+        hash: Binary::from(
+            Sha256::new()
+                .chain_update(b"recipient")
+                .chain_update(salt.as_slice())
+                .finalize()
+                .to_vec(),
+        ),
+        salt,
+    };
+    let expected_err = ContractError::Std(StdError::generic_err(
+        "Swap recipient doesn't match the commitment",
+    ));
+
+    let swap_msg = |to: &str| ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: None,
+        to: Some(to.to_string()),
+        deadline: None,
+        to_commitment: Some(to_commitment.clone()),
+    };
+
+    // The relayer tries to redirect the output
+    let info = mock_info("relayer", &[coin(100, "uusd")]);
+    let err = execute(deps.as_mut(), mock_env(), info, swap_msg("relayer")).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: String::from("relayer"),
+        amount: Uint128::from(100u128),
+        msg: to_json_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: Some("relayer".to_string()),
+            deadline: None,
+            to_commitment: Some(to_commitment.clone()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, expected_err);
+
+    // The revealed recipient matches the commitment
+    let info = mock_info("relayer", &[coin(100, "uusd")]);
+    let err = execute(deps.as_mut(), mock_env(), info, swap_msg("recipient")).unwrap_err();
+    assert_ne!(err, expected_err);
+}
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies(&[]);
+
+    cw2::set_contract_version(&mut deps.storage, "astroport-pair", "1.5.1").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), Empty {}).unwrap_err();
+    assert_eq!(err, ContractError::MigrationError {});
+
+    cw2::set_contract_version(&mut deps.storage, "astroport-pair", "2.1.0").unwrap();
+    migrate(deps.as_mut(), mock_env(), Empty {}).unwrap();
+
+    // Buffers introduced after 2.1.0 are initialized
+    let res = query_invariant_checkpoints(deps.as_ref(), None).unwrap();
+    assert!(res.is_empty());
+
+    let version = cw2::get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
@@ -1,21 +1,26 @@
 #![cfg(not(tarpaulin_include))]
 
 use cosmwasm_std::{
-    attr, coin, to_json_binary, Addr, Coin, Decimal, DepsMut, Empty, Env, MessageInfo, Response,
-    StdResult, Uint128, Uint64,
+    attr, coin, from_json, to_json_binary, wasm_execute, Addr, Binary, Coin, Decimal, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Response, StdResult, Uint128, Uint64,
 };
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 
-use astroport::asset::{native_asset_info, Asset, AssetInfo, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::asset::{
+    native_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+};
 use astroport::common::LP_SUBDENOM;
 use astroport::factory::{
     ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType,
     QueryMsg as FactoryQueryMsg, TrackerConfig,
 };
+use astroport::observation::{ObservationsInfoResponse, OracleObservation, VolumeSmaResponse};
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, FeeShareConfig,
-    InstantiateMsg, PoolResponse, QueryMsg, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    MAX_FEE_SHARE_BPS, TWAP_PRECISION,
+    AccruedFees, AccruedFeesResponse, CommissionAsset, ConfigResponse, CumulativePricesResponse,
+    Cw20HookMsg, ExecuteMsg, FeeAprResponse, FeeShareConfig, FlashLoanReceiverMsg, InstantiateMsg,
+    PairCreationInfo, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    SwapBatch, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams, MAX_FEE_SHARE_BPS,
+    TWAP_PRECISION,
 };
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport::tokenfactory_tracker::{
@@ -24,6 +29,7 @@ use astroport::tokenfactory_tracker::{
 use astroport_pair::error::ContractError;
 use astroport_test::cw_multi_test::{AppBuilder, ContractWrapper, Executor, TOKEN_FACTORY_MODULE};
 use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+use astroport_test::pair::check_first_depositor_donation_attack;
 
 const OWNER: &str = "owner";
 
@@ -109,6 +115,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: Some(String::from("generator")),
@@ -116,6 +123,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -214,6 +222,7 @@ fn test_provide_and_withdraw_liquidity() {
 
     // Init pair
     let pair_instance = instantiate_pair(&mut router, &owner);
+    let creation_block = router.block_info();
 
     let res: PairInfo = router
         .wrap()
@@ -369,6 +378,7 @@ fn test_provide_and_withdraw_liquidity() {
     let msg = ExecuteMsg::WithdrawLiquidity {
         assets: vec![],
         min_assets_to_receive: None,
+        deadline: None,
     };
     // Try to send withdraw liquidity with uluna token
     let err = router
@@ -411,6 +421,7 @@ fn test_provide_and_withdraw_liquidity() {
                         })
                         .collect(),
                 ),
+                deadline: None,
             },
             &[coin(100u128, lp_token.clone())],
         )
@@ -444,6 +455,7 @@ fn test_provide_and_withdraw_liquidity() {
             &ExecuteMsg::WithdrawLiquidity {
                 assets: vec![],
                 min_assets_to_receive: Some(min_assets_to_receive),
+                deadline: None,
             },
             &[coin(100u128, lp_token.clone())],
         )
@@ -478,12 +490,19 @@ fn test_provide_and_withdraw_liquidity() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    circuit_breaker: None,
+                    batch_swaps: false,
+                    batch_min_offer: vec![],
                 })
                 .unwrap()
             ),
             owner,
             factory_addr: config.factory_addr,
             tracker_addr: config.tracker_addr,
+            pair_type: Some(PairType::Xyk {}),
+            created_at: Some(PairCreationInfo::from(&creation_block)),
         }
     )
 }
@@ -514,6 +533,8 @@ fn provide_liquidity_msg(
         auto_stake: None,
         receiver,
         min_lp_to_receive,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -624,6 +645,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -631,6 +653,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -705,6 +728,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -737,6 +762,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -751,6 +778,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -1014,6 +1043,7 @@ fn asset_balances_tracking_works_correctly() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1024,6 +1054,7 @@ fn asset_balances_tracking_works_correctly() {
             code_id: store_tracker_contract(&mut app),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -1051,6 +1082,8 @@ fn asset_balances_tracking_works_correctly() {
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1138,6 +1171,8 @@ fn asset_balances_tracking_works_correctly() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let send_funds = vec![Coin {
         denom: "uusd".to_owned(),
@@ -1182,6 +1217,7 @@ fn asset_balances_tracking_works_correctly() {
         &ExecuteMsg::WithdrawLiquidity {
             assets: vec![],
             min_assets_to_receive: None,
+            deadline: None,
         },
         &[coin(500_000000u128, lp_token_address)],
     )
@@ -1252,6 +1288,7 @@ fn update_pair_config() {
             code_id: store_tracker_contract(&mut router),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1304,12 +1341,19 @@ fn update_pair_config() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    circuit_breaker: None,
+                    batch_swaps: false,
+                    batch_min_offer: vec![],
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Xyk {}),
+            created_at: res.created_at.clone()
         }
     );
 }
@@ -1345,6 +1389,7 @@ fn enable_disable_fee_sharing() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1397,12 +1442,19 @@ fn enable_disable_fee_sharing() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    circuit_breaker: None,
+                    batch_swaps: false,
+                    batch_min_offer: vec![],
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Xyk {}),
+            created_at: res.created_at.clone()
         }
     );
 
@@ -1469,13 +1521,21 @@ fn enable_disable_fee_sharing() {
                     fee_share: Some(FeeShareConfig {
                         bps: fee_share_bps,
                         recipient: Addr::unchecked(fee_share_contract),
+                        callback: false,
                     }),
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    circuit_breaker: None,
+                    batch_swaps: false,
+                    batch_min_offer: vec![],
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Xyk {}),
+            created_at: res.created_at.clone()
         }
     );
 
@@ -1500,12 +1560,19 @@ fn enable_disable_fee_sharing() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    circuit_breaker: None,
+                    batch_swaps: false,
+                    batch_min_offer: vec![],
                 })
                 .unwrap()
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Xyk {}),
+            created_at: res.created_at.clone()
         }
     );
 }
@@ -1571,6 +1638,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: None,
@@ -1581,6 +1649,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
             code_id: store_tracker_contract(&mut router),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1605,6 +1674,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
                 guardian: None,
                 incentivization_fee_info: None,
                 vesting_contract: "vesting".to_string(),
+                ownership_timelock: None,
             },
             &[],
             "generator",
@@ -1640,6 +1710,8 @@ fn provide_liquidity_with_autostaking_to_generator() {
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1671,6 +1743,8 @@ fn provide_liquidity_with_autostaking_to_generator() {
         auto_stake: Some(true),
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -1720,6 +1794,133 @@ fn provide_liquidity_with_autostaking_to_generator() {
         .unwrap();
 
     assert_eq!(amount, Uint128::new(99999000));
+
+    // Register a second staking contract as the default auto-stake target
+    let new_incentives_instance = router
+        .instantiate_contract(
+            generator_code_id,
+            owner.clone(),
+            &astroport::incentives::InstantiateMsg {
+                astro_token: native_asset_info("astro".to_string()),
+                factory: factory_instance.to_string(),
+                owner: owner.to_string(),
+                guardian: None,
+                incentivization_fee_info: None,
+                vesting_contract: "vesting".to_string(),
+                ownership_timelock: None,
+            },
+            &[],
+            "new_incentives",
+            None,
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &astroport::factory::ExecuteMsg::UpdateAutoStakeTarget {
+                auto_stake_target: Some(new_incentives_instance.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let provide_msg = |staking_target: Option<String>| ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            native_asset_info("uusd".to_string()).with_balance(10_000_000u128),
+            native_asset_info("uluna".to_string()).with_balance(10_000_000u128),
+        ],
+        slippage_tolerance: None,
+        auto_stake: Some(true),
+        receiver: None,
+        min_lp_to_receive: None,
+        staking_target,
+        deadline: None,
+    };
+    let coins = [coin(10_000_000, "uluna"), coin(10_000_000, "uusd")];
+    let query_deposit = |router: &TestApp, staking_contract: &Addr| -> Uint128 {
+        router
+            .wrap()
+            .query_wasm_smart(
+                staking_contract,
+                &astroport::incentives::QueryMsg::Deposit {
+                    lp_token: lp_token_address.to_string(),
+                    user: alice_address.to_string(),
+                },
+            )
+            .unwrap()
+    };
+
+    // LP tokens are staked in the factory default target
+    router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &provide_msg(None),
+            &coins,
+        )
+        .unwrap();
+    assert_eq!(
+        query_deposit(&router, &new_incentives_instance).u128(),
+        10_000_000
+    );
+    assert_eq!(query_deposit(&router, &generator_instance).u128(), 99999000);
+
+    // Legacy staking contract can be chosen explicitly
+    router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &provide_msg(Some(generator_instance.to_string())),
+            &coins,
+        )
+        .unwrap();
+    assert_eq!(
+        query_deposit(&router, &generator_instance).u128(),
+        109999000
+    );
+
+    // Unregistered staking contracts are rejected
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &provide_msg(Some("random".to_string())),
+            &coins,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: random is not a registered staking contract"
+    );
+
+    // Once the auto-stake target is removed, LP tokens are staked in the generator again
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &astroport::factory::ExecuteMsg::UpdateAutoStakeTarget {
+                auto_stake_target: None,
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &provide_msg(None),
+            &coins,
+        )
+        .unwrap();
+    assert_eq!(
+        query_deposit(&router, &generator_instance).u128(),
+        119999000
+    );
+    assert_eq!(
+        query_deposit(&router, &new_incentives_instance).u128(),
+        10_000_000
+    );
 }
 
 #[test]
@@ -1812,6 +2013,7 @@ fn test_imbalanced_withdraw_is_disabled() {
             amount: Uint128::from(100u8),
         }],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     let err = router
@@ -1966,6 +2168,7 @@ fn test_fee_share(
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1976,6 +2179,7 @@ fn test_fee_share(
             code_id: tracker_code_id,
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -2002,6 +2206,8 @@ fn test_fee_share(
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -2067,6 +2273,8 @@ fn test_fee_share(
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -2092,6 +2300,8 @@ fn test_fee_share(
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -2166,6 +2376,68 @@ fn test_fee_share(
         y_amount - y_expected_return - expected_maker_fee - expected_fee_share
             + acceptable_spread_amount
     );
+
+    // Fees are accounted in the ask asset only
+    let res: Vec<AccruedFeesResponse> = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::AccruedFees {})
+        .unwrap();
+    assert_eq!(res[0].fees, AccruedFees::default());
+    let fees = &res[1].fees;
+    assert_eq!(fees.maker, expected_maker_fee);
+    assert_eq!(
+        fees.fee_share,
+        expected_fee_share - acceptable_spread_amount
+    );
+    assert_eq!(fees.total, fees.maker + fees.fee_share + fees.lp);
+    assert!(!fees.lp.is_zero());
+
+    // LP fees accrued since the observation recorded before the swap are annualized
+    app.update_block(|b| b.time = b.time.plus_seconds(3600));
+    let res: FeeAprResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::FeeApr { window: 86400 })
+        .unwrap();
+    assert_eq!(res.period, 3600);
+    assert!(res.lp_fees[0].amount.is_zero());
+    assert_eq!(res.lp_fees[1].amount, fees.lp);
+    assert!(!res.apr.is_zero());
+
+    // There are no observations in the window
+    app.update_block(|b| b.time = b.time.plus_seconds(86400));
+    let res: FeeAprResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::FeeApr { window: 3600 })
+        .unwrap();
+    assert_eq!(res.period, 0);
+    assert!(res.apr.is_zero());
+}
+
+#[test]
+fn test_first_depositor_donation_attack() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            coin(100_000_000_000u128, "uusd"),
+            coin(100_000_000_000u128, "uluna"),
+        ],
+    );
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    let commission_asset: CommissionAsset = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::CommissionAsset {})
+        .unwrap();
+    assert_eq!(commission_asset, CommissionAsset::Ask);
+
+    check_first_depositor_donation_attack(
+        &mut router,
+        &pair_instance,
+        MINIMUM_LIQUIDITY_AMOUNT.u128() + 1,
+        ContractError::MinimumLiquidityAmountError {},
+        ContractError::LiquidityAmountTooSmall {},
+    );
 }
 
 #[test]
@@ -2298,6 +2570,7 @@ fn test_tracker_contract() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -2308,6 +2581,7 @@ fn test_tracker_contract() {
             code_id: store_tracker_contract(&mut app),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -2335,6 +2609,8 @@ fn test_tracker_contract() {
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -2451,6 +2727,7 @@ fn test_create_xyk_custom_type() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
             PairConfig {
                 code_id: pair_code_id,
@@ -2460,6 +2737,7 @@ fn test_create_xyk_custom_type() {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
         ],
         token_code_id,
@@ -2468,6 +2746,7 @@ fn test_create_xyk_custom_type() {
         whitelist_code_id: 0,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -2551,3 +2830,1455 @@ fn test_create_xyk_custom_type() {
 
     assert_eq!(res.pair_type, PairType::Xyk {});
 }
+
+/// Mock flash loan borrower. The callback `msg` selects the behaviour:
+/// 0 - keeps the loan, 1 - repays the loan with fee or pays for the flash swap,
+/// 2 - tries to swap in the pair, 3 - queries the pool.
+fn store_flash_borrower_code(app: &mut TestApp) -> u64 {
+    let borrower_contract = Box::new(ContractWrapper::new_with_empty(
+        |deps: DepsMut,
+         _: Env,
+         info: MessageInfo,
+         msg: FlashLoanReceiverMsg|
+         -> StdResult<Response> {
+            let (loan, repayment, msg) = match msg {
+                FlashLoanReceiverMsg::FlashLoanCallback { loan, fee, msg } => {
+                    let repayment = loan.info.with_balance(loan.amount + fee.amount);
+                    (loan, repayment, msg)
+                }
+                FlashLoanReceiverMsg::FlashSwapCallback { ask, offer, msg } => (ask, offer, msg),
+            };
+            match from_json::<u8>(&msg)? {
+                1 => Ok(Response::new().add_message(repayment.into_msg(info.sender)?)),
+                2 => Ok(Response::new().add_message(wasm_execute(
+                    info.sender,
+                    &ExecuteMsg::Swap {
+                        offer_asset: loan.clone(),
+                        ask_asset_info: None,
+                        belief_price: None,
+                        max_spread: None,
+                        to: None,
+                        deadline: None,
+                        to_commitment: None,
+                    },
+                    vec![coin(loan.amount.u128(), loan.info.to_string())],
+                )?)),
+                3 => {
+                    deps.querier
+                        .query_wasm_smart::<PoolResponse>(&info.sender, &QueryMsg::Pool {})?;
+                    Ok(Response::new())
+                }
+                _ => Ok(Response::new()),
+            }
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Default::default())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { unimplemented!() },
+    ));
+
+    app.store_code(borrower_contract)
+}
+
+#[test]
+fn test_flash_loan() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            coin(100_000_000_000u128, "uusd"),
+            coin(100_000_000_000u128, "uluna"),
+        ],
+    );
+
+    let pair = instantiate_pair(&mut router, &owner);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    let factory = config.factory_addr;
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair.clone(), &msg, &coins)
+        .unwrap();
+
+    let borrower_code_id = store_flash_borrower_code(&mut router);
+    let borrower = router
+        .instantiate_contract(
+            borrower_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "BORROWER",
+            None,
+        )
+        .unwrap();
+    // The borrower pays the fee from its own balance
+    router
+        .send_tokens(owner.clone(), borrower.clone(), &[coin(10_000, "uusd")])
+        .unwrap();
+
+    let flash_loan_msg = |mode: u8| ExecuteMsg::FlashLoan {
+        asset: native_asset_info("uusd".to_string()).with_balance(1_000_000u128),
+        msg: to_json_binary(&mode).unwrap(),
+    };
+
+    // The pair hasn't opted in
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FlashLoansDisabled {}
+    );
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::EnableFlashLoans { fee_bps: 1001 })
+                    .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FlashLoanFeeOutOfBounds {}
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::EnableFlashLoans { fee_bps: 30 })
+                    .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert_eq!(params.flash_loan_fee_bps, Some(30));
+
+    // Flash loans are still disabled in the factory
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FlashLoansDisabled {}
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            factory.clone(),
+            &FactoryExecuteMsg::UpdateFlashLoans { enabled: true },
+            &[],
+        )
+        .unwrap();
+
+    // The loan is not repaid
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(0), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::FlashLoanNotRepaid {
+            expected: Uint128::new(100_003_000),
+            actual: Uint128::new(99_000_000),
+        }
+        .to_string()
+    );
+
+    // The pair is locked until the loan is finalized
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(2), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::FlashLoanInProgress {}.to_string()
+    );
+
+    // Drained reserves can't be observed from the callback
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(3), &[])
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains(&ContractError::FlashLoanInProgress {}.to_string()));
+
+    router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap();
+
+    // The fee stays in the pool
+    let pool_balance = router.wrap().query_balance(&pair, "uusd").unwrap().amount;
+    assert_eq!(pool_balance.u128(), 100_003_000);
+    let borrower_balance = router
+        .wrap()
+        .query_balance(&borrower, "uusd")
+        .unwrap()
+        .amount;
+    assert_eq!(borrower_balance.u128(), 7_000);
+
+    // The fee is rounded up thus small loans are not free
+    router
+        .execute_contract(
+            borrower.clone(),
+            pair.clone(),
+            &ExecuteMsg::FlashLoan {
+                asset: native_asset_info("uusd".to_string()).with_balance(100u128),
+                msg: to_json_binary(&1u8).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let pool_balance = router.wrap().query_balance(&pair, "uusd").unwrap().amount;
+    assert_eq!(pool_balance.u128(), 100_003_001);
+
+    // Only the pair itself can finalize a flash loan
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::FinalizeFlashLoan {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // Paused pairs don't lend. The pair isn't registered in the factory thus all pairs are paused
+    let pause_all_pairs = |paused: bool| FactoryExecuteMsg::UpdatePauseState {
+        pair_creation: None,
+        all_pairs: Some(paused),
+        pause: None,
+        unpause: None,
+    };
+    router
+        .execute_contract(owner.clone(), factory.clone(), &pause_all_pairs(true), &[])
+        .unwrap();
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairPaused {}
+    );
+    router
+        .execute_contract(owner.clone(), factory.clone(), &pause_all_pairs(false), &[])
+        .unwrap();
+    router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap();
+
+    // Neither do halted pairs
+    let guardian = Addr::unchecked("guardian");
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::SetCircuitBreaker {
+                    guardian: guardian.to_string(),
+                    max_ratio_change: Decimal::percent(10),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            guardian.clone(),
+            pair.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairHalted {}
+    );
+    router
+        .execute_contract(guardian, pair.clone(), &ExecuteMsg::ResumePair {}, &[])
+        .unwrap();
+    router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap();
+
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::DisableFlashLoans).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_loan_msg(1), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::FlashLoansDisabled {}
+    );
+}
+
+#[test]
+fn test_flash_swap() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            coin(100_000_000_000u128, "uusd"),
+            coin(100_000_000_000u128, "uluna"),
+        ],
+    );
+
+    let pair = instantiate_pair(&mut router, &owner);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Config {})
+        .unwrap();
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000_000),
+        Uint128::new(100_000_000),
+        None,
+        None,
+        None,
+    );
+    router
+        .execute_contract(owner.clone(), pair.clone(), &msg, &coins)
+        .unwrap();
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::EnableFlashLoans { fee_bps: 30 })
+                    .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            owner.clone(),
+            config.factory_addr,
+            &FactoryExecuteMsg::UpdateFlashLoans { enabled: true },
+            &[],
+        )
+        .unwrap();
+
+    let borrower_code_id = store_flash_borrower_code(&mut router);
+    let borrower = router
+        .instantiate_contract(
+            borrower_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "BORROWER",
+            None,
+        )
+        .unwrap();
+    router
+        .send_tokens(owner.clone(), borrower.clone(), &[coin(2_000_000, "uusd")])
+        .unwrap();
+
+    let ask_asset = native_asset_info("uluna".to_string()).with_balance(1_000_000u128);
+    let sim: ReverseSimulationResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &pair,
+            &QueryMsg::ReverseSimulation {
+                offer_asset_info: None,
+                ask_asset: ask_asset.clone(),
+            },
+        )
+        .unwrap();
+    let fee = (sim.offer_amount.u128() * 30 + 9999) / 10000;
+
+    let flash_swap_msg = |mode: u8| ExecuteMsg::FlashSwap {
+        ask_asset: ask_asset.clone(),
+        offer_asset_info: native_asset_info("uusd".to_string()),
+        msg: to_json_binary(&mode).unwrap(),
+    };
+
+    // The ask asset is not paid for
+    let err = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_swap_msg(0), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::FlashLoanNotRepaid {
+            expected: Uint128::new(100_000_000 + sim.offer_amount.u128() + fee),
+            actual: Uint128::new(100_000_000),
+        }
+        .to_string()
+    );
+
+    let err = router
+        .execute_contract(
+            borrower.clone(),
+            pair.clone(),
+            &ExecuteMsg::FlashSwap {
+                ask_asset: ask_asset.clone(),
+                offer_asset_info: native_asset_info("uluna".to_string()),
+                msg: to_json_binary(&1u8).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssetMismatch {}
+    );
+
+    let res = router
+        .execute_contract(borrower.clone(), pair.clone(), &flash_swap_msg(1), &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "flash_swap")
+            .add_attribute("fee_amount", fee.to_string())
+    ));
+
+    // The borrower paid the regular swap price plus the flash loan fee
+    let balance = |router: &TestApp, addr: &Addr, denom: &str| {
+        router
+            .wrap()
+            .query_balance(addr, denom)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(balance(&router, &borrower, "uluna"), 1_000_000);
+    assert_eq!(
+        balance(&router, &borrower, "uusd"),
+        2_000_000 - sim.offer_amount.u128() - fee
+    );
+    assert_eq!(
+        balance(&router, &pair, "uusd"),
+        100_000_000 + sim.offer_amount.u128() + fee
+    );
+    let pool: PoolResponse = router
+        .wrap()
+        .query_wasm_smart(&pair, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.assets[1].amount.u128(), 100_000_000 - 1_000_000);
+}
+
+#[test]
+fn test_balance_caps() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let token_code_id = store_token_code(&mut app);
+    let pair_code_id = store_pair_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+
+    let init_msg = FactoryInstantiateMsg {
+        fee_address: None,
+        pair_configs: vec![PairConfig {
+            code_id: pair_code_id,
+            maker_fee_bps: 0,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 0,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+            min_trade_notional: None,
+        }],
+        token_code_id,
+        generator_address: None,
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+        tracker_config: None,
+        ownership_timelock: None,
+    };
+
+    let factory_instance = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let asset_infos = vec![
+        native_asset_info("uluna".to_string()),
+        native_asset_info("uusd".to_string()),
+    ];
+    let create_pair_msg = |caps: Vec<Asset>| FactoryExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pair_type: PairType::Xyk {},
+        init_params: Some(
+            to_json_binary(&XYKPoolParams {
+                track_asset_balances: None,
+                lp_token_metadata: None,
+                balance_caps: Some(caps),
+            })
+            .unwrap(),
+        ),
+    };
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &create_pair_msg(vec![
+                native_asset_info("uatom".to_string()).with_balance(1u8)
+            ]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Asset uatom is not in the pair"
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        factory_instance.clone(),
+        &create_pair_msg(vec![
+            native_asset_info("uusd".to_string()).with_balance(1000_000000u128)
+        ]),
+        &[],
+    )
+    .unwrap();
+
+    let pair_instance = app
+        .wrap()
+        .query_wasm_smart::<PairInfo>(
+            &factory_instance,
+            &FactoryQueryMsg::Pair {
+                asset_infos: asset_infos.clone(),
+            },
+        )
+        .unwrap()
+        .contract_addr;
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(600_000000),
+        Uint128::new(600_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    // The uusd balance would become 1200_000000
+    let err = app
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Pool balance of uusd would exceed its cap of 1000000000"
+    );
+
+    // The cap can be filled up exactly
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(400_000000),
+        Uint128::new(400_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    let update_caps_msg = |caps: Vec<Asset>| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::UpdateBalanceCaps { caps }).unwrap(),
+    };
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair_instance.clone(),
+            &update_caps_msg(vec![]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // Raise the uusd cap and cap uluna
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &update_caps_msg(vec![
+            native_asset_info("uusd".to_string()).with_balance(2000_000000u128),
+            native_asset_info("uluna".to_string()).with_balance(1500_000000u128),
+        ]),
+        &[],
+    )
+    .unwrap();
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert_eq!(
+        params.balance_caps,
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(2000_000000u128),
+            native_asset_info("uluna".to_string()).with_balance(1500_000000u128),
+        ]
+    );
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(600_000000),
+        Uint128::new(600_000000),
+        None,
+        None,
+        None,
+    );
+    let err = app
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Pool balance of uluna would exceed its cap of 1500000000"
+    );
+
+    // Remove all caps
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &update_caps_msg(vec![
+            native_asset_info("uusd".to_string()).with_balance(0u8),
+            native_asset_info("uluna".to_string()).with_balance(0u8),
+        ]),
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert_eq!(params.balance_caps, vec![]);
+}
+
+#[test]
+fn test_circuit_breaker() {
+    let owner = Addr::unchecked(OWNER);
+    let guardian = Addr::unchecked("guardian");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    let set_circuit_breaker_msg = |max_ratio_change: &str| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::SetCircuitBreaker {
+            guardian: guardian.to_string(),
+            max_ratio_change: max_ratio_change.parse().unwrap(),
+        })
+        .unwrap(),
+    };
+
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            pair_instance.clone(),
+            &set_circuit_breaker_msg("0.1"),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &set_circuit_breaker_msg("0"),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidMaxRatioChange {}
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &set_circuit_breaker_msg("0.1"),
+        &[],
+    )
+    .unwrap();
+
+    let swap = |app: &mut TestApp, amount: u128| {
+        app.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(amount, "uusd")],
+        )
+    };
+    let query_circuit_breaker = |app: &TestApp| {
+        let config: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+            .unwrap();
+        from_json::<XYKPoolConfig>(config.params.unwrap())
+            .unwrap()
+            .circuit_breaker
+            .unwrap()
+    };
+
+    // ~2% ratio change is below the limit
+    swap(&mut app, 10_000000).unwrap();
+    assert_eq!(query_circuit_breaker(&app).tripped_at, None);
+
+    // ~17% ratio change is rejected and doesn't halt the pair
+    let err = swap(&mut app, 100_000000).unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReserveRatioChangeTooLarge { max, .. } if max == Decimal::percent(10)
+    ));
+    assert_eq!(query_circuit_breaker(&app).tripped_at, None);
+    swap(&mut app, 1_000000).unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair_instance.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    app.execute_contract(
+        guardian.clone(),
+        pair_instance.clone(),
+        &ExecuteMsg::HaltPair {},
+        &[],
+    )
+    .unwrap();
+    let tripped_at = app.block_info().time.seconds();
+    assert_eq!(query_circuit_breaker(&app).tripped_at, Some(tripped_at));
+
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairHalted {}
+    );
+
+    let err = swap(&mut app, 1_000000).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairHalted {}
+    );
+    let err = app
+        .execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairHalted {}
+    );
+
+    // Withdrawals are still allowed
+    let lp_denom = format!("factory/{pair_instance}/{LP_SUBDENOM}");
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &ExecuteMsg::WithdrawLiquidity {
+            assets: vec![],
+            min_assets_to_receive: None,
+            deadline: None,
+        },
+        &[coin(1_000000, &lp_denom)],
+    )
+    .unwrap();
+
+    // Updating the settings doesn't resume the pair
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &set_circuit_breaker_msg("0.2"),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_circuit_breaker(&app).tripped_at, Some(tripped_at));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair_instance.clone(),
+            &ExecuteMsg::ResumePair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    app.execute_contract(
+        guardian.clone(),
+        pair_instance.clone(),
+        &ExecuteMsg::ResumePair {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_circuit_breaker(&app).tripped_at, None);
+
+    let err = app
+        .execute_contract(
+            guardian.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ResumePair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairNotHalted {}
+    );
+
+    swap(&mut app, 1_000000).unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        pair_instance.clone(),
+        &ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::RemoveCircuitBreaker).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert_eq!(params.circuit_breaker, None);
+}
+
+#[test]
+fn test_batch_swaps() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+    app.send_tokens(owner.clone(), alice.clone(), &[coin(200_000000, "uusd")])
+        .unwrap();
+    app.send_tokens(
+        owner.clone(),
+        bob.clone(),
+        &[coin(100_000000, "uusd"), coin(100_000000, "uluna")],
+    )
+    .unwrap();
+
+    let enable_msg = ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::EnableBatchSwaps).unwrap(),
+    };
+    let err = app
+        .execute_contract(alice.clone(), pair_instance.clone(), &enable_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &enable_msg, &[])
+        .unwrap();
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert!(params.batch_swaps);
+
+    let swap = |app: &mut TestApp,
+                sender: &Addr,
+                denom: &str,
+                amount: u128,
+                belief_price: Option<Decimal>,
+                max_spread: Decimal| {
+        app.execute_contract(
+            sender.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: native_asset_info(denom.to_string()).with_balance(amount),
+                ask_asset_info: None,
+                belief_price,
+                max_spread: Some(max_spread),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(amount, denom)],
+        )
+    };
+    let balance = |app: &TestApp, addr: &Addr, denom: &str| {
+        app.wrap().query_balance(addr, denom).unwrap().amount.u128()
+    };
+    let pending_batch = |app: &TestApp| -> Option<SwapBatch> {
+        app.wrap()
+            .query_wasm_smart(&pair_instance, &QueryMsg::PendingBatch {})
+            .unwrap()
+    };
+    let settle = |app: &mut TestApp| {
+        app.execute_contract(
+            Addr::unchecked("keeper"),
+            pair_instance.clone(),
+            &ExecuteMsg::SettleBatch {},
+            &[],
+        )
+    };
+
+    // Opposite swaps of the same value are matched against each other at the spot price
+    swap(
+        &mut app,
+        &alice,
+        "uusd",
+        100_000000,
+        None,
+        Decimal::percent(1),
+    )
+    .unwrap();
+    swap(
+        &mut app,
+        &bob,
+        "uluna",
+        100_000000,
+        None,
+        Decimal::percent(1),
+    )
+    .unwrap();
+    assert_eq!(balance(&app, &alice, "uluna"), 0);
+    let batch = pending_batch(&app).unwrap();
+    assert_eq!(batch.height, app.block_info().height);
+    assert_eq!(batch.swaps.len(), 2);
+
+    // Queued offers are not part of the pool
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.assets[0].amount.u128(), 1000_000000);
+    assert_eq!(pool.assets[1].amount.u128(), 1000_000000);
+
+    // The batch can't be settled before the block is over
+    let err = settle(&mut app).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToSettle {}
+    );
+
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    let res = settle(&mut app).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute("action", "settle_batch")));
+    assert_eq!(pending_batch(&app), None);
+    assert_eq!(balance(&app, &alice, "uluna"), 100_000000);
+    assert_eq!(balance(&app, &bob, "uusd"), 200_000000);
+
+    // Alice's spread check fails at the clearing price thus her swap is refunded.
+    // Bob's swap alone settles exactly as an instant swap would.
+    swap(
+        &mut app,
+        &alice,
+        "uusd",
+        100_000000,
+        Some(Decimal::one()),
+        Decimal::percent(1),
+    )
+    .unwrap();
+    swap(
+        &mut app,
+        &bob,
+        "uusd",
+        100_000000,
+        None,
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert_eq!(balance(&app, &alice, "uusd"), 0);
+
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    // The first swap in the next block settles the previous batch
+    let res = swap(
+        &mut app,
+        &owner,
+        "uusd",
+        10_000000,
+        None,
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute("refunded_swaps", "1")));
+    assert!(res.has_event(
+        &Event::new("wasm-batch_swap")
+            .add_attribute("receiver", bob.as_str())
+            .add_attribute("return_amount", "90909090")
+    ));
+    assert_eq!(balance(&app, &alice, "uusd"), 100_000000);
+    assert_eq!(balance(&app, &bob, "uluna"), 90_909090);
+    assert_eq!(pending_batch(&app).unwrap().swaps.len(), 1);
+
+    // Disabling the batch mode settles the pending batch right away
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::DisableBatchSwaps).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute("action", "settle_batch")));
+    assert_eq!(pending_batch(&app), None);
+
+    let luna_before = balance(&app, &alice, "uluna");
+    swap(
+        &mut app,
+        &alice,
+        "uusd",
+        10_000000,
+        None,
+        Decimal::percent(50),
+    )
+    .unwrap();
+    assert!(balance(&app, &alice, "uluna") > luna_before);
+    let err = settle(&mut app).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToSettle {}
+    );
+}
+
+#[test]
+fn test_batch_refunded_by_circuit_breaker() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+    app.send_tokens(owner.clone(), alice.clone(), &[coin(200_000000, "uusd")])
+        .unwrap();
+
+    for params in [
+        XYKPoolUpdateParams::EnableBatchSwaps,
+        XYKPoolUpdateParams::SetCircuitBreaker {
+            guardian: "guardian".to_string(),
+            max_ratio_change: Decimal::percent(10),
+        },
+    ] {
+        app.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&params).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        alice.clone(),
+        pair_instance.clone(),
+        &ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(200_000000u128),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+            deadline: None,
+            to_commitment: None,
+        },
+        &[coin(200_000000, "uusd")],
+    )
+    .unwrap();
+
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+
+    // The batch would shift the reserve ratio by ~44% thus it is refunded instead of reverting
+    let res = app
+        .execute_contract(
+            Addr::unchecked("keeper"),
+            pair_instance.clone(),
+            &ExecuteMsg::SettleBatch {},
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute("refunded_swaps", "1")));
+    assert_eq!(
+        app.wrap()
+            .query_balance(&alice, "uusd")
+            .unwrap()
+            .amount
+            .u128(),
+        200_000000
+    );
+
+    let pool: PoolResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Pool {})
+        .unwrap();
+    assert_eq!(pool.assets[0].amount.u128(), 1000_000000);
+    assert_eq!(pool.assets[1].amount.u128(), 1000_000000);
+}
+
+#[test]
+fn test_batch_swap_limits() {
+    let owner = Addr::unchecked(OWNER);
+    let alice = Addr::unchecked("alice");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+    app.send_tokens(owner.clone(), alice.clone(), &[coin(100_000000, "uusd")])
+        .unwrap();
+
+    for params in [
+        XYKPoolUpdateParams::EnableBatchSwaps,
+        XYKPoolUpdateParams::UpdateBatchMinOffer {
+            min_offer: vec![native_asset_info("uusd".to_string()).with_balance(1_000000u128)],
+        },
+    ] {
+        app.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&params).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Config {})
+        .unwrap();
+    let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert_eq!(
+        params.batch_min_offer,
+        vec![native_asset_info("uusd".to_string()).with_balance(1_000000u128)]
+    );
+
+    let swap = |app: &mut TestApp, amount: u128| {
+        app.execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(amount, "uusd")],
+        )
+    };
+
+    // Dust swaps can't fill the batch
+    let err = swap(&mut app, 999999).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BatchOfferTooSmall {
+            min_offer: "1000000uusd".to_string()
+        }
+    );
+
+    // A single sender can't take the whole batch
+    for _ in 0..5 {
+        swap(&mut app, 1_000000).unwrap();
+    }
+    let err = swap(&mut app, 1_000000).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TooManyBatchedSwaps {}
+    );
+
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    app.execute_contract(
+        Addr::unchecked("keeper"),
+        pair_instance.clone(),
+        &ExecuteMsg::SettleBatch {},
+        &[],
+    )
+    .unwrap();
+    assert!(
+        app.wrap()
+            .query_balance(&alice, "uluna")
+            .unwrap()
+            .amount
+            .u128()
+            > 0
+    );
+
+    // All payouts reached the receiver thus nothing is left to claim
+    let unclaimed: Vec<Asset> = app
+        .wrap()
+        .query_wasm_smart(
+            &pair_instance,
+            &QueryMsg::UnclaimedBatchPayouts {
+                address: alice.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(unclaimed.is_empty());
+    let err = app
+        .execute_contract(
+            alice.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::ClaimBatchPayouts {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoUnclaimedPayouts {}
+    );
+}
+
+#[test]
+fn test_swap_observations() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    let swap = |app: &mut TestApp, denom: &str, amount: u128| {
+        let offer_asset = native_asset_info(denom.to_string()).with_balance(amount);
+        let sim: SimulationResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &pair_instance,
+                &QueryMsg::Simulation {
+                    offer_asset: offer_asset.clone(),
+                    ask_asset_info: None,
+                },
+            )
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset,
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(amount, denom)],
+        )
+        .unwrap();
+        sim.return_amount
+    };
+    let volume_sma = |app: &TestApp, window: u64| -> VolumeSmaResponse {
+        app.wrap()
+            .query_wasm_smart(&pair_instance, &QueryMsg::VolumeSma { window })
+            .unwrap()
+    };
+
+    // Both swaps are accumulated within the same block
+    let luna_received = swap(&mut app, "uusd", 10_000000);
+    let usd_received = swap(&mut app, "uluna", 5_000000);
+
+    // Observations of the current block are not committed yet
+    let res = volume_sma(&app, 3600);
+    assert_eq!(res.observations, 0);
+    assert_eq!(res.oldest_ts, None);
+    let swap_ts = app.block_info().time.seconds();
+
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(5);
+    });
+    swap(&mut app, "uusd", 1_000000);
+
+    let base_volume = Uint128::new(10_000000) + usd_received;
+    let quote_volume = luna_received + Uint128::new(5_000000);
+    let res = volume_sma(&app, 3600);
+    assert_eq!(
+        res,
+        VolumeSmaResponse {
+            base_volume,
+            quote_volume,
+            base_volume_sma: base_volume,
+            quote_volume_sma: quote_volume,
+            observations: 1,
+            oldest_ts: Some(swap_ts),
+        }
+    );
+
+    let res: OracleObservation = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Observe { seconds_ago: 0 })
+        .unwrap();
+    assert_eq!(res.price, Decimal::from_ratio(base_volume, quote_volume));
+
+    let res: ObservationsInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::ObservationsInfo {})
+        .unwrap();
+    assert_eq!(res.observations, 1);
+    assert_eq!(res.newest_ts, Some(swap_ts));
+
+    // The next block commits the previous one
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(5);
+    });
+    swap(&mut app, "uluna", 1_000000);
+
+    let res = volume_sma(&app, 3600);
+    assert_eq!(res.observations, 2);
+    assert_eq!(res.oldest_ts, Some(swap_ts));
+    assert_eq!(res.base_volume_sma, res.base_volume / Uint128::new(2));
+
+    // Observations outside of the window are not counted
+    let res = volume_sma(&app, 5);
+    assert_eq!(res.observations, 1);
+    assert_eq!(res.oldest_ts, Some(swap_ts + 5));
+
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(7200);
+    });
+    let res = volume_sma(&app, 3600);
+    assert_eq!(res.observations, 0);
+    assert_eq!(res.base_volume, Uint128::zero());
+}
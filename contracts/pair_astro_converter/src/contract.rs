@@ -121,6 +121,7 @@ pub fn swap(
                 amount: offer_asset.amount,
                 msg: to_json_binary(&astro_converter::Cw20HookMsg {
                     receiver: Some(receiver.to_string()),
+                    ibc_forward: None,
                 })?,
             },
             vec![],
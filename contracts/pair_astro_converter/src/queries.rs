@@ -5,6 +5,7 @@ use cosmwasm_std::{
 use astroport::asset::{Asset, AssetInfoExt};
 use astroport::pair::{
     ConfigResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    COMMISSION_ASSET,
 };
 use astroport::querier::query_factory_config;
 
@@ -54,6 +55,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
                 commission_amount: Uint128::zero(),
             })?)
         }
+        QueryMsg::CommissionAsset {} => Ok(to_json_binary(&COMMISSION_ASSET)?),
         _ => Err(ContractError::NotSupported {}),
     }
 }
@@ -191,12 +191,14 @@ impl Helper {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             }],
             token_code_id,
             generator_address: None,
             owner: owner.to_string(),
             whitelist_code_id: 0,
             coin_registry_address: "registry".to_string(),
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
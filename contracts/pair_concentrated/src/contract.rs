@@ -4,8 +4,8 @@ use std::vec;
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, coin, ensure, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, Binary, Coin,
-    CosmosMsg, Decimal, Decimal256, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdError,
-    StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    CosmosMsg, Decimal, Decimal256, DepsMut, Empty, Env, MessageInfo, Order, Reply, Response,
+    StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -20,35 +20,47 @@ use astroport::asset::{
 };
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner, LP_SUBDENOM};
 use astroport::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use astroport::observation::{PrecommitObservation, OBSERVATIONS_SIZE};
+use astroport::events::{ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent};
+use astroport::observation::{store_fee_observation, PrecommitObservation, OBSERVATIONS_SIZE};
 use astroport::pair::{
-    Cw20HookMsg, ExecuteMsg, FeeShareConfig, InstantiateMsg, ReplyIds, MAX_FEE_SHARE_BPS,
-    MIN_TRADE_SIZE,
+    check_balance_caps, check_deadline, check_recipient_commitment, load_lp_fees,
+    update_balance_caps, Cw20HookMsg, ExecuteMsg, FeeShareCallbackMsg, FeeShareConfig,
+    InstantiateMsg, PairCreationInfo, ReplyIds, MAX_FEE_SHARE_BPS, MIN_TRADE_SIZE,
 };
+#[cfg(feature = "sudo")]
+use astroport::pair_concentrated::SudoMsg;
 use astroport::pair_concentrated::{
     ConcentratedPoolParams, ConcentratedPoolUpdateParams, UpdatePoolParams,
 };
 use astroport::querier::{
-    query_factory_config, query_fee_info, query_native_supply, query_tracker_config,
+    query_factory_config, query_fee_info, query_min_trade_notional, query_native_supply,
+    query_pair_paused, query_tracker_config,
 };
 use astroport::token_factory::{
     tf_before_send_hook_msg, tf_burn_msg, tf_create_denom_msg, MsgCreateDenomResponse,
 };
 use astroport::tokenfactory_tracker;
 use astroport_circular_buffer::BufferManager;
+use astroport_pair_common::liquidity::{
+    check_asset_infos, mint_liquidity_token_message, resolve_receiver,
+};
 use astroport_pcl_common::state::{
     AmpGamma, Config, PoolParams, PoolState, Precisions, PriceState,
 };
 use astroport_pcl_common::utils::{
-    accumulate_prices, assert_max_spread, before_swap_check, calc_last_prices, check_asset_infos,
-    check_cw20_in_pool, compute_swap, get_share_in_assets, mint_liquidity_token_message,
+    accumulate_prices, assert_max_spread, before_swap_check, calc_last_prices, check_cw20_in_pool,
+    compute_swap, get_share_in_assets,
 };
 use astroport_pcl_common::{calc_d, get_xcp};
 
 use crate::error::ContractError;
-use crate::state::{BALANCES, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL};
+use crate::state::{
+    ACCRUED_FEES, BALANCES, BALANCE_CAPS, CONFIG, CREATION_INFO, FEE_OBSERVATIONS,
+    LP_TOKEN_METADATA, OBSERVATIONS, OWNERSHIP_PROPOSAL,
+};
 use crate::utils::{
-    accumulate_swap_sizes, calculate_shares, get_assets_with_precision, query_pools,
+    accumulate_swap_sizes, calculate_shares, get_assets_with_precision, poke_price_scale,
+    query_pools,
 };
 
 /// Contract name that is used for migration.
@@ -109,6 +121,7 @@ pub fn instantiate(
         repeg_profit_threshold: Some(params.repeg_profit_threshold),
         min_price_scale_delta: Some(params.min_price_scale_delta),
         ma_half_time: Some(params.ma_half_time),
+        max_price_scale_change_per_hour: params.max_price_scale_change_per_hour,
     })?;
 
     let pool_state = PoolState {
@@ -123,9 +136,14 @@ pub fn instantiate(
             last_price_update: env.block.time.seconds(),
             xcp_profit: Decimal256::zero(),
             xcp_profit_real: Decimal256::zero(),
+            last_repeg: 0,
+            last_repeg_delta: Decimal256::zero(),
         },
     };
 
+    let min_trade_notional =
+        query_min_trade_notional(&deps.querier, &factory_addr, &msg.pair_type)?;
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
@@ -142,6 +160,7 @@ pub fn instantiate(
         track_asset_balances: params.track_asset_balances.unwrap_or_default(),
         fee_share: None,
         tracker_addr: None,
+        min_trade_notional,
     };
 
     if config.track_asset_balances {
@@ -151,9 +170,20 @@ pub fn instantiate(
     }
 
     CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
 
     BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
 
+    if let Some(lp_token_metadata) = params.lp_token_metadata {
+        lp_token_metadata.validate()?;
+        LP_TOKEN_METADATA.save(deps.storage, &lp_token_metadata)?;
+    }
+
+    if let Some(caps) = params.balance_caps {
+        let caps = update_balance_caps(vec![], caps, &msg.asset_infos)?;
+        BALANCE_CAPS.save(deps.storage, &caps)?;
+    }
+
     // Create LP token
     let sub_msg = SubMsg::reply_on_success(
         tf_create_denom_msg(env.contract.address.to_string(), LP_SUBDENOM),
@@ -201,6 +231,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                                     .to_string(),
                                 tracked_denom: new_token_denom.clone(),
                                 track_over_seconds: false,
+                                leaderboard_min_balance: None,
                             })?,
                             funds: vec![],
                             label: format!("{new_token_denom} tracking contract"),
@@ -211,6 +242,14 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                     sub_msgs.extend(sub_msg);
                 }
 
+                if let Some(lp_token_metadata) = LP_TOKEN_METADATA.may_load(deps.storage)? {
+                    LP_TOKEN_METADATA.remove(deps.storage);
+                    sub_msgs.push(SubMsg::new(lp_token_metadata.into_denom_metadata_msg(
+                        env.contract.address.clone(),
+                        &new_token_denom,
+                    )));
+                }
+
                 CONFIG.update(deps.storage, |mut config| {
                     if !config.pair_info.liquidity_token.is_empty() {
                         return Err(StdError::generic_err(
@@ -249,6 +288,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                 .add_message(set_hook_msg)
                 .add_attribute("tracker_contract", contract_address))
         }
+        ReplyIds::BatchPayout => Err(ContractError::FailedToParseReply {}),
     }
 }
 
@@ -265,6 +305,9 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             min_lp_to_receive,
+///             staking_target,
+///             deadline,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
@@ -272,7 +315,10 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             belief_price,
 ///             max_spread,
 ///             to,
+///             deadline,
 ///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::Poke {}** Updates the oracle price and repegs the pool using current pool volumes.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -290,23 +336,35 @@ pub fn execute(
             auto_stake,
             receiver,
             min_lp_to_receive,
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-            min_lp_to_receive,
-        ),
+            staking_target,
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+                staking_target,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -331,7 +389,14 @@ pub fn execute(
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
-            let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+            let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+
+            // The factory propagates its owner to pairs which keep their own owner
+            let current_owner = match config.owner {
+                Some(_) if info.sender == config.factory_addr => config.factory_addr,
+                Some(owner) => owner,
+                None => factory_config.owner,
+            };
 
             propose_new_owner(
                 deps,
@@ -339,7 +404,7 @@ pub fn execute(
                 env,
                 owner,
                 expires_in,
-                config.owner.unwrap_or(factory_config.owner),
+                current_owner,
                 OWNERSHIP_PROPOSAL,
             )
             .map_err(Into::into)
@@ -366,7 +431,43 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
-        ExecuteMsg::WithdrawLiquidity { assets, .. } => withdraw_liquidity(deps, env, info, assets),
+        ExecuteMsg::WithdrawLiquidity {
+            assets, deadline, ..
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets)
+        }
+        ExecuteMsg::FlashLoan { .. }
+        | ExecuteMsg::FlashSwap { .. }
+        | ExecuteMsg::FinalizeFlashLoan {} => {
+            Err(StdError::generic_err("Flash loans are not supported").into())
+        }
+        ExecuteMsg::Poke {} => poke(deps, env),
+        ExecuteMsg::HaltPair {} | ExecuteMsg::ResumePair {} => {
+            Err(StdError::generic_err("Circuit breaker is not supported").into())
+        }
+        ExecuteMsg::SettleBatch {} | ExecuteMsg::ClaimBatchPayouts {} => {
+            Err(StdError::generic_err("Batch swaps are not supported").into())
+        }
+        ExecuteMsg::ClaimLentRefunds {} => {
+            Err(StdError::generic_err("Lending is not supported").into())
+        }
+    }
+}
+
+/// Exposes sudo messages available to chain modules.
+///
+/// * **SudoMsg::Poke {}** Updates the oracle price and repegs the pool if the price scale is stale.
+/// Returns an empty response if there is nothing to poke.
+#[cfg(feature = "sudo")]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Poke {} => match poke(deps, env) {
+            Err(ContractError::NothingToPoke {}) => Ok(Response::new()),
+            result => result,
+        },
     }
 }
 
@@ -384,8 +485,13 @@ fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             let config = CONFIG.load(deps.storage)?;
 
             // Only asset contract can execute this message
@@ -418,6 +524,9 @@ fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+/// It must be registered in the factory; the factory default is used if not set.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
 #[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
@@ -429,9 +538,15 @@ pub fn provide_liquidity(
     auto_stake: Option<bool>,
     receiver: Option<String>,
     min_lp_to_receive: Option<Uint128>,
+    staking_target: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?
         .to_decimal256(LP_TOKEN_PRECISION)?;
 
@@ -478,6 +593,22 @@ pub fn provide_liquidity(
         }
     }
 
+    let new_balances = pools
+        .iter()
+        .zip(&deposits)
+        .map(|(pool, deposit)| {
+            let amount = pool
+                .amount
+                .checked_add(*deposit)?
+                .to_uint(precisions.get_precision(&pool.info)?)?;
+            Ok(pool.info.with_balance(amount))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    check_balance_caps(
+        &BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+        &new_balances,
+    )?;
+
     let (share_uint128, slippage) = calculate_shares(
         &env,
         &mut config,
@@ -490,11 +621,13 @@ pub fn provide_liquidity(
     if total_share.is_zero() {
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.factory_addr,
+            &config.pair_info.liquidity_token,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
             false,
+            None,
         )?);
     }
 
@@ -505,15 +638,17 @@ pub fn provide_liquidity(
     );
 
     // Mint LP tokens for the sender or for the receiver (if set)
-    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let receiver = resolve_receiver(deps.api, &receiver, &info.sender)?;
     let auto_stake = auto_stake.unwrap_or(false);
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.factory_addr,
+        &config.pair_info.liquidity_token,
         &env.contract.address,
         &receiver,
         share_uint128,
         auto_stake,
+        staking_target.as_deref(),
     )?);
 
     if config.track_asset_balances {
@@ -534,16 +669,18 @@ pub fn provide_liquidity(
 
     CONFIG.save(deps.storage, &config)?;
 
-    let attrs = vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", format!("{}, {}", &assets[0], &assets[1])),
-        attr("share", share_uint128),
-        attr("slippage", slippage.to_string()),
-    ];
+    let event = ProvideLiquidityEvent {
+        pool: config.pair_info.contract_addr,
+        sender: info.sender,
+        receiver,
+        assets,
+        share: share_uint128,
+    };
 
-    Ok(Response::new().add_messages(messages).add_attributes(attrs))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(event.into_attributes())
+        .add_attribute("slippage", slippage.to_string()))
 }
 
 /// Withdraw liquidity from the pool.
@@ -637,12 +774,15 @@ fn withdraw_liquidity(
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("sender", info.sender),
-        attr("withdrawn_share", amount),
-        attr("refund_assets", refund_assets.iter().join(", ")),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        WithdrawLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            withdrawn_share: amount,
+            refund_assets,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Performs an swap operation with the specified parameters. The trader must approve the
@@ -671,6 +811,11 @@ fn swap(
     let offer_asset_dec = offer_asset.to_decimal_asset(offer_asset_prec)?;
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let mut pools = query_pools(deps.querier, &env.contract.address, &config, &precisions)?;
 
     let (offer_ind, _) = pools
@@ -680,6 +825,19 @@ fn swap(
     let ask_ind = 1 ^ offer_ind;
     let ask_asset_prec = precisions.get_precision(&pools[ask_ind].info)?;
 
+    if let Some(min) = config.min_trade_notional {
+        // Offer amount denominated in the first pool asset
+        let notional = if offer_ind == 0 {
+            offer_asset_dec.amount
+        } else {
+            offer_asset_dec.amount * config.pool_state.price_state.price_scale
+        };
+        ensure!(
+            notional >= Decimal256::from(min),
+            ContractError::TradeTooSmall { min, notional }
+        );
+    }
+
     pools[offer_ind].amount -= offer_asset_dec.amount;
 
     before_swap_check(&pools, offer_asset_dec.amount)?;
@@ -756,7 +914,17 @@ fn swap(
         fee_share_amount = swap_result.share_fee.to_uint(ask_asset_prec)?;
         if !fee_share_amount.is_zero() {
             let fee = pools[ask_ind].info.with_balance(fee_share_amount);
-            messages.push(fee.into_msg(fee_share.recipient)?);
+            messages.push(fee.clone().into_msg(&fee_share.recipient)?);
+            if fee_share.callback {
+                messages.push(
+                    wasm_execute(
+                        &fee_share.recipient,
+                        &FeeShareCallbackMsg::FeeShareReceived { asset: fee },
+                        vec![],
+                    )?
+                    .into(),
+                );
+            }
         }
     }
 
@@ -770,6 +938,19 @@ fn swap(
         }
     }
 
+    let commission_amount = swap_result.total_fee.to_uint(ask_asset_prec)?;
+    store_fee_observation(
+        deps.storage,
+        &env,
+        FEE_OBSERVATIONS,
+        load_lp_fees(deps.storage, ACCRUED_FEES, &config.pair_info.asset_infos)?,
+    )?;
+    let mut accrued_fees = ACCRUED_FEES
+        .may_load(deps.storage, &pools[ask_ind].info)?
+        .unwrap_or_default();
+    accrued_fees.add(commission_amount, maker_fee, fee_share_amount);
+    ACCRUED_FEES.save(deps.storage, &pools[ask_ind].info, &accrued_fees)?;
+
     accumulate_prices(&env, &mut config, old_real_price);
 
     // Store observation from precommit data
@@ -807,21 +988,52 @@ fn swap(
         )?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "swap"),
-        attr("sender", sender),
-        attr("receiver", receiver),
-        attr("offer_asset", offer_asset_dec.info.to_string()),
-        attr("ask_asset", pools[ask_ind].info.to_string()),
-        attr("offer_amount", offer_asset.amount),
-        attr("return_amount", return_amount),
-        attr("spread_amount", spread_amount),
-        attr(
-            "commission_amount",
-            swap_result.total_fee.to_uint(ask_asset_prec)?,
-        ),
-        attr("maker_fee_amount", maker_fee),
-        attr("fee_share_amount", fee_share_amount),
+    Ok(Response::new().add_messages(messages).add_attributes(
+        SwapEvent {
+            pool: config.pair_info.contract_addr,
+            sender,
+            receiver,
+            offer_asset,
+            return_asset: pools[ask_ind].info.with_balance(return_amount),
+            spread_amount,
+            commission_amount,
+            maker_fee_amount: maker_fee,
+            fee_share_amount,
+        }
+        .into_attributes(),
+    ))
+}
+
+/// Runs the price scale update for a pool which has been idle for a while.
+/// Anyone can call this endpoint. Fails if the price scale stays the same.
+fn poke(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let precisions = Precisions::new(deps.storage)?;
+
+    let xs = query_pools(deps.querier, &env.contract.address, &config, &precisions)?
+        .into_iter()
+        .map(|asset| asset.amount)
+        .collect_vec();
+
+    let mut new_config = config.clone();
+    let old_price_scale = poke_price_scale(deps.querier, &env, &mut new_config, &xs)?;
+    let new_price_scale = new_config.pool_state.price_state.price_scale;
+
+    ensure!(
+        new_price_scale != old_price_scale,
+        ContractError::NothingToPoke {}
+    );
+
+    // Cumulative prices must account for the period before the price scale was changed
+    let old_real_price = calc_last_prices(&xs, &config, &env)?;
+    accumulate_prices(&env, &mut new_config, old_real_price);
+
+    CONFIG.save(deps.storage, &new_config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "poke"),
+        attr("old_price_scale", old_price_scale.to_string()),
+        attr("new_price_scale", new_price_scale.to_string()),
     ]))
 }
 
@@ -829,7 +1041,7 @@ fn swap(
 ///
 /// * **params** new parameter values in [`Binary`] form.
 fn update_config(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     params: Binary,
@@ -863,6 +1075,7 @@ fn update_config(
         ConcentratedPoolUpdateParams::EnableFeeShare {
             fee_share_bps,
             fee_share_address,
+            callback,
         } => {
             // Enable fee sharing for this contract
             // If fee sharing is already enabled, we should be able to overwrite
@@ -877,12 +1090,14 @@ fn update_config(
             config.fee_share = Some(FeeShareConfig {
                 bps: fee_share_bps,
                 recipient: deps.api.addr_validate(&fee_share_address)?,
+                callback,
             });
 
             response.attributes.extend(vec![
                 attr("action", "enable_fee_share"),
                 attr("fee_share_bps", fee_share_bps.to_string()),
                 attr("fee_share_address", fee_share_address),
+                attr("fee_share_callback", callback.to_string()),
             ]);
         }
         ConcentratedPoolUpdateParams::DisableFeeShare => {
@@ -893,6 +1108,44 @@ fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        ConcentratedPoolUpdateParams::UpdateBalanceCaps { caps } => {
+            let caps = update_balance_caps(
+                BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+                caps,
+                &config.pair_info.asset_infos,
+            )?;
+            BALANCE_CAPS.save(deps.storage, &caps)?;
+
+            response.attributes.extend([
+                attr("action", "update_balance_caps"),
+                attr("balance_caps", caps.iter().join(", ")),
+            ]);
+        }
+        ConcentratedPoolUpdateParams::RefreshPrecisions {} => {
+            let load_precisions = |storage: &dyn Storage| {
+                Precisions::PRECISIONS
+                    .range(storage, None, None, Order::Ascending)
+                    .collect::<StdResult<Vec<_>>>()
+            };
+            let cached_precisions = load_precisions(deps.storage)?;
+            Precisions::store_precisions(
+                deps.branch(),
+                &config.pair_info.asset_infos,
+                &config.factory_addr,
+            )?;
+
+            // The price scale and D are computed with the cached precisions
+            // thus they can't change after the first provision
+            let total_share =
+                query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+            ensure!(
+                load_precisions(deps.storage)? == cached_precisions || total_share.is_zero(),
+                ContractError::PrecisionChangeWithLiquidity {}
+            );
+            response
+                .attributes
+                .push(attr("action", "refresh_precisions"));
+        }
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -905,7 +1158,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
 
     match contract_version.contract.as_ref() {
         "astroport-pair-concentrated" => match contract_version.version.as_ref() {
-            "4.0.0" | "4.0.1" => {}
+            "4.0.0" | "4.0.1" | "4.1.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -1,10 +1,13 @@
-use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError, Uint128};
+use cosmwasm_std::{
+    ConversionOverflowError, Decimal, Decimal256, OverflowError, StdError, Uint128,
+};
 use thiserror::Error;
 
 use cw_utils::{ParseReplyError, PaymentError};
 
 use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
 use astroport_circular_buffer::error::BufferError;
+use astroport_pair_common::error::PairCommonError;
 use astroport_pcl_common::error::PclError;
 
 /// This enum describes pair contract errors
@@ -46,6 +49,9 @@ pub enum ContractError {
     #[error("Initial liquidity must be more than {}", MINIMUM_LIQUIDITY_AMOUNT)]
     MinimumLiquidityAmountError {},
 
+    #[error("Insufficient amount of liquidity")]
+    LiquidityAmountTooSmall {},
+
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
 
@@ -69,4 +75,27 @@ pub enum ContractError {
 
     #[error("Slippage is more than expected: received {0}, expected {1} LP tokens")]
     ProvideSlippageViolation(Uint128, Uint128),
+
+    #[error("Poke doesn't change the price scale")]
+    NothingToPoke {},
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
+
+    #[error("Swap notional {notional} is below the minimum trade notional {min}")]
+    TradeTooSmall { min: Decimal, notional: Decimal256 },
+
+    #[error("Asset precisions can't be changed while the pool has liquidity")]
+    PrecisionChangeWithLiquidity {},
+}
+
+impl From<PairCommonError> for ContractError {
+    fn from(err: PairCommonError) -> Self {
+        match err {
+            PairCommonError::Std(err) => ContractError::Std(err),
+            PairCommonError::DoublingAssets {} => PclError::DoublingAssets {}.into(),
+            PairCommonError::AutoStakeError {} => PclError::AutoStakeError {}.into(),
+            err => ContractError::Std(StdError::generic_err(err.to_string())),
+        }
+    }
 }
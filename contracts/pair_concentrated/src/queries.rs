@@ -6,14 +6,22 @@ use cosmwasm_std::{
 use itertools::Itertools;
 
 use astroport::asset::{Asset, AssetInfo};
-use astroport::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use astroport::observation::query_observation;
+use astroport::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
+use astroport::observation::{
+    query_fee_apr, query_observation, query_observations_info, query_safe_price,
+};
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    load_lp_fees, AccruedFeesResponse, ConfigResponse, CumulativePricesResponse,
+    MigrationStatusResponse, MinimumLiquidityResponse, PoolResponse, ReverseSimulationResponse,
+    SimulationResponse, COMMISSION_ASSET,
+};
+use astroport::pair_concentrated::{
+    ConcentratedPoolConfig, LastRepegResponse, PokeSimulationResponse, QueryMsg,
+};
+use astroport::querier::{
+    migration_check, query_factory_config, query_fee_info, query_native_supply,
 };
-use astroport::pair_concentrated::{ConcentratedPoolConfig, QueryMsg};
-use astroport::querier::{query_factory_config, query_fee_info, query_native_supply};
+use astroport_pair_common::liquidity::pool_info;
 use astroport_pcl_common::state::Precisions;
 use astroport_pcl_common::utils::{
     accumulate_prices, before_swap_check, calc_last_prices, compute_offer_amount, compute_swap,
@@ -23,8 +31,10 @@ use astroport_pcl_common::{calc_d, get_xcp};
 
 use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
-use crate::state::{BALANCES, CONFIG, OBSERVATIONS};
-use crate::utils::{calculate_shares, get_assets_with_precision, pool_info, query_pools};
+use crate::state::{
+    ACCRUED_FEES, BALANCES, BALANCE_CAPS, CONFIG, CREATION_INFO, FEE_OBSERVATIONS, OBSERVATIONS,
+};
+use crate::utils::{calculate_shares, get_assets_with_precision, poke_price_scale, query_pools};
 
 /// Exposes all the queries available in the contract.
 ///
@@ -49,6 +59,20 @@ use crate::utils::{calculate_shares, get_assets_with_precision, pool_info, query
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified
 /// asset that was in the pool just preceding the moment of the specified block height creation.
+///
+/// * **QueryMsg::MinimumLiquidity {}** Returns the amount of LP tokens locked in the pair on the first provision.
+///
+/// * **QueryMsg::CommissionAsset {}** Returns the asset in which the pair charges its commission.
+///
+/// * **QueryMsg::MigrationStatus {}** Returns whether the factory has scheduled a code migration of the pair.
+///
+/// * **QueryMsg::SimulatePoke {}** Returns whether a poke would change the price scale and by how much.
+///
+/// * **QueryMsg::AccruedFees {}** Returns swap fees collected in each pool asset since inception.
+///
+/// * **QueryMsg::FeeApr { window }** Returns the LP fee APR estimated over the last `window` seconds.
+///
+/// * **QueryMsg::LastRepeg {}** Returns the last price scale change and the repeg rate limit.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -72,6 +96,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Observe { seconds_ago } => {
             to_json_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
         }
+        QueryMsg::SafePrice { window } => {
+            to_json_binary(&query_safe_price(deps, env, OBSERVATIONS, window)?)
+        }
+        QueryMsg::ObservationsInfo {} => {
+            to_json_binary(&query_observations_info(deps, env, OBSERVATIONS)?)
+        }
         QueryMsg::Config {} => to_json_binary(&query_config(deps, env)?),
         QueryMsg::LpPrice {} => to_json_binary(&query_lp_price(deps, env)?),
         QueryMsg::ComputeD {} => to_json_binary(&query_compute_d(deps, env)?),
@@ -91,14 +121,78 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SimulateWithdraw { lp_amount } => to_json_binary(
             &query_share(deps, lp_amount).map_err(|err| StdError::generic_err(err.to_string()))?,
         ),
+        QueryMsg::MinimumLiquidity {} => {
+            to_json_binary(&MinimumLiquidityResponse::new(env.contract.address))
+        }
+        QueryMsg::CommissionAsset {} => to_json_binary(&COMMISSION_ASSET),
+        QueryMsg::MigrationStatus {} => to_json_binary(&query_migration_status(deps, env)?),
+        QueryMsg::SimulatePoke {} => to_json_binary(&query_simulate_poke(deps, env)?),
+        QueryMsg::AccruedFees {} => to_json_binary(&query_accrued_fees(deps)?),
+        QueryMsg::LastRepeg {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let price_state = config.pool_state.price_state;
+
+            to_json_binary(&LastRepegResponse {
+                timestamp: price_state.last_repeg,
+                price_scale: price_state.price_scale,
+                delta: price_state.last_repeg_delta,
+                max_price_scale_change_per_hour: config.pool_params.max_price_scale_change_per_hour,
+            })
+        }
+        QueryMsg::FeeApr { window } => {
+            let config = CONFIG.load(deps.storage)?;
+            let lp_fees = load_lp_fees(deps.storage, ACCRUED_FEES, &config.pair_info.asset_infos)?;
+            let reserves = config
+                .pair_info
+                .query_pools(&deps.querier, &env.contract.address)?;
+
+            to_json_binary(&query_fee_apr(
+                deps,
+                env,
+                FEE_OBSERVATIONS,
+                lp_fees,
+                reserves,
+                window,
+            )?)
+        }
     }
 }
 
+/// Returns swap fees collected in each pool asset. Assets which were never swapped to have zero fees.
+fn query_accrued_fees(deps: Deps) -> StdResult<Vec<AccruedFeesResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    config
+        .pair_info
+        .asset_infos
+        .into_iter()
+        .map(|asset_info| {
+            let fees = ACCRUED_FEES
+                .may_load(deps.storage, &asset_info)?
+                .unwrap_or_default();
+            Ok(AccruedFeesResponse { asset_info, fees })
+        })
+        .collect()
+}
+
+/// Returns whether the factory has scheduled a code migration of the pair.
+fn query_migration_status(deps: Deps, env: Env) -> StdResult<MigrationStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(MigrationStatusResponse {
+        migration_scheduled: migration_check(
+            &deps.querier,
+            &config.factory_addr,
+            &env.contract.address,
+        )?,
+    })
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     let resp = PoolResponse {
         assets,
@@ -224,8 +318,8 @@ pub fn query_reverse_simulation(
 
     Ok(ReverseSimulationResponse {
         offer_amount: offer_amount.to_uint(offer_asset_prec)?,
-        spread_amount: spread_amount.to_uint(offer_asset_prec)?,
-        commission_amount: commission_amount.to_uint(offer_asset_prec)?,
+        spread_amount: spread_amount.to_uint(ask_asset_prec)?,
+        commission_amount: commission_amount.to_uint(ask_asset_prec)?,
     })
 }
 
@@ -243,7 +337,7 @@ fn query_cumulative_prices(
 
     accumulate_prices(&env, &mut config, last_real_price);
 
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     Ok(CumulativePricesResponse {
         assets,
@@ -302,10 +396,37 @@ pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
             ma_half_time: config.pool_params.ma_half_time,
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            balance_caps: BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+            max_price_scale_change_per_hour: config.pool_params.max_price_scale_change_per_hour,
         })?),
         owner: config.owner.unwrap_or(factory_config.owner),
         factory_addr: config.factory_addr,
         tracker_addr: config.tracker_addr,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
+    })
+}
+
+/// Simulates [`astroport::pair::ExecuteMsg::Poke`] in the current block.
+pub fn query_simulate_poke(deps: Deps, env: Env) -> StdResult<PokeSimulationResponse> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let precisions = Precisions::new(deps.storage)?;
+
+    let xs = query_pools(deps.querier, &env.contract.address, &config, &precisions)
+        .map_err(|e| StdError::generic_err(e.to_string()))?
+        .into_iter()
+        .map(|a| a.amount)
+        .collect_vec();
+
+    let price_scale = poke_price_scale(deps.querier, &env, &mut config, &xs)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let new_price_scale = config.pool_state.price_state.price_scale;
+
+    Ok(PokeSimulationResponse {
+        would_change: new_price_scale != price_scale,
+        price_scale,
+        new_price_scale,
+        change: new_price_scale.diff(price_scale),
     })
 }
 
@@ -1,15 +1,25 @@
 use cosmwasm_std::Uint128;
-use cw_storage_plus::{Item, SnapshotMap};
+use cw_storage_plus::{Item, Map, SnapshotMap};
 
-use astroport::asset::AssetInfo;
+use astroport::asset::{Asset, AssetInfo};
 use astroport::common::OwnershipProposal;
-use astroport::observation::Observation;
+use astroport::observation::{FeeObservation, Observation};
+use astroport::pair::{AccruedFees, LpTokenMetadata, PairCreationInfo};
 use astroport_circular_buffer::CircularBuffer;
 use astroport_pcl_common::state::Config;
 
 /// Stores pool parameters and state.
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
+
+/// Temporarily stores custom LP token metadata until the LP denom is created
+pub const LP_TOKEN_METADATA: Item<LpTokenMetadata> = Item::new("lp_token_metadata");
+
+/// Caps on pool balances. Liquidity provision is not capped if not set
+pub const BALANCE_CAPS: Item<Vec<Asset>> = Item::new("balance_caps");
+
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
@@ -17,6 +27,13 @@ pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_pro
 pub const OBSERVATIONS: CircularBuffer<Observation> =
     CircularBuffer::new("observations_state", "observations_buffer");
 
+/// Cumulative swap fees collected in each pool asset. key: ask asset info
+pub const ACCRUED_FEES: Map<&AssetInfo, AccruedFees> = Map::new("accrued_fees");
+
+/// Circular buffer to store cumulative LP fee snapshots used to estimate the fee APR
+pub const FEE_OBSERVATIONS: CircularBuffer<FeeObservation> =
+    CircularBuffer::new("fee_observations_state", "fee_observations_buffer");
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
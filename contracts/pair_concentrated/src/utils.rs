@@ -1,10 +1,11 @@
 use astroport::cosmwasm_ext::{AbsDiff, DecimalToInteger, IntegerToDecimal};
+use astroport_pair_common::liquidity::check_assets;
 use astroport_pcl_common::utils::{
-    assert_slippage_tolerance, calc_provide_fee, check_assets, check_pair_registered,
+    assert_slippage_tolerance, calc_provide_fee, check_pair_registered,
 };
 use astroport_pcl_common::{calc_d, get_xcp};
 use cosmwasm_std::{
-    Addr, Decimal, Decimal256, Deps, Env, QuerierWrapper, StdError, StdResult, Storage, Uint128,
+    Addr, Decimal, Decimal256, Deps, Env, QuerierWrapper, StdError, Storage, Uint128,
 };
 
 use astroport::asset::{Asset, Decimal256Ext, DecimalAsset, MINIMUM_LIQUIDITY_AMOUNT};
@@ -21,18 +22,33 @@ use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
 use crate::state::OBSERVATIONS;
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub(crate) fn pool_info(
+/// Runs the repegging mechanism against the current pool volumes without any trade.
+/// The last traded price is reused thus only the oracle price and the price scale are affected.
+/// Returns the price scale before the update.
+///
+/// * **xs** - pool volumes where amounts are in [`Decimal256`] form.
+pub(crate) fn poke_price_scale(
     querier: QuerierWrapper,
-    config: &Config,
-) -> StdResult<(Vec<Asset>, Uint128)> {
-    let pools = config
-        .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
+    env: &Env,
+    config: &mut Config,
+    xs: &[Decimal256],
+) -> Result<Decimal256, ContractError> {
+    if xs.iter().any(|x| x.is_zero()) {
+        return Err(StdError::generic_err("Pools are empty").into());
+    }
 
-    let total_share = query_native_supply(&querier, config.pair_info.liquidity_token.to_string())?;
+    let total_share = query_native_supply(&querier, &config.pair_info.liquidity_token)?
+        .to_decimal256(LP_TOKEN_PRECISION)?;
+    let price_scale = config.pool_state.price_state.price_scale;
+    let last_price = config.pool_state.price_state.last_price;
 
-    Ok((pools, total_share))
+    // update_price() works only with internal representation
+    let ixs = [xs[0], xs[1] * price_scale];
+    config
+        .pool_state
+        .update_price(&config.pool_params, env, total_share, &ixs, last_price)?;
+
+    Ok(price_scale)
 }
 
 /// Returns current pool's volumes where amount is in [`Decimal256`] form.
@@ -217,6 +233,12 @@ pub(crate) fn calculate_shares(
         share * (Decimal256::one() - calc_provide_fee(&ideposits, &new_xp, &config.pool_params))
     };
 
+    // Donations to the pool must not round a provision down to zero LP tokens
+    let share_uint128 = share.to_uint(LP_TOKEN_PRECISION)?;
+    if share_uint128.is_zero() {
+        return Err(ContractError::LiquidityAmountTooSmall {});
+    }
+
     // calculate accrued share
     let share_ratio = share / (total_share + share);
     let balanced_share = [
@@ -249,7 +271,7 @@ pub(crate) fn calculate_shares(
         )?;
     }
 
-    Ok((share.to_uint(LP_TOKEN_PRECISION)?, slippage))
+    Ok((share_uint128, slippage))
 }
 
 #[cfg(test)]
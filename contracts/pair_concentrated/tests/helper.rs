@@ -22,7 +22,8 @@ use astroport::pair::{
     ReverseSimulationResponse, SimulationResponse,
 };
 use astroport::pair_concentrated::{
-    ConcentratedPoolConfig, ConcentratedPoolParams, ConcentratedPoolUpdateParams, QueryMsg,
+    ConcentratedPoolConfig, ConcentratedPoolParams, ConcentratedPoolUpdateParams,
+    LastRepegResponse, PokeSimulationResponse, QueryMsg,
 };
 use astroport_pair_concentrated::contract::{execute, instantiate, reply};
 use astroport_pair_concentrated::queries::query;
@@ -50,6 +51,9 @@ pub fn common_pcl_params() -> ConcentratedPoolParams {
         ma_half_time: 600,
         track_asset_balances: None,
         fee_share: None,
+        lp_token_metadata: None,
+        balance_caps: None,
+        max_price_scale_change_per_hour: None,
     }
 }
 
@@ -85,7 +89,12 @@ fn token_contract() -> Box<dyn Contract<Empty>> {
 }
 
 fn pair_contract() -> Box<dyn Contract<Empty>> {
-    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_reply_empty(reply))
+    let contract =
+        ContractWrapper::new_with_empty(execute, instantiate, query).with_reply_empty(reply);
+    #[cfg(feature = "sudo")]
+    let contract = contract.with_sudo_empty(astroport_pair_concentrated::contract::sudo);
+
+    Box::new(contract)
 }
 
 fn coin_registry_contract() -> Box<dyn Contract<Empty>> {
@@ -224,6 +233,7 @@ impl Helper {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             }],
             token_code_id,
             generator_address: None,
@@ -234,6 +244,7 @@ impl Helper {
                 code_id: tracker_code_id,
                 token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
             }),
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
@@ -258,6 +269,7 @@ impl Helper {
                     guardian: None,
                     incentivization_fee_info: None,
                     vesting_contract: "vesting".to_string(),
+                    ownership_timelock: None,
                 },
                 &[],
                 "generator",
@@ -332,6 +344,8 @@ impl Helper {
             auto_stake: Some(true),
             receiver: None,
             min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -353,6 +367,8 @@ impl Helper {
             auto_stake: None,
             receiver: None,
             min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -377,6 +393,8 @@ impl Helper {
             auto_stake,
             receiver,
             min_lp_to_receive,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -395,6 +413,7 @@ impl Helper {
             &ExecuteMsg::WithdrawLiquidity {
                 assets,
                 min_assets_to_receive: None,
+                deadline: None,
             },
             &[coin(amount, self.lp_token.to_string())],
         )
@@ -426,6 +445,8 @@ impl Helper {
                         belief_price,
                         max_spread,
                         to: None,
+                        deadline: None,
+                        to_commitment: None,
                     })
                     .unwrap(),
                 };
@@ -447,6 +468,8 @@ impl Helper {
                     belief_price,
                     max_spread,
                     to: None,
+                    deadline: None,
+                    to_commitment: None,
                 };
 
                 self.app
@@ -588,6 +611,35 @@ impl Helper {
             .query_wasm_smart(&self.pair_addr, &QueryMsg::Pool {})
     }
 
+    pub fn poke(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::Poke {},
+            &[],
+        )
+    }
+
+    #[cfg(feature = "sudo")]
+    pub fn sudo_poke(&mut self) -> AnyResult<AppResponse> {
+        self.app.wasm_sudo(
+            self.pair_addr.clone(),
+            &astroport::pair_concentrated::SudoMsg::Poke {},
+        )
+    }
+
+    pub fn simulate_poke(&self) -> StdResult<PokeSimulationResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::SimulatePoke {})
+    }
+
+    pub fn query_last_repeg(&self) -> StdResult<LastRepegResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::LastRepeg {})
+    }
+
     pub fn query_lp_price(&self) -> StdResult<Decimal256> {
         self.app
             .wrap()
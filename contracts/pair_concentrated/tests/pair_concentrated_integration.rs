@@ -2,7 +2,10 @@
 
 use std::str::FromStr;
 
-use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, StdError, Uint128};
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Response, StdError, StdResult, Uint128,
+};
 use itertools::{max, Itertools};
 
 use astroport::asset::{
@@ -10,7 +13,10 @@ use astroport::asset::{
 };
 use astroport::cosmwasm_ext::{AbsDiff, IntegerToDecimal};
 use astroport::observation::OracleObservation;
-use astroport::pair::{ExecuteMsg, PoolResponse, MAX_FEE_SHARE_BPS};
+use astroport::pair::{
+    AccruedFees, AccruedFeesResponse, CommissionAsset, ExecuteMsg, FeeShareCallbackMsg,
+    PoolResponse, MAX_FEE_SHARE_BPS,
+};
 use astroport::pair_concentrated::{
     ConcentratedPoolParams, ConcentratedPoolUpdateParams, PromoteParams, QueryMsg, UpdatePoolParams,
 };
@@ -18,12 +24,16 @@ use astroport::tokenfactory_tracker::{
     ConfigResponse as TrackerConfigResponse, QueryMsg as TrackerQueryMsg,
 };
 use astroport_pair_concentrated::error::ContractError;
-use astroport_pcl_common::consts::{AMP_MAX, AMP_MIN, MA_HALF_TIME_LIMITS};
+use astroport_pcl_common::consts::{
+    AMP_MAX, AMP_MIN, MA_HALF_TIME_LIMITS, PRICE_SCALE_CHANGE_PER_HOUR_MAX,
+    PRICE_SCALE_CHANGE_PER_HOUR_MIN,
+};
 use astroport_pcl_common::error::PclError;
 
 use astroport_test::coins::TestCoin;
 use astroport_test::convert::{dec_to_f64, f64_to_dec};
-use astroport_test::cw_multi_test::{Executor, TOKEN_FACTORY_MODULE};
+use astroport_test::cw_multi_test::{ContractWrapper, Executor, TOKEN_FACTORY_MODULE};
+use astroport_test::pair::check_first_depositor_donation_attack;
 
 use crate::helper::{common_pcl_params, AppExtension, Helper};
 
@@ -389,6 +399,21 @@ fn provide_and_withdraw() {
     assert_eq!(26653_440612, helper.coin_balance(&test_coins[1], &user2));
 }
 
+#[test]
+fn first_depositor_donation_attack() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+    let mut helper = Helper::new(&owner, test_coins, common_pcl_params()).unwrap();
+
+    check_first_depositor_donation_attack(
+        &mut helper.app,
+        &helper.pair_addr,
+        MINIMUM_LIQUIDITY_AMOUNT.u128() + 1,
+        ContractError::MinimumLiquidityAmountError {},
+        ContractError::LiquidityAmountTooSmall {},
+    );
+}
+
 #[test]
 fn check_imbalanced_provide() {
     let owner = Addr::unchecked("owner");
@@ -524,8 +549,15 @@ fn swap_different_precisions() {
         )
         .unwrap();
     assert_eq!(reverse_sim_resp.offer_amount.u128(), 10019003);
-    assert_eq!(reverse_sim_resp.commission_amount.u128(), 45084);
-    assert_eq!(reverse_sim_resp.spread_amount.u128(), 125);
+    // Commission and spread are denominated in the ask asset (BAR has 6 decimals)
+    let commission_asset: CommissionAsset = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.pair_addr, &QueryMsg::CommissionAsset {})
+        .unwrap();
+    assert_eq!(commission_asset, CommissionAsset::Ask);
+    assert_eq!(reverse_sim_resp.commission_amount.u128(), 450849);
+    assert_eq!(reverse_sim_resp.spread_amount.u128(), 1256);
 
     helper.give_me_money(&[offer_asset.clone()], &user);
     helper.swap(&user, &offer_asset, None).unwrap();
@@ -679,6 +711,8 @@ fn check_swaps_simple() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
+                to_commitment: None,
             },
             &[],
         )
@@ -851,6 +885,7 @@ fn check_amp_gamma_change() {
         repeg_profit_threshold: None,
         min_price_scale_delta: None,
         ma_half_time: None,
+        max_price_scale_change_per_hour: None,
     });
 
     let err = helper.update_config(&random_user, &action).unwrap_err();
@@ -1098,6 +1133,109 @@ fn update_owner() {
     assert_eq!(config.owner.unwrap().to_string(), new_owner)
 }
 
+#[test]
+fn propagate_factory_owner() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uusd"), TestCoin::cw20("USDX")];
+
+    let mut helper = Helper::new(&owner, test_coins, common_pcl_params()).unwrap();
+
+    // The pair keeps its own owner
+    let pair_admin = Addr::unchecked("pair_admin");
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ProposeNewOwner {
+                owner: pair_admin.to_string(),
+                expires_in: 100,
+            },
+            &[],
+        )
+        .unwrap();
+    helper
+        .app
+        .execute_contract(
+            pair_admin.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ClaimOwnership {},
+            &[],
+        )
+        .unwrap();
+
+    // Factory ownership changes
+    let new_owner = Addr::unchecked("new_owner");
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &astroport::factory::ExecuteMsg::ProposeNewOwner {
+                owner: new_owner.to_string(),
+                expires_in: 100,
+            },
+            &[],
+        )
+        .unwrap();
+    helper
+        .app
+        .execute_contract(
+            new_owner.clone(),
+            helper.factory.clone(),
+            &astroport::factory::ExecuteMsg::ClaimOwnership {},
+            &[],
+        )
+        .unwrap();
+
+    // Only the factory contract itself is allowed to propose on behalf of the pair owner
+    let err = helper
+        .app
+        .execute_contract(
+            new_owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ProposeNewOwner {
+                owner: new_owner.to_string(),
+                expires_in: 100,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Generic error: Unauthorized");
+
+    let res = helper
+        .app
+        .execute_contract(
+            new_owner.clone(),
+            helper.factory.clone(),
+            &astroport::factory::ExecuteMsg::PropagateOwnership {
+                expires_in: 100,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "propose_new_owner")
+            .add_attribute("new_owner", new_owner.as_str())
+    ));
+
+    helper
+        .app
+        .execute_contract(
+            new_owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ClaimOwnership {},
+            &[],
+        )
+        .unwrap();
+
+    let config = helper.query_config().unwrap();
+    assert_eq!(config.owner, Some(new_owner));
+}
+
 #[test]
 fn query_d_test() {
     let owner = Addr::unchecked("owner");
@@ -1542,6 +1680,7 @@ fn check_correct_fee_share() {
     let action = ConcentratedPoolUpdateParams::EnableFeeShare {
         fee_share_bps: MAX_FEE_SHARE_BPS + 1,
         fee_share_address: share_recipient.to_string(),
+        callback: false,
     };
     let err = helper.update_config(&owner, &action).unwrap_err();
     assert_eq!(
@@ -1553,6 +1692,7 @@ fn check_correct_fee_share() {
     let action = ConcentratedPoolUpdateParams::EnableFeeShare {
         fee_share_bps: 0,
         fee_share_address: share_recipient.to_string(),
+        callback: false,
     };
     let err = helper.update_config(&owner, &action).unwrap_err();
     assert_eq!(
@@ -1566,6 +1706,7 @@ fn check_correct_fee_share() {
     let action = ConcentratedPoolUpdateParams::EnableFeeShare {
         fee_share_bps: 1000,
         fee_share_address: share_recipient.to_string(),
+        callback: false,
     };
     helper.update_config(&owner, &action).unwrap();
 
@@ -1605,6 +1746,20 @@ fn check_correct_fee_share() {
     let recipient_balance = helper.coin_balance(&test_coins[1], &share_recipient);
     assert_eq!(recipient_balance, expected_fee_share);
 
+    let accrued_fees: Vec<AccruedFeesResponse> = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.pair_addr, &QueryMsg::AccruedFees {})
+        .unwrap();
+    assert_eq!(accrued_fees[0].fees, AccruedFees::default());
+    let fees = &accrued_fees[1].fees;
+    assert_eq!(fees.fee_share.u128(), expected_fee_share);
+    assert_eq!(
+        fees.maker.u128(),
+        helper.coin_balance(&test_coins[1], &helper.fake_maker)
+    );
+    assert_eq!(fees.total, fees.maker + fees.fee_share + fees.lp);
+
     let provider = Addr::unchecked("provider");
     let assets = vec![
         helper.assets[&test_coins[0]].with_balance(1_000_000000u128),
@@ -1655,6 +1810,82 @@ fn check_correct_fee_share() {
     assert!(config.fee_share.is_none());
 }
 
+#[test]
+fn check_fee_share_callback() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusdc")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    // Mock recipient which records every callback in its response attributes
+    let recipient_code_id = helper
+        .app
+        .store_code(Box::new(ContractWrapper::new_with_empty(
+            |_: DepsMut,
+             _: Env,
+             info: MessageInfo,
+             msg: FeeShareCallbackMsg|
+             -> StdResult<Response> {
+                match msg {
+                    FeeShareCallbackMsg::FeeShareReceived { asset } => Ok(Response::new()
+                        .add_attribute("fee_share_sender", info.sender)
+                        .add_attribute("fee_share_asset", asset.to_string())),
+                }
+            },
+            |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+                Ok(Response::new())
+            },
+            |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { unimplemented!() },
+        )));
+    let share_recipient = helper
+        .app
+        .instantiate_contract(
+            recipient_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Fee share recipient",
+            None,
+        )
+        .unwrap();
+
+    let action = ConcentratedPoolUpdateParams::EnableFeeShare {
+        fee_share_bps: 1000,
+        fee_share_address: share_recipient.to_string(),
+        callback: true,
+    };
+    helper.update_config(&owner, &action).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    helper.app.next_block(1000);
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let res = helper.swap(&user, &offer_asset, None).unwrap();
+
+    // The callback is sent right after the shared fee transfer
+    let expected_fee_share = 26081u128;
+    let recipient_balance = helper.coin_balance(&test_coins[1], &share_recipient);
+    assert_eq!(recipient_balance, expected_fee_share);
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("fee_share_sender", helper.pair_addr.to_string())
+            .add_attribute(
+                "fee_share_asset",
+                helper.assets[&test_coins[1]]
+                    .with_balance(expected_fee_share)
+                    .to_string()
+            )
+    ));
+}
+
 #[test]
 fn check_small_trades() {
     let owner = Addr::unchecked("owner");
@@ -1834,6 +2065,9 @@ fn check_lsd_swaps_with_price_update() {
         ma_half_time: 600,
         track_asset_balances: None,
         fee_share: None,
+        lp_token_metadata: None,
+        balance_caps: None,
+        max_price_scale_change_per_hour: None,
     };
     let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
 
@@ -1913,6 +2147,8 @@ fn test_provide_liquidity_without_funds() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let err = helper
@@ -2057,3 +2293,442 @@ fn test_tracker_contract() {
 
     assert_eq!(alice_share[0].amount, alice_hist_bal);
 }
+
+#[test]
+fn check_poke_idle_pool() {
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let err = helper.poke(&keeper).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Pools are empty"
+    );
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block(1000);
+
+    // Oracle price equals to the price scale thus there is nothing to repeg
+    let simulation = helper.simulate_poke().unwrap();
+    assert!(!simulation.would_change);
+    assert_eq!(simulation.price_scale, simulation.new_price_scale);
+    assert_eq!(simulation.change, Decimal256::zero());
+    let err = helper.poke(&keeper).unwrap_err();
+    assert_eq!(ContractError::NothingToPoke {}, err.downcast().unwrap());
+
+    // Several swaps within one block move the last price while the price scale stays the same
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(2_000_000000u128);
+    for _ in 0..4 {
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper.swap(&user, &offer_asset, None).unwrap();
+    }
+    let price_scale_before = helper
+        .query_config()
+        .unwrap()
+        .pool_state
+        .price_state
+        .price_scale;
+
+    // The pool stays idle for a day
+    helper.app.next_block(86400);
+
+    let simulation = helper.simulate_poke().unwrap();
+    assert!(simulation.would_change);
+    assert_eq!(simulation.price_scale, price_scale_before);
+    assert_eq!(
+        simulation.change,
+        simulation.new_price_scale.diff(simulation.price_scale)
+    );
+
+    // Anyone can poke the pool
+    let resp = helper.poke(&keeper).unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "poke")
+            .add_attribute("old_price_scale", price_scale_before.to_string())
+            .add_attribute("new_price_scale", simulation.new_price_scale.to_string())
+    ));
+
+    let price_state = helper.query_config().unwrap().pool_state.price_state;
+    assert_eq!(price_state.price_scale, simulation.new_price_scale);
+    assert_eq!(
+        price_state.last_price_update,
+        helper.app.block_info().time.seconds()
+    );
+
+    // Ask asset became more expensive after the swaps thus the price scale moved up
+    assert!(price_state.price_scale > price_scale_before);
+}
+
+#[cfg(feature = "sudo")]
+#[test]
+fn check_sudo_poke() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block(1000);
+
+    // Nothing to repeg doesn't fail the hook
+    let resp = helper.sudo_poke().unwrap();
+    assert!(!resp.has_event(&Event::new("wasm").add_attribute("action", "poke")));
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(2_000_000000u128);
+    for _ in 0..4 {
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper.swap(&user, &offer_asset, None).unwrap();
+    }
+    helper.app.next_block(86400);
+
+    let simulation = helper.simulate_poke().unwrap();
+    assert!(simulation.would_change);
+
+    let resp = helper.sudo_poke().unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("action", "poke")));
+    assert_eq!(
+        helper
+            .query_config()
+            .unwrap()
+            .pool_state
+            .price_state
+            .price_scale,
+        simulation.new_price_scale
+    );
+}
+
+#[test]
+fn check_balance_caps() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let params = ConcentratedPoolParams {
+        balance_caps: Some(vec![
+            native_asset_info("uusd".to_string()).with_balance(150_000_000000u128)
+        ]),
+        ..common_pcl_params()
+    };
+    let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let err = helper.provide_liquidity(&owner, &assets).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Pool balance of uusd would exceed its cap of 150000000000"
+    );
+
+    // Single sided provide of the uncapped asset is still possible
+    helper
+        .provide_liquidity(
+            &owner,
+            &[helper.assets[&test_coins[0]].with_balance(10_000_000000u128)],
+        )
+        .unwrap();
+
+    let action = ConcentratedPoolUpdateParams::UpdateBalanceCaps {
+        caps: vec![native_asset_info("uusd".to_string()).with_balance(0u8)],
+    };
+    let err = helper
+        .update_config(&Addr::unchecked("random"), &action)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    helper.update_config(&owner, &action).unwrap();
+
+    helper.provide_liquidity(&owner, &assets).unwrap();
+}
+
+#[test]
+fn check_refresh_precisions() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    let factory_config: astroport::factory::ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &astroport::factory::QueryMsg::Config {})
+        .unwrap();
+    let coin_registry = factory_config.coin_registry_address;
+    let set_uusd_precision = |helper: &mut Helper, precision: u8| {
+        helper
+            .app
+            .execute_contract(
+                owner.clone(),
+                coin_registry.clone(),
+                &astroport::native_coin_registry::ExecuteMsg::Add {
+                    native_coins: vec![("uusd".to_string(), precision)],
+                },
+                &[],
+            )
+            .unwrap();
+    };
+
+    // uusd precision was registered incorrectly
+    set_uusd_precision(&mut helper, 7);
+
+    let action = ConcentratedPoolUpdateParams::RefreshPrecisions {};
+    let err = helper
+        .update_config(&Addr::unchecked("random"), &action)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // The pool is empty thus precisions can be changed
+    helper.update_config(&owner, &action).unwrap();
+
+    // Reserves are equal in value with uusd having 7 decimals
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_0000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000000u128);
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    assert!(sim.return_amount.u128() > 9_900000, "{sim:?}");
+
+    // Precisions can't change once the pool has liquidity
+    set_uusd_precision(&mut helper, 6);
+    let err = helper.update_config(&owner, &action).unwrap_err();
+    assert_eq!(
+        ContractError::PrecisionChangeWithLiquidity {},
+        err.downcast().unwrap()
+    );
+
+    // Swaps, provisions and simulations don't query the registry at all:
+    // they keep working after both coins are removed from it
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            coin_registry.clone(),
+            &astroport::native_coin_registry::ExecuteMsg::Remove {
+                native_coins: vec!["uluna".to_string(), "uusd".to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(helper.simulate_swap(&offer_asset, None).unwrap(), sim);
+    helper
+        .simulate_reverse_swap(
+            &helper.assets[&test_coins[1]].with_balance(1_000000u128),
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    // Refreshing precisions is the only operation which queries the registry
+    helper.update_config(&owner, &action).unwrap_err();
+}
+
+#[test]
+fn check_repeg_rate_limit() {
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    // The limit is below min_price_scale_delta thus every repeg is capped
+    let max_change = f64_to_dec(0.0001);
+    let params = ConcentratedPoolParams {
+        max_price_scale_change_per_hour: Some(max_change),
+        ..common_pcl_params()
+    };
+    let mut helper = Helper::new(&owner, test_coins.clone(), params).unwrap();
+
+    let last_repeg = helper.query_last_repeg().unwrap();
+    assert_eq!(last_repeg.timestamp, 0);
+    assert_eq!(last_repeg.delta, Decimal256::zero());
+    assert_eq!(last_repeg.max_price_scale_change_per_hour, max_change);
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+    helper.app.next_block(1000);
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(2_000_000000u128);
+    for _ in 0..4 {
+        helper.give_me_money(&[offer_asset.clone()], &user);
+        helper.swap(&user, &offer_asset, None).unwrap();
+    }
+    let price_scale_before = helper.query_last_repeg().unwrap().price_scale;
+
+    helper.app.next_block(86400);
+
+    let simulation = helper.simulate_poke().unwrap();
+    assert!(simulation.would_change);
+    let max_abs_change = price_scale_before * Decimal256::from(max_change);
+    assert!(simulation.change.diff(max_abs_change) <= Decimal256::raw(1));
+
+    helper.poke(&keeper).unwrap();
+    let last_repeg = helper.query_last_repeg().unwrap();
+    assert_eq!(last_repeg.timestamp, helper.app.block_info().time.seconds());
+    assert_eq!(last_repeg.delta, Decimal256::from(max_change));
+    assert_eq!(last_repeg.price_scale, simulation.new_price_scale);
+
+    // No budget is left within the same block
+    let err = helper.poke(&keeper).unwrap_err();
+    assert_eq!(ContractError::NothingToPoke {}, err.downcast().unwrap());
+
+    let action = ConcentratedPoolUpdateParams::Update(UpdatePoolParams {
+        mid_fee: None,
+        out_fee: None,
+        fee_gamma: None,
+        repeg_profit_threshold: None,
+        min_price_scale_delta: None,
+        ma_half_time: None,
+        max_price_scale_change_per_hour: Some(f64_to_dec(0.6)),
+    });
+    let err = helper.update_config(&owner, &action).unwrap_err();
+    assert_eq!(
+        ContractError::PclError(PclError::IncorrectPoolParam(
+            "max_price_scale_change_per_hour".to_string(),
+            PRICE_SCALE_CHANGE_PER_HOUR_MIN.to_string(),
+            PRICE_SCALE_CHANGE_PER_HOUR_MAX.to_string()
+        )),
+        err.downcast().unwrap(),
+    );
+
+    // Zero disables the limit
+    let action = ConcentratedPoolUpdateParams::Update(UpdatePoolParams {
+        mid_fee: None,
+        out_fee: None,
+        fee_gamma: None,
+        repeg_profit_threshold: None,
+        min_price_scale_delta: None,
+        ma_half_time: None,
+        max_price_scale_change_per_hour: Some(Decimal::zero()),
+    });
+    helper.update_config(&owner, &action).unwrap();
+    assert_eq!(
+        helper
+            .query_last_repeg()
+            .unwrap()
+            .max_price_scale_change_per_hour,
+        Decimal::zero()
+    );
+}
+
+#[test]
+fn check_min_trade_notional() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), common_pcl_params()).unwrap();
+
+    // Pairs read the minimum at instantiation thus the pair is recreated after the config update
+    let factory_config: astroport::factory::ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &astroport::factory::QueryMsg::Config {})
+        .unwrap();
+    let mut pair_config = factory_config.pair_configs[0].clone();
+    pair_config.min_trade_notional = Some(Decimal::from_ratio(10u8, 1u8));
+    let asset_infos = vec![
+        helper.assets[&test_coins[0]].clone(),
+        helper.assets[&test_coins[1]].clone(),
+    ];
+    for msg in [
+        astroport::factory::ExecuteMsg::UpdatePairConfig {
+            config: pair_config.clone(),
+        },
+        astroport::factory::ExecuteMsg::Deregister {
+            asset_infos: asset_infos.clone(),
+        },
+        astroport::factory::ExecuteMsg::CreatePair {
+            pair_type: pair_config.pair_type,
+            asset_infos: asset_infos.clone(),
+            init_params: Some(
+                to_json_binary(&ConcentratedPoolParams {
+                    price_scale: Decimal::from_ratio(2u8, 1u8),
+                    ..common_pcl_params()
+                })
+                .unwrap(),
+            ),
+        },
+    ] {
+        helper
+            .app
+            .execute_contract(owner.clone(), helper.factory.clone(), &msg, &[])
+            .unwrap();
+    }
+    let pair_info: astroport::asset::PairInfo = helper
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &astroport::factory::QueryMsg::Pair { asset_infos },
+        )
+        .unwrap();
+    helper.pair_addr = pair_info.contract_addr;
+    helper.lp_token = pair_info.liquidity_token;
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(200_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(9_999999u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let err = helper.swap(&user, &offer_asset, None).unwrap_err();
+    assert_eq!(
+        ContractError::TradeTooSmall {
+            min: Decimal::from_ratio(10u8, 1u8),
+            notional: Decimal256::from_str("9.999999").unwrap(),
+        },
+        err.downcast().unwrap()
+    );
+
+    // The second asset notional is converted with the price scale
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(4_900000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let err = helper.swap(&user, &offer_asset, None).unwrap_err();
+    assert_eq!(
+        ContractError::TradeTooSmall {
+            min: Decimal::from_ratio(10u8, 1u8),
+            notional: Decimal256::from_str("9.8").unwrap(),
+        },
+        err.downcast().unwrap()
+    );
+
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(5_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+}
@@ -80,6 +80,7 @@ fn simulate_fee_share_case(case: Vec<(usize, u128, u64)>) {
     let action = ConcentratedPoolUpdateParams::EnableFeeShare {
         fee_share_bps: 1000,
         fee_share_address: "share_address".to_string(),
+        callback: false,
     };
     helper.update_config(&owner, &action).unwrap();
 
@@ -1,14 +1,14 @@
 use std::vec;
 
 use cosmwasm_std::{
-    attr, entry_point, from_json, wasm_execute, wasm_instantiate, Addr, Binary, CustomMsg, Decimal,
-    Decimal256, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg,
+    attr, ensure, entry_point, from_json, wasm_execute, wasm_instantiate, Addr, Binary, CustomMsg,
+    Decimal, Decimal256, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg,
     SubMsgResponse, SubMsgResult, Uint128,
 };
 use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use cw_utils::parse_instantiate_response_data;
-use injective_cosmwasm::{InjectiveMsgWrapper, InjectiveQuerier, InjectiveQueryWrapper};
+use injective_cosmwasm::{InjectiveMsgWrapper, InjectiveQuerier, InjectiveQueryWrapper, MarketId};
 use itertools::Itertools;
 
 use astroport::asset::{
@@ -24,7 +24,9 @@ use astroport::pair_concentrated::UpdatePoolParams;
 use astroport::pair_concentrated_inj::{
     ConcentratedInjObParams, ConcentratedObPoolUpdateParams, ExecuteMsg,
 };
-use astroport::querier::{query_factory_config, query_fee_info, query_supply};
+use astroport::querier::{
+    query_factory_config, query_fee_info, query_min_trade_notional, query_supply,
+};
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport_circular_buffer::BufferManager;
 use astroport_pcl_common::state::{
@@ -41,7 +43,7 @@ use crate::error::ContractError;
 use crate::orderbook::state::OrderbookState;
 use crate::orderbook::utils::{
     get_subaccount_balances, is_allowed_for_begin_blocker, is_contract_active, leave_orderbook,
-    process_cumulative_trade,
+    maybe_cancel_orders, process_cumulative_trade,
 };
 use crate::state::{CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL};
 use crate::utils::{accumulate_swap_sizes, query_contract_balances, query_pools};
@@ -125,12 +127,15 @@ pub fn instantiate(
         },
     };
 
+    let pair_type = PairType::Custom("concentrated_inj_orderbook".to_string());
+    let min_trade_notional = query_min_trade_notional(&deps.querier, &factory_addr, &pair_type)?;
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address.clone(),
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
-            pair_type: PairType::Custom("concentrated_inj_orderbook".to_string()),
+            pair_type,
         },
         factory_addr,
         pool_params,
@@ -138,6 +143,7 @@ pub fn instantiate(
         owner: None,
         track_asset_balances: false, // TODO: decide whether to track asset balances in PCL inj pool
         fee_share: None,             // TODO: decide whether to enable fee sharing or not
+        min_trade_notional,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -216,6 +222,7 @@ pub fn reply(
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             staking_target,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
@@ -240,6 +247,7 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
+            staking_target,
         } => provide_liquidity(
             deps,
             env,
@@ -248,6 +256,7 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
+            staking_target,
         ),
         ExecuteMsg::Swap {
             offer_asset,
@@ -350,7 +359,11 @@ fn receive_cw20(
 ///
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
-pub fn provide_liquidity<T>(
+///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+/// It must be registered in the factory; the factory default is used if not set.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity(
     deps: DepsMut<InjectiveQueryWrapper>,
     env: Env,
     info: MessageInfo,
@@ -358,10 +371,8 @@ pub fn provide_liquidity<T>(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
-) -> Result<Response<T>, ContractError>
-where
-    T: CustomMsg,
-{
+    staking_target: Option<String>,
+) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
     if !check_pair_registered(
@@ -495,6 +506,7 @@ where
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
             false,
+            None,
         )?);
 
         // share cannot become zero after minimum liquidity subtraction
@@ -552,6 +564,14 @@ where
 
     let share_uint128 = share.to_uint(LP_TOKEN_PRECISION)?;
 
+    // Large provide makes outstanding orders undersized. Cancel them until the next begin blocker
+    messages.extend(maybe_cancel_orders(
+        &ob_state,
+        &env,
+        share,
+        total_share + share,
+    ));
+
     // Mint LP tokens for the sender or for the receiver (if set)
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
     let auto_stake = auto_stake.unwrap_or(false);
@@ -562,9 +582,11 @@ where
         &receiver,
         share_uint128,
         auto_stake,
+        staking_target.as_deref(),
     )?);
 
-    ob_state.enabled = is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
+    ob_state.enabled =
+        !ob_state.deactivated && is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
     ob_state.reconcile(deps.storage)?;
     CONFIG.save(deps.storage, &config)?;
 
@@ -637,6 +659,14 @@ fn withdraw_liquidity(
             &ob_state.subaccount,
         )?;
         response = leave_orderbook(&ob_state, orderbook_balances, &env).map_err(StdError::from)?;
+    } else {
+        // Outstanding orders must not exceed the remaining liquidity between blocks
+        messages.extend(maybe_cancel_orders(
+            &ob_state,
+            &env,
+            amount.to_decimal256(LP_TOKEN_PRECISION)?,
+            total_share.to_decimal256(LP_TOKEN_PRECISION)?,
+        ));
     }
 
     // decrease XCP
@@ -735,6 +765,19 @@ where
     let ask_ind = 1 ^ offer_ind;
     let ask_asset_prec = precisions.get_precision(&pools[ask_ind].info)?;
 
+    if let Some(min) = config.min_trade_notional {
+        // Offer amount denominated in the first pool asset
+        let notional = if offer_ind == 0 {
+            offer_asset_dec.amount
+        } else {
+            offer_asset_dec.amount * config.pool_state.price_state.price_scale
+        };
+        ensure!(
+            notional >= Decimal256::from(min),
+            ContractError::TradeTooSmall { min, notional }
+        );
+    }
+
     pools[offer_ind].amount -= offer_asset_dec.amount;
 
     before_swap_check(&pools, offer_asset_dec.amount)?;
@@ -854,7 +897,8 @@ where
     }
 
     CONFIG.save(deps.storage, &config)?;
-    ob_state.enabled = is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
+    ob_state.enabled =
+        !ob_state.deactivated && is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
     ob_state.reconcile(deps.storage)?;
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
@@ -877,12 +921,12 @@ where
 /// Updates the pool configuration with the specified parameters in the `params` variable.
 ///
 /// * **params** new parameter values in [`Binary`] form.
-fn update_config<T>(
-    deps: DepsMut<InjectiveQueryWrapper>,
+fn update_config(
+    mut deps: DepsMut<InjectiveQueryWrapper>,
     env: Env,
     info: MessageInfo,
     params: Binary,
-) -> Result<Response<T>, ContractError> {
+) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
@@ -891,6 +935,7 @@ fn update_config<T>(
         return Err(ContractError::Unauthorized {});
     }
 
+    let mut response = Response::default();
     let attributes = match from_json::<ConcentratedObPoolUpdateParams>(&params)? {
         ConcentratedObPoolUpdateParams::Update(update_params) => {
             let mut attrs = config.pool_params.update_params(update_params)?;
@@ -911,40 +956,85 @@ fn update_config<T>(
             config.pool_state.stop_promotion(&env);
             vec![attr("action", "stop_changing_amp_gamma")]
         }
-        ConcentratedObPoolUpdateParams::UpdateOrderbookParams { orders_number } => {
-            OrderbookState::update_orders_number(deps.storage, orders_number)?;
-            vec![
-                attr("action", "update_orderbook_params"),
-                attr("orders_number", orders_number.to_string()),
-            ]
+        ConcentratedObPoolUpdateParams::UpdateOrderbookParams {
+            orders_number,
+            min_trades_to_avg,
+            active,
+            market_id,
+        } => {
+            let mut ob_state = OrderbookState::load(deps.storage)?;
+            let mut attrs = vec![attr("action", "update_orderbook_params")];
+
+            if let Some(orders_number) = orders_number {
+                ob_state.set_orders_number(orders_number)?;
+                attrs.push(attr("orders_number", orders_number.to_string()));
+            }
+
+            if let Some(min_trades_to_avg) = min_trades_to_avg {
+                let buffer = BufferManager::new(deps.storage, OBSERVATIONS)?;
+                let observations_count = if buffer.exists(deps.storage, buffer.head()) {
+                    buffer.capacity()
+                } else {
+                    buffer.head()
+                };
+                ob_state.set_min_trades_to_avg(min_trades_to_avg, observations_count)?;
+                attrs.push(attr("min_trades_to_avg", min_trades_to_avg.to_string()));
+            }
+
+            let new_market_id = market_id
+                .map(MarketId::new)
+                .transpose()?
+                .filter(|market_id| *market_id != ob_state.market_id);
+            let deactivate = active == Some(false) && !ob_state.deactivated;
+
+            // Open orders must not be left in the previous market or after deactivation
+            if new_market_id.is_some() || deactivate {
+                response = withdraw_orderbook_liquidity(deps.branch(), &env, &mut ob_state)?;
+                // Pool state might have been updated by trades processed during the withdrawal
+                config = CONFIG.load(deps.storage)?;
+            }
+
+            if let Some(market_id) = new_market_id {
+                let base_precision =
+                    ob_state.asset_infos[0].decimals(&deps.querier, &config.factory_addr)?;
+                ob_state.set_market(deps.querier, market_id, base_precision)?;
+                attrs.push(attr("market_id", ob_state.market_id.as_str()));
+            }
+
+            if let Some(active) = active {
+                let inj_querier = InjectiveQuerier::new(&deps.querier);
+                ob_state.deactivated = !active;
+                ob_state.enabled =
+                    active && is_allowed_for_begin_blocker(&inj_querier, &config.pair_info);
+                if active {
+                    ob_state.need_reconcile = true;
+                }
+                attrs.push(attr("active", active.to_string()));
+            }
+
+            ob_state.save(deps.storage)?;
+
+            attrs
         }
     };
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::default().add_attributes(attributes))
+    Ok(response.add_attributes(attributes))
 }
 
-/// In case for some reason orderbook was disabled and liquidity left in the subaccount
-/// this permissionless endpoint can be used to withdraw whole balance to the contract address.
-pub fn orderbook_emergency_withdraw(
+/// Cancels all orders and withdraws the whole subaccount balance to the contract.
+/// Trades which happened since the last begin blocker are processed beforehand.
+/// The caller is responsible for saving the orderbook state.
+fn withdraw_orderbook_liquidity(
     deps: DepsMut<InjectiveQueryWrapper>,
-    env: Env,
+    env: &Env,
+    ob_state: &mut OrderbookState,
 ) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
     let querier = InjectiveQuerier::new(&deps.querier);
-
-    // Ask chain whether the pair contract is still active in begin blocker
-    if is_contract_active(&querier, &env.contract.address) {
-        return Err(StdError::generic_err(
-            "Failed to withdraw liquidity from orderbook: contract is active",
-        )
-        .into());
-    }
-
-    let mut ob_state = OrderbookState::load(deps.storage)?;
     let balances = get_subaccount_balances(&ob_state.asset_infos, &querier, &ob_state.subaccount)?;
 
     let mut response = if !(balances[0].amount + balances[1].amount).is_zero() {
-        leave_orderbook(&ob_state, balances.clone(), &env).map_err(StdError::from)?
+        leave_orderbook(ob_state, balances.clone(), env).map_err(StdError::from)?
     } else {
         Response::new()
     };
@@ -956,7 +1046,7 @@ pub fn orderbook_emergency_withdraw(
             deps.querier,
             &env.contract.address,
             &config,
-            &ob_state,
+            ob_state,
             &precisions,
             None,
         )?
@@ -967,8 +1057,8 @@ pub fn orderbook_emergency_withdraw(
         let quote_asset_precision = precisions.get_precision(&config.pair_info.asset_infos[1])?;
         let maker_fee_message = process_cumulative_trade(
             deps.querier,
-            &env,
-            &ob_state,
+            env,
+            ob_state,
             &mut config,
             &mut pools,
             &balances,
@@ -981,12 +1071,35 @@ pub fn orderbook_emergency_withdraw(
         response = response.add_messages(maker_fee_message);
     }
 
-    ob_state.enabled = false;
-    let new_balances = vec![
+    ob_state.need_reconcile = false;
+    ob_state.last_balances = vec![
         ob_state.asset_infos[0].with_balance(0u8),
         ob_state.asset_infos[1].with_balance(0u8),
     ];
-    ob_state.reconciliation_done(deps.storage, new_balances)?;
+
+    Ok(response)
+}
+
+/// In case for some reason orderbook was disabled and liquidity left in the subaccount
+/// this permissionless endpoint can be used to withdraw whole balance to the contract address.
+pub fn orderbook_emergency_withdraw(
+    mut deps: DepsMut<InjectiveQueryWrapper>,
+    env: Env,
+) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
+    let querier = InjectiveQuerier::new(&deps.querier);
+
+    // Ask chain whether the pair contract is still active in begin blocker
+    if is_contract_active(&querier, &env.contract.address) {
+        return Err(StdError::generic_err(
+            "Failed to withdraw liquidity from orderbook: contract is active",
+        )
+        .into());
+    }
+
+    let mut ob_state = OrderbookState::load(deps.storage)?;
+    let response = withdraw_orderbook_liquidity(deps.branch(), &env, &mut ob_state)?;
+    ob_state.enabled = false;
+    ob_state.save(deps.storage)?;
 
     Ok(response.add_attributes(vec![
         attr("action", "emergency_orderbook_withdraw"),
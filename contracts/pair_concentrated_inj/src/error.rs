@@ -1,4 +1,4 @@
-use cosmwasm_std::{ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{ConversionOverflowError, Decimal, Decimal256, OverflowError, StdError};
 use thiserror::Error;
 
 use astroport::asset::MINIMUM_LIQUIDITY_AMOUNT;
@@ -49,4 +49,7 @@ pub enum ContractError {
 
     #[error("Operation is not supported")]
     NotSupported {},
+
+    #[error("Swap notional {notional} is below the minimum trade notional {min}")]
+    TradeTooSmall { min: Decimal, notional: Decimal256 },
 }
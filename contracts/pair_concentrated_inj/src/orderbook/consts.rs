@@ -1,4 +1,5 @@
 use astroport::observation::OBSERVATIONS_SIZE;
+use cosmwasm_std::Decimal256;
 use std::ops::RangeInclusive;
 
 /// Validation limits for order size.
@@ -14,3 +15,7 @@ pub const SUBACC_NONCE: u32 = 1;
 
 /// Gas fee denom used on chain. Currently it's `inj` for Injective chain.
 pub const GAS_FEE_DENOM: &str = "inj";
+
+/// Share of the pool liquidity (10%) which being provided or withdrawn in a single transaction
+/// cancels all outstanding orders. Orders are placed again in the next begin blocker according to the new liquidity.
+pub const LIQUIDITY_CHANGE_CANCEL_THRESHOLD: Decimal256 = Decimal256::raw(100000000000000000);
@@ -54,6 +54,10 @@ pub struct OrderbookState {
     pub ready: bool,
     /// Whether the begin blocker execution is allowed or not. Default: true
     pub enabled: bool,
+    /// Whether the owner has deactivated orderbook integration.
+    /// Begin blocker execution is not allowed until the owner reactivates it.
+    #[serde(default)]
+    pub deactivated: bool,
 }
 
 const OB_CONFIG: Item<OrderbookState> = Item::new("orderbook_config");
@@ -93,6 +97,7 @@ impl OrderbookState {
             min_trades_to_avg,
             ready: false,
             enabled: true,
+            deactivated: false,
         };
 
         state.set_ticks(querier, base_precision)?;
@@ -116,6 +121,12 @@ impl OrderbookState {
         orders_number: u8,
         min_trades_to_avg: u32,
     ) -> StdResult<()> {
+        Self::validate_orders_number(orders_number)?;
+        Self::validate_min_trades_to_avg(min_trades_to_avg)?;
+        Self::validate_market(querier, asset_infos, market_id)
+    }
+
+    fn validate_orders_number(orders_number: u8) -> StdResult<()> {
         validate_param!(
             orders_number,
             orders_number,
@@ -123,6 +134,10 @@ impl OrderbookState {
             *ORDER_SIZE_LIMITS.end()
         );
 
+        Ok(())
+    }
+
+    fn validate_min_trades_to_avg(min_trades_to_avg: u32) -> StdResult<()> {
         validate_param!(
             min_trades_to_avg,
             min_trades_to_avg,
@@ -130,6 +145,15 @@ impl OrderbookState {
             *MIN_TRADES_TO_AVG_LIMITS.end()
         );
 
+        Ok(())
+    }
+
+    /// Validates that the market exists and matches pair asset infos
+    fn validate_market(
+        querier: QuerierWrapper<InjectiveQueryWrapper>,
+        asset_infos: &[AssetInfo],
+        market_id: &MarketId,
+    ) -> StdResult<()> {
         let market_ids = calc_market_ids(asset_infos)?;
 
         if market_id.as_str() == market_ids[1] {
@@ -222,21 +246,48 @@ impl OrderbookState {
         self.ready = ready;
     }
 
-    /// Validates new orders number parameter and saves it in storage.
-    pub fn update_orders_number(storage: &mut dyn Storage, orders_number: u8) -> StdResult<()> {
-        validate_param!(
-            orders_number,
-            orders_number,
-            *ORDER_SIZE_LIMITS.start(),
-            *ORDER_SIZE_LIMITS.end()
-        );
+    /// Validates and sets new orders number. Orders are placed again on the next begin blocker.
+    pub fn set_orders_number(&mut self, orders_number: u8) -> StdResult<()> {
+        Self::validate_orders_number(orders_number)?;
+        self.orders_number = orders_number;
+        self.need_reconcile = true;
 
-        OB_CONFIG
-            .update(storage, |mut ob_state| {
-                ob_state.orders_number = orders_number;
-                Ok(ob_state)
-            })
-            .map(|_| ())
+        Ok(())
+    }
+
+    /// Validates and sets new minimum number of trades to average.
+    /// The pool becomes ready right away if `observations_count` already reaches it.
+    /// A ready pool stays ready as the parameter only delays the initial integration.
+    pub fn set_min_trades_to_avg(
+        &mut self,
+        min_trades_to_avg: u32,
+        observations_count: u32,
+    ) -> StdResult<()> {
+        Self::validate_min_trades_to_avg(min_trades_to_avg)?;
+        self.min_trades_to_avg = min_trades_to_avg;
+        self.ready = self.ready || observations_count >= min_trades_to_avg;
+
+        Ok(())
+    }
+
+    /// Validates and switches to the new market. Tick sizes are refreshed and the orderbook
+    /// is reconciled on the next begin blocker.
+    /// All orders in the previous market must be cancelled beforehand.
+    pub fn set_market(
+        &mut self,
+        querier: QuerierWrapper<InjectiveQueryWrapper>,
+        market_id: MarketId,
+        base_precision: u8,
+    ) -> StdResult<()> {
+        Self::validate_market(querier, &self.asset_infos, &market_id)?;
+
+        self.market_id = market_id;
+        self.min_price_tick_size = Default::default();
+        self.min_quantity_tick_size = Default::default();
+        self.set_ticks(querier, base_precision)?;
+        self.need_reconcile = true;
+
+        Ok(())
     }
 }
 
@@ -253,6 +304,7 @@ impl From<OrderbookState> for OrderbookStateResponse {
             min_trades_to_avg: value.min_trades_to_avg,
             ready: value.ready,
             enabled: value.enabled,
+            deactivated: value.deactivated,
         }
     }
 }
@@ -21,7 +21,7 @@ use astroport_pcl_common::state::{AmpGamma, Config, Precisions};
 
 use crate::contract::LP_TOKEN_PRECISION;
 use crate::error::ContractError;
-use crate::orderbook::consts::{GAS_FEE_DENOM, SUBACC_NONCE};
+use crate::orderbook::consts::{GAS_FEE_DENOM, LIQUIDITY_CHANGE_CANCEL_THRESHOLD, SUBACC_NONCE};
 use crate::orderbook::error::OrderbookError;
 use crate::orderbook::state::OrderbookState;
 
@@ -99,6 +99,33 @@ pub fn cancel_all_orders(
     )
 }
 
+/// Returns a message cancelling all outstanding orders if the liquidity change exceeds
+/// [`LIQUIDITY_CHANGE_CANCEL_THRESHOLD`] of the pool liquidity and orders might have been placed.
+///
+/// * **liquidity_change** amount of LP tokens being minted or burned.
+///
+/// * **total_share** total amount of LP tokens after the change for provides or before the change for withdrawals.
+pub fn maybe_cancel_orders(
+    ob_state: &OrderbookState,
+    env: &Env,
+    liquidity_change: Decimal256,
+    total_share: Decimal256,
+) -> Option<CosmosMsg<InjectiveMsgWrapper>> {
+    if !(ob_state.ready && ob_state.enabled) || total_share.is_zero() {
+        return None;
+    }
+
+    if liquidity_change / total_share > LIQUIDITY_CHANGE_CANCEL_THRESHOLD {
+        Some(cancel_all_orders(
+            &env.contract.address,
+            &ob_state.subaccount,
+            &ob_state.market_id,
+        ))
+    } else {
+        None
+    }
+}
+
 /// Fetches subaccount balances in decimal representation.
 pub(crate) fn get_subaccount_balances_dec(
     asset_infos: &[AssetInfo],
@@ -488,6 +515,7 @@ where
 
 #[cfg(test)]
 mod tests {
+    use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::Addr;
 
     use astroport::asset::{native_asset_info, token_asset_info};
@@ -523,4 +551,61 @@ mod tests {
 
         assert_eq!(err.to_string(), "Generic error: CW20 tokens not supported");
     }
+
+    #[test]
+    fn test_maybe_cancel_orders() {
+        let env = mock_env();
+        let mut ob_state = OrderbookState {
+            market_id: MarketId::unchecked("test"),
+            subaccount: SubaccountId::unchecked("test"),
+            asset_infos: vec![],
+            min_price_tick_size: Default::default(),
+            min_quantity_tick_size: Default::default(),
+            need_reconcile: false,
+            last_balances: vec![],
+            orders_number: 5,
+            min_trades_to_avg: 10,
+            ready: false,
+            enabled: true,
+            deactivated: false,
+        };
+        let total_share = Decimal256::from_ratio(1000u16, 1u8);
+        let large_change = Decimal256::from_ratio(101u8, 1u8);
+
+        // Orders are never placed before the contract is ready
+        assert_eq!(
+            maybe_cancel_orders(&ob_state, &env, large_change, total_share),
+            None
+        );
+
+        ob_state.ready = true;
+        assert_eq!(
+            maybe_cancel_orders(&ob_state, &env, large_change, total_share),
+            Some(cancel_all_orders(
+                &env.contract.address,
+                &ob_state.subaccount,
+                &ob_state.market_id
+            ))
+        );
+        // Changes up to the threshold keep outstanding orders
+        assert_eq!(
+            maybe_cancel_orders(
+                &ob_state,
+                &env,
+                total_share * LIQUIDITY_CHANGE_CANCEL_THRESHOLD,
+                total_share
+            ),
+            None
+        );
+        assert_eq!(
+            maybe_cancel_orders(&ob_state, &env, large_change, Decimal256::zero()),
+            None
+        );
+
+        ob_state.enabled = false;
+        assert_eq!(
+            maybe_cancel_orders(&ob_state, &env, large_change, total_share),
+            None
+        );
+    }
 }
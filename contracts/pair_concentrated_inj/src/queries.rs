@@ -243,8 +243,8 @@ pub fn query_reverse_simulation(
 
     Ok(ReverseSimulationResponse {
         offer_amount: offer_amount.to_uint(offer_asset_prec)?,
-        spread_amount: spread_amount.to_uint(offer_asset_prec)?,
-        commission_amount: commission_amount.to_uint(offer_asset_prec)?,
+        spread_amount: spread_amount.to_uint(ask_asset_prec)?,
+        commission_amount: commission_amount.to_uint(ask_asset_prec)?,
     })
 }
 
@@ -191,6 +191,7 @@ mod tests {
             min_trades_to_avg: *MIN_TRADES_TO_AVG_LIMITS.start(),
             ready: false,
             enabled: true,
+            deactivated: false,
         };
         BufferManager::init(&mut store, OBSERVATIONS, 10).unwrap();
 
@@ -226,6 +227,7 @@ mod tests {
             min_trades_to_avg,
             ready: false,
             enabled: true,
+            deactivated: false,
         };
         BufferManager::init(&mut store, OBSERVATIONS, min_trades_to_avg).unwrap();
 
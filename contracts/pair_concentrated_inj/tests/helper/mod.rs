@@ -303,6 +303,7 @@ impl Helper {
             owner: owner.to_string(),
             whitelist_code_id: 234u64,
             coin_registry_address: coin_registry_address.to_string(),
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
@@ -405,6 +406,7 @@ impl Helper {
             slippage_tolerance,
             auto_stake: None,
             receiver: None,
+            staking_target: None,
         };
 
         self.app
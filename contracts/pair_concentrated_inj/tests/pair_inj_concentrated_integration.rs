@@ -3,7 +3,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use cosmwasm_std::{coins, Addr, Coin, Decimal, Decimal256, StdError, Uint128};
+use cosmwasm_std::{coins, to_json_binary, Addr, Coin, Decimal, Decimal256, StdError, Uint128};
 use injective_cosmwasm::InjectiveQuerier;
 use injective_testing::generate_inj_address;
 use itertools::{max, Itertools};
@@ -14,10 +14,13 @@ use astroport::factory::PairType;
 use astroport::pair_concentrated::{
     ConcentratedPoolParams, ConcentratedPoolUpdateParams, PromoteParams, UpdatePoolParams,
 };
-use astroport::pair_concentrated_inj::{ExecuteMsg, MigrateMsg, OrderbookConfig};
+use astroport::pair_concentrated_inj::{
+    ConcentratedObPoolUpdateParams, ExecuteMsg, MigrateMsg, OrderbookConfig,
+};
 use astroport_mocks::cw_multi_test::Executor;
 use astroport_pair_concentrated_injective::error::ContractError;
 use astroport_pair_concentrated_injective::orderbook::consts::MIN_TRADES_TO_AVG_LIMITS;
+use astroport_pair_concentrated_injective::orderbook::utils::calc_market_ids;
 use astroport_pcl_common::consts::{AMP_MAX, AMP_MIN, MA_HALF_TIME_LIMITS};
 use astroport_pcl_common::error::PclError;
 
@@ -404,8 +407,9 @@ fn swap_different_precisions() {
         )
         .unwrap();
     assert_eq!(reverse_sim_resp.offer_amount.u128(), 10019003);
-    assert_eq!(reverse_sim_resp.commission_amount.u128(), 45084);
-    assert_eq!(reverse_sim_resp.spread_amount.u128(), 125);
+    // Commission and spread are denominated in the ask asset (BAR has 6 decimals)
+    assert_eq!(reverse_sim_resp.commission_amount.u128(), 450849);
+    assert_eq!(reverse_sim_resp.spread_amount.u128(), 1256);
 
     helper.give_me_money(&[offer_asset.clone()], &user);
     helper.swap(&user, &offer_asset, None).unwrap();
@@ -1027,6 +1031,101 @@ fn check_last_withdraw() {
     assert_eq!(contract_balances.assets[1].amount.u128(), 1416);
 }
 
+#[test]
+fn check_large_withdraw_cancels_orders() {
+    let owner = generate_inj_address();
+
+    let test_coins = vec![TestCoin::native("inj"), TestCoin::native("astro")];
+
+    let params = ConcentratedPoolParams {
+        price_scale: f64_to_dec(0.5),
+        ..common_pcl_params()
+    };
+    let mut helper = Helper::new(&owner, test_coins.clone(), params, true).unwrap();
+
+    helper
+        .app
+        .enable_contract(
+            helper.pair_addr.clone(),
+            MockFundingMode::GrantOnly(helper.owner.clone()),
+        )
+        .unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance((400_000f64 * 1e18) as u128),
+        helper.assets[&test_coins[1]].with_balance((800_000f64 * 1e6) as u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = generate_inj_address();
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance((100_000f64 * 1e18) as u128),
+        helper.assets[&test_coins[1]].with_balance((200_000f64 * 1e6) as u128),
+    ];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+
+    for _ in 0..10 {
+        helper
+            .swap(
+                &owner,
+                &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+                None,
+            )
+            .unwrap();
+        helper.next_block(true).unwrap();
+        helper
+            .swap(
+                &owner,
+                &helper.assets[&test_coins[0]].with_balance((500.0 * 1e18) as u128),
+                None,
+            )
+            .unwrap();
+        helper.next_block(true).unwrap();
+    }
+
+    let ob_config = helper.query_ob_config().unwrap();
+    let orders_count = |helper: &Helper| {
+        let querier_wrapper = helper.app.wrap();
+        InjectiveQuerier::new(&querier_wrapper)
+            .query_trader_spot_orders(&ob_config.market_id, &ob_config.subaccount)
+            .unwrap()
+            .orders
+            .map(|orders| orders.len())
+            .unwrap_or_default()
+    };
+    assert_eq!(orders_count(&helper), 10);
+
+    // Small withdrawal keeps outstanding orders intact
+    let user_lp_balance = helper.token_balance(&helper.lp_token, &user);
+    helper
+        .withdraw_liquidity(&user, user_lp_balance / 10, vec![])
+        .unwrap();
+    assert_eq!(orders_count(&helper), 10);
+
+    // User withdraws ~20% of the pool liquidity. All orders must be cancelled
+    helper
+        .withdraw_liquidity(&user, user_lp_balance - user_lp_balance / 10, vec![])
+        .unwrap();
+    assert_eq!(orders_count(&helper), 0);
+
+    // Orders are placed again in the next begin blocker according to the remaining liquidity
+    helper.next_block(true).unwrap();
+    assert_eq!(orders_count(&helper), 10);
+
+    // The same applies to large provides
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance((200_000f64 * 1e18) as u128),
+        helper.assets[&test_coins[1]].with_balance((400_000f64 * 1e6) as u128),
+    ];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+    assert_eq!(orders_count(&helper), 0);
+
+    helper.next_block(true).unwrap();
+    assert_eq!(orders_count(&helper), 10);
+}
+
 #[test]
 fn check_deactivate_orderbook() {
     let owner = generate_inj_address();
@@ -1978,3 +2077,160 @@ fn check_small_trades() {
         relative_diff
     );
 }
+
+#[test]
+fn check_update_orderbook_params() {
+    let owner = generate_inj_address();
+
+    let test_coins = vec![TestCoin::native("inj"), TestCoin::native("astro")];
+
+    let params = ConcentratedPoolParams {
+        price_scale: f64_to_dec(0.5),
+        ..common_pcl_params()
+    };
+    let mut helper = Helper::new(&owner, test_coins.clone(), params, true).unwrap();
+
+    helper
+        .app
+        .enable_contract(
+            helper.pair_addr.clone(),
+            MockFundingMode::GrantOnly(helper.owner.clone()),
+        )
+        .unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance((500_000f64 * 1e18) as u128),
+        helper.assets[&test_coins[1]].with_balance((1_000_000f64 * 1e6) as u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    for _ in 0..10 {
+        helper
+            .swap(
+                &owner,
+                &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+                None,
+            )
+            .unwrap();
+        helper.next_block(true).unwrap();
+        helper
+            .swap(
+                &owner,
+                &helper.assets[&test_coins[0]].with_balance((500.0 * 1e18) as u128),
+                None,
+            )
+            .unwrap();
+        helper.next_block(true).unwrap();
+    }
+
+    let ob_config = helper.query_ob_config().unwrap();
+    let orders_count = |helper: &Helper| {
+        let querier_wrapper = helper.app.wrap();
+        InjectiveQuerier::new(&querier_wrapper)
+            .query_trader_spot_orders(&ob_config.market_id, &ob_config.subaccount)
+            .unwrap()
+            .orders
+            .map(|orders| orders.len())
+            .unwrap_or_default()
+    };
+    let subaccount_balance = |helper: &Helper, denom: &str| -> u128 {
+        let querier_wrapper = helper.app.wrap();
+        InjectiveQuerier::new(&querier_wrapper)
+            .query_subaccount_deposit(&ob_config.subaccount, &denom.to_string())
+            .unwrap()
+            .deposits
+            .total_balance
+            .into()
+    };
+    assert_eq!(orders_count(&helper), 10);
+
+    let update_ob_params =
+        |helper: &mut Helper, sender: &Addr, params: ConcentratedObPoolUpdateParams| {
+            helper.app.execute_contract(
+                sender.clone(),
+                helper.pair_addr.clone(),
+                &ExecuteMsg::UpdateConfig {
+                    params: to_json_binary(&params).unwrap(),
+                },
+                &[],
+            )
+        };
+    let ob_params = |orders_number: Option<u8>,
+                     min_trades_to_avg: Option<u32>,
+                     active: Option<bool>,
+                     market_id: Option<String>| {
+        ConcentratedObPoolUpdateParams::UpdateOrderbookParams {
+            orders_number,
+            min_trades_to_avg,
+            active,
+            market_id,
+        }
+    };
+
+    let err = update_ob_params(
+        &mut helper,
+        &generate_inj_address(),
+        ob_params(Some(3), None, None, None),
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    update_ob_params(&mut helper, &owner, ob_params(Some(0), None, None, None)).unwrap_err();
+
+    // The market must match pair assets
+    let reversed_market_id = calc_market_ids(&[
+        helper.assets[&test_coins[0]].clone(),
+        helper.assets[&test_coins[1]].clone(),
+    ])
+    .unwrap()[1]
+        .clone();
+    update_ob_params(
+        &mut helper,
+        &owner,
+        ob_params(None, None, None, Some(reversed_market_id)),
+    )
+    .unwrap_err();
+
+    // New orders number is applied on the next begin blocker
+    update_ob_params(&mut helper, &owner, ob_params(Some(3), None, None, None)).unwrap();
+    helper.next_block(true).unwrap();
+    assert_eq!(orders_count(&helper), 6);
+
+    // Deactivation cancels all orders and withdraws the subaccount liquidity
+    update_ob_params(
+        &mut helper,
+        &owner,
+        ob_params(None, None, Some(false), None),
+    )
+    .unwrap();
+    assert_eq!(orders_count(&helper), 0);
+    assert_eq!(subaccount_balance(&helper, "inj"), 0);
+    assert_eq!(subaccount_balance(&helper, "astro"), 0);
+
+    let ob_state = helper.query_ob_config_smart().unwrap();
+    assert!(ob_state.deactivated);
+    assert!(!ob_state.enabled);
+
+    // Swaps don't enable orderbook integration while it is deactivated
+    helper
+        .swap(
+            &owner,
+            &helper.assets[&test_coins[1]].with_balance((1000.0 * 1e6) as u128),
+            None,
+        )
+        .unwrap();
+    helper.next_block(true).unwrap();
+    assert_eq!(orders_count(&helper), 0);
+    assert!(!helper.query_ob_config_smart().unwrap().enabled);
+
+    // Reactivation places orders again on the next begin blocker
+    update_ob_params(&mut helper, &owner, ob_params(None, None, Some(true), None)).unwrap();
+    helper.next_block(true).unwrap();
+    assert_eq!(orders_count(&helper), 6);
+
+    // Lowering min trades to average keeps the pool ready
+    update_ob_params(&mut helper, &owner, ob_params(None, Some(2), None, None)).unwrap();
+    let ob_state = helper.query_ob_config_smart().unwrap();
+    assert_eq!(ob_state.min_trades_to_avg, 2);
+    assert!(ob_state.ready);
+}
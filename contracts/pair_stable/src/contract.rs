@@ -5,48 +5,69 @@ use std::vec;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, ensure_eq, from_json, to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal,
-    Decimal256, Deps, DepsMut, Empty, Env, Fraction, MessageInfo, QuerierWrapper, Reply, Response,
-    StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    attr, coin, coins, ensure, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, BankMsg,
+    Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Fraction, MessageInfo,
+    Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+    WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw_utils::{one_coin, PaymentError};
 use itertools::Itertools;
 
 use astroport::asset::{
-    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, CoinsExt, Decimal256Ext,
-    DecimalAsset, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, AssetInfoExt, CoinsExt,
+    Decimal256Ext, DecimalAsset, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
 };
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner, LP_SUBDENOM};
 use astroport::cosmwasm_ext::IntegerToDecimal;
-use astroport::observation::{query_observation, PrecommitObservation, OBSERVATIONS_SIZE};
+use astroport::events::{
+    FlashLoanEvent, FlashSwapEvent, ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent,
+};
+use astroport::factory::{FeeInfoResponse, QueryMsg as FactoryQueryMsg};
+use astroport::lp_pricing::{VirtualPriceResponse, VIRTUAL_PRICE_PRECISION};
+use astroport::observation::{
+    query_fee_apr, query_observation, query_observations_info, query_safe_price,
+    store_fee_observation, PrecommitObservation, OBSERVATIONS_SIZE,
+};
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, FeeShareConfig, InstantiateMsg, StablePoolParams,
-    StablePoolUpdateParams, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
-    MIN_TRADE_SIZE,
+    check_balance_caps, check_deadline, check_recipient_commitment, flash_loan_fee, load_lp_fees,
+    update_balance_caps, CircuitBreaker, ConfigResponse, CumulativePricesResponse, DynamicFees,
+    FeeShareCallbackMsg, FeeShareConfig, FlashLoanReceiverMsg, InstantiateMsg, PairCreationInfo,
+    StablePoolParams, StablePoolUpdateParams, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE,
+    MAX_DYNAMIC_FEE_BPS, MAX_FEE_SHARE_BPS, MAX_FLASH_LOAN_FEE_BPS, MIN_TRADE_SIZE,
 };
 use astroport::pair::{
-    Cw20HookMsg, ExecuteMsg, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
-    StablePoolConfig,
+    AccruedFeesResponse, Cw20HookMsg, ExecuteMsg, MigrationStatusResponse,
+    MinimumLiquidityResponse, PoolResponse, QueryMsg, ReverseSimulationResponse,
+    SimulationResponse, StablePoolConfig, COMMISSION_ASSET,
+};
+use astroport::querier::{
+    migration_check, query_factory_config, query_fee_info, query_flash_loans_enabled,
+    query_min_trade_notional, query_native_supply, query_pair_paused, FeeInfo,
 };
-use astroport::querier::{query_factory_config, query_fee_info, query_native_supply};
 use astroport::token_factory::{tf_burn_msg, tf_create_denom_msg, MsgCreateDenomResponse};
 use astroport::DecimalCheckedOps;
 use astroport_circular_buffer::BufferManager;
+use astroport_pair_common::liquidity::{
+    check_asset_infos, ensure_min_assets_to_receive, get_share_in_assets,
+    mint_liquidity_token_message, pool_info, resolve_receiver,
+};
 
 use crate::error::ContractError;
 use crate::math::{
     calc_y, compute_d, AMP_PRECISION, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME,
 };
 use crate::state::{
-    get_precision, store_precisions, Config, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL,
+    get_precision, store_precisions, Config, FlashLoanState, ACCRUED_FEES, BALANCE_CAPS,
+    CIRCUIT_BREAKER, CONFIG, CREATION_INFO, DYNAMIC_FEES, FEE_OBSERVATIONS, FLASH_LOAN,
+    FLASH_LOAN_FEE_BPS, LP_TOKEN_METADATA, MAKER_FEE_BPS, OBSERVATIONS, OWNERSHIP_PROPOSAL,
 };
 use crate::utils::{
-    accumulate_prices, accumulate_swap_sizes, adjust_precision, calculate_shares,
-    check_asset_infos, check_cw20_in_pool, compute_current_amp, compute_swap,
-    determine_base_quote_amount, get_assets_collection, get_share_in_assets,
-    mint_liquidity_token_message, select_pools, SwapResult,
+    accumulate_prices, accumulate_swap_sizes, adjust_precision, calculate_imbalanced_withdraw,
+    calculate_shares, check_cw20_in_pool, compute_current_amp, compute_swap,
+    determine_base_quote_amount, get_assets_collection, query_pools_decimal, select_pools,
+    swap_fee_rate, SwapResult,
 };
 
 /// Contract name that is used for migration.
@@ -57,6 +78,8 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const CREATE_DENOM_REPLY_ID: u64 = 1;
 /// Number of assets in the pool.
 const N_COINS: usize = 2;
+/// Maximum number of fee rate refinements in the reverse swap simulation with dynamic fees
+const MAX_FEE_ITERATIONS: u8 = 16;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -87,6 +110,11 @@ pub fn instantiate(
     let factory_addr = deps.api.addr_validate(&msg.factory_addr)?;
     let greatest_precision = store_precisions(deps.branch(), &msg.asset_infos, &factory_addr)?;
 
+    if let Some(caps) = params.balance_caps {
+        let caps = update_balance_caps(vec![], caps, &msg.asset_infos)?;
+        BALANCE_CAPS.save(deps.storage, &caps)?;
+    }
+
     // Initializing cumulative prices
     let mut cumulative_prices = vec![];
     for from_pool in &msg.asset_infos {
@@ -97,6 +125,9 @@ pub fn instantiate(
         }
     }
 
+    let min_trade_notional =
+        query_min_trade_notional(&deps.querier, &factory_addr, &msg.pair_type)?;
+
     let config = Config {
         owner: addr_opt_validate(deps.api, &params.owner)?,
         pair_info: PairInfo {
@@ -115,11 +146,18 @@ pub fn instantiate(
         cumulative_prices,
         fee_share: None,
         tracker_addr: None,
+        min_trade_notional,
     };
 
     CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
     BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
 
+    if let Some(lp_token_metadata) = params.lp_token_metadata {
+        lp_token_metadata.validate()?;
+        LP_TOKEN_METADATA.save(deps.storage, &lp_token_metadata)?;
+    }
+
     // Create LP token
     let sub_msg = SubMsg::reply_on_success(
         tf_create_denom_msg(env.contract.address.to_string(), LP_SUBDENOM),
@@ -131,7 +169,7 @@ pub fn instantiate(
 
 /// The entry point to the contract for processing replies from submessages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: CREATE_DENOM_REPLY_ID,
@@ -152,7 +190,16 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 Ok(config)
             })?;
 
-            Ok(Response::new().add_attribute("lp_denom", new_token_denom))
+            let mut response = Response::new();
+            if let Some(lp_token_metadata) = LP_TOKEN_METADATA.may_load(deps.storage)? {
+                LP_TOKEN_METADATA.remove(deps.storage);
+                response = response.add_message(
+                    lp_token_metadata
+                        .into_denom_metadata_msg(env.contract.address, &new_token_denom),
+                );
+            }
+
+            Ok(response.add_attribute("lp_denom", new_token_denom))
         }
         _ => Err(ContractError::FailedToParseReply {}),
     }
@@ -173,6 +220,8 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             auto_stake,
 ///             receiver,
 ///            min_lp_to_receive,
+///            staking_target,
+///            deadline,
 ///         }** Provides liquidity in the pair using the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
@@ -180,11 +229,24 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             belief_price,
 ///             max_spread,
 ///             to,
+///             deadline,
 ///         }** Performs an swap using the specified parameters.
 /// * **ExecuteMsg::WithdrawLiquidity {
 ///            assets,
 ///           min_assets_to_receive,
+///           deadline,
 ///       }** Withdraws liquidity from the pool.
+///
+/// * **ExecuteMsg::FlashLoan { asset, msg }** Lends a pool asset to the sender within a single transaction.
+///
+/// * **ExecuteMsg::FlashSwap { ask_asset, offer_asset_info, msg }** Sends the ask asset to the sender
+/// which pays for it within a single transaction.
+///
+/// * **ExecuteMsg::FinalizeFlashLoan {}** Verifies the flash loan repayment. Callable only by the pair itself.
+///
+/// * **ExecuteMsg::HaltPair {}** Halts swaps and liquidity provision. Callable by the circuit breaker guardian.
+///
+/// * **ExecuteMsg::ResumePair {}** Resumes the pair after it was halted.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -192,6 +254,11 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Pool reserves are not consistent until the ongoing flash loan is repaid
+    if FLASH_LOAN.exists(deps.storage) && !matches!(msg, ExecuteMsg::FinalizeFlashLoan {}) {
+        return Err(ContractError::FlashLoanInProgress {});
+    }
+
     match msg {
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
@@ -200,24 +267,35 @@ pub fn execute(
             auto_stake,
             receiver,
             min_lp_to_receive,
+            staking_target,
+            deadline,
             ..
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            auto_stake,
-            receiver,
-            min_lp_to_receive,
-        ),
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+                staking_target,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             ask_asset_info,
             belief_price,
             max_spread,
             to,
-            ..
+            deadline,
+            to_commitment,
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -241,13 +319,20 @@ pub fn execute(
             let cfg = CONFIG.load(deps.storage)?;
             let factory_config = query_factory_config(&deps.querier, cfg.factory_addr.clone())?;
 
+            // The factory propagates its owner to pairs which keep their own owner
+            let current_owner = match cfg.owner {
+                Some(_) if info.sender == cfg.factory_addr => cfg.factory_addr,
+                Some(owner) => owner,
+                None => factory_config.owner,
+            };
+
             propose_new_owner(
                 deps,
                 info,
                 env,
                 owner,
                 expires_in,
-                cfg.owner.unwrap_or(factory_config.owner),
+                current_owner,
                 OWNERSHIP_PROPOSAL,
             )
             .map_err(|e| e.into())
@@ -278,7 +363,28 @@ pub fn execute(
         ExecuteMsg::WithdrawLiquidity {
             assets,
             min_assets_to_receive,
-        } => withdraw_liquidity(deps, env, info, assets, min_assets_to_receive),
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets, min_assets_to_receive)
+        }
+        ExecuteMsg::FlashLoan { asset, msg } => flash_loan(deps, env, info, asset, msg),
+        ExecuteMsg::FlashSwap {
+            ask_asset,
+            offer_asset_info,
+            msg,
+        } => flash_swap(deps, env, info, ask_asset, offer_asset_info, msg),
+        ExecuteMsg::FinalizeFlashLoan {} => finalize_flash_loan(deps, env, info),
+        ExecuteMsg::Poke {} => Err(StdError::generic_err("Poke is not supported").into()),
+        ExecuteMsg::HaltPair {} => halt_pair(deps, env, info),
+        ExecuteMsg::ResumePair {} => resume_pair(deps, info),
+        ExecuteMsg::SettleBatch {} | ExecuteMsg::ClaimBatchPayouts {} => {
+            Err(StdError::generic_err("Batch swaps are not supported").into())
+        }
+        ExecuteMsg::ClaimLentRefunds {} => {
+            Err(StdError::generic_err("Lending is not supported").into())
+        }
     }
 }
 
@@ -297,7 +403,12 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             let config = CONFIG.load(deps.storage)?;
 
             // Only asset contract can execute this message
@@ -333,6 +444,9 @@ pub fn receive_cw20(
 /// * **receiver** address that receives LP tokens. If this address isn't specified, the function will default to the caller.
 ///
 /// * **min_lp_to_receive** is an optional parameter which specifies the minimum amount of LP tokens to receive.
+///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+/// It must be registered in the factory; the factory default is used if not set.
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
 pub fn provide_liquidity(
     deps: DepsMut,
@@ -342,9 +456,15 @@ pub fn provide_liquidity(
     auto_stake: Option<bool>,
     receiver: Option<String>,
     min_lp_to_receive: Option<Uint128>,
+    staking_target: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let pools = config
         .pair_info
         .query_pools(&deps.querier, &env.contract.address)?
@@ -387,6 +507,21 @@ pub fn provide_liquidity(
         }
     }
 
+    let new_balances = assets_collection
+        .iter()
+        .map(|(deposit, pool)| Ok(deposit.info.with_balance(pool.checked_add(deposit.amount)?)))
+        .collect::<StdResult<Vec<_>>>()?;
+    check_balance_caps(
+        &BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+        &new_balances,
+    )?;
+
+    let old_balances = assets_collection
+        .iter()
+        .map(|(deposit, pool)| deposit.info.with_balance(*pool))
+        .collect_vec();
+    check_circuit_breaker(deps.storage, &old_balances, &new_balances)?;
+
     let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
     let auto_stake = auto_stake.unwrap_or(false);
@@ -396,11 +531,13 @@ pub fn provide_liquidity(
     if total_share.is_zero() {
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.factory_addr,
+            &config.pair_info.liquidity_token,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
             false,
+            None,
         )?);
     }
 
@@ -414,14 +551,16 @@ pub fn provide_liquidity(
     }
 
     // Mint LP token for the caller (or for the receiver if it was set)
-    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let receiver = resolve_receiver(deps.api, &receiver, &info.sender)?;
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.factory_addr,
+        &config.pair_info.liquidity_token,
         &env.contract.address,
         &receiver,
         share,
         auto_stake,
+        staking_target.as_deref(),
     )?);
 
     let pools = pools
@@ -439,16 +578,23 @@ pub fn provide_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", assets.iter().join(", ")),
-        attr("share", share),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        ProvideLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            receiver,
+            assets,
+            share,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Withdraw liquidity from the pool.
+///
+/// * **assets** exact amounts of assets to withdraw. If empty, the withdrawal is balanced
+/// and all sent LP tokens are burned. Otherwise, only the required amount of LP tokens is burned
+/// and the rest is returned to the sender.
 pub fn withdraw_liquidity(
     deps: DepsMut,
     env: Env,
@@ -466,27 +612,92 @@ pub fn withdraw_liquidity(
         PaymentError::MissingDenom(config.pair_info.liquidity_token.to_string())
     );
 
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
-    let refund_assets = if assets.is_empty() {
+    let (refund_assets, burn_amount) = if assets.is_empty() {
         // Usual withdraw (balanced)
-        get_share_in_assets(&pools, amount, total_share)
+        (get_share_in_assets(&pools, amount, total_share), amount)
     } else {
-        return Err(StdError::generic_err("Imbalanced withdraw is currently disabled").into());
+        // Imbalanced withdraw of exact asset amounts
+        let fee_info = query_fee_info(
+            &deps.querier,
+            &config.factory_addr,
+            config.pair_info.pair_type.clone(),
+        )?;
+        let burn_amount = calculate_imbalanced_withdraw(
+            deps.as_ref(),
+            &env,
+            &config,
+            &pools,
+            &assets,
+            total_share,
+            fee_info.total_fee_rate,
+        )?;
+        ensure!(
+            burn_amount <= amount,
+            ContractError::NotEnoughLpTokens(burn_amount)
+        );
+
+        let refund_assets = pools
+            .iter()
+            .map(|pool| {
+                let amount = assets
+                    .iter()
+                    .find(|asset| asset.info == pool.info)
+                    .map(|asset| asset.amount)
+                    .unwrap_or_default();
+                pool.info.with_balance(amount)
+            })
+            .collect_vec();
+
+        let new_reserves = pools
+            .iter()
+            .zip(&refund_assets)
+            .map(|(pool, refund)| {
+                Ok(pool
+                    .info
+                    .with_balance(pool.amount.checked_sub(refund.amount)?))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        check_withdrawal_circuit_breaker(deps.storage, &pools, &new_reserves)?;
+
+        (refund_assets, burn_amount)
     };
 
-    ensure_min_assets_to_receive(&config, refund_assets.clone(), min_assets_to_receive)?;
+    ensure_min_assets_to_receive(
+        &config.pair_info.asset_infos,
+        refund_assets.clone(),
+        min_assets_to_receive,
+    )?;
 
     let mut messages = refund_assets
-        .clone()
-        .into_iter()
-        .map(|asset| asset.into_msg(&info.sender))
+        .iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .map(|asset| asset.clone().into_msg(&info.sender))
         .collect::<StdResult<Vec<_>>>()?;
     messages.push(tf_burn_msg(
         env.contract.address.to_string(),
-        coin(amount.u128(), config.pair_info.liquidity_token.to_string()),
+        coin(
+            burn_amount.u128(),
+            config.pair_info.liquidity_token.to_string(),
+        ),
     ));
 
+    // Return unused LP tokens back to the sender
+    let unused_amount = amount - burn_amount;
+    if !unused_amount.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(
+                    unused_amount.u128(),
+                    config.pair_info.liquidity_token.to_string(),
+                ),
+            }
+            .into(),
+        );
+    }
+
     let pools = pools
         .iter()
         .map(|pool| {
@@ -499,12 +710,15 @@ pub fn withdraw_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("sender", info.sender),
-        attr("withdrawn_share", amount),
-        attr("refund_assets", refund_assets.iter().join(", ")),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        WithdrawLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            withdrawn_share: burn_amount,
+            refund_assets,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Performs an swap operation with the specified parameters.
@@ -533,6 +747,11 @@ pub fn swap(
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     // If the asset balance already increased
     // We should subtract the user deposit from the pool offer asset amount
     let pools = config
@@ -541,13 +760,9 @@ pub fn swap(
         .into_iter()
         .map(|mut pool| {
             if pool.info.equal(&offer_asset.info) {
-                pool.amount = pool.amount.checked_sub(offer_asset.amount)?;
+                pool = pool.checked_sub(&offer_asset)?;
             }
-            let token_precision = get_precision(deps.storage, &pool.info)?;
-            Ok(DecimalAsset {
-                info: pool.info,
-                amount: Decimal256::with_precision(pool.amount, token_precision)?,
-            })
+            pool.to_decimal_asset(get_precision(deps.storage, &pool.info)?)
         })
         .collect::<StdResult<Vec<_>>>()?;
 
@@ -556,20 +771,34 @@ pub fn swap(
 
     let offer_precision = get_precision(deps.storage, &offer_pool.info)?;
 
+    let reserves = pools
+        .iter()
+        .map(|pool| {
+            let amount = pool
+                .amount
+                .to_uint128_with_precision(get_precision(deps.storage, &pool.info)?)?;
+            Ok(pool.info.with_balance(amount))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
     // Check if the liquidity is non-zero
     check_swap_parameters(
-        pools
-            .iter()
-            .map(|pool| {
-                pool.amount
-                    .to_uint128_with_precision(get_precision(deps.storage, &pool.info)?)
-            })
-            .collect::<StdResult<Vec<Uint128>>>()?,
+        reserves.iter().map(|reserve| reserve.amount).collect(),
         offer_asset.amount,
     )?;
 
     let offer_asset_dec = offer_asset.to_decimal_asset(offer_precision)?;
 
+    if let Some(min) = config.min_trade_notional {
+        ensure!(
+            offer_asset_dec.amount >= Decimal256::from(min),
+            ContractError::TradeTooSmall {
+                min,
+                notional: offer_asset_dec.amount,
+            }
+        );
+    }
+
     let SwapResult {
         return_amount,
         spread_amount,
@@ -589,7 +818,17 @@ pub fn swap(
         &config.factory_addr,
         config.pair_info.pair_type.clone(),
     )?;
-    let commission_amount = fee_info.total_fee_rate.checked_mul_uint128(return_amount)?;
+    let fee_rate = swap_fee_rate(
+        deps.storage,
+        fee_info.total_fee_rate,
+        &offer_asset_dec,
+        &ask_pool
+            .info
+            .with_balance(return_amount)
+            .to_decimal_asset(get_precision(deps.storage, &ask_pool.info)?)?,
+        &pools,
+    )?;
+    let commission_amount = fee_rate.checked_mul_uint128(return_amount)?;
     let return_amount = return_amount.saturating_sub(commission_amount);
 
     // Check the max spread limit (if it was specified)
@@ -613,43 +852,32 @@ pub fn swap(
         messages.push(return_asset.into_msg(receiver.clone())?)
     }
 
-    // If this pool is configured to share fees, calculate the amount to send
-    // to the receiver and add the transfer message
-    // The calculation works as follows: We take the share percentage first,
-    // and the remainder is then split between LPs and maker
-    let mut fees_commission_amount = commission_amount;
-    let mut fee_share_amount = Uint128::zero();
-    if let Some(ref fee_share) = config.fee_share {
-        // Calculate the fee share amount from the full commission amount
-        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
-        fee_share_amount = fees_commission_amount * share_fee_rate;
-
-        if !fee_share_amount.is_zero() {
-            // Subtract the fee share amount from the commission
-            fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
-
-            // Build send message for the shared amount
-            let fee_share_msg = Asset {
-                info: ask_pool.info.clone(),
-                amount: fee_share_amount,
-            }
-            .into_msg(&fee_share.recipient)?;
-            messages.push(fee_share_msg);
-        }
-    }
+    let (fee_share_amount, maker_fee_amount, fee_messages) = charge_commission(
+        deps.storage,
+        &env,
+        &config,
+        &fee_info,
+        &ask_pool.info,
+        commission_amount,
+    )?;
+    messages.extend(fee_messages);
 
-    // Compute the Maker fee
-    let mut maker_fee_amount = Uint128::zero();
-    if let Some(fee_address) = fee_info.fee_address {
-        if let Some(f) = calculate_maker_fee(
-            &ask_pool.info,
-            fees_commission_amount,
-            fee_info.maker_fee_rate,
-        ) {
-            maker_fee_amount = f.amount;
-            messages.push(f.into_msg(fee_address)?);
-        }
-    }
+    let new_reserves = reserves
+        .iter()
+        .map(|reserve| {
+            let amount = if reserve.info == offer_pool.info {
+                reserve.amount.checked_add(offer_asset.amount)?
+            } else if reserve.info == ask_pool.info {
+                reserve
+                    .amount
+                    .checked_sub(return_amount + maker_fee_amount + fee_share_amount)?
+            } else {
+                reserve.amount
+            };
+            Ok(reserve.info.with_balance(amount))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    check_circuit_breaker(deps.storage, &reserves, &new_reserves)?;
 
     if accumulate_prices(deps.storage, &env, &mut config, &pools)? {
         CONFIG.save(deps.storage, &config)?;
@@ -677,19 +905,98 @@ pub fn swap(
             // 2. send inactive commission fees to the Maker contract
             messages,
         )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
-            attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_asset.amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("fee_share_amount", fee_share_amount),
-        ]))
+        .add_attributes(
+            SwapEvent {
+                pool: config.pair_info.contract_addr,
+                sender,
+                receiver,
+                offer_asset,
+                return_asset: ask_pool.info.with_balance(return_amount),
+                spread_amount,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Splits the swap commission between the fee share recipient, the Maker and liquidity providers
+/// and records it in the accrued fees.
+/// Returns the fee share and Maker fee amounts along with the messages sending them out.
+///
+/// * **ask_info** is the asset in which the commission is charged.
+fn charge_commission(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    fee_info: &FeeInfo,
+    ask_info: &AssetInfo,
+    commission_amount: Uint128,
+) -> Result<(Uint128, Uint128, Vec<CosmosMsg>), ContractError> {
+    let mut messages = vec![];
+
+    // If this pool is configured to share fees, calculate the amount to send
+    // to the receiver and add the transfer message
+    // The calculation works as follows: We take the share percentage first,
+    // and the remainder is then split between LPs and maker
+    let mut fees_commission_amount = commission_amount;
+    let mut fee_share_amount = Uint128::zero();
+    if let Some(ref fee_share) = config.fee_share {
+        // Calculate the fee share amount from the full commission amount
+        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
+        fee_share_amount = fees_commission_amount * share_fee_rate;
+
+        if !fee_share_amount.is_zero() {
+            // Subtract the fee share amount from the commission
+            fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
+
+            // Build send message for the shared amount
+            let fee = ask_info.with_balance(fee_share_amount);
+            messages.push(fee.clone().into_msg(&fee_share.recipient)?);
+            if fee_share.callback {
+                messages.push(
+                    wasm_execute(
+                        &fee_share.recipient,
+                        &FeeShareCallbackMsg::FeeShareReceived { asset: fee },
+                        vec![],
+                    )?
+                    .into(),
+                );
+            }
+        }
+    }
+
+    // The pool may route a smaller part of the commission to the Maker
+    let maker_fee_rate = match MAKER_FEE_BPS.may_load(storage)? {
+        Some(bps) => fee_info
+            .maker_fee_rate
+            .min(Decimal::from_ratio(bps, 10000u16)),
+        None => fee_info.maker_fee_rate,
+    };
+
+    // Compute the Maker fee
+    let mut maker_fee_amount = Uint128::zero();
+    if let Some(fee_address) = &fee_info.fee_address {
+        if let Some(f) = calculate_maker_fee(ask_info, fees_commission_amount, maker_fee_rate) {
+            maker_fee_amount = f.amount;
+            messages.push(f.into_msg(fee_address)?);
+        }
+    }
+
+    store_fee_observation(
+        storage,
+        env,
+        FEE_OBSERVATIONS,
+        load_lp_fees(storage, ACCRUED_FEES, &config.pair_info.asset_infos)?,
+    )?;
+    let mut accrued_fees = ACCRUED_FEES
+        .may_load(storage, ask_info)?
+        .unwrap_or_default();
+    accrued_fees.add(commission_amount, maker_fee_amount, fee_share_amount);
+    ACCRUED_FEES.save(storage, ask_info, &accrued_fees)?;
+
+    Ok((fee_share_amount, maker_fee_amount, messages))
 }
 
 /// Calculates the amount of fees the Maker contract gets according to specified pair parameters.
@@ -738,8 +1045,27 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::SimulateWithdraw { lp_amount }** Returns the amount of assets that could be withdrawn from the pool
 /// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
 /// * **QueryMsg::SimulateProvide { msg }** Simulates the liquidity provision in the pair contract.
+///
+/// * **QueryMsg::MinimumLiquidity {}** Returns the amount of LP tokens locked in the pair on the first provision.
+///
+/// * **QueryMsg::CommissionAsset {}** Returns the asset in which the pair charges its commission.
+///
+/// * **QueryMsg::MigrationStatus {}** Returns whether the factory has scheduled a code migration of the pair.
+///
+/// * **QueryMsg::AccruedFees {}** Returns swap fees collected in each pool asset since inception.
+///
+/// * **QueryMsg::FeeApr { window }** Returns the LP fee APR estimated over the last `window` seconds.
+///
+/// * **QueryMsg::VirtualPrice {}** Returns the pool invariant D per LP token.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    // The borrowed amount is out of the pool until the flash loan is repaid
+    if FLASH_LOAN.exists(deps.storage) && msg.depends_on_reserves() {
+        return Err(StdError::generic_err(
+            ContractError::FlashLoanInProgress {}.to_string(),
+        ));
+    }
+
     match msg {
         QueryMsg::Pair {} => to_json_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Pool {} => to_json_binary(&query_pool(deps)?),
@@ -761,6 +1087,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Observe { seconds_ago } => {
             to_json_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
         }
+        QueryMsg::SafePrice { window } => {
+            to_json_binary(&query_safe_price(deps, env, OBSERVATIONS, window)?)
+        }
+        QueryMsg::ObservationsInfo {} => {
+            to_json_binary(&query_observations_info(deps, env, OBSERVATIONS)?)
+        }
         QueryMsg::Config {} => to_json_binary(&query_config(deps, env)?),
         QueryMsg::SimulateWithdraw { lp_amount } => to_json_binary(&query_share(deps, lp_amount)?),
         QueryMsg::SimulateProvide { assets, .. } => to_json_binary(
@@ -768,15 +1100,68 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .map_err(|e| StdError::generic_err(e.to_string()))?,
         ),
         QueryMsg::QueryComputeD {} => to_json_binary(&query_compute_d(deps, env)?),
+        QueryMsg::VirtualPrice {} => to_json_binary(&query_virtual_price(deps, env)?),
+        QueryMsg::MinimumLiquidity {} => {
+            to_json_binary(&MinimumLiquidityResponse::new(env.contract.address))
+        }
+        QueryMsg::CommissionAsset {} => to_json_binary(&COMMISSION_ASSET),
+        QueryMsg::MigrationStatus {} => to_json_binary(&query_migration_status(deps, env)?),
+        QueryMsg::AccruedFees {} => to_json_binary(&query_accrued_fees(deps)?),
+        QueryMsg::FeeApr { window } => {
+            let config = CONFIG.load(deps.storage)?;
+            let lp_fees = load_lp_fees(deps.storage, ACCRUED_FEES, &config.pair_info.asset_infos)?;
+            let reserves = config
+                .pair_info
+                .query_pools(&deps.querier, &env.contract.address)?;
+
+            to_json_binary(&query_fee_apr(
+                deps,
+                env,
+                FEE_OBSERVATIONS,
+                lp_fees,
+                reserves,
+                window,
+            )?)
+        }
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
 
+/// Returns swap fees collected in each pool asset. Assets which were never swapped to have zero fees.
+pub fn query_accrued_fees(deps: Deps) -> StdResult<Vec<AccruedFeesResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    config
+        .pair_info
+        .asset_infos
+        .into_iter()
+        .map(|asset_info| {
+            let fees = ACCRUED_FEES
+                .may_load(deps.storage, &asset_info)?
+                .unwrap_or_default();
+            Ok(AccruedFeesResponse { asset_info, fees })
+        })
+        .collect()
+}
+
+/// Returns whether the factory has scheduled a code migration of the pair.
+pub fn query_migration_status(deps: Deps, env: Env) -> StdResult<MigrationStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(MigrationStatusResponse {
+        migration_scheduled: migration_check(
+            &deps.querier,
+            &config.factory_addr,
+            &env.contract.address,
+        )?,
+    })
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     let resp = PoolResponse {
         assets,
@@ -792,7 +1177,7 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
 /// * **amount** is the amount of LP tokens for which we calculate associated amounts of assets.
 pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     let config = CONFIG.load(deps.storage)?;
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, &config.pair_info)?;
     let refund_assets = get_share_in_assets(&pools, amount, total_share);
 
     Ok(refund_assets)
@@ -808,11 +1193,7 @@ pub fn query_simulation(
     ask_asset_info: Option<AssetInfo>,
 ) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let pools = config.pair_info.query_pools_decimal(
-        &deps.querier,
-        &config.pair_info.contract_addr,
-        &config.factory_addr,
-    )?;
+    let pools = query_pools_decimal(deps, &config, &config.pair_info.contract_addr)?;
 
     let (offer_pool, ask_pool) =
         select_pools(Some(&offer_asset.info), ask_asset_info.as_ref(), &pools)
@@ -839,6 +1220,8 @@ pub fn query_simulation(
         });
     }
 
+    let offer_asset_dec = offer_asset.to_decimal_asset(offer_precision)?;
+
     let SwapResult {
         return_amount,
         spread_amount,
@@ -846,7 +1229,7 @@ pub fn query_simulation(
         deps.storage,
         &env,
         &config,
-        &offer_asset.to_decimal_asset(offer_precision)?,
+        &offer_asset_dec,
         &offer_pool,
         &ask_pool,
         &pools,
@@ -859,8 +1242,18 @@ pub fn query_simulation(
         &config.factory_addr,
         config.pair_info.pair_type.clone(),
     )?;
+    let fee_rate = swap_fee_rate(
+        deps.storage,
+        fee_info.total_fee_rate,
+        &offer_asset_dec,
+        &ask_pool
+            .info
+            .with_balance(return_amount)
+            .to_decimal_asset(get_precision(deps.storage, &ask_pool.info)?)?,
+        &pools,
+    )?;
 
-    let commission_amount = fee_info.total_fee_rate.checked_mul_uint128(return_amount)?;
+    let commission_amount = fee_rate.checked_mul_uint128(return_amount)?;
     let return_amount = return_amount.saturating_sub(commission_amount);
 
     Ok(SimulationResponse {
@@ -884,11 +1277,7 @@ pub fn query_reverse_simulation(
     offer_asset_info: Option<AssetInfo>,
 ) -> StdResult<ReverseSimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let pools = config.pair_info.query_pools_decimal(
-        &deps.querier,
-        &config.pair_info.contract_addr,
-        &config.factory_addr,
-    )?;
+    let pools = query_pools_decimal(deps, &config, &config.pair_info.contract_addr)?;
     let (offer_pool, ask_pool) =
         select_pools(offer_asset_info.as_ref(), Some(&ask_asset.info), &pools)
             .map_err(|err| StdError::generic_err(format!("{err}")))?;
@@ -922,41 +1311,85 @@ pub fn query_reverse_simulation(
         &config.factory_addr,
         config.pair_info.pair_type.clone(),
     )?;
-    let before_commission = (Decimal256::one()
-        - Decimal256::new(fee_info.total_fee_rate.atomics().into()))
-    .inv()
-    .ok_or_else(|| StdError::generic_err("The pool must have less than 100% fee!"))?
-    .checked_mul(Decimal256::with_precision(ask_asset.amount, ask_precision)?)?;
-
-    let xp = pools.into_iter().map(|pool| pool.amount).collect_vec();
-    let new_offer_pool_amount = calc_y(
-        compute_current_amp(&config, &env)?,
-        ask_pool.amount - before_commission,
-        &xp,
-        config.greatest_precision,
-    )?;
+    let ask_amount = Decimal256::with_precision(ask_asset.amount, ask_precision)?;
+    let amp = compute_current_amp(&config, &env)?;
+    let xp = pools.iter().map(|pool| pool.amount).collect_vec();
+    let reverse_swap = |fee_rate: Decimal| -> StdResult<(Decimal256, Uint128)> {
+        let before_commission = (Decimal256::one() - Decimal256::new(fee_rate.atomics().into()))
+            .inv()
+            .ok_or_else(|| StdError::generic_err("The pool must have less than 100% fee!"))?
+            .checked_mul(ask_amount)?;
+        let new_offer_pool_amount = calc_y(
+            amp,
+            ask_pool.amount - before_commission,
+            &xp,
+            config.greatest_precision,
+        )?;
+
+        Ok((before_commission, new_offer_pool_amount))
+    };
 
-    let offer_amount = new_offer_pool_amount.checked_sub(
+    // Dynamic fees depend on the swap size which in turn depends on the fee rate.
+    // Start from the factory fee and iterate until the fee rate settles so the result matches the forward swap.
+    let mut fee_rate = fee_info.total_fee_rate;
+    let (mut before_commission, mut new_offer_pool_amount) = reverse_swap(fee_rate)?;
+    for _ in 0..MAX_FEE_ITERATIONS {
+        let next_fee_rate = swap_fee_rate(
+            deps.storage,
+            fee_info.total_fee_rate,
+            &DecimalAsset {
+                info: offer_pool.info.clone(),
+                amount: Decimal256::with_precision(
+                    new_offer_pool_amount,
+                    config.greatest_precision,
+                )?
+                .saturating_sub(offer_pool.amount),
+            },
+            &DecimalAsset {
+                info: ask_pool.info.clone(),
+                amount: before_commission,
+            },
+            &pools,
+        )?;
+        if next_fee_rate == fee_rate {
+            break;
+        }
+
+        fee_rate = next_fee_rate;
+        (before_commission, new_offer_pool_amount) = reverse_swap(fee_rate)?;
+    }
+
+    let precise_offer_amount = new_offer_pool_amount.checked_sub(
         offer_pool
             .amount
             .to_uint128_with_precision(config.greatest_precision)?,
     )?;
-    let offer_amount = adjust_precision(offer_amount, config.greatest_precision, offer_precision)?;
+    let mut offer_amount = adjust_precision(
+        precise_offer_amount,
+        config.greatest_precision,
+        offer_precision,
+    )?;
+    // Round up so that a forward swap of the returned offer amount yields at least the ask amount
+    if adjust_precision(offer_amount, offer_precision, config.greatest_precision)?
+        < precise_offer_amount
+    {
+        offer_amount += Uint128::one();
+    }
 
+    // Spread and commission are denominated in the ask asset as in forward swap simulation
+    let before_commission = before_commission.to_uint128_with_precision(ask_precision)?;
     Ok(ReverseSimulationResponse {
         offer_amount,
-        spread_amount: offer_amount
-            .saturating_sub(before_commission.to_uint128_with_precision(offer_precision)?),
-        commission_amount: fee_info
-            .total_fee_rate
-            .checked_mul_uint128(before_commission.to_uint128_with_precision(ask_precision)?)?,
+        spread_amount: adjust_precision(offer_amount, offer_precision, ask_precision)?
+            .saturating_sub(before_commission),
+        commission_amount: fee_rate.checked_mul_uint128(before_commission)?,
     })
 }
 
 /// Returns information about cumulative prices for the assets in the pool using a [`CumulativePricesResponse`] object.
 pub fn query_cumulative_prices(deps: Deps, env: Env) -> StdResult<CumulativePricesResponse> {
     let mut config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
     let decimal_assets = assets
         .iter()
         .cloned()
@@ -985,10 +1418,17 @@ pub fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
         params: Some(to_json_binary(&StablePoolConfig {
             amp: Decimal::from_ratio(compute_current_amp(&config, &env)?, AMP_PRECISION),
             fee_share: config.fee_share,
+            flash_loan_fee_bps: FLASH_LOAN_FEE_BPS.may_load(deps.storage)?,
+            balance_caps: BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+            maker_fee_bps: MAKER_FEE_BPS.may_load(deps.storage)?,
+            circuit_breaker: CIRCUIT_BREAKER.may_load(deps.storage)?,
+            dynamic_fees: DYNAMIC_FEES.may_load(deps.storage)?,
         })?),
         owner: config.owner.unwrap_or(factory_config.owner),
         factory_addr: config.factory_addr,
         tracker_addr: config.tracker_addr,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
     })
 }
 
@@ -1043,25 +1483,324 @@ pub fn assert_max_spread(
 
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    unimplemented!("No safe path available for migration from cw20 to tokenfactory LP tokens")
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    // There is no safe path for migration from cw20 to tokenfactory LP tokens
+    match contract_version.contract.as_ref() {
+        "astroport-pair-stable" => match contract_version.version.as_ref() {
+            "4.1.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
+}
+
+/// Lends a pool asset to the sender and schedules the callback into the borrower
+/// followed by the repayment check.
+///
+/// * **asset** is the borrowed asset.
+///
+/// * **msg** is passed to the borrower's callback as is.
+pub fn flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let fee_bps = FLASH_LOAN_FEE_BPS
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FlashLoansDisabled {})?;
+    if !query_flash_loans_enabled(&deps.querier, &config.factory_addr)? {
+        return Err(ContractError::FlashLoansDisabled {});
+    }
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+    // Loans are repaid to the same reserves thus the circuit breaker isn't checked on finalization
+    ensure!(
+        CIRCUIT_BREAKER
+            .may_load(deps.storage)?
+            .and_then(|circuit_breaker| circuit_breaker.tripped_at)
+            .is_none(),
+        ContractError::PairHalted {}
+    );
+
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let reserve = pools
+        .iter()
+        .find(|pool| pool.info.equal(&asset.info))
+        .ok_or(ContractError::AssetMismatch {})?;
+    if asset.amount.is_zero() || asset.amount >= reserve.amount {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let fee = flash_loan_fee(asset.amount, fee_bps)?;
+    if fee.is_zero() {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    // Prices are accumulated with the reserves before the loan
+    let decimal_pools = pools
+        .iter()
+        .map(|pool| {
+            let precision = get_precision(deps.storage, &pool.info)?;
+            pool.to_decimal_asset(precision)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if accumulate_prices(deps.storage, &env, &mut config, &decimal_pools)? {
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    FLASH_LOAN.save(
+        deps.storage,
+        &FlashLoanState {
+            loan: asset.clone(),
+            repayment: asset.info.with_balance(fee),
+            balance_before: reserve.amount,
+            fee,
+            reserves_before: vec![],
+        },
+    )?;
+
+    let callback_msg = wasm_execute(
+        &info.sender,
+        &FlashLoanReceiverMsg::FlashLoanCallback {
+            loan: asset.clone(),
+            fee: asset.info.with_balance(fee),
+            msg,
+        },
+        vec![],
+    )?;
+    let finalize_msg = wasm_execute(
+        &env.contract.address,
+        &ExecuteMsg::FinalizeFlashLoan {},
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(asset.clone().into_msg(&info.sender)?)
+        .add_message(callback_msg)
+        .add_message(finalize_msg)
+        .add_attributes(
+            FlashLoanEvent {
+                pool: config.pair_info.contract_addr,
+                borrower: info.sender,
+                loan: asset,
+                fee_amount: fee,
+            }
+            .into_attributes(),
+        ))
 }
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub fn pool_info(querier: QuerierWrapper, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
+/// Sends the ask asset to the sender and schedules the callback into the borrower
+/// followed by the payment check. The swap is priced as a regular one plus the flash loan fee.
+///
+/// * **ask_asset** is the asset received before paying for it.
+///
+/// * **offer_asset_info** is the asset used to pay for the swap.
+///
+/// * **msg** is passed to the borrower's callback as is.
+pub fn flash_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset: Asset,
+    offer_asset_info: AssetInfo,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let fee_bps = FLASH_LOAN_FEE_BPS
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FlashLoansDisabled {})?;
+    if !query_flash_loans_enabled(&deps.querier, &config.factory_addr)? {
+        return Err(ContractError::FlashLoansDisabled {});
+    }
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let pools = config
         .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
-    let total_share = query_native_supply(&querier, &config.pair_info.liquidity_token)?;
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let offer_pool = pools
+        .iter()
+        .find(|pool| pool.info.equal(&offer_asset_info))
+        .ok_or(ContractError::AssetMismatch {})?;
+    let ask_pool = pools
+        .iter()
+        .find(|pool| pool.info.equal(&ask_asset.info))
+        .ok_or(ContractError::AssetMismatch {})?;
+    if offer_pool.info.equal(&ask_pool.info) {
+        return Err(ContractError::SameAssets {});
+    }
+    if ask_asset.amount.is_zero() || ask_asset.amount >= ask_pool.amount {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let ReverseSimulationResponse {
+        offer_amount,
+        commission_amount,
+        ..
+    } = query_reverse_simulation(
+        deps.as_ref(),
+        env.clone(),
+        ask_asset.clone(),
+        Some(offer_asset_info.clone()),
+    )?;
+    let fee = flash_loan_fee(offer_amount, fee_bps)?;
+    if offer_amount.is_zero() || fee.is_zero() {
+        return Err(ContractError::InvalidFlashLoanAmount {});
+    }
+
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let (fee_share_amount, maker_fee_amount, fee_messages) = charge_commission(
+        deps.storage,
+        &env,
+        &config,
+        &fee_info,
+        &ask_pool.info,
+        commission_amount,
+    )?;
+
+    // Prices are accumulated with the reserves before the swap
+    let decimal_pools = pools
+        .iter()
+        .map(|pool| {
+            let precision = get_precision(deps.storage, &pool.info)?;
+            pool.to_decimal_asset(precision)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if accumulate_prices(deps.storage, &env, &mut config, &decimal_pools)? {
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let repayment = offer_asset_info.with_balance(offer_amount + fee);
+    FLASH_LOAN.save(
+        deps.storage,
+        &FlashLoanState {
+            loan: ask_asset.clone(),
+            repayment: repayment.clone(),
+            balance_before: offer_pool.amount,
+            fee,
+            reserves_before: pools.clone(),
+        },
+    )?;
 
-    Ok((pools, total_share))
+    let callback_msg = wasm_execute(
+        &info.sender,
+        &FlashLoanReceiverMsg::FlashSwapCallback {
+            ask: ask_asset.clone(),
+            offer: repayment.clone(),
+            msg,
+        },
+        vec![],
+    )?;
+    let finalize_msg = wasm_execute(
+        &env.contract.address,
+        &ExecuteMsg::FinalizeFlashLoan {},
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .add_message(ask_asset.clone().into_msg(&info.sender)?)
+        .add_messages(fee_messages)
+        .add_message(callback_msg)
+        .add_message(finalize_msg)
+        .add_attributes(
+            FlashSwapEvent {
+                pool: config.pair_info.contract_addr,
+                borrower: info.sender,
+                ask_asset,
+                offer_asset: repayment,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount,
+                fee_amount: fee,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Verifies that the ongoing flash loan or flash swap was repaid together with the fee.
+pub fn finalize_flash_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let flash_loan = FLASH_LOAN.load(deps.storage)?;
+    FLASH_LOAN.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let actual = flash_loan
+        .repayment
+        .info
+        .query_pool(&deps.querier, &config.pair_info.contract_addr)?;
+
+    let expected = flash_loan.balance_before + flash_loan.repayment.amount;
+    if actual < expected {
+        return Err(ContractError::FlashLoanNotRepaid { expected, actual });
+    }
+
+    // A flash swap moves the reserves as a regular swap does
+    if !flash_loan.reserves_before.is_empty() {
+        let pools = config
+            .pair_info
+            .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+        check_circuit_breaker(deps.storage, &flash_loan.reserves_before, &pools)?;
+
+        accumulate_swap_sizes(deps.storage, &env)?;
+        let decimal_pools = flash_loan
+            .reserves_before
+            .iter()
+            .map(|pool| {
+                let precision = get_precision(deps.storage, &pool.info)?;
+                pool.to_decimal_asset(precision)
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let (base_amount, quote_amount) = determine_base_quote_amount(
+            &decimal_pools,
+            &flash_loan.repayment,
+            flash_loan.loan.amount,
+        )?;
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "finalize_flash_loan"),
+        attr("fee_amount", flash_loan.fee),
+    ]))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
 ///
 /// * **params** new parameter values.
 pub fn update_config(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     params: Binary,
@@ -1090,6 +1829,7 @@ pub fn update_config(
         StablePoolUpdateParams::EnableFeeShare {
             fee_share_bps,
             fee_share_address,
+            callback,
         } => {
             // Enable fee sharing for this contract
             // If fee sharing is already enabled, we should be able to overwrite
@@ -1104,6 +1844,7 @@ pub fn update_config(
             config.fee_share = Some(FeeShareConfig {
                 bps: fee_share_bps,
                 recipient: deps.api.addr_validate(&fee_share_address)?,
+                callback,
             });
 
             CONFIG.save(deps.storage, &config)?;
@@ -1115,6 +1856,9 @@ pub fn update_config(
             response
                 .attributes
                 .push(attr("fee_share_address", fee_share_address));
+            response
+                .attributes
+                .push(attr("fee_share_callback", callback.to_string()));
         }
         StablePoolUpdateParams::DisableFeeShare => {
             // Disable fee sharing for this contract by setting bps and
@@ -1125,11 +1869,263 @@ pub fn update_config(
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        StablePoolUpdateParams::EnableFlashLoans { fee_bps } => {
+            if fee_bps == 0 || fee_bps > MAX_FLASH_LOAN_FEE_BPS {
+                return Err(ContractError::FlashLoanFeeOutOfBounds {});
+            }
+
+            FLASH_LOAN_FEE_BPS.save(deps.storage, &fee_bps)?;
+
+            response
+                .attributes
+                .push(attr("action", "enable_flash_loans"));
+            response
+                .attributes
+                .push(attr("fee_bps", fee_bps.to_string()));
+        }
+        StablePoolUpdateParams::DisableFlashLoans => {
+            FLASH_LOAN_FEE_BPS.remove(deps.storage);
+            response
+                .attributes
+                .push(attr("action", "disable_flash_loans"));
+        }
+        StablePoolUpdateParams::UpdateBalanceCaps { caps } => {
+            let caps = update_balance_caps(
+                BALANCE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+                caps,
+                &config.pair_info.asset_infos,
+            )?;
+            BALANCE_CAPS.save(deps.storage, &caps)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_balance_caps"));
+            response
+                .attributes
+                .push(attr("balance_caps", caps.iter().join(", ")));
+        }
+        StablePoolUpdateParams::SetMakerFee { maker_fee_bps } => {
+            if let Some(maker_fee_bps) = maker_fee_bps {
+                let max = deps
+                    .querier
+                    .query_wasm_smart::<FeeInfoResponse>(
+                        &config.factory_addr,
+                        &FactoryQueryMsg::FeeInfo {
+                            pair_type: config.pair_info.pair_type.clone(),
+                        },
+                    )?
+                    .maker_fee_bps;
+                ensure!(
+                    maker_fee_bps <= max,
+                    ContractError::MakerFeeOutOfBounds { max }
+                );
+                MAKER_FEE_BPS.save(deps.storage, &maker_fee_bps)?;
+            } else {
+                MAKER_FEE_BPS.remove(deps.storage);
+            }
+
+            response.attributes.push(attr("action", "set_maker_fee"));
+            response.attributes.push(attr(
+                "maker_fee_bps",
+                maker_fee_bps.map_or_else(|| "default".to_string(), |bps| bps.to_string()),
+            ));
+        }
+        StablePoolUpdateParams::SetCircuitBreaker {
+            guardian,
+            max_ratio_change,
+        } => {
+            ensure!(
+                !max_ratio_change.is_zero(),
+                ContractError::InvalidMaxRatioChange {}
+            );
+
+            let circuit_breaker = CircuitBreaker {
+                guardian: deps.api.addr_validate(&guardian)?,
+                max_ratio_change,
+                tripped_at: CIRCUIT_BREAKER
+                    .may_load(deps.storage)?
+                    .and_then(|circuit_breaker| circuit_breaker.tripped_at),
+            };
+            CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+            response
+                .attributes
+                .push(attr("action", "set_circuit_breaker"));
+            response.attributes.push(attr("guardian", guardian));
+            response
+                .attributes
+                .push(attr("max_ratio_change", max_ratio_change.to_string()));
+        }
+        StablePoolUpdateParams::RemoveCircuitBreaker => {
+            CIRCUIT_BREAKER.remove(deps.storage);
+            response
+                .attributes
+                .push(attr("action", "remove_circuit_breaker"));
+        }
+        StablePoolUpdateParams::SetDynamicFees {
+            base_fee_bps,
+            max_fee_bps,
+        } => {
+            ensure!(
+                base_fee_bps <= max_fee_bps && max_fee_bps <= MAX_DYNAMIC_FEE_BPS,
+                ContractError::DynamicFeesOutOfBounds {}
+            );
+
+            DYNAMIC_FEES.save(
+                deps.storage,
+                &DynamicFees {
+                    base_fee_bps,
+                    max_fee_bps,
+                },
+            )?;
+
+            response.attributes.push(attr("action", "set_dynamic_fees"));
+            response
+                .attributes
+                .push(attr("base_fee_bps", base_fee_bps.to_string()));
+            response
+                .attributes
+                .push(attr("max_fee_bps", max_fee_bps.to_string()));
+        }
+        StablePoolUpdateParams::DisableDynamicFees => {
+            DYNAMIC_FEES.remove(deps.storage);
+            response
+                .attributes
+                .push(attr("action", "disable_dynamic_fees"));
+        }
+        StablePoolUpdateParams::RefreshPrecisions {} => {
+            let cached_precisions = config
+                .pair_info
+                .asset_infos
+                .iter()
+                .map(|asset_info| get_precision(deps.storage, asset_info))
+                .collect::<StdResult<Vec<_>>>()?;
+            config.greatest_precision = store_precisions(
+                deps.branch(),
+                &config.pair_info.asset_infos,
+                &config.factory_addr,
+            )?;
+            let precisions = config
+                .pair_info
+                .asset_infos
+                .iter()
+                .map(|asset_info| get_precision(deps.storage, asset_info))
+                .collect::<StdResult<Vec<_>>>()?;
+
+            // Reserves are normalized with the cached precisions thus they can't change after the first provision
+            let total_share =
+                query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+            ensure!(
+                precisions == cached_precisions || total_share.is_zero(),
+                ContractError::PrecisionChangeWithLiquidity {}
+            );
+            CONFIG.save(deps.storage, &config)?;
+            response
+                .attributes
+                .push(attr("action", "refresh_precisions"));
+        }
     }
 
     Ok(response)
 }
 
+/// Halts swaps and liquidity provision until the pair is resumed. Withdrawals are still allowed.
+/// Only the circuit breaker guardian or the pair owner can execute this.
+pub fn halt_pair(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut circuit_breaker = CIRCUIT_BREAKER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::CircuitBreakerNotSet {})?;
+    ensure!(
+        circuit_breaker.tripped_at.is_none(),
+        ContractError::PairHalted {}
+    );
+    assert_guardian(deps.as_ref(), &info, &circuit_breaker)?;
+
+    circuit_breaker.tripped_at = Some(env.block.time.seconds());
+    CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+    Ok(Response::new().add_attribute("action", "halt_pair"))
+}
+
+/// Resumes swaps and liquidity provision after the pair was halted.
+/// Only the circuit breaker guardian or the pair owner can execute this.
+pub fn resume_pair(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut circuit_breaker = CIRCUIT_BREAKER
+        .may_load(deps.storage)?
+        .filter(|circuit_breaker| circuit_breaker.tripped_at.is_some())
+        .ok_or(ContractError::PairNotHalted {})?;
+    assert_guardian(deps.as_ref(), &info, &circuit_breaker)?;
+
+    circuit_breaker.tripped_at = None;
+    CIRCUIT_BREAKER.save(deps.storage, &circuit_breaker)?;
+
+    Ok(Response::new().add_attribute("action", "resume_pair"))
+}
+
+/// Checks that the sender is the circuit breaker guardian or the pair owner.
+fn assert_guardian(
+    deps: Deps,
+    info: &MessageInfo,
+    circuit_breaker: &CircuitBreaker,
+) -> Result<(), ContractError> {
+    if info.sender != circuit_breaker.guardian {
+        let config = CONFIG.load(deps.storage)?;
+        let owner = match config.owner {
+            Some(owner) => owner,
+            None => query_factory_config(&deps.querier, &config.factory_addr)?.owner,
+        };
+        ensure_eq!(info.sender, owner, ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+/// Fails if the pair is halted or if the operation would shift the reserve ratio
+/// by more than the circuit breaker allows.
+fn check_circuit_breaker(
+    storage: &dyn Storage,
+    old_reserves: &[Asset],
+    new_reserves: &[Asset],
+) -> Result<(), ContractError> {
+    let Some(circuit_breaker) = CIRCUIT_BREAKER.may_load(storage)? else {
+        return Ok(());
+    };
+
+    ensure!(
+        circuit_breaker.tripped_at.is_none(),
+        ContractError::PairHalted {}
+    );
+
+    check_ratio_change(&circuit_breaker, old_reserves, new_reserves)
+}
+
+/// Fails if an imbalanced withdrawal would shift the reserve ratio by more than the circuit breaker allows.
+/// Withdrawals are allowed while the pair is halted.
+fn check_withdrawal_circuit_breaker(
+    storage: &dyn Storage,
+    old_reserves: &[Asset],
+    new_reserves: &[Asset],
+) -> Result<(), ContractError> {
+    match CIRCUIT_BREAKER.may_load(storage)? {
+        Some(circuit_breaker) => check_ratio_change(&circuit_breaker, old_reserves, new_reserves),
+        None => Ok(()),
+    }
+}
+
+fn check_ratio_change(
+    circuit_breaker: &CircuitBreaker,
+    old_reserves: &[Asset],
+    new_reserves: &[Asset],
+) -> Result<(), ContractError> {
+    match circuit_breaker.check(old_reserves, new_reserves)? {
+        Some(change) => Err(ContractError::ReserveRatioChangeTooLarge {
+            change,
+            max: circuit_breaker.max_ratio_change,
+        }),
+        None => Ok(()),
+    }
+}
+
 /// Start changing the AMP value.
 ///
 /// * **next_amp** new value for AMP.
@@ -1194,9 +2190,7 @@ fn query_compute_d(deps: Deps, env: Env) -> StdResult<Uint128> {
     let config = CONFIG.load(deps.storage)?;
 
     let amp = compute_current_amp(&config, &env)?;
-    let pools = config
-        .pair_info
-        .query_pools_decimal(&deps.querier, env.contract.address, &config.factory_addr)?
+    let pools = query_pools_decimal(deps, &config, &env.contract.address)?
         .into_iter()
         .map(|pool| pool.amount)
         .collect::<Vec<_>>();
@@ -1206,47 +2200,31 @@ fn query_compute_d(deps: Deps, env: Env) -> StdResult<Uint128> {
         .to_uint128_with_precision(config.greatest_precision)
 }
 
-fn ensure_min_assets_to_receive(
-    config: &Config,
-    mut refund_assets: Vec<Asset>,
-    min_assets_to_receive: Option<Vec<Asset>>,
-) -> Result<(), ContractError> {
-    if let Some(min_assets_to_receive) = min_assets_to_receive {
-        if refund_assets.len() != min_assets_to_receive.len() {
-            return Err(ContractError::WrongAssetLength {
-                expected: refund_assets.len(),
-                actual: min_assets_to_receive.len(),
-            });
-        }
-
-        for asset in &min_assets_to_receive {
-            if !config.pair_info.asset_infos.contains(&asset.info) {
-                return Err(ContractError::AssetMismatch {});
-            }
-        }
+/// Returns the pool invariant D per one whole LP token in a [`VirtualPriceResponse`] object.
+/// LP tokens have the greatest precision of the pool assets.
+fn query_virtual_price(deps: Deps, env: Env) -> StdResult<VirtualPriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
 
-        if refund_assets[0].info.ne(&min_assets_to_receive[0].info) {
-            refund_assets.swap(0, 1)
-        }
+    let total_share = query_native_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    if total_share.is_zero() {
+        return Err(StdError::generic_err(
+            "Virtual price is not defined for an empty pool",
+        ));
+    }
 
-        if refund_assets[0].amount < min_assets_to_receive[0].amount {
-            return Err(ContractError::WithdrawSlippageViolation {
-                asset_name: refund_assets[0].info.to_string(),
-                received: refund_assets[0].amount,
-                expected: min_assets_to_receive[0].amount,
-            });
-        }
+    let amp = compute_current_amp(&config, &env)?;
+    let pools = query_pools_decimal(deps, &config, &env.contract.address)?
+        .into_iter()
+        .map(|pool| pool.amount)
+        .collect::<Vec<_>>();
+    let d =
+        compute_d(amp, &pools).map_err(|_| StdError::generic_err("Failed to calculate the D"))?;
 
-        if refund_assets[1].amount < min_assets_to_receive[1].amount {
-            return Err(ContractError::WithdrawSlippageViolation {
-                asset_name: refund_assets[1].info.to_string(),
-                received: refund_assets[1].amount,
-                expected: min_assets_to_receive[1].amount,
-            });
-        }
-    }
+    let virtual_price = d / Decimal256::with_precision(total_share, config.greatest_precision)?;
 
-    Ok(())
+    Ok(VirtualPriceResponse {
+        virtual_price: virtual_price.to_uint128_with_precision(VIRTUAL_PRICE_PRECISION)?,
+    })
 }
 
 fn query_simulate_provide(
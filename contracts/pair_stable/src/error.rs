@@ -1,13 +1,18 @@
 use cosmwasm_std::{
-    CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError, Uint128,
+    CheckedFromRatioError, CheckedMultiplyRatioError, ConversionOverflowError, Decimal, Decimal256,
+    OverflowError, StdError, Uint128,
 };
 use cw_utils::PaymentError;
 use thiserror::Error;
 
-use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
+use astroport::{
+    asset::MINIMUM_LIQUIDITY_AMOUNT,
+    pair::{MAX_DYNAMIC_FEE_BPS, MAX_FEE_SHARE_BPS, MAX_FLASH_LOAN_FEE_BPS},
+};
 use astroport_circular_buffer::error::BufferError;
+use astroport_pair_common::error::PairCommonError;
 
-use crate::math::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME};
+use crate::math::{ITERATIONS, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME};
 
 /// This enum describes stableswap pair contract errors
 #[derive(Error, Debug, PartialEq)]
@@ -112,8 +117,116 @@ pub enum ContractError {
         expected: Uint128,
     },
 
+    #[error("Not enough LP tokens. You need {0} LP tokens.")]
+    NotEnoughLpTokens(Uint128),
+
+    #[error("Not enough liquidity in the pool to withdraw {0}")]
+    InsufficientPoolLiquidity(String),
+
     #[error("Wrong asset length: expected {expected}, actual {actual}")]
     WrongAssetLength { expected: usize, actual: usize },
+
+    #[error(
+        "Flash loan fee is 0 or exceeds maximum allowed value of {} bps",
+        MAX_FLASH_LOAN_FEE_BPS
+    )]
+    FlashLoanFeeOutOfBounds {},
+
+    #[error("Maker fee can't exceed the factory setting of {max} bps")]
+    MakerFeeOutOfBounds { max: u16 },
+
+    #[error("Flash loans are disabled")]
+    FlashLoansDisabled {},
+
+    #[error("Operation is not allowed while a flash loan is in progress")]
+    FlashLoanInProgress {},
+
+    #[error("Flash loan amount must be positive and less than the pool reserve")]
+    InvalidFlashLoanAmount {},
+
+    #[error("Flash loan is not repaid: pool balance is {actual}, expected at least {expected}")]
+    FlashLoanNotRepaid { expected: Uint128, actual: Uint128 },
+
+    #[error("Pair is halted by the circuit breaker guardian, only withdrawals are allowed")]
+    PairHalted {},
+
+    #[error("Pair is not halted")]
+    PairNotHalted {},
+
+    #[error("Circuit breaker is not set")]
+    CircuitBreakerNotSet {},
+
+    #[error("Operation would shift the reserve ratio by {change}, the circuit breaker allows at most {max}")]
+    ReserveRatioChangeTooLarge { change: Decimal256, max: Decimal },
+
+    #[error("Circuit breaker max ratio change must be positive")]
+    InvalidMaxRatioChange {},
+
+    #[error(
+        "Dynamic fees must satisfy base fee <= max fee <= {} bps",
+        MAX_DYNAMIC_FEE_BPS
+    )]
+    DynamicFeesOutOfBounds {},
+
+    #[error("{0}")]
+    StableMath(StableMathError),
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
+
+    #[error("Swap notional {notional} is below the minimum trade notional {min}")]
+    TradeTooSmall { min: Decimal, notional: Decimal256 },
+    #[error("Asset precisions can't be changed while the pool has liquidity")]
+    PrecisionChangeWithLiquidity {},
+}
+
+/// This enum describes stableswap math errors
+#[derive(Error, Debug, PartialEq)]
+pub enum StableMathError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error(
+        "Newton's method for D failed to converge in {} iterations",
+        ITERATIONS
+    )]
+    DNotConverging {},
+
+    #[error(
+        "Newton's method for y failed to converge in {} iterations",
+        ITERATIONS
+    )]
+    YNotConverging {},
+}
+
+impl From<OverflowError> for StableMathError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
+
+impl From<CheckedFromRatioError> for StableMathError {
+    fn from(e: CheckedFromRatioError) -> Self {
+        StdError::generic_err(e.to_string()).into()
+    }
+}
+
+impl From<StableMathError> for StdError {
+    fn from(err: StableMathError) -> Self {
+        match err {
+            StableMathError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
+
+impl From<StableMathError> for ContractError {
+    fn from(err: StableMathError) -> Self {
+        match err {
+            StableMathError::Std(err) => ContractError::Std(err),
+            err => ContractError::StableMath(err),
+        }
+    }
 }
 
 impl From<OverflowError> for ContractError {
@@ -127,3 +240,26 @@ impl From<ConversionOverflowError> for ContractError {
         StdError::from(o).into()
     }
 }
+
+impl From<PairCommonError> for ContractError {
+    fn from(err: PairCommonError) -> Self {
+        match err {
+            PairCommonError::Std(err) => ContractError::Std(err),
+            PairCommonError::DoublingAssets {} => ContractError::DoublingAssets {},
+            PairCommonError::AutoStakeError {} => ContractError::AutoStakeError {},
+            PairCommonError::WrongAssetLength { expected, actual } => {
+                ContractError::WrongAssetLength { expected, actual }
+            }
+            PairCommonError::AssetMismatch {} => ContractError::AssetMismatch {},
+            PairCommonError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            } => ContractError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            },
+        }
+    }
+}
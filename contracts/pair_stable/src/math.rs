@@ -1,9 +1,11 @@
-use cosmwasm_std::{Decimal256, StdError, StdResult, Uint128, Uint64};
+use cosmwasm_std::{Decimal256, Uint128, Uint64};
 
 use astroport::asset::Decimal256Ext;
 
+use crate::error::StableMathError;
+
 /// The maximum number of calculation steps for Newton's method.
-const ITERATIONS: u8 = 64;
+pub const ITERATIONS: u8 = 64;
 
 pub const MAX_AMP: u64 = 1_000_000;
 pub const MAX_AMP_CHANGE: u64 = 10;
@@ -20,33 +22,58 @@ pub const TOL: Decimal256 = Decimal256::raw(1000000000000);
 ///
 /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
 ///
-pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
+pub fn compute_d(amp: Uint64, pools: &[Decimal256]) -> Result<Decimal256, StableMathError> {
     let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
     let amount_a_times_coins = pools[0] * N_COINS;
     let amount_b_times_coins = pools[1] * N_COINS;
 
     let sum_x = pools[0].checked_add(pools[1])?; // sum(x_i), a.k.a S
     if sum_x.is_zero() {
-        Ok(Decimal256::zero())
-    } else {
-        let mut d_previous: Decimal256;
-        let mut d: Decimal256 = sum_x;
-
-        // Newton's method to approximate D
-        for _ in 0..ITERATIONS {
-            let d_product = d.pow(3) / (amount_a_times_coins * amount_b_times_coins);
-            d_previous = d;
-            d = calculate_step(d, leverage, sum_x, d_product)?;
-            // Equality with the precision of 1e-6
-            if d.abs_diff(d_previous) <= TOL {
-                return Ok(d);
-            }
-        }
+        return Ok(Decimal256::zero());
+    }
+
+    let mut d_previous: Decimal256;
+    let mut d = initial_d(leverage, pools, sum_x)?;
 
-        Err(StdError::generic_err(
-            "Newton method for D failed to converge",
-        ))
+    // Newton's method to approximate D
+    for _ in 0..ITERATIONS {
+        let d_product = d.pow(3) / (amount_a_times_coins * amount_b_times_coins);
+        d_previous = d;
+        d = calculate_step(d, leverage, sum_x, d_product)?;
+        // Equality with the precision of 1e-6
+        if d.abs_diff(d_previous) <= TOL {
+            return Ok(d);
+        }
     }
+
+    Err(StableMathError::DNotConverging {})
+}
+
+/// Returns the starting point of Newton's method for D.
+/// Far above the root every step shrinks D by a third only, thus starting from sum(x_i) takes
+/// dozens of steps for imbalanced pools. D is bounded by the constant product and the constant sum
+/// invariants, i.e. 2 * sqrt(x * y) <= D <= x + y. Putting the lower bound into the invariant gives
+/// a tighter upper bound:
+///
+/// D <= sqrt(2 * sqrt(x * y) * (leverage + 1) * (x + y))
+///
+/// Starting above the root keeps the iterations decreasing monotonically.
+fn initial_d(
+    leverage: Decimal256,
+    pools: &[Decimal256],
+    sum_x: Decimal256,
+) -> Result<Decimal256, StableMathError> {
+    let upper_bound = pools[0]
+        .checked_mul(pools[1])?
+        .sqrt()
+        .checked_mul(N_COINS)?
+        .checked_mul(leverage.checked_add(Decimal256::one())?)?
+        .checked_mul(sum_x)?
+        .sqrt()
+        // Compensates rounding of the square roots
+        .checked_add(TOL)?;
+
+    Ok(upper_bound.min(sum_x))
 }
 
 /// Helper function used to calculate the D invariant as a last step in the `compute_d` public function.
@@ -59,7 +86,7 @@ fn calculate_step(
     leverage: Decimal256,
     sum_x: Decimal256,
     d_product: Decimal256,
-) -> StdResult<Decimal256> {
+) -> Result<Decimal256, StableMathError> {
     let leverage_mul = leverage.checked_mul(sum_x)?;
     let d_p_mul = d_product.checked_mul(N_COINS)?;
 
@@ -70,9 +97,7 @@ fn calculate_step(
 
     let r_val = leverage_sub.checked_add(n_coins_sum)?;
 
-    l_val
-        .checked_div(r_val)
-        .map_err(|e| StdError::generic_err(e.to_string()))
+    Ok(l_val.checked_div(r_val)?)
 }
 
 /// Compute the swap amount `y` in proportion to `x`.
@@ -87,7 +112,7 @@ pub(crate) fn calc_y(
     new_amount: Decimal256,
     xp: &[Decimal256],
     target_precision: u8,
-) -> StdResult<Uint128> {
+) -> Result<Uint128, StableMathError> {
     let d = compute_d(amp, xp)?;
     let leverage = Decimal256::from_ratio(amp, 1u8) * N_COINS;
     let amp_prec = Decimal256::from_ratio(AMP_PRECISION, 1u8);
@@ -99,21 +124,148 @@ pub(crate) fn calc_y(
 
     let b = new_amount.checked_add(d.checked_mul(amp_prec)? / leverage)?;
 
-    // Solve for y by approximating: y**2 + b*y = c
+    // Newton's method polishes the closed-form root which is imprecise due to the square root
     let mut y_prev;
-    let mut y = d;
+    let mut y = initial_y(b, c, d)?;
     for _ in 0..ITERATIONS {
         y_prev = y;
         y = y
             .checked_pow(2)?
             .checked_add(c)?
-            .checked_div(y.checked_mul(N_COINS)?.checked_add(b)?.checked_sub(d)?)
-            .map_err(|e| StdError::generic_err(e.to_string()))?;
+            .checked_div(y.checked_mul(N_COINS)?.checked_add(b)?.checked_sub(d)?)?;
         if y.abs_diff(y_prev) <= TOL {
-            return y.to_uint128_with_precision(target_precision);
+            return Ok(y.to_uint128_with_precision(target_precision)?);
         }
     }
 
-    // Should definitely converge in 64 iterations.
-    Err(StdError::generic_err("y is not converging"))
+    Err(StableMathError::YNotConverging {})
+}
+
+/// Returns the positive root of y**2 + (b - D) * y - c = 0 which is the starting point of Newton's method for y.
+/// The root is computed in the form which doesn't subtract close numbers:
+///
+/// b >= D: y = 2 * c / ((b - D) + sqrt((b - D)**2 + 4 * c))
+///
+/// b < D: y = ((D - b) + sqrt((D - b)**2 + 4 * c)) / 2
+fn initial_y(b: Decimal256, c: Decimal256, d: Decimal256) -> Result<Decimal256, StableMathError> {
+    let b_diff = b.abs_diff(d);
+    let discriminant_sqrt = b_diff
+        .checked_pow(2)?
+        .checked_add(c.checked_mul(N_COINS * N_COINS)?)?
+        .sqrt();
+
+    if b >= d {
+        let numerator = c.checked_mul(N_COINS)?;
+        Ok(numerator.checked_div(b_diff.checked_add(discriminant_sqrt)?)?)
+    } else {
+        Ok(b_diff
+            .checked_add(discriminant_sqrt)?
+            .checked_div(N_COINS)?)
+    }
+}
+
+/// Computes the amount of the ask pool left after swapping `offer_amount` into a pool
+/// with the given balances. Exposed for fuzz targets only.
+///
+/// * **amp** amplification coefficient multiplied by [`AMP_PRECISION`].
+#[cfg(feature = "fuzzing")]
+pub fn fuzz_swap(
+    amp: u64,
+    pools: [Decimal256; 2],
+    offer_amount: Decimal256,
+    target_precision: u8,
+) -> Result<Uint128, StableMathError> {
+    calc_y(
+        Uint64::new(amp),
+        pools[0].checked_add(offer_amount)?,
+        &pools,
+        target_precision,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn dec(amount: u128) -> Decimal256 {
+        Decimal256::with_precision(amount, 6u8).unwrap()
+    }
+
+    #[test]
+    fn initial_guesses() {
+        let amp = Uint64::new(100 * AMP_PRECISION);
+        let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
+
+        // Balanced pools start from the constant sum
+        let pools = [dec(1_000_000000), dec(1_000_000000)];
+        assert_eq!(
+            initial_d(leverage, &pools, pools[0] + pools[1]).unwrap(),
+            pools[0] + pools[1]
+        );
+
+        // Imbalanced pools start much closer to D
+        let pools = [dec(1_000_000_000_000000), dec(1_000000)];
+        let sum_x = pools[0] + pools[1];
+        let d = compute_d(amp, &pools).unwrap();
+        let start = initial_d(leverage, &pools, sum_x).unwrap();
+        assert!(start >= d && start < sum_x / Decimal256::from_ratio(10u8, 1u8));
+
+        // The closed-form y is the root of y**2 + (b - D) * y = c
+        for (b, c, d) in [
+            (dec(5_000000), dec(6_000000), dec(0)),
+            (dec(1_000000), dec(12_000000), dec(2_000000)),
+        ] {
+            let y = initial_y(b, c, d).unwrap();
+            let lhs = y * y + y * b;
+            let rhs = c + y * d;
+            assert!(lhs.abs_diff(rhs) < TOL, "y={y}, lhs={lhs}, rhs={rhs}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn d_converges_within_bounds(
+            x in 1_000000..1_000_000_000_000_000000u128,
+            y in 1_000000..1_000_000_000_000_000000u128,
+            amp in 1..=MAX_AMP,
+        ) {
+            let pools = [dec(x), dec(y)];
+            let d = compute_d(Uint64::new(amp * AMP_PRECISION), &pools).unwrap();
+
+            // 2 * sqrt(x * y) <= D <= x + y
+            let tol = Decimal256::permille(1);
+            let lower_bound = (pools[0] * pools[1]).sqrt() * N_COINS;
+            prop_assert!(d + tol >= lower_bound, "d={d}, lower_bound={lower_bound}");
+            prop_assert!(d <= pools[0] + pools[1] + tol, "d={d}");
+        }
+
+        #[test]
+        fn swap_keeps_invariant(
+            x in 1_000000..1_000_000_000_000_000000u128,
+            y in 1_000000..1_000_000_000_000_000000u128,
+            offer_pct in 0..100u128,
+            amp in 1..=MAX_AMP,
+        ) {
+            prop_assume!(x / y <= 1_000_000 && y / x <= 1_000_000);
+
+            let amp = Uint64::new(amp * AMP_PRECISION);
+            let pools = [dec(x), dec(y)];
+            let d = compute_d(amp, &pools).unwrap();
+
+            let new_x = x + x * offer_pct / 100;
+            let new_y = calc_y(amp, dec(new_x), &pools, 6).unwrap();
+
+            // The ask pool never grows
+            prop_assert!(new_y.u128() <= y + 1, "new_y={new_y}, y={y}");
+            if offer_pct == 0 {
+                prop_assert!(new_y.u128().abs_diff(y) <= 1, "new_y={new_y}, y={y}");
+            }
+
+            // D stays the same up to rounding of the ask pool
+            let new_d = compute_d(amp, &[dec(new_x), dec(new_y.u128())]).unwrap();
+            prop_assert!(new_d.abs_diff(d) <= Decimal256::percent(1), "d={d}, new_d={new_d}");
+        }
+    }
 }
@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
-    SystemError, SystemResult, Uint128, WasmQuery,
+    from_json, to_json_binary, Addr, Binary, Coin, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
@@ -97,6 +97,7 @@ impl WasmMockQuerier {
                                 token_code_id: 0,
                                 fee_address: Some(Addr::unchecked("fee_address")),
                                 generator_address: None,
+                                auto_stake_target: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
                             })
@@ -164,6 +165,7 @@ impl WasmMockQuerier {
                                 token_code_id: 0,
                                 fee_address: Some(Addr::unchecked("fee_address")),
                                 generator_address: None,
+                                auto_stake_target: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
                             })
@@ -171,6 +173,12 @@ impl WasmMockQuerier {
                         )
                     } else if key.as_slice() == b"pairs_to_migrate".as_slice() {
                         SystemResult::Ok(to_json_binary(&Vec::<Addr>::new()).into())
+                    } else if key.as_slice()[2..].starts_with(b"decimals_overrides")
+                        || key.as_slice() == b"pairs_paused".as_slice()
+                        || key.as_slice()[2..].starts_with(b"paused_pairs")
+                        || key.as_slice()[2..].starts_with(b"pair_configs")
+                    {
+                        SystemResult::Ok(Binary::default().into())
                     } else {
                         panic!("DO NOT ENTER HERE");
                     }
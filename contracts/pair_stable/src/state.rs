@@ -1,11 +1,14 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, DepsMut, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Decimal, DepsMut, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 
-use astroport::asset::{AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::common::OwnershipProposal;
-use astroport::observation::Observation;
-use astroport::pair::FeeShareConfig;
+use astroport::observation::{FeeObservation, Observation};
+use astroport::pair::{
+    AccruedFees, CircuitBreaker, DynamicFees, FeeShareConfig, LpTokenMetadata, PairCreationInfo,
+};
+use astroport::querier::query_checked_precision;
 use astroport_circular_buffer::CircularBuffer;
 
 /// This structure stores the main stableswap pair parameters.
@@ -35,6 +38,9 @@ pub struct Config {
     pub fee_share: Option<FeeShareConfig>,
     /// The tracker contract address
     pub tracker_addr: Option<Addr>,
+    /// Minimum swap size in whole offer tokens set by the factory pair config at instantiation
+    #[serde(default)]
+    pub min_trade_notional: Option<Decimal>,
 }
 
 /// Circular buffer to store trade size observations
@@ -43,12 +49,60 @@ pub const OBSERVATIONS: CircularBuffer<Observation> =
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
+
+/// Temporarily stores custom LP token metadata until the LP denom is created
+pub const LP_TOKEN_METADATA: Item<LpTokenMetadata> = Item::new("lp_token_metadata");
+
 /// Stores map of AssetInfo (as String) -> precision
 pub const PRECISIONS: Map<String, u8> = Map::new("precisions");
 
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Flash loan fee in bps. Flash loans are disabled in the pair if not set
+pub const FLASH_LOAN_FEE_BPS: Item<u16> = Item::new("flash_loan_fee_bps");
+
+/// Caps on pool balances. Liquidity provision is not capped if not set
+pub const BALANCE_CAPS: Item<Vec<Asset>> = Item::new("balance_caps");
+
+/// Halts swaps and liquidity provision on large reserve ratio shifts. Disabled if not set
+pub const CIRCUIT_BREAKER: Item<CircuitBreaker> = Item::new("circuit_breaker");
+
+/// Swap fee tiers based on the pool imbalance. The factory fee applies if not set
+pub const DYNAMIC_FEES: Item<DynamicFees> = Item::new("dynamic_fees");
+
+/// Maker fee override in bps. The factory setting applies if not set
+pub const MAKER_FEE_BPS: Item<u16> = Item::new("maker_fee_bps");
+
+/// Cumulative swap fees collected in each pool asset. key: ask asset info
+pub const ACCRUED_FEES: Map<&AssetInfo, AccruedFees> = Map::new("accrued_fees");
+
+/// Circular buffer to store cumulative LP fee snapshots used to estimate the fee APR
+pub const FEE_OBSERVATIONS: CircularBuffer<FeeObservation> =
+    CircularBuffer::new("fee_observations_state", "fee_observations_buffer");
+
+/// Stores the ongoing flash loan until it is finalized in the same transaction
+pub const FLASH_LOAN: Item<FlashLoanState> = Item::new("flash_loan");
+
+/// The state needed to verify the flash loan or flash swap repayment.
+#[cw_serde]
+pub struct FlashLoanState {
+    /// The borrowed asset. For flash swaps it is the ask asset which is not returned
+    pub loan: Asset,
+    /// The repayment asset and the amount by which its pool balance must grow.
+    /// For flash loans it is the fee in the borrowed asset, for flash swaps it is the whole offer
+    pub repayment: Asset,
+    /// The pool balance of the repayment asset before the loan
+    pub balance_before: Uint128,
+    /// The flash loan fee included in the repayment
+    pub fee: Uint128,
+    /// The pool reserves before a flash swap. Empty for flash loans
+    #[serde(default)]
+    pub reserves_before: Vec<Asset>,
+}
+
 /// Store all token precisions and return the greatest one.
 pub(crate) fn store_precisions(
     deps: DepsMut,
@@ -58,7 +112,7 @@ pub(crate) fn store_precisions(
     let mut max = 0u8;
 
     for asset_info in asset_infos {
-        let precision = asset_info.decimals(&deps.querier, factory_addr)?;
+        let precision = query_checked_precision(&deps.querier, asset_info, factory_addr)?;
         max = max.max(precision);
         PRECISIONS.save(deps.storage, asset_info.to_string(), &precision)?;
     }
@@ -12,13 +12,17 @@ use sim::StableSwapModel;
 
 use astroport::asset::{native_asset, native_asset_info, Asset, AssetInfo};
 use astroport::common::LP_SUBDENOM;
+use astroport::events::EVENTS_SCHEMA_VERSION;
 use astroport::factory::PairType;
 use astroport::observation::query_observation;
 use astroport::observation::Observation;
 use astroport::observation::OracleObservation;
+use astroport::observation::{
+    query_observations_info, query_safe_price, ObservationsInfoResponse, SafePriceResponse,
+};
 use astroport::pair::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    SimulationResponse, StablePoolParams,
+    SimulationResponse, StablePoolConfig, StablePoolParams, StablePoolUpdateParams,
 };
 use astroport::token_factory::{MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint};
 use astroport_circular_buffer::BufferManager;
@@ -31,7 +35,7 @@ use crate::contract::{
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
 use crate::state::{CONFIG, OBSERVATIONS};
-use crate::utils::{compute_swap, select_pools};
+use crate::utils::{calculate_imbalanced_withdraw, calculate_shares, compute_swap, select_pools};
 
 #[derive(Clone, PartialEq, Message)]
 struct MsgInstantiateContractResponse {
@@ -83,6 +87,8 @@ fn proper_initialization() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -169,6 +175,8 @@ fn provide_liquidity() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -203,6 +211,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -329,6 +339,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -408,6 +420,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -485,6 +499,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -526,6 +542,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -565,6 +583,8 @@ fn withdraw_liquidity() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -595,6 +615,7 @@ fn withdraw_liquidity() {
     let msg = ExecuteMsg::WithdrawLiquidity {
         assets: vec![],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -712,6 +733,8 @@ fn try_native_to_token() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -737,6 +760,8 @@ fn try_native_to_token() {
         belief_price: None,
         max_spread: Some(Decimal::percent(50)),
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info(
@@ -806,6 +831,16 @@ fn try_native_to_token() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    Uint128::new(7593888),
+                    Uint128::new(1487928894) + Uint128::new(7593888) + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
         ]
     );
 
@@ -829,6 +864,131 @@ fn try_native_to_token() {
     );
 }
 
+#[test]
+fn maker_fee_override() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+    let offer_amount = Uint128::new(1500000000u128);
+
+    let mut deps =
+        mock_dependencies(&[coin((collateral_pool_amount + offer_amount).u128(), "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        pair_type: PairType::Stable {},
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
+            })
+            .unwrap(),
+        ),
+    };
+    instantiate(
+        deps.as_mut(),
+        mock_env_with_block_time(100),
+        mock_info("addr0000", &[]),
+        msg,
+    )
+    .unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let set_maker_fee_msg = |maker_fee_bps| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&StablePoolUpdateParams::SetMakerFee { maker_fee_bps }).unwrap(),
+    };
+    let env = mock_env_with_block_time(1000);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        set_maker_fee_msg(Some(0)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The mocked factory sets 16.6% maker fee
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        set_maker_fee_msg(Some(1661)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MakerFeeOutOfBounds { max: 1660 });
+
+    // Route all fees to LPs
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        set_maker_fee_msg(Some(0)),
+    )
+    .unwrap();
+
+    let res: ConfigResponse =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    let params: StablePoolConfig = from_json(res.params.unwrap()).unwrap();
+    assert_eq!(params.maker_fee_bps, Some(0));
+
+    let swap_msg = ExecuteMsg::Swap {
+        offer_asset: native_asset("uusd".to_string(), offer_amount),
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        to: None,
+        deadline: None,
+        to_commitment: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+        swap_msg.clone(),
+    )
+    .unwrap();
+    assert!(res.attributes.contains(&attr("maker_fee_amount", "0")));
+    assert_eq!(res.messages.len(), 1);
+
+    // Reset to the factory setting
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        set_maker_fee_msg(None),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+        swap_msg,
+    )
+    .unwrap();
+    assert!(!res.attributes.contains(&attr("maker_fee_amount", "0")));
+    assert_eq!(res.messages.len(), 2);
+}
+
 #[test]
 fn try_token_to_native() {
     let total_share = Uint128::new(30000000000u128);
@@ -870,6 +1030,8 @@ fn try_token_to_native() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -895,6 +1057,8 @@ fn try_token_to_native() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info("addr0000", &[]);
@@ -910,6 +1074,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -979,6 +1145,16 @@ fn try_token_to_native() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    expected_spread_amount,
+                    Uint128::new(1500851252) + expected_spread_amount + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
         ]
     );
 
@@ -1008,6 +1184,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -1098,6 +1276,8 @@ fn test_query_pool() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1173,6 +1353,8 @@ fn test_query_share() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1238,6 +1420,141 @@ fn observations_full_buffer() {
     );
 }
 
+#[test]
+fn safe_price_is_median_of_observations() {
+    let mut deps = mock_dependencies(&[]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(100_000);
+    BufferManager::init(&mut deps.storage, OBSERVATIONS, 20).unwrap();
+
+    let err = query_safe_price(deps.as_ref(), env.clone(), OBSERVATIONS, 100).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: No observations within the last 100 seconds"
+    );
+
+    let mut buffer = BufferManager::new(&deps.storage, OBSERVATIONS).unwrap();
+    let array = (1..=10u64)
+        .map(|i| Observation {
+            ts: env.block.time.seconds() + i * 10,
+            // Manipulated price at the 5th observation
+            price: if i == 5 {
+                Decimal::from_ratio(1000u16, 1u8)
+            } else {
+                Decimal::from_ratio(i, 1u8)
+            },
+            price_sma: Default::default(),
+        })
+        .collect_vec();
+    buffer.push_many(&array);
+    buffer.commit(&mut deps.storage).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(100);
+
+    assert_eq!(
+        query_safe_price(deps.as_ref(), env.clone(), OBSERVATIONS, 0).unwrap(),
+        SafePriceResponse {
+            price: Decimal::from_ratio(10u8, 1u8),
+            observations: 1,
+            oldest_ts: 100_100,
+        }
+    );
+    assert_eq!(
+        query_safe_price(deps.as_ref(), env.clone(), OBSERVATIONS, 50).unwrap(),
+        SafePriceResponse {
+            price: f64_to_dec(8.5),
+            observations: 6,
+            oldest_ts: 100_050,
+        }
+    );
+    assert_eq!(
+        query_safe_price(deps.as_ref(), env.clone(), OBSERVATIONS, 10_000).unwrap(),
+        SafePriceResponse {
+            price: f64_to_dec(6.5),
+            observations: 10,
+            oldest_ts: 100_010,
+        }
+    );
+
+    // No fresh observations
+    env.block.time = env.block.time.plus_seconds(1000);
+    let err = query_safe_price(deps.as_ref(), env, OBSERVATIONS, 100).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Generic error: No observations within the last 100 seconds"
+    );
+}
+
+#[test]
+fn observations_info_describes_buffer_density() {
+    let mut deps = mock_dependencies(&[]);
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(100_000);
+    BufferManager::init(&mut deps.storage, OBSERVATIONS, 20).unwrap();
+
+    assert_eq!(
+        query_observations_info(deps.as_ref(), env.clone(), OBSERVATIONS).unwrap(),
+        ObservationsInfoResponse {
+            capacity: 20,
+            observations: 0,
+            fill_ratio: Decimal::zero(),
+            oldest_ts: None,
+            newest_ts: None,
+            recent_observations: 0,
+        }
+    );
+
+    // One observation per hour
+    let mut buffer = BufferManager::new(&deps.storage, OBSERVATIONS).unwrap();
+    let array = (1..=10u64)
+        .map(|i| Observation {
+            ts: env.block.time.seconds() + i * 3600,
+            price: Decimal::one(),
+            price_sma: Decimal::one(),
+        })
+        .collect_vec();
+    buffer.push_many(&array);
+    buffer.commit(&mut deps.storage).unwrap();
+
+    env.block.time = Timestamp::from_seconds(100_000 + 10 * 3600);
+    assert_eq!(
+        query_observations_info(deps.as_ref(), env.clone(), OBSERVATIONS).unwrap(),
+        ObservationsInfoResponse {
+            capacity: 20,
+            observations: 10,
+            fill_ratio: Decimal::percent(50),
+            oldest_ts: Some(103_600),
+            newest_ts: Some(136_000),
+            recent_observations: 10,
+        }
+    );
+
+    // Buffer overflows and old observations leave the 24h window
+    let mut buffer = BufferManager::new(&deps.storage, OBSERVATIONS).unwrap();
+    let array = (11..=25u64)
+        .map(|i| Observation {
+            ts: 100_000 + i * 3600,
+            price: Decimal::one(),
+            price_sma: Decimal::one(),
+        })
+        .collect_vec();
+    buffer.push_many(&array);
+    buffer.commit(&mut deps.storage).unwrap();
+
+    env.block.time = Timestamp::from_seconds(100_000 + 35 * 3600);
+    assert_eq!(
+        query_observations_info(deps.as_ref(), env, OBSERVATIONS).unwrap(),
+        ObservationsInfoResponse {
+            capacity: 20,
+            observations: 20,
+            fill_ratio: Decimal::one(),
+            oldest_ts: Some(100_000 + 6 * 3600),
+            newest_ts: Some(100_000 + 25 * 3600),
+            recent_observations: 15,
+        }
+    );
+}
+
 #[test]
 fn observations_incomplete_buffer() {
     let mut deps = mock_dependencies(&[]);
@@ -1362,7 +1679,7 @@ proptest! {
             factory_addr: String::from("factory"),
             asset_infos: vec![offer_asset.info.clone(), ask_asset.clone()],
             token_code_id: 10u64,
-            init_params: Some(to_json_binary(&StablePoolParams { amp, owner: None }).unwrap()),
+            init_params: Some(to_json_binary(&StablePoolParams { amp, owner: None, lp_token_metadata: None, balance_caps: None, }).unwrap()),
         };
 
         let env = mock_env();
@@ -1382,7 +1699,9 @@ proptest! {
             deps.as_ref().storage,
             &env,
             &config,
-            &offer_asset.to_decimal_asset(offer_asset.info.decimals(&deps.as_ref().querier, &config.factory_addr).unwrap()).unwrap(),
+            &offer_asset
+                .query_decimal_asset(&deps.as_ref().querier, &config.factory_addr)
+                .unwrap(),
             &offer_pool,
             &ask_pool,
             &pools,
@@ -1432,6 +1751,115 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn imbalanced_withdraw_against_provide(
+        balance_x in 1_000_000..1_000_000_000_000_000u128,
+        balance_y in 1_000_000..1_000_000_000_000_000u128,
+        amount_x in 1000..1_000_000_000_000u128,
+        amount_y in 0..1_000_000_000_000u128,
+        share_pct in 1..50u64,
+        amp in 1..150u64
+    ) {
+        prop_assume!(balance_x / balance_y < 10 && balance_y / balance_x < 10);
+        prop_assume!(amount_x < balance_x / 2 && amount_y < balance_y / 2);
+
+        let msg = InstantiateMsg {
+            pair_type: PairType::Stable {},
+            factory_addr: String::from("factory"),
+            asset_infos: vec![
+                native_asset_info("uusd".to_string()),
+                native_asset_info("uluna".to_string()),
+            ],
+            token_code_id: 10u64,
+            init_params: Some(to_json_binary(&StablePoolParams { amp, owner: None, lp_token_metadata: None, balance_caps: None, }).unwrap()),
+        };
+
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut deps = mock_dependencies(&[coin(balance_x, "uusd"), coin(balance_y, "uluna")]);
+
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        let fee_rate = Decimal::permille(5);
+        let total_share = Uint128::new(balance_x + balance_y);
+        let deposit = vec![
+            native_asset("uusd".to_string(), Uint128::new(amount_x)),
+            native_asset("uluna".to_string(), Uint128::new(amount_y)),
+        ];
+
+        // Provide path
+        let minted = calculate_shares(
+            deps.as_ref(),
+            &env,
+            &config,
+            total_share,
+            vec![
+                (deposit[0].clone(), Uint128::new(balance_x)),
+                (deposit[1].clone(), Uint128::new(balance_y)),
+            ],
+        )
+        .unwrap();
+
+        // Withdrawing the same assets right after the provide
+        let pools = vec![
+            native_asset("uusd".to_string(), Uint128::new(balance_x + amount_x)),
+            native_asset("uluna".to_string(), Uint128::new(balance_y + amount_y)),
+        ];
+        let burn_no_fee = calculate_imbalanced_withdraw(
+            deps.as_ref(),
+            &env,
+            &config,
+            &pools,
+            &deposit,
+            total_share + minted,
+            Decimal::zero(),
+        )
+        .unwrap();
+        let tolerance = Uint128::new(10) + minted / Uint128::new(1_000_000);
+        prop_assert!(
+            burn_no_fee.abs_diff(minted) <= tolerance,
+            "burn={burn_no_fee}, minted={minted}"
+        );
+
+        // The imbalance fee makes the round trip unprofitable
+        let burn = calculate_imbalanced_withdraw(
+            deps.as_ref(),
+            &env,
+            &config,
+            &pools,
+            &deposit,
+            total_share + minted,
+            fee_rate,
+        )
+        .unwrap();
+        prop_assert!(burn >= burn_no_fee, "burn={burn}, burn_no_fee={burn_no_fee}");
+
+        // Balanced request costs the same as the usual withdrawal
+        let ratio = Decimal::percent(share_pct);
+        let balanced_assets = pools
+            .iter()
+            .map(|pool| native_asset(pool.info.to_string(), pool.amount * ratio))
+            .collect_vec();
+        let burn = calculate_imbalanced_withdraw(
+            deps.as_ref(),
+            &env,
+            &config,
+            &pools,
+            &balanced_assets,
+            total_share + minted,
+            fee_rate,
+        )
+        .unwrap();
+        let balanced_share = (total_share + minted) * ratio;
+        let tolerance = Uint128::new(10) + balanced_share / Uint128::new(100_000);
+        prop_assert!(
+            burn.abs_diff(balanced_share) <= tolerance,
+            "burn={burn}, balanced_share={balanced_share}"
+        );
+    }
+}
+
 #[test]
 fn update_owner() {
     let mut deps = mock_dependencies(&[]);
@@ -1453,6 +1881,8 @@ fn update_owner() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: Some(owner.to_owned()),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
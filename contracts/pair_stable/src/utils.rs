@@ -1,47 +1,38 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-use astroport::incentives::ExecuteMsg as IncentiveExecuteMsg;
-use astroport::token_factory::tf_mint_msg;
 use cosmwasm_std::{
-    coin, wasm_execute, Addr, Api, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256, Deps,
-    Env, QuerierWrapper, StdResult, Storage, Uint128, Uint64,
+    Addr, Decimal, Decimal256, Deps, Env, StdResult, Storage, Uint128, Uint256, Uint64,
 };
 
 use itertools::Itertools;
 
-use astroport::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset};
 use astroport::observation::{
-    safe_sma_buffer_not_full, safe_sma_calculation, Observation, PrecommitObservation,
+    safe_sma_buffer_not_full, safe_sma_calculation, try_dec256_into_dec, Observation,
+    PrecommitObservation,
 };
-use astroport::pair::TWAP_PRECISION;
-use astroport::querier::query_factory_config;
+use astroport::pair::{initial_share_after_lockup, TWAP_PRECISION};
 use astroport_circular_buffer::error::BufferResult;
 use astroport_circular_buffer::BufferManager;
+use astroport_pair_common::liquidity::check_assets;
 
 use crate::error::ContractError;
 use crate::math::{calc_y, compute_d};
-use crate::state::{get_precision, Config, OBSERVATIONS};
-
-/// Helper function to check if the given asset infos are valid.
-pub(crate) fn check_asset_infos(
-    api: &dyn Api,
-    asset_infos: &[AssetInfo],
-) -> Result<(), ContractError> {
-    if !asset_infos.iter().all_unique() {
-        return Err(ContractError::DoublingAssets {});
-    }
+use crate::state::{get_precision, Config, DYNAMIC_FEES, OBSERVATIONS};
 
-    asset_infos
-        .iter()
-        .try_for_each(|asset_info| asset_info.check(api))
-        .map_err(Into::into)
-}
-
-/// Helper function to check that the assets in a given array are valid.
-pub(crate) fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), ContractError> {
-    let asset_infos = assets.iter().map(|asset| asset.info.clone()).collect_vec();
-    check_asset_infos(api, &asset_infos)
+/// Returns the balance for each asset in the pool in decimal using cached asset precisions.
+pub(crate) fn query_pools_decimal(
+    deps: Deps,
+    config: &Config,
+    contract_addr: &Addr,
+) -> StdResult<Vec<DecimalAsset>> {
+    config
+        .pair_info
+        .query_pools(&deps.querier, contract_addr)?
+        .into_iter()
+        .map(|pool| pool.to_decimal_asset(get_precision(deps.storage, &pool.info)?))
+        .collect()
 }
 
 /// Checks that cw20 token is part of the pool.
@@ -161,80 +152,6 @@ pub(crate) fn adjust_precision(
     })
 }
 
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Incentive contract (if auto staking is specified).
-///
-/// * **recipient** LP token recipient.
-///
-/// * **coin** denom and amount of LP tokens that will be minted for the recipient.
-///
-/// * **auto_stake** determines whether the newly minted LP tokens will
-/// be automatically staked in the Incentives contract on behalf of the recipient.
-pub fn mint_liquidity_token_message<T, C>(
-    querier: QuerierWrapper<C>,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg<T>>, ContractError>
-where
-    C: CustomQuery,
-    T: CustomMsg,
-{
-    let coin = coin(amount.into(), config.pair_info.liquidity_token.to_string());
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(tf_mint_msg(contract_address, coin, recipient));
-    }
-
-    // Mint for the pair contract and stake into the Incentives contract
-    let incentives_addr = query_factory_config(&querier, &config.factory_addr)?.generator_address;
-
-    if let Some(address) = incentives_addr {
-        let mut msgs = tf_mint_msg(contract_address, coin.clone(), contract_address);
-        msgs.push(
-            wasm_execute(
-                address,
-                &IncentiveExecuteMsg::Deposit {
-                    recipient: Some(recipient.to_string()),
-                },
-                vec![coin],
-            )?
-            .into(),
-        );
-        Ok(msgs)
-    } else {
-        Err(ContractError::AutoStakeError {})
-    }
-}
-
-/// Return the amount of tokens that a specific amount of LP tokens would withdraw.
-///
-/// * **pools** array with assets available in the pool.
-///
-/// * **amount** amount of LP tokens to calculate underlying amounts for.
-///
-/// * **total_share** total amount of LP tokens currently issued by the pool.
-pub(crate) fn get_share_in_assets(
-    pools: &[Asset],
-    amount: Uint128,
-    total_share: Uint128,
-) -> Vec<Asset> {
-    let mut share_ratio = Decimal::zero();
-    if !total_share.is_zero() {
-        share_ratio = Decimal::from_ratio(amount, total_share);
-    }
-
-    pools
-        .iter()
-        .map(|pool| Asset {
-            info: pool.info.clone(),
-            amount: pool.amount * share_ratio,
-        })
-        .collect()
-}
-
 /// Structure for internal use which represents swap result.
 pub(crate) struct SwapResult {
     pub return_amount: Uint128,
@@ -283,6 +200,54 @@ pub(crate) fn compute_swap(
     })
 }
 
+/// Returns the pool imbalance `1 - min_reserve / max_reserve`. Zero if the pool is empty.
+pub(crate) fn pool_imbalance(reserves: &[Decimal256]) -> StdResult<Decimal> {
+    match reserves.iter().minmax().into_option() {
+        Some((min, max)) if !max.is_zero() => try_dec256_into_dec(Decimal256::one() - *min / *max),
+        _ => Ok(Decimal::zero()),
+    }
+}
+
+/// Returns the swap fee rate. Dynamic fees replace the factory fee if they are enabled.
+///
+/// * **total_fee_rate** the factory fee rate.
+///
+/// * **offer_asset** asset that is being offered.
+///
+/// * **ask_asset** asset that leaves the pool before the commission is deducted.
+///
+/// * **pools** array with assets available in the pool before the swap.
+pub(crate) fn swap_fee_rate(
+    storage: &dyn Storage,
+    total_fee_rate: Decimal,
+    offer_asset: &DecimalAsset,
+    ask_asset: &DecimalAsset,
+    pools: &[DecimalAsset],
+) -> StdResult<Decimal> {
+    let Some(dynamic_fees) = DYNAMIC_FEES.may_load(storage)? else {
+        return Ok(total_fee_rate);
+    };
+
+    let reserves_before = pools.iter().map(|pool| pool.amount).collect_vec();
+    let reserves_after = pools
+        .iter()
+        .map(|pool| {
+            if pool.info == offer_asset.info {
+                pool.amount.checked_add(offer_asset.amount)
+            } else if pool.info == ask_asset.info {
+                pool.amount.checked_sub(ask_asset.amount)
+            } else {
+                Ok(pool.amount)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(dynamic_fees.fee_rate(
+        pool_imbalance(&reserves_before)?,
+        pool_imbalance(&reserves_after)?,
+    ))
+}
+
 /// Accumulate token prices for the assets in the pool.
 ///
 /// * **pools** array with assets available in the pool.
@@ -441,17 +406,10 @@ pub(crate) fn calculate_shares(
     let deposit_d = compute_d(amp, &new_balances)?;
 
     let share = if total_share.is_zero() {
-        let share = deposit_d
-            .to_uint128_with_precision(config.greatest_precision)?
-            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
-            .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+        let share = deposit_d.to_uint128_with_precision(config.greatest_precision)?;
 
         // share cannot become zero after minimum liquidity subtraction
-        if share.is_zero() {
-            return Err(ContractError::MinimumLiquidityAmountError {});
-        }
-
-        share
+        initial_share_after_lockup(share).ok_or(ContractError::MinimumLiquidityAmountError {})?
     } else {
         // Initial invariant (D)
         let old_balances = assets_collection
@@ -464,6 +422,7 @@ pub(crate) fn calculate_shares(
             .checked_multiply_ratio(deposit_d.saturating_sub(init_d), init_d)?
             .to_uint128_with_precision(config.greatest_precision)?;
 
+        // Donations to the pool must not round a provision down to zero LP tokens
         if share.is_zero() {
             return Err(ContractError::LiquidityAmountTooSmall {});
         }
@@ -473,6 +432,107 @@ pub(crate) fn calculate_shares(
     Ok(share)
 }
 
+/// Calculates the amount of LP tokens which must be burned to withdraw exact amounts of assets.
+/// The difference between the requested and the ideal (balanced) withdrawal is charged with an imbalance fee
+/// which stays in the pool.
+///
+/// * **pools** array with assets available in the pool.
+///
+/// * **assets** exact amounts of assets to withdraw. Omitted assets are not withdrawn.
+///
+/// * **total_share** total amount of LP tokens currently issued by the pool.
+///
+/// * **fee_rate** total swap fee rate of the pair.
+pub(crate) fn calculate_imbalanced_withdraw(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    pools: &[Asset],
+    assets: &[Asset],
+    total_share: Uint128,
+    fee_rate: Decimal,
+) -> Result<Uint128, ContractError> {
+    check_assets(deps.api, assets)?;
+
+    if assets.len() > pools.len() {
+        return Err(ContractError::InvalidNumberOfAssets(pools.len()));
+    }
+
+    if !assets.iter().map(|asset| &asset.info).all_unique() {
+        return Err(ContractError::DoublingAssets {});
+    }
+
+    if assets.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    if let Some(asset) = assets
+        .iter()
+        .find(|asset| !pools.iter().any(|pool| pool.info == asset.info))
+    {
+        return Err(ContractError::InvalidAsset(asset.info.to_string()));
+    }
+
+    let mut old_balances = vec![];
+    let mut new_balances = vec![];
+    for pool in pools {
+        let precision = get_precision(deps.storage, &pool.info)?;
+        let withdraw_amount = assets
+            .iter()
+            .find(|asset| asset.info == pool.info)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        // Withdrawing the whole pool balance would make the invariant undefined
+        if withdraw_amount >= pool.amount {
+            return Err(ContractError::InsufficientPoolLiquidity(
+                pool.info.to_string(),
+            ));
+        }
+
+        old_balances.push(Decimal256::with_precision(pool.amount, precision)?);
+        new_balances.push(Decimal256::with_precision(
+            pool.amount - withdraw_amount,
+            precision,
+        )?);
+    }
+
+    let amp = compute_current_amp(config, env)?;
+
+    // Initial invariant (D)
+    let init_d = compute_d(amp, &old_balances)?;
+    // Invariant (D) after assets withdrawn
+    let withdraw_d = compute_d(amp, &new_balances)?;
+
+    // Imbalance fee rate as in the Curve stableswap: fee * n / (4 * (n - 1))
+    let n_coins = pools.len() as u128;
+    let fee_rate = Decimal256::from(fee_rate)
+        .checked_mul(Decimal256::from_ratio(n_coins, 4 * (n_coins - 1)))?;
+
+    for (new_balance, old_balance) in new_balances.iter_mut().zip(&old_balances) {
+        let ideal_balance = withdraw_d.checked_multiply_ratio(*old_balance, init_d)?;
+        let fee = fee_rate.checked_mul(ideal_balance.abs_diff(*new_balance))?;
+        *new_balance = new_balance.saturating_sub(fee);
+    }
+
+    if new_balances.iter().any(Decimal256::is_zero) {
+        return Err(ContractError::LiquidityAmountTooSmall {});
+    }
+
+    // Invariant (D) after assets withdrawn and imbalance fee charged
+    let after_fee_d = compute_d(amp, &new_balances)?;
+
+    // Rounding errors are resolved in favor of the pool
+    let burn_amount: Uint128 = Uint256::from(total_share)
+        .checked_multiply_ratio(
+            init_d.atomics().saturating_sub(after_fee_d.atomics()),
+            init_d.atomics(),
+        )?
+        .checked_add(Uint256::one())?
+        .try_into()?;
+
+    Ok(burn_amount)
+}
+
 pub(crate) fn get_assets_collection(
     deps: Deps,
     config: &Config,
@@ -157,6 +157,7 @@ impl Helper {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             }],
             token_code_id,
             generator_address: None,
@@ -164,6 +165,7 @@ impl Helper {
             whitelist_code_id: 234u64,
             coin_registry_address: coin_registry_address.to_string(),
             tracker_config: None,
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
@@ -183,7 +185,15 @@ impl Helper {
         let init_pair_msg = astroport::factory::ExecuteMsg::CreatePair {
             pair_type: PairType::Stable {},
             asset_infos: asset_infos.clone(),
-            init_params: Some(to_json_binary(&StablePoolParams { amp, owner: None }).unwrap()),
+            init_params: Some(
+                to_json_binary(&StablePoolParams {
+                    amp,
+                    owner: None,
+                    lp_token_metadata: None,
+                    balance_caps: None,
+                })
+                .unwrap(),
+            ),
         };
 
         app.execute_contract(owner.clone(), factory.clone(), &init_pair_msg, &[])?;
@@ -219,6 +229,8 @@ impl Helper {
             auto_stake: None,
             receiver: None,
             min_lp_to_receive,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -238,6 +250,7 @@ impl Helper {
             &ExecuteMsg::WithdrawLiquidity {
                 assets,
                 min_assets_to_receive,
+                deadline: None,
             },
             &[coin(amount, self.lp_token.to_string())],
         )
@@ -259,6 +272,8 @@ impl Helper {
                         belief_price: None,
                         max_spread: None,
                         to: None,
+                        deadline: None,
+                        to_commitment: None,
                     })
                     .unwrap(),
                 };
@@ -280,6 +295,8 @@ impl Helper {
                     belief_price: None,
                     max_spread: None,
                     to: None,
+                    deadline: None,
+                    to_commitment: None,
                 };
 
                 self.app
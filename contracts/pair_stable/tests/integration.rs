@@ -7,9 +7,9 @@ use astroport::factory::{
 };
 use astroport::observation::OracleObservation;
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
-    PoolResponse, QueryMsg, StablePoolConfig, StablePoolParams, StablePoolUpdateParams,
-    MAX_FEE_SHARE_BPS, TWAP_PRECISION,
+    AccruedFees, AccruedFeesResponse, ConfigResponse, CumulativePricesResponse, Cw20HookMsg,
+    ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg, StablePoolConfig, StablePoolParams,
+    StablePoolUpdateParams, MAX_FEE_SHARE_BPS, TWAP_PRECISION,
 };
 
 use astroport_pair_stable::error::ContractError;
@@ -145,6 +145,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: None,
@@ -152,6 +153,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_addr = router
@@ -211,6 +213,8 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -473,6 +477,7 @@ fn test_provide_and_withdraw_liquidity() {
                         })
                         .collect(),
                 ),
+                deadline: None,
             },
             &[coin(100u128, lp_token.clone())],
         )
@@ -506,6 +511,7 @@ fn test_provide_and_withdraw_liquidity() {
             &ExecuteMsg::WithdrawLiquidity {
                 assets: vec![],
                 min_assets_to_receive: Some(min_assets_to_receive),
+                deadline: None,
             },
             &[coin(100u128, lp_token.clone())],
         )
@@ -519,6 +525,7 @@ fn test_provide_and_withdraw_liquidity() {
             &ExecuteMsg::WithdrawLiquidity {
                 assets: vec![],
                 min_assets_to_receive: None,
+                deadline: None,
             },
             &[coin(50u128, lp_token.clone())],
         )
@@ -550,6 +557,8 @@ fn provide_liquidity_msg(
         auto_stake: None,
         receiver,
         min_lp_to_receive,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -659,6 +668,7 @@ fn provide_lp_for_single_token() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -666,6 +676,7 @@ fn provide_lp_for_single_token() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -693,6 +704,8 @@ fn provide_lp_for_single_token() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -744,6 +757,8 @@ fn provide_lp_for_single_token() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -776,6 +791,8 @@ fn provide_lp_for_single_token() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let err = app
@@ -805,6 +822,8 @@ fn provide_lp_for_single_token() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -830,6 +849,8 @@ fn provide_lp_for_single_token() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -863,6 +884,8 @@ fn provide_lp_for_single_token() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -876,6 +899,8 @@ fn provide_lp_for_single_token() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -892,6 +917,8 @@ fn provide_lp_for_single_token() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: swap_amount,
@@ -999,6 +1026,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1006,6 +1034,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -1033,6 +1062,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1096,6 +1127,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -1116,6 +1149,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -1323,6 +1358,7 @@ fn update_pair_config() {
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1352,6 +1388,8 @@ fn update_pair_config() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1572,6 +1610,7 @@ fn enable_disable_fee_sharing() {
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1601,6 +1640,8 @@ fn enable_disable_fee_sharing() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1632,6 +1673,7 @@ fn enable_disable_fee_sharing() {
         params: to_json_binary(&StablePoolUpdateParams::EnableFeeShare {
             fee_share_bps: MAX_FEE_SHARE_BPS + 1,
             fee_share_address: "contract".to_string(),
+            callback: false,
         })
         .unwrap(),
     };
@@ -1650,6 +1692,7 @@ fn enable_disable_fee_sharing() {
         params: to_json_binary(&StablePoolUpdateParams::EnableFeeShare {
             fee_share_bps: 0,
             fee_share_address: "contract".to_string(),
+            callback: false,
         })
         .unwrap(),
     };
@@ -1671,6 +1714,7 @@ fn enable_disable_fee_sharing() {
         params: to_json_binary(&StablePoolUpdateParams::EnableFeeShare {
             fee_share_bps,
             fee_share_address: fee_share_address.clone(),
+            callback: false,
         })
         .unwrap(),
     };
@@ -1769,6 +1813,8 @@ fn check_observe_queries() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let send_funds = vec![Coin {
         denom: "uusd".to_owned(),
@@ -1865,6 +1911,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: None,
@@ -1872,6 +1919,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1896,6 +1944,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
                 guardian: None,
                 incentivization_fee_info: None,
                 vesting_contract: "vesting".to_string(),
+                ownership_timelock: None,
             },
             &[],
             "generator",
@@ -1932,6 +1981,8 @@ fn provide_liquidity_with_autostaking_to_generator() {
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -1963,6 +2014,8 @@ fn provide_liquidity_with_autostaking_to_generator() {
         auto_stake: Some(true),
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -2015,7 +2068,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
 }
 
 #[test]
-fn test_imbalance_withdraw_is_disabled() {
+fn test_imbalanced_withdraw() {
     let owner = Addr::unchecked("owner");
     let alice_address = Addr::unchecked("alice");
 
@@ -2102,7 +2155,6 @@ fn test_imbalance_withdraw_is_disabled() {
         .execute_contract(alice_address.clone(), pair_instance.clone(), &msg, &coins)
         .unwrap();
 
-    // Check that imbalanced withdraw is currently disabled
     let msg_imbalance = ExecuteMsg::WithdrawLiquidity {
         assets: vec![Asset {
             info: AssetInfo::NativeToken {
@@ -2111,19 +2163,73 @@ fn test_imbalance_withdraw_is_disabled() {
             amount: Uint128::from(100u8),
         }],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
+    // Withdrawing 100 uusd out of a balanced pool costs a bit more than 100 LP tokens
     let err = router
         .execute_contract(
             alice_address.clone(),
             pair_instance.clone(),
             &msg_imbalance,
-            &[coin(100u128, lp_token)],
+            &[coin(100u128, &lp_token)],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotEnoughLpTokens(amount) if amount > Uint128::new(100)
+    ));
+
+    let uusd_before = router.wrap().query_balance(&alice_address, "uusd").unwrap();
+    let lp_before = router
+        .wrap()
+        .query_balance(&alice_address, &lp_token)
+        .unwrap();
+
+    router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &msg_imbalance,
+            &[coin(200u128, &lp_token)],
+        )
+        .unwrap();
+
+    let uusd_after = router.wrap().query_balance(&alice_address, "uusd").unwrap();
+    let lp_after = router
+        .wrap()
+        .query_balance(&alice_address, &lp_token)
+        .unwrap();
+
+    // Exact amount received, unused LP tokens returned
+    assert_eq!(uusd_after.amount - uusd_before.amount, Uint128::new(100));
+    let burned = lp_before.amount - lp_after.amount;
+    assert!(
+        burned > Uint128::new(100) && burned < Uint128::new(110),
+        "burned {burned}"
+    );
+
+    // The pool can't be drained with an imbalanced withdraw
+    let err = router
+        .execute_contract(
+            alice_address.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::WithdrawLiquidity {
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    amount: Uint128::new(200_000),
+                }],
+                min_assets_to_receive: None,
+                deadline: None,
+            },
+            &[coin(100_000u128, &lp_token)],
         )
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "Generic error: Imbalanced withdraw is currently disabled"
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InsufficientPoolLiquidity("uluna".to_string())
     );
 }
 
@@ -2354,6 +2460,7 @@ fn test_fee_share(
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -2361,6 +2468,7 @@ fn test_fee_share(
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -2388,6 +2496,8 @@ fn test_fee_share(
             to_json_binary(&StablePoolParams {
                 amp: 100,
                 owner: Some(owner.to_string()),
+                lp_token_metadata: None,
+                balance_caps: None,
             })
             .unwrap(),
         ),
@@ -2451,6 +2561,8 @@ fn test_fee_share(
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
@@ -2469,6 +2581,7 @@ fn test_fee_share(
         params: to_json_binary(&StablePoolUpdateParams::EnableFeeShare {
             fee_share_bps,
             fee_share_address: fee_share_address.clone(),
+            callback: false,
         })
         .unwrap(),
     };
@@ -2485,6 +2598,8 @@ fn test_fee_share(
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -2540,4 +2655,16 @@ fn test_fee_share(
         res.assets[1].amount,
         y_amount - y_expected_return - expected_maker_fee - expected_fee_share
     );
+
+    // Fees are accounted in the ask asset only
+    let res: Vec<AccruedFeesResponse> = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::AccruedFees {})
+        .unwrap();
+    assert_eq!(res[0].fees, AccruedFees::default());
+    let fees = &res[1].fees;
+    assert_eq!(fees.maker, expected_maker_fee);
+    assert_eq!(fees.fee_share, expected_fee_share);
+    assert_eq!(fees.total, fees.maker + fees.fee_share + fees.lp);
+    assert!(!fees.lp.is_zero());
 }
@@ -1,15 +1,26 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{Addr, Decimal, StdError};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Response, StdError, StdResult,
+};
 use itertools::Itertools;
 use std::str::FromStr;
 
-use astroport::asset::AssetInfoExt;
+use astroport::asset::{native_asset_info, AssetInfoExt, MINIMUM_LIQUIDITY_AMOUNT};
 use astroport::cosmwasm_ext::AbsDiff;
+use astroport::factory::{ExecuteMsg as FactoryExecuteMsg, PairType, QueryMsg as FactoryQueryMsg};
+use astroport::lp_pricing::{query_lp_token_price, query_virtual_price, VirtualPriceResponse};
 use astroport::observation::OracleObservation;
+use astroport::pair::{
+    ConfigResponse, DynamicFees, ExecuteMsg, FeeShareCallbackMsg, QueryMsg, StablePoolConfig,
+    StablePoolParams, StablePoolUpdateParams,
+};
 use astroport_pair_stable::error::ContractError;
 use astroport_test::coins::TestCoin;
 use astroport_test::convert::f64_to_dec;
+use astroport_test::cw_multi_test::{ContractWrapper, Executor};
+use astroport_test::pair::check_first_depositor_donation_attack;
 use helper::AppExtension;
 
 use crate::helper::Helper;
@@ -136,6 +147,22 @@ fn provide_and_withdraw_no_fee() {
     assert_eq!(0, helper.coin_balance(&test_coins[2], &user3));
 }
 
+#[test]
+fn first_depositor_donation_attack() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+    let mut helper = Helper::new(&owner, test_coins, 100u64, None).unwrap();
+
+    // Invariant D of a balanced deposit equals the sum of the deposited amounts
+    check_first_depositor_donation_attack(
+        &mut helper.app,
+        &helper.pair_addr,
+        MINIMUM_LIQUIDITY_AMOUNT.u128() / 2 + 1,
+        ContractError::MinimumLiquidityAmountError {},
+        ContractError::LiquidityAmountTooSmall {},
+    );
+}
+
 #[test]
 fn provide_with_different_precision() {
     let owner = Addr::unchecked("owner");
@@ -338,6 +365,129 @@ fn check_wrong_initializations() {
     .unwrap();
 }
 
+#[test]
+fn check_decimals_guard_rails() {
+    let owner = Addr::unchecked("owner");
+
+    let err = Helper::new(
+        &owner,
+        vec![TestCoin::native("unregistered"), TestCoin::cw20("two")],
+        100u64,
+        None,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Failed to resolve decimals of unregistered"));
+
+    let err = Helper::new(
+        &owner,
+        vec![TestCoin::native("uluna"), TestCoin::cw20precise("big", 24)],
+        100u64,
+        None,
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("has 24 decimals which exceeds the maximum of 18"));
+
+    let mut helper = Helper::new(
+        &owner,
+        vec![TestCoin::native("uluna"), TestCoin::cw20("two")],
+        100u64,
+        None,
+    )
+    .unwrap();
+
+    let exotic = native_asset_info("uexotic".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let create_pair_msg = FactoryExecuteMsg::CreatePair {
+        pair_type: PairType::Stable {},
+        asset_infos: vec![exotic.clone(), uluna.clone()],
+        init_params: Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
+            })
+            .unwrap(),
+        ),
+    };
+
+    let err = helper
+        .app
+        .execute_contract(owner.clone(), helper.factory.clone(), &create_pair_msg, &[])
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Failed to resolve decimals of uexotic"));
+
+    // Only the factory owner can set decimals overrides
+    helper
+        .app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::SetDecimalsOverride {
+                asset_info: exotic.clone(),
+                decimals: Some(8),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::SetDecimalsOverride {
+                asset_info: exotic.clone(),
+                decimals: Some(19),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Decimals override cannot be higher than 18"
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::SetDecimalsOverride {
+                asset_info: exotic.clone(),
+                decimals: Some(8),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let decimals: Option<u8> = helper
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &helper.factory,
+            &FactoryQueryMsg::DecimalsOverride {
+                asset_info: exotic.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(decimals, Some(8));
+
+    helper
+        .app
+        .execute_contract(owner.clone(), helper.factory.clone(), &create_pair_msg, &[])
+        .unwrap();
+}
+
 #[ignore]
 #[test]
 fn check_withdraw_charges_fees() {
@@ -602,3 +752,786 @@ fn check_pool_prices() {
     );
     helper.app.next_block(10);
 }
+
+#[test]
+fn check_balance_caps() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let update_caps_msg = |caps| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&StablePoolUpdateParams::UpdateBalanceCaps { caps }).unwrap(),
+    };
+
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &update_caps_msg(vec![
+                native_asset_info("uatom".to_string()).with_balance(1u8)
+            ]),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Asset uatom is not in the pair"
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &update_caps_msg(vec![
+                helper.assets[&test_coins[0]].with_balance(150_000_000000u128)
+            ]),
+            &[],
+        )
+        .unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    let err = helper.provide_liquidity(&owner, &assets, None).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Pool balance of uluna would exceed its cap of 150000000000"
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &update_caps_msg(vec![
+                helper.assets[&test_coins[0]].with_balance(200_000_000000u128)
+            ]),
+            &[],
+        )
+        .unwrap();
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+}
+
+#[test]
+fn check_circuit_breaker() {
+    let owner = Addr::unchecked("owner");
+    let guardian = Addr::unchecked("guardian");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    let err = helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::SetCircuitBreaker {
+                    guardian: guardian.to_string(),
+                    max_ratio_change: Decimal::zero(),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidMaxRatioChange {},
+        err.downcast().unwrap()
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::SetCircuitBreaker {
+                    guardian: guardian.to_string(),
+                    max_ratio_change: Decimal::percent(10),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let query_tripped_at = |helper: &Helper| {
+        let config: ConfigResponse = helper
+            .app
+            .wrap()
+            .query_wasm_smart(&helper.pair_addr, &QueryMsg::Config {})
+            .unwrap();
+        from_json::<StablePoolConfig>(config.params.unwrap())
+            .unwrap()
+            .circuit_breaker
+            .unwrap()
+            .tripped_at
+    };
+
+    // ~2% reserve ratio change is fine
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000_000000u128);
+    helper.swap(&owner, &offer_asset, None).unwrap();
+    assert_eq!(query_tripped_at(&helper), None);
+
+    // ~18% reserve ratio change is rejected and doesn't halt the pair
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000_000000u128);
+    let err = helper.swap(&owner, &offer_asset, None).unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReserveRatioChangeTooLarge { max, .. } if max == Decimal::percent(10)
+    ));
+    assert_eq!(query_tripped_at(&helper), None);
+
+    // So is an imbalanced withdrawal
+    let err = helper
+        .withdraw_liquidity(
+            &owner,
+            50_000_000000,
+            vec![helper.assets[&test_coins[0]].with_balance(30_000_000000u128)],
+            None,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReserveRatioChangeTooLarge { .. }
+    ));
+
+    let err = helper
+        .app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    helper
+        .app
+        .execute_contract(
+            guardian.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        query_tripped_at(&helper),
+        Some(helper.app.block_info().time.seconds())
+    );
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000_000000u128);
+    let err = helper.swap(&owner, &offer_asset, None).unwrap_err();
+    assert_eq!(ContractError::PairHalted {}, err.downcast().unwrap());
+    let err = helper.provide_liquidity(&owner, &assets, None).unwrap_err();
+    assert_eq!(ContractError::PairHalted {}, err.downcast().unwrap());
+
+    helper
+        .withdraw_liquidity(&owner, 1_000000, vec![], None)
+        .unwrap();
+
+    let err = helper
+        .app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ResumePair {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    helper
+        .app
+        .execute_contract(
+            guardian.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::ResumePair {},
+            &[],
+        )
+        .unwrap();
+    assert_eq!(query_tripped_at(&helper), None);
+
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(1_000_000000u128);
+    helper.swap(&owner, &offer_asset, None).unwrap();
+}
+
+#[test]
+fn check_flash_loan_guards() {
+    let owner = Addr::unchecked("owner");
+    let borrower = Addr::unchecked("borrower");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::EnableFlashLoans { fee_bps: 30 })
+                    .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::UpdateFlashLoans { enabled: true },
+            &[],
+        )
+        .unwrap();
+
+    let flash_loan_msg = ExecuteMsg::FlashLoan {
+        asset: helper.assets[&test_coins[0]].with_balance(1_000000u128),
+        msg: to_json_binary(&1u8).unwrap(),
+    };
+
+    // Paused pairs don't lend
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::UpdatePauseState {
+                pair_creation: None,
+                all_pairs: None,
+                pause: Some(vec![helper.pair_addr.to_string()]),
+                unpause: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let err = helper
+        .app
+        .execute_contract(
+            borrower.clone(),
+            helper.pair_addr.clone(),
+            &flash_loan_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::PairPaused {}, err.downcast().unwrap());
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &FactoryExecuteMsg::UpdatePauseState {
+                pair_creation: None,
+                all_pairs: None,
+                pause: None,
+                unpause: Some(vec![helper.pair_addr.to_string()]),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Neither do halted pairs
+    let guardian = Addr::unchecked("guardian");
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::SetCircuitBreaker {
+                    guardian: guardian.to_string(),
+                    max_ratio_change: Decimal::percent(10),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    helper
+        .app
+        .execute_contract(
+            guardian,
+            helper.pair_addr.clone(),
+            &ExecuteMsg::HaltPair {},
+            &[],
+        )
+        .unwrap();
+    let err = helper
+        .app
+        .execute_contract(borrower, helper.pair_addr.clone(), &flash_loan_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::PairHalted {}, err.downcast().unwrap());
+}
+
+#[test]
+fn check_dynamic_fees() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    // 5 bps factory fee
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    let set_dynamic_fees = |helper: &mut Helper, base_fee_bps: u16, max_fee_bps: u16| {
+        helper.app.execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::SetDynamicFees {
+                    base_fee_bps,
+                    max_fee_bps,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+    };
+
+    for (base_fee_bps, max_fee_bps) in [(10, 5), (10, 2000)] {
+        let err = set_dynamic_fees(&mut helper, base_fee_bps, max_fee_bps).unwrap_err();
+        assert_eq!(
+            ContractError::DynamicFeesOutOfBounds {},
+            err.downcast().unwrap()
+        );
+    }
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000_000000u128);
+    let factory_fee_sim = helper.simulate_swap(&offer_asset, None).unwrap();
+
+    set_dynamic_fees(&mut helper, 5, 100).unwrap();
+    let config: ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.pair_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        from_json::<StablePoolConfig>(config.params.unwrap())
+            .unwrap()
+            .dynamic_fees,
+        Some(DynamicFees {
+            base_fee_bps: 5,
+            max_fee_bps: 100,
+        })
+    );
+
+    // Swaps which unbalance the pool are charged more than the base fee
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    assert!(sim.commission_amount > factory_fee_sim.commission_amount);
+    assert_eq!(
+        sim.return_amount + sim.commission_amount,
+        factory_fee_sim.return_amount + factory_fee_sim.commission_amount
+    );
+
+    let user = Addr::unchecked("user");
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+    assert_eq!(
+        sim.return_amount.u128(),
+        helper.coin_balance(&test_coins[1], &user)
+    );
+
+    // Swaps which restore the balance are charged less than the base fee
+    let offer_asset = helper.assets[&test_coins[1]].with_balance(5_000_000000u128);
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    let base_commission =
+        Decimal::from_ratio(5u16, 10000u16) * (sim.return_amount + sim.commission_amount);
+    assert!(sim.commission_amount < base_commission);
+
+    // Reverse simulation charges the same dynamic fee as the forward swap
+    let reverse_sim = helper
+        .simulate_reverse_swap(
+            &helper.assets[&test_coins[0]].with_balance(sim.return_amount),
+            None,
+        )
+        .unwrap();
+    assert_eq!(reverse_sim.offer_amount, offer_asset.amount);
+
+    let ask_asset = helper.assets[&test_coins[0]].with_balance(4_000_000000u128);
+    let reverse_sim = helper.simulate_reverse_swap(&ask_asset, None).unwrap();
+    let sim = helper
+        .simulate_swap(
+            &helper.assets[&test_coins[1]].with_balance(reverse_sim.offer_amount),
+            None,
+        )
+        .unwrap();
+    assert!(
+        sim.return_amount >= ask_asset.amount,
+        "{} < {}",
+        sim.return_amount,
+        ask_asset.amount
+    );
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::DisableDynamicFees).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    assert_eq!(
+        sim.commission_amount,
+        Decimal::from_ratio(5u16, 10000u16) * (sim.return_amount + sim.commission_amount)
+    );
+}
+
+#[test]
+fn check_refresh_precisions() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let factory_config: astroport::factory::ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &FactoryQueryMsg::Config {})
+        .unwrap();
+    let coin_registry = factory_config.coin_registry_address;
+    let refresh_msg = ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&StablePoolUpdateParams::RefreshPrecisions {}).unwrap(),
+    };
+
+    // uusd precision was registered incorrectly
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            coin_registry.clone(),
+            &astroport::native_coin_registry::ExecuteMsg::Add {
+                native_coins: vec![("uusd".to_string(), 7)],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = helper
+        .app
+        .execute_contract(
+            Addr::unchecked("random"),
+            helper.pair_addr.clone(),
+            &refresh_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    // The pool is empty thus precisions can be changed
+    helper
+        .app
+        .execute_contract(owner.clone(), helper.pair_addr.clone(), &refresh_msg, &[])
+        .unwrap();
+
+    // Reserves are equal in value with uusd having 7 decimals
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(1_000_000_0000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(1_000000u128);
+    let sim = helper.simulate_swap(&offer_asset, None).unwrap();
+    assert!(sim.return_amount.u128() > 9_990000, "{sim:?}");
+
+    // Precisions can't change once the pool has liquidity
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            coin_registry.clone(),
+            &astroport::native_coin_registry::ExecuteMsg::Add {
+                native_coins: vec![("uusd".to_string(), 6)],
+            },
+            &[],
+        )
+        .unwrap();
+    let err = helper
+        .app
+        .execute_contract(owner.clone(), helper.pair_addr.clone(), &refresh_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PrecisionChangeWithLiquidity {},
+        err.downcast().unwrap()
+    );
+
+    // Swaps, provisions and simulations don't query the registry at all:
+    // they keep working after both coins are removed from it
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            coin_registry,
+            &astroport::native_coin_registry::ExecuteMsg::Remove {
+                native_coins: vec!["uluna".to_string(), "uusd".to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(helper.simulate_swap(&offer_asset, None).unwrap(), sim);
+    helper
+        .simulate_reverse_swap(
+            &helper.assets[&test_coins[1]].with_balance(1_000000u128),
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    // Refreshing precisions is the only operation which queries the registry
+    helper
+        .app
+        .execute_contract(owner.clone(), helper.pair_addr.clone(), &refresh_msg, &[])
+        .unwrap_err();
+}
+
+#[test]
+fn check_fee_share_callback() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, Some(100u16)).unwrap();
+
+    // Mock recipient which records every callback in its response attributes
+    let recipient_code_id = helper
+        .app
+        .store_code(Box::new(ContractWrapper::new_with_empty(
+            |_: DepsMut,
+             _: Env,
+             info: MessageInfo,
+             msg: FeeShareCallbackMsg|
+             -> StdResult<Response> {
+                match msg {
+                    FeeShareCallbackMsg::FeeShareReceived { asset } => Ok(Response::new()
+                        .add_attribute("fee_share_sender", info.sender)
+                        .add_attribute("fee_share_asset", asset.to_string())),
+                }
+            },
+            |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+                Ok(Response::new())
+            },
+            |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { unimplemented!() },
+        )));
+    let share_recipient = helper
+        .app
+        .instantiate_contract(
+            recipient_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Fee share recipient",
+            None,
+        )
+        .unwrap();
+
+    helper
+        .app
+        .execute_contract(
+            owner.clone(),
+            helper.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&StablePoolUpdateParams::EnableFeeShare {
+                    fee_share_bps: 1000,
+                    fee_share_address: share_recipient.to_string(),
+                    callback: true,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let config: ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.pair_addr, &QueryMsg::Config {})
+        .unwrap();
+    let params: StablePoolConfig = from_json(config.params.unwrap()).unwrap();
+    assert!(params.fee_share.unwrap().callback);
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets, None).unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(100_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let res = helper.swap(&user, &offer_asset, None).unwrap();
+
+    // 10% of 1% commission is shared and the recipient is notified about it
+    let fee_share = helper.coin_balance(&test_coins[1], &share_recipient);
+    assert!(fee_share.abs_diff(100_000) <= 10, "fee share {fee_share}");
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("fee_share_sender", helper.pair_addr.to_string())
+            .add_attribute(
+                "fee_share_asset",
+                helper.assets[&test_coins[1]]
+                    .with_balance(fee_share)
+                    .to_string()
+            )
+    ));
+}
+
+#[test]
+fn check_min_trade_notional() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    // Pairs read the minimum at instantiation thus the pair is recreated after the config update
+    let factory_config: astroport::factory::ConfigResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &FactoryQueryMsg::Config {})
+        .unwrap();
+    let mut pair_config = factory_config.pair_configs[0].clone();
+    pair_config.min_trade_notional = Some(Decimal::from_ratio(5u8, 1u8));
+    let asset_infos = vec![
+        helper.assets[&test_coins[0]].clone(),
+        helper.assets[&test_coins[1]].clone(),
+    ];
+    for msg in [
+        FactoryExecuteMsg::UpdatePairConfig {
+            config: pair_config,
+        },
+        FactoryExecuteMsg::Deregister {
+            asset_infos: asset_infos.clone(),
+        },
+        FactoryExecuteMsg::CreatePair {
+            pair_type: PairType::Stable {},
+            asset_infos: asset_infos.clone(),
+            init_params: Some(
+                to_json_binary(&StablePoolParams {
+                    amp: 100,
+                    owner: None,
+                    lp_token_metadata: None,
+                    balance_caps: None,
+                })
+                .unwrap(),
+            ),
+        },
+    ] {
+        helper
+            .app
+            .execute_contract(owner.clone(), helper.factory.clone(), &msg, &[])
+            .unwrap();
+    }
+    let pair_info: astroport::asset::PairInfo = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.factory, &FactoryQueryMsg::Pair { asset_infos })
+        .unwrap();
+    helper.pair_addr = pair_info.contract_addr;
+    helper.lp_token = pair_info.liquidity_token;
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(4_999999u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    let err = helper.swap(&user, &offer_asset, None).unwrap_err();
+    assert_eq!(
+        ContractError::TradeTooSmall {
+            min: Decimal::from_ratio(5u8, 1u8),
+            notional: Decimal256::from_str("4.999999").unwrap(),
+        },
+        err.downcast().unwrap()
+    );
+
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(5_000000u128);
+    helper.give_me_money(&[offer_asset.clone()], &user);
+    helper.swap(&user, &offer_asset, None).unwrap();
+}
+
+#[test]
+fn check_virtual_price() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::native("uusd")];
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let err = helper
+        .app
+        .wrap()
+        .query_wasm_smart::<VirtualPriceResponse>(&helper.pair_addr, &QueryMsg::VirtualPrice {})
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: Generic error: Virtual price is not defined for an empty pool"
+        )
+    );
+
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000_000000u128),
+    ];
+    helper.provide_liquidity(&owner, &assets).unwrap();
+
+    let resp: VirtualPriceResponse = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&helper.pair_addr, &QueryMsg::VirtualPrice {})
+        .unwrap();
+    assert_eq!(resp.virtual_price.u128(), 1_000000);
+
+    // Swap fees increase the invariant
+    let offer_asset = helper.assets[&test_coins[0]].with_balance(10_000_000000u128);
+    helper.swap(&owner, &offer_asset, None).unwrap();
+    let virtual_price = query_virtual_price(&helper.app.wrap(), &helper.pair_addr).unwrap();
+    assert!(virtual_price > Decimal256::one());
+
+    // LP tokens are priced by the cheapest pool asset
+    let lp_price = query_lp_token_price(
+        &helper.app.wrap(),
+        &helper.pair_addr,
+        &[Decimal256::one(), Decimal256::percent(95)],
+    )
+    .unwrap();
+    assert_eq!(lp_price, virtual_price * Decimal256::percent(95));
+}
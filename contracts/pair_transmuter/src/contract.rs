@@ -1,23 +1,26 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, ensure, ensure_eq, BankMsg, Coin, DepsMut, Empty, Env, MessageInfo, Reply,
-    Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+    coin, ensure, ensure_eq, BankMsg, Coin, DepsMut, Empty, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
 };
 use cw2::set_contract_version;
 use cw_utils::{one_coin, PaymentError};
-use itertools::Itertools;
 
 use astroport::asset::{addr_opt_validate, Asset, AssetInfo, CoinsExt, PairInfo};
 use astroport::common::LP_SUBDENOM;
+use astroport::events::{ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent};
 use astroport::factory::PairType;
-use astroport::pair::{ExecuteMsg, InstantiateMsg};
+use astroport::pair::{
+    check_deadline, check_recipient_commitment, ExecuteMsg, InstantiateMsg, PairCreationInfo,
+};
+use astroport::querier::query_pair_paused;
 use astroport::token_factory::{
     tf_burn_msg, tf_create_denom_msg, tf_mint_msg, MsgCreateDenomResponse,
 };
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CREATION_INFO};
 use crate::utils::{
     assert_and_swap, check_asset_infos, check_assets, get_share_in_assets, pool_info,
 };
@@ -56,6 +59,7 @@ pub fn instantiate(
     let config = Config::new(deps.querier, pair_info, factory_addr)?;
 
     CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
 
     // Create LP token
     let sub_msg = SubMsg::reply_on_success(
@@ -108,8 +112,11 @@ pub fn execute(
             assets,
             auto_stake,
             receiver,
+            deadline,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+
             ensure!(
                 auto_stake.is_none() || matches!(auto_stake, Some(false)),
                 StdError::generic_err("Auto stake is not supported")
@@ -121,9 +128,22 @@ pub fn execute(
             offer_asset,
             to,
             ask_asset_info,
+            deadline,
+            to_commitment,
             ..
-        } => swap(deps, info, offer_asset, ask_asset_info, to),
-        ExecuteMsg::WithdrawLiquidity { assets, .. } => withdraw_liquidity(deps, env, info, assets),
+        } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
+            swap(deps, env, info, offer_asset, ask_asset_info, to)
+        }
+        ExecuteMsg::WithdrawLiquidity {
+            assets, deadline, ..
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets)
+        }
         _ => Err(ContractError::NotSupported {}),
     }
 }
@@ -202,10 +222,9 @@ pub fn withdraw_liquidity(
     };
 
     let send_msgs = refund_assets
-        .clone()
-        .into_iter()
+        .iter()
         .filter(|asset| !asset.amount.is_zero())
-        .map(|asset| asset.into_msg(&info.sender))
+        .map(|asset| asset.clone().into_msg(&info.sender))
         .collect::<StdResult<Vec<_>>>()?;
     messages.extend(send_msgs);
     messages.push(tf_burn_msg(
@@ -213,11 +232,15 @@ pub fn withdraw_liquidity(
         coin(amount.u128(), config.pair_info.liquidity_token.to_string()),
     ));
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("withdrawn_share", amount),
-        attr("refund_assets", refund_assets.iter().join(", ")),
-    ]))
+    Ok(Response::new().add_messages(messages).add_attributes(
+        WithdrawLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            withdrawn_share: amount,
+            refund_assets,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Provides liquidity with the specified input parameters.
@@ -235,6 +258,12 @@ pub fn provide_liquidity(
     check_assets(deps.api, &assets)?;
 
     let config = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
 
@@ -255,12 +284,16 @@ pub fn provide_liquidity(
             coin.clone(),
             receiver.clone(),
         ))
-        .add_attributes([
-            attr("action", "provide_liquidity"),
-            attr("receiver", receiver),
-            attr("assets", assets.iter().join(", ")),
-            attr("share", share),
-        ]))
+        .add_attributes(
+            ProvideLiquidityEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                receiver,
+                assets,
+                share,
+            }
+            .into_attributes(),
+        ))
 }
 
 /// Performs an swap operation with the specified parameters.
@@ -272,6 +305,7 @@ pub fn provide_liquidity(
 /// * **to** sets the recipient of the swap operation.
 pub fn swap(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     offer_asset: Asset,
     ask_asset_info: Option<AssetInfo>,
@@ -279,25 +313,35 @@ pub fn swap(
 ) -> Result<Response, ContractError> {
     offer_asset.assert_sent_native_token_balance(&info)?;
 
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let return_asset = assert_and_swap(deps.as_ref(), &offer_asset, ask_asset_info)?;
 
     let receiver = addr_opt_validate(deps.api, &to)?.unwrap_or_else(|| info.sender.clone());
 
-    let attrs = [
-        attr("action", "swap"),
-        attr("receiver", &receiver),
-        attr("offer_asset", offer_asset.info.to_string()),
-        attr("ask_asset", return_asset.info.to_string()),
-        attr("offer_amount", offer_asset.amount),
-        attr("return_amount", return_asset.amount),
-        attr("spread_amount", "0"),
-        attr("commission_amount", "0"),
-        attr("maker_fee_amount", "0"),
-    ];
-
-    let send_msg = return_asset.into_msg(&receiver)?;
-
-    Ok(Response::new().add_message(send_msg).add_attributes(attrs))
+    let send_msg = return_asset.clone().into_msg(&receiver)?;
+
+    // Transmuter swaps 1:1 without spread and fees
+    let event = SwapEvent {
+        pool: env.contract.address,
+        sender: info.sender,
+        receiver,
+        offer_asset,
+        return_asset,
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+        maker_fee_amount: Uint128::zero(),
+        fee_share_amount: Uint128::zero(),
+    };
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_attributes(event.into_attributes()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -56,4 +56,7 @@ pub enum ContractError {
 
     #[error("ask_asset_info must be set for pools with >2 assets")]
     AskAssetMustBeSet {},
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
 }
@@ -3,11 +3,12 @@ use cosmwasm_std::{entry_point, to_json_binary, Binary, Deps, Env, StdResult, Ui
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use astroport::pair::{
     ConfigResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    COMMISSION_ASSET,
 };
 use astroport::querier::query_factory_config;
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CREATION_INFO};
 use crate::utils::{assert_and_swap, get_share_in_assets, pool_info};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -41,6 +42,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
                 commission_amount: Uint128::zero(),
             })?)
         }
+        QueryMsg::CommissionAsset {} => Ok(to_json_binary(&COMMISSION_ASSET)?),
         _ => Err(ContractError::NotSupported {}),
     }
 }
@@ -70,6 +72,8 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
         tracker_addr: None,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
     })
 }
 
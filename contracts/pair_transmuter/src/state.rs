@@ -5,6 +5,7 @@ use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Uint128};
 use cw_storage_plus::Item;
 
 use astroport::asset::{Asset, AssetInfoExt, PairInfo};
+use astroport::pair::PairCreationInfo;
 
 use crate::error::ContractError;
 
@@ -64,3 +65,6 @@ impl Config {
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
@@ -172,6 +172,7 @@ impl Helper {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: true,
+                min_trade_notional: None,
             }],
             token_code_id,
             generator_address: None,
@@ -179,6 +180,7 @@ impl Helper {
             whitelist_code_id: 0,
             coin_registry_address: coin_registry_address.to_string(),
             tracker_config: None,
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
@@ -229,6 +231,8 @@ impl Helper {
             auto_stake: None,
             receiver: None,
             min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -247,6 +251,7 @@ impl Helper {
             &ExecuteMsg::WithdrawLiquidity {
                 assets,
                 min_assets_to_receive: None,
+                deadline: None,
             },
             &[coin(amount, self.lp_token.clone())],
         )
@@ -269,6 +274,8 @@ impl Helper {
                         belief_price: None,
                         max_spread: None,
                         to,
+                        deadline: None,
+                        to_commitment: None,
                     })
                     .unwrap(),
                 };
@@ -290,6 +297,8 @@ impl Helper {
                     belief_price: None,
                     max_spread: None,
                     to,
+                    deadline: None,
+                    to_commitment: None,
                 };
 
                 self.app
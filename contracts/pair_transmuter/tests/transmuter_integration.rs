@@ -1,6 +1,7 @@
 use cosmwasm_std::{Addr, StdError};
 
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::factory::PairType;
 use astroport::pair::{
     ConfigResponse, CumulativePricesResponse, ExecuteMsg, QueryMsg, ReverseSimulationResponse,
     SimulationResponse,
@@ -105,6 +106,8 @@ fn test_provide_and_withdraw() {
                 auto_stake: Some(true),
                 receiver: None,
                 min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
             },
             &[
                 helper.assets[&test_coins[0]]
@@ -135,6 +138,8 @@ fn test_provide_and_withdraw() {
                 auto_stake: Some(false),
                 receiver: None,
                 min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
             },
             &[
                 helper.assets[&test_coins[0]]
@@ -479,6 +484,8 @@ fn test_provide_liquidity_without_funds() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let err = helper
@@ -536,7 +543,9 @@ fn test_queries() {
             params: None,
             owner: owner.clone(),
             factory_addr: helper.factory.clone(),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Custom("transmuter".to_string())),
+            created_at: pool_info.created_at.clone()
         }
     );
 
@@ -660,6 +669,8 @@ fn test_drain_pool() {
                 belief_price: None,
                 max_spread: None,
                 to: None,
+                deadline: None,
+                to_commitment: None,
             },
             &[],
         )
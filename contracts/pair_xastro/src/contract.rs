@@ -1,15 +1,16 @@
 use cosmwasm_std::{
-    attr, ensure, from_json, wasm_execute, Addr, DepsMut, Env, MessageInfo, QuerierWrapper,
-    Response, StdError, Uint128,
+    ensure, from_json, wasm_execute, Addr, DepsMut, Env, MessageInfo, QuerierWrapper, Response,
+    StdError, Uint128,
 };
 
 use astroport::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
-use astroport::pair::ExecuteMsg;
+use astroport::events::SwapEvent;
+use astroport::pair::{check_deadline, check_recipient_commitment, ExecuteMsg, PairCreationInfo};
 use astroport::pair_xastro::XastroPairInitParams;
 use astroport::{pair, staking};
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CREATION_INFO};
 
 /// Contract name that is used for migration.
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -67,6 +68,7 @@ pub fn instantiate(
             xastro_denom: staking_config.xastro_denom,
         },
     )?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
 
     Ok(Response::new())
 }
@@ -82,20 +84,28 @@ pub fn instantiate(
 ///             belief_price,
 ///             max_spread,
 ///             to,
+///             deadline,
 ///         }** Performs a swap operation with the specified parameters.
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Swap {
-            offer_asset, to, ..
+            offer_asset,
+            to,
+            deadline,
+            to_commitment,
+            ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             offer_asset.assert_sent_native_token_balance(&info)?;
-            swap(deps, info.sender, offer_asset, to)
+            swap(deps, env, info.sender, offer_asset, to)
         }
         _ => Err(ContractError::NotSupported {}),
     }
@@ -110,6 +120,7 @@ pub fn execute(
 /// * **to_addr** sets the recipient of the swap operation.
 pub fn swap(
     deps: DepsMut,
+    env: Env,
     sender: Addr,
     offer_asset: Asset,
     to_addr: Option<String>,
@@ -130,18 +141,21 @@ pub fn swap(
 
             let mint_amount = predict_stake(deps.querier, &config, offer_asset.amount)?;
 
-            Ok(Response::new().add_message(stake_msg).add_attributes([
-                attr("action", "swap"),
-                attr("receiver", receiver),
-                attr("offer_asset", &config.astro_denom),
-                attr("ask_asset", &config.xastro_denom),
-                attr("offer_amount", offer_asset.amount),
-                attr("return_amount", mint_amount),
-                attr("spread_amount", "0"),
-                attr("commission_amount", "0"),
-                attr("maker_fee_amount", "0"),
-                attr("fee_share_amount", "0"),
-            ]))
+            let event = SwapEvent {
+                pool: env.contract.address,
+                sender,
+                receiver,
+                return_asset: Asset::native(&config.xastro_denom, mint_amount),
+                offer_asset,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+                maker_fee_amount: Uint128::zero(),
+                fee_share_amount: Uint128::zero(),
+            };
+
+            Ok(Response::new()
+                .add_message(stake_msg)
+                .add_attributes(event.into_attributes()))
         }
         AssetInfo::NativeToken { denom } if denom == &config.xastro_denom => {
             let unstake_msg = wasm_execute(
@@ -154,18 +168,21 @@ pub fn swap(
 
             let return_amount = predict_unstake(deps.querier, &config, offer_asset.amount)?;
 
-            Ok(Response::new().add_message(unstake_msg).add_attributes([
-                attr("action", "swap"),
-                attr("receiver", receiver),
-                attr("offer_asset", &config.xastro_denom),
-                attr("ask_asset", &config.astro_denom),
-                attr("offer_amount", offer_asset.amount),
-                attr("return_amount", return_amount),
-                attr("spread_amount", "0"),
-                attr("commission_amount", "0"),
-                attr("maker_fee_amount", "0"),
-                attr("fee_share_amount", "0"),
-            ]))
+            let event = SwapEvent {
+                pool: env.contract.address,
+                sender,
+                receiver,
+                return_asset: Asset::native(&config.astro_denom, return_amount),
+                offer_asset,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+                maker_fee_amount: Uint128::zero(),
+                fee_share_amount: Uint128::zero(),
+            };
+
+            Ok(Response::new()
+                .add_message(unstake_msg)
+                .add_attributes(event.into_attributes()))
         }
         _ => Err(ContractError::InvalidAsset(offer_asset.info.to_string())),
     }
@@ -175,9 +192,9 @@ pub fn query_deposit_and_shares(
     querier: QuerierWrapper,
     config: &Config,
 ) -> Result<(Uint128, Uint128), ContractError> {
-    let total_deposit = querier
-        .query_balance(&config.staking, &config.astro_denom)?
-        .amount;
+    // ASTRO transferred directly to the staking contract is not accounted until it is absorbed
+    let total_deposit =
+        querier.query_wasm_smart(&config.staking, &staking::QueryMsg::TotalDeposit {})?;
     let total_shares = querier.query_supply(&config.xastro_denom)?.amount;
 
     Ok((total_deposit, total_shares))
@@ -3,12 +3,13 @@ use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult, Storage, Uint12
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use astroport::pair::{
     ConfigResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    COMMISSION_ASSET,
 };
 use astroport::querier::query_factory_config;
 
 use crate::contract::{predict_stake, predict_unstake};
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CREATION_INFO};
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
@@ -54,6 +55,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
                 commission_amount: Uint128::zero(),
             })?)
         }
+        QueryMsg::CommissionAsset {} => Ok(to_json_binary(&COMMISSION_ASSET)?),
         _ => Err(ContractError::NotSupported {}),
     }
 }
@@ -80,6 +82,8 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
         tracker_addr: None,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
     })
 }
 
@@ -3,6 +3,7 @@ use cosmwasm_std::Addr;
 use cw_storage_plus::Item;
 
 use astroport::asset::PairInfo;
+use astroport::pair::PairCreationInfo;
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -21,3 +22,6 @@ pub struct Config {
 
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
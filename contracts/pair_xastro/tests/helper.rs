@@ -129,6 +129,7 @@ impl Helper {
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: true,
+                min_trade_notional: None,
             }],
             token_code_id: 0,
             generator_address: None,
@@ -136,6 +137,7 @@ impl Helper {
             whitelist_code_id: 0,
             coin_registry_address: "coin_registry".to_string(),
             tracker_config: None,
+            ownership_timelock: None,
         };
 
         let factory = app.instantiate_contract(
@@ -154,6 +156,9 @@ impl Helper {
             tracking_admin: owner.to_string(),
             tracking_code_id: tracker_code_id,
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
+            absorb_interval: None,
+            owner: None,
+            ownership_timelock: None,
         };
         let staking = app
             .instantiate_contract(
@@ -213,6 +218,8 @@ impl Helper {
             auto_stake: None,
             receiver: None,
             min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -244,6 +251,8 @@ impl Helper {
                     belief_price: None,
                     max_spread: None,
                     to,
+                    deadline: None,
+                    to_commitment: None,
                 };
 
                 self.app
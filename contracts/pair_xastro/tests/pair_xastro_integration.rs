@@ -133,6 +133,8 @@ fn test_queries() {
             owner: owner.clone(),
             factory_addr: helper.factory.clone(),
             tracker_addr: None,
+            pair_type: Some(PairType::Custom("pair_xastro".to_string())),
+            created_at: config.created_at.clone(),
         }
     );
 
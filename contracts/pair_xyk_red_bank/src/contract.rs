@@ -0,0 +1,687 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, coin, ensure, ensure_eq, from_json, wasm_execute, Binary, Coin, Decimal, DepsMut, Empty,
+    Env, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse,
+    SubMsgResult, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw_utils::{one_coin, PaymentError};
+
+use astroport::asset::{Asset, AssetInfoExt, CoinsExt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::common::LP_SUBDENOM;
+use astroport::events::{ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent};
+use astroport::factory::PairType;
+use astroport::pair::{
+    check_deadline, check_recipient_commitment, ExecuteMsg, InstantiateMsg, PairCreationInfo,
+};
+use astroport::pair_red_bank::{RedBankPoolParams, RedBankPoolUpdateParams};
+use astroport::querier::{query_balance, query_factory_config, query_fee_info, query_pair_paused};
+use astroport::red_bank::ExecuteMsg as RedBankExecuteMsg;
+use astroport::token_factory::{tf_burn_msg, tf_create_denom_msg, MsgCreateDenomResponse};
+use astroport_pair::contract::{calculate_maker_fee, compute_swap};
+use astroport_pair_common::liquidity::{
+    check_asset_infos, check_assets, ensure_min_assets_to_receive, get_share_in_assets,
+    mint_liquidity_token_message, resolve_receiver,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    Config, PendingExit, CONFIG, CREATION_INFO, LENT_REFUNDS, PENDING_EXIT, PENDING_WITHDRAWALS,
+    TOTAL_LENT_REFUNDS,
+};
+use crate::utils::{
+    assert_max_spread, calculate_shares, get_deposits_from_assets, pool_info, query_reserves,
+    rebalance, total_reserves, validate_buffer, Rebalance,
+};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Reply ID for create denom reply
+const CREATE_DENOM_REPLY_ID: u64 = 1;
+/// Reply ID for failed Red Bank deposits
+pub(crate) const DEPOSIT_REPLY_ID: u64 = 2;
+/// Reply ID for Red Bank withdrawals
+pub(crate) const WITHDRAW_REPLY_ID: u64 = 3;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    ensure!(
+        msg.asset_infos.len() == 2 && msg.asset_infos.iter().all(|info| info.is_native_token()),
+        ContractError::InvalidAssets {}
+    );
+    check_asset_infos(deps.api, &msg.asset_infos)?;
+
+    let params: RedBankPoolParams = msg
+        .init_params
+        .map(from_json)
+        .transpose()?
+        .ok_or(ContractError::InitParamsNotFound {})?;
+    validate_buffer(params.buffer)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        pair_info: PairInfo {
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: "".to_owned(),
+            asset_infos: msg.asset_infos,
+            pair_type: PairType::Custom("xyk_red_bank".to_string()),
+        },
+        factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
+        red_bank: deps.api.addr_validate(&params.red_bank)?,
+        buffer: params.buffer,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
+
+    // Create LP token
+    let sub_msg = SubMsg::reply_on_success(
+        tf_create_denom_msg(env.contract.address.to_string(), LP_SUBDENOM),
+        CREATE_DENOM_REPLY_ID,
+    );
+
+    Ok(Response::new().add_submessage(sub_msg))
+}
+
+/// The entry point to the contract for processing replies from submessages.
+///
+/// * A failed Red Bank deposit leaves the funds in the pair.
+///
+/// * A Red Bank withdrawal must leave enough funds in the pair to pay out the operation.
+/// If it fails during a liquidity withdrawal, the liquid part of the refund is paid out
+/// and the rest is recorded as a refund claimable later. Other operations revert.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        Reply {
+            id: CREATE_DENOM_REPLY_ID,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }),
+        } => {
+            let MsgCreateDenomResponse { new_token_denom } = data.try_into()?;
+
+            CONFIG.update(deps.storage, |mut config| {
+                if !config.pair_info.liquidity_token.is_empty() {
+                    return Err(StdError::generic_err(
+                        "Liquidity token is already set in the config",
+                    ));
+                }
+
+                config.pair_info.liquidity_token = new_token_denom.clone();
+                Ok(config)
+            })?;
+
+            Ok(Response::new().add_attribute("lp_denom", new_token_denom))
+        }
+        Reply {
+            id: WITHDRAW_REPLY_ID,
+            result,
+        } => {
+            let mut pending = PENDING_WITHDRAWALS.load(deps.storage)?;
+            let expected = pending.remove(0);
+            if pending.is_empty() {
+                PENDING_WITHDRAWALS.remove(deps.storage);
+            } else {
+                PENDING_WITHDRAWALS.save(deps.storage, &pending)?;
+            }
+
+            match result {
+                SubMsgResult::Ok(_) => {
+                    let available =
+                        query_balance(&deps.querier, env.contract.address, &expected.denom)?;
+                    ensure!(
+                        available >= expected.amount,
+                        ContractError::WithdrawalShortfall {
+                            denom: expected.denom,
+                            expected: expected.amount,
+                            available,
+                        }
+                    );
+                }
+                SubMsgResult::Err(reason) => {
+                    let mut exit = PENDING_EXIT.may_load(deps.storage)?.ok_or(
+                        ContractError::WithdrawalFailed {
+                            denom: expected.denom.clone(),
+                            reason,
+                        },
+                    )?;
+                    exit.failed.push(expected.denom);
+                    PENDING_EXIT.save(deps.storage, &exit)?;
+                }
+            }
+
+            match PENDING_EXIT.may_load(deps.storage)? {
+                Some(exit) if pending.is_empty() => {
+                    PENDING_EXIT.remove(deps.storage);
+                    pay_out_exit(deps.storage, exit)
+                }
+                _ => Ok(Response::new()),
+            }
+        }
+        Reply {
+            id: DEPOSIT_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new().add_attributes([
+            attr("action", "red_bank_deposit_failed"),
+            attr("error", err),
+        ])),
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+            min_lp_to_receive,
+            staking_target,
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                min_lp_to_receive,
+                staking_target,
+            )
+        }
+        ExecuteMsg::Swap {
+            offer_asset,
+            belief_price,
+            max_spread,
+            to,
+            deadline,
+            to_commitment,
+            ..
+        } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
+            swap(deps, env, info, offer_asset, belief_price, max_spread, to)
+        }
+        ExecuteMsg::WithdrawLiquidity {
+            assets,
+            min_assets_to_receive,
+            deadline,
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets, min_assets_to_receive)
+        }
+        ExecuteMsg::UpdateConfig { params } => update_config(deps, info, params),
+        ExecuteMsg::ClaimLentRefunds {} => claim_lent_refunds(deps, info),
+        _ => Err(ContractError::NotSupported {}),
+    }
+}
+
+/// Provides liquidity with the specified input parameters.
+/// The liquid excess over the buffer is deposited into the Red Bank.
+///
+/// * **assets** vector with assets meant to be provided to the pool.
+///
+/// * **slippage_tolerance** is an optional parameter which is used to specify how much
+/// the pool price can move until the provide liquidity transaction goes through.
+///
+/// * **auto_stake** is an optional parameter which determines whether the LP tokens minted after
+/// liquidity provision are automatically staked in the incentives contract on behalf of the LP token receiver.
+///
+/// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
+/// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
+///
+/// * **min_lp_to_receive** is an optional parameter which specifies the minimum amount of LP tokens to receive.
+///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    auto_stake: Option<bool>,
+    receiver: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+    staking_target: Option<String>,
+) -> Result<Response, ContractError> {
+    check_assets(deps.api, &assets)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
+    info.funds
+        .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
+
+    let (reserves, mut pools, total_share) = pool_info(&deps.querier, deps.storage, &config)?;
+    let deposits = get_deposits_from_assets(&assets, &pools)?;
+
+    // The pool balance is already increased by the user deposit
+    for (pool, deposit) in pools.iter_mut().zip(deposits) {
+        pool.amount = pool.amount.checked_sub(deposit)?;
+    }
+
+    let share = calculate_shares(&deposits, &pools, total_share, slippage_tolerance)?;
+
+    let min_amount_lp = min_lp_to_receive.unwrap_or_default();
+    if share < min_amount_lp {
+        return Err(ContractError::ProvideSlippageViolation(
+            share,
+            min_amount_lp,
+        ));
+    }
+
+    let mut messages = vec![];
+    if total_share.is_zero() {
+        messages.extend(mint_liquidity_token_message(
+            deps.querier,
+            &config.factory_addr,
+            &config.pair_info.liquidity_token,
+            &env.contract.address,
+            &env.contract.address,
+            MINIMUM_LIQUIDITY_AMOUNT,
+            false,
+            None,
+        )?);
+    }
+
+    // Mint LP tokens for the sender or for the receiver (if set)
+    let receiver = resolve_receiver(deps.api, &receiver, &info.sender)?;
+    messages.extend(mint_liquidity_token_message(
+        deps.querier,
+        &config.factory_addr,
+        &config.pair_info.liquidity_token,
+        &env.contract.address,
+        &receiver,
+        share,
+        auto_stake.unwrap_or(false),
+        staking_target.as_deref(),
+    )?);
+
+    let Rebalance {
+        deposits: deposit_msgs,
+        ..
+    } = rebalance(deps.storage, &config, &reserves, &[], false)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(deposit_msgs)
+        .add_attributes(
+            ProvideLiquidityEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                receiver,
+                assets,
+                share,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Withdraws liquidity from the pool. Burns the LP tokens and sends back the assets
+/// in proportion to the withdrawn share. Collateral is withdrawn from the Red Bank first
+/// if the liquid part of the reserves doesn't cover the refund. In that case the refund is paid out
+/// once the withdrawals are replied to. If a withdrawal fails, the sender receives the pro rata share
+/// of the liquid reserve and can claim the rest with [`ExecuteMsg::ClaimLentRefunds`] later.
+///
+/// * **assets** must be empty. Imbalanced withdrawals are not supported.
+///
+/// * **min_assets_to_receive** is an optional parameter which specifies the minimum amount of assets to receive.
+pub fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    min_assets_to_receive: Option<Vec<Asset>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let Coin { amount, denom } = one_coin(&info)?;
+    ensure_eq!(
+        denom,
+        config.pair_info.liquidity_token,
+        PaymentError::MissingDenom(config.pair_info.liquidity_token.to_string())
+    );
+    ensure!(
+        assets.is_empty(),
+        StdError::generic_err("Imbalanced withdraw is currently disabled")
+    );
+
+    let (reserves, pools, total_share) = pool_info(&deps.querier, deps.storage, &config)?;
+    let refund_assets = get_share_in_assets(&pools, amount, total_share);
+
+    ensure_min_assets_to_receive(
+        &config.pair_info.asset_infos,
+        refund_assets.clone(),
+        min_assets_to_receive,
+    )?;
+
+    let Rebalance {
+        withdrawals,
+        deposits,
+    } = rebalance(deps.storage, &config, &reserves, &refund_assets, false)?;
+
+    let mut messages = vec![];
+    if withdrawals.is_empty() {
+        for asset in refund_assets.iter().filter(|asset| !asset.amount.is_zero()) {
+            messages.push(asset.clone().into_msg(&info.sender)?);
+        }
+    } else {
+        let liquid_assets = reserves
+            .iter()
+            .zip(&refund_assets)
+            .map(|(reserve, refund)| {
+                let amount = if reserve.lent.is_zero() {
+                    refund.amount
+                } else {
+                    refund
+                        .amount
+                        .multiply_ratio(reserve.liquid, reserve.total())
+                };
+                refund.info.with_balance(amount)
+            })
+            .collect();
+        PENDING_EXIT.save(
+            deps.storage,
+            &PendingExit {
+                receiver: info.sender.clone(),
+                refund_assets: refund_assets.clone(),
+                liquid_assets,
+                failed: vec![],
+            },
+        )?;
+    }
+    messages.push(tf_burn_msg(
+        env.contract.address,
+        coin(amount.u128(), &config.pair_info.liquidity_token),
+    ));
+
+    Ok(Response::new()
+        .add_submessages(withdrawals)
+        .add_messages(messages)
+        .add_submessages(deposits)
+        .add_attributes(
+            WithdrawLiquidityEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                withdrawn_share: amount,
+                refund_assets,
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Performs a swap with the specified parameters. Collateral is withdrawn from the Red Bank first
+/// if the liquid ask reserve doesn't cover the swap. The liquid excess of the offer asset is deposited.
+///
+/// * **offer_asset** proposed asset for swapping.
+///
+/// * **belief_price** is used to calculate the maximum swap spread.
+///
+/// * **max_spread** sets the maximum spread of the swap operation.
+///
+/// * **to** sets the recipient of the swap operation.
+pub fn swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    offer_asset.assert_sent_native_token_balance(&info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
+    let reserves = query_reserves(&deps.querier, deps.storage, &config)?;
+    let pools = total_reserves(&reserves);
+
+    let (offer_pool, ask_pool) = if offer_asset.info == pools[0].info {
+        (&pools[0], &pools[1])
+    } else if offer_asset.info == pools[1].info {
+        (&pools[1], &pools[0])
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    // The offer pool balance is already increased by the offer amount
+    let offer_amount = offer_asset.amount;
+    let offer_pool_amount = offer_pool.amount.checked_sub(offer_amount)?;
+
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool_amount,
+        ask_pool.amount,
+        offer_amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    // Check the max spread limit (if it was specified)
+    assert_max_spread(
+        belief_price,
+        max_spread,
+        offer_amount,
+        return_amount + commission_amount,
+        spread_amount,
+    )?;
+
+    let receiver = resolve_receiver(deps.api, &to, &info.sender)?;
+    let return_asset = ask_pool.info.with_balance(return_amount);
+
+    let mut payouts = vec![];
+    if !return_amount.is_zero() {
+        payouts.push((return_asset.clone(), receiver.clone()));
+    }
+
+    let mut maker_fee_amount = Uint128::zero();
+    if let Some(fee_address) = fee_info.fee_address {
+        if let Some(maker_fee) =
+            calculate_maker_fee(&ask_pool.info, commission_amount, fee_info.maker_fee_rate)
+        {
+            maker_fee_amount = maker_fee.amount;
+            payouts.push((maker_fee, fee_address));
+        }
+    }
+
+    let payout_assets = payouts
+        .iter()
+        .map(|(asset, _)| asset.clone())
+        .collect::<Vec<_>>();
+    let Rebalance {
+        withdrawals,
+        deposits,
+    } = rebalance(deps.storage, &config, &reserves, &payout_assets, false)?;
+
+    let mut messages = vec![];
+    for (asset, recipient) in payouts {
+        messages.push(asset.into_msg(recipient)?);
+    }
+
+    Ok(Response::new()
+        .add_submessages(withdrawals)
+        .add_messages(messages)
+        .add_submessages(deposits)
+        .add_attributes(
+            SwapEvent {
+                pool: config.pair_info.contract_addr,
+                sender: info.sender,
+                receiver,
+                offer_asset,
+                return_asset,
+                spread_amount,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount: Uint128::zero(),
+            }
+            .into_attributes(),
+        ))
+}
+
+/// Updates the pool parameters. Only the factory owner can execute this.
+///
+/// * **params** new parameter values serialized as [`RedBankPoolUpdateParams`].
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    params: Binary,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+
+    if info.sender != factory_config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_json::<RedBankPoolUpdateParams>(&params)? {
+        RedBankPoolUpdateParams::UpdateBuffer { buffer } => {
+            validate_buffer(buffer)?;
+            config.buffer = buffer;
+            CONFIG.save(deps.storage, &config)?;
+
+            let reserves = query_reserves(&deps.querier, deps.storage, &config)?;
+            let Rebalance {
+                withdrawals,
+                deposits,
+            } = rebalance(deps.storage, &config, &reserves, &[], true)?;
+
+            Ok(Response::new()
+                .add_submessages(withdrawals)
+                .add_submessages(deposits)
+                .add_attributes([
+                    attr("action", "update_buffer"),
+                    attr("buffer", buffer.to_string()),
+                ]))
+        }
+    }
+}
+
+/// Pays out a liquidity refund once all Red Bank withdrawals are replied to.
+/// Assets which withdrawals failed are paid out in the pro rata share of the liquid reserve,
+/// the rest is recorded as a refund claimable with [`ExecuteMsg::ClaimLentRefunds`].
+fn pay_out_exit(storage: &mut dyn Storage, exit: PendingExit) -> Result<Response, ContractError> {
+    let mut messages = vec![];
+    let mut attrs = vec![attr("action", "pay_out_withdrawal")];
+
+    for (refund, liquid) in exit.refund_assets.into_iter().zip(exit.liquid_assets) {
+        let denom = refund.info.to_string();
+        let paid = if exit.failed.contains(&denom) {
+            let owed = refund.amount.checked_sub(liquid.amount)?;
+            if !owed.is_zero() {
+                LENT_REFUNDS.update(storage, (&exit.receiver, &denom), |v| -> StdResult<_> {
+                    Ok(v.unwrap_or_default().checked_add(owed)?)
+                })?;
+                TOTAL_LENT_REFUNDS.update(storage, &denom, |v| -> StdResult<_> {
+                    Ok(v.unwrap_or_default().checked_add(owed)?)
+                })?;
+                attrs.push(attr("lent_refund", coin(owed.u128(), &denom).to_string()));
+            }
+            liquid
+        } else {
+            refund
+        };
+
+        if !paid.amount.is_zero() {
+            messages.push(paid.into_msg(&exit.receiver)?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(attrs))
+}
+
+/// Withdraws the liquidity refund parts of the sender which couldn't be withdrawn from the Red Bank
+/// during the liquidity withdrawal. Collateral is withdrawn straight to the sender.
+/// A failed withdrawal reverts the claim so that it can be retried later.
+pub fn claim_lent_refunds(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let refunds = LENT_REFUNDS
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    ensure!(!refunds.is_empty(), ContractError::NoLentRefunds {});
+
+    let mut messages = vec![];
+    for (denom, amount) in refunds {
+        LENT_REFUNDS.remove(deps.storage, (&info.sender, &denom));
+        TOTAL_LENT_REFUNDS.update(deps.storage, &denom, |v| -> StdResult<_> {
+            Ok(v.unwrap_or_default().checked_sub(amount)?)
+        })?;
+
+        messages.push(wasm_execute(
+            &config.red_bank,
+            &RedBankExecuteMsg::Withdraw {
+                denom,
+                amount: Some(amount),
+                recipient: Some(info.sender.to_string()),
+                account_id: None,
+                liquidation_related: None,
+            },
+            vec![],
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "claim_lent_refunds"),
+        attr("receiver", info.sender),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        "astroport-pair-xyk-red-bank" => match contract_version.version.as_ref() {
+            "1.0.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
+}
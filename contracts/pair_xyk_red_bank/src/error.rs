@@ -0,0 +1,125 @@
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+use astroport::asset::MINIMUM_LIQUIDITY_AMOUNT;
+use astroport_pair_common::error::PairCommonError;
+
+/// This enum describes pair contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Endpoint is not supported")]
+    NotSupported {},
+
+    #[error("Invalid reply message")]
+    FailedToParseReply {},
+
+    #[error("Doubling assets in asset infos")]
+    DoublingAssets {},
+
+    #[error("The pair must consist of two native assets")]
+    InvalidAssets {},
+
+    #[error("The asset {0} does not belong to the pair")]
+    InvalidAsset(String),
+
+    #[error("Event of zero transfer")]
+    InvalidZeroAmount {},
+
+    #[error("You need to provide init params")]
+    InitParamsNotFound {},
+
+    #[error("Buffer must be within (0, 1] range, got {0}")]
+    InvalidBuffer(Decimal),
+
+    #[error("Operation exceeds max spread limit")]
+    MaxSpreadAssertion {},
+
+    #[error("Provided spread amount exceeds allowed limit")]
+    AllowedSpreadAssertion {},
+
+    #[error("Operation exceeds max splippage tolerance")]
+    MaxSlippageAssertion {},
+
+    #[error("Slippage is more than expected: received {0}, expected {1} LP tokens")]
+    ProvideSlippageViolation(Uint128, Uint128),
+
+    #[error("Received {received} {asset_name} but expected {expected}")]
+    WithdrawSlippageViolation {
+        asset_name: String,
+        received: Uint128,
+        expected: Uint128,
+    },
+
+    #[error("Wrong asset length: expected {expected}, actual {actual}")]
+    WrongAssetLength { expected: usize, actual: usize },
+
+    #[error("Asset mismatch between the requested and the stored asset in contract")]
+    AssetMismatch {},
+
+    #[error("Incentives address is not set in factory. Cannot auto-stake")]
+    AutoStakeError {},
+
+    #[error("Initial liquidity must be more than {}", MINIMUM_LIQUIDITY_AMOUNT)]
+    MinimumLiquidityAmountError {},
+
+    #[error("Insufficient amount of liquidity")]
+    LiquidityAmountTooSmall {},
+
+    #[error("Red Bank withdrawal of {denom} left {available} in the pair, expected {expected}")]
+    WithdrawalShortfall {
+        denom: String,
+        expected: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Red Bank withdrawal of {denom} failed: {reason}")]
+    WithdrawalFailed { denom: String, reason: String },
+
+    #[error("No liquidity refunds to claim")]
+    NoLentRefunds {},
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
+
+    #[error("Contract can't be migrated!")]
+    MigrationError {},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
+
+impl From<PairCommonError> for ContractError {
+    fn from(err: PairCommonError) -> Self {
+        match err {
+            PairCommonError::Std(err) => ContractError::Std(err),
+            PairCommonError::DoublingAssets {} => ContractError::DoublingAssets {},
+            PairCommonError::AutoStakeError {} => ContractError::AutoStakeError {},
+            PairCommonError::WrongAssetLength { expected, actual } => {
+                ContractError::WrongAssetLength { expected, actual }
+            }
+            PairCommonError::AssetMismatch {} => ContractError::AssetMismatch {},
+            PairCommonError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            } => ContractError::WithdrawSlippageViolation {
+                asset_name,
+                received,
+                expected,
+            },
+        }
+    }
+}
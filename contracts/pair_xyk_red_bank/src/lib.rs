@@ -0,0 +1,5 @@
+pub mod contract;
+pub mod error;
+pub mod queries;
+pub mod state;
+mod utils;
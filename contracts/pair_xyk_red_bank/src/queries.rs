@@ -0,0 +1,178 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult, Uint128};
+
+use astroport::asset::{native_asset, Asset, AssetInfo};
+use astroport::pair::{
+    ConfigResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    COMMISSION_ASSET,
+};
+use astroport::pair_red_bank::RedBankPoolConfig;
+use astroport::querier::{query_factory_config, query_fee_info};
+use astroport_pair::contract::{compute_offer_amount, compute_swap};
+use astroport_pair_common::liquidity::get_share_in_assets;
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, CREATION_INFO, LENT_REFUNDS};
+use crate::utils::pool_info;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Pair {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?.pair_info)?),
+        QueryMsg::Pool {} => Ok(to_json_binary(&query_pool(deps)?)?),
+        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::Share { amount } => Ok(to_json_binary(&query_share(deps, amount)?)?),
+        QueryMsg::Simulation { offer_asset, .. } => {
+            Ok(to_json_binary(&query_simulation(deps, offer_asset)?)?)
+        }
+        QueryMsg::ReverseSimulation { ask_asset, .. } => {
+            Ok(to_json_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+        }
+        QueryMsg::UnclaimedLentRefunds { address } => Ok(to_json_binary(
+            &query_unclaimed_lent_refunds(deps, address)?,
+        )?),
+        QueryMsg::CommissionAsset {} => Ok(to_json_binary(&COMMISSION_ASSET)?),
+        _ => Err(ContractError::NotSupported {}),
+    }
+}
+
+/// Returns the liquidity refund parts of `address` which are still lent to the Red Bank
+/// and can be claimed with [`astroport::pair::ExecuteMsg::ClaimLentRefunds`].
+pub fn query_unclaimed_lent_refunds(deps: Deps, address: String) -> StdResult<Vec<Asset>> {
+    let address = deps.api.addr_validate(&address)?;
+
+    LENT_REFUNDS
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| native_asset(denom, amount)))
+        .collect()
+}
+
+/// Returns the pool reserves including the Red Bank collateral as well as the amount of LP
+/// tokens currently minted in an object of type [`PoolResponse`].
+pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (_, assets, total_share) = pool_info(&deps.querier, deps.storage, &config)?;
+
+    Ok(PoolResponse {
+        assets,
+        total_share,
+    })
+}
+
+/// Returns the pair contract configuration in a [`ConfigResponse`] object.
+/// The Red Bank parameters are returned as [`RedBankPoolConfig`].
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
+
+    Ok(ConfigResponse {
+        block_time_last: 0,
+        params: Some(to_json_binary(&RedBankPoolConfig {
+            red_bank: config.red_bank,
+            buffer: config.buffer,
+        })?),
+        owner: factory_config.owner,
+        factory_addr: config.factory_addr,
+        tracker_addr: None,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
+    })
+}
+
+/// Returns the amount of assets that could be withdrawn from the pool using a specific amount of LP tokens.
+///
+/// * **amount** is the amount of LP tokens for which we calculate associated amounts of assets.
+pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
+    let config = CONFIG.load(deps.storage)?;
+    let (_, pools, total_share) = pool_info(&deps.querier, deps.storage, &config)?;
+
+    Ok(get_share_in_assets(&pools, amount, total_share))
+}
+
+/// Returns the result of a swap simulation in a [`SimulationResponse`] object.
+///
+/// * **offer_asset** is the asset to swap as well as an amount of the said asset.
+pub fn query_simulation(
+    deps: Deps,
+    offer_asset: Asset,
+) -> Result<SimulationResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (_, pools, _) = pool_info(&deps.querier, deps.storage, &config)?;
+    let (offer_pool, ask_pool) = select_pools(&pools, &offer_asset.info, true)?;
+
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type,
+    )?;
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_asset.amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    Ok(SimulationResponse {
+        return_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Returns the amount of offer assets required to receive the specified ask asset amount
+/// in a [`ReverseSimulationResponse`] object.
+///
+/// * **ask_asset** is the asset to receive as well as the desired amount of it.
+pub fn query_reverse_simulation(
+    deps: Deps,
+    ask_asset: Asset,
+) -> Result<ReverseSimulationResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (_, pools, _) = pool_info(&deps.querier, deps.storage, &config)?;
+    let (offer_pool, ask_pool) = select_pools(&pools, &ask_asset.info, false)?;
+
+    let fee_info = query_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        config.pair_info.pair_type,
+    )?;
+
+    let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
+        offer_pool.amount,
+        ask_pool.amount,
+        ask_asset.amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    Ok(ReverseSimulationResponse {
+        offer_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Returns the offer and ask pools for the given asset.
+///
+/// * **is_offer** whether the asset is the offer asset.
+fn select_pools<'a>(
+    pools: &'a [Asset],
+    info: &AssetInfo,
+    is_offer: bool,
+) -> Result<(&'a Asset, &'a Asset), ContractError> {
+    let (pool, other) = if info == &pools[0].info {
+        (&pools[0], &pools[1])
+    } else if info == &pools[1].info {
+        (&pools[1], &pools[0])
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    Ok(if is_offer {
+        (pool, other)
+    } else {
+        (other, pool)
+    })
+}
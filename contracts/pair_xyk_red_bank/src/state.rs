@@ -0,0 +1,54 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use astroport::asset::{Asset, PairInfo};
+use astroport::pair::PairCreationInfo;
+
+/// This structure stores the main pair parameters.
+#[cw_serde]
+pub struct Config {
+    /// The pair information stored in a [`PairInfo`] struct
+    pub pair_info: PairInfo,
+    /// The factory contract address
+    pub factory_addr: Addr,
+    /// The Mars Red Bank contract which idle reserves are lent to
+    pub red_bank: Addr,
+    /// Share of every reserve kept in the pair to serve swaps and withdrawals
+    pub buffer: Decimal,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
+
+/// Liquid balances the pair must hold right after the pending Red Bank withdrawals.
+/// Withdrawals are replied to in the order they were dispatched.
+/// Cleared within the same transaction once all replies are processed
+pub const PENDING_WITHDRAWALS: Item<Vec<Coin>> = Item::new("pending_withdrawals");
+
+/// This structure describes a liquidity refund which waits for the Red Bank withdrawals to be replied to.
+#[cw_serde]
+pub struct PendingExit {
+    /// Receiver of the refund
+    pub receiver: Addr,
+    /// Full refund in the pair assets order
+    pub refund_assets: Vec<Asset>,
+    /// Pro rata share of the liquid reserves in the refund. Paid out instead of the full refund
+    /// if the Red Bank withdrawal of the asset fails
+    pub liquid_assets: Vec<Asset>,
+    /// Denoms which Red Bank withdrawals failed
+    pub failed: Vec<String>,
+}
+
+/// Stores the liquidity refund paid out once all pending Red Bank withdrawals are replied to.
+/// Cleared within the same transaction
+pub const PENDING_EXIT: Item<PendingExit> = Item::new("pending_exit");
+
+/// Liquidity refund parts which couldn't be withdrawn from the Red Bank keyed by receiver and denom.
+/// They are backed by the pair collateral and excluded from the pool reserves until claimed
+pub const LENT_REFUNDS: Map<(&Addr, &str), Uint128> = Map::new("lent_refunds");
+
+/// Total of [`LENT_REFUNDS`] per denom
+pub const TOTAL_LENT_REFUNDS: Map<&str, Uint128> = Map::new("total_lent_refunds");
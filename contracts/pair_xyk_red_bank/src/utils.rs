@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    coin, wasm_execute, Decimal, Decimal256, QuerierWrapper, StdError, StdResult, Storage, SubMsg,
+    Uint128, Uint256,
+};
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::pair::{initial_share_after_lockup, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE};
+use astroport::querier::{query_balance, query_native_supply};
+use astroport::red_bank::{query_collateral, ExecuteMsg as RedBankExecuteMsg};
+use astroport::U256;
+
+use crate::contract::{DEPOSIT_REPLY_ID, WITHDRAW_REPLY_ID};
+use crate::error::ContractError;
+use crate::state::{Config, PENDING_WITHDRAWALS, TOTAL_LENT_REFUNDS};
+
+/// A pool reserve split between the liquid part held by the pair and the part lent to the Red Bank.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reserve {
+    pub info: AssetInfo,
+    /// Balance held by the pair
+    pub liquid: Uint128,
+    /// Red Bank collateral including accrued interest
+    pub lent: Uint128,
+}
+
+impl Reserve {
+    pub fn total(&self) -> Uint128 {
+        self.liquid + self.lent
+    }
+}
+
+/// Red Bank messages which keep the liquid part of every reserve at the configured buffer.
+pub struct Rebalance {
+    /// Withdrawals which must be dispatched before the payouts
+    pub withdrawals: Vec<SubMsg>,
+    /// Deposits of the liquid excess over the buffer
+    pub deposits: Vec<SubMsg>,
+}
+
+/// Returns the pair reserves in the pair assets order.
+/// The liquid part includes the funds attached to the current message.
+/// Collateral owed to the liquidity providers as unclaimed refunds is excluded.
+pub fn query_reserves(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+) -> StdResult<Vec<Reserve>> {
+    config
+        .pair_info
+        .asset_infos
+        .iter()
+        .map(|info| {
+            let denom = info.to_string();
+            let collateral = query_collateral(
+                querier,
+                &config.red_bank,
+                &config.pair_info.contract_addr,
+                &denom,
+            )?;
+            let owed = TOTAL_LENT_REFUNDS
+                .may_load(storage, &denom)?
+                .unwrap_or_default();
+
+            Ok(Reserve {
+                info: info.clone(),
+                liquid: query_balance(querier, &config.pair_info.contract_addr, &denom)?,
+                lent: collateral.saturating_sub(owed),
+            })
+        })
+        .collect()
+}
+
+/// Returns the total pool reserves as well as the total amount of LP tokens currently minted.
+pub fn pool_info(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+) -> StdResult<(Vec<Reserve>, Vec<Asset>, Uint128)> {
+    let reserves = query_reserves(querier, storage, config)?;
+    let pools = total_reserves(&reserves);
+    let total_share = query_native_supply(querier, &config.pair_info.liquidity_token)?;
+
+    Ok((reserves, pools, total_share))
+}
+
+/// Converts reserves to the total pool assets.
+pub fn total_reserves(reserves: &[Reserve]) -> Vec<Asset> {
+    reserves
+        .iter()
+        .map(|reserve| reserve.info.with_balance(reserve.total()))
+        .collect()
+}
+
+/// Builds the Red Bank messages for the current reserves and the payouts of an operation.
+/// Collateral is withdrawn on demand only, i.e. if the liquid balance doesn't cover the payout,
+/// in which case the buffer is restored as well. The liquid excess over the buffer is deposited.
+/// Withdrawals are replied to in any case so that a liquidity withdrawal can fall back to a partial refund.
+///
+/// * **reserves** current reserves including the funds received by the operation.
+///
+/// * **payouts** assets sent out by the operation.
+///
+/// * **restore_buffer** whether to withdraw collateral if the liquid part is below the buffer
+/// even though it covers the payouts.
+pub fn rebalance(
+    storage: &mut dyn Storage,
+    config: &Config,
+    reserves: &[Reserve],
+    payouts: &[Asset],
+    restore_buffer: bool,
+) -> Result<Rebalance, ContractError> {
+    let mut rebalance = Rebalance {
+        withdrawals: vec![],
+        deposits: vec![],
+    };
+    let mut pending = vec![];
+
+    for reserve in reserves {
+        let denom = reserve.info.to_string();
+        let payout = payouts
+            .iter()
+            .filter(|asset| asset.info == reserve.info)
+            .try_fold(Uint128::zero(), |acc, asset| acc.checked_add(asset.amount))?;
+        let target = reserve.total().checked_sub(payout)? * config.buffer;
+        let required = target + payout;
+
+        if reserve.liquid < payout || (restore_buffer && reserve.liquid < required) {
+            // The buffer never exceeds the remaining reserve, hence the collateral always covers the shortfall
+            let amount = (required - reserve.liquid).min(reserve.lent);
+            if amount.is_zero() {
+                continue;
+            }
+
+            pending.push(coin((reserve.liquid + amount).u128(), &denom));
+            rebalance.withdrawals.push(SubMsg::reply_always(
+                wasm_execute(
+                    &config.red_bank,
+                    &RedBankExecuteMsg::Withdraw {
+                        denom,
+                        amount: Some(amount),
+                        recipient: None,
+                        account_id: None,
+                        liquidation_related: None,
+                    },
+                    vec![],
+                )?,
+                WITHDRAW_REPLY_ID,
+            ));
+        } else if reserve.liquid > required {
+            // A failed deposit leaves the funds in the pair instead of reverting the operation
+            rebalance.deposits.push(SubMsg::reply_on_error(
+                wasm_execute(
+                    &config.red_bank,
+                    &RedBankExecuteMsg::Deposit {
+                        account_id: None,
+                        on_behalf_of: None,
+                    },
+                    vec![coin((reserve.liquid - required).u128(), denom)],
+                )?,
+                DEPOSIT_REPLY_ID,
+            ));
+        }
+    }
+
+    if !pending.is_empty() {
+        PENDING_WITHDRAWALS.save(storage, &pending)?;
+    }
+
+    Ok(rebalance)
+}
+
+/// Validates the Red Bank buffer.
+pub fn validate_buffer(buffer: Decimal) -> Result<(), ContractError> {
+    if buffer.is_zero() || buffer > Decimal::one() {
+        return Err(ContractError::InvalidBuffer(buffer));
+    }
+
+    Ok(())
+}
+
+/// Returns the deposit amounts in the pair assets order.
+///
+/// * **assets** is an array with assets provided by the user.
+///
+/// * **pools** is the array with assets in the pool.
+pub fn get_deposits_from_assets(
+    assets: &[Asset],
+    pools: &[Asset],
+) -> Result<[Uint128; 2], ContractError> {
+    if assets.len() != 2 {
+        return Err(ContractError::WrongAssetLength {
+            expected: 2,
+            actual: assets.len(),
+        });
+    }
+
+    let mut deposits = [Uint128::zero(); 2];
+    for (deposit, pool) in deposits.iter_mut().zip(pools) {
+        *deposit = assets
+            .iter()
+            .find(|asset| asset.info == pool.info)
+            .map(|asset| asset.amount)
+            .ok_or(ContractError::AssetMismatch {})?;
+    }
+
+    if deposits[0].is_zero() || deposits[1].is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    Ok(deposits)
+}
+
+/// Returns shares for the provided deposits.
+///
+/// * **deposits** is an array with asset amounts
+///
+/// * **pools** is an array with total amount of assets in the pool
+///
+/// * **total_share** is the total amount of LP tokens currently minted
+///
+/// * **slippage_tolerance** is an optional parameter which is used to specify how much
+/// the pool price can move until the provide liquidity transaction goes through.
+pub fn calculate_shares(
+    deposits: &[Uint128; 2],
+    pools: &[Asset],
+    total_share: Uint128,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Uint128, ContractError> {
+    if total_share.is_zero() {
+        let share = Uint128::new(
+            (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
+                .integer_sqrt()
+                .as_u128(),
+        );
+
+        // share cannot become zero after minimum liquidity subtraction
+        return initial_share_after_lockup(share)
+            .ok_or(ContractError::MinimumLiquidityAmountError {});
+    }
+
+    assert_slippage_tolerance(slippage_tolerance, deposits, pools)?;
+
+    let share = std::cmp::min(
+        deposits[0].multiply_ratio(total_share, pools[0].amount),
+        deposits[1].multiply_ratio(total_share, pools[1].amount),
+    );
+
+    if share.is_zero() {
+        return Err(ContractError::LiquidityAmountTooSmall {});
+    }
+
+    Ok(share)
+}
+
+/// If `belief_price` and `max_spread` are both specified, we compute a new spread,
+/// otherwise we just use the swap spread to check `max_spread`.
+pub fn assert_max_spread(
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    spread_amount: Uint128,
+) -> Result<(), ContractError> {
+    let default_spread = Decimal::from_str(DEFAULT_SLIPPAGE)?;
+    let max_allowed_spread = Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?;
+
+    let max_spread = max_spread.unwrap_or(default_spread);
+    if max_spread > max_allowed_spread {
+        return Err(ContractError::AllowedSpreadAssertion {});
+    }
+
+    if let Some(belief_price) = belief_price {
+        let expected_return = offer_amount
+            * belief_price
+                .inv()
+                .ok_or_else(|| StdError::generic_err("Belief price must not be zero!"))?;
+        let spread_amount = expected_return.saturating_sub(return_amount);
+
+        if return_amount < expected_return
+            && Decimal::from_ratio(spread_amount, expected_return) > max_spread
+        {
+            return Err(ContractError::MaxSpreadAssertion {});
+        }
+    } else if Decimal::from_ratio(spread_amount, return_amount + spread_amount) > max_spread {
+        return Err(ContractError::MaxSpreadAssertion {});
+    }
+
+    Ok(())
+}
+
+/// Enforces the slippage tolerance for liquidity provision.
+fn assert_slippage_tolerance(
+    slippage_tolerance: Option<Decimal>,
+    deposits: &[Uint128; 2],
+    pools: &[Asset],
+) -> Result<(), ContractError> {
+    let default_slippage = Decimal::from_str(DEFAULT_SLIPPAGE)?;
+    let max_allowed_slippage = Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?;
+
+    let slippage_tolerance = slippage_tolerance.unwrap_or(default_slippage);
+    if slippage_tolerance > max_allowed_slippage {
+        return Err(ContractError::AllowedSpreadAssertion {});
+    }
+
+    let one_minus_slippage_tolerance = Decimal256::one() - Decimal256::from(slippage_tolerance);
+    let deposits: [Uint256; 2] = [deposits[0].into(), deposits[1].into()];
+    let pools: [Uint256; 2] = [pools[0].amount.into(), pools[1].amount.into()];
+
+    // Ensure each price does not change more than what the slippage tolerance allows
+    if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
+        > Decimal256::from_ratio(pools[0], pools[1])
+        || Decimal256::from_ratio(deposits[1], deposits[0]) * one_minus_slippage_tolerance
+            > Decimal256::from_ratio(pools[1], pools[0])
+    {
+        return Err(ContractError::MaxSlippageAssertion {});
+    }
+
+    Ok(())
+}
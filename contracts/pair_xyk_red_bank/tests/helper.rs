@@ -0,0 +1,547 @@
+#![cfg(not(tarpaulin_include))]
+#![allow(dead_code)]
+
+use anyhow::Result as AnyResult;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+use derivative::Derivative;
+
+use astroport::asset::{native_asset_info, Asset, AssetInfo, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::pair::{ConfigResponse, ExecuteMsg, PoolResponse, QueryMsg, SimulationResponse};
+use astroport::pair_red_bank::{RedBankPoolParams, RedBankPoolUpdateParams};
+use astroport::red_bank::{
+    ExecuteMsg as RedBankExecuteMsg, QueryMsg as RedBankQueryMsg, UserCollateralResponse,
+};
+use astroport_pair_xyk_red_bank::contract::{execute, instantiate, reply};
+use astroport_pair_xyk_red_bank::queries::query;
+use astroport_test::cw_multi_test::{AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+
+pub const DENOMS: [&str; 2] = ["uusd", "uluna"];
+
+/// Simplified Red Bank which lends nothing but accrues interest when coins are sent to it.
+/// Collateral is tracked in scaled amounts, so the whole contract balance belongs to the depositors.
+mod mock_red_bank {
+    use super::*;
+
+    const SCALED: Map<(&str, &str), Uint128> = Map::new("scaled");
+    const TOTAL_SCALED: Map<&str, Uint128> = Map::new("total_scaled");
+    const DEPOSITS_DISABLED: Item<bool> = Item::new("deposits_disabled");
+    const WITHDRAWALS_DISABLED: Item<bool> = Item::new("withdrawals_disabled");
+
+    #[cw_serde]
+    #[serde(untagged)]
+    pub enum MockExecuteMsg {
+        RedBank(RedBankExecuteMsg),
+        Mock(MockMsg),
+    }
+
+    #[cw_serde]
+    pub enum MockMsg {
+        SetDepositsDisabled { disabled: bool },
+        SetWithdrawalsDisabled { disabled: bool },
+    }
+
+    fn collateral(deps: Deps, env: &Env, user: &str, denom: &str) -> StdResult<Uint128> {
+        let scaled = SCALED
+            .may_load(deps.storage, (user, denom))?
+            .unwrap_or_default();
+        let total_scaled = TOTAL_SCALED
+            .may_load(deps.storage, denom)?
+            .unwrap_or_default();
+        if total_scaled.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount;
+
+        Ok(scaled.multiply_ratio(balance, total_scaled))
+    }
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: MockExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            MockExecuteMsg::RedBank(RedBankExecuteMsg::Deposit { .. }) => {
+                if DEPOSITS_DISABLED
+                    .may_load(deps.storage)?
+                    .unwrap_or_default()
+                {
+                    return Err(StdError::generic_err("Deposits are disabled"));
+                }
+                let [Coin { denom, amount }] = info.funds.as_slice() else {
+                    return Err(StdError::generic_err("Exactly one coin must be sent"));
+                };
+
+                let balance = deps
+                    .querier
+                    .query_balance(&env.contract.address, denom)?
+                    .amount
+                    - amount;
+                let total_scaled = TOTAL_SCALED
+                    .may_load(deps.storage, denom)?
+                    .unwrap_or_default();
+                let scaled = if total_scaled.is_zero() {
+                    *amount
+                } else {
+                    amount.multiply_ratio(total_scaled, balance)
+                };
+
+                TOTAL_SCALED.save(deps.storage, denom, &(total_scaled + scaled))?;
+                SCALED.update(deps.storage, (info.sender.as_str(), denom.as_str()), |v| {
+                    Ok::<_, StdError>(v.unwrap_or_default() + scaled)
+                })?;
+
+                Ok(Response::new())
+            }
+            MockExecuteMsg::RedBank(RedBankExecuteMsg::Withdraw {
+                denom,
+                amount,
+                recipient,
+                ..
+            }) => {
+                if WITHDRAWALS_DISABLED
+                    .may_load(deps.storage)?
+                    .unwrap_or_default()
+                {
+                    return Err(StdError::generic_err("Withdrawals are disabled"));
+                }
+                let user = info.sender.as_str();
+                let available = collateral(deps.as_ref(), &env, user, &denom)?;
+                let amount = amount.unwrap_or(available);
+                if amount > available {
+                    return Err(StdError::generic_err("Insufficient collateral"));
+                }
+
+                let balance = deps
+                    .querier
+                    .query_balance(&env.contract.address, &denom)?
+                    .amount;
+                let total_scaled = TOTAL_SCALED.load(deps.storage, &denom)?;
+                let user_scaled = SCALED.load(deps.storage, (user, denom.as_str()))?;
+                // Round the burnt scaled amount up in favor of the remaining depositors
+                let mut scaled = amount.multiply_ratio(total_scaled, balance);
+                if scaled.multiply_ratio(balance, total_scaled) < amount {
+                    scaled += Uint128::one();
+                }
+                let scaled = scaled.min(user_scaled);
+
+                TOTAL_SCALED.save(deps.storage, &denom, &(total_scaled - scaled))?;
+                SCALED.save(
+                    deps.storage,
+                    (user, denom.as_str()),
+                    &(user_scaled - scaled),
+                )?;
+
+                Ok(Response::new().add_message(BankMsg::Send {
+                    to_address: recipient.unwrap_or_else(|| info.sender.to_string()),
+                    amount: vec![coin(amount.u128(), denom)],
+                }))
+            }
+            MockExecuteMsg::Mock(MockMsg::SetDepositsDisabled { disabled }) => {
+                DEPOSITS_DISABLED.save(deps.storage, &disabled)?;
+                Ok(Response::new())
+            }
+            MockExecuteMsg::Mock(MockMsg::SetWithdrawalsDisabled { disabled }) => {
+                WITHDRAWALS_DISABLED.save(deps.storage, &disabled)?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, env: Env, msg: RedBankQueryMsg) -> StdResult<Binary> {
+        match msg {
+            RedBankQueryMsg::UserCollateral { user, denom, .. } => {
+                to_json_binary(&UserCollateralResponse {
+                    amount_scaled: SCALED
+                        .may_load(deps.storage, (user.as_str(), denom.as_str()))?
+                        .unwrap_or_default(),
+                    amount: collateral(deps, &env, &user, &denom)?,
+                    denom,
+                    enabled: false,
+                })
+            }
+        }
+    }
+}
+
+pub use mock_red_bank::MockMsg;
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_reply_empty(reply))
+}
+
+fn red_bank_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_red_bank::execute,
+        mock_red_bank::instantiate,
+        mock_red_bank::query,
+    ))
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    )
+}
+
+fn coin_registry_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        astroport_native_coin_registry::contract::execute,
+        astroport_native_coin_registry::contract::instantiate,
+        astroport_native_coin_registry::contract::query,
+    ))
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Helper {
+    #[derivative(Debug = "ignore")]
+    pub app: TestApp,
+    pub owner: Addr,
+    pub factory: Addr,
+    pub red_bank: Addr,
+    pub pair_addr: Addr,
+    pub lp_token: String,
+    pub fake_maker: Addr,
+}
+
+impl Helper {
+    /// Creates the pair with the given init params. The Red Bank address is filled in if `red_bank` is empty.
+    pub fn new(owner: &Addr, params: Option<RedBankPoolParams>) -> AnyResult<Self> {
+        let mut app = AppBuilder::new_custom()
+            .with_stargate(MockStargate::default())
+            .build(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        owner,
+                        DENOMS.iter().map(|denom| coin(u128::MAX, *denom)).collect(),
+                    )
+                    .unwrap()
+            });
+
+        let pair_code_id = app.store_code(pair_contract());
+        let factory_code_id = app.store_code(factory_contract());
+        let red_bank_code_id = app.store_code(red_bank_contract());
+        let coin_registry_id = app.store_code(coin_registry_contract());
+        let pair_type = PairType::Custom("xyk_red_bank".to_string());
+        let fake_maker = Addr::unchecked("fake_maker");
+
+        let red_bank = app.instantiate_contract(
+            red_bank_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Red Bank",
+            None,
+        )?;
+
+        let coin_registry_address = app.instantiate_contract(
+            coin_registry_id,
+            owner.clone(),
+            &astroport::native_coin_registry::InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            "Coin registry",
+            None,
+        )?;
+
+        app.execute_contract(
+            owner.clone(),
+            coin_registry_address.clone(),
+            &astroport::native_coin_registry::ExecuteMsg::Add {
+                native_coins: DENOMS.iter().map(|denom| (denom.to_string(), 6)).collect(),
+            },
+            &[],
+        )?;
+
+        let factory = app.instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                fee_address: Some(fake_maker.to_string()),
+                pair_configs: vec![PairConfig {
+                    code_id: pair_code_id,
+                    maker_fee_bps: 5000,
+                    total_fee_bps: 30,
+                    pair_type: pair_type.clone(),
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                    permissioned: true,
+                    min_trade_notional: None,
+                }],
+                token_code_id: 0,
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: coin_registry_address.to_string(),
+                tracker_config: None,
+                ownership_timelock: None,
+            },
+            &[],
+            "factory label",
+            None,
+        )?;
+
+        let asset_infos = DENOMS
+            .iter()
+            .map(|denom| native_asset_info(denom.to_string()))
+            .collect::<Vec<_>>();
+        let init_params = params
+            .map(|mut params| {
+                if params.red_bank.is_empty() {
+                    params.red_bank = red_bank.to_string();
+                }
+                to_json_binary(&params)
+            })
+            .transpose()?;
+
+        app.execute_contract(
+            owner.clone(),
+            factory.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type,
+                asset_infos: asset_infos.clone(),
+                init_params,
+            },
+            &[],
+        )?;
+
+        let resp: PairInfo = app.wrap().query_wasm_smart(
+            &factory,
+            &astroport::factory::QueryMsg::Pair { asset_infos },
+        )?;
+
+        Ok(Self {
+            app,
+            owner: owner.clone(),
+            factory,
+            red_bank,
+            pair_addr: resp.contract_addr,
+            lp_token: resp.liquidity_token,
+            fake_maker,
+        })
+    }
+
+    pub fn params(red_bank: impl Into<String>, buffer: Decimal) -> Option<RedBankPoolParams> {
+        Some(RedBankPoolParams {
+            red_bank: red_bank.into(),
+            buffer,
+        })
+    }
+
+    pub fn provide_liquidity(&mut self, sender: &Addr, assets: &[Asset]) -> AnyResult<AppResponse> {
+        let funds = assets
+            .iter()
+            .map(|asset| asset.as_coin())
+            .collect::<StdResult<Vec<_>>>()?;
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::ProvideLiquidity {
+                assets: assets.to_vec(),
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+                min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
+            },
+            &funds,
+        )
+    }
+
+    pub fn withdraw_liquidity(&mut self, sender: &Addr, amount: u128) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::WithdrawLiquidity {
+                assets: vec![],
+                min_assets_to_receive: None,
+                deadline: None,
+            },
+            &[coin(amount, &self.lp_token)],
+        )
+    }
+
+    pub fn swap(&mut self, sender: &Addr, offer_asset: &Asset) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[offer_asset.as_coin()?],
+        )
+    }
+
+    pub fn update_buffer(&mut self, sender: &Addr, buffer: Decimal) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&RedBankPoolUpdateParams::UpdateBuffer { buffer })?,
+            },
+            &[],
+        )
+    }
+
+    /// Simulates Red Bank interest by sending coins to the Red Bank
+    pub fn accrue_interest(&mut self, denom: &str, amount: u128) {
+        self.app
+            .send_tokens(
+                self.owner.clone(),
+                self.red_bank.clone(),
+                &[coin(amount, denom)],
+            )
+            .unwrap();
+    }
+
+    pub fn set_deposits_disabled(&mut self, disabled: bool) {
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.red_bank.clone(),
+                &MockMsg::SetDepositsDisabled { disabled },
+                &[],
+            )
+            .unwrap();
+    }
+
+    pub fn set_withdrawals_disabled(&mut self, disabled: bool) {
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.red_bank.clone(),
+                &MockMsg::SetWithdrawalsDisabled { disabled },
+                &[],
+            )
+            .unwrap();
+    }
+
+    pub fn claim_lent_refunds(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pair_addr.clone(),
+            &ExecuteMsg::ClaimLentRefunds {},
+            &[],
+        )
+    }
+
+    pub fn give_me_money(&mut self, assets: &[Asset], recipient: &Addr) {
+        let funds = assets
+            .iter()
+            .map(|asset| asset.as_coin().unwrap())
+            .collect::<Vec<_>>();
+
+        self.app
+            .send_tokens(self.owner.clone(), recipient.clone(), &funds)
+            .unwrap();
+    }
+
+    pub fn native_balance(&self, denom: &str, user: &Addr) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(user, denom)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    /// Returns the Red Bank collateral of the pair
+    pub fn lent(&self, denom: &str) -> u128 {
+        let resp: UserCollateralResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.red_bank,
+                &RedBankQueryMsg::UserCollateral {
+                    user: self.pair_addr.to_string(),
+                    account_id: None,
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap();
+
+        resp.amount.u128()
+    }
+
+    /// Returns the pair balance
+    pub fn liquid(&self, denom: &str) -> u128 {
+        self.native_balance(denom, &self.pair_addr)
+    }
+
+    pub fn simulate_swap(&self, offer_asset: &Asset) -> StdResult<SimulationResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: None,
+            },
+        )
+    }
+
+    pub fn query_config(&self) -> StdResult<ConfigResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::Config {})
+    }
+
+    pub fn query_pool(&self) -> StdResult<PoolResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.pair_addr, &QueryMsg::Pool {})
+    }
+
+    pub fn query_unclaimed_lent_refunds(&self, address: &Addr) -> StdResult<Vec<Asset>> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::UnclaimedLentRefunds {
+                address: address.to_string(),
+            },
+        )
+    }
+}
+
+pub fn asset(denom: &str, amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        },
+        amount: amount.into(),
+    }
+}
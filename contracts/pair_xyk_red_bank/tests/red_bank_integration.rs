@@ -0,0 +1,295 @@
+use cosmwasm_std::{from_json, Addr, Decimal, Event};
+
+use astroport::pair_red_bank::RedBankPoolConfig;
+use astroport_pair_xyk_red_bank::error::ContractError;
+
+use crate::helper::{asset, Helper};
+
+mod helper;
+
+#[test]
+fn test_instantiate() {
+    let owner = Addr::unchecked("owner");
+
+    let err = Helper::new(&owner, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InitParamsNotFound {}
+    );
+
+    let err = Helper::new(&owner, Helper::params("", Decimal::zero())).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBuffer(Decimal::zero())
+    );
+
+    let err = Helper::new(&owner, Helper::params("", Decimal::percent(101))).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBuffer(Decimal::percent(101))
+    );
+
+    let helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+    let config = helper.query_config().unwrap();
+    assert_eq!(
+        from_json::<RedBankPoolConfig>(config.params.unwrap()).unwrap(),
+        RedBankPoolConfig {
+            red_bank: helper.red_bank.clone(),
+            buffer: Decimal::percent(10),
+        }
+    );
+}
+
+#[test]
+fn test_provide_deposits_excess() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[asset("uusd", 100_000_000), asset("uluna", 100_000_000)],
+        )
+        .unwrap();
+
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 10_000_000);
+        assert_eq!(helper.lent(denom), 90_000_000);
+    }
+
+    // Reserves include the Red Bank collateral
+    let pool = helper.query_pool().unwrap();
+    assert_eq!(
+        pool.assets,
+        vec![asset("uusd", 100_000_000), asset("uluna", 100_000_000)]
+    );
+    assert_eq!(pool.total_share.u128(), 100_000_000);
+    assert_eq!(helper.native_balance(&helper.lp_token, &owner), 99_999_000);
+
+    // Second provision gets the share of the total reserves
+    let user = Addr::unchecked("user");
+    let assets = [asset("uusd", 10_000_000), asset("uluna", 10_000_000)];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+
+    assert_eq!(helper.native_balance(&helper.lp_token, &user), 10_000_000);
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 11_000_000);
+        assert_eq!(helper.lent(denom), 99_000_000);
+    }
+}
+
+#[test]
+fn test_swap_withdraws_on_demand() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[asset("uusd", 100_000_000), asset("uluna", 100_000_000)],
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    let offer = asset("uusd", 5_000_000);
+    helper.give_me_money(&[offer.clone()], &user);
+    let sim = helper.simulate_swap(&offer).unwrap();
+    helper.swap(&user, &offer).unwrap();
+
+    assert_eq!(
+        helper.native_balance("uluna", &user),
+        sim.return_amount.u128()
+    );
+    // The liquid ask reserve covered the swap, the collateral is untouched
+    assert_eq!(helper.lent("uluna"), 90_000_000);
+    // The offer asset excess over the buffer is deposited
+    assert_eq!(helper.liquid("uusd"), 10_500_000);
+    assert_eq!(helper.lent("uusd"), 94_500_000);
+
+    // The swap exceeds the liquid ask reserve
+    let offer = asset("uusd", 20_000_000);
+    helper.give_me_money(&[offer.clone()], &user);
+    let lent_before = helper.lent("uluna");
+    let sim = helper.simulate_swap(&offer).unwrap();
+    assert!(sim.return_amount.u128() > helper.liquid("uluna"));
+    helper.swap(&user, &offer).unwrap();
+
+    assert!(helper.lent("uluna") < lent_before);
+    // The buffer is restored after the withdrawal
+    let pool = helper.query_pool().unwrap();
+    let expected_liquid = pool.assets[1].amount * Decimal::percent(10);
+    assert!(expected_liquid.u128().abs_diff(helper.liquid("uluna")) <= 1);
+
+    // The maker fee is paid out as well
+    assert!(helper.native_balance("uluna", &helper.fake_maker) > 0);
+}
+
+#[test]
+fn test_interest_accrues_to_lps() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    let user = Addr::unchecked("user");
+    let assets = [asset("uusd", 100_000_000), asset("uluna", 100_000_000)];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+
+    helper.accrue_interest("uusd", 9_000_000);
+    assert_eq!(helper.lent("uusd"), 99_000_000);
+    assert_eq!(
+        helper.query_pool().unwrap().assets,
+        vec![asset("uusd", 109_000_000), asset("uluna", 100_000_000)]
+    );
+
+    helper.withdraw_liquidity(&user, 99_999_000).unwrap();
+
+    assert_eq!(helper.native_balance("uusd", &user), 108_998_910);
+    assert_eq!(helper.native_balance("uluna", &user), 99_999_000);
+
+    // The locked minimum liquidity stays in the pool split by the buffer
+    assert_eq!(helper.liquid("uusd") + helper.lent("uusd"), 1_090);
+    assert_eq!(helper.liquid("uusd"), 109);
+}
+
+#[test]
+fn test_failed_deposit_keeps_funds() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    helper.set_deposits_disabled(true);
+
+    let resp = helper
+        .provide_liquidity(
+            &owner,
+            &[asset("uusd", 100_000_000), asset("uluna", 100_000_000)],
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("action", "red_bank_deposit_failed")));
+
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 100_000_000);
+        assert_eq!(helper.lent(denom), 0);
+    }
+
+    // The next operation deposits the excess once the Red Bank accepts deposits again
+    helper.set_deposits_disabled(false);
+    helper.swap(&owner, &asset("uusd", 1_000_000)).unwrap();
+
+    assert_eq!(helper.liquid("uusd"), 10_100_000);
+    assert_eq!(helper.lent("uusd"), 90_900_000);
+    assert!(helper.lent("uluna") > 0);
+}
+
+#[test]
+fn test_failed_withdrawal_records_lent_refund() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[asset("uusd", 100_000_000), asset("uluna", 100_000_000)],
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    let assets = [asset("uusd", 100_000_000), asset("uluna", 100_000_000)];
+    helper.give_me_money(&assets, &user);
+    helper.provide_liquidity(&user, &assets).unwrap();
+
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 20_000_000);
+        assert_eq!(helper.lent(denom), 180_000_000);
+    }
+
+    // Red Bank withdrawals fail, but the LP can still exit with the liquid share
+    helper.set_withdrawals_disabled(true);
+    helper.withdraw_liquidity(&user, 100_000_000).unwrap();
+
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.native_balance(denom, &user), 10_000_000);
+        assert_eq!(helper.liquid(denom), 10_000_000);
+        assert_eq!(helper.lent(denom), 180_000_000);
+    }
+    assert_eq!(
+        helper.query_unclaimed_lent_refunds(&user).unwrap(),
+        [asset("uluna", 90_000_000), asset("uusd", 90_000_000)]
+    );
+
+    // Reserves exclude the collateral owed to the LP
+    let pool = helper.query_pool().unwrap();
+    assert_eq!(pool.total_share.u128(), 100_000_000);
+    for asset in pool.assets {
+        assert_eq!(asset.amount.u128(), 100_000_000);
+    }
+
+    // The claim reverts while withdrawals are disabled
+    helper.claim_lent_refunds(&user).unwrap_err();
+
+    helper.set_withdrawals_disabled(false);
+    helper.claim_lent_refunds(&user).unwrap();
+
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.native_balance(denom, &user), 100_000_000);
+        assert_eq!(helper.lent(denom), 90_000_000);
+    }
+    assert!(helper
+        .query_unclaimed_lent_refunds(&user)
+        .unwrap()
+        .is_empty());
+
+    let err = helper.claim_lent_refunds(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoLentRefunds {}
+    );
+}
+
+#[test]
+fn test_update_buffer() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner, Helper::params("", Decimal::percent(10))).unwrap();
+
+    helper
+        .provide_liquidity(
+            &owner,
+            &[asset("uusd", 100_000_000), asset("uluna", 100_000_000)],
+        )
+        .unwrap();
+
+    let err = helper
+        .update_buffer(&Addr::unchecked("random"), Decimal::one())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = helper.update_buffer(&owner, Decimal::zero()).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBuffer(Decimal::zero())
+    );
+
+    // Buffer of 1 withdraws everything from the Red Bank
+    helper.update_buffer(&owner, Decimal::one()).unwrap();
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 100_000_000);
+        assert_eq!(helper.lent(denom), 0);
+    }
+
+    helper.update_buffer(&owner, Decimal::percent(50)).unwrap();
+    for denom in ["uusd", "uluna"] {
+        assert_eq!(helper.liquid(denom), 50_000_000);
+        assert_eq!(helper.lent(denom), 50_000_000);
+    }
+
+    let config = helper.query_config().unwrap();
+    assert_eq!(
+        from_json::<RedBankPoolConfig>(config.params.unwrap())
+            .unwrap()
+            .buffer,
+        Decimal::percent(50)
+    );
+}
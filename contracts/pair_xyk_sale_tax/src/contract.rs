@@ -6,10 +6,9 @@ use cosmwasm_schema::cw_serde;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, coins, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, BankMsg, Binary,
-    Coin, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256, Deps, DepsMut, Env, Fraction,
-    MessageInfo, QuerierWrapper, Reply, Response, StdError, StdResult, SubMsg, SubMsgResponse,
-    SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
+    coin, coins, ensure, ensure_eq, from_json, to_json_binary, Addr, BankMsg, Binary, Coin,
+    CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env, Fraction, MessageInfo, Reply, Response,
+    StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -18,31 +17,46 @@ use cw_utils::{
 };
 
 use astroport::asset::{
-    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, CoinsExt, PairInfo,
+    addr_opt_validate, check_swap_parameters, Asset, AssetInfo, AssetInfoExt, CoinsExt, PairInfo,
     MINIMUM_LIQUIDITY_AMOUNT,
 };
 use astroport::common::LP_SUBDENOM;
+use astroport::events::{ProvideLiquidityEvent, SwapEvent, WithdrawLiquidityEvent};
 use astroport::factory::PairType;
-use astroport::incentives::ExecuteMsg as IncentiveExecuteMsg;
-use astroport::pair::{ConfigResponse, ReplyIds, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE};
+use astroport::observation::{
+    accumulate_swap_sizes, query_observation, query_observations_info, query_safe_price,
+    query_volume_sma, PrecommitObservation, OBSERVATIONS_SIZE, VOLUME_OBSERVATIONS_SIZE,
+};
+use astroport::pair::{
+    check_deadline, check_recipient_commitment, initial_share_after_lockup,
+    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MinimumLiquidityResponse,
+    PairCreationInfo, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    TWAP_PRECISION,
+};
 use astroport::pair::{
-    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
+    ConfigResponse, ReplyIds, COMMISSION_ASSET, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE,
 };
 use astroport::pair_xyk_sale_tax::{
     MigrateMsg, SaleTaxConfigUpdates, SaleTaxInitParams, TaxConfigChecked,
 };
 use astroport::querier::{
-    query_factory_config, query_fee_info, query_native_supply, query_tracker_config,
+    query_factory_config, query_fee_info, query_native_supply, query_pair_paused,
+    query_tracker_config,
 };
 use astroport::token_factory::{
-    tf_before_send_hook_msg, tf_burn_msg, tf_create_denom_msg, tf_mint_msg, MsgCreateDenomResponse,
+    tf_before_send_hook_msg, tf_burn_msg, tf_create_denom_msg, MsgCreateDenomResponse,
 };
 use astroport::{tokenfactory_tracker, U256};
+use astroport_circular_buffer::BufferManager;
 use astroport_pair::state::{Config as XykConfig, CONFIG as XYK_CONFIG};
+use astroport_pair_common::liquidity::{
+    get_share_in_assets, mint_liquidity_token_message, pool_info, resolve_receiver,
+};
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::state::{
+    Config, BALANCES, CONFIG, CREATION_INFO, LP_TOKEN_METADATA, OBSERVATIONS, VOLUME_OBSERVATIONS,
+};
 
 /// Contract name that is used for migration.
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -96,7 +110,16 @@ pub fn instantiate(
         }
     }
 
+    if let Some(lp_token_metadata) = init_params.lp_token_metadata {
+        lp_token_metadata.validate()?;
+        LP_TOKEN_METADATA.save(deps.storage, &lp_token_metadata)?;
+    }
+
     CONFIG.save(deps.storage, &config)?;
+    CREATION_INFO.save(deps.storage, &PairCreationInfo::from(&env.block))?;
+
+    BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
+    BufferManager::init(deps.storage, VOLUME_OBSERVATIONS, VOLUME_OBSERVATIONS_SIZE)?;
 
     // Create LP token
     let sub_msg = SubMsg::reply_on_success(
@@ -145,6 +168,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                                     .to_string(),
                                 tracked_denom: new_token_denom.clone(),
                                 track_over_seconds: false,
+                                leaderboard_min_balance: None,
                             })?,
                             funds: vec![],
                             label: format!("{new_token_denom} tracking contract"),
@@ -155,6 +179,14 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                     sub_msgs.extend(sub_msg);
                 }
 
+                if let Some(lp_token_metadata) = LP_TOKEN_METADATA.may_load(deps.storage)? {
+                    LP_TOKEN_METADATA.remove(deps.storage);
+                    sub_msgs.push(SubMsg::new(lp_token_metadata.into_denom_metadata_msg(
+                        env.contract.address.clone(),
+                        &new_token_denom,
+                    )));
+                }
+
                 CONFIG.update(deps.storage, |mut config| {
                     if !config.pair_info.liquidity_token.is_empty() {
                         return Err(ContractError::Unauthorized {});
@@ -191,6 +223,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
                 .add_message(set_hook_msg)
                 .add_attribute("tracker_contract", contract_address))
         }
+        ReplyIds::BatchPayout => Err(ContractError::FailedToParseReply {}),
     }
 }
 
@@ -207,6 +240,8 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             slippage_tolerance,
 ///             auto_stake,
 ///             receiver,
+///             staking_target,
+///             deadline,
 ///         }** Provides liquidity in the pair with the specified input parameters.
 ///
 /// * **ExecuteMsg::Swap {
@@ -214,6 +249,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
 ///             belief_price,
 ///             max_spread,
 ///             to,
+///             deadline,
 ///         }** Performs a swap operation with the specified parameters.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -229,23 +265,35 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
+            staking_target,
+            deadline,
             ..
-        } => provide_liquidity(
-            deps,
-            env,
-            info,
-            assets,
-            slippage_tolerance,
-            auto_stake,
-            receiver,
-        ),
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            provide_liquidity(
+                deps,
+                env,
+                info,
+                assets,
+                slippage_tolerance,
+                auto_stake,
+                receiver,
+                staking_target,
+            )
+        }
         ExecuteMsg::Swap {
             offer_asset,
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
                 return Err(ContractError::Cw20DirectSwap {});
@@ -265,7 +313,13 @@ pub fn execute(
             )
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, info, params),
-        ExecuteMsg::WithdrawLiquidity { assets, .. } => withdraw_liquidity(deps, env, info, assets),
+        ExecuteMsg::WithdrawLiquidity {
+            assets, deadline, ..
+        } => {
+            check_deadline(&env.block, deadline)?;
+
+            withdraw_liquidity(deps, env, info, assets)
+        }
         _ => Err(ContractError::NonSupported {}),
     }
 }
@@ -284,8 +338,13 @@ pub fn receive_cw20(
             belief_price,
             max_spread,
             to,
+            deadline,
+            to_commitment,
             ..
         } => {
+            check_deadline(&env.block, deadline)?;
+            check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
             // Only asset contract can execute this message
             let mut authorized = false;
             let config = CONFIG.load(deps.storage)?;
@@ -335,6 +394,9 @@ pub fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **staking_target** is an optional staking contract which receives auto-staked LP tokens.
+/// It must be registered in the factory; the factory default is used if not set.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
 pub fn provide_liquidity(
     deps: DepsMut,
@@ -344,9 +406,15 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    staking_target: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     let mut pools = config
         .pair_info
         .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
@@ -386,23 +454,27 @@ pub fn provide_liquidity(
     if total_share.is_zero() {
         messages.extend(mint_liquidity_token_message(
             deps.querier,
-            &config,
+            &config.factory_addr,
+            &config.pair_info.liquidity_token,
             &env.contract.address,
             &env.contract.address,
             MINIMUM_LIQUIDITY_AMOUNT,
             false,
+            None,
         )?);
     }
 
     // Mint LP tokens for the sender or for the receiver (if set)
-    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    let receiver = resolve_receiver(deps.api, &receiver, &info.sender)?;
     messages.extend(mint_liquidity_token_message(
         deps.querier,
-        &config,
+        &config.factory_addr,
+        &config.pair_info.liquidity_token,
         &env.contract.address,
         &receiver,
         share,
         auto_stake,
+        staking_target.as_deref(),
     )?);
 
     if config.track_asset_balances {
@@ -426,61 +498,16 @@ pub fn provide_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", format!("{}, {}", assets[0], assets[1])),
-        attr("share", share),
-    ]))
-}
-
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Incentive contract (if auto staking is specified).
-///
-/// * **recipient** LP token recipient.
-///
-/// * **coin** denom and amount of LP tokens that will be minted for the recipient.
-///
-/// * **auto_stake** determines whether the newly minted LP tokens will
-/// be automatically staked in the Incentives contract on behalf of the recipient.
-pub fn mint_liquidity_token_message<T, C>(
-    querier: QuerierWrapper<C>,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg<T>>, ContractError>
-where
-    C: CustomQuery,
-    T: CustomMsg,
-{
-    let coin = coin(amount.into(), config.pair_info.liquidity_token.to_string());
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(tf_mint_msg(contract_address, coin, recipient));
-    }
-
-    // Mint for the pair contract and stake into the Incentives contract
-    let incentives_addr = query_factory_config(&querier, &config.factory_addr)?.generator_address;
-
-    if let Some(address) = incentives_addr {
-        let mut msgs = tf_mint_msg(contract_address, coin.clone(), contract_address);
-        msgs.push(
-            wasm_execute(
-                address,
-                &IncentiveExecuteMsg::Deposit {
-                    recipient: Some(recipient.to_string()),
-                },
-                vec![coin],
-            )?
-            .into(),
-        );
-        Ok(msgs)
-    } else {
-        Err(ContractError::AutoStakeError {})
-    }
+    Ok(Response::new().add_messages(messages).add_attributes(
+        ProvideLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            receiver,
+            assets,
+            share,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Withdraw liquidity from the pool.
@@ -503,7 +530,7 @@ pub fn withdraw_liquidity(
         PaymentError::MissingDenom(config.pair_info.liquidity_token.to_string())
     );
 
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     let refund_assets = if assets.is_empty() {
         // Usual withdraw (balanced)
@@ -544,37 +571,15 @@ pub fn withdraw_liquidity(
         coin(amount.u128(), config.pair_info.liquidity_token.to_string()),
     ));
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("sender", info.sender),
-        attr("withdrawn_share", amount),
-        attr(
-            "refund_assets",
-            format!("{}, {}", refund_assets[0], refund_assets[1]),
-        ),
-    ]))
-}
-
-/// Returns the amount of pool assets that correspond to an amount of LP tokens.
-///
-/// * **pools** is the array with assets in the pool.
-///
-/// * **amount** is amount of LP tokens to compute a corresponding amount of assets for.
-///
-/// * **total_share** is the total amount of LP tokens currently minted.
-pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint128) -> Vec<Asset> {
-    let mut share_ratio = Decimal::zero();
-    if !total_share.is_zero() {
-        share_ratio = Decimal::from_ratio(amount, total_share);
-    }
-
-    pools
-        .iter()
-        .map(|a| Asset {
-            info: a.info.clone(),
-            amount: a.amount * share_ratio,
-        })
-        .collect()
+    Ok(Response::new().add_messages(messages).add_attributes(
+        WithdrawLiquidityEvent {
+            pool: config.pair_info.contract_addr,
+            sender: info.sender,
+            withdrawn_share: amount,
+            refund_assets,
+        }
+        .into_attributes(),
+    ))
 }
 
 /// Performs an swap operation with the specified parameters. The trader must approve the
@@ -606,6 +611,11 @@ pub fn swap(
 
     let mut config = CONFIG.load(deps.storage)?;
 
+    ensure!(
+        !query_pair_paused(&deps.querier, &config.factory_addr, &env.contract.address)?,
+        ContractError::PairPaused {}
+    );
+
     // If the asset balance is already increased, we should subtract the user deposit from the pool amount
     let pools = config
         .pair_info
@@ -714,6 +724,20 @@ pub fn swap(
         )?;
     }
 
+    // Store observation from precommit data
+    accumulate_swap_sizes(deps.storage, &env, OBSERVATIONS, VOLUME_OBSERVATIONS)?;
+
+    // Store time series data in precommit observation.
+    // This data will be reflected in observations on the next action.
+    if !offer_amount.is_zero() && !return_amount.is_zero() {
+        let (base_amount, quote_amount) = if offer_pool.info.equal(&pools[0].info) {
+            (offer_amount, return_amount)
+        } else {
+            (return_amount, offer_amount)
+        };
+        PrecommitObservation::save(deps.storage, &env, base_amount, quote_amount)?;
+    }
+
     // Accumulate prices for the assets in the pool
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
         accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
@@ -730,19 +754,21 @@ pub fn swap(
             // 2. send inactive commission fees to the Maker contract
             messages,
         )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
-            attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_asset.amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("sale_tax", sale_tax),
-        ]))
+        .add_attributes(
+            SwapEvent {
+                pool: config.pair_info.contract_addr,
+                sender,
+                receiver,
+                offer_asset,
+                return_asset: ask_pool.info.with_balance(return_amount),
+                spread_amount,
+                commission_amount,
+                maker_fee_amount,
+                fee_share_amount: Uint128::zero(),
+            }
+            .into_attributes(),
+        )
+        .add_attribute("sale_tax", sale_tax))
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -871,6 +897,17 @@ pub fn calculate_maker_fee(
 /// * **QueryMsg::SimulateProvide { assets, slippage_tolerance }** Returns the amount of LP tokens that will be minted
 ///
 /// * **QueryMsg::SimulateWithdraw { lp_amount }** Returns the amount of assets that could be withdrawn from the pool using a specific amount of LP tokens.
+/// * **QueryMsg::MinimumLiquidity {}** Returns the amount of LP tokens locked in the pair on the first provision.
+///
+/// * **QueryMsg::CommissionAsset {}** Returns the asset in which the pair charges its commission.
+///
+/// * **QueryMsg::Observe { seconds_ago }** Returns the price observed `seconds_ago` seconds ago.
+///
+/// * **QueryMsg::SafePrice { window }** Returns the median of the prices observed within the last `window` seconds.
+///
+/// * **QueryMsg::ObservationsInfo {}** Returns the observations buffer metadata.
+///
+/// * **QueryMsg::VolumeSma { window }** Returns the swap volumes observed within the last `window` seconds.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -884,6 +921,18 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query_reverse_simulation(deps, ask_asset)?)
         }
         QueryMsg::CumulativePrices {} => to_json_binary(&query_cumulative_prices(deps, env)?),
+        QueryMsg::Observe { seconds_ago } => {
+            to_json_binary(&query_observation(deps, env, OBSERVATIONS, seconds_ago)?)
+        }
+        QueryMsg::SafePrice { window } => {
+            to_json_binary(&query_safe_price(deps, env, OBSERVATIONS, window)?)
+        }
+        QueryMsg::ObservationsInfo {} => {
+            to_json_binary(&query_observations_info(deps, env, OBSERVATIONS)?)
+        }
+        QueryMsg::VolumeSma { window } => {
+            to_json_binary(&query_volume_sma(deps, env, VOLUME_OBSERVATIONS, window)?)
+        }
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::AssetBalanceAt {
             asset_info,
@@ -894,6 +943,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             slippage_tolerance,
         } => to_json_binary(&query_simulate_provide(deps, assets, slippage_tolerance)?),
         QueryMsg::SimulateWithdraw { lp_amount } => to_json_binary(&query_share(deps, lp_amount)?),
+        QueryMsg::MinimumLiquidity {} => {
+            to_json_binary(&MinimumLiquidityResponse::new(env.contract.address))
+        }
+        QueryMsg::CommissionAsset {} => to_json_binary(&COMMISSION_ASSET),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
@@ -902,7 +955,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     let resp = PoolResponse {
         assets,
@@ -918,7 +971,7 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
 /// * **amount** is the amount of LP tokens for which we calculate associated amounts of assets.
 pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     let config = CONFIG.load(deps.storage)?;
-    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let (pools, total_share) = pool_info(deps.querier, &config.pair_info)?;
     let refund_assets = get_share_in_assets(&pools, amount, total_share);
 
     Ok(refund_assets)
@@ -1032,7 +1085,7 @@ pub fn query_reverse_simulation(
 /// Returns information about cumulative prices for the assets in the pool using a [`CumulativePricesResponse`] object.
 pub fn query_cumulative_prices(deps: Deps, env: Env) -> StdResult<CumulativePricesResponse> {
     let config = CONFIG.load(deps.storage)?;
-    let (assets, total_share) = pool_info(deps.querier, &config)?;
+    let (assets, total_share) = pool_info(deps.querier, &config.pair_info)?;
 
     let mut price0_cumulative_last = config.price0_cumulative_last;
     let mut price1_cumulative_last = config.price1_cumulative_last;
@@ -1078,10 +1131,13 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             track_asset_balances: config.track_asset_balances,
             tax_configs: config.tax_configs.into(),
             tax_config_admin: config.tax_config_admin.to_string(),
+            lp_token_metadata: None,
         })?),
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
         tracker_addr: config.tracker_addr,
+        pair_type: Some(config.pair_info.pair_type),
+        created_at: CREATION_INFO.may_load(deps.storage)?,
     })
 }
 
@@ -1266,16 +1322,10 @@ pub fn calculate_shares(
             (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
                 .integer_sqrt()
                 .as_u128(),
-        )
-        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
-        .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+        );
 
         // share cannot become zero after minimum liquidity subtraction
-        if share.is_zero() {
-            return Err(ContractError::MinimumLiquidityAmountError {});
-        }
-
-        share
+        initial_share_after_lockup(share).ok_or(ContractError::MinimumLiquidityAmountError {})?
     } else {
         // Assert slippage tolerance
         assert_slippage_tolerance(slippage_tolerance, deposits, pools)?;
@@ -1285,10 +1335,16 @@ pub fn calculate_shares(
         // == deposit_0 * total_share / pool_0
         // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
         // == deposit_1 * total_share / pool_1
-        std::cmp::min(
+        let share = std::cmp::min(
             deposits[0].multiply_ratio(total_share, pools[0].amount),
             deposits[1].multiply_ratio(total_share, pools[1].amount),
-        )
+        );
+
+        if share.is_zero() {
+            return Err(ContractError::LiquidityAmountTooSmall {});
+        }
+
+        share
     };
     Ok(share)
 }
@@ -1467,16 +1523,6 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
     ]))
 }
 
-/// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
-pub fn pool_info(querier: QuerierWrapper, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
-    let pools = config
-        .pair_info
-        .query_pools(&querier, &config.pair_info.contract_addr)?;
-    let total_share = query_native_supply(&querier, &config.pair_info.liquidity_token)?;
-
-    Ok((pools, total_share))
-}
-
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::{Addr, Decimal, Uint128};
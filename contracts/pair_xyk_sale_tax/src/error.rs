@@ -1,4 +1,5 @@
 use astroport::asset::MINIMUM_LIQUIDITY_AMOUNT;
+use astroport_pair_common::error::PairCommonError;
 use cosmwasm_std::{OverflowError, StdError};
 use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
@@ -51,11 +52,17 @@ pub enum ContractError {
     #[error("Initial liquidity must be more than {}", MINIMUM_LIQUIDITY_AMOUNT)]
     MinimumLiquidityAmountError {},
 
+    #[error("Insufficient amount of liquidity")]
+    LiquidityAmountTooSmall {},
+
     #[error("Failed to migrate the contract")]
     MigrationError {},
 
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("Pair is paused in the factory, only withdrawals are allowed")]
+    PairPaused {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -63,3 +70,15 @@ impl From<OverflowError> for ContractError {
         StdError::from(o).into()
     }
 }
+
+impl From<PairCommonError> for ContractError {
+    fn from(err: PairCommonError) -> Self {
+        match err {
+            PairCommonError::Std(err) => ContractError::Std(err),
+            PairCommonError::DoublingAssets {} => ContractError::DoublingAssets {},
+            PairCommonError::AutoStakeError {} => ContractError::AutoStakeError {},
+            PairCommonError::AssetMismatch {} => ContractError::AssetMismatch {},
+            err => ContractError::Std(StdError::generic_err(err.to_string())),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
-    SystemError, SystemResult, Uint128, WasmQuery,
+    from_json, to_json_binary, Addr, Binary, Coin, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 
@@ -142,9 +142,15 @@ impl WasmMockQuerier {
                     }
                 }
             }
-            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, .. }) => {
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
                 if contract_addr == "factory" {
-                    SystemResult::Ok(to_json_binary(&Vec::<Addr>::new()).into())
+                    if key.as_slice() == b"pairs_paused".as_slice()
+                        || key.as_slice()[2..].starts_with(b"paused_pairs")
+                    {
+                        SystemResult::Ok(Binary::default().into())
+                    } else {
+                        SystemResult::Ok(to_json_binary(&Vec::<Addr>::new()).into())
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE");
                 }
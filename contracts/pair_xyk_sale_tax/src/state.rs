@@ -1,7 +1,10 @@
 use astroport::{
     asset::{AssetInfo, PairInfo},
+    observation::{Observation, VolumeObservation},
+    pair::{LpTokenMetadata, PairCreationInfo},
     pair_xyk_sale_tax::TaxConfigs,
 };
+use astroport_circular_buffer::CircularBuffer;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, SnapshotMap};
@@ -32,6 +35,12 @@ pub struct Config {
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the block at which the pair was created
+pub const CREATION_INFO: Item<PairCreationInfo> = Item::new("creation_info");
+
+/// Temporarily stores custom LP token metadata until the LP denom is created
+pub const LP_TOKEN_METADATA: Item<LpTokenMetadata> = Item::new("lp_token_metadata");
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
@@ -39,3 +48,11 @@ pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances_change",
     cw_storage_plus::Strategy::EveryBlock,
 );
+
+/// Circular buffer to store trade size observations
+pub const OBSERVATIONS: CircularBuffer<Observation> =
+    CircularBuffer::new("observations_state", "observations_buffer");
+
+/// Circular buffer to store swap volumes traded within a block
+pub const VOLUME_OBSERVATIONS: CircularBuffer<VolumeObservation> =
+    CircularBuffer::new("volume_observations_state", "volume_observations_buffer");
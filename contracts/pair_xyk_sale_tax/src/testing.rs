@@ -10,6 +10,7 @@ use prost::Message;
 
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::common::LP_SUBDENOM;
+use astroport::events::EVENTS_SCHEMA_VERSION;
 use astroport::factory::PairType;
 use astroport::pair::{
     Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
@@ -182,6 +183,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -305,6 +308,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -384,6 +389,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -448,6 +455,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -490,6 +499,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -532,6 +543,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -573,6 +586,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let env = mock_env_with_block_time(env.block.time.seconds() + 1000);
@@ -604,6 +619,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0001",
@@ -634,6 +651,8 @@ fn provide_liquidity() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
     let info = mock_info(
         "addr0001",
@@ -697,6 +716,7 @@ fn withdraw_liquidity() {
     let msg = ExecuteMsg::WithdrawLiquidity {
         assets: vec![],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     let env = mock_env();
@@ -833,6 +853,8 @@ fn try_native_to_token() {
         belief_price: None,
         max_spread: Some(Decimal::percent(50)),
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info(
@@ -964,6 +986,17 @@ fn try_native_to_token() {
             attr("spread_amount", expected_spread_amount.to_string()),
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
+            attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    expected_spread_amount,
+                    expected_return_amount + expected_spread_amount + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
             attr("sale_tax", expected_tax_amount.to_string()),
         ]
     );
@@ -1063,6 +1096,8 @@ fn try_token_to_native() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let env = mock_env_with_block_time(1000);
     let info = mock_info("addr0000", &[]);
@@ -1078,6 +1113,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: Some(Decimal::percent(50)),
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -1171,6 +1208,17 @@ fn try_token_to_native() {
             attr("spread_amount", expected_spread_amount.to_string()),
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
+            attr("fee_share_amount", "0"),
+            attr(
+                "price_impact",
+                Decimal::from_ratio(
+                    expected_spread_amount,
+                    expected_return_amount + expected_spread_amount + expected_commission_amount
+                )
+                .to_string(),
+            ),
+            attr("pool", MOCK_CONTRACT_ADDR),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
             attr("sale_tax", Uint128::zero().to_string()),
         ]
     );
@@ -1201,6 +1249,8 @@ fn try_token_to_native() {
             belief_price: None,
             max_spread: None,
             to: None,
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
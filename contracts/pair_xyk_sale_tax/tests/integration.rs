@@ -1,13 +1,16 @@
 #![cfg(not(tarpaulin_include))]
 
-use astroport::asset::{native_asset_info, Asset, AssetInfo, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::asset::{
+    native_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+};
 use astroport::factory::{
     ExecuteMsg as FactoryExecuteMsg, InstantiateMsg as FactoryInstantiateMsg, PairConfig, PairType,
     QueryMsg as FactoryQueryMsg, TrackerConfig,
 };
+use astroport::observation::{OracleObservation, VolumeSmaResponse};
 use astroport::pair::{
     ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    TWAP_PRECISION,
+    SimulationResponse, TWAP_PRECISION,
 };
 use astroport::pair_xyk_sale_tax::{
     MigrateMsg, SaleTaxConfigUpdates, SaleTaxInitParams, TaxConfigUnchecked, TaxConfigsUnchecked,
@@ -22,6 +25,7 @@ use astroport_pair_xyk_sale_tax::contract::CONTRACT_NAME;
 use astroport_pair_xyk_sale_tax::error::ContractError;
 use astroport_test::cw_multi_test::{AppBuilder, ContractWrapper, Executor, TOKEN_FACTORY_MODULE};
 use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+use astroport_test::pair::check_first_depositor_donation_attack;
 use cosmwasm_std::{
     attr, coin, to_json_binary, Addr, Coin, Decimal, DepsMut, Empty, Env, MessageInfo, Response,
     StdError, StdResult, Uint128,
@@ -145,6 +149,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: Some(String::from("generator")),
@@ -152,6 +157,7 @@ fn instantiate_pair(mut router: &mut TestApp, owner: &Addr) -> Addr {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -220,6 +226,7 @@ fn instantiate_standard_xyk_pair(mut router: &mut TestApp, owner: &Addr, version
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: Some(String::from("generator")),
@@ -227,6 +234,7 @@ fn instantiate_standard_xyk_pair(mut router: &mut TestApp, owner: &Addr, version
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -397,6 +405,7 @@ fn test_provide_and_withdraw_liquidity() {
     let msg = ExecuteMsg::WithdrawLiquidity {
         assets: vec![],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     // Try to send withdraw liquidity with uluna token
@@ -445,7 +454,9 @@ fn test_provide_and_withdraw_liquidity() {
             params: Some(to_json_binary(&SaleTaxInitParams::default()).unwrap()),
             owner,
             factory_addr: config.factory_addr,
-            tracker_addr: config.tracker_addr
+            tracker_addr: config.tracker_addr,
+            pair_type: Some(PairType::Custom(CONTRACT_NAME.to_string())),
+            created_at: config.created_at.clone()
         }
     )
 }
@@ -475,6 +486,8 @@ fn provide_liquidity_msg(
         auto_stake: None,
         receiver,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -491,6 +504,27 @@ fn provide_liquidity_msg(
     (msg, coins)
 }
 
+#[test]
+fn test_first_depositor_donation_attack() {
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            coin(100_000_000_000u128, "uusd"),
+            coin(100_000_000_000u128, "uluna"),
+        ],
+    );
+    let pair_instance = instantiate_pair(&mut router, &owner);
+
+    check_first_depositor_donation_attack(
+        &mut router,
+        &pair_instance,
+        MINIMUM_LIQUIDITY_AMOUNT.u128() + 1,
+        ContractError::MinimumLiquidityAmountError {},
+        ContractError::LiquidityAmountTooSmall {},
+    );
+}
+
 #[test]
 fn simulate_provide() {
     let owner = Addr::unchecked("owner");
@@ -693,6 +727,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -700,6 +735,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -761,6 +797,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -791,6 +829,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     app.execute_contract(
@@ -810,6 +850,8 @@ fn test_compatibility_of_tokens_with_different_precision() {
             belief_price: None,
             max_spread: None,
             to: Some(user.to_string()),
+            deadline: None,
+            to_commitment: None,
         })
         .unwrap(),
         amount: x_offer,
@@ -1072,6 +1114,7 @@ fn asset_balances_tracking_works_correctly() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id,
         generator_address: Some(String::from("generator")),
@@ -1082,6 +1125,7 @@ fn asset_balances_tracking_works_correctly() {
             code_id: store_tracker_contract(&mut app),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = app
@@ -1111,6 +1155,7 @@ fn asset_balances_tracking_works_correctly() {
                 track_asset_balances: true,
                 tax_configs: TaxConfigsUnchecked::new(),
                 tax_config_admin: "tax_config_admin".to_string(),
+                lp_token_metadata: None,
             })
             .unwrap(),
         ),
@@ -1288,6 +1333,8 @@ fn asset_balances_tracking_works_correctly() {
         belief_price: None,
         max_spread: None,
         to: None,
+        deadline: None,
+        to_commitment: None,
     };
     let send_funds = vec![Coin {
         denom: "uusd".to_owned(),
@@ -1333,6 +1380,7 @@ fn asset_balances_tracking_works_correctly() {
         &ExecuteMsg::WithdrawLiquidity {
             assets: vec![],
             min_assets_to_receive: None,
+            deadline: None,
         },
         &[coin(500_000000u128, lp_token_address)],
     )
@@ -1403,6 +1451,7 @@ fn update_pair_config() {
             code_id: store_tracker_contract(&mut router),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1454,7 +1503,9 @@ fn update_pair_config() {
             params: Some(to_json_binary(&SaleTaxInitParams::default()).unwrap()),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Custom(CONTRACT_NAME.to_string())),
+            created_at: res.created_at.clone()
         }
     );
 }
@@ -1492,6 +1543,7 @@ fn update_tax_configs() {
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
         tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1543,7 +1595,9 @@ fn update_tax_configs() {
             params: Some(to_json_binary(&SaleTaxInitParams::default()).unwrap()),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Custom(CONTRACT_NAME.to_string())),
+            created_at: res.created_at.clone()
         }
     );
 
@@ -1585,7 +1639,9 @@ fn update_tax_configs() {
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Custom(CONTRACT_NAME.to_string())),
+            created_at: res.created_at.clone()
         }
     );
 
@@ -1636,7 +1692,9 @@ fn update_tax_configs() {
             ),
             owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("contract0"),
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: Some(PairType::Custom(CONTRACT_NAME.to_string())),
+            created_at: res.created_at.clone()
         }
     );
 }
@@ -1702,6 +1760,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: token_contract_code_id,
         generator_address: None,
@@ -1712,6 +1771,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
             code_id: store_tracker_contract(&mut router),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1736,6 +1796,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
                 guardian: None,
                 incentivization_fee_info: None,
                 vesting_contract: "vesting".to_string(),
+                ownership_timelock: None,
             },
             &[],
             "generator",
@@ -1773,6 +1834,7 @@ fn provide_liquidity_with_autostaking_to_generator() {
                 track_asset_balances: true,
                 tax_configs: TaxConfigsUnchecked::new(),
                 tax_config_admin: "tax_config_admin".to_string(),
+                lp_token_metadata: None,
             })
             .unwrap(),
         ),
@@ -1804,6 +1866,8 @@ fn provide_liquidity_with_autostaking_to_generator() {
         auto_stake: Some(true),
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -1886,6 +1950,7 @@ fn test_tracker_contract() {
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: 0,
         generator_address: None,
@@ -1896,6 +1961,7 @@ fn test_tracker_contract() {
             code_id: store_tracker_contract(&mut router),
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
         }),
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -1924,6 +1990,7 @@ fn test_tracker_contract() {
                 track_asset_balances: true,
                 tax_configs: TaxConfigsUnchecked::new(),
                 tax_config_admin: "tax_config_admin".to_string(),
+                lp_token_metadata: None,
             })
             .unwrap(),
         ),
@@ -1955,6 +2022,8 @@ fn test_tracker_contract() {
         auto_stake: None,
         receiver: None,
         min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
     };
 
     let coins = [
@@ -2140,6 +2209,7 @@ fn test_imbalanced_withdraw_is_disabled() {
             amount: Uint128::from(100u8),
         }],
         min_assets_to_receive: None,
+        deadline: None,
     };
 
     let err = router
@@ -2207,7 +2277,93 @@ fn test_migrate_from_standard_xyk(old_version: &str) {
             params: Some(to_json_binary(&SaleTaxInitParams::default()).unwrap()),
             owner,
             factory_addr: config.factory_addr,
-            tracker_addr: None
+            tracker_addr: None,
+            pair_type: config.pair_type.clone(),
+            created_at: None
         }
     )
 }
+
+#[test]
+fn test_swap_observations() {
+    let owner = Addr::unchecked("owner");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![coin(10000_000000, "uluna"), coin(10000_000000, "uusd")],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+
+    let (msg, send_funds) = provide_liquidity_msg(
+        Uint128::new(1000_000000),
+        Uint128::new(1000_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &send_funds)
+        .unwrap();
+
+    let swap = |app: &mut TestApp, denom: &str, amount: u128| {
+        let offer_asset = native_asset_info(denom.to_string()).with_balance(amount);
+        let sim: SimulationResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &pair_instance,
+                &QueryMsg::Simulation {
+                    offer_asset: offer_asset.clone(),
+                    ask_asset_info: None,
+                },
+            )
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::Swap {
+                offer_asset,
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(amount, denom)],
+        )
+        .unwrap();
+        sim.return_amount
+    };
+
+    let luna_received = swap(&mut app, "uusd", 10_000000);
+    let swap_ts = app.block_info().time.seconds();
+
+    app.update_block(|b| {
+        b.height += 1;
+        b.time = b.time.plus_seconds(5);
+    });
+    swap(&mut app, "uluna", 1_000000);
+
+    // The sale tax doesn't count towards the volume
+    let res: VolumeSmaResponse = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::VolumeSma { window: 3600 })
+        .unwrap();
+    assert_eq!(
+        res,
+        VolumeSmaResponse {
+            base_volume: Uint128::new(9_500000),
+            quote_volume: luna_received,
+            base_volume_sma: Uint128::new(9_500000),
+            quote_volume_sma: luna_received,
+            observations: 1,
+            oldest_ts: Some(swap_ts),
+        }
+    );
+
+    let res: OracleObservation = app
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::Observe { seconds_ago: 0 })
+        .unwrap();
+    assert_eq!(
+        res.price,
+        Decimal::from_ratio(Uint128::new(9_500000), luna_received)
+    );
+}
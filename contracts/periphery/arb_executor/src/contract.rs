@@ -0,0 +1,351 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, ensure, Addr, Binary, Decimal, DepsMut, Env, MessageInfo, Reply, Response, StdError,
+    SubMsg, Uint128,
+};
+use cw2::set_contract_version;
+
+use astroport::arb_executor::{Config, ExecuteMsg, InstantiateMsg};
+use astroport::asset::{Asset, AssetInfoExt};
+use astroport::common::{
+    claim_ownership, drop_ownership_proposal, propose_new_owner, validate_addresses,
+};
+use astroport::querier::query_factory_config;
+
+use crate::error::ContractError;
+use crate::state::{ReplyContext, CONFIG, OWNERSHIP_PROPOSAL, REPLY_CONTEXT};
+use crate::utils::{check_pairs, find_best_route, flash_swap_msg, simulate_swap, swap_msg};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Reply ID of the swap in the pair where the offer asset is bought back
+const SWAP_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut keepers = validate_addresses(deps.api, &msg.keepers)?;
+    keepers.sort();
+    keepers.dedup();
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
+            keepers,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Arbitrage {
+            pair_a,
+            pair_b,
+            offer_asset,
+            min_profit,
+            max_spread,
+        } => arbitrage(
+            deps,
+            env,
+            info,
+            pair_a,
+            pair_b,
+            offer_asset,
+            min_profit,
+            max_spread,
+        ),
+        ExecuteMsg::FlashSwapCallback { ask, offer, msg } => {
+            flash_swap_callback(deps, info, ask, offer, msg)
+        }
+        ExecuteMsg::UpdateConfig {
+            add_keepers,
+            remove_keepers,
+        } => update_config(deps, info, add_keepers, remove_keepers),
+        ExecuteMsg::Withdraw { assets, receiver } => withdraw(deps, info, assets, receiver),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Flash swaps the offer asset in the pair where it is priced higher. The ask asset is received
+/// before paying for it and is swapped back to the offer asset in [`flash_swap_callback`].
+///
+/// * **min_profit** the minimum profit denominated in the offer asset.
+///
+/// * **max_spread** the maximum spread of the swap in the pair where the offer asset is bought back.
+#[allow(clippy::too_many_arguments)]
+fn arbitrage(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair_a: String,
+    pair_b: String,
+    offer_asset: Asset,
+    min_profit: Option<Uint128>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner && !config.keepers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let (pair_a_info, pair_b_info, ask_info) = check_pairs(
+        &deps.querier,
+        &config.factory_addr,
+        &pair_a,
+        &pair_b,
+        &offer_asset.info,
+    )?;
+
+    let route = find_best_route(
+        &deps.querier,
+        &pair_a_info.contract_addr,
+        &pair_b_info.contract_addr,
+        &offer_asset,
+        &ask_info,
+    )?;
+    let min_profit = min_profit.unwrap_or_default().max(Uint128::one());
+    if route.profit < min_profit {
+        return Err(ContractError::NoProfitableArbitrage {
+            profit: route.profit,
+            min_profit,
+        });
+    }
+
+    let maker = query_factory_config(&deps.querier, &config.factory_addr)?
+        .fee_address
+        .ok_or(ContractError::MakerNotSet {})?;
+
+    let ask_amount = simulate_swap(&deps.querier, &route.first_pair, &offer_asset, &ask_info)?;
+
+    REPLY_CONTEXT.save(
+        deps.storage,
+        &ReplyContext {
+            first_pair: route.first_pair.clone(),
+            second_pair: route.second_pair.clone(),
+            offer_info: offer_asset.info.clone(),
+            ask_info: ask_info.clone(),
+            offer_balance_before: offer_asset
+                .info
+                .query_pool(&deps.querier, &env.contract.address)?,
+            repayment: Uint128::zero(),
+            min_profit,
+            max_spread,
+            maker,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(flash_swap_msg(
+            &route.first_pair,
+            &ask_info.with_balance(ask_amount),
+            &offer_asset.info,
+        )?)
+        .add_attributes([
+            attr("action", "arbitrage"),
+            attr("first_pair", route.first_pair),
+            attr("second_pair", route.second_pair),
+            attr("offer_asset", offer_asset.to_string()),
+            attr("expected_profit", route.profit),
+        ]))
+}
+
+/// Swaps the ask asset received from the flash swap back to the offer asset in the second pair.
+/// The flash swap is repaid in [`reply`]. Can be called only by the first pair during an arbitrage.
+fn flash_swap_callback(
+    deps: DepsMut,
+    info: MessageInfo,
+    ask: Asset,
+    offer: Asset,
+    _msg: Binary,
+) -> Result<Response, ContractError> {
+    let mut context = REPLY_CONTEXT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::Unauthorized {})?;
+    ensure!(
+        info.sender == context.first_pair
+            && ask.info == context.ask_info
+            && offer.info == context.offer_info,
+        ContractError::Unauthorized {}
+    );
+
+    context.repayment = offer.amount;
+    REPLY_CONTEXT.save(deps.storage, &context)?;
+
+    let msg = swap_msg(
+        &context.second_pair,
+        &ask,
+        &context.offer_info,
+        context.max_spread,
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(msg, SWAP_REPLY_ID))
+        .add_attributes([
+            attr("action", "arbitrage_flash_swap_callback"),
+            attr("received", ask.to_string()),
+            attr("repayment", offer.to_string()),
+        ]))
+}
+
+/// Repays the flash swap to the first pair and sends the profit to the Maker.
+/// The whole arbitrage reverts if the profit is below the minimum.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SWAP_REPLY_ID => {
+            let context = REPLY_CONTEXT.load(deps.storage)?;
+            REPLY_CONTEXT.remove(deps.storage);
+
+            let profit = context
+                .offer_info
+                .query_pool(&deps.querier, &env.contract.address)?
+                .saturating_sub(context.offer_balance_before)
+                .saturating_sub(context.repayment);
+            if profit < context.min_profit {
+                return Err(ContractError::NoProfitableArbitrage {
+                    profit,
+                    min_profit: context.min_profit,
+                });
+            }
+
+            Ok(Response::new()
+                .add_messages([
+                    context
+                        .offer_info
+                        .with_balance(context.repayment)
+                        .into_msg(&context.first_pair)?,
+                    context
+                        .offer_info
+                        .with_balance(profit)
+                        .into_msg(&context.maker)?,
+                ])
+                .add_attributes([
+                    attr("action", "arbitrage_swap_reply"),
+                    attr("repayment", context.repayment),
+                    attr("profit", profit),
+                    attr("maker", context.maker),
+                ]))
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+/// Adds and removes keepers. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    add_keepers: Option<Vec<String>>,
+    remove_keepers: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(add_keepers) = add_keepers {
+        config
+            .keepers
+            .extend(validate_addresses(deps.api, &add_keepers)?);
+        config.keepers.sort();
+        config.keepers.dedup();
+        attributes.push(attr("add_keepers", add_keepers.join(",")));
+    }
+
+    if let Some(remove_keepers) = remove_keepers {
+        let remove_keepers = validate_addresses(deps.api, &remove_keepers)?;
+        config
+            .keepers
+            .retain(|keeper| !remove_keepers.contains(keeper));
+        attributes.push(attr(
+            "remove_keepers",
+            remove_keepers
+                .iter()
+                .map(Addr::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Withdraws assets stuck in the contract. Only the owner can execute this.
+fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let receiver = receiver
+        .map(|receiver| deps.api.addr_validate(&receiver))
+        .transpose()?
+        .unwrap_or(config.owner);
+
+    let messages = assets
+        .iter()
+        .map(|asset| asset.clone().into_msg(&receiver))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes([attr("action", "withdraw"), attr("receiver", receiver)]))
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+/// This enum describes arbitrage executor contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Pairs {0} and {1} must be different pools of the same two assets")]
+    PairsMismatch(String, String),
+
+    #[error("Pair {0} wasn't created by the factory")]
+    UnknownPair(String),
+
+    #[error("Asset {0} doesn't belong to the pairs")]
+    InvalidOfferAsset(String),
+
+    #[error("No profitable arbitrage: expected profit {profit}, required at least {min_profit}")]
+    NoProfitableArbitrage {
+        profit: Uint128,
+        min_profit: Uint128,
+    },
+
+    #[error("Maker is not set in the factory")]
+    MakerNotSet {},
+
+    #[error("Failed to parse reply")]
+    FailedToParseReply {},
+}
@@ -0,0 +1,38 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env};
+
+use astroport::arb_executor::QueryMsg;
+
+use crate::error::ContractError;
+use crate::state::CONFIG;
+use crate::utils::{check_pairs, find_best_route};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::SimulateArbitrage {
+            pair_a,
+            pair_b,
+            offer_asset,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let (pair_a_info, pair_b_info, ask_info) = check_pairs(
+                &deps.querier,
+                &config.factory_addr,
+                &pair_a,
+                &pair_b,
+                &offer_asset.info,
+            )?;
+
+            Ok(to_json_binary(&find_best_route(
+                &deps.querier,
+                &pair_a_info.contract_addr,
+                &pair_b_info.contract_addr,
+                &offer_asset,
+                &ask_info,
+            )?)?)
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::Item;
+
+use astroport::arb_executor::Config;
+use astroport::asset::AssetInfo;
+use astroport::common::OwnershipProposal;
+
+/// Stores the contract configuration
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest contract ownership transfer proposal
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Temporarily stores the context of an arbitrage until the flash swap is repaid
+pub const REPLY_CONTEXT: Item<ReplyContext> = Item::new("reply_context");
+
+/// The state needed to continue and finalize an arbitrage in the flash swap callback and the reply handler.
+#[cw_serde]
+pub struct ReplyContext {
+    /// The pair where the offer asset is flash swapped
+    pub first_pair: Addr,
+    /// The pair where the offer asset is bought back
+    pub second_pair: Addr,
+    /// The asset owed to the first pair
+    pub offer_info: AssetInfo,
+    /// The asset received from the flash swap
+    pub ask_info: AssetInfo,
+    /// Contract's offer asset balance before the arbitrage
+    pub offer_balance_before: Uint128,
+    /// The amount owed to the first pair. Set in the flash swap callback
+    pub repayment: Uint128,
+    /// The minimum profit denominated in the offer asset
+    pub min_profit: Uint128,
+    /// The maximum spread of the swap in the second pair
+    pub max_spread: Option<Decimal>,
+    /// Receives the profit
+    pub maker: Addr,
+}
@@ -0,0 +1,192 @@
+use cosmwasm_std::{
+    to_json_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, QuerierWrapper, StdResult,
+    Uint128,
+};
+use cw20::Cw20ExecuteMsg;
+
+use astroport::arb_executor::ArbitrageSimulation;
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::pair::{
+    Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
+    SimulationResponse,
+};
+use astroport::querier::query_pair_info;
+
+use crate::error::ContractError;
+
+/// Queries both pairs and checks that they are different factory pools of the same two assets
+/// and that the offer asset belongs to them. Returns the asset received in the first swap.
+pub fn check_pairs(
+    querier: &QuerierWrapper,
+    factory: &Addr,
+    pair_a: &str,
+    pair_b: &str,
+    offer_info: &AssetInfo,
+) -> Result<(PairInfo, PairInfo, AssetInfo), ContractError> {
+    let pair_a_info = query_factory_pair(querier, factory, pair_a)?;
+    let pair_b_info = query_factory_pair(querier, factory, pair_b)?;
+
+    let same_assets = pair_a_info.asset_infos.len() == 2
+        && pair_b_info.asset_infos.len() == 2
+        && pair_a_info
+            .asset_infos
+            .iter()
+            .all(|info| pair_b_info.asset_infos.contains(info));
+    if pair_a_info.contract_addr == pair_b_info.contract_addr || !same_assets {
+        return Err(ContractError::PairsMismatch(
+            pair_a.to_string(),
+            pair_b.to_string(),
+        ));
+    }
+
+    if !pair_a_info.asset_infos.contains(offer_info) {
+        return Err(ContractError::InvalidOfferAsset(offer_info.to_string()));
+    }
+    let ask_info = pair_a_info
+        .asset_infos
+        .iter()
+        .find(|info| *info != offer_info)
+        .cloned()
+        .ok_or_else(|| ContractError::InvalidOfferAsset(offer_info.to_string()))?;
+
+    Ok((pair_a_info, pair_b_info, ask_info))
+}
+
+/// Returns the pair info if the pair is registered in the factory or was created by it.
+/// The factory registers only one pair per asset set, thus other pools of the same assets
+/// are recognized by their creator.
+fn query_factory_pair(
+    querier: &QuerierWrapper,
+    factory: &Addr,
+    pair: &str,
+) -> Result<PairInfo, ContractError> {
+    let pair_info: PairInfo = querier.query_wasm_smart(pair, &PairQueryMsg::Pair {})?;
+
+    let registered = query_pair_info(querier, factory, &pair_info.asset_infos)
+        .map(|registered| registered.contract_addr == pair_info.contract_addr)
+        .unwrap_or_default();
+    if !registered && querier.query_wasm_contract_info(pair)?.creator != factory.as_str() {
+        return Err(ContractError::UnknownPair(pair.to_string()));
+    }
+
+    Ok(pair_info)
+}
+
+/// Returns the amount of ask assets the pair returns for the offer asset.
+pub fn simulate_swap(
+    querier: &QuerierWrapper,
+    pair: &Addr,
+    offer_asset: &Asset,
+    ask_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    let response: SimulationResponse = querier.query_wasm_smart(
+        pair,
+        &PairQueryMsg::Simulation {
+            offer_asset: offer_asset.clone(),
+            ask_asset_info: Some(ask_info.clone()),
+        },
+    )?;
+
+    Ok(response.return_amount)
+}
+
+/// Simulates selling the offer asset in one pair and buying it back in the other one
+/// in both directions and returns the more profitable route.
+pub fn find_best_route(
+    querier: &QuerierWrapper,
+    pair_a: &Addr,
+    pair_b: &Addr,
+    offer_asset: &Asset,
+    ask_info: &AssetInfo,
+) -> StdResult<ArbitrageSimulation> {
+    let simulate_route = |first_pair: &Addr, second_pair: &Addr| -> StdResult<_> {
+        let intermediate = simulate_swap(querier, first_pair, offer_asset, ask_info)?;
+        let return_amount = if intermediate.is_zero() {
+            Uint128::zero()
+        } else {
+            simulate_swap(
+                querier,
+                second_pair,
+                &ask_info.with_balance(intermediate),
+                &offer_asset.info,
+            )?
+        };
+
+        Ok(ArbitrageSimulation {
+            first_pair: first_pair.clone(),
+            second_pair: second_pair.clone(),
+            return_amount,
+            profit: return_amount.saturating_sub(offer_asset.amount),
+        })
+    };
+
+    let a_to_b = simulate_route(pair_a, pair_b)?;
+    let b_to_a = simulate_route(pair_b, pair_a)?;
+
+    if b_to_a.return_amount > a_to_b.return_amount {
+        Ok(b_to_a)
+    } else {
+        Ok(a_to_b)
+    }
+}
+
+/// Builds a flash swap message. The ask asset is sent to the contract before it pays for it.
+pub fn flash_swap_msg(
+    pair: &Addr,
+    ask_asset: &Asset,
+    offer_info: &AssetInfo,
+) -> StdResult<CosmosMsg> {
+    Ok(wasm_execute(
+        pair,
+        &PairExecuteMsg::FlashSwap {
+            ask_asset: ask_asset.clone(),
+            offer_asset_info: offer_info.clone(),
+            msg: Binary::default(),
+        },
+        vec![],
+    )?
+    .into())
+}
+
+/// Builds a swap message. The spread is checked by the pair against `max_spread`,
+/// the arbitrage outcome is additionally validated by the contract after the swap.
+pub fn swap_msg(
+    pair: &Addr,
+    offer_asset: &Asset,
+    ask_info: &AssetInfo,
+    max_spread: Option<Decimal>,
+) -> StdResult<CosmosMsg> {
+    let msg = match &offer_asset.info {
+        AssetInfo::NativeToken { .. } => wasm_execute(
+            pair,
+            &PairExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset_info: Some(ask_info.clone()),
+                belief_price: None,
+                max_spread,
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            vec![offer_asset.as_coin()?],
+        )?,
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: offer_asset.amount,
+                msg: to_json_binary(&PairCw20HookMsg::Swap {
+                    ask_asset_info: Some(ask_info.clone()),
+                    belief_price: None,
+                    max_spread,
+                    to: None,
+                    deadline: None,
+                    to_commitment: None,
+                })?,
+            },
+            vec![],
+        )?,
+    };
+
+    Ok(msg.into())
+}
@@ -0,0 +1,504 @@
+#![cfg(not(tarpaulin_include))]
+
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, to_json_binary, Addr, Coin, Decimal, Uint128};
+
+use astroport::arb_executor::{ArbitrageSimulation, Config, ExecuteMsg, InstantiateMsg, QueryMsg};
+use astroport::asset::{native_asset_info, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::pair::XYKPoolUpdateParams;
+use astroport_arb_executor::error::ContractError;
+use astroport_test::cw_multi_test::{AppBuilder, ContractWrapper, Executor};
+use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+
+const OWNER: &str = "owner";
+const KEEPER: &str = "keeper";
+const MAKER: &str = "maker";
+
+struct TestSuite {
+    app: TestApp,
+    executor: Addr,
+    factory: Addr,
+    pair_code_id: u64,
+    pair_a: Addr,
+    pair_b: Addr,
+}
+
+fn asset_infos() -> Vec<AssetInfo> {
+    vec![
+        native_asset_info("uusd".to_string()),
+        native_asset_info("uluna".to_string()),
+    ]
+}
+
+fn create_pair(app: &mut TestApp, factory: &Addr) -> Addr {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: asset_infos(),
+            init_params: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let pair = app
+        .wrap()
+        .query_wasm_smart::<PairInfo>(
+            factory,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: asset_infos(),
+            },
+        )
+        .unwrap()
+        .contract_addr;
+
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        pair.clone(),
+        &astroport::pair::ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::EnableFlashLoans { fee_bps: 5 }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    pair
+}
+
+/// Creates two XYK pools of the same assets with flash loans enabled. The factory holds only
+/// one pair per asset set, thus the first pool is deregistered before the second one is created.
+fn setup() -> TestSuite {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = AppBuilder::new_custom()
+        .with_stargate(MockStargate::default())
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &owner,
+                    vec![
+                        coin(100_000_000_000, "uusd"),
+                        coin(100_000_000_000, "uluna"),
+                    ],
+                )
+                .unwrap()
+        });
+
+    let pair_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            astroport_pair::contract::query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply),
+    ));
+    let factory_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    ));
+    let executor_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_arb_executor::contract::execute,
+            astroport_arb_executor::contract::instantiate,
+            astroport_arb_executor::query::query,
+        )
+        .with_reply_empty(astroport_arb_executor::contract::reply),
+    ));
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                pair_configs: vec![PairConfig {
+                    code_id: pair_code_id,
+                    pair_type: PairType::Xyk {},
+                    total_fee_bps: 30,
+                    maker_fee_bps: 0,
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                    permissioned: false,
+                    min_trade_notional: None,
+                }],
+                token_code_id: 0,
+                fee_address: Some(MAKER.to_string()),
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: "coin_registry".to_string(),
+                tracker_config: None,
+                ownership_timelock: None,
+            },
+            &[],
+            "Factory",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::UpdateFlashLoans { enabled: true },
+        &[],
+    )
+    .unwrap();
+
+    let pair_a = create_pair(&mut app, &factory);
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::Deregister {
+            asset_infos: asset_infos(),
+        },
+        &[],
+    )
+    .unwrap();
+    let pair_b = create_pair(&mut app, &factory);
+
+    let executor = app
+        .instantiate_contract(
+            executor_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+                factory_addr: factory.to_string(),
+                keepers: vec![KEEPER.to_string()],
+            },
+            &[],
+            "Arbitrage executor",
+            None,
+        )
+        .unwrap();
+
+    TestSuite {
+        app,
+        executor,
+        factory,
+        pair_code_id,
+        pair_a,
+        pair_b,
+    }
+}
+
+fn provide(env: &mut TestSuite, pair: &Addr, uusd: u128, uluna: u128) {
+    let infos = asset_infos();
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            pair.clone(),
+            &astroport::pair::ExecuteMsg::ProvideLiquidity {
+                assets: vec![infos[0].with_balance(uusd), infos[1].with_balance(uluna)],
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+                min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
+            },
+            &[coin(uluna, "uluna"), coin(uusd, "uusd")],
+        )
+        .unwrap();
+}
+
+fn fund(env: &mut TestSuite, funds: &[Coin]) {
+    env.app
+        .send_tokens(Addr::unchecked(OWNER), env.executor.clone(), funds)
+        .unwrap();
+}
+
+fn balance(env: &TestSuite, user: &Addr, denom: &str) -> u128 {
+    env.app
+        .wrap()
+        .query_balance(user, denom)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+fn arbitrage_msg(env: &TestSuite, amount: u128, min_profit: Option<u128>) -> ExecuteMsg {
+    ExecuteMsg::Arbitrage {
+        pair_a: env.pair_a.to_string(),
+        pair_b: env.pair_b.to_string(),
+        offer_asset: asset_infos()[0].with_balance(amount),
+        min_profit: min_profit.map(Uint128::new),
+        max_spread: Some(Decimal::from_str("0.05").unwrap()),
+    }
+}
+
+#[test]
+fn test_arbitrage() {
+    let mut env = setup();
+    let (pair_a, pair_b) = (env.pair_a.clone(), env.pair_b.clone());
+    let maker = Addr::unchecked(MAKER);
+
+    // uusd is cheaper in the first pool
+    provide(&mut env, &pair_a, 1_000_000_000, 1_000_000_000);
+    provide(&mut env, &pair_b, 1_000_000_000, 1_200_000_000);
+
+    let simulation: ArbitrageSimulation = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.executor,
+            &QueryMsg::SimulateArbitrage {
+                pair_a: pair_a.to_string(),
+                pair_b: pair_b.to_string(),
+                offer_asset: asset_infos()[0].with_balance(10_000_000u128),
+            },
+        )
+        .unwrap();
+    assert_eq!(simulation.first_pair, pair_b);
+    assert_eq!(simulation.second_pair, pair_a);
+    assert!(!simulation.profit.is_zero());
+
+    // Only the owner or keepers can trigger arbitrage
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked("random"),
+            env.executor.clone(),
+            &arbitrage_msg(&env, 10_000_000, None),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let min_profit = simulation.profit + Uint128::one();
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &arbitrage_msg(&env, 10_000_000, Some(min_profit.u128())),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoProfitableArbitrage {
+            profit: simulation.profit,
+            min_profit,
+        }
+    );
+
+    env.app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &arbitrage_msg(&env, 10_000_000, None),
+            &[],
+        )
+        .unwrap();
+
+    // No inventory is needed. The profit goes to the Maker and is lower than the simulated one
+    // by the flash swap fee of the first pair
+    let profit = balance(&env, &maker, "uusd");
+    assert!(profit > 0 && profit < simulation.profit.u128());
+    assert_eq!(balance(&env, &env.executor, "uusd"), 0);
+    assert_eq!(balance(&env, &env.executor, "uluna"), 0);
+    assert_eq!(balance(&env, &Addr::unchecked(KEEPER), "uusd"), 0);
+
+    // The flash swap callback can't be called outside of an arbitrage
+    let err = env
+        .app
+        .execute_contract(
+            pair_b.clone(),
+            env.executor.clone(),
+            &ExecuteMsg::FlashSwapCallback {
+                ask: asset_infos()[1].with_balance(1000u128),
+                offer: asset_infos()[0].with_balance(1000u128),
+                msg: Default::default(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // A trade which overshoots the remaining gap is rejected
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &arbitrage_msg(&env, 100_000_000, None),
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoProfitableArbitrage { .. }
+    ));
+}
+
+#[test]
+fn test_pairs_validation() {
+    let mut env = setup();
+    let pair_a = env.pair_a.clone();
+    provide(&mut env, &pair_a, 1_000_000_000, 1_000_000_000);
+
+    // Pools which weren't created by the factory are rejected
+    let foreign_pair = env
+        .app
+        .instantiate_contract(
+            env.pair_code_id,
+            Addr::unchecked(OWNER),
+            &astroport::pair::InstantiateMsg {
+                pair_type: PairType::Xyk {},
+                asset_infos: asset_infos(),
+                token_code_id: 0,
+                factory_addr: env.factory.to_string(),
+                init_params: None,
+            },
+            &[],
+            "Foreign pair",
+            None,
+        )
+        .unwrap();
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &ExecuteMsg::Arbitrage {
+                pair_a: pair_a.to_string(),
+                pair_b: foreign_pair.to_string(),
+                offer_asset: asset_infos()[0].with_balance(10_000_000u128),
+                min_profit: None,
+                max_spread: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnknownPair(foreign_pair.to_string())
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &ExecuteMsg::Arbitrage {
+                pair_a: pair_a.to_string(),
+                pair_b: pair_a.to_string(),
+                offer_asset: asset_infos()[0].with_balance(10_000_000u128),
+                min_profit: None,
+                max_spread: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairsMismatch(pair_a.to_string(), pair_a.to_string())
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &ExecuteMsg::Arbitrage {
+                pair_a: env.pair_a.to_string(),
+                pair_b: env.pair_b.to_string(),
+                offer_asset: native_asset_info("uatom".to_string()).with_balance(10_000_000u128),
+                min_profit: None,
+                max_spread: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidOfferAsset("uatom".to_string())
+    );
+}
+
+#[test]
+fn test_update_config_and_withdraw() {
+    let mut env = setup();
+    let owner = Addr::unchecked(OWNER);
+    fund(&mut env, &[coin(100_000_000, "uusd")]);
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &ExecuteMsg::UpdateConfig {
+                add_keepers: Some(vec!["keeper2".to_string()]),
+                remove_keepers: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    env.app
+        .execute_contract(
+            owner.clone(),
+            env.executor.clone(),
+            &ExecuteMsg::UpdateConfig {
+                add_keepers: Some(vec!["keeper2".to_string()]),
+                remove_keepers: Some(vec![KEEPER.to_string()]),
+            },
+            &[],
+        )
+        .unwrap();
+    let config: Config = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.executor, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.keepers, vec![Addr::unchecked("keeper2")]);
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.executor.clone(),
+            &ExecuteMsg::Withdraw {
+                assets: vec![asset_infos()[0].with_balance(100_000_000u128)],
+                receiver: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let owner_balance = balance(&env, &owner, "uusd");
+    env.app
+        .execute_contract(
+            owner.clone(),
+            env.executor.clone(),
+            &ExecuteMsg::Withdraw {
+                assets: vec![asset_infos()[0].with_balance(100_000_000u128)],
+                receiver: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(balance(&env, &owner, "uusd"), owner_balance + 100_000_000);
+    assert_eq!(balance(&env, &env.executor, "uusd"), 0);
+}
@@ -1,10 +1,11 @@
 use cosmwasm_schema::write_api;
 
-use astroport::astro_converter::{ExecuteMsg, InstantiateMsg};
+use astroport::astro_converter::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
+        query: QueryMsg,
     }
 }
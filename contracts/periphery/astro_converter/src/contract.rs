@@ -1,21 +1,24 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, coins, ensure, from_json, to_json_binary, wasm_execute, Api, BankMsg, Binary,
-    CosmosMsg, CustomMsg, Deps, DepsMut, Env, IbcMsg, IbcTimeout, MessageInfo, QuerierWrapper,
-    Response, StdError, StdResult,
+    attr, coin, coins, ensure, from_json, to_json_binary, wasm_execute, Addr, Api, BankMsg, Binary,
+    CosmosMsg, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, IbcMsg, IbcTimeout, MessageInfo,
+    Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
-use cw_utils::{must_pay, nonpayable};
+use cw_storage_plus::Bound;
+use cw_utils::{must_pay, nonpayable, one_coin};
 
-use astroport::asset::{addr_opt_validate, validate_native_denom, AssetInfo};
+use astroport::asset::{addr_opt_validate, validate_native_denom, Asset, AssetInfo, AssetInfoExt};
 use astroport::astro_converter::{
-    Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, DEFAULT_TIMEOUT, TIMEOUT_LIMITS,
+    Config, ConversionStatsResponse, Cw20HookMsg, EpochConversion, ExecuteMsg, IbcForward,
+    InstantiateMsg, Migration, MigrationInfo, QueryMsg, CONVERSION_EPOCH_LENGTH, DEFAULT_TIMEOUT,
+    MAX_EPOCHS_LIMIT, MAX_MIGRATIONS_LIMIT, TIMEOUT_LIMITS,
 };
 
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{CONFIG, EPOCH_CAP, EPOCH_CONVERTED, MIGRATED, MIGRATIONS, TOTAL_CONVERTED};
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -94,27 +97,387 @@ pub fn execute(
     let config = CONFIG.load(deps.storage)?;
 
     match msg {
-        ExecuteMsg::Receive(cw20_msg) => cw20_receive(deps.api, config, info, cw20_msg),
-        ExecuteMsg::Convert { receiver } => convert(deps.api, config, info, receiver),
+        ExecuteMsg::Receive(cw20_msg) => {
+            cw20_receive(deps.storage, deps.api, &env, config, info, cw20_msg)
+        }
+        ExecuteMsg::Convert { receiver } => {
+            convert(deps.storage, deps.api, &env, config, info, receiver)
+        }
         ExecuteMsg::TransferForBurning { timeout } => {
             ibc_transfer_for_burning(deps.querier, env, info, config, timeout)
         }
         ExecuteMsg::Burn {} => burn(deps.querier, env, info, config),
+        ExecuteMsg::UpdateEpochCap { epoch_cap } => {
+            update_epoch_cap(deps.storage, deps.querier, env, info, epoch_cap)
+        }
+        ExecuteMsg::MigrateTokens {
+            receiver,
+            ibc_forward,
+        } => {
+            let asset = one_coin(&info)?.into();
+            migrate_tokens(
+                deps.storage,
+                deps.api,
+                &env,
+                asset,
+                info.sender,
+                receiver,
+                ibc_forward,
+            )
+        }
+        ExecuteMsg::SetMigration { migration } => set_migration(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            info,
+            config,
+            migration,
+        ),
+        ExecuteMsg::RemoveMigration { from } => {
+            remove_migration(deps.storage, deps.querier, env, info, from)
+        }
+        ExecuteMsg::SweepMigrated { from, recipient } => sweep_migrated(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            info,
+            config,
+            from,
+            recipient,
+        ),
+    }
+}
+
+/// Add converted amount to the current epoch and total statistics.
+/// Fails if the amount exceeds the remaining per-epoch cap.
+pub fn record_conversion(
+    storage: &mut dyn Storage,
+    env: &Env,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let epoch = env.block.time.seconds() / CONVERSION_EPOCH_LENGTH;
+    let epoch_converted = EPOCH_CONVERTED
+        .may_load(storage, epoch)?
+        .unwrap_or_default();
+
+    if let Some(epoch_cap) = EPOCH_CAP.may_load(storage)? {
+        let remaining = epoch_cap.saturating_sub(epoch_converted);
+        ensure!(
+            amount <= remaining,
+            ContractError::EpochCapExceeded { remaining }
+        );
+    }
+
+    EPOCH_CONVERTED.save(storage, epoch, &(epoch_converted + amount))?;
+    let total_converted = TOTAL_CONVERTED.may_load(storage)?.unwrap_or_default();
+    TOTAL_CONVERTED.save(storage, &(total_converted + amount))?;
+
+    Ok(())
+}
+
+/// Set or remove the per-epoch conversion cap. Only the contract admin can execute this.
+pub fn update_epoch_cap<C: CustomQuery, M: CustomMsg>(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper<C>,
+    env: Env,
+    info: MessageInfo,
+    epoch_cap: Option<Uint128>,
+) -> Result<Response<M>, ContractError> {
+    nonpayable(&info)?;
+    ensure_admin(querier, &env, &info)?;
+
+    let cap_attr = match epoch_cap {
+        Some(epoch_cap) => {
+            EPOCH_CAP.save(storage, &epoch_cap)?;
+            epoch_cap.to_string()
+        }
+        None => {
+            EPOCH_CAP.remove(storage);
+            "none".to_string()
+        }
+    };
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_epoch_cap"),
+        attr("epoch_cap", cap_attr),
+    ]))
+}
+
+/// Checks that the sender is the contract admin.
+fn ensure_admin<C: CustomQuery>(
+    querier: QuerierWrapper<C>,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    let admin = querier
+        .query_wasm_contract_info(&env.contract.address)?
+        .admin;
+    ensure!(
+        admin.as_deref() == Some(info.sender.as_str()),
+        ContractError::Unauthorized {}
+    );
+
+    Ok(())
+}
+
+/// Register or update a migration. Only the contract admin can execute this.
+pub fn set_migration<C: CustomQuery, M: CustomMsg>(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    querier: QuerierWrapper<C>,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    migration: Migration,
+) -> Result<Response<M>, ContractError> {
+    nonpayable(&info)?;
+    ensure_admin(querier, &env, &info)?;
+
+    validate_native_denom(&migration.to_denom)?;
+    migration.from.check(api)?;
+    ensure!(
+        migration.from != AssetInfo::native(&migration.to_denom),
+        StdError::generic_err("Cannot convert to the same asset")
+    );
+    ensure!(
+        migration.from != config.old_astro_asset_info,
+        StdError::generic_err("Old ASTRO is converted via the default conversion")
+    );
+    ensure!(
+        !migration.rate.is_zero(),
+        StdError::generic_err("Rate must be greater than zero")
+    );
+    if let Some(deadline) = migration.deadline {
+        ensure!(
+            deadline > env.block.time.seconds(),
+            StdError::generic_err("Deadline must be in the future")
+        );
     }
+
+    MIGRATIONS.save(storage, &migration.from, &migration)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_migration"),
+        attr("from", migration.from.to_string()),
+        attr("to", migration.to_denom),
+        attr("rate", migration.rate.to_string()),
+        attr("burn_source", migration.burn_source.to_string()),
+    ]))
+}
+
+/// Close a migration. Migrated amounts are kept. Only the contract admin can execute this.
+pub fn remove_migration<C: CustomQuery, M: CustomMsg>(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper<C>,
+    env: Env,
+    info: MessageInfo,
+    from: AssetInfo,
+) -> Result<Response<M>, ContractError> {
+    nonpayable(&info)?;
+    ensure_admin(querier, &env, &info)?;
+
+    ensure!(
+        MIGRATIONS.has(storage, &from),
+        ContractError::MigrationNotFound(from.to_string())
+    );
+    MIGRATIONS.remove(storage, &from);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "remove_migration"),
+        attr("from", from.to_string()),
+    ]))
+}
+
+/// Send the whole contract balance of source tokens kept by migrations without `burn_source`
+/// to `recipient` (sender by default). Only assets received via migrations can be swept
+/// and denoms released by conversions are never touched. Only the contract admin can execute this.
+#[allow(clippy::too_many_arguments)]
+pub fn sweep_migrated<C: CustomQuery, M: CustomMsg>(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    querier: QuerierWrapper<C>,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    from: AssetInfo,
+    recipient: Option<String>,
+) -> Result<Response<M>, ContractError> {
+    nonpayable(&info)?;
+    ensure_admin(querier, &env, &info)?;
+
+    ensure!(
+        MIGRATED.has(storage, &from),
+        ContractError::MigrationNotFound(from.to_string())
+    );
+
+    if let AssetInfo::NativeToken { denom } = &from {
+        let mut released_denoms = MIGRATIONS
+            .range_raw(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, migration)| migration.to_denom))
+            .collect::<StdResult<Vec<_>>>()?;
+        released_denoms.push(config.new_astro_denom);
+        ensure!(
+            !released_denoms.contains(denom),
+            StdError::generic_err(format!(
+                "{denom} is released by conversions and can't be swept"
+            ))
+        );
+    }
+
+    let amount = from.query_pool(&querier, &env.contract.address)?;
+    ensure!(
+        !amount.is_zero(),
+        StdError::generic_err("No tokens to sweep")
+    );
+
+    addr_opt_validate(api, &recipient)?;
+    let recipient = recipient.unwrap_or_else(|| info.sender.to_string());
+    let swept = from.with_balance(amount);
+
+    Ok(Response::new()
+        .add_message(swept.clone().into_msg(&recipient)?)
+        .add_attributes([
+            attr("action", "sweep_migrated"),
+            attr("swept", swept.to_string()),
+            attr("recipient", recipient),
+        ]))
+}
+
+/// Release converted tokens for the received asset according to its registered migration.
+/// Converted tokens are sent to `receiver` (sender by default) or forwarded over IBC.
+pub fn migrate_tokens<M: CustomMsg>(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    asset: Asset,
+    sender: Addr,
+    receiver: Option<String>,
+    ibc_forward: Option<IbcForward>,
+) -> Result<Response<M>, ContractError> {
+    let migration = MIGRATIONS
+        .may_load(storage, &asset.info)?
+        .ok_or_else(|| ContractError::MigrationNotFound(asset.info.to_string()))?;
+
+    if let Some(deadline) = migration.deadline {
+        ensure!(
+            env.block.time.seconds() < deadline,
+            ContractError::MigrationClosed { deadline }
+        );
+    }
+
+    let amount = asset.amount * migration.rate;
+    ensure!(
+        !amount.is_zero(),
+        StdError::generic_err("Converted amount is zero")
+    );
+    let converted = coin(amount.u128(), migration.to_denom);
+
+    let migrated = MIGRATED.may_load(storage, &asset.info)?.unwrap_or_default();
+    MIGRATED.save(storage, &asset.info, &(migrated + asset.amount))?;
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "migrate"),
+        attr("from", asset.to_string()),
+        attr("to", converted.to_string()),
+    ]);
+
+    response = match ibc_forward {
+        Some(forward) => {
+            ensure!(
+                receiver.is_none(),
+                StdError::generic_err("Receiver must be unset when forwarding over IBC")
+            );
+            let timeout = forward.timeout.unwrap_or(DEFAULT_TIMEOUT);
+            ensure!(
+                TIMEOUT_LIMITS.contains(&timeout),
+                ContractError::InvalidTimeout {}
+            );
+
+            response
+                .add_message(IbcMsg::Transfer {
+                    channel_id: forward.channel.clone(),
+                    to_address: forward.receiver.clone(),
+                    amount: converted,
+                    timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(timeout)),
+                })
+                .add_attributes([
+                    attr("receiver", forward.receiver),
+                    attr("channel", forward.channel),
+                ])
+        }
+        None => {
+            addr_opt_validate(api, &receiver)?;
+            let receiver = receiver.unwrap_or_else(|| sender.to_string());
+
+            response
+                .add_message(BankMsg::Send {
+                    to_address: receiver.clone(),
+                    amount: vec![converted],
+                })
+                .add_attribute("receiver", receiver)
+        }
+    };
+
+    if migration.burn_source {
+        let burn_msg: CosmosMsg<M> = match &asset.info {
+            AssetInfo::Token { contract_addr } => wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::Burn {
+                    amount: asset.amount,
+                },
+                vec![],
+            )?
+            .into(),
+            AssetInfo::NativeToken { denom } => BankMsg::Burn {
+                amount: coins(asset.amount.u128(), denom),
+            }
+            .into(),
+        };
+        response = response.add_message(burn_msg);
+    }
+
+    Ok(response)
 }
 
 pub fn cw20_receive<M: CustomMsg>(
+    storage: &mut dyn Storage,
     api: &dyn Api,
+    env: &Env,
     config: Config,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response<M>, ContractError> {
+    let from = AssetInfo::cw20(info.sender.clone());
+    if MIGRATIONS.has(storage, &from) {
+        let hook = from_json::<Cw20HookMsg>(&cw20_msg.msg)?;
+        let sender = api.addr_validate(&cw20_msg.sender)?;
+
+        return migrate_tokens(
+            storage,
+            api,
+            env,
+            from.with_balance(cw20_msg.amount),
+            sender,
+            hook.receiver,
+            hook.ibc_forward,
+        );
+    }
+
     match config.old_astro_asset_info {
         AssetInfo::Token { contract_addr } => {
             if info.sender == contract_addr {
-                let receiver = from_json::<Cw20HookMsg>(&cw20_msg.msg)?.receiver;
+                let hook = from_json::<Cw20HookMsg>(&cw20_msg.msg)?;
+                ensure!(
+                    hook.ibc_forward.is_none(),
+                    ContractError::IbcForwardNotSupported {}
+                );
+                let receiver = hook.receiver;
                 addr_opt_validate(api, &receiver)?;
 
+                record_conversion(storage, env, cw20_msg.amount)?;
+
                 let receiver = receiver.unwrap_or(cw20_msg.sender);
                 let bank_msg = BankMsg::Send {
                     to_address: receiver.clone(),
@@ -136,7 +499,9 @@ pub fn cw20_receive<M: CustomMsg>(
 }
 
 pub fn convert<M: CustomMsg>(
+    storage: &mut dyn Storage,
     api: &dyn Api,
+    env: &Env,
     config: Config,
     info: MessageInfo,
     receiver: Option<String>,
@@ -145,6 +510,7 @@ pub fn convert<M: CustomMsg>(
         AssetInfo::NativeToken { denom } => {
             let amount = must_pay(&info, &denom)?;
             addr_opt_validate(api, &receiver)?;
+            record_conversion(storage, env, amount)?;
 
             let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
             let bank_msg = BankMsg::Send {
@@ -243,12 +609,111 @@ pub fn burn<M: CustomMsg>(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::ConversionStats {} => to_json_binary(&query_conversion_stats(deps, env)?),
+        QueryMsg::EpochConversions { start_after, limit } => {
+            to_json_binary(&query_epoch_conversions(deps, start_after, limit)?)
+        }
+        QueryMsg::Migrations { start_after, limit } => {
+            to_json_binary(&query_migrations(deps, start_after, limit)?)
+        }
     }
 }
 
+pub fn query_conversion_stats(deps: Deps, env: Env) -> StdResult<ConversionStatsResponse> {
+    let epoch = env.block.time.seconds() / CONVERSION_EPOCH_LENGTH;
+    let epoch_converted = EPOCH_CONVERTED
+        .may_load(deps.storage, epoch)?
+        .unwrap_or_default();
+    let epoch_cap = EPOCH_CAP.may_load(deps.storage)?;
+
+    Ok(ConversionStatsResponse {
+        total_converted: TOTAL_CONVERTED.may_load(deps.storage)?.unwrap_or_default(),
+        epoch,
+        epoch_start: epoch * CONVERSION_EPOCH_LENGTH,
+        epoch_converted,
+        epoch_cap,
+        epoch_remaining: epoch_cap.map(|cap| cap.saturating_sub(epoch_converted)),
+    })
+}
+
+pub fn query_epoch_conversions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<EpochConversion>> {
+    let limit = limit.unwrap_or(MAX_EPOCHS_LIMIT).min(MAX_EPOCHS_LIMIT) as usize;
+    EPOCH_CONVERTED
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(epoch, converted)| EpochConversion {
+                epoch,
+                epoch_start: epoch * CONVERSION_EPOCH_LENGTH,
+                converted,
+            })
+        })
+        .collect()
+}
+
+pub fn query_migrations(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u8>,
+) -> StdResult<Vec<MigrationInfo>> {
+    let limit = limit
+        .unwrap_or(MAX_MIGRATIONS_LIMIT)
+        .min(MAX_MIGRATIONS_LIMIT) as usize;
+    MIGRATIONS
+        .range_raw(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (_, migration) = item?;
+            let total_migrated = MIGRATED
+                .may_load(deps.storage, &migration.from)?
+                .unwrap_or_default();
+
+            Ok(MigrationInfo {
+                migration,
+                total_migrated,
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        CONTRACT_NAME => match contract_version.version.as_ref() {
+            "1.0.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
+}
+
 #[cfg(test)]
 mod testing {
     use cosmwasm_std::testing::{
@@ -256,8 +721,8 @@ mod testing {
         MockQuerier,
     };
     use cosmwasm_std::{
-        from_json, to_json_binary, Addr, ContractResult, Empty, SubMsg, SystemResult, Uint128,
-        WasmMsg, WasmQuery,
+        from_json, to_json_binary, Addr, ContractInfoResponse, ContractResult, Decimal, SubMsg,
+        SystemResult, WasmMsg, WasmQuery,
     };
     use cw_utils::PaymentError::{MissingDenom, NoFunds};
 
@@ -334,6 +799,8 @@ mod testing {
             outpost_burn_params: None,
         };
         let mock_api = MockApi::default();
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
         let mut cw20_msg = Cw20ReceiveMsg {
             sender: "sender".to_string(),
@@ -341,7 +808,9 @@ mod testing {
             msg: to_json_binary(&Empty {}).unwrap(),
         };
         let err = cw20_receive::<Empty>(
+            deps.as_mut().storage,
             &mock_api,
+            &env,
             config.clone(),
             mock_info("random_cw20", &[]),
             cw20_msg.clone(),
@@ -352,7 +821,9 @@ mod testing {
         config.old_astro_asset_info = AssetInfo::cw20_unchecked("terra1xxx");
 
         let err = cw20_receive::<Empty>(
+            deps.as_mut().storage,
             &mock_api,
+            &env,
             config.clone(),
             mock_info("random_cw20", &[]),
             cw20_msg.clone(),
@@ -364,7 +835,9 @@ mod testing {
         );
 
         let res = cw20_receive::<Empty>(
+            deps.as_mut().storage,
             &mock_api,
+            &env,
             config.clone(),
             mock_info("terra1xxx", &[]),
             cw20_msg.clone(),
@@ -381,10 +854,13 @@ mod testing {
 
         cw20_msg.msg = to_json_binary(&Cw20HookMsg {
             receiver: Some("receiver".to_string()),
+            ibc_forward: None,
         })
         .unwrap();
         let res = cw20_receive::<Empty>(
+            deps.as_mut().storage,
             &mock_api,
+            &env,
             config.clone(),
             mock_info("terra1xxx", &[]),
             cw20_msg.clone(),
@@ -408,26 +884,60 @@ mod testing {
             outpost_burn_params: None,
         };
         let mock_api = MockApi::default();
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
         let info = mock_info("sender", &[]);
-        let err = convert::<Empty>(&mock_api, config.clone(), info, None).unwrap_err();
+        let err = convert::<Empty>(
+            deps.as_mut().storage,
+            &mock_api,
+            &env,
+            config.clone(),
+            info,
+            None,
+        )
+        .unwrap_err();
         assert_eq!(err, ContractError::InvalidEndpoint {});
 
         config.old_astro_asset_info = AssetInfo::native("ibc/old_astro");
 
         let info = mock_info("sender", &[]);
-        let err = convert::<Empty>(&mock_api, config.clone(), info, None).unwrap_err();
+        let err = convert::<Empty>(
+            deps.as_mut().storage,
+            &mock_api,
+            &env,
+            config.clone(),
+            info,
+            None,
+        )
+        .unwrap_err();
         assert_eq!(err, ContractError::PaymentError(NoFunds {}));
 
         let info = mock_info("sender", &coins(100, "random_coin"));
-        let err = convert::<Empty>(&mock_api, config.clone(), info, None).unwrap_err();
+        let err = convert::<Empty>(
+            deps.as_mut().storage,
+            &mock_api,
+            &env,
+            config.clone(),
+            info,
+            None,
+        )
+        .unwrap_err();
         assert_eq!(
             err,
             ContractError::PaymentError(MissingDenom("ibc/old_astro".to_string()))
         );
 
         let info = mock_info("sender", &coins(100, "ibc/old_astro"));
-        let res = convert::<Empty>(&mock_api, config.clone(), info.clone(), None).unwrap();
+        let res = convert::<Empty>(
+            deps.as_mut().storage,
+            &mock_api,
+            &env,
+            config.clone(),
+            info.clone(),
+            None,
+        )
+        .unwrap();
         assert_eq!(
             res.messages,
             [SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
@@ -437,7 +947,9 @@ mod testing {
         );
 
         let res = convert::<Empty>(
+            deps.as_mut().storage,
             &mock_api,
+            &env,
             config.clone(),
             info.clone(),
             Some("receiver".to_string()),
@@ -452,6 +964,510 @@ mod testing {
         );
     }
 
+    #[test]
+    fn test_conversion_stats_and_epoch_cap() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let mut contract_info = ContractInfoResponse::new(1, "creator");
+                contract_info.admin = Some("admin".to_string());
+                SystemResult::Ok(to_json_binary(&contract_info).into())
+            }
+            _ => unimplemented!(),
+        });
+        let mut env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            InstantiateMsg {
+                old_astro_asset_info: AssetInfo::native("ibc/old_astro"),
+                new_astro_denom: "uastro".to_string(),
+                outpost_burn_params: Some(OutpostBurnParams {
+                    terra_burn_addr: "terra1xxx".to_string(),
+                    old_astro_transfer_channel: "channel-1".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+
+        let update_cap_msg = ExecuteMsg::UpdateEpochCap {
+            epoch_cap: Some(150u128.into()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            update_cap_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            update_cap_msg,
+        )
+        .unwrap();
+
+        let convert_msg = ExecuteMsg::Convert { receiver: None };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/old_astro")),
+            convert_msg.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/old_astro")),
+            convert_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::EpochCapExceeded {
+                remaining: 50u128.into()
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(50, "ibc/old_astro")),
+            convert_msg.clone(),
+        )
+        .unwrap();
+
+        let epoch = env.block.time.seconds() / CONVERSION_EPOCH_LENGTH;
+        let stats: ConversionStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::ConversionStats {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            stats,
+            ConversionStatsResponse {
+                total_converted: 150u128.into(),
+                epoch,
+                epoch_start: epoch * CONVERSION_EPOCH_LENGTH,
+                epoch_converted: 150u128.into(),
+                epoch_cap: Some(150u128.into()),
+                epoch_remaining: Some(Uint128::zero()),
+            }
+        );
+
+        // Cap resets in the next epoch
+        env.block.time = env.block.time.plus_seconds(CONVERSION_EPOCH_LENGTH);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/old_astro")),
+            convert_msg.clone(),
+        )
+        .unwrap();
+
+        // Removing the cap allows unlimited conversions
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::UpdateEpochCap { epoch_cap: None },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(1000, "ibc/old_astro")),
+            convert_msg,
+        )
+        .unwrap();
+
+        let epochs: Vec<EpochConversion> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::EpochConversions {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            epochs,
+            [
+                EpochConversion {
+                    epoch,
+                    epoch_start: epoch * CONVERSION_EPOCH_LENGTH,
+                    converted: 150u128.into(),
+                },
+                EpochConversion {
+                    epoch: epoch + 1,
+                    epoch_start: (epoch + 1) * CONVERSION_EPOCH_LENGTH,
+                    converted: 1100u128.into(),
+                }
+            ]
+        );
+
+        let stats: ConversionStatsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::ConversionStats {}).unwrap()).unwrap();
+        assert_eq!(stats.total_converted.u128(), 1250);
+        assert_eq!(stats.epoch_remaining, None);
+    }
+
+    #[test]
+    fn test_migrations() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let mut contract_info = ContractInfoResponse::new(1, "creator");
+                contract_info.admin = Some("admin".to_string());
+                SystemResult::Ok(to_json_binary(&contract_info).into())
+            }
+            _ => unimplemented!(),
+        });
+        let mut env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            InstantiateMsg {
+                old_astro_asset_info: AssetInfo::native("ibc/old_astro"),
+                new_astro_denom: "uastro".to_string(),
+                outpost_burn_params: Some(OutpostBurnParams {
+                    terra_burn_addr: "terra1xxx".to_string(),
+                    old_astro_transfer_channel: "channel-1".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+
+        let deadline = env.block.time.seconds() + 86400;
+        let mut migration = Migration {
+            from: AssetInfo::native("ibc/bridged"),
+            to_denom: "unative".to_string(),
+            rate: Decimal::percent(200),
+            deadline: Some(deadline),
+            burn_source: true,
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::SetMigration {
+                migration: migration.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetMigration {
+                migration: Migration {
+                    from: AssetInfo::native("ibc/old_astro"),
+                    ..migration.clone()
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Old ASTRO is converted via the default conversion"
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetMigration {
+                migration: migration.clone(),
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "random_coin")),
+            ExecuteMsg::MigrateTokens {
+                receiver: None,
+                ibc_forward: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MigrationNotFound("random_coin".to_string())
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/bridged")),
+            ExecuteMsg::MigrateTokens {
+                receiver: Some("receiver".to_string()),
+                ibc_forward: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [
+                SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "receiver".to_string(),
+                    amount: coins(200, "unative")
+                })),
+                SubMsg::new(CosmosMsg::Bank(BankMsg::Burn {
+                    amount: coins(100, "ibc/bridged")
+                }))
+            ]
+        );
+
+        let ibc_forward = IbcForward {
+            channel: "channel-2".to_string(),
+            receiver: "osmo1xxx".to_string(),
+            timeout: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(50, "ibc/bridged")),
+            ExecuteMsg::MigrateTokens {
+                receiver: None,
+                ibc_forward: Some(ibc_forward.clone()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: ibc_forward.channel,
+                to_address: ibc_forward.receiver,
+                amount: coin(100, "unative"),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(DEFAULT_TIMEOUT)),
+            }))
+        );
+
+        // Cw20 tokens are migrated via the send hook and kept in the contract
+        migration = Migration {
+            from: AssetInfo::cw20_unchecked("cw20token"),
+            to_denom: "unative".to_string(),
+            rate: Decimal::percent(50),
+            deadline: None,
+            burn_source: false,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetMigration {
+                migration: migration.clone(),
+            },
+        )
+        .unwrap();
+        let cw20_msg = Cw20ReceiveMsg {
+            sender: "user".to_string(),
+            amount: 100u128.into(),
+            msg: to_json_binary(&Empty {}).unwrap(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20token", &[]),
+            ExecuteMsg::Receive(cw20_msg.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(50, "unative")
+            }))]
+        );
+
+        let migrations: Vec<MigrationInfo> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Migrations {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(migrations.len(), 2);
+        let bridged = migrations
+            .iter()
+            .find(|info| info.migration.from == AssetInfo::native("ibc/bridged"))
+            .unwrap();
+        assert_eq!(bridged.total_migrated.u128(), 150);
+
+        // Migration is closed after the deadline
+        env.block.time = env.block.time.plus_seconds(86400);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/bridged")),
+            ExecuteMsg::MigrateTokens {
+                receiver: None,
+                ibc_forward: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MigrationClosed { deadline });
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RemoveMigration {
+                from: migration.from,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("cw20token", &[]),
+            ExecuteMsg::Receive(cw20_msg),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidEndpoint {});
+    }
+
+    #[test]
+    fn test_sweep_migrated() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let mut contract_info = ContractInfoResponse::new(1, "creator");
+                contract_info.admin = Some("admin".to_string());
+                SystemResult::Ok(to_json_binary(&contract_info).into())
+            }
+            _ => unimplemented!(),
+        });
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            InstantiateMsg {
+                old_astro_asset_info: AssetInfo::native("ibc/old_astro"),
+                new_astro_denom: "uastro".to_string(),
+                outpost_burn_params: Some(OutpostBurnParams {
+                    terra_burn_addr: "terra1xxx".to_string(),
+                    old_astro_transfer_channel: "channel-1".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+
+        let migration = Migration {
+            from: AssetInfo::native("ibc/kept"),
+            to_denom: "unative".to_string(),
+            rate: Decimal::one(),
+            deadline: None,
+            burn_source: false,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetMigration {
+                migration: migration.clone(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "ibc/kept")),
+            ExecuteMsg::MigrateTokens {
+                receiver: None,
+                ibc_forward: None,
+            },
+        )
+        .unwrap();
+        deps.querier.update_balance(
+            &env.contract.address,
+            vec![coin(100, "ibc/kept"), coin(1000, "unative")],
+        );
+
+        let sweep_msg = |from: &str| ExecuteMsg::SweepMigrated {
+            from: AssetInfo::native(from),
+            recipient: None,
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            sweep_msg("ibc/kept"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Only tokens received via migrations can be swept
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            sweep_msg("unative"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MigrationNotFound("unative".to_string()));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            sweep_msg("ibc/kept"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "admin".to_string(),
+                amount: coins(100, "ibc/kept")
+            }))]
+        );
+
+        // Denoms released by other migrations are never swept
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetMigration {
+                migration: Migration {
+                    from: AssetInfo::native("ibc/older"),
+                    to_denom: "ibc/kept".to_string(),
+                    ..migration
+                },
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("admin", &[]),
+            sweep_msg("ibc/kept"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: ibc/kept is released by conversions and can't be swept"
+        );
+    }
+
     #[test]
     fn test_ibc_transfer() {
         let deps = mock_dependencies();
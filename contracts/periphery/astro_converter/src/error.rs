@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, StdError};
+use cosmwasm_std::{Addr, StdError, Uint128};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -29,4 +29,22 @@ pub enum ContractError {
 
     #[error("Invalid timeout: {0}. Max {}s, min {}s", TIMEOUT_LIMITS.end(), TIMEOUT_LIMITS.start())]
     InvalidTimeout {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Conversion exceeds the epoch cap. Remaining amount in this epoch: {remaining}")]
+    EpochCapExceeded { remaining: Uint128 },
+
+    #[error("No migration registered for {0}")]
+    MigrationNotFound(String),
+
+    #[error("Migration closed at {deadline}")]
+    MigrationClosed { deadline: u64 },
+
+    #[error("IBC forwarding is not supported for this conversion")]
+    IbcForwardNotSupported {},
+
+    #[error("Contract can't be migrated!")]
+    MigrationError {},
 }
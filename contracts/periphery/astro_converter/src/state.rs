@@ -1,5 +1,17 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::Uint128;
+use cw_storage_plus::{Item, Map};
 
-use astroport::astro_converter::Config;
+use astroport::asset::AssetInfo;
+use astroport::astro_converter::{Config, Migration};
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Max amount of old ASTRO which can be converted within one epoch. No limit if not set
+pub const EPOCH_CAP: Item<Uint128> = Item::new("epoch_cap");
+/// Total amount of converted old ASTRO
+pub const TOTAL_CONVERTED: Item<Uint128> = Item::new("total_converted");
+/// Converted amounts per epoch. key: epoch number, value: converted amount
+pub const EPOCH_CONVERTED: Map<u64, Uint128> = Map::new("epoch_converted");
+/// Migrations registered by the contract admin. key: source asset, value: migration parameters
+pub const MIGRATIONS: Map<&AssetInfo, Migration> = Map::new("migrations");
+/// Total migrated amounts. key: source asset, value: amount of source tokens received
+pub const MIGRATED: Map<&AssetInfo, Uint128> = Map::new("migrated");
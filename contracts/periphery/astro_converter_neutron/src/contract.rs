@@ -1,22 +1,25 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, ensure, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    Uint128,
+    attr, coin, ensure, from_json, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
 };
-use cw2::set_contract_version;
-use cw_utils::may_pay;
+use cw2::{get_contract_version, set_contract_version};
+use cw_utils::{may_pay, one_coin};
 use neutron_sdk::bindings::msg::{IbcFee, NeutronMsg};
 use neutron_sdk::bindings::query::NeutronQuery;
 use neutron_sdk::query::min_ibc_fee::query_min_ibc_fee;
 use neutron_sdk::sudo::msg::{RequestPacketTimeoutHeight, TransferSudoMsg};
 
-use astro_token_converter::contract::{convert, cw20_receive};
+use astro_token_converter::contract::{
+    convert, cw20_receive, migrate_tokens, remove_migration, set_migration, sweep_migrated,
+    update_epoch_cap,
+};
 use astro_token_converter::error::ContractError;
 use astro_token_converter::state::CONFIG;
 use astroport::asset::AssetInfo;
 use astroport::astro_converter::{
-    Config, ExecuteMsg, InstantiateMsg, QueryMsg, DEFAULT_TIMEOUT, TIMEOUT_LIMITS,
+    Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, DEFAULT_TIMEOUT, TIMEOUT_LIMITS,
 };
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -45,12 +48,68 @@ pub fn execute(
     let config = CONFIG.load(deps.storage)?;
 
     match msg {
-        ExecuteMsg::Receive(cw20_msg) => cw20_receive(deps.api, config, info, cw20_msg),
-        ExecuteMsg::Convert { receiver } => convert(deps.api, config, info, receiver),
+        ExecuteMsg::Receive(cw20_msg) => {
+            // Neutron requires IBC fees which are not paid for forwarded tokens
+            if let Ok(Cw20HookMsg {
+                ibc_forward: Some(_),
+                ..
+            }) = from_json(&cw20_msg.msg)
+            {
+                return Err(ContractError::IbcForwardNotSupported {});
+            }
+            cw20_receive(deps.storage, deps.api, &env, config, info, cw20_msg)
+        }
+        ExecuteMsg::Convert { receiver } => {
+            convert(deps.storage, deps.api, &env, config, info, receiver)
+        }
         ExecuteMsg::TransferForBurning { timeout } => {
             ibc_transfer_for_burning(deps.as_ref(), env, info, config, timeout)
         }
         ExecuteMsg::Burn {} => Err(ContractError::BurnError {}), // burn is only available on Terra
+        ExecuteMsg::UpdateEpochCap { epoch_cap } => {
+            update_epoch_cap(deps.storage, deps.querier, env, info, epoch_cap)
+        }
+        ExecuteMsg::MigrateTokens {
+            receiver,
+            ibc_forward,
+        } => {
+            ensure!(
+                ibc_forward.is_none(),
+                ContractError::IbcForwardNotSupported {}
+            );
+            let asset = one_coin(&info)?.into();
+            migrate_tokens(
+                deps.storage,
+                deps.api,
+                &env,
+                asset,
+                info.sender,
+                receiver,
+                None,
+            )
+        }
+        ExecuteMsg::SetMigration { migration } => set_migration(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            info,
+            config,
+            migration,
+        ),
+        ExecuteMsg::RemoveMigration { from } => {
+            remove_migration(deps.storage, deps.querier, env, info, from)
+        }
+        ExecuteMsg::SweepMigrated { from, recipient } => sweep_migrated(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            info,
+            config,
+            from,
+            recipient,
+        ),
     }
 }
 
@@ -140,6 +199,27 @@ pub fn ibc_transfer_for_burning(
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    let contract_version = get_contract_version(deps.storage)?;
+
+    match contract_version.contract.as_ref() {
+        CONTRACT_NAME => match contract_version.version.as_ref() {
+            "1.0.0" => {}
+            _ => return Err(ContractError::MigrationError {}),
+        },
+        _ => return Err(ContractError::MigrationError {}),
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("previous_contract_name", &contract_version.contract)
+        .add_attribute("previous_contract_version", &contract_version.version)
+        .add_attribute("new_contract_name", CONTRACT_NAME)
+        .add_attribute("new_contract_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(_deps: DepsMut, _env: Env, _msg: TransferSudoMsg) -> StdResult<Response> {
     // Neutron requires sudo endpoint to be implemented
@@ -8,18 +8,21 @@ use cosmos_sdk_proto::Any;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coins, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError,
-    StdResult, Uint128,
+    attr, coins, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, Uint128,
 };
 use cw2::set_contract_version;
 use cw_utils::must_pay;
 
 use astroport::asset::validate_native_denom;
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
-use astroport::fee_granter::{Config, ExecuteMsg, InstantiateMsg};
+use astroport::fee_granter::{
+    query_fee_allowance, Config, ExecuteMsg, InstantiateMsg, RenewableGrant,
+};
 
 use crate::error::ContractError;
-use crate::state::{update_admins_with_validation, CONFIG, GRANTS, OWNERSHIP_PROPOSAL};
+use crate::state::{
+    update_admins_with_validation, CONFIG, GRANTS, OWNERSHIP_PROPOSAL, RENEWABLE_GRANTS,
+};
 
 pub(crate) const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -75,6 +78,24 @@ pub fn execute(
                 bypass_amount_check,
             )
         }
+        ExecuteMsg::GrantRenewable {
+            grantee_contract,
+            amount,
+            period,
+            bypass_amount_check,
+        } => {
+            let grantee_contract = deps.api.addr_validate(&grantee_contract)?;
+            grant_renewable(
+                deps,
+                env,
+                info,
+                grantee_contract,
+                amount,
+                period,
+                bypass_amount_check,
+            )
+        }
+        ExecuteMsg::Renew {} => renew(deps, env, info),
         ExecuteMsg::Revoke { grantee_contract } => {
             let grantee_contract = deps.api.addr_validate(&grantee_contract)?;
             revoke(deps, env, info, grantee_contract)
@@ -131,52 +152,105 @@ fn grant(
     }
 
     if !bypass_amount_check {
-        let sent_amount = must_pay(&info, &config.gas_denom)?;
-        if sent_amount != amount {
-            return Err(ContractError::InvalidAmount {
-                expected: amount,
-                actual: sent_amount,
-            });
-        }
+        check_sent_amount(&info, &config.gas_denom, amount)?;
     }
 
-    GRANTS.update(
-        deps.storage,
-        &grantee_contract,
-        |existing| -> StdResult<_> {
-            match existing {
-                None => Ok(amount),
-                Some(_) => Err(StdError::generic_err(format!(
-                    "Grant already exists for {grantee_contract}",
-                ))),
-            }
-        },
-    )?;
+    if RENEWABLE_GRANTS.has(deps.storage, &grantee_contract) {
+        return Err(ContractError::GrantExists(grantee_contract.to_string()));
+    }
+    GRANTS.update(deps.storage, &grantee_contract, |existing| match existing {
+        None => Ok(amount),
+        Some(_) => Err(ContractError::GrantExists(grantee_contract.to_string())),
+    })?;
 
-    let allowance = BasicAllowance {
-        spend_limit: vec![SdkCoin {
-            denom: config.gas_denom,
-            amount: amount.to_string(),
-        }],
-        expiration: None,
-    };
-    let grant_msg = MsgGrantAllowance {
-        granter: env.contract.address.to_string(),
-        grantee: grantee_contract.to_string(),
-        allowance: Some(Any {
-            type_url: BasicAllowance::TYPE_URL.to_string(),
-            value: allowance.encode_to_vec(),
+    let msg = grant_allowance_msg(&env, &grantee_contract, &config.gas_denom, amount);
+    Ok(Response::default().add_message(msg).add_attributes([
+        ("action", "grant"),
+        ("grantee_contract", grantee_contract.as_str()),
+        ("amount", amount.to_string().as_str()),
+    ]))
+}
+
+fn grant_renewable(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    grantee_contract: Addr,
+    amount: Uint128,
+    period: u64,
+    bypass_amount_check: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender && !config.admins.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if period == 0 {
+        return Err(ContractError::InvalidPeriod {});
+    }
+
+    if !bypass_amount_check {
+        check_sent_amount(&info, &config.gas_denom, amount)?;
+    }
+
+    if GRANTS.has(deps.storage, &grantee_contract) {
+        return Err(ContractError::GrantExists(grantee_contract.to_string()));
+    }
+    RENEWABLE_GRANTS.update(deps.storage, &grantee_contract, |existing| match existing {
+        None => Ok(RenewableGrant {
+            amount,
+            period,
+            last_renewal: env.block.time.seconds(),
         }),
-    };
+        Some(_) => Err(ContractError::GrantExists(grantee_contract.to_string())),
+    })?;
 
-    let msg = CosmosMsg::Stargate {
-        type_url: MsgGrantAllowance::TYPE_URL.to_string(),
-        value: grant_msg.encode_to_vec().into(),
-    };
+    let msg = grant_allowance_msg(&env, &grantee_contract, &config.gas_denom, amount);
     Ok(Response::default().add_message(msg).add_attributes([
-        ("action", "grant"),
+        ("action", "grant_renewable"),
         ("grantee_contract", grantee_contract.as_str()),
         ("amount", amount.to_string().as_str()),
+        ("period", period.to_string().as_str()),
+    ]))
+}
+
+fn renew(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut grant = RENEWABLE_GRANTS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::RenewableGrantNotFound(info.sender.to_string()))?;
+
+    let next_renewal = grant.last_renewal + grant.period;
+    if env.block.time.seconds() < next_renewal {
+        return Err(ContractError::RenewalNotAvailable { next_renewal });
+    }
+
+    grant.last_renewal = env.block.time.seconds();
+    RENEWABLE_GRANTS.save(deps.storage, &info.sender, &grant)?;
+
+    // Feegrant module doesn't allow to overwrite existing allowance thus we revoke it first.
+    // Fully spent allowance is pruned by the module and revoking it would fail
+    let remaining = query_fee_allowance(
+        &deps.querier,
+        &env.contract.address,
+        &info.sender,
+        &config.gas_denom,
+    )?;
+    let mut messages = vec![];
+    if remaining.is_some() {
+        messages.push(revoke_allowance_msg(&env, &info.sender));
+    }
+    messages.push(grant_allowance_msg(
+        &env,
+        &info.sender,
+        &config.gas_denom,
+        grant.amount,
+    ));
+
+    Ok(Response::default().add_messages(messages).add_attributes([
+        ("action", "renew"),
+        ("grantee_contract", info.sender.as_str()),
+        ("amount", grant.amount.to_string().as_str()),
     ]))
 }
 
@@ -192,15 +266,9 @@ fn revoke(
     }
 
     GRANTS.remove(deps.storage, &grantee_contract);
+    RENEWABLE_GRANTS.remove(deps.storage, &grantee_contract);
 
-    let revoke_msg = MsgRevokeAllowance {
-        granter: env.contract.address.to_string(),
-        grantee: grantee_contract.to_string(),
-    };
-    let msg = CosmosMsg::Stargate {
-        type_url: MsgRevokeAllowance::TYPE_URL.to_string(),
-        value: revoke_msg.encode_to_vec().into(),
-    };
+    let msg = revoke_allowance_msg(&env, &grantee_contract);
 
     Ok(Response::default().add_message(msg).add_attributes([
         ("action", "revoke"),
@@ -208,6 +276,57 @@ fn revoke(
     ]))
 }
 
+fn check_sent_amount(
+    info: &MessageInfo,
+    gas_denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let sent_amount = must_pay(info, gas_denom)?;
+    if sent_amount != amount {
+        return Err(ContractError::InvalidAmount {
+            expected: amount,
+            actual: sent_amount,
+        });
+    }
+
+    Ok(())
+}
+
+fn grant_allowance_msg(env: &Env, grantee: &Addr, gas_denom: &str, amount: Uint128) -> CosmosMsg {
+    let allowance = BasicAllowance {
+        spend_limit: vec![SdkCoin {
+            denom: gas_denom.to_string(),
+            amount: amount.to_string(),
+        }],
+        expiration: None,
+    };
+    let grant_msg = MsgGrantAllowance {
+        granter: env.contract.address.to_string(),
+        grantee: grantee.to_string(),
+        allowance: Some(Any {
+            type_url: BasicAllowance::TYPE_URL.to_string(),
+            value: allowance.encode_to_vec(),
+        }),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgGrantAllowance::TYPE_URL.to_string(),
+        value: grant_msg.encode_to_vec().into(),
+    }
+}
+
+fn revoke_allowance_msg(env: &Env, grantee: &Addr) -> CosmosMsg {
+    let revoke_msg = MsgRevokeAllowance {
+        granter: env.contract.address.to_string(),
+        grantee: grantee.to_string(),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgRevokeAllowance::TYPE_URL.to_string(),
+        value: revoke_msg.encode_to_vec().into(),
+    }
+}
+
 fn transfer_coins(
     deps: DepsMut,
     info: MessageInfo,
@@ -16,4 +16,16 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Grant already exists for {0}")]
+    GrantExists(String),
+
+    #[error("Renewable grant not found for {0}")]
+    RenewableGrantNotFound(String),
+
+    #[error("Renewal period must be greater than 0")]
+    InvalidPeriod {},
+
+    #[error("Grant can't be renewed until {next_renewal}")]
+    RenewalNotAvailable { next_renewal: u64 },
 }
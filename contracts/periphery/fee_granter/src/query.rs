@@ -1,16 +1,18 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult, Uint128};
 use cw_storage_plus::Bound;
 
-use crate::state::{CONFIG, GRANTS};
-use astroport::fee_granter::{GrantResponse, QueryMsg};
+use crate::state::{CONFIG, GRANTS, RENEWABLE_GRANTS};
+use astroport::fee_granter::{
+    query_fee_allowance, GrantResponse, QueryMsg, RenewableGrant, RenewableGrantResponse,
+};
 
 /// Default pagination limit
 const DEFAULT_LIMIT: u32 = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::GrantsList { start_after, limit } => {
@@ -19,6 +21,15 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GrantFor { grantee_contract } => {
             to_json_binary(&grant_for(deps, grantee_contract)?)
         }
+        QueryMsg::RenewableGrantsList { start_after, limit } => {
+            to_json_binary(&list_renewable_grants(deps, start_after, limit)?)
+        }
+        QueryMsg::RenewableGrantFor { grantee_contract } => {
+            to_json_binary(&renewable_grant_for(deps, grantee_contract)?)
+        }
+        QueryMsg::RemainingAllowance { grantee_contract } => {
+            to_json_binary(&remaining_allowance(deps, env, grantee_contract)?)
+        }
     }
 }
 
@@ -55,16 +66,109 @@ fn grant_for(deps: Deps, grantee_contract: String) -> StdResult<GrantResponse> {
     })
 }
 
+fn list_renewable_grants(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RenewableGrantResponse>> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start_after = start_after.as_ref().map(Bound::exclusive);
+    RENEWABLE_GRANTS
+        .range(deps.storage, start_after, None, Order::Ascending)
+        .take(limit.unwrap_or(DEFAULT_LIMIT) as usize)
+        .map(|item| {
+            let (k, grant) = item?;
+            Ok(renewable_grant_response(k.to_string(), grant))
+        })
+        .collect()
+}
+
+fn renewable_grant_for(deps: Deps, grantee_contract: String) -> StdResult<RenewableGrantResponse> {
+    let grantee_contract = deps.api.addr_validate(&grantee_contract)?;
+    let grant = RENEWABLE_GRANTS
+        .may_load(deps.storage, &grantee_contract)?
+        .ok_or_else(|| {
+            StdError::generic_err(format!("Renewable grant not found for {grantee_contract}"))
+        })?;
+    Ok(renewable_grant_response(
+        grantee_contract.to_string(),
+        grant,
+    ))
+}
+
+fn remaining_allowance(deps: Deps, env: Env, grantee_contract: String) -> StdResult<Uint128> {
+    let grantee_contract = deps.api.addr_validate(&grantee_contract)?;
+    let config = CONFIG.load(deps.storage)?;
+    let remaining = query_fee_allowance(
+        &deps.querier,
+        env.contract.address,
+        grantee_contract,
+        &config.gas_denom,
+    )?;
+    Ok(remaining.unwrap_or_default())
+}
+
+fn renewable_grant_response(
+    grantee_contract: String,
+    grant: RenewableGrant,
+) -> RenewableGrantResponse {
+    RenewableGrantResponse {
+        grantee_contract,
+        amount: grant.amount,
+        period: grant.period,
+        last_renewal: grant.last_renewal,
+        next_renewal: grant.last_renewal + grant.period,
+    }
+}
+
 #[cfg(test)]
 mod unit_tests {
     use super::*;
     use crate::contract::{execute, instantiate};
-    use astroport::fee_granter::{Config, ExecuteMsg, InstantiateMsg};
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_json, Addr, Uint128};
+    use crate::error::ContractError;
+    use astroport::fee_granter::{Config, ExecuteMsg, InstantiateMsg, FEE_ALLOWANCE_QUERY_PATH};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coins, from_json, Addr, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
+        QueryRequest, SystemResult, Uint128,
+    };
+    use std::marker::PhantomData;
 
     const GAS_DENOM: &str = "inj";
 
+    /// Mocks the feegrant module Allowance query. `None` means the allowance doesn't exist.
+    struct FeeGrantQuerier {
+        base: MockQuerier,
+        allowance: Option<Uint128>,
+    }
+
+    impl Querier for FeeGrantQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_json(bin_request) {
+                Ok(QueryRequest::<Empty>::Stargate { path, .. })
+                    if path == FEE_ALLOWANCE_QUERY_PATH =>
+                {
+                    let result = match self.allowance {
+                        Some(amount) => ContractResult::Ok(
+                            format!(
+                                r#"{{"allowance":{{"granter":"cosmos2contract","grantee":"contract100","allowance":{{"@type":"/cosmos.feegrant.v1beta1.BasicAllowance","spend_limit":[{{"denom":"{GAS_DENOM}","amount":"{amount}"}}],"expiration":null}}}}}}"#
+                            )
+                            .into_bytes()
+                            .into(),
+                        ),
+                        None => ContractResult::Err("fee-grant not found".to_string()),
+                    };
+                    SystemResult::Ok(result)
+                }
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
     #[test]
     fn test_queries() {
         let mut deps = mock_dependencies();
@@ -200,4 +304,184 @@ mod unit_tests {
             ]
         );
     }
+
+    #[test]
+    fn test_renewable_grants() {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: FeeGrantQuerier {
+                base: MockQuerier::new(&[]),
+                allowance: None,
+            },
+            custom_query_type: PhantomData::<Empty>,
+        };
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            admins: vec!["admin".to_string()],
+            gas_denom: GAS_DENOM.to_string(),
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::GrantRenewable {
+            grantee_contract: "contract100".to_string(),
+            amount: 100u128.into(),
+            period: 0,
+            bypass_amount_check: false,
+        };
+        let info = mock_info("admin", &coins(100, GAS_DENOM));
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPeriod {});
+
+        let msg = ExecuteMsg::GrantRenewable {
+            grantee_contract: "contract100".to_string(),
+            amount: 100u128.into(),
+            period: 86400,
+            bypass_amount_check: false,
+        };
+        let info = mock_info("random", &coins(100, GAS_DENOM));
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &coins(100, GAS_DENOM));
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::GrantExists("contract100".to_string()));
+
+        // One-shot grant can't clash with renewable one
+        let msg = ExecuteMsg::Grant {
+            grantee_contract: "contract100".to_string(),
+            amount: 100u128.into(),
+            bypass_amount_check: false,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::GrantExists("contract100".to_string()));
+
+        let granted_at = env.block.time.seconds();
+        let resp = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RenewableGrantFor {
+                grantee_contract: "contract100".to_string(),
+            },
+        )
+        .unwrap();
+        let grant: RenewableGrantResponse = from_json(&resp).unwrap();
+        assert_eq!(
+            grant,
+            RenewableGrantResponse {
+                grantee_contract: "contract100".to_string(),
+                amount: 100u128.into(),
+                period: 86400,
+                last_renewal: granted_at,
+                next_renewal: granted_at + 86400,
+            }
+        );
+
+        // Only grantee can renew
+        let info = mock_info("random", &[]);
+        let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Renew {}).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RenewableGrantNotFound("random".to_string())
+        );
+
+        // Period hasn't lapsed yet
+        env.block.time = env.block.time.plus_seconds(86399);
+        let info = mock_info("contract100", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Renew {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RenewalNotAvailable {
+                next_renewal: granted_at + 86400
+            }
+        );
+
+        // Partially spent allowance
+        deps.querier.allowance = Some(40u128.into());
+        let resp = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RemainingAllowance {
+                grantee_contract: "contract100".to_string(),
+            },
+        )
+        .unwrap();
+        let remaining: Uint128 = from_json(&resp).unwrap();
+        assert_eq!(remaining.u128(), 40);
+
+        env.block.time = env.block.time.plus_seconds(1);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Renew {},
+        )
+        .unwrap();
+        // Revoke existing allowance and grant a new one
+        assert_eq!(resp.messages.len(), 2);
+
+        let resp = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RenewableGrantsList {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let grants: Vec<RenewableGrantResponse> = from_json(&resp).unwrap();
+        assert_eq!(
+            grants,
+            [RenewableGrantResponse {
+                grantee_contract: "contract100".to_string(),
+                amount: 100u128.into(),
+                period: 86400,
+                last_renewal: granted_at + 86400,
+                next_renewal: granted_at + 2 * 86400,
+            }]
+        );
+
+        // Fully spent allowance is pruned by the feegrant module thus it is only granted again
+        deps.querier.allowance = None;
+        let resp = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RemainingAllowance {
+                grantee_contract: "contract100".to_string(),
+            },
+        )
+        .unwrap();
+        let remaining: Uint128 = from_json(&resp).unwrap();
+        assert_eq!(remaining.u128(), 0);
+
+        env.block.time = env.block.time.plus_seconds(86400);
+        let resp = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Renew {}).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+
+        // Revoke removes renewable grant
+        let msg = ExecuteMsg::Revoke {
+            grantee_contract: "contract100".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RenewableGrantFor {
+                grantee_contract: "contract100".to_string(),
+            },
+        )
+        .unwrap_err();
+    }
 }
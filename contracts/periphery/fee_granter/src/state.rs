@@ -3,12 +3,14 @@ use cw_storage_plus::{Item, Map};
 use std::collections::HashSet;
 
 use astroport::common::{validate_addresses, OwnershipProposal};
-use astroport::fee_granter::Config;
+use astroport::fee_granter::{Config, RenewableGrant};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
 pub const GRANTS: Map<&Addr, Uint128> = Map::new("grants");
 
+pub const RENEWABLE_GRANTS: Map<&Addr, RenewableGrant> = Map::new("renewable_grants");
+
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
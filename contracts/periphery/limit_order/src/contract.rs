@@ -0,0 +1,287 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{attr, from_json, Addr, DepsMut, Env, MessageInfo, Response, StdError};
+use cw2::set_contract_version;
+use cw20::Cw20ReceiveMsg;
+
+use astroport::asset::{Asset, AssetInfoExt};
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::limit_order::{
+    Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, Order, OrderParams, MAX_TIP_BPS,
+};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, LAST_ORDER_ID, ORDERS, OWNERSHIP_PROPOSAL, OWNER_ORDERS};
+use crate::utils::{order_trigger, query_ask_asset, query_twap_return, swap_msg};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.max_tip_bps > MAX_TIP_BPS {
+        return Err(ContractError::MaxTipTooHigh {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            router: deps.api.addr_validate(&msg.router)?,
+            max_tip_bps: msg.max_tip_bps,
+        },
+    )?;
+    LAST_ORDER_ID.save(deps.storage, &0)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::PlaceOrder {
+            offer_asset,
+            params,
+        } => {
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Cw20DirectPlacement {});
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            place_order(deps, env, info.sender, offer_asset, params)
+        }
+        ExecuteMsg::CancelOrder { order_id } => cancel_order(deps, info, order_id),
+        ExecuteMsg::ExecuteOrder { order_id } => execute_order(deps, info, order_id),
+        ExecuteMsg::UpdateConfig {
+            router,
+            max_tip_bps,
+        } => update_config(deps, info, router, max_tip_bps),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG
+                    .update::<_, StdError>(deps.storage, |mut v| {
+                        v.owner = new_owner;
+                        Ok(v)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+    }
+}
+
+/// Places an order selling the received cw20 tokens.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::PlaceOrder(params) => {
+            let owner = deps.api.addr_validate(&cw20_msg.sender)?;
+            let offer_asset = Asset::cw20(info.sender, cw20_msg.amount);
+
+            place_order(deps, env, owner, offer_asset, params)
+        }
+    }
+}
+
+/// Validates the order against the router and the oracle and escrows the offer asset.
+/// The tip is bounded by the configured share of the offer amount.
+fn place_order(
+    deps: DepsMut,
+    env: Env,
+    owner: Addr,
+    offer_asset: Asset,
+    params: OrderParams,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if offer_asset.amount.is_zero() || params.min_receive.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let max_tip = offer_asset
+        .amount
+        .multiply_ratio(config.max_tip_bps, 10000u16);
+    if params.tip > max_tip || params.tip >= offer_asset.amount {
+        return Err(ContractError::TipTooHigh {
+            tip: params.tip,
+            max_tip,
+        });
+    }
+
+    let ask_asset_info = query_ask_asset(
+        &deps.querier,
+        &config.router,
+        &params.operations,
+        &offer_asset.info,
+    )?;
+    let oracle = deps.api.addr_validate(&params.oracle)?;
+    // Fails unless the oracle prices the offer asset in the ask asset
+    query_twap_return(&deps.querier, &oracle, &offer_asset, &ask_asset_info)?;
+
+    let id = LAST_ORDER_ID.load(deps.storage)? + 1;
+    LAST_ORDER_ID.save(deps.storage, &id)?;
+
+    let order = Order {
+        id,
+        owner,
+        offer_asset,
+        ask_asset_info,
+        operations: params.operations,
+        min_receive: params.min_receive,
+        oracle,
+        tip: params.tip,
+        max_spread: params.max_spread,
+        created_at: env.block.time.seconds(),
+    };
+    ORDERS.save(deps.storage, id, &order)?;
+    OWNER_ORDERS.save(deps.storage, (&order.owner, id), &())?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "place_order"),
+        attr("order_id", id.to_string()),
+        attr("owner", order.owner),
+        attr("offer_asset", order.offer_asset.to_string()),
+        attr("ask_asset", order.ask_asset_info.to_string()),
+        attr("min_receive", order.min_receive),
+        attr("tip", order.tip),
+    ]))
+}
+
+/// Removes the order and returns the escrowed assets to its owner.
+fn cancel_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let order = ORDERS
+        .may_load(deps.storage, order_id)?
+        .ok_or(ContractError::OrderNotFound(order_id))?;
+    if info.sender != order.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ORDERS.remove(deps.storage, order_id);
+    OWNER_ORDERS.remove(deps.storage, (&order.owner, order_id));
+
+    Ok(Response::new()
+        .add_message(order.offer_asset.clone().into_msg(&order.owner)?)
+        .add_attributes([
+            attr("action", "cancel_order"),
+            attr("order_id", order_id.to_string()),
+            attr("refund", order.offer_asset.to_string()),
+        ]))
+}
+
+/// Executes the order through the router once the oracle TWAP reaches the order price and pays the tip
+/// to the keeper. The router guarantees the order owner receives at least the minimum receive amount.
+fn execute_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let order = ORDERS
+        .may_load(deps.storage, order_id)?
+        .ok_or(ContractError::OrderNotFound(order_id))?;
+
+    let trigger = order_trigger(&deps.querier, &order)?;
+    if !trigger.triggered {
+        return Err(ContractError::TriggerNotReached {
+            twap_return: trigger.twap_return,
+            min_receive: order.min_receive,
+        });
+    }
+
+    ORDERS.remove(deps.storage, order_id);
+    OWNER_ORDERS.remove(deps.storage, (&order.owner, order_id));
+
+    let mut response = Response::new().add_message(swap_msg(&config.router, &order)?);
+    if !order.tip.is_zero() {
+        response = response.add_message(
+            order
+                .offer_asset
+                .info
+                .with_balance(order.tip)
+                .into_msg(&info.sender)?,
+        );
+    }
+
+    Ok(response.add_attributes([
+        attr("action", "execute_order"),
+        attr("order_id", order_id.to_string()),
+        attr("keeper", info.sender),
+        attr("twap_return", trigger.twap_return.to_string()),
+        attr("tip", order.tip),
+    ]))
+}
+
+/// Updates the router and the maximum tip. Placed orders keep their tips.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    router: Option<String>,
+    max_tip_bps: Option<u16>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_config")];
+
+    if let Some(router) = router {
+        config.router = deps.api.addr_validate(&router)?;
+        attributes.push(attr("router", router));
+    }
+
+    if let Some(max_tip_bps) = max_tip_bps {
+        if max_tip_bps > MAX_TIP_BPS {
+            return Err(ContractError::MaxTipTooHigh {});
+        }
+        config.max_tip_bps = max_tip_bps;
+        attributes.push(attr("max_tip_bps", max_tip_bps.to_string()));
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
@@ -0,0 +1,45 @@
+use cosmwasm_std::{StdError, Uint128, Uint256};
+use thiserror::Error;
+
+use astroport::limit_order::MAX_TIP_BPS;
+
+/// This enum describes limit order contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Offer amount and minimum receive amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Cw20 tokens must be sent via the Receive hook")]
+    Cw20DirectPlacement {},
+
+    #[error("Tip {tip} exceeds the maximum allowed tip {max_tip}")]
+    TipTooHigh { tip: Uint128, max_tip: Uint128 },
+
+    #[error("Max tip can't exceed {MAX_TIP_BPS} bps")]
+    MaxTipTooHigh {},
+
+    #[error("Swap operations must start with the offer asset {0}")]
+    InvalidOperations(String),
+
+    #[error("Oracle {oracle} doesn't provide prices for {offer_asset} -> {ask_asset}")]
+    OracleMismatch {
+        oracle: String,
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("Order {0} not found")]
+    OrderNotFound(u64),
+
+    #[error("Order is not triggered: TWAP return {twap_return}, minimum receive {min_receive}")]
+    TriggerNotReached {
+        twap_return: Uint256,
+        min_receive: Uint128,
+    },
+}
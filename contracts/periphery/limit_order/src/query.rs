@@ -0,0 +1,55 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order as IterOrder, StdResult};
+use cw_storage_plus::Bound;
+
+use astroport::limit_order::{Order, QueryMsg, MAX_PAGE_LIMIT};
+
+use crate::error::ContractError;
+use crate::state::{CONFIG, ORDERS, OWNER_ORDERS};
+use crate::utils::order_trigger;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::Order { order_id } => Ok(to_json_binary(&load_order(deps, order_id)?)?),
+        QueryMsg::Orders {
+            owner,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+            let start = start_after.map(Bound::exclusive);
+
+            let orders = match owner {
+                Some(owner) => {
+                    let owner = deps.api.addr_validate(&owner)?;
+                    OWNER_ORDERS
+                        .prefix(&owner)
+                        .keys(deps.storage, start, None, IterOrder::Ascending)
+                        .take(limit)
+                        .map(|order_id| ORDERS.load(deps.storage, order_id?))
+                        .collect::<StdResult<Vec<_>>>()?
+                }
+                None => ORDERS
+                    .range(deps.storage, start, None, IterOrder::Ascending)
+                    .take(limit)
+                    .map(|item| item.map(|(_, order)| order))
+                    .collect::<StdResult<Vec<_>>>()?,
+            };
+
+            Ok(to_json_binary(&orders)?)
+        }
+        QueryMsg::OrderTrigger { order_id } => {
+            let order = load_order(deps, order_id)?;
+            Ok(to_json_binary(&order_trigger(&deps.querier, &order)?)?)
+        }
+    }
+}
+
+fn load_order(deps: Deps, order_id: u64) -> Result<Order, ContractError> {
+    ORDERS
+        .may_load(deps.storage, order_id)?
+        .ok_or(ContractError::OrderNotFound(order_id))
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+use astroport::common::OwnershipProposal;
+use astroport::limit_order::{Config, Order};
+
+/// Stores the contract configuration
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest contract ownership transfer proposal
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Stores the id of the last placed order
+pub const LAST_ORDER_ID: Item<u64> = Item::new("last_order_id");
+
+/// Stores open orders by their id
+pub const ORDERS: Map<u64, Order> = Map::new("orders");
+
+/// Indexes open orders by their owner
+pub const OWNER_ORDERS: Map<(&Addr, u64), ()> = Map::new("owner_orders");
@@ -0,0 +1,119 @@
+use cosmwasm_std::{
+    to_json_binary, wasm_execute, Addr, CosmosMsg, QuerierWrapper, StdResult, Uint256,
+};
+use cw20::Cw20ExecuteMsg;
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::limit_order::{Order, OrderTrigger};
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::route::Route;
+use astroport::router::{
+    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, QueryMsg as RouterQueryMsg,
+    SwapOperation,
+};
+
+use crate::error::ContractError;
+
+/// Validates swap operations through the router and returns the asset received at the end of the route.
+pub fn query_ask_asset(
+    querier: &QuerierWrapper,
+    router: &Addr,
+    operations: &[SwapOperation],
+    offer_info: &AssetInfo,
+) -> Result<AssetInfo, ContractError> {
+    let route: Route = querier.query_wasm_smart(
+        router,
+        &RouterQueryMsg::Route {
+            operations: operations.to_vec(),
+        },
+    )?;
+
+    if route.assets.first() != Some(offer_info) {
+        return Err(ContractError::InvalidOperations(offer_info.to_string()));
+    }
+
+    Ok(route.assets[route.assets.len() - 1].clone())
+}
+
+/// Returns the oracle TWAP value of the offer asset denominated in the ask asset.
+pub fn query_twap_return(
+    querier: &QuerierWrapper,
+    oracle: &Addr,
+    offer_asset: &Asset,
+    ask_info: &AssetInfo,
+) -> Result<Uint256, ContractError> {
+    let amounts: Vec<(AssetInfo, Uint256)> = querier.query_wasm_smart(
+        oracle,
+        &OracleQueryMsg::Consult {
+            token: offer_asset.info.clone(),
+            amount: offer_asset.amount,
+        },
+    )?;
+
+    amounts
+        .into_iter()
+        .find(|(info, _)| info == ask_info)
+        .map(|(_, amount)| amount)
+        .ok_or_else(|| ContractError::OracleMismatch {
+            oracle: oracle.to_string(),
+            offer_asset: offer_asset.info.to_string(),
+            ask_asset: ask_info.to_string(),
+        })
+}
+
+/// Compares the oracle TWAP value of the swapped amount with the order minimum receive amount.
+pub fn order_trigger(
+    querier: &QuerierWrapper,
+    order: &Order,
+) -> Result<OrderTrigger, ContractError> {
+    let twap_return = query_twap_return(
+        querier,
+        &order.oracle,
+        &order.offer_asset.info.with_balance(order.swap_amount()),
+        &order.ask_asset_info,
+    )?;
+
+    Ok(OrderTrigger {
+        twap_return,
+        triggered: twap_return >= Uint256::from(order.min_receive),
+    })
+}
+
+/// Builds the router message which swaps the order amount less the tip and sends
+/// at least the minimum receive amount to the order owner.
+pub fn swap_msg(router: &Addr, order: &Order) -> StdResult<CosmosMsg> {
+    let minimum_receive = Some(order.min_receive);
+    let to = Some(order.owner.to_string());
+    let amount = order.swap_amount();
+
+    let msg = match &order.offer_asset.info {
+        AssetInfo::NativeToken { .. } => wasm_execute(
+            router,
+            &RouterExecuteMsg::ExecuteSwapOperations {
+                operations: order.operations.clone(),
+                minimum_receive,
+                to,
+                max_spread: order.max_spread,
+                to_commitment: None,
+            },
+            vec![order.offer_asset.info.with_balance(amount).as_coin()?],
+        )?,
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount,
+                msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: order.operations.clone(),
+                    minimum_receive,
+                    to,
+                    max_spread: order.max_spread,
+                    to_commitment: None,
+                })?,
+            },
+            vec![],
+        )?,
+    };
+
+    Ok(msg.into())
+}
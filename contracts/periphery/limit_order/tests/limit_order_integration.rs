@@ -0,0 +1,521 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+use astroport::asset::{native_asset_info, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::limit_order::{
+    Config, ExecuteMsg, InstantiateMsg, Order, OrderParams, OrderTrigger, QueryMsg,
+};
+use astroport::router::SwapOperation;
+use astroport_limit_order::error::ContractError;
+use astroport_test::cw_multi_test::{AppBuilder, AppResponse, ContractWrapper, Executor};
+use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+
+const OWNER: &str = "owner";
+const USER: &str = "user";
+const KEEPER: &str = "keeper";
+
+struct TestSuite {
+    app: TestApp,
+    limit_order: Addr,
+    pair: Addr,
+    oracle: Addr,
+}
+
+fn asset_infos() -> Vec<AssetInfo> {
+    vec![
+        native_asset_info("uusd".to_string()),
+        native_asset_info("uluna".to_string()),
+    ]
+}
+
+/// Creates an XYK uusd/uluna pool with 1:1 price along with the router and the oracle of this pool.
+fn setup() -> TestSuite {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = AppBuilder::new_custom()
+        .with_stargate(MockStargate::default())
+        .build(|router, _, storage| {
+            for addr in [OWNER, USER] {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(addr),
+                        vec![
+                            coin(100_000_000_000, "uusd"),
+                            coin(100_000_000_000, "uluna"),
+                        ],
+                    )
+                    .unwrap()
+            }
+        });
+
+    let coin_registry_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_native_coin_registry::contract::execute,
+        astroport_native_coin_registry::contract::instantiate,
+        astroport_native_coin_registry::contract::query,
+    )));
+    let pair_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            astroport_pair::contract::query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply),
+    ));
+    let factory_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    ));
+    let router_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_router::contract::execute,
+            astroport_router::contract::instantiate,
+            astroport_router::contract::query,
+        )
+        .with_reply_empty(astroport_router::contract::reply),
+    ));
+    let oracle_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_oracle::contract::execute,
+        astroport_oracle::contract::instantiate,
+        astroport_oracle::contract::query,
+    )));
+    let limit_order_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_limit_order::contract::execute,
+        astroport_limit_order::contract::instantiate,
+        astroport_limit_order::query::query,
+    )));
+
+    let coin_registry = app
+        .instantiate_contract(
+            coin_registry_code_id,
+            owner.clone(),
+            &astroport::native_coin_registry::InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            "Coin registry",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        coin_registry.clone(),
+        &astroport::native_coin_registry::ExecuteMsg::Add {
+            native_coins: vec![("uusd".to_string(), 6), ("uluna".to_string(), 6)],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                pair_configs: vec![PairConfig {
+                    code_id: pair_code_id,
+                    pair_type: PairType::Xyk {},
+                    total_fee_bps: 30,
+                    maker_fee_bps: 0,
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                    permissioned: false,
+                    min_trade_notional: None,
+                }],
+                token_code_id: 0,
+                fee_address: None,
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: coin_registry.to_string(),
+                tracker_config: None,
+                ownership_timelock: None,
+            },
+            &[],
+            "Factory",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: asset_infos(),
+            init_params: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let pair = app
+        .wrap()
+        .query_wasm_smart::<PairInfo>(
+            &factory,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: asset_infos(),
+            },
+        )
+        .unwrap()
+        .contract_addr;
+
+    let infos = asset_infos();
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &astroport::pair::ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                infos[0].with_balance(1_000_000_000u128),
+                infos[1].with_balance(1_000_000_000u128),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+            min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
+        },
+        &[coin(1_000_000_000, "uluna"), coin(1_000_000_000, "uusd")],
+    )
+    .unwrap();
+
+    let router = app
+        .instantiate_contract(
+            router_code_id,
+            owner.clone(),
+            &astroport::router::InstantiateMsg {
+                astroport_factory: factory.to_string(),
+            },
+            &[],
+            "Router",
+            None,
+        )
+        .unwrap();
+
+    let oracle = app
+        .instantiate_contract(
+            oracle_code_id,
+            owner.clone(),
+            &astroport::oracle::InstantiateMsg {
+                factory_contract: factory.to_string(),
+                asset_infos: asset_infos(),
+                price_hub: None,
+            },
+            &[],
+            "Oracle",
+            None,
+        )
+        .unwrap();
+
+    let limit_order = app
+        .instantiate_contract(
+            limit_order_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+                router: router.to_string(),
+                max_tip_bps: 100,
+            },
+            &[],
+            "Limit order",
+            None,
+        )
+        .unwrap();
+
+    TestSuite {
+        app,
+        limit_order,
+        pair,
+        oracle,
+    }
+}
+
+fn uusd_to_uluna() -> Vec<SwapOperation> {
+    let infos = asset_infos();
+    vec![SwapOperation::AstroSwap {
+        offer_asset_info: infos[0].clone(),
+        ask_asset_info: infos[1].clone(),
+        minimum_receive: None,
+    }]
+}
+
+fn place_order(
+    env: &mut TestSuite,
+    amount: u128,
+    min_receive: u128,
+    tip: u128,
+    operations: Vec<SwapOperation>,
+) -> anyhow::Result<AppResponse> {
+    env.app.execute_contract(
+        Addr::unchecked(USER),
+        env.limit_order.clone(),
+        &ExecuteMsg::PlaceOrder {
+            offer_asset: asset_infos()[0].with_balance(amount),
+            params: OrderParams {
+                operations,
+                min_receive: Uint128::new(min_receive),
+                oracle: env.oracle.to_string(),
+                tip: Uint128::new(tip),
+                max_spread: Some(Decimal::percent(5)),
+            },
+        },
+        &[coin(amount, "uusd")],
+    )
+}
+
+fn execute_order(env: &mut TestSuite, order_id: u64) -> anyhow::Result<AppResponse> {
+    env.app.execute_contract(
+        Addr::unchecked(KEEPER),
+        env.limit_order.clone(),
+        &ExecuteMsg::ExecuteOrder { order_id },
+        &[],
+    )
+}
+
+fn balance(env: &TestSuite, user: &Addr, denom: &str) -> u128 {
+    env.app
+        .wrap()
+        .query_balance(user, denom)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn test_place_and_execute_order() {
+    let mut env = setup();
+    let user = Addr::unchecked(USER);
+    let keeper = Addr::unchecked(KEEPER);
+
+    let err = place_order(&mut env, 10_000_000, 11_000_000, 100_001, uusd_to_uluna()).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TipTooHigh {
+            tip: Uint128::new(100_001),
+            max_tip: Uint128::new(100_000),
+        }
+    );
+
+    let err = place_order(&mut env, 10_000_000, 0, 0, uusd_to_uluna()).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ZeroAmount {}
+    );
+
+    let infos = asset_infos();
+    let reversed = vec![SwapOperation::AstroSwap {
+        offer_asset_info: infos[1].clone(),
+        ask_asset_info: infos[0].clone(),
+        minimum_receive: None,
+    }];
+    let err = place_order(&mut env, 10_000_000, 11_000_000, 0, reversed).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidOperations("uusd".to_string())
+    );
+
+    let user_uusd = balance(&env, &user, "uusd");
+    place_order(&mut env, 10_000_000, 11_000_000, 100_000, uusd_to_uluna()).unwrap();
+    assert_eq!(balance(&env, &user, "uusd"), user_uusd - 10_000_000);
+
+    let orders: Vec<Order> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.limit_order,
+            &QueryMsg::Orders {
+                owner: Some(USER.to_string()),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].id, 1);
+    assert_eq!(orders[0].ask_asset_info, infos[1]);
+
+    // The pool price is 1:1 thus the order is not triggered yet
+    let trigger: OrderTrigger = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.limit_order, &QueryMsg::OrderTrigger { order_id: 1 })
+        .unwrap();
+    assert!(!trigger.triggered);
+    let err = execute_order(&mut env, 1).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TriggerNotReached {
+            twap_return: trigger.twap_return,
+            min_receive: Uint128::new(11_000_000),
+        }
+    );
+
+    // uusd becomes more expensive and stays so for a whole oracle period
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.pair.clone(),
+            &astroport::pair::ExecuteMsg::Swap {
+                offer_asset: infos[1].with_balance(500_000_000u128),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+                deadline: None,
+                to_commitment: None,
+            },
+            &[coin(500_000_000, "uluna")],
+        )
+        .unwrap();
+    env.app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(86400);
+    });
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.oracle.clone(),
+            &astroport::oracle::ExecuteMsg::Update {},
+            &[],
+        )
+        .unwrap();
+
+    let trigger: OrderTrigger = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.limit_order, &QueryMsg::OrderTrigger { order_id: 1 })
+        .unwrap();
+    assert!(trigger.triggered);
+
+    let user_uluna = balance(&env, &user, "uluna");
+    execute_order(&mut env, 1).unwrap();
+
+    assert_eq!(balance(&env, &keeper, "uusd"), 100_000);
+    assert!(balance(&env, &user, "uluna") - user_uluna >= 11_000_000);
+    assert_eq!(balance(&env, &env.limit_order, "uusd"), 0);
+
+    let err = execute_order(&mut env, 1).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::OrderNotFound(1)
+    );
+}
+
+#[test]
+fn test_cancel_order() {
+    let mut env = setup();
+    let user = Addr::unchecked(USER);
+
+    let user_uusd = balance(&env, &user, "uusd");
+    place_order(&mut env, 10_000_000, 11_000_000, 50_000, uusd_to_uluna()).unwrap();
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(KEEPER),
+            env.limit_order.clone(),
+            &ExecuteMsg::CancelOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    env.app
+        .execute_contract(
+            user.clone(),
+            env.limit_order.clone(),
+            &ExecuteMsg::CancelOrder { order_id: 1 },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(balance(&env, &user, "uusd"), user_uusd);
+
+    let orders: Vec<Order> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.limit_order,
+            &QueryMsg::Orders {
+                owner: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(orders.is_empty());
+}
+
+#[test]
+fn test_update_config() {
+    let mut env = setup();
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            env.limit_order.clone(),
+            &ExecuteMsg::UpdateConfig {
+                router: None,
+                max_tip_bps: Some(50),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.limit_order.clone(),
+            &ExecuteMsg::UpdateConfig {
+                router: None,
+                max_tip_bps: Some(1001),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::MaxTipTooHigh {}
+    );
+
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.limit_order.clone(),
+            &ExecuteMsg::UpdateConfig {
+                router: None,
+                max_tip_bps: Some(50),
+            },
+            &[],
+        )
+        .unwrap();
+    let config: Config = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.limit_order, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.max_tip_bps, 50);
+
+    let err = place_order(&mut env, 10_000_000, 11_000_000, 100_000, uusd_to_uluna()).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TipTooHigh {
+            tip: Uint128::new(100_000),
+            max_tip: Uint128::new(50_000),
+        }
+    );
+}
@@ -0,0 +1,469 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, from_json, to_json_binary, wasm_execute, Addr, CosmosMsg, Decimal, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, SubMsg, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_utils::must_pay;
+
+use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::incentives::{Cw20Msg as IncentivesCw20Msg, ExecuteMsg as IncentivesExecuteMsg};
+use astroport::liquidity_manager::{Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use astroport::querier::query_staking_target;
+
+use crate::error::ContractError;
+use crate::state::{ReplyContext, CONFIG, REPLY_CONTEXT};
+use crate::utils::{
+    check_assets, query_balances, query_registered_pair, simulate_withdraw, CompatPairExecuteMsg,
+};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Reply ID of the liquidity provision submessage
+const PROVIDE_REPLY_ID: u64 = 1;
+/// Reply ID of the liquidity withdrawal submessage
+const WITHDRAW_REPLY_ID: u64 = 2;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            astroport_factory: deps.api.addr_validate(&msg.astroport_factory)?,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ProvideLiquidity {
+            pair_addr,
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+            min_lp_to_receive,
+        } => provide_liquidity(
+            deps,
+            env,
+            info,
+            pair_addr,
+            assets,
+            slippage_tolerance,
+            auto_stake.unwrap_or(false),
+            receiver,
+            min_lp_to_receive,
+        ),
+        ExecuteMsg::WithdrawLiquidity {
+            pair_addr,
+            min_assets_to_receive,
+            receiver,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let pair_info =
+                query_registered_pair(&deps.querier, &config.astroport_factory, &pair_addr)?;
+            let lp_amount = must_pay(&info, &pair_info.liquidity_token)?;
+
+            let withdraw_msg = wasm_execute(
+                &pair_info.contract_addr,
+                &CompatPairExecuteMsg::WithdrawLiquidity { assets: vec![] },
+                info.funds,
+            )?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                pair_info,
+                lp_amount,
+                withdraw_msg.into(),
+                receiver.unwrap_or_else(|| info.sender.to_string()),
+                min_assets_to_receive,
+            )
+        }
+        ExecuteMsg::Receive(cw20_msg) => receive_cw20(deps, env, info, cw20_msg),
+    }
+}
+
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+///
+/// * **cw20_msg** CW20 message to process.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&cw20_msg.msg)? {
+        Cw20HookMsg::WithdrawLiquidity {
+            pair_addr,
+            min_assets_to_receive,
+            receiver,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let pair_info =
+                query_registered_pair(&deps.querier, &config.astroport_factory, &pair_addr)?;
+
+            if info.sender != pair_info.liquidity_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let withdraw_msg = wasm_execute(
+                &info.sender,
+                &Cw20ExecuteMsg::Send {
+                    contract: pair_info.contract_addr.to_string(),
+                    amount: cw20_msg.amount,
+                    msg: to_json_binary(&CompatPairExecuteMsg::WithdrawLiquidity {
+                        assets: vec![],
+                    })?,
+                },
+                vec![],
+            )?;
+
+            withdraw_liquidity(
+                deps,
+                env,
+                pair_info,
+                cw20_msg.amount,
+                withdraw_msg.into(),
+                receiver.unwrap_or(cw20_msg.sender),
+                min_assets_to_receive,
+            )
+        }
+    }
+}
+
+/// Provides liquidity to the pair with the contract as the LP token receiver.
+/// The minted amount is validated in [`reply`].
+///
+/// * **auto_stake** whether to stake LP tokens on behalf of the receiver.
+///
+/// * **min_lp_to_receive** the minimum amount of LP tokens to receive. A simulation in the same transaction
+/// sees the already manipulated pool, thus the minimum must come from the user.
+#[allow(clippy::too_many_arguments)]
+fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair_addr: String,
+    assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    auto_stake: bool,
+    receiver: Option<String>,
+    min_lp_to_receive: Uint128,
+) -> Result<Response, ContractError> {
+    if min_lp_to_receive.is_zero() {
+        return Err(ContractError::ZeroMinLpToReceive {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pair_info = query_registered_pair(&deps.querier, &config.astroport_factory, &pair_addr)?;
+    check_assets(&pair_info, &assets)?;
+
+    let receiver = deps
+        .api
+        .addr_validate(receiver.as_deref().unwrap_or(info.sender.as_str()))?;
+
+    // Every sent coin must be one of the provided assets
+    let native_assets = assets
+        .iter()
+        .filter(|asset| asset.info.is_native_token() && !asset.amount.is_zero())
+        .collect::<Vec<_>>();
+    if native_assets.len() != info.funds.len() {
+        return Err(ContractError::InvalidFunds {});
+    }
+    for asset in &native_assets {
+        asset
+            .assert_sent_native_token_balance(&info)
+            .map_err(|_| ContractError::InvalidFunds {})?;
+    }
+
+    let balances_before =
+        query_balances(&deps.querier, &pair_info.asset_infos, &env.contract.address)?
+            .into_iter()
+            .map(|balance| {
+                let deposited = native_assets
+                    .iter()
+                    .find(|asset| asset.info == balance.info)
+                    .map(|asset| asset.amount)
+                    .unwrap_or_default();
+                Ok(balance
+                    .info
+                    .with_balance(balance.amount.checked_sub(deposited)?))
+            })
+            .collect::<Result<Vec<_>, StdError>>()?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset in &assets {
+        if let AssetInfo::Token { contract_addr } = &asset.info {
+            if asset.amount.is_zero() {
+                continue;
+            }
+            messages.push(
+                wasm_execute(
+                    contract_addr,
+                    &Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: asset.amount,
+                    },
+                    vec![],
+                )?
+                .into(),
+            );
+            messages.push(
+                wasm_execute(
+                    contract_addr,
+                    &Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pair_info.contract_addr.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    },
+                    vec![],
+                )?
+                .into(),
+            );
+        }
+    }
+
+    let lp_token = determine_asset_info(&pair_info.liquidity_token, deps.api)?;
+    let lp_balance_before = lp_token.query_pool(&deps.querier, &env.contract.address)?;
+
+    let provide_msg = wasm_execute(
+        &pair_info.contract_addr,
+        &CompatPairExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            auto_stake: None,
+            receiver: None,
+        },
+        info.funds,
+    )?;
+
+    REPLY_CONTEXT.save(
+        deps.storage,
+        &ReplyContext::Provide {
+            sender: info.sender,
+            receiver: receiver.clone(),
+            lp_token,
+            lp_balance_before,
+            min_lp_to_receive,
+            balances_before,
+            auto_stake,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessage(SubMsg::reply_on_success(provide_msg, PROVIDE_REPLY_ID))
+        .add_attributes([
+            attr("action", "provide_liquidity"),
+            attr("pair", pair_info.contract_addr),
+            attr("receiver", receiver),
+            attr("min_lp_to_receive", min_lp_to_receive),
+        ]))
+}
+
+/// Withdraws liquidity from the pair. Received assets are validated in [`reply`].
+///
+/// * **withdraw_msg** the message which sends LP tokens to the pair.
+///
+/// * **min_assets_to_receive** the minimum amounts of assets to receive.
+/// The simulated amounts are enforced regardless.
+fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    pair_info: PairInfo,
+    lp_amount: Uint128,
+    withdraw_msg: CosmosMsg,
+    receiver: String,
+    min_assets_to_receive: Option<Vec<Asset>>,
+) -> Result<Response, ContractError> {
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    let min_assets = min_assets_to_receive.unwrap_or_default();
+    check_assets(&pair_info, &min_assets)?;
+
+    let min_assets_to_receive = simulate_withdraw(&deps.querier, &pair_info, lp_amount)?
+        .into_iter()
+        .map(|simulated| {
+            let min_amount = min_assets
+                .iter()
+                .find(|asset| asset.info == simulated.info)
+                .map(|asset| asset.amount)
+                .unwrap_or_default();
+            simulated
+                .info
+                .with_balance(simulated.amount.max(min_amount))
+        })
+        .collect();
+
+    let balances_before =
+        query_balances(&deps.querier, &pair_info.asset_infos, &env.contract.address)?;
+
+    REPLY_CONTEXT.save(
+        deps.storage,
+        &ReplyContext::Withdraw {
+            receiver: receiver.clone(),
+            balances_before,
+            min_assets_to_receive,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(withdraw_msg, WITHDRAW_REPLY_ID))
+        .add_attributes([
+            attr("action", "withdraw_liquidity"),
+            attr("pair", pair_info.contract_addr),
+            attr("receiver", receiver),
+            attr("withdrawn_share", lp_amount),
+        ]))
+}
+
+/// Validates the outcome of a liquidity operation and forwards received tokens.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let context = REPLY_CONTEXT.load(deps.storage)?;
+    REPLY_CONTEXT.remove(deps.storage);
+
+    match (msg.id, context) {
+        (
+            PROVIDE_REPLY_ID,
+            ReplyContext::Provide {
+                sender,
+                receiver,
+                lp_token,
+                lp_balance_before,
+                min_lp_to_receive,
+                balances_before,
+                auto_stake,
+            },
+        ) => {
+            let lp_received = lp_token
+                .query_pool(&deps.querier, &env.contract.address)?
+                .checked_sub(lp_balance_before)?;
+            if lp_received < min_lp_to_receive {
+                return Err(ContractError::ReceivedLpTooLow {
+                    received: lp_received,
+                    expected: min_lp_to_receive,
+                });
+            }
+
+            let mut messages = vec![];
+
+            // Return the assets which the pair didn't take
+            for before in balances_before {
+                let unused = before
+                    .info
+                    .query_pool(&deps.querier, &env.contract.address)?
+                    .checked_sub(before.amount)?;
+                if !unused.is_zero() {
+                    messages.push(before.info.with_balance(unused).into_msg(&sender)?);
+                }
+            }
+
+            if auto_stake {
+                let config = CONFIG.load(deps.storage)?;
+                // The default target is always the incentives contract
+                let staking = query_staking_target(&deps.querier, &config.astroport_factory, None)?
+                    .ok_or(ContractError::StakingNotSet {})?;
+                messages.push(stake_lp_msg(
+                    &lp_token.with_balance(lp_received),
+                    staking.addr(),
+                    &receiver,
+                )?);
+            } else {
+                messages.push(lp_token.with_balance(lp_received).into_msg(&receiver)?);
+            }
+
+            Ok(Response::new().add_messages(messages).add_attributes([
+                attr("action", "provide_liquidity_reply"),
+                attr("lp_received", lp_received),
+                attr("auto_stake", auto_stake.to_string()),
+            ]))
+        }
+        (
+            WITHDRAW_REPLY_ID,
+            ReplyContext::Withdraw {
+                receiver,
+                balances_before,
+                min_assets_to_receive,
+            },
+        ) => {
+            let mut messages = vec![];
+            for before in balances_before {
+                let received = before
+                    .info
+                    .query_pool(&deps.querier, &env.contract.address)?
+                    .checked_sub(before.amount)?;
+                let min_amount = min_assets_to_receive
+                    .iter()
+                    .find(|asset| asset.info == before.info)
+                    .map(|asset| asset.amount)
+                    .unwrap_or_default();
+                if received < min_amount {
+                    return Err(ContractError::ReceivedAssetTooLow {
+                        asset: before.info.to_string(),
+                        received,
+                        expected: min_amount,
+                    });
+                }
+                if !received.is_zero() {
+                    messages.push(before.info.with_balance(received).into_msg(&receiver)?);
+                }
+            }
+
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "withdraw_liquidity_reply"))
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+/// Builds a message which stakes LP tokens in the staking contract on behalf of the receiver.
+fn stake_lp_msg(lp: &Asset, staking: &Addr, receiver: &Addr) -> Result<CosmosMsg, ContractError> {
+    let msg = match &lp.info {
+        AssetInfo::NativeToken { .. } => wasm_execute(
+            staking,
+            &IncentivesExecuteMsg::Deposit {
+                recipient: Some(receiver.to_string()),
+                referrer: None,
+            },
+            vec![lp.as_coin()?],
+        )?,
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &Cw20ExecuteMsg::Send {
+                contract: staking.to_string(),
+                amount: lp.amount,
+                msg: to_json_binary(&IncentivesCw20Msg::Deposit {
+                    recipient: Some(receiver.to_string()),
+                    referrer: None,
+                })?,
+            },
+            vec![],
+        )?,
+    };
+
+    Ok(msg.into())
+}
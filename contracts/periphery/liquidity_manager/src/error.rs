@@ -0,0 +1,47 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+/// This enum describes liquidity manager contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Pair {0} is not registered in the factory")]
+    UnknownPair(String),
+
+    #[error("Invalid assets. Expected assets of the pair {0}")]
+    InvalidAssets(String),
+
+    #[error("Native token balance mismatch between the argument and the transferred")]
+    InvalidFunds {},
+
+    #[error("min_lp_to_receive must be greater than zero")]
+    ZeroMinLpToReceive {},
+
+    #[error("Received {received} LP tokens, expected at least {expected}")]
+    ReceivedLpTooLow {
+        received: Uint128,
+        expected: Uint128,
+    },
+
+    #[error("Received {received} {asset}, expected at least {expected}")]
+    ReceivedAssetTooLow {
+        asset: String,
+        received: Uint128,
+        expected: Uint128,
+    },
+
+    #[error("Staking contract is not set in the factory")]
+    StakingNotSet {},
+
+    #[error("Failed to parse reply")]
+    FailedToParseReply {},
+}
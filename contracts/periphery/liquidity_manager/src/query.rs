@@ -0,0 +1,47 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env};
+
+use astroport::liquidity_manager::QueryMsg;
+
+use crate::error::ContractError;
+use crate::state::CONFIG;
+use crate::utils::{check_assets, query_registered_pair, simulate_provide, simulate_withdraw};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::SimulateProvide {
+            pair_addr,
+            assets,
+            slippage_tolerance,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let pair_info =
+                query_registered_pair(&deps.querier, &config.astroport_factory, &pair_addr)?;
+            check_assets(&pair_info, &assets)?;
+
+            Ok(to_json_binary(&simulate_provide(
+                &deps.querier,
+                &pair_info,
+                &assets,
+                slippage_tolerance,
+            )?)?)
+        }
+        QueryMsg::SimulateWithdraw {
+            pair_addr,
+            lp_amount,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let pair_info =
+                query_registered_pair(&deps.querier, &config.astroport_factory, &pair_addr)?;
+
+            Ok(to_json_binary(&simulate_withdraw(
+                &deps.querier,
+                &pair_info,
+                lp_amount,
+            )?)?)
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::Item;
+
+use astroport::asset::{Asset, AssetInfo};
+use astroport::liquidity_manager::Config;
+
+/// Stores the contract configuration
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Temporarily stores the context of a provide or withdraw operation until the pair reply is received
+pub const REPLY_CONTEXT: Item<ReplyContext> = Item::new("reply_context");
+
+/// The state needed to validate and finalize a liquidity operation in the reply handler.
+#[cw_serde]
+pub enum ReplyContext {
+    Provide {
+        /// Receives unused assets
+        sender: Addr,
+        /// Receives LP tokens or their stake in the staking contract
+        receiver: Addr,
+        /// The LP token of the pair
+        lp_token: AssetInfo,
+        /// Contract's LP token balance before the provision
+        lp_balance_before: Uint128,
+        /// The minimum amount of LP tokens that must be minted
+        min_lp_to_receive: Uint128,
+        /// Contract's pool asset balances excluding the deposited assets
+        balances_before: Vec<Asset>,
+        /// Whether to stake LP tokens on behalf of the receiver
+        auto_stake: bool,
+    },
+    Withdraw {
+        /// Receives withdrawn assets
+        receiver: Addr,
+        /// Contract's pool asset balances before the withdrawal
+        balances_before: Vec<Asset>,
+        /// The minimum amounts of assets that must be received
+        min_assets_to_receive: Vec<Asset>,
+    },
+}
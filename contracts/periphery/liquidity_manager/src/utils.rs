@@ -0,0 +1,171 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, StdError, StdResult, Uint128};
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::PairType;
+use astroport::pair::{initial_share_after_lockup, PoolResponse, QueryMsg as PairQueryMsg};
+use astroport::querier::query_pair_info;
+use astroport::U256;
+
+use crate::error::ContractError;
+
+/// Pair messages which are understood by every Astroport pair version.
+/// Optional fields are omitted when not set, so that pairs which don't know them
+/// don't reject the message.
+#[cw_serde]
+pub enum CompatPairExecuteMsg {
+    ProvideLiquidity {
+        assets: Vec<Asset>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        slippage_tolerance: Option<Decimal>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        auto_stake: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        receiver: Option<String>,
+    },
+    /// Also used as a cw20 hook message by pairs with cw20 LP tokens
+    WithdrawLiquidity {
+        #[serde(default)]
+        assets: Vec<Asset>,
+    },
+}
+
+/// Returns the pair information if the pair is registered in the factory.
+pub fn query_registered_pair(
+    querier: &QuerierWrapper,
+    factory: &Addr,
+    pair_addr: &str,
+) -> Result<PairInfo, ContractError> {
+    let pair_info: PairInfo = querier.query_wasm_smart(pair_addr, &PairQueryMsg::Pair {})?;
+
+    let registered = query_pair_info(querier, factory, &pair_info.asset_infos)
+        .map(|info| info.contract_addr == pair_addr)
+        .unwrap_or(false);
+
+    if !registered {
+        return Err(ContractError::UnknownPair(pair_addr.to_string()));
+    }
+
+    Ok(pair_info)
+}
+
+/// Checks that every asset belongs to the pair and is specified only once.
+pub fn check_assets(pair_info: &PairInfo, assets: &[Asset]) -> Result<(), ContractError> {
+    let is_valid = assets.iter().enumerate().all(|(i, asset)| {
+        pair_info.asset_infos.contains(&asset.info)
+            && !assets[..i].iter().any(|prev| prev.info == asset.info)
+    });
+
+    if !is_valid {
+        return Err(ContractError::InvalidAssets(
+            pair_info.contract_addr.to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the balances of the pair assets held by `addr`.
+pub fn query_balances(
+    querier: &QuerierWrapper,
+    asset_infos: &[AssetInfo],
+    addr: &Addr,
+) -> StdResult<Vec<Asset>> {
+    asset_infos
+        .iter()
+        .map(|info| Ok(info.with_balance(info.query_pool(querier, addr)?)))
+        .collect()
+}
+
+/// Returns the amount of LP tokens minted for the given assets.
+/// XYK shares are calculated by the contract itself which makes the simulation available for
+/// XYK pairs of any version. Other pair types are asked for their own simulation.
+pub fn simulate_provide(
+    querier: &QuerierWrapper,
+    pair_info: &PairInfo,
+    assets: &[Asset],
+    slippage_tolerance: Option<Decimal>,
+) -> StdResult<Uint128> {
+    match &pair_info.pair_type {
+        PairType::Xyk {} => {
+            let pool: PoolResponse =
+                querier.query_wasm_smart(&pair_info.contract_addr, &PairQueryMsg::Pool {})?;
+            let deposits = pool
+                .assets
+                .iter()
+                .map(|pool_asset| {
+                    assets
+                        .iter()
+                        .find(|asset| asset.info == pool_asset.info)
+                        .map(|asset| asset.amount)
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+
+            xyk_provide_simulation(&deposits, &pool)
+        }
+        _ => querier.query_wasm_smart(
+            &pair_info.contract_addr,
+            &PairQueryMsg::SimulateProvide {
+                assets: assets.to_vec(),
+                slippage_tolerance,
+            },
+        ),
+    }
+}
+
+/// Calculates the XYK pool share the same way XYK pairs do.
+///
+/// * **deposits** deposited amounts in the pool assets order.
+pub fn xyk_provide_simulation(deposits: &[Uint128], pool: &PoolResponse) -> StdResult<Uint128> {
+    if deposits.len() != 2 || pool.assets.len() != 2 {
+        return Err(StdError::generic_err(
+            "XYK pool must contain exactly two assets",
+        ));
+    }
+
+    let share = if pool.total_share.is_zero() {
+        let share = (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
+            .integer_sqrt()
+            .as_u128();
+
+        initial_share_after_lockup(share.into()).unwrap_or_default()
+    } else {
+        std::cmp::min(
+            deposits[0].multiply_ratio(pool.total_share, pool.assets[0].amount),
+            deposits[1].multiply_ratio(pool.total_share, pool.assets[1].amount),
+        )
+    };
+
+    if share.is_zero() {
+        return Err(StdError::generic_err("Provided liquidity is too small"));
+    }
+
+    Ok(share)
+}
+
+/// Returns the assets received for the given amount of LP tokens.
+/// Every pair type withdraws liquidity proportionally to its reserves thus the simulation
+/// doesn't depend on the pair version.
+pub fn simulate_withdraw(
+    querier: &QuerierWrapper,
+    pair_info: &PairInfo,
+    lp_amount: Uint128,
+) -> StdResult<Vec<Asset>> {
+    let pool: PoolResponse =
+        querier.query_wasm_smart(&pair_info.contract_addr, &PairQueryMsg::Pool {})?;
+
+    if pool.total_share.is_zero() {
+        return Err(StdError::generic_err("Pool is empty"));
+    }
+
+    Ok(pool
+        .assets
+        .into_iter()
+        .map(|asset| {
+            asset
+                .info
+                .with_balance(asset.amount.multiply_ratio(lp_amount, pool.total_share))
+        })
+        .collect())
+}
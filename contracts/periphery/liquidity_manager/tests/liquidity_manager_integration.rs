@@ -0,0 +1,452 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{coin, coins, Addr, Coin, Uint128};
+
+use astroport::asset::{native_asset_info, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::liquidity_manager::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use astroport_liquidity_manager::error::ContractError;
+use astroport_test::cw_multi_test::{AppBuilder, ContractWrapper, Executor};
+use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
+
+const OWNER: &str = "owner";
+
+struct TestSuite {
+    app: TestApp,
+    factory: Addr,
+    incentives: Addr,
+    manager: Addr,
+    pair_info: PairInfo,
+}
+
+fn asset_infos() -> Vec<AssetInfo> {
+    vec![
+        native_asset_info("uusd".to_string()),
+        native_asset_info("uluna".to_string()),
+    ]
+}
+
+fn setup() -> TestSuite {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = AppBuilder::new_custom()
+        .with_stargate(MockStargate::default())
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &owner,
+                    vec![
+                        coin(100_000_000_000, "uusd"),
+                        coin(100_000_000_000, "uluna"),
+                        coin(100_000_000_000, "cny"),
+                    ],
+                )
+                .unwrap()
+        });
+
+    let pair_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair::contract::execute,
+            astroport_pair::contract::instantiate,
+            astroport_pair::contract::query,
+        )
+        .with_reply_empty(astroport_pair::contract::reply),
+    ));
+    let factory_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_factory::contract::execute,
+            astroport_factory::contract::instantiate,
+            astroport_factory::contract::query,
+        )
+        .with_reply_empty(astroport_factory::contract::reply),
+    ));
+    let incentives_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+        astroport_incentives::execute::execute,
+        astroport_incentives::instantiate::instantiate,
+        astroport_incentives::query::query,
+    )));
+    let manager_code_id = app.store_code(Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_liquidity_manager::contract::execute,
+            astroport_liquidity_manager::contract::instantiate,
+            astroport_liquidity_manager::query::query,
+        )
+        .with_reply_empty(astroport_liquidity_manager::contract::reply),
+    ));
+
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                pair_configs: vec![PairConfig {
+                    code_id: pair_code_id,
+                    pair_type: PairType::Xyk {},
+                    total_fee_bps: 0,
+                    maker_fee_bps: 0,
+                    is_disabled: false,
+                    is_generator_disabled: false,
+                    permissioned: false,
+                    min_trade_notional: None,
+                }],
+                token_code_id: 0,
+                fee_address: None,
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: "coin_registry".to_string(),
+                tracker_config: None,
+                ownership_timelock: None,
+            },
+            &[],
+            "Factory",
+            None,
+        )
+        .unwrap();
+
+    let incentives = app
+        .instantiate_contract(
+            incentives_code_id,
+            owner.clone(),
+            &astroport::incentives::InstantiateMsg {
+                astro_token: native_asset_info("astro".to_string()),
+                factory: factory.to_string(),
+                owner: owner.to_string(),
+                guardian: None,
+                incentivization_fee_info: None,
+                vesting_contract: "vesting".to_string(),
+                ownership_timelock: None,
+            },
+            &[],
+            "Incentives",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::UpdateConfig {
+            token_code_id: None,
+            fee_address: None,
+            generator_address: Some(incentives.to_string()),
+            whitelist_code_id: None,
+            coin_registry_address: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &astroport::factory::ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: asset_infos(),
+            init_params: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(
+            &factory,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: asset_infos(),
+            },
+        )
+        .unwrap();
+
+    let manager = app
+        .instantiate_contract(
+            manager_code_id,
+            owner,
+            &InstantiateMsg {
+                astroport_factory: factory.to_string(),
+            },
+            &[],
+            "Liquidity manager",
+            None,
+        )
+        .unwrap();
+
+    TestSuite {
+        app,
+        factory,
+        incentives,
+        manager,
+        pair_info,
+    }
+}
+
+fn mint(env: &mut TestSuite, user: &Addr, funds: &[Coin]) {
+    env.app
+        .send_tokens(Addr::unchecked(OWNER), user.clone(), funds)
+        .unwrap();
+}
+
+fn provide_msg(
+    env: &TestSuite,
+    uusd: u128,
+    uluna: u128,
+    auto_stake: Option<bool>,
+    min_lp_to_receive: u128,
+) -> ExecuteMsg {
+    let infos = asset_infos();
+    ExecuteMsg::ProvideLiquidity {
+        pair_addr: env.pair_info.contract_addr.to_string(),
+        assets: vec![infos[0].with_balance(uusd), infos[1].with_balance(uluna)],
+        slippage_tolerance: None,
+        auto_stake,
+        receiver: None,
+        min_lp_to_receive: Uint128::new(min_lp_to_receive),
+    }
+}
+
+fn balance(env: &TestSuite, user: &Addr, denom: &str) -> u128 {
+    env.app
+        .wrap()
+        .query_balance(user, denom)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn test_provide_and_withdraw() {
+    let mut env = setup();
+    let user = Addr::unchecked("user");
+    let lp_denom = env.pair_info.liquidity_token.clone();
+    let funds = [coin(100_000_000, "uluna"), coin(100_000_000, "uusd")];
+    mint(&mut env, &user, &funds);
+
+    let simulated: Uint128 = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.manager,
+            &QueryMsg::SimulateProvide {
+                pair_addr: env.pair_info.contract_addr.to_string(),
+                assets: asset_infos()
+                    .iter()
+                    .map(|info| info.with_balance(100_000_000u128))
+                    .collect(),
+                slippage_tolerance: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(simulated.u128(), 100_000_000 - 1000);
+
+    // Minimum which can't be reached
+    let err = env
+        .app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &provide_msg(&env, 100_000_000, 100_000_000, None, 100_000_000),
+            &funds,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReceivedLpTooLow {
+            received: simulated,
+            expected: Uint128::new(100_000_000),
+        }
+    );
+
+    // The minimum must be set by the user
+    let err = env
+        .app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &provide_msg(&env, 100_000_000, 100_000_000, None, 0),
+            &funds,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ZeroMinLpToReceive {}
+    );
+
+    // Sent funds must match the assets
+    let err = env
+        .app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &provide_msg(&env, 100_000_000, 100_000_000, None, simulated.u128()),
+            &[coin(100_000_000, "uluna")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidFunds {}
+    );
+
+    env.app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &provide_msg(&env, 100_000_000, 100_000_000, None, simulated.u128()),
+            &funds,
+        )
+        .unwrap();
+    assert_eq!(balance(&env, &user, &lp_denom), simulated.u128());
+    assert_eq!(balance(&env, &env.manager, &lp_denom), 0);
+
+    let simulated_assets: Vec<astroport::asset::Asset> = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.manager,
+            &QueryMsg::SimulateWithdraw {
+                pair_addr: env.pair_info.contract_addr.to_string(),
+                lp_amount: Uint128::new(50_000_000),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        simulated_assets,
+        asset_infos()
+            .iter()
+            .map(|info| info.with_balance(50_000_000u128))
+            .collect::<Vec<_>>()
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &ExecuteMsg::WithdrawLiquidity {
+                pair_addr: env.pair_info.contract_addr.to_string(),
+                min_assets_to_receive: Some(vec![
+                    native_asset_info("uusd".to_string()).with_balance(50_000_001u128)
+                ]),
+                receiver: None,
+            },
+            &coins(50_000_000, &lp_denom),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ReceivedAssetTooLow {
+            asset: "uusd".to_string(),
+            received: Uint128::new(50_000_000),
+            expected: Uint128::new(50_000_001),
+        }
+    );
+
+    let receiver = Addr::unchecked("receiver");
+    env.app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &ExecuteMsg::WithdrawLiquidity {
+                pair_addr: env.pair_info.contract_addr.to_string(),
+                min_assets_to_receive: None,
+                receiver: Some(receiver.to_string()),
+            },
+            &coins(50_000_000, &lp_denom),
+        )
+        .unwrap();
+    assert_eq!(balance(&env, &receiver, "uusd"), 50_000_000);
+    assert_eq!(balance(&env, &receiver, "uluna"), 50_000_000);
+    assert_eq!(
+        balance(&env, &user, &lp_denom),
+        simulated.u128() - 50_000_000
+    );
+    assert_eq!(balance(&env, &env.manager, "uusd"), 0);
+    assert_eq!(balance(&env, &env.manager, "uluna"), 0);
+}
+
+#[test]
+fn test_provide_with_auto_stake() {
+    let mut env = setup();
+    let user = Addr::unchecked("user");
+    let lp_denom = env.pair_info.liquidity_token.clone();
+    let funds = [coin(100_000_000, "uluna"), coin(100_000_000, "uusd")];
+    mint(&mut env, &user, &funds);
+
+    env.app
+        .execute_contract(
+            user.clone(),
+            env.manager.clone(),
+            &provide_msg(
+                &env,
+                100_000_000,
+                100_000_000,
+                Some(true),
+                100_000_000 - 1000,
+            ),
+            &funds,
+        )
+        .unwrap();
+
+    let staked: Uint128 = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.incentives,
+            &astroport::incentives::QueryMsg::Deposit {
+                lp_token: lp_denom.clone(),
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(staked.u128(), 100_000_000 - 1000);
+    assert_eq!(balance(&env, &user, &lp_denom), 0);
+    assert_eq!(balance(&env, &env.manager, &lp_denom), 0);
+}
+
+#[test]
+fn test_unknown_pair() {
+    let mut env = setup();
+    let user = Addr::unchecked("user");
+    let funds = [coin(100_000_000, "uluna"), coin(100_000_000, "uusd")];
+    mint(&mut env, &user, &funds);
+
+    // The same pair code instantiated outside of the factory
+    let pair_code_id = env
+        .app
+        .wrap()
+        .query_wasm_contract_info(&env.pair_info.contract_addr)
+        .unwrap()
+        .code_id;
+    let fake_pair = env
+        .app
+        .instantiate_contract(
+            pair_code_id,
+            user.clone(),
+            &astroport::pair::InstantiateMsg {
+                pair_type: PairType::Xyk {},
+                asset_infos: asset_infos(),
+                token_code_id: 0,
+                factory_addr: env.factory.to_string(),
+                init_params: None,
+            },
+            &[],
+            "Fake pair",
+            None,
+        )
+        .unwrap();
+
+    let mut msg = provide_msg(&env, 100_000_000, 100_000_000, None, 1);
+    if let ExecuteMsg::ProvideLiquidity { pair_addr, .. } = &mut msg {
+        *pair_addr = fake_pair.to_string();
+    }
+
+    let err = env
+        .app
+        .execute_contract(user, env.manager.clone(), &msg, &funds)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnknownPair(fake_pair.to_string())
+    );
+}
@@ -13,7 +13,8 @@ use itertools::Itertools;
 
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::native_coin_registry::{
-    CoinResponse, Config, ExecuteMsg, InstantiateMsg, QueryMsg, COINS_INFO,
+    is_token_factory_denom, query_denom_metadata_decimals, CoinResponse, Config, ExecuteMsg,
+    InstantiateMsg, QueryMsg, COINS_INFO,
 };
 
 use crate::error::ContractError;
@@ -57,6 +58,7 @@ pub fn execute(
     match msg {
         ExecuteMsg::Add { native_coins } => update_decimals(deps, info, native_coins),
         ExecuteMsg::Register { native_coins } => register_decimals(deps, info, native_coins),
+        ExecuteMsg::RegisterTokenFactory { denoms } => register_token_factory(deps, denoms),
         ExecuteMsg::Remove { native_coins } => remove(deps, info, native_coins),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
@@ -124,6 +126,37 @@ pub fn register_decimals(
     inner_add(deps.storage, native_coins, Some(send_msg))
 }
 
+/// Resolves decimals of tokenfactory denoms from the bank module metadata and caches them.
+/// Denoms which are already in the registry are skipped.
+/// Permissionless.
+///
+/// * **denoms** is a vector with the tokenfactory denoms we are adding to the registry.
+pub fn register_token_factory(
+    deps: DepsMut,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    // Check for duplicate denoms
+    let mut uniq = HashSet::new();
+    if !denoms.iter().all(|denom| uniq.insert(denom)) {
+        return Err(ContractError::DuplicateCoins {});
+    }
+
+    let native_coins = denoms
+        .into_iter()
+        .filter(|denom| !COINS_INFO.has(deps.storage, denom.clone()))
+        .map(|denom| {
+            ensure!(
+                is_token_factory_denom(&denom),
+                ContractError::NotTokenFactoryDenom(denom)
+            );
+            let decimals = query_denom_metadata_decimals(&deps.querier, &denom)?;
+            Ok((denom, decimals))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    inner_add(deps.storage, native_coins, None)
+}
+
 /// Adds or updates a native asset in the registry.
 ///
 /// * **native_coins** is a vector with the assets we are adding to the registry.
@@ -229,13 +262,32 @@ pub fn remove(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::NativeToken { denom } => to_json_binary(&COINS_INFO.load(deps.storage, denom)?),
+        QueryMsg::NativeToken { denom } => to_json_binary(&query_native_token(deps, denom)?),
         QueryMsg::NativeTokens { start_after, limit } => {
             to_json_binary(&query_native_tokens(deps, start_after, limit)?)
         }
     }
 }
 
+/// Returns the precision of a native asset.
+/// Tokenfactory denoms which are not registered yet are resolved from the bank module metadata.
+pub fn query_native_token(deps: Deps, denom: String) -> StdResult<u8> {
+    match COINS_INFO.may_load(deps.storage, denom.clone())? {
+        Some(decimals) => Ok(decimals),
+        None if is_token_factory_denom(&denom) => {
+            let decimals = query_denom_metadata_decimals(&deps.querier, &denom)?;
+            if ALLOWED_DECIMALS.contains(&decimals) {
+                Ok(decimals)
+            } else {
+                Err(StdError::generic_err(
+                    ContractError::InvalidDecimals { denom, decimals }.to_string(),
+                ))
+            }
+        }
+        None => COINS_INFO.load(deps.storage, denom),
+    }
+}
+
 /// Returns a vector with native assets by specified parameters.
 pub fn query_native_tokens(
     deps: Deps,
@@ -260,7 +312,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
 
     match contract_version.contract.as_ref() {
         "astroport-native-coin-registry" => match contract_version.version.as_ref() {
-            "1.0.1" => {}
+            "1.0.1" | "1.1.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
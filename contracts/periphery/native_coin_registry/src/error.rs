@@ -26,4 +26,7 @@ pub enum ContractError {
 
     #[error("You must send 1 {0} unit")]
     MustSendCoin(String),
+
+    #[error("Not a tokenfactory denom: {0}")]
+    NotTokenFactoryDenom(String),
 }
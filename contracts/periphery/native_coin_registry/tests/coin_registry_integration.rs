@@ -1,16 +1,167 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{coin, Addr};
-use cw_multi_test::{App, BankSudo, ContractWrapper, Executor};
-
-use astroport::native_coin_registry::{CoinResponse, Config, ExecuteMsg, InstantiateMsg, QueryMsg};
+use cosmos_sdk_proto::cosmos::bank::v1beta1::QueryDenomMetadataRequest;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, to_json_binary, Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Empty, Querier,
+    Storage,
+};
+use cw_multi_test::error::{anyhow, AnyResult};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, BankSudo, ContractWrapper, CosmosRouter,
+    DistributionKeeper, Executor, FailingModule, GovFailingModule, IbcFailingModule, Module,
+    StakeKeeper, Stargate, StargateMsg, StargateQuery, WasmKeeper,
+};
+use prost::Message;
+
+use astroport::native_coin_registry::{
+    CoinResponse, Config, ExecuteMsg, InstantiateMsg, QueryMsg, DENOM_METADATA_QUERY_PATH,
+};
 use astroport_native_coin_registry::error::ContractError;
 
 fn mock_app() -> App {
     App::default()
 }
 
-fn store_native_registry_code(app: &mut App) -> u64 {
+#[cw_serde]
+struct MockDenomUnit {
+    denom: String,
+    exponent: u32,
+    aliases: Vec<String>,
+}
+
+#[cw_serde]
+struct MockMetadata {
+    description: String,
+    denom_units: Vec<MockDenomUnit>,
+    base: String,
+    display: String,
+}
+
+#[cw_serde]
+struct MockMetadataResponse {
+    metadata: MockMetadata,
+}
+
+/// Bank metadata mock which serves the tokenfactory denoms used in tests.
+#[derive(Default)]
+struct MockBankMetadata {}
+
+impl Stargate for MockBankMetadata {}
+
+impl Module for MockBankMetadata {
+    type ExecT = StargateMsg;
+    type QueryT = StargateQuery;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: Self::ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        Err(anyhow!("Unexpected exec msg {msg:?} from {sender}"))
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: Self::QueryT,
+    ) -> AnyResult<Binary> {
+        if request.path != DENOM_METADATA_QUERY_PATH {
+            return Err(anyhow!("Unexpected query path {}", request.path));
+        }
+        let denom = QueryDenomMetadataRequest::decode(request.data.as_slice())?.denom;
+
+        let (display, exponent) = match denom.as_str() {
+            "factory/creator/utoken" => ("token", 6),
+            "factory/creator/atoken" => ("token", 18),
+            "factory/creator/bigtoken" => ("token", 24),
+            _ => return Err(anyhow!("Metadata for {denom} not found")),
+        };
+
+        Ok(to_json_binary(&MockMetadataResponse {
+            metadata: MockMetadata {
+                description: String::new(),
+                denom_units: vec![
+                    MockDenomUnit {
+                        denom: denom.clone(),
+                        exponent: 0,
+                        aliases: vec![],
+                    },
+                    MockDenomUnit {
+                        denom: display.to_string(),
+                        exponent,
+                        aliases: vec![],
+                    },
+                ],
+                base: denom,
+                display: display.to_string(),
+            },
+        })?)
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Self::SudoT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        unimplemented!("Sudo not implemented")
+    }
+}
+
+type MetadataApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    MockBankMetadata,
+>;
+
+fn mock_app_with_metadata() -> MetadataApp {
+    AppBuilder::new()
+        .with_stargate(MockBankMetadata::default())
+        .build(|_, _, _| {})
+}
+
+fn store_native_registry_code<StargateT: Stargate>(
+    app: &mut App<
+        BankKeeper,
+        MockApi,
+        MockStorage,
+        FailingModule<Empty, Empty, Empty>,
+        WasmKeeper<Empty, Empty>,
+        StakeKeeper,
+        DistributionKeeper,
+        IbcFailingModule,
+        GovFailingModule,
+        StargateT,
+    >,
+) -> u64 {
     let contract = Box::new(ContractWrapper::new_with_empty(
         astroport_native_coin_registry::contract::execute,
         astroport_native_coin_registry::contract::instantiate,
@@ -547,3 +698,142 @@ fn test_permissionless_add() {
         .unwrap();
     assert_eq!(coin_decimals, 6);
 }
+
+#[test]
+fn test_token_factory_decimals_discovery() {
+    let mut app = mock_app_with_metadata();
+    let owner = Addr::unchecked("owner");
+
+    let native_registry_code_id = store_native_registry_code(&mut app);
+    let native_registry_instance = app
+        .instantiate_contract(
+            native_registry_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            "label",
+            None,
+        )
+        .unwrap();
+
+    // Unregistered tokenfactory denoms are resolved on the fly
+    let decimals: u8 = app
+        .wrap()
+        .query_wasm_smart(
+            &native_registry_instance,
+            &QueryMsg::NativeToken {
+                denom: "factory/creator/atoken".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(decimals, 18);
+
+    // Other unregistered denoms are not
+    app.wrap()
+        .query_wasm_smart::<u8>(
+            &native_registry_instance,
+            &QueryMsg::NativeToken {
+                denom: "utoken".to_string(),
+            },
+        )
+        .unwrap_err();
+
+    // Resolved decimals must be within the allowed range
+    let err = app
+        .wrap()
+        .query_wasm_smart::<u8>(
+            &native_registry_instance,
+            &QueryMsg::NativeToken {
+                denom: "factory/creator/bigtoken".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Invalid decimals 24 for factory/creator/bigtoken"));
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            native_registry_instance.clone(),
+            &ExecuteMsg::RegisterTokenFactory {
+                denoms: vec!["utoken".to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NotTokenFactoryDenom("utoken".to_string())
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            native_registry_instance.clone(),
+            &ExecuteMsg::RegisterTokenFactory {
+                denoms: vec!["factory/creator/bigtoken".to_string()],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidDecimals {
+            denom: "factory/creator/bigtoken".to_string(),
+            decimals: 24
+        }
+    );
+
+    // The owner overrides decimals of one denom
+    app.execute_contract(
+        owner.clone(),
+        native_registry_instance.clone(),
+        &ExecuteMsg::Add {
+            native_coins: vec![("factory/creator/atoken".to_string(), 8)],
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Anyone can cache decimals without sending funds
+    app.execute_contract(
+        Addr::unchecked("random"),
+        native_registry_instance.clone(),
+        &ExecuteMsg::RegisterTokenFactory {
+            denoms: vec![
+                "factory/creator/utoken".to_string(),
+                "factory/creator/atoken".to_string(),
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let coins: Vec<CoinResponse> = app
+        .wrap()
+        .query_wasm_smart(
+            &native_registry_instance,
+            &QueryMsg::NativeTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    // Existing entries are not overwritten
+    assert_eq!(
+        coins,
+        vec![
+            CoinResponse {
+                denom: "factory/creator/atoken".to_string(),
+                decimals: 8
+            },
+            CoinResponse {
+                denom: "factory/creator/utoken".to_string(),
+                decimals: 6
+            },
+        ]
+    );
+}
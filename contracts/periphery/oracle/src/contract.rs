@@ -4,14 +4,15 @@ use crate::querier::{query_cumulative_prices, query_prices};
 use crate::state::{
     get_precision, store_precisions, Config, PriceCumulativeLast, CONFIG, PRICE_LAST,
 };
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
 use astroport::oracle::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use astroport::pair::TWAP_PRECISION;
+use astroport::price_hub::{ExecuteMsg as PriceHubExecuteMsg, PriceFeed};
 use astroport::querier::query_pair_info;
 
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128, Uint256,
+    attr, entry_point, to_json_binary, wasm_execute, Binary, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, Uint256,
 };
 use cw2::{get_contract_version, set_contract_version};
 
@@ -46,6 +47,7 @@ pub fn instantiate(
         factory: factory_contract,
         asset_infos: msg.asset_infos,
         pair: pair_info.clone(),
+        price_hub: addr_opt_validate(deps.api, &msg.price_hub)?,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -71,19 +73,23 @@ pub fn instantiate(
 ///
 /// ## Variants
 /// * **ExecuteMsg::Update {}** Updates the local TWAP values for the assets in the Astroport pool.
+///
+/// * **ExecuteMsg::SetPriceHub { price_hub }** Sets the contract which receives average prices on every update.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Update {} => update(deps, env),
+        ExecuteMsg::SetPriceHub { price_hub } => set_price_hub(deps, info, price_hub),
     }
 }
 
 /// Updates the local TWAP values for the tokens in the target Astroport pool.
+/// If the price hub is set, the new average prices are published to it.
 pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let price_last = PRICE_LAST.load(deps.storage)?;
@@ -112,13 +118,57 @@ pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
         }
     }
 
+    let mut response = Response::default();
+
+    if let Some(price_hub) = config.price_hub {
+        let price_precision = Decimal256::from_ratio(10_u128.pow(TWAP_PRECISION.into()), 1u8);
+        let prices = average_prices
+            .iter()
+            .map(|(base, quote, price_average)| PriceFeed {
+                base: base.clone(),
+                quote: quote.clone(),
+                price: *price_average / price_precision,
+                updated_at: env.block.time.seconds(),
+            })
+            .collect();
+
+        response = response
+            .add_message(wasm_execute(
+                &price_hub,
+                &PriceHubExecuteMsg::PublishPrices { prices },
+                vec![],
+            )?)
+            .add_attribute("price_hub", price_hub);
+    }
+
     let prices = PriceCumulativeLast {
         cumulative_prices: prices.cumulative_prices,
         average_prices,
         block_timestamp_last: env.block.time.seconds(),
     };
     PRICE_LAST.save(deps.storage, &prices)?;
-    Ok(Response::default())
+    Ok(response)
+}
+
+/// Sets the price hub which receives average prices on every update. Publishing is disabled if `price_hub` is not set.
+pub fn set_price_hub(
+    deps: DepsMut,
+    info: MessageInfo,
+    price_hub: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.price_hub = addr_opt_validate(deps.api, &price_hub)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_price_hub"),
+        attr("price_hub", price_hub.unwrap_or_default()),
+    ]))
 }
 
 /// Exposes all the queries available in the contract.
@@ -126,13 +176,48 @@ pub fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
 /// ## Queries
 /// * **QueryMsg::Consult { token, amount }** Validates assets and calculates a new average
 /// amount with updated precision
+///
+/// * **QueryMsg::ConsultMany { assets }** Same as Consult but for a batch of assets
+///
+/// * **QueryMsg::ConsultManyPerUnit { assets }** Calculates average amounts for one whole unit of each asset
+///
+/// * **QueryMsg::PriceHub {}** Returns the price hub which receives average prices on every update
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Consult { token, amount } => to_json_binary(&consult(deps, token, amount)?),
+        QueryMsg::ConsultMany { assets } => to_json_binary(&consult_many(deps, assets)?),
+        QueryMsg::ConsultManyPerUnit { assets } => {
+            let assets = assets
+                .into_iter()
+                .map(|asset| {
+                    let p = get_precision(deps.storage, &asset)?;
+                    Ok((asset, Uint128::new(10_u128.pow(p.into()))))
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            to_json_binary(&consult_many(deps, assets)?)
+        }
+        QueryMsg::PriceHub {} => to_json_binary(&CONFIG.load(deps.storage)?.price_hub),
     }
 }
 
+/// Multiplies each token amount by its latest TWAP value.
+/// Results are returned in the same order as the assets in the request.
+///
+/// * **assets** pairs of (token, amount) for which we compute TWAP converted amounts.
+fn consult_many(
+    deps: Deps,
+    assets: Vec<(AssetInfo, Uint128)>,
+) -> StdResult<Vec<(AssetInfo, Vec<(AssetInfo, Uint256)>)>> {
+    assets
+        .into_iter()
+        .map(|(token, amount)| {
+            let amounts = consult(deps, token.clone(), amount)?;
+            Ok((token, amounts))
+        })
+        .collect()
+}
+
 /// Multiplies a token amount by its latest TWAP value.
 /// * **token** token for which we multiply its TWAP value by an amount.
 ///
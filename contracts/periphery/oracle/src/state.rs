@@ -31,6 +31,9 @@ pub struct Config {
     pub asset_infos: Vec<AssetInfo>,
     /// Information about the pair (LP token address, pair type etc)
     pub pair: PairInfo,
+    /// The price hub which receives average prices on every update
+    #[serde(default)]
+    pub price_hub: Option<Addr>,
 }
 
 /// Stores map of AssetInfo (as String) -> precision
@@ -1,9 +1,11 @@
 use crate::contract::{execute, instantiate};
+use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
 use astroport::asset::{Asset, AssetInfo};
 use astroport::oracle::{ExecuteMsg, InstantiateMsg};
+use astroport::price_hub::{ExecuteMsg as PriceHubExecuteMsg, PriceFeed};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{Addr, Decimal256, Uint128, Uint256};
+use cosmwasm_std::{from_json, Addr, CosmosMsg, Decimal256, SubMsg, Uint128, Uint256, WasmMsg};
 use std::ops::Mul;
 
 #[test]
@@ -64,6 +66,7 @@ fn oracle_overflow() {
     let instantiate_msg = InstantiateMsg {
         factory_contract: factory.to_string(),
         asset_infos: vec![astro_asset_info, usdc_asset_info],
+        price_hub: None,
     };
 
     // Set cumulative price to 192738282u128
@@ -107,3 +110,149 @@ fn oracle_overflow() {
     env.block.time = env.block.time.plus_seconds(86400);
     execute(deps.as_mut(), env, info, ExecuteMsg::Update {}).unwrap();
 }
+
+#[test]
+fn publish_prices_to_hub() {
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("owner", &[]);
+    let mut env = mock_env();
+
+    let astro_token_contract = Addr::unchecked("astro-token");
+    let usdc_token_contract = Addr::unchecked("usdc-token");
+    deps.querier.with_token_balances(&[
+        (
+            &astro_token_contract.to_string(),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(10000))],
+        ),
+        (
+            &usdc_token_contract.to_string(),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(10000))],
+        ),
+    ]);
+
+    let assets = vec![
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: astro_token_contract,
+            },
+            amount: Uint128::zero(),
+        },
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: usdc_token_contract,
+            },
+            amount: Uint128::zero(),
+        },
+    ];
+    let cumulative_prices = |price0: u128, price1: u128| {
+        vec![
+            (
+                assets[0].info.clone(),
+                assets[1].info.clone(),
+                Uint128::new(price0),
+            ),
+            (
+                assets[1].info.clone(),
+                assets[0].info.clone(),
+                Uint128::new(price1),
+            ),
+        ]
+    };
+
+    deps.querier.set_cumulative_price(
+        Addr::unchecked("pair"),
+        assets.clone(),
+        Uint128::zero(),
+        cumulative_prices(0, 0),
+    );
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            factory_contract: "factory".to_string(),
+            asset_infos: vec![assets[0].info.clone(), assets[1].info.clone()],
+            price_hub: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        ExecuteMsg::SetPriceHub {
+            price_hub: Some("hub".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::SetPriceHub {
+            price_hub: Some("hub".to_string()),
+        },
+    )
+    .unwrap();
+
+    // 1 ASTRO = 2 USDC over the whole period
+    deps.querier.set_cumulative_price(
+        Addr::unchecked("pair"),
+        assets.clone(),
+        Uint128::zero(),
+        cumulative_prices(86400 * 2_000000, 86400 * 500000),
+    );
+    env.block.time = env.block.time.plus_seconds(86400);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Update {},
+    )
+    .unwrap();
+
+    let [SubMsg {
+        msg: CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }),
+        ..
+    }] = res.messages.as_slice()
+    else {
+        panic!("Unexpected messages {:?}", res.messages);
+    };
+    assert_eq!(contract_addr, "hub");
+    assert_eq!(
+        from_json::<PriceHubExecuteMsg>(msg).unwrap(),
+        PriceHubExecuteMsg::PublishPrices {
+            prices: vec![
+                PriceFeed {
+                    base: assets[0].info.clone(),
+                    quote: assets[1].info.clone(),
+                    price: Decimal256::from_ratio(2u8, 1u8),
+                    updated_at: env.block.time.seconds(),
+                },
+                PriceFeed {
+                    base: assets[1].info.clone(),
+                    quote: assets[0].info.clone(),
+                    price: Decimal256::from_ratio(1u8, 2u8),
+                    updated_at: env.block.time.seconds(),
+                },
+            ]
+        }
+    );
+
+    // Publishing is disabled without the price hub
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::SetPriceHub { price_hub: None },
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(86400);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Update {}).unwrap();
+    assert!(res.messages.is_empty());
+}
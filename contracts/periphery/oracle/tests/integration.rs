@@ -15,7 +15,7 @@ use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport::factory::{PairConfig, PairType};
 
 use astroport::oracle::QueryMsg::Consult;
-use astroport::oracle::{ExecuteMsg, InstantiateMsg};
+use astroport::oracle::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 const OWNER: &str = "owner";
 
@@ -156,6 +156,7 @@ fn instantiate_contracts(mut router: &mut App, owner: Addr) -> (Addr, Addr, u64)
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
             PairConfig {
                 code_id: pair_stable_code_id,
@@ -165,6 +166,7 @@ fn instantiate_contracts(mut router: &mut App, owner: Addr) -> (Addr, Addr, u64)
                 is_disabled: false,
                 is_generator_disabled: false,
                 permissioned: false,
+                min_trade_notional: None,
             },
         ],
         token_code_id: 1u64,
@@ -173,6 +175,8 @@ fn instantiate_contracts(mut router: &mut App, owner: Addr) -> (Addr, Addr, u64)
         owner: owner.to_string(),
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
+        tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -328,6 +332,9 @@ fn provide_liquidity(
             slippage_tolerance: None,
             auto_stake: None,
             receiver: None,
+            min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         },
         &funds,
     )
@@ -445,6 +452,9 @@ fn change_provide_liquidity(
                 slippage_tolerance: Some(Decimal::percent(50)),
                 auto_stake: None,
                 receiver: None,
+                min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
             },
             &vec![],
         )
@@ -534,6 +544,7 @@ fn consult() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        price_hub: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -597,6 +608,77 @@ fn consult() {
             .unwrap();
         assert_eq!(res[0].1, amount);
     }
+
+    let astro_asset_info = AssetInfo::Token {
+        contract_addr: astro_token_instance.clone(),
+    };
+    let usdc_asset_info = AssetInfo::Token {
+        contract_addr: usdc_token_instance.clone(),
+    };
+
+    let res: Vec<(AssetInfo, Vec<(AssetInfo, Uint128)>)> = router
+        .wrap()
+        .query_wasm_smart(
+            &oracle_instance,
+            &QueryMsg::ConsultMany {
+                assets: vec![
+                    (astro_asset_info.clone(), Uint128::from(1000u128)),
+                    (usdc_asset_info.clone(), Uint128::from(100u128)),
+                ],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            (
+                astro_asset_info.clone(),
+                vec![(usdc_asset_info.clone(), Uint128::from(1000u128))]
+            ),
+            (
+                usdc_asset_info.clone(),
+                vec![(astro_asset_info.clone(), Uint128::from(100u128))]
+            ),
+        ]
+    );
+
+    // Both tokens have 6 decimals
+    let res: Vec<(AssetInfo, Vec<(AssetInfo, Uint128)>)> = router
+        .wrap()
+        .query_wasm_smart(
+            &oracle_instance,
+            &QueryMsg::ConsultManyPerUnit {
+                assets: vec![usdc_asset_info.clone(), astro_asset_info.clone()],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            (
+                usdc_asset_info.clone(),
+                vec![(astro_asset_info.clone(), Uint128::from(1_000000u128))]
+            ),
+            (
+                astro_asset_info.clone(),
+                vec![(usdc_asset_info.clone(), Uint128::from(1_000000u128))]
+            ),
+        ]
+    );
+
+    let err = router
+        .wrap()
+        .query_wasm_smart::<Vec<(AssetInfo, Vec<(AssetInfo, Uint128)>)>>(
+            &oracle_instance,
+            &QueryMsg::ConsultMany {
+                assets: vec![
+                    (astro_asset_info, Uint128::from(1000u128)),
+                    (AssetInfo::native("uluna"), Uint128::from(100u128)),
+                ],
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid Token"), "{err}");
 }
 
 #[test]
@@ -722,6 +804,7 @@ fn consult2() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        price_hub: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -936,6 +1019,7 @@ fn consult_zero_price() {
     let msg = InstantiateMsg {
         factory_contract: factory_instance.to_string(),
         asset_infos: asset_infos.clone(),
+        price_hub: None,
     };
     let oracle_instance = router
         .instantiate_contract(
@@ -1062,6 +1146,7 @@ fn consult_zero_price() {
             &InstantiateMsg {
                 factory_contract: factory_instance.to_string(),
                 asset_infos: asset_infos.clone(),
+                price_hub: None,
             },
             &[],
             String::from("ORACLE 2"),
@@ -1,14 +1,14 @@
 use cosmwasm_schema::cw_serde;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError, Uint128};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128};
 use cw2::{get_contract_version, set_contract_version};
 
 use astroport::asset::validate_native_denom;
 use astroport::tokenfactory_tracker::{InstantiateMsg, SudoMsg};
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG, TOTAL_SUPPLY_HISTORY};
+use crate::state::{Config, BALANCES, CONFIG, TOP_HOLDERS, TOTAL_SUPPLY_HISTORY};
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -26,10 +26,15 @@ pub fn instantiate(
 
     validate_native_denom(&msg.tracked_denom)?;
 
+    if msg.leaderboard_min_balance == Some(Uint128::zero()) {
+        return Err(ContractError::InvalidLeaderboardMinBalance {});
+    }
+
     let config = Config {
         d: msg.tracked_denom.clone(),
         m: msg.tokenfactory_module_address,
         t: msg.track_over_seconds,
+        l: msg.leaderboard_min_balance,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -86,6 +91,7 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
 /// - if from == module_address -> mint
 /// - if to == module_address -> burn
 /// - other scenarios are simple transfers between addresses
+/// If the leaderboard is enabled, [`TOP_HOLDERS`] index is updated along with balances.
 /// Possible errors:
 /// - serialization/deserialization errors.
 /// It should never happen if both BALANCES and TOTAL_SUPPLY_HISTORY storage keys and data layout are not changed.
@@ -100,22 +106,16 @@ pub fn track_balances(
     if from != to {
         if from != config.m {
             let from_balance = deps.querier.query_balance(&from, &config.d)?.amount;
-            BALANCES.save(
-                deps.storage,
-                &from,
-                &from_balance.checked_sub(amount)?,
-                tracking_unit,
-            )?;
+            let new_balance = from_balance.checked_sub(amount)?;
+            BALANCES.save(deps.storage, &from, &new_balance, tracking_unit)?;
+            update_top_holders(deps.storage, config, &from, from_balance, new_balance)?;
         }
 
         if to != config.m {
             let to_balance = deps.querier.query_balance(&to, &config.d)?.amount;
-            BALANCES.save(
-                deps.storage,
-                &to,
-                &to_balance.checked_add(amount)?,
-                tracking_unit,
-            )?;
+            let new_balance = to_balance.checked_add(amount)?;
+            BALANCES.save(deps.storage, &to, &new_balance, tracking_unit)?;
+            update_top_holders(deps.storage, config, &to, to_balance, new_balance)?;
         }
     }
 
@@ -125,6 +125,31 @@ pub fn track_balances(
     Ok(Response::default())
 }
 
+/// Moves the holder to the new position in the [`TOP_HOLDERS`] index.
+/// Holders with balance below the leaderboard min balance are not indexed.
+/// Does nothing if the leaderboard is disabled.
+fn update_top_holders(
+    storage: &mut dyn Storage,
+    config: &Config,
+    holder: &str,
+    prev_balance: Uint128,
+    new_balance: Uint128,
+) -> StdResult<()> {
+    let Some(min_balance) = config.l else {
+        return Ok(());
+    };
+
+    if prev_balance >= min_balance {
+        TOP_HOLDERS.remove(storage, (prev_balance.u128(), holder));
+    }
+
+    if new_balance >= min_balance {
+        TOP_HOLDERS.save(storage, (new_balance.u128(), holder), &())?;
+    }
+
+    Ok(())
+}
+
 #[cw_serde]
 pub struct MigrateMsg {
     pub track_over_seconds: bool,
@@ -145,9 +170,11 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
                 d: config.d,
                 m: config.m,
                 t: msg.track_over_seconds,
+                l: None,
             };
             CONFIG.save(deps.storage, &new_config)?;
         }
+        ("astroport-tokenfactory-tracker", "2.0.0") => {}
         _ => {
             return Err(StdError::generic_err(format!(
                 "Unsupported contract version: {} {}",
@@ -260,6 +287,7 @@ mod tests {
                     tokenfactory_module_address: MODULE_ADDRESS.to_string(),
                     tracked_denom: DENOM.to_string(),
                     track_over_seconds: true,
+                    leaderboard_min_balance: None,
                 },
                 &[],
                 "label",
@@ -377,6 +405,92 @@ mod tests {
         assert_eq!(balance, expected_total_supply);
     }
 
+    #[test]
+    fn track_top_holders() {
+        let mut app = App::new(|router, _, store| {
+            router
+                .bank
+                .init_balance(store, &Addr::unchecked(MODULE_ADDRESS), coins(1000, DENOM))
+                .unwrap();
+        });
+
+        let tracker_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(instantiate, instantiate, query).with_sudo_empty(sudo),
+        ));
+        let tracker_contract = app
+            .instantiate_contract(
+                tracker_code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    tokenfactory_module_address: MODULE_ADDRESS.to_string(),
+                    tracked_denom: DENOM.to_string(),
+                    track_over_seconds: false,
+                    leaderboard_min_balance: Some(Uint128::new(50)),
+                },
+                &[],
+                "label",
+                None,
+            )
+            .unwrap();
+        app.sudo(
+            BankSudo::SetHook {
+                denom: DENOM.to_string(),
+                contract_addr: tracker_contract.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+        for (to, amount) in [
+            ("user1", 300),
+            ("user2", 200),
+            ("user3", 200),
+            ("user4", 40),
+        ] {
+            app.send_tokens(
+                Addr::unchecked(MODULE_ADDRESS),
+                Addr::unchecked(to),
+                &coins(amount, DENOM),
+            )
+            .unwrap();
+        }
+        // user1 drops below user2 and user3, user4 gets above the min balance
+        app.send_tokens(
+            Addr::unchecked("user1"),
+            Addr::unchecked("user4"),
+            &coins(150, DENOM),
+        )
+        .unwrap();
+        // user3 drops below the min balance
+        app.send_tokens(
+            Addr::unchecked("user3"),
+            Addr::unchecked("user2"),
+            &coins(160, DENOM),
+        )
+        .unwrap();
+
+        let top_holders = |start_after: Option<&str>, limit: Option<u32>| {
+            app.wrap()
+                .query_wasm_smart::<Vec<(String, Uint128)>>(
+                    &tracker_contract,
+                    &QueryMsg::TopHolders {
+                        start_after: start_after.map(ToString::to_string),
+                        limit,
+                    },
+                )
+                .unwrap()
+        };
+
+        let expected = vec![
+            ("user2".to_string(), Uint128::new(360)),
+            ("user4".to_string(), Uint128::new(190)),
+            ("user1".to_string(), Uint128::new(150)),
+        ];
+        assert_eq!(top_holders(None, None), expected);
+        assert_eq!(top_holders(None, Some(2)), expected[..2]);
+        assert_eq!(top_holders(Some("user4"), None), expected[2..]);
+    }
+
     #[test]
     fn no_track_other_token() {
         let mut deps = mock_dependencies();
@@ -391,6 +505,7 @@ mod tests {
                 tokenfactory_module_address: MODULE_ADDRESS.to_string(),
                 tracked_denom: DENOM.to_string(),
                 track_over_seconds: true,
+                leaderboard_min_balance: None,
             },
         )
         .unwrap();
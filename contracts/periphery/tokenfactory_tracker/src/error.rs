@@ -11,4 +11,7 @@ pub enum ContractError {
 
     #[error("Invalid denom, expected {expected_denom}")]
     InvalidDenom { expected_denom: String },
+
+    #[error("Leaderboard min balance must be greater than zero")]
+    InvalidLeaderboardMinBalance {},
 }
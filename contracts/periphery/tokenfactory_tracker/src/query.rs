@@ -1,10 +1,11 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult, Uint128};
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::Bound;
 
 use astroport::tokenfactory_tracker::{ConfigResponse, QueryMsg};
 
-use crate::state::{BALANCES, CONFIG, TOTAL_SUPPLY_HISTORY};
+use crate::state::{BALANCES, CONFIG, DEFAULT_LIMIT, MAX_LIMIT, TOP_HOLDERS, TOTAL_SUPPLY_HISTORY};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -19,8 +20,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 tracked_denom: config.d,
                 token_factory_module: config.m,
                 track_over_seconds: config.t,
+                leaderboard_min_balance: config.l,
             })
         }
+        QueryMsg::TopHolders { start_after, limit } => {
+            to_json_binary(&top_holders(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -53,3 +58,37 @@ fn total_supply_at(deps: Deps, env: Env, unit: Option<u64>) -> StdResult<Uint128
     }
     .map(|total_supply| total_supply.unwrap_or_default())
 }
+
+fn top_holders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.l.is_none() {
+        return Err(StdError::generic_err("Leaderboard is disabled"));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|holder| -> StdResult<_> {
+            let balance = BALANCES
+                .may_load(deps.storage, &holder)?
+                .unwrap_or_default();
+            Ok((balance.u128(), holder))
+        })
+        .transpose()?;
+
+    TOP_HOLDERS
+        .keys(
+            deps.storage,
+            None,
+            start_after
+                .as_ref()
+                .map(|(balance, holder)| Bound::exclusive((*balance, holder.as_str()))),
+            Order::Descending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(balance, holder)| (holder, Uint128::new(balance))))
+        .collect()
+}
@@ -1,6 +1,11 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Uint128;
-use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+
+/// Default number of holders returned by the top holders query
+pub const DEFAULT_LIMIT: u32 = 10;
+/// Max number of holders returned by the top holders query
+pub const MAX_LIMIT: u32 = 100;
 
 #[cw_serde]
 pub struct Config {
@@ -14,6 +19,10 @@ pub struct Config {
     /// Default is false.
     #[serde(default)]
     pub t: bool,
+    /// Minimum balance of holders indexed in the leaderboard.
+    /// If not set, the leaderboard is disabled.
+    #[serde(default)]
+    pub l: Option<Uint128>,
 }
 
 pub const CONFIG: Item<Config> = Item::new("c");
@@ -25,3 +34,7 @@ pub const BALANCES: SnapshotMap<&str, Uint128> =
 /// Contains the history of the total supply of the tracked denom
 pub const TOTAL_SUPPLY_HISTORY: SnapshotItem<Uint128> =
     SnapshotItem::new("t", "t_chpts", "t_chlg", Strategy::EveryBlock);
+
+/// Holders with balance of at least [`Config::l`] ordered by balance.
+/// key: (balance, address)
+pub const TOP_HOLDERS: Map<(u128, &str), ()> = Map::new("th");
@@ -162,6 +162,7 @@ impl<'a> TestSuite<'a> {
             tokenfactory_module_address: self.tokenfactory_module_address.clone(),
             tracked_denom: denom.to_string(),
             track_over_seconds: true,
+            leaderboard_min_balance: None,
         };
         let tracker_addr = self
             .wasm
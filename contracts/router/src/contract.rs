@@ -1,21 +1,31 @@
 use cosmwasm_std::{
-    entry_point, from_json, to_json_binary, wasm_execute, Addr, Api, Binary, Decimal, Deps,
-    DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128,
+    attr, ensure, entry_point, from_json, to_json_binary, wasm_execute, Addr, Api, Binary, Coin,
+    Decimal, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg,
+    SubMsgResult, Uint128,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
 
 use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
-use astroport::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
-use astroport::querier::query_pair_info;
+use astroport::pair::{
+    check_recipient_commitment, QueryMsg as PairQueryMsg, RecipientCommitment, SimulationResponse,
+};
+use astroport::querier::query_factory_config;
+use astroport::route::{HopKind, Route};
 use astroport::router::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, SwapResponseData, MAX_SWAP_OPERATIONS,
+    SimulateSwapOperationsResponse, SwapOperation, SwapResponseData,
 };
 
 use crate::error::ContractError;
-use crate::operations::execute_swap_operation;
-use crate::state::{Config, ReplyData, CONFIG, REPLY_DATA};
+use crate::operations::{
+    execute_swap_operation, native_hop_msg, native_route_offer, validate_route_pairs,
+};
+use crate::state::{
+    Config, NativeRoute, ReplyData, CONFIG, DEFAULT_LIMIT, HOP_REPLY_DATA, MAX_LIMIT, NATIVE_ROUTE,
+    REPLY_DATA, ROUTES,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-router";
@@ -23,6 +33,8 @@ const CONTRACT_NAME: &str = "astroport-router";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const AFTER_SWAP_REPLY_ID: u64 = 1;
+pub const NATIVE_HOP_REPLY_ID: u64 = 2;
+pub const HOP_SWAP_REPLY_ID: u64 = 3;
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -64,6 +76,12 @@ pub fn instantiate(
 ///             minimum_receive,
 ///             receiver
 ///         }** Checks if an ask amount is higher than or equal to the minimum amount to receive.
+///
+/// * **ExecuteMsg::SwapByRegisteredRoute { .. }** Swaps along the route registered for the given assets.
+///
+/// * **ExecuteMsg::RegisterRoute { route }** Stores a canonical route. Factory owner only.
+///
+/// * **ExecuteMsg::DeregisterRoute { .. }** Removes a registered route. Factory owner only.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -72,20 +90,23 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ExecuteSwapOperations {
             operations,
             minimum_receive,
             to,
             max_spread,
+            to_commitment,
         } => execute_swap_operations(
             deps,
             env,
             info.sender,
+            info.funds,
             operations,
             minimum_receive,
             to,
             max_spread,
+            to_commitment,
         ),
         ExecuteMsg::ExecuteSwapOperation {
             operation,
@@ -93,6 +114,30 @@ pub fn execute(
             max_spread,
             single,
         } => execute_swap_operation(deps, env, info, operation, to, max_spread, single),
+        ExecuteMsg::SwapByRegisteredRoute {
+            offer_asset_info,
+            ask_asset_info,
+            minimum_receive,
+            to,
+            max_spread,
+            to_commitment,
+        } => swap_by_registered_route(
+            deps,
+            env,
+            info.sender,
+            info.funds,
+            &offer_asset_info,
+            &ask_asset_info,
+            minimum_receive,
+            to,
+            max_spread,
+            to_commitment,
+        ),
+        ExecuteMsg::RegisterRoute { route } => register_route(deps, info, route),
+        ExecuteMsg::DeregisterRoute {
+            offer_asset_info,
+            ask_asset_info,
+        } => deregister_route(deps, info, &offer_asset_info, &ask_asset_info),
     }
 }
 
@@ -102,6 +147,7 @@ pub fn execute(
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     match from_json(&cw20_msg.msg)? {
@@ -110,40 +156,89 @@ pub fn receive_cw20(
             minimum_receive,
             to,
             max_spread,
+            to_commitment,
         } => execute_swap_operations(
             deps,
             env,
             Addr::unchecked(cw20_msg.sender),
+            vec![],
             operations,
             minimum_receive,
             to,
             max_spread,
+            to_commitment,
+        ),
+        Cw20HookMsg::SwapByRegisteredRoute {
+            ask_asset_info,
+            minimum_receive,
+            to,
+            max_spread,
+            to_commitment,
+        } => swap_by_registered_route(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            vec![],
+            &AssetInfo::cw20(info.sender),
+            &ask_asset_info,
+            minimum_receive,
+            to,
+            max_spread,
+            to_commitment,
         ),
     }
 }
 
 /// Performs swap operations with the specified parameters.
+/// Routes swapping only native coins which are attached to the message take the fast path:
+/// each hop is sent to the pair directly with the coins returned by the previous hop.
 ///
 /// * **sender** address that swaps tokens.
 ///
+/// * **funds** native coins attached to the message.
+///
 /// * **operations** all swap operations to perform.
 ///
 /// * **minimum_receive** used to guarantee that the ask amount is above a minimum amount.
 ///
 /// * **to** recipient of the ask tokens.
+///
+/// * **to_commitment** optional commitment the recipient must match.
 #[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
     env: Env,
     sender: Addr,
+    funds: Vec<Coin>,
     operations: Vec<SwapOperation>,
     minimum_receive: Option<Uint128>,
     to: Option<String>,
     max_spread: Option<Decimal>,
+    to_commitment: Option<RecipientCommitment>,
 ) -> Result<Response, ContractError> {
-    assert_operations(deps.api, &operations)?;
+    // Only the last hop sends tokens out of the router, so checking the recipient once is enough
+    check_recipient_commitment(to.as_deref(), to_commitment.as_ref())?;
+
+    let (operations, _) = normalize_operations(deps.api, operations)?;
+    validate_route_pairs(deps.as_ref(), &operations)?;
 
     let to = addr_opt_validate(deps.api, &to)?.unwrap_or(sender);
+
+    if let Some(offer_coin) = native_route_offer(&operations, &funds) {
+        let mut route = NativeRoute {
+            operations,
+            hop: 0,
+            to,
+            max_spread,
+            minimum_receive,
+            prev_balance: Uint128::zero(),
+        };
+        let msg = native_hop_msg(deps.as_ref(), &env, &mut route, offer_coin.amount)?;
+        NATIVE_ROUTE.save(deps.storage, &route)?;
+
+        return Ok(Response::new().add_submessage(msg));
+    }
+
     let target_asset_info = operations.last().unwrap().get_target_asset_info();
     let operations_len = operations.len();
 
@@ -193,8 +288,110 @@ pub fn execute_swap_operations(
     Ok(Response::new().add_submessages(messages))
 }
 
+/// Performs swap operations along the route registered for the given offer and ask assets.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_by_registered_route(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    funds: Vec<Coin>,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+    max_spread: Option<Decimal>,
+    to_commitment: Option<RecipientCommitment>,
+) -> Result<Response, ContractError> {
+    let route = load_route(deps.as_ref(), offer_asset_info, ask_asset_info)?;
+
+    execute_swap_operations(
+        deps,
+        env,
+        sender,
+        funds,
+        route.to_swap_operations()?,
+        minimum_receive,
+        to,
+        max_spread,
+        to_commitment,
+    )
+}
+
+/// Stores the route under its (offer, ask) key. Routes are executed through factory pairs thus
+/// hops can't pin pair addresses.
+fn register_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    route: Route,
+) -> Result<Response, ContractError> {
+    assert_factory_owner(deps.as_ref(), &info.sender)?;
+
+    ensure!(
+        route.hops.iter().all(|hop| hop.pair.is_none()),
+        ContractError::PinnedPairNotSupported {}
+    );
+    route.validate(deps.api)?;
+    let operations = route.to_swap_operations()?;
+    assert_operations(deps.api, &operations)?;
+    validate_route_pairs(deps.as_ref(), &operations)?;
+
+    let offer_asset = &route.assets[0];
+    let ask_asset = &route.assets[route.hops.len()];
+    ROUTES.save(deps.storage, (offer_asset, ask_asset), &route)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "register_route"),
+        attr("offer_asset", offer_asset.to_string()),
+        attr("ask_asset", ask_asset.to_string()),
+        attr("hops", route.hops.len().to_string()),
+    ]))
+}
+
+fn deregister_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Result<Response, ContractError> {
+    assert_factory_owner(deps.as_ref(), &info.sender)?;
+
+    load_route(deps.as_ref(), offer_asset_info, ask_asset_info)?;
+    ROUTES.remove(deps.storage, (offer_asset_info, ask_asset_info));
+
+    Ok(Response::new().add_attributes([
+        attr("action", "deregister_route"),
+        attr("offer_asset", offer_asset_info.to_string()),
+        attr("ask_asset", ask_asset_info.to_string()),
+    ]))
+}
+
+/// Routes are governed by the factory owner.
+fn assert_factory_owner(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let factory_config = query_factory_config(&deps.querier, config.astroport_factory)?;
+    ensure!(
+        *sender == factory_config.owner,
+        ContractError::Unauthorized {}
+    );
+
+    Ok(())
+}
+
+fn load_route(
+    deps: Deps,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Result<Route, ContractError> {
+    ROUTES
+        .may_load(deps.storage, (offer_asset_info, ask_asset_info))?
+        .ok_or_else(|| ContractError::RouteNotFound {
+            offer_asset: offer_asset_info.to_string(),
+            ask_asset: ask_asset_info.to_string(),
+        })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: AFTER_SWAP_REPLY_ID,
@@ -222,10 +419,90 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             Ok(Response::new().set_data(data))
         }
+        Reply {
+            id: NATIVE_HOP_REPLY_ID,
+            result: SubMsgResult::Ok(..),
+        } => native_hop_reply(deps, env),
+        Reply {
+            id: HOP_SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(..),
+        } => {
+            let hop = HOP_REPLY_DATA.load(deps.storage)?;
+            let receiver_balance = hop
+                .ask_asset_info
+                .query_pool(&deps.querier, &hop.receiver)?;
+            let return_amount = receiver_balance.checked_sub(hop.prev_balance)?;
+
+            if return_amount < hop.minimum_receive {
+                return Err(ContractError::AssertionHopMinimumReceive {
+                    offer_asset: hop.offer_asset_info.to_string(),
+                    ask_asset: hop.ask_asset_info.to_string(),
+                    receive: hop.minimum_receive,
+                    amount: return_amount,
+                });
+            }
+
+            Ok(Response::new())
+        }
         _ => Err(StdError::generic_err("Failed to process reply").into()),
     }
 }
 
+/// Continues a native route with the coins returned by the finished hop.
+/// The hop output is the ask coin balance change of the hop receiver.
+fn native_hop_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut route = NATIVE_ROUTE.load(deps.storage)?;
+
+    let SwapOperation::AstroSwap {
+        offer_asset_info,
+        ask_asset_info,
+        minimum_receive,
+    } = &route.operations[route.hop]
+    else {
+        return Err(ContractError::NativeSwapNotSupported {});
+    };
+
+    let receiver = if route.hop + 1 == route.operations.len() {
+        &route.to
+    } else {
+        &env.contract.address
+    };
+    let return_amount = ask_asset_info
+        .query_pool(&deps.querier, receiver)?
+        .checked_sub(route.prev_balance)?;
+    if let Some(minimum_receive) = minimum_receive {
+        if return_amount < *minimum_receive {
+            return Err(ContractError::AssertionHopMinimumReceive {
+                offer_asset: offer_asset_info.to_string(),
+                ask_asset: ask_asset_info.to_string(),
+                receive: *minimum_receive,
+                amount: return_amount,
+            });
+        }
+    }
+
+    route.hop += 1;
+    if route.hop < route.operations.len() {
+        let msg = native_hop_msg(deps.as_ref(), &env, &mut route, return_amount)?;
+        NATIVE_ROUTE.save(deps.storage, &route)?;
+
+        return Ok(Response::new().add_submessage(msg));
+    }
+
+    NATIVE_ROUTE.remove(deps.storage);
+
+    if let Some(minimum_receive) = route.minimum_receive {
+        if return_amount < minimum_receive {
+            return Err(ContractError::AssertionMinimumReceive {
+                receive: minimum_receive,
+                amount: return_amount,
+            });
+        }
+    }
+
+    Ok(Response::new().set_data(to_json_binary(&SwapResponseData { return_amount })?))
+}
+
 /// Exposes all the queries available in the contract.
 /// ## Queries
 /// * **QueryMsg::Config {}** Returns general router parameters using a [`ConfigResponse`] object.
@@ -233,6 +510,11 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             offer_amount,
 ///             operations,
 ///         }** Simulates one or multiple swap operations and returns the end result in a [`SimulateSwapOperationsResponse`] object.
+/// * **QueryMsg::Route { operations }** Returns the deduplicated and validated swap operations as a [`Route`] object.
+/// * **QueryMsg::RegisteredRoute { .. }** Returns the [`Route`] registered for the given assets.
+/// * **QueryMsg::RegisteredRoutes { start_after, limit }** Lists registered routes.
+/// * **QueryMsg::ValidateRoute { operations }** Returns the [`Route`] with pairs resolved through the factory
+/// or an error naming the first hop whose pair is deregistered or disabled.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -245,9 +527,64 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             offer_amount,
             operations,
         )?)?),
+        QueryMsg::Route { operations } => {
+            let (_, route) = normalize_operations(deps.api, operations)?;
+            Ok(to_json_binary(&route)?)
+        }
+        QueryMsg::RegisteredRoute {
+            offer_asset_info,
+            ask_asset_info,
+        } => Ok(to_json_binary(&load_route(
+            deps,
+            &offer_asset_info,
+            &ask_asset_info,
+        )?)?),
+        QueryMsg::RegisteredRoutes { start_after, limit } => Ok(to_json_binary(
+            &query_registered_routes(deps, start_after, limit)?,
+        )?),
+        QueryMsg::ValidateRoute { operations } => {
+            Ok(to_json_binary(&query_validate_route(deps, operations)?)?)
+        }
     }
 }
 
+/// Validates swap operations the same way the router does before executing a swap.
+/// Returns the canonical [`Route`] with every hop pinned to the pair resolved through the factory.
+///
+/// * **operations** swap operations to validate.
+fn query_validate_route(
+    deps: Deps,
+    operations: Vec<SwapOperation>,
+) -> Result<Route, ContractError> {
+    let (operations, mut route) = normalize_operations(deps.api, operations)?;
+    let pairs = validate_route_pairs(deps, &operations)?;
+
+    for (hop, pair) in route.hops.iter_mut().zip(pairs) {
+        hop.pair = Some(pair.into_string());
+    }
+
+    Ok(route)
+}
+
+/// Returns registered routes ordered by their (offer, ask) key.
+fn query_registered_routes(
+    deps: Deps,
+    start_after: Option<(AssetInfo, AssetInfo)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Route>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(offer_asset, ask_asset)| Bound::exclusive((offer_asset, ask_asset)));
+
+    // Asset infos can't be deserialized from storage keys thus routes are read as values only
+    ROUTES
+        .range_raw(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, route)| route))
+        .collect()
+}
+
 /// Returns general contract settings in a [`ConfigResponse`] object.
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     let state = CONFIG.load(deps.storage)?;
@@ -266,7 +603,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     match contract_version.contract.as_ref() {
         "astroport-router" => match contract_version.version.as_ref() {
-            "1.1.1" => {}
+            "1.1.1" | "1.2.1" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -293,26 +630,20 @@ fn simulate_swap_operations(
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
-    assert_operations(deps.api, &operations)?;
+    let (operations, _) = normalize_operations(deps.api, operations)?;
+    let pairs = validate_route_pairs(deps, &operations)?;
 
-    let config = CONFIG.load(deps.storage)?;
-    let astroport_factory = config.astroport_factory;
     let mut return_amount = offer_amount;
 
-    for operation in operations.into_iter() {
+    for (operation, pair) in operations.into_iter().zip(pairs) {
         match operation {
             SwapOperation::AstroSwap {
                 offer_asset_info,
                 ask_asset_info,
+                minimum_receive,
             } => {
-                let pair_info = query_pair_info(
-                    &deps.querier,
-                    astroport_factory.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )?;
-
                 let res: SimulationResponse = deps.querier.query_wasm_smart(
-                    pair_info.contract_addr,
+                    pair,
                     &PairQueryMsg::Simulation {
                         offer_asset: Asset {
                             info: offer_asset_info.clone(),
@@ -322,6 +653,17 @@ fn simulate_swap_operations(
                     },
                 )?;
 
+                if let Some(minimum_receive) = minimum_receive {
+                    if res.return_amount < minimum_receive {
+                        return Err(ContractError::AssertionHopMinimumReceive {
+                            offer_asset: offer_asset_info.to_string(),
+                            ask_asset: ask_asset_info.to_string(),
+                            receive: minimum_receive,
+                            amount: res.return_amount,
+                        });
+                    }
+                }
+
                 return_amount = res.return_amount;
             }
             SwapOperation::NativeSwap { .. } => {
@@ -335,60 +677,36 @@ fn simulate_swap_operations(
     })
 }
 
-/// Validates swap operations.
+/// Validates swap operations by converting them into the canonical [`Route`].
 ///
 /// * **operations** is a vector that contains objects of type [`SwapOperation`]. These are all the swap operations we check.
-fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<(), ContractError> {
-    let operations_len = operations.len();
-    if operations_len == 0 {
-        return Err(ContractError::MustProvideOperations {});
-    }
+fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<Route, ContractError> {
+    let route = Route::try_from(operations)?;
+    route.validate(api)?;
 
-    if operations_len > MAX_SWAP_OPERATIONS {
-        return Err(ContractError::SwapLimitExceeded {});
+    if route.hops.iter().any(|hop| hop.kind == HopKind::Native) {
+        return Err(ContractError::NativeSwapNotSupported {});
     }
 
-    let mut prev_ask_asset: Option<AssetInfo> = None;
-
-    for operation in operations {
-        let (offer_asset, ask_asset) = match operation {
-            SwapOperation::AstroSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => (offer_asset_info.clone(), ask_asset_info.clone()),
-            SwapOperation::NativeSwap { .. } => {
-                return Err(ContractError::NativeSwapNotSupported {})
-            }
-        };
-
-        offer_asset.check(api)?;
-        ask_asset.check(api)?;
-
-        if offer_asset.equal(&ask_asset) {
-            return Err(ContractError::DoublingAssetsPath {
-                offer_asset: offer_asset.to_string(),
-                ask_asset: ask_asset.to_string(),
-            });
-        }
+    Ok(route)
+}
 
-        if let Some(prev_ask_asset) = prev_ask_asset {
-            if prev_ask_asset != offer_asset {
-                return Err(ContractError::InvalidPathOperations {
-                    prev_ask_asset: prev_ask_asset.to_string(),
-                    next_offer_asset: offer_asset.to_string(),
-                    next_ask_asset: ask_asset.to_string(),
-                });
-            }
-        }
+/// Removes identical consecutive swap operations and validates the rest.
+/// Returns the remaining operations along with the canonical [`Route`] they describe.
+fn normalize_operations(
+    api: &dyn Api,
+    mut operations: Vec<SwapOperation>,
+) -> Result<(Vec<SwapOperation>, Route), ContractError> {
+    operations.dedup();
+    let route = assert_operations(api, &operations)?;
 
-        prev_ask_asset = Some(ask_asset);
-    }
-
-    Ok(())
+    Ok((operations, route))
 }
 
 #[cfg(test)]
 mod testing {
+    use astroport::asset::AssetInfo;
+
     use super::*;
 
     #[test]
@@ -411,6 +729,7 @@ mod testing {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: Addr::unchecked("asset0001"),
                         },
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::Token {
@@ -419,6 +738,7 @@ mod testing {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        minimum_receive: None,
                     },
                 ]
             )
@@ -438,6 +758,7 @@ mod testing {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: Addr::unchecked("asset0001"),
                         },
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::Token {
@@ -446,6 +767,7 @@ mod testing {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
@@ -454,6 +776,7 @@ mod testing {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: Addr::unchecked("asset0002"),
                         },
+                        minimum_receive: None,
                     },
                 ]
             )
@@ -473,6 +796,7 @@ mod testing {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: Addr::unchecked("asset0001"),
                         },
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::Token {
@@ -481,6 +805,7 @@ mod testing {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uaud".to_string(),
                         },
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
@@ -489,10 +814,61 @@ mod testing {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: Addr::unchecked("asset0002"),
                         },
+                        minimum_receive: None,
                     },
                 ]
             )
             .is_err()
         );
     }
+
+    #[test]
+    fn test_normalize_operations() {
+        use cosmwasm_std::testing::mock_dependencies;
+        let deps = mock_dependencies();
+
+        let first = SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::native("ukrw"),
+            ask_asset_info: AssetInfo::cw20_unchecked("asset0001"),
+            minimum_receive: None,
+        };
+        let second = SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::cw20_unchecked("asset0001"),
+            ask_asset_info: AssetInfo::native("uluna"),
+            minimum_receive: None,
+        };
+
+        // Identical consecutive hops are removed
+        let (operations, route) = normalize_operations(
+            deps.as_ref().api,
+            vec![first.clone(), first.clone(), second.clone(), second.clone()],
+        )
+        .unwrap();
+        assert_eq!(operations, vec![first.clone(), second.clone()]);
+        assert_eq!(
+            route.assets,
+            vec![
+                AssetInfo::native("ukrw"),
+                AssetInfo::cw20_unchecked("asset0001"),
+                AssetInfo::native("uluna"),
+            ]
+        );
+
+        // The broken hop index is reported
+        let third = SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::native("uaud"),
+            ask_asset_info: AssetInfo::cw20_unchecked("asset0002"),
+            minimum_receive: None,
+        };
+        let err = normalize_operations(deps.as_ref().api, vec![first, second, third]).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPathOperations {
+                hop: 2,
+                prev_ask_asset: "uluna".to_string(),
+                next_offer_asset: "uaud".to_string(),
+                next_ask_asset: "asset0002".to_string(),
+            }
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use astroport::route::RouteError;
+use cosmwasm_std::{Addr, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// This enum describes oracle contract errors
@@ -14,17 +15,19 @@ pub enum ContractError {
     Unauthorized {},
 
     #[error(
-        "The next offer asset must be the same as the previous ask asset; \
+        "Invalid swap operation {hop}. The next offer asset must be the same as the previous ask asset; \
     {prev_ask_asset} --> {next_offer_asset} --> {next_ask_asset}"
     )]
     InvalidPathOperations {
+        hop: usize,
         prev_ask_asset: String,
         next_offer_asset: String,
         next_ask_asset: String,
     },
 
-    #[error("Doubling assets in one batch of path; {offer_asset} --> {ask_asset}")]
+    #[error("Doubling assets in swap operation {hop}; {offer_asset} --> {ask_asset}")]
     DoublingAssetsPath {
+        hop: usize,
         offer_asset: String,
         ask_asset: String,
     },
@@ -35,12 +38,82 @@ pub enum ContractError {
     #[error("Assertion failed; minimum receive amount: {receive}, swap amount: {amount}")]
     AssertionMinimumReceive { receive: Uint128, amount: Uint128 },
 
+    #[error(
+        "Assertion failed for {offer_asset} --> {ask_asset} hop; \
+    minimum receive amount: {receive}, swap amount: {amount}"
+    )]
+    AssertionHopMinimumReceive {
+        offer_asset: String,
+        ask_asset: String,
+        receive: Uint128,
+        amount: Uint128,
+    },
+
     #[error("The swap operation limit was exceeded!")]
     SwapLimitExceeded {},
 
     #[error("Native swap operations are not supported!")]
     NativeSwapNotSupported {},
 
+    #[error("No route is registered for {offer_asset} --> {ask_asset}")]
+    RouteNotFound {
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("Registered routes can't pin pair addresses. Pairs are resolved through the factory")]
+    PinnedPairNotSupported {},
+
+    #[error(
+        "Swap operation {hop} {offer_asset} --> {ask_asset} has no pair registered in the factory"
+    )]
+    PairNotRegistered {
+        hop: usize,
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("Swap operation {hop} {offer_asset} --> {ask_asset} goes through pair {pair} which is disabled in the factory")]
+    PairDisabled {
+        hop: usize,
+        offer_asset: String,
+        ask_asset: String,
+        pair: Addr,
+    },
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
 }
+
+impl From<RouteError> for ContractError {
+    fn from(err: RouteError) -> Self {
+        match err {
+            RouteError::Std(err) => ContractError::Std(err),
+            RouteError::EmptyRoute {} => ContractError::MustProvideOperations {},
+            RouteError::TooManyHops { .. } => ContractError::SwapLimitExceeded {},
+            RouteError::DoublingAssets {
+                hop,
+                offer_asset,
+                ask_asset,
+            } => ContractError::DoublingAssetsPath {
+                hop,
+                offer_asset,
+                ask_asset,
+            },
+            RouteError::DisconnectedPath {
+                hop,
+                prev_ask_asset,
+                next_offer_asset,
+                next_ask_asset,
+            } => ContractError::InvalidPathOperations {
+                hop,
+                prev_ask_asset,
+                next_offer_asset,
+                next_ask_asset,
+            },
+            RouteError::HopsMismatch { .. } | RouteError::InvalidNativeHop { .. } => {
+                ContractError::Std(StdError::generic_err(err.to_string()))
+            }
+        }
+    }
+}
@@ -1,15 +1,16 @@
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo, AssetInfoExt};
 use astroport::pair::ExecuteMsg as PairExecuteMsg;
-use astroport::querier::{query_balance, query_pair_info, query_token_balance};
+use astroport::querier::{query_balance, query_pair_info, query_pair_paused, query_token_balance};
 use astroport::router::SwapOperation;
 use cosmwasm_std::{
-    to_json_binary, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, StdResult,
-    WasmMsg,
+    to_json_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
+use crate::contract::{HOP_SWAP_REPLY_ID, NATIVE_HOP_REPLY_ID};
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{HopReplyData, NativeRoute, CONFIG, HOP_REPLY_DATA};
 
 /// Execute a swap operation.
 ///
@@ -35,6 +36,7 @@ pub fn execute_swap_operation(
         SwapOperation::AstroSwap {
             offer_asset_info,
             ask_asset_info,
+            minimum_receive,
         } => {
             let config = CONFIG.load(deps.storage)?;
             let pair_info = query_pair_info(
@@ -45,30 +47,169 @@ pub fn execute_swap_operation(
 
             let amount = match &offer_asset_info {
                 AssetInfo::NativeToken { denom } => {
-                    query_balance(&deps.querier, env.contract.address, denom)?
+                    query_balance(&deps.querier, &env.contract.address, denom)?
                 }
                 AssetInfo::Token { contract_addr } => {
-                    query_token_balance(&deps.querier, contract_addr, env.contract.address)?
+                    query_token_balance(&deps.querier, contract_addr, &env.contract.address)?
                 }
             };
             let offer_asset = Asset {
-                info: offer_asset_info,
+                info: offer_asset_info.clone(),
                 amount,
             };
 
-            asset_into_swap_msg(
+            let msg = asset_into_swap_msg(
                 pair_info.contract_addr.to_string(),
                 offer_asset,
-                ask_asset_info,
+                ask_asset_info.clone(),
                 max_spread,
-                to,
+                to.clone(),
                 single,
-            )?
+            )?;
+
+            match minimum_receive {
+                Some(minimum_receive) => {
+                    let receiver =
+                        addr_opt_validate(deps.api, &to)?.unwrap_or(env.contract.address);
+                    let prev_balance = ask_asset_info.query_pool(&deps.querier, &receiver)?;
+                    HOP_REPLY_DATA.save(
+                        deps.storage,
+                        &HopReplyData {
+                            offer_asset_info,
+                            ask_asset_info,
+                            prev_balance,
+                            minimum_receive,
+                            receiver,
+                        },
+                    )?;
+
+                    SubMsg::reply_on_success(msg, HOP_SWAP_REPLY_ID)
+                }
+                None => SubMsg::new(msg),
+            }
         }
         SwapOperation::NativeSwap { .. } => return Err(ContractError::NativeSwapNotSupported {}),
     };
 
-    Ok(Response::new().add_message(message))
+    Ok(Response::new().add_submessage(message))
+}
+
+/// Resolves the pairs of all swap operations through the factory and ensures none of them
+/// is deregistered or paused. Returns pair addresses in the operations order.
+///
+/// * **operations** normalized swap operations.
+pub fn validate_route_pairs(
+    deps: Deps,
+    operations: &[SwapOperation],
+) -> Result<Vec<Addr>, ContractError> {
+    let factory = CONFIG.load(deps.storage)?.astroport_factory;
+
+    operations
+        .iter()
+        .enumerate()
+        .map(|(hop, operation)| {
+            let SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+                ..
+            } = operation
+            else {
+                return Err(ContractError::NativeSwapNotSupported {});
+            };
+
+            let pair = query_pair_info(
+                &deps.querier,
+                &factory,
+                &[offer_asset_info.clone(), ask_asset_info.clone()],
+            )
+            .map_err(|_| ContractError::PairNotRegistered {
+                hop,
+                offer_asset: offer_asset_info.to_string(),
+                ask_asset: ask_asset_info.to_string(),
+            })?
+            .contract_addr;
+
+            if query_pair_paused(&deps.querier, &factory, &pair)? {
+                return Err(ContractError::PairDisabled {
+                    hop,
+                    offer_asset: offer_asset_info.to_string(),
+                    ask_asset: ask_asset_info.to_string(),
+                    pair,
+                });
+            }
+
+            Ok(pair)
+        })
+        .collect()
+}
+
+/// Returns the offered coin if the route can be executed by the native fast path,
+/// i.e., every hop swaps native coins and only the first offer denom is attached to the message.
+pub fn native_route_offer(operations: &[SwapOperation], funds: &[Coin]) -> Option<Coin> {
+    let all_native = operations.iter().all(|op| {
+        matches!(op, SwapOperation::AstroSwap { offer_asset_info, ask_asset_info, .. }
+            if offer_asset_info.is_native_token() && ask_asset_info.is_native_token())
+    });
+
+    match (operations.first(), funds) {
+        (
+            Some(SwapOperation::AstroSwap {
+                offer_asset_info: AssetInfo::NativeToken { denom },
+                ..
+            }),
+            [coin],
+        ) if all_native && coin.denom == *denom && !coin.amount.is_zero() => Some(coin.clone()),
+        _ => None,
+    }
+}
+
+/// Creates the swap message for the current hop of a native route. The offered coins are sent
+/// along with the message thus the router doesn't query its own balance.
+/// Intermediate hops return coins to the router while the last one pays the route recipient.
+/// The receiver's ask coin balance is saved to the route to compute the hop output in the reply.
+///
+/// * **amount** amount of coins offered to the pair.
+pub fn native_hop_msg(
+    deps: Deps,
+    env: &Env,
+    route: &mut NativeRoute,
+    amount: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let SwapOperation::AstroSwap {
+        offer_asset_info,
+        ask_asset_info,
+        ..
+    } = &route.operations[route.hop]
+    else {
+        return Err(ContractError::NativeSwapNotSupported {});
+    };
+
+    let config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        config.astroport_factory,
+        &[offer_asset_info.clone(), ask_asset_info.clone()],
+    )?;
+
+    let is_last_hop = route.hop + 1 == route.operations.len();
+    let receiver = if is_last_hop {
+        &route.to
+    } else {
+        &env.contract.address
+    };
+    let prev_balance = ask_asset_info.query_pool(&deps.querier, receiver)?;
+
+    let msg = asset_into_swap_msg(
+        pair_info.contract_addr.to_string(),
+        offer_asset_info.with_balance(amount),
+        ask_asset_info.clone(),
+        route.max_spread,
+        is_last_hop.then(|| route.to.to_string()),
+        route.operations.len() == 1,
+    )?;
+    route.prev_balance = prev_balance;
+
+    Ok(SubMsg::reply_on_success(msg, NATIVE_HOP_REPLY_ID))
 }
 
 /// Creates a message of type [`CosmosMsg`] representing a swap operation.
@@ -111,6 +252,8 @@ pub fn asset_into_swap_msg(
                 belief_price,
                 max_spread,
                 to,
+                deadline: None,
+                to_commitment: None,
             })?,
         })),
         AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -124,6 +267,8 @@ pub fn asset_into_swap_msg(
                     belief_price,
                     max_spread,
                     to,
+                    deadline: None,
+                    to_commitment: None,
                 })?,
             })?,
         })),
@@ -1,7 +1,9 @@
 use astroport::asset::AssetInfo;
+use astroport::route::Route;
+use astroport::router::SwapOperation;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -13,6 +15,13 @@ pub struct Config {
     pub astroport_factory: Addr,
 }
 
+/// Canonical routes registered by governance keyed by (offer asset, ask asset)
+pub const ROUTES: Map<(&AssetInfo, &AssetInfo), Route> = Map::new("routes");
+
+/// Settings for pagination
+pub(crate) const MAX_LIMIT: u32 = 30;
+pub(crate) const DEFAULT_LIMIT: u32 = 10;
+
 pub const REPLY_DATA: Item<ReplyData> = Item::new("reply_data");
 
 #[cw_serde]
@@ -22,3 +31,33 @@ pub struct ReplyData {
     pub minimum_receive: Option<Uint128>,
     pub receiver: String,
 }
+
+/// Receiver balance before a hop with a minimum receive amount
+pub const HOP_REPLY_DATA: Item<HopReplyData> = Item::new("hop_reply_data");
+
+#[cw_serde]
+pub struct HopReplyData {
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+    pub prev_balance: Uint128,
+    pub minimum_receive: Uint128,
+    pub receiver: Addr,
+}
+
+/// Progress of a route executed by the native fast path
+pub const NATIVE_ROUTE: Item<NativeRoute> = Item::new("native_route");
+
+/// This structure holds a native-only route which is swapped hop by hop in replies
+#[cw_serde]
+pub struct NativeRoute {
+    /// All swap operations of the route
+    pub operations: Vec<SwapOperation>,
+    /// Index of the operation being executed
+    pub hop: usize,
+    /// Recipient of the ask coins of the last hop
+    pub to: Addr,
+    pub max_spread: Option<Decimal>,
+    pub minimum_receive: Option<Uint128>,
+    /// Ask coin balance of the current hop receiver before the hop
+    pub prev_balance: Uint128,
+}
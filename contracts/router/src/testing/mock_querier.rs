@@ -127,6 +127,7 @@ impl WasmMockQuerier {
                     self.handle_default(&msg)
                 }
             }
+            QueryRequest::Wasm(WasmQuery::Raw { key, .. }) => self.handle_factory_raw(key),
             _ => self.base.handle_query(request),
         }
     }
@@ -134,11 +135,14 @@ impl WasmMockQuerier {
     fn handle_default(&self, msg: &Binary) -> QuerierResult {
         match from_json(&msg).unwrap() {
             QueryMsg::Pair { asset_infos } => {
+                // The factory doesn't depend on the assets order
                 let key = asset_infos[0].to_string() + asset_infos[1].to_string().as_str();
-                match self.astroport_factory_querier.pairs.get(&key) {
+                let reversed_key = asset_infos[1].to_string() + asset_infos[0].to_string().as_str();
+                let pairs = &self.astroport_factory_querier.pairs;
+                match pairs.get(&key).or_else(|| pairs.get(&reversed_key)) {
                     Some(v) => SystemResult::Ok(ContractResult::from(to_json_binary(&PairInfo {
                         contract_addr: Addr::unchecked(v),
-                        liquidity_token: Addr::unchecked("liquidity"),
+                        liquidity_token: "liquidity".to_string(),
                         asset_infos: vec![
                             AssetInfo::NativeToken {
                                 denom: "uusd".to_string(),
@@ -165,6 +169,11 @@ impl WasmMockQuerier {
         }
     }
 
+    /// Serves raw queries of the factory pause state. Pairs are never paused.
+    fn handle_factory_raw(&self, _key: &Binary) -> QuerierResult {
+        SystemResult::Ok(ContractResult::Ok(Binary::default()))
+    }
+
     fn handle_cw20(&self, contract_addr: &String, msg: &Binary) -> QuerierResult {
         match from_json(&msg).unwrap() {
             Cw20QueryMsg::TokenInfo {} => {
@@ -1,8 +1,11 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_json, to_json_binary, Addr, Coin, ReplyOn, SubMsg, Uint128, WasmMsg};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Coin, ReplyOn, StdError, SubMsg, Uint128, WasmMsg,
+};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 use astroport::asset::{native_asset_info, AssetInfo};
+use astroport::pair::RecipientCommitment;
 use astroport::router::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
     SimulateSwapOperationsResponse, SwapOperation, MAX_SWAP_OPERATIONS,
@@ -45,11 +48,18 @@ fn execute_swap_operations() {
     // We can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
+    deps.querier.with_astroport_pairs(&[
+        (&"ukrwasset0001".to_string(), &String::from("pair0000")),
+        (&"asset0001uluna".to_string(), &String::from("pair0001")),
+        (&"ulunaasset0002".to_string(), &String::from("pair0002")),
+    ]);
+
     let msg = ExecuteMsg::ExecuteSwapOperations {
         operations: vec![],
         minimum_receive: None,
         to: None,
         max_spread: None,
+        to_commitment: None,
     };
 
     let env = mock_env();
@@ -66,6 +76,7 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: Addr::unchecked("asset0001"),
                 },
+                minimum_receive: None,
             },
             SwapOperation::AstroSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -74,6 +85,7 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                minimum_receive: None,
             },
             SwapOperation::AstroSwap {
                 offer_asset_info: AssetInfo::NativeToken {
@@ -82,11 +94,13 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: Addr::unchecked("asset0002"),
                 },
+                minimum_receive: None,
             },
         ],
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
         max_spread: None,
+        to_commitment: None,
     };
 
     let env = mock_env();
@@ -107,6 +121,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::Token {
                                 contract_addr: Addr::unchecked("asset0001"),
                             },
+                            minimum_receive: None,
                         },
                         to: None,
                         max_spread: None,
@@ -131,6 +146,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
                             },
+                            minimum_receive: None,
                         },
                         to: None,
                         max_spread: None,
@@ -155,6 +171,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::Token {
                                 contract_addr: Addr::unchecked("asset0002"),
                             },
+                            minimum_receive: None,
                         },
                         to: Some(String::from("addr0000")),
                         max_spread: None,
@@ -182,6 +199,7 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: Addr::unchecked("asset0001"),
                     },
+                    minimum_receive: None,
                 },
                 SwapOperation::AstroSwap {
                     offer_asset_info: AssetInfo::Token {
@@ -190,6 +208,7 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
+                    minimum_receive: None,
                 },
                 SwapOperation::AstroSwap {
                     offer_asset_info: AssetInfo::NativeToken {
@@ -198,11 +217,13 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: Addr::unchecked("asset0002"),
                     },
+                    minimum_receive: None,
                 },
             ],
             minimum_receive: None,
             to: Some(String::from("addr0002")),
             max_spread: None,
+            to_commitment: None,
         })
         .unwrap(),
     });
@@ -225,6 +246,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::Token {
                                 contract_addr: Addr::unchecked("asset0001"),
                             },
+                            minimum_receive: None,
                         },
                         to: None,
                         max_spread: None,
@@ -249,6 +271,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
                             },
+                            minimum_receive: None,
                         },
                         to: None,
                         max_spread: None,
@@ -273,6 +296,7 @@ fn execute_swap_operations() {
                             ask_asset_info: AssetInfo::Token {
                                 contract_addr: Addr::unchecked("asset0002"),
                             },
+                            minimum_receive: None,
                         },
                         to: Some(String::from("addr0002")),
                         max_spread: None,
@@ -289,6 +313,63 @@ fn execute_swap_operations() {
     );
 }
 
+#[test]
+fn execute_swap_operations_with_recipient_commitment() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        astroport_factory: String::from("astroportfactory"),
+    };
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: native_asset_info("ukrw".to_string()),
+        ask_asset_info: native_asset_info("uluna".to_string()),
+        minimum_receive: None,
+    }];
+    let to_commitment = RecipientCommitment {
+        hash: Binary::from([1u8; 32]),
+        salt: Binary::from(b"salt".to_vec()),
+    };
+
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        to_commitment: Some(to_commitment.clone()),
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Swap recipient must be set when the recipient commitment is used"
+        ))
+    );
+
+    // A relayer can't redirect the output
+    let msg = Cw20HookMsg::ExecuteSwapOperations {
+        operations,
+        minimum_receive: None,
+        to: Some("relayer".to_string()),
+        max_spread: None,
+        to_commitment: Some(to_commitment),
+    };
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_json_binary(&msg).unwrap(),
+    });
+    let err = execute(deps.as_mut(), mock_env(), mock_info("asset0000", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "Swap recipient doesn't match the commitment"
+        ))
+    );
+}
+
 #[test]
 fn execute_swap_operation() {
     let mut deps = mock_dependencies(&[]);
@@ -329,6 +410,7 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
+            minimum_receive: None,
         },
         to: Some(String::from("addr0000")),
         max_spread: None,
@@ -351,6 +433,8 @@ fn execute_swap_operation() {
                         belief_price: None,
                         max_spread: None,
                         to: Some(String::from("addr0000")),
+                        deadline: None,
+                        to_commitment: None,
                     })
                     .unwrap()
                 })
@@ -362,6 +446,23 @@ fn execute_swap_operation() {
             reply_on: ReplyOn::Never,
         }]
     );
+
+    // Unset optional fields are omitted thus pairs deployed before they existed accept the message
+    assert_eq!(
+        to_json_binary(&astroport::pair::Cw20HookMsg::Swap {
+            ask_asset_info: Some(native_asset_info("uusd".to_string())),
+            belief_price: None,
+            max_spread: None,
+            to: Some(String::from("addr0000")),
+            deadline: None,
+            to_commitment: None,
+        })
+        .unwrap(),
+        Binary::from(
+            br#"{"swap":{"ask_asset_info":{"native_token":{"denom":"uusd"}},"belief_price":null,"max_spread":null,"to":"addr0000"}}"#
+                .as_slice()
+        )
+    );
 }
 
 #[test]
@@ -388,6 +489,7 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: Addr::unchecked("asset0000"),
                 },
+                minimum_receive: None,
             },
             SwapOperation::AstroSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -396,6 +498,7 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                minimum_receive: None,
             },
         ],
     };
@@ -455,6 +558,7 @@ fn assert_maximum_receive_swap_operations() {
         minimum_receive: None,
         to: None,
         max_spread: None,
+        to_commitment: None,
     };
 
     let env = mock_env();
@@ -1,13 +1,17 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_std::{coins, from_json, to_json_binary, Addr, Empty, StdError};
+use cosmwasm_std::{coins, from_json, to_json_binary, Addr, Empty, StdError, Uint128};
 use cw20::Cw20ExecuteMsg;
 
 use astroport::asset::{native_asset_info, token_asset_info};
 use astroport::factory::PairType;
-use astroport::router::{ExecuteMsg, InstantiateMsg, SwapOperation, SwapResponseData};
+use astroport::route::Route;
+use astroport::router::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SimulateSwapOperationsResponse, SwapOperation,
+    SwapResponseData,
+};
 use astroport_router::error::ContractError;
-use astroport_test::cw_multi_test::{AppBuilder, Contract, ContractWrapper, Executor};
+use astroport_test::cw_multi_test::{AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 use astroport_test::modules::stargate::{MockStargate, StargateApp as App};
 
 use crate::factory_helper::{instantiate_token, mint, mint_native, FactoryHelper};
@@ -87,15 +91,18 @@ fn router_does_not_enforce_spread_assertion() {
                         SwapOperation::AstroSwap {
                             offer_asset_info: token_asset_info(token_x.clone()),
                             ask_asset_info: token_asset_info(token_y.clone()),
+                            minimum_receive: None,
                         },
                         SwapOperation::AstroSwap {
                             offer_asset_info: token_asset_info(token_y.clone()),
                             ask_asset_info: token_asset_info(token_z.clone()),
+                            minimum_receive: None,
                         },
                     ],
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    to_commitment: None,
                 })
                 .unwrap(),
             },
@@ -122,10 +129,12 @@ fn router_does_not_enforce_spread_assertion() {
                     operations: vec![SwapOperation::AstroSwap {
                         offer_asset_info: token_asset_info(token_x.clone()),
                         ask_asset_info: token_asset_info(token_y.clone()),
+                        minimum_receive: None,
                     }],
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    to_commitment: None,
                 })
                 .unwrap(),
             },
@@ -138,6 +147,89 @@ fn router_does_not_enforce_spread_assertion() {
     )
 }
 
+#[test]
+fn hop_minimum_receive_checks_received_amount() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token_x = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOX", None);
+    let token_y = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOY", None);
+    let token_z = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOZ", None);
+
+    for (a, b) in [(&token_x, &token_y), (&token_y, &token_z)] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [token_asset_info(a.clone()), token_asset_info(b.clone())],
+                None,
+            )
+            .unwrap();
+        mint(&mut app, &owner, a, 100_000_000000, &pair).unwrap();
+        mint(&mut app, &owner, b, 100_000_000000, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let swap_msg = |hop_minimum_receive: u128| Cw20ExecuteMsg::Send {
+        contract: router.to_string(),
+        amount: 1_000000u128.into(),
+        msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperations {
+            operations: vec![
+                SwapOperation::AstroSwap {
+                    offer_asset_info: token_asset_info(token_x.clone()),
+                    ask_asset_info: token_asset_info(token_y.clone()),
+                    minimum_receive: Some(hop_minimum_receive.into()),
+                },
+                SwapOperation::AstroSwap {
+                    offer_asset_info: token_asset_info(token_y.clone()),
+                    ask_asset_info: token_asset_info(token_z.clone()),
+                    minimum_receive: None,
+                },
+            ],
+            minimum_receive: None,
+            to: None,
+            max_spread: None,
+            to_commitment: None,
+        })
+        .unwrap(),
+    };
+
+    // The router already holds 1 TOY, but only the amount received by the hop counts.
+    // 1 TOX is swapped to slightly less than 1 TOY due to the commission and the price impact
+    mint(&mut app, &owner, &token_y, 1_000000, &router).unwrap();
+    mint(&mut app, &owner, &token_x, 2_000000, &owner).unwrap();
+    let err = app
+        .execute_contract(owner.clone(), token_x.clone(), &swap_msg(1_000000), &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssertionHopMinimumReceive { offer_asset, ask_asset, receive, amount }
+            if offer_asset == token_x.as_str()
+                && ask_asset == token_y.as_str()
+                && receive.u128() == 1_000000
+                && amount.u128() < 1_000000
+    ));
+
+    app.execute_contract(owner.clone(), token_x.clone(), &swap_msg(990_000), &[])
+        .unwrap();
+}
+
 #[test]
 fn route_through_pairs_with_natives() {
     let mut app = mock_app();
@@ -193,6 +285,7 @@ fn route_through_pairs_with_natives() {
                 operation: SwapOperation::AstroSwap {
                     offer_asset_info: native_asset_info(denom_x.to_string()),
                     ask_asset_info: native_asset_info(denom_y.to_string()),
+                    minimum_receive: None,
                 },
                 to: None,
                 max_spread: None,
@@ -216,6 +309,7 @@ fn route_through_pairs_with_natives() {
                 }],
                 to: None,
                 max_spread: None,
+                to_commitment: None,
                 minimum_receive: None,
             },
             &[],
@@ -234,9 +328,11 @@ fn route_through_pairs_with_natives() {
                 operations: vec![SwapOperation::AstroSwap {
                     offer_asset_info: native_asset_info(denom_x.to_string()),
                     ask_asset_info: native_asset_info(denom_x.to_string()),
+                    minimum_receive: None,
                 }],
                 to: None,
                 max_spread: None,
+                to_commitment: None,
                 minimum_receive: None,
             },
             &[],
@@ -245,6 +341,7 @@ fn route_through_pairs_with_natives() {
     assert_eq!(
         err.downcast::<ContractError>().unwrap(),
         ContractError::DoublingAssetsPath {
+            hop: 0,
             offer_asset: denom_x.to_string(),
             ask_asset: denom_x.to_string()
         }
@@ -262,15 +359,18 @@ fn route_through_pairs_with_natives() {
                     SwapOperation::AstroSwap {
                         offer_asset_info: native_asset_info(denom_x.to_string()),
                         ask_asset_info: native_asset_info(denom_y.to_string()),
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: native_asset_info(denom_y.to_string()),
                         ask_asset_info: native_asset_info(denom_z.to_string()),
+                        minimum_receive: None,
                     },
                 ],
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -284,21 +384,24 @@ fn route_through_pairs_with_natives() {
     let err = app
         .execute_contract(
             owner.clone(),
-            router,
+            router.clone(),
             &ExecuteMsg::ExecuteSwapOperations {
                 operations: vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: native_asset_info(denom_x.to_string()),
                         ask_asset_info: native_asset_info(denom_y.to_string()),
+                        minimum_receive: None,
                     },
                     SwapOperation::AstroSwap {
                         offer_asset_info: native_asset_info(denom_y.to_string()),
                         ask_asset_info: native_asset_info(denom_z.to_string()),
+                        minimum_receive: None,
                     },
                 ],
                 minimum_receive: Some(50_000_000000u128.into()), // <--- enforcing minimum receive with 1:1 rate (which practically impossible)
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -311,6 +414,551 @@ fn route_through_pairs_with_natives() {
             amount: 15_360_983102u128.into()
         }
     );
+
+    // Enforcing minimum receive on the intermediate hop fails the route before the last hop
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router,
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: native_asset_info(denom_x.to_string()),
+                        ask_asset_info: native_asset_info(denom_y.to_string()),
+                        minimum_receive: Some(50_000_000000u128.into()),
+                    },
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: native_asset_info(denom_y.to_string()),
+                        ask_asset_info: native_asset_info(denom_z.to_string()),
+                        minimum_receive: None,
+                    },
+                ],
+                minimum_receive: None,
+                to: None,
+                max_spread: None,
+                to_commitment: None,
+            },
+            &coins(50_000_000000, denom_x),
+        )
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssertionHopMinimumReceive { offer_asset, ask_asset, receive, .. }
+            if offer_asset == denom_x && ask_asset == denom_y && receive.u128() == 50_000_000000
+    ));
+}
+
+#[test]
+fn native_route_fast_path() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+    let denom_z = "denom_z";
+
+    for (a, b, typ) in [
+        (&denom_x, &denom_y, PairType::Xyk {}),
+        (&denom_y, &denom_z, PairType::Stable {}),
+    ] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                typ,
+                [
+                    native_asset_info(a.to_string()),
+                    native_asset_info(b.to_string()),
+                ],
+                None,
+            )
+            .unwrap();
+        mint_native(&mut app, a, 1_000_000_000000, &pair).unwrap();
+        mint_native(&mut app, b, 1_000_000_000000, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info(denom_x.to_string()),
+            ask_asset_info: native_asset_info(denom_y.to_string()),
+            minimum_receive: None,
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info(denom_y.to_string()),
+            ask_asset_info: native_asset_info(denom_z.to_string()),
+            minimum_receive: None,
+        },
+    ];
+    let swap_msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        to_commitment: None,
+    };
+    let simulate = |app: &App| {
+        app.wrap()
+            .query_wasm_smart::<SimulateSwapOperationsResponse>(
+                &router,
+                &QueryMsg::SimulateSwapOperations {
+                    offer_amount: 1_000000u128.into(),
+                    operations: operations.clone(),
+                },
+            )
+            .unwrap()
+            .amount
+    };
+    // cw-multi-test doesn't meter gas thus the number of contract executions is compared instead
+    let executions = |resp: &AppResponse| resp.events.iter().filter(|e| e.ty == "execute").count();
+
+    // Coins attached to the message take the fast path
+    mint_native(&mut app, &denom_x, 1_000000, &owner).unwrap();
+    let expected = simulate(&app);
+    let resp = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg,
+            &coins(1_000000, denom_x),
+        )
+        .unwrap();
+    let resp_data: SwapResponseData = from_json(&resp.data.clone().unwrap()).unwrap();
+    assert_eq!(resp_data.return_amount, expected);
+    // The router and two pairs
+    assert_eq!(executions(&resp), 3);
+    let fast_path_received = app.wrap().query_balance(&owner, denom_z).unwrap().amount;
+    assert_eq!(fast_path_received, expected);
+
+    // Coins sent to the router beforehand are swapped via router self-calls and balance queries
+    mint_native(&mut app, &denom_x, 1_000000, &router).unwrap();
+    let expected = simulate(&app);
+    let resp = app
+        .execute_contract(owner.clone(), router.clone(), &swap_msg, &[])
+        .unwrap();
+    let resp_data: SwapResponseData = from_json(&resp.data.clone().unwrap()).unwrap();
+    assert_eq!(resp_data.return_amount, expected);
+    // The router, two self-calls and two pairs
+    assert_eq!(executions(&resp), 5);
+
+    // Intermediate coins held by the router aren't counted as the hop output
+    mint_native(&mut app, &denom_y, 1_000000, &router).unwrap();
+    mint_native(&mut app, &denom_x, 1_000000, &owner).unwrap();
+    let expected = simulate(&app);
+    let resp = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg,
+            &coins(1_000000, denom_x),
+        )
+        .unwrap();
+    let resp_data: SwapResponseData = from_json(&resp.data.clone().unwrap()).unwrap();
+    assert_eq!(resp_data.return_amount, expected);
+    let router_balance = app.wrap().query_balance(&router, denom_y).unwrap().amount;
+    assert_eq!(router_balance.u128(), 1_000000);
+
+    // Hop minimum receive is checked against the receiver balance change
+    mint_native(&mut app, &denom_x, 1_000000, &owner).unwrap();
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: native_asset_info(denom_x.to_string()),
+                        ask_asset_info: native_asset_info(denom_y.to_string()),
+                        minimum_receive: Some(1_000000u128.into()),
+                    },
+                    operations[1].clone(),
+                ],
+                minimum_receive: None,
+                to: None,
+                max_spread: None,
+                to_commitment: None,
+            },
+            &coins(1_000000, denom_x),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssertionHopMinimumReceive { offer_asset, ask_asset, receive, .. }
+            if offer_asset == denom_x && ask_asset == denom_y && receive.u128() == 1_000000
+    ));
+
+    let expected = simulate(&app);
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router,
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: operations.clone(),
+                minimum_receive: Some(expected + Uint128::one()),
+                to: None,
+                max_spread: None,
+                to_commitment: None,
+            },
+            &coins(1_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AssertionMinimumReceive {
+            receive: expected + Uint128::one(),
+            amount: expected,
+        }
+    );
+}
+
+#[test]
+fn swap_by_registered_route() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+    let denom_z = "denom_z";
+
+    for (a, b, typ, liq) in [
+        (&denom_x, &denom_y, PairType::Xyk {}, 100_000_000000),
+        (&denom_y, &denom_z, PairType::Stable {}, 1_000_000_000000),
+    ] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                typ,
+                [
+                    native_asset_info(a.to_string()),
+                    native_asset_info(b.to_string()),
+                ],
+                None,
+            )
+            .unwrap();
+        mint_native(&mut app, a, liq, &pair).unwrap();
+        mint_native(&mut app, b, liq, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let route = Route::from_assets(vec![
+        native_asset_info(denom_x.to_string()),
+        native_asset_info(denom_y.to_string()),
+        native_asset_info(denom_z.to_string()),
+    ]);
+    let swap_msg = ExecuteMsg::SwapByRegisteredRoute {
+        offer_asset_info: native_asset_info(denom_x.to_string()),
+        ask_asset_info: native_asset_info(denom_z.to_string()),
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        to_commitment: None,
+    };
+
+    mint_native(&mut app, &denom_x, 50_000_000000, &owner).unwrap();
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg,
+            &coins(50_000_000000, denom_x),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RouteNotFound {
+            offer_asset: denom_x.to_string(),
+            ask_asset: denom_z.to_string()
+        }
+    );
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("random"),
+            router.clone(),
+            &ExecuteMsg::RegisterRoute {
+                route: route.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let mut pinned = route.clone();
+    pinned.hops[0].pair = Some("pair".to_string());
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &ExecuteMsg::RegisterRoute { route: pinned },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PinnedPairNotSupported {}
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        router.clone(),
+        &ExecuteMsg::RegisterRoute {
+            route: route.clone(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let routes: Vec<Route> = app
+        .wrap()
+        .query_wasm_smart(
+            &router,
+            &QueryMsg::RegisteredRoutes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(routes, vec![route.clone()]);
+
+    let routes: Vec<Route> = app
+        .wrap()
+        .query_wasm_smart(
+            &router,
+            &QueryMsg::RegisteredRoutes {
+                start_after: Some((
+                    native_asset_info(denom_x.to_string()),
+                    native_asset_info(denom_z.to_string()),
+                )),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(routes, vec![]);
+
+    // Same result as swapping through the explicit operations
+    let resp = app
+        .execute_contract(
+            owner.clone(),
+            router.clone(),
+            &swap_msg,
+            &coins(50_000_000000, denom_x),
+        )
+        .unwrap();
+    let resp_data: SwapResponseData = from_json(&resp.data.unwrap()).unwrap();
+    assert_eq!(resp_data.return_amount.u128(), 32_258_064515);
+
+    app.execute_contract(
+        owner.clone(),
+        router.clone(),
+        &ExecuteMsg::DeregisterRoute {
+            offer_asset_info: native_asset_info(denom_x.to_string()),
+            ask_asset_info: native_asset_info(denom_z.to_string()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Route>(
+            &router,
+            &QueryMsg::RegisteredRoute {
+                offer_asset_info: native_asset_info(denom_x.to_string()),
+                ask_asset_info: native_asset_info(denom_z.to_string()),
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("No route is registered"));
+}
+
+#[test]
+fn refuses_disabled_and_deregistered_pairs() {
+    let mut app = mock_app();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let denom_x = "denom_x";
+    let denom_y = "denom_y";
+    let denom_z = "denom_z";
+
+    let mut pairs = vec![];
+    for (a, b) in [(&denom_x, &denom_y), (&denom_y, &denom_z)] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [
+                    native_asset_info(a.to_string()),
+                    native_asset_info(b.to_string()),
+                ],
+                None,
+            )
+            .unwrap();
+        mint_native(&mut app, a, 1_000_000_000000, &pair).unwrap();
+        mint_native(&mut app, b, 1_000_000_000000, &pair).unwrap();
+        pairs.push(pair);
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info(denom_x.to_string()),
+            ask_asset_info: native_asset_info(denom_y.to_string()),
+            minimum_receive: None,
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info(denom_y.to_string()),
+            ask_asset_info: native_asset_info(denom_z.to_string()),
+            minimum_receive: None,
+        },
+    ];
+    let validate_route = |app: &App| {
+        app.wrap().query_wasm_smart::<Route>(
+            &router,
+            &QueryMsg::ValidateRoute {
+                operations: operations.clone(),
+            },
+        )
+    };
+    let swap = |app: &mut App| {
+        mint_native(app, denom_x, 1_000000, &owner).unwrap();
+        app.execute_contract(
+            owner.clone(),
+            router.clone(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: operations.clone(),
+                minimum_receive: None,
+                to: None,
+                max_spread: None,
+                to_commitment: None,
+            },
+            &coins(1_000000, denom_x),
+        )
+    };
+    let update_pause_state = |app: &mut App, all_pairs, pause, unpause| {
+        app.execute_contract(
+            owner.clone(),
+            helper.factory.clone(),
+            &astroport::factory::ExecuteMsg::UpdatePauseState {
+                pair_creation: None,
+                all_pairs,
+                pause,
+                unpause,
+            },
+            &[],
+        )
+        .unwrap();
+    };
+
+    // Every hop is pinned to its factory pair
+    let route = validate_route(&app).unwrap();
+    assert_eq!(
+        route
+            .hops
+            .iter()
+            .map(|hop| hop.pair.clone().unwrap())
+            .collect::<Vec<_>>(),
+        vec![pairs[0].to_string(), pairs[1].to_string()]
+    );
+    swap(&mut app).unwrap();
+
+    update_pause_state(&mut app, None, Some(vec![pairs[1].to_string()]), None);
+    let expected_err = ContractError::PairDisabled {
+        hop: 1,
+        offer_asset: denom_y.to_string(),
+        ask_asset: denom_z.to_string(),
+        pair: pairs[1].clone(),
+    };
+    let err = validate_route(&app).unwrap_err();
+    assert!(err.to_string().contains(&expected_err.to_string()));
+    let err = swap(&mut app).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), expected_err);
+
+    update_pause_state(&mut app, Some(true), None, Some(vec![pairs[1].to_string()]));
+    let err = swap(&mut app).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PairDisabled {
+            hop: 0,
+            offer_asset: denom_x.to_string(),
+            ask_asset: denom_y.to_string(),
+            pair: pairs[0].clone(),
+        }
+    );
+
+    update_pause_state(&mut app, Some(false), None, None);
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &astroport::factory::ExecuteMsg::Deregister {
+            asset_infos: vec![
+                native_asset_info(denom_x.to_string()),
+                native_asset_info(denom_y.to_string()),
+            ],
+        },
+        &[],
+    )
+    .unwrap();
+
+    let expected_err = ContractError::PairNotRegistered {
+        hop: 0,
+        offer_asset: denom_x.to_string(),
+        ask_asset: denom_y.to_string(),
+    };
+    let err = validate_route(&app).unwrap_err();
+    assert!(err.to_string().contains(&expected_err.to_string()));
+    let err = swap(&mut app).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), expected_err);
 }
 
 #[test]
@@ -374,6 +1022,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: inj.clone(),
             },
+            minimum_receive: None,
         },
         SwapOperation::AstroSwap {
             offer_asset_info: AssetInfo::Token {
@@ -382,6 +1031,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: osmo.clone(),
             },
+            minimum_receive: None,
         },
     ];
 
@@ -413,6 +1063,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: inj.clone(),
             },
+            minimum_receive: None,
         },
         SwapOperation::AstroSwap {
             offer_asset_info: AssetInfo::Token {
@@ -421,6 +1072,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: osmo.clone(),
             },
+            minimum_receive: None,
         },
         SwapOperation::AstroSwap {
             offer_asset_info: AssetInfo::Token {
@@ -429,6 +1081,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: atom.clone(),
             },
+            minimum_receive: None,
         },
         SwapOperation::AstroSwap {
             offer_asset_info: AssetInfo::Token {
@@ -437,6 +1090,7 @@ fn test_swap_route() {
             ask_asset_info: AssetInfo::Token {
                 contract_addr: osmo.clone(),
             },
+            minimum_receive: None,
         },
     ];
 
@@ -485,6 +1139,7 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             })
             .unwrap(),
         },
@@ -572,6 +1227,7 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             })
             .unwrap(),
         },
@@ -660,10 +1316,12 @@ fn test_swap_route() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: atom.clone(),
                     },
+                    minimum_receive: None,
                 }],
                 minimum_receive: Some(Uint128::new(9_997_000)),
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             },
             &[],
         )
@@ -799,6 +1457,7 @@ fn test_swap_route() {
                 minimum_receive: Some(donated_atom),
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             })
             .unwrap(),
         },
@@ -853,10 +1512,12 @@ fn test_swap_route() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: atom.clone(),
                     },
+                    minimum_receive: None,
                 }],
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                to_commitment: None,
             },
             &[],
         )
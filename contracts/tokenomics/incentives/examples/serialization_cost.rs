@@ -17,6 +17,8 @@ fn main() {
         rps: Default::default(),
         index: Default::default(),
         orphaned: Default::default(),
+        rpb: Default::default(),
+        next_update_height: Default::default(),
     };
     let pool_info = PoolInfo::default();
 
@@ -5,7 +5,7 @@ use cw_utils::PaymentError;
 use thiserror::Error;
 
 use astroport::factory::PairType;
-use astroport::incentives::MAX_REWARD_TOKENS;
+use astroport::incentives::{MAX_REWARD_TOKENS, MAX_STAKING_HOOKS};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -30,6 +30,9 @@ pub enum ContractError {
     #[error("Duplicated pool found")]
     DuplicatedPoolFound {},
 
+    #[error("ASTRO staking contract is not set")]
+    XastroStakingNotSet {},
+
     #[error("Amount to withdraw {withdraw_amount} exceeds balance {available}")]
     AmountExceedsBalance {
         available: Uint128,
@@ -72,4 +75,71 @@ pub enum ContractError {
 
     #[error("Sent insufficient reward {reward} for pool {lp_token}")]
     InsuffiicientRewardToken { reward: String, lp_token: String },
+
+    #[error("Rewards in the pool's own LP token {lp_token} are compounded and can't vest")]
+    CompoundingRewardVesting { lp_token: String },
+
+    #[error("Reward {reward} in pool {lp_token} vests over {expected} seconds")]
+    VestingDurationMismatch {
+        lp_token: String,
+        reward: String,
+        expected: u64,
+    },
+
+    #[error("No vested rewards to claim")]
+    NoVestedRewards {},
+
+    #[error("Pool {lp_token} doesn't receive ASTRO emissions")]
+    PoolIsNotActive { lp_token: String },
+
+    #[error("ASTRO emissions of pool {lp_token} are not scaled")]
+    EmissionsNotScaled { lp_token: String },
+
+    #[error("Pool {lp_token} is paused")]
+    PoolPaused { lp_token: String },
+
+    #[error("Pool {lp_token} is not paused")]
+    PoolNotPaused { lp_token: String },
+
+    #[error("Reward schedule cancellation is disabled")]
+    ScheduleCancellationDisabled {},
+
+    #[error("No cancellable {reward} schedules funded by the sender in pool {lp_token}")]
+    NothingToCancel { lp_token: String, reward: String },
+
+    #[error("Pool {lp_token} doesn't have active time based {reward} schedules")]
+    NoActiveSchedule { lp_token: String, reward: String },
+
+    #[error("Pooled schedule {schedule_id} not found")]
+    ScheduleNotFound { schedule_id: u64 },
+
+    #[error("Pooled schedule {schedule_id} has a different pool, reward or duration")]
+    ScheduleMismatch { schedule_id: u64 },
+
+    #[error("Funding window of pooled schedule {schedule_id} is closed")]
+    ScheduleFundingClosed { schedule_id: u64 },
+
+    #[error("Staker can't be their own referrer")]
+    SelfReferral {},
+
+    #[error("Withdrawal requires burning {expected} stake receipts, but {actual} were sent")]
+    InvalidStakeReceipts { expected: Uint128, actual: Uint128 },
+
+    #[error("{denom} is not a stake receipt denom")]
+    UnknownReceiptDenom { denom: String },
+
+    #[error("Too many staking hooks in pool {lp_token}. Maximum allowed is {MAX_STAKING_HOOKS}")]
+    TooManyStakingHooks { lp_token: String },
+
+    #[error("{operator} is not approved to claim rewards on behalf of {user}")]
+    ClaimOperatorNotApproved { user: String, operator: String },
+
+    #[error("Claim operator approval expiry {expires_at} is in the past")]
+    ClaimOperatorExpiryInPast { expires_at: u64 },
+
+    #[error("Stakers index is already built")]
+    StakersIndexBuilt {},
+
+    #[error("Stakers index is not built yet. Execute BuildStakersIndex to continue indexing")]
+    StakersIndexNotBuilt {},
 }
@@ -3,28 +3,42 @@ use std::collections::HashSet;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, ensure, from_json, Addr, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-    Uint128,
+    attr, coin, coins, ensure, from_json, Addr, BankMsg, CosmosMsg, Decimal, Decimal256, Deps,
+    DepsMut, Env, MessageInfo, Order, ReplyOn, Response, StdError, StdResult, Uint128,
 };
-use cw_utils::one_coin;
+use cw_storage_plus::PrefixBound;
+use cw_utils::{may_pay, one_coin};
 use itertools::Itertools;
 
 use astroport::asset::{
     addr_opt_validate, determine_asset_info, validate_native_denom, Asset, AssetInfo, AssetInfoExt,
 };
-use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::common::{
+    claim_ownership, drop_ownership_proposal, propose_new_owner, update_ownership_timelock,
+};
 use astroport::factory;
 use astroport::factory::PairType;
-use astroport::incentives::{Cw20Msg, ExecuteMsg, IncentivizationFeeInfo, RewardType};
+use astroport::incentives::{
+    ClaimOperator, Cw20Msg, EmissionScale, ExecuteMsg, IncentivizationFeeInfo, RewardMetadata,
+    RewardTokenMetadata, RewardType, ScheduleCancellationConfig, StakingHookMsg, EPOCHS_START,
+    EPOCH_LENGTH, MAX_POOL_UPDATES_LIMIT, MAX_STAKERS_INDEX_LIMIT, MAX_STAKING_HOOKS,
+};
+use astroport::token_factory::tf_burn_msg;
 
 use crate::error::ContractError;
+use crate::reply::POST_TRANSFER_REPLY_ID;
 use crate::state::{
-    Op, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CONFIG, OWNERSHIP_PROPOSAL,
+    build_stakers_index, last_emission_checkpoint, save_emission_checkpoint, scaled_alloc_points,
+    Op, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CLAIM_OPERATORS, CONFIG,
+    CREATOR_SCHEDULES, EMISSION_SCALES, OWNERSHIP_PROPOSAL, PAUSED_POOLS, POOLED_SCHEDULES, POOLS,
+    POOLS_BY_CHECKPOINT, RECEIPT_DENOMS, RECEIVED_RECEIPTS, REFERRERS, REWARD_CREATORS,
+    REWARD_METADATA, REWARD_RECEIVERS, SCHEDULE_FUNDERS, STAKERS_INDEX_CURSOR, STAKING_HOOKS,
+    VESTING_REWARDS,
 };
 use crate::utils::{
     asset_info_key, claim_orphaned_rewards, claim_rewards, deactivate_blocked_pools,
-    deactivate_pool, incentivize_many, is_pool_registered, query_pair_info,
-    remove_reward_from_pool,
+    deactivate_pool, incentivize_many, is_pool_registered, mint_receipts_msgs, query_pair_info,
+    remove_reward_from_pool, staking_hook_msgs, top_up_schedule,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -36,46 +50,31 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SetupPools { pools } => setup_pools(deps, env, info, pools),
-        ExecuteMsg::ClaimRewards { lp_tokens } => {
-            // Check for duplicated pools
-            ensure!(
-                lp_tokens.iter().all_unique(),
-                ContractError::DuplicatedPoolFound {}
-            );
-
-            // Collect in-memory mutable objects
-            let mut tuples = lp_tokens
-                .into_iter()
-                .map(|lp_token| {
-                    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
-                    let pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
-                    let user_pos = UserInfo::load_position(deps.storage, &info.sender, &lp_asset)?;
-                    Ok((lp_asset, pool_info, user_pos))
-                })
-                .collect::<Result<Vec<_>, ContractError>>()?;
-
-            // Convert to mutable references
-            let mut_tuples = tuples
-                .iter_mut()
-                .map(|(lp_asset, pool_info, user_pos)| (&*lp_asset, pool_info, user_pos))
-                .collect_vec();
-
-            // Compose response. Return early in case of error
-            let response = claim_rewards(deps.storage, None, env, &info.sender, mut_tuples)?;
-
-            // Save updates in state
-            for (lp_asset, pool_info, user_pos) in tuples {
-                pool_info.save(deps.storage, &lp_asset)?;
-                user_pos.save(deps.storage, &info.sender, &lp_asset)?;
-            }
-
-            Ok(response)
+        ExecuteMsg::ClaimRewards {
+            lp_tokens,
+            on_behalf_of,
+        } => {
+            let user = match on_behalf_of {
+                Some(user) => check_claim_operator(deps.as_ref(), &env, &info.sender, user)?,
+                None => info.sender,
+            };
+            claim(deps, env, user, lp_tokens, false, false)
+        }
+        ExecuteMsg::ClaimIntoXastro { lp_tokens } => {
+            claim(deps, env, info.sender, lp_tokens, true, false)
+        }
+        ExecuteMsg::ClaimAndStakeLpRewards { lp_tokens } => {
+            claim(deps, env, info.sender, lp_tokens, false, true)
         }
+        ExecuteMsg::ClaimVested {} => claim_vested(deps, env, info),
         ExecuteMsg::Receive(cw20msg) => {
             let maybe_lp = Asset::cw20(info.sender, cw20msg.amount);
-            let recipient = match from_json(&cw20msg.msg)? {
-                Cw20Msg::Deposit { recipient } => recipient,
-                Cw20Msg::DepositFor(recipient) => Some(recipient),
+            let (recipient, referrer) = match from_json(&cw20msg.msg)? {
+                Cw20Msg::Deposit {
+                    recipient,
+                    referrer,
+                } => (recipient, referrer),
+                Cw20Msg::DepositFor(recipient) => (Some(recipient), None),
             };
 
             deposit(
@@ -84,13 +83,17 @@ pub fn execute(
                 maybe_lp,
                 Addr::unchecked(cw20msg.sender),
                 recipient,
+                referrer,
             )
         }
-        ExecuteMsg::Deposit { recipient } => {
+        ExecuteMsg::Deposit {
+            recipient,
+            referrer,
+        } => {
             let maybe_lp_coin = one_coin(&info)?;
             let maybe_lp = Asset::native(maybe_lp_coin.denom, maybe_lp_coin.amount);
 
-            deposit(deps, env, maybe_lp, info.sender, recipient)
+            deposit(deps, env, maybe_lp, info.sender, recipient, referrer)
         }
         ExecuteMsg::Withdraw { lp_token, amount } => withdraw(deps, env, info, lp_token, amount),
         ExecuteMsg::SetTokensPerSecond { amount } => set_tokens_per_second(deps, env, info, amount),
@@ -98,6 +101,9 @@ pub fn execute(
             incentivize_many(deps, info, env, vec![(lp_token, schedule)])
         }
         ExecuteMsg::IncentivizeMany(incentives) => incentivize_many(deps, info, env, incentives),
+        ExecuteMsg::TopUpSchedule { lp_token, reward } => {
+            top_up_schedule(deps, info, env, lp_token, reward)
+        }
         ExecuteMsg::RemoveRewardFromPool {
             lp_token,
             reward,
@@ -133,8 +139,39 @@ pub fn execute(
         ExecuteMsg::UpdateBlockedTokenslist { add, remove } => {
             update_blocked_pool_tokens(deps, env, info, add, remove)
         }
+        ExecuteMsg::UpdateEmissionController { controller } => {
+            update_emission_controller(deps, info, controller)
+        }
+        ExecuteMsg::UpdateXastroStaking { staking_contract } => {
+            update_xastro_staking(deps, info, staking_contract)
+        }
+        ExecuteMsg::UpdateStakeReceipts { enabled } => update_stake_receipts(deps, info, enabled),
+        ExecuteMsg::UpdateAstroPullBatching { enabled } => {
+            update_astro_pull_batching(deps, info, enabled)
+        }
+        ExecuteMsg::UpdateScheduleCancellation { config } => {
+            update_schedule_cancellation(deps, info, config)
+        }
+        ExecuteMsg::CancelSchedule {
+            lp_token,
+            reward,
+            receiver,
+        } => cancel_schedule(deps, env, info, lp_token, reward, receiver),
+        ExecuteMsg::ScaleEmissions {
+            lp_token,
+            factor,
+            duration,
+        } => scale_emissions(deps, env, info, lp_token, factor, duration),
+        ExecuteMsg::RestoreEmissions { lp_token } => restore_emissions(deps, env, info, lp_token),
+        ExecuteMsg::PausePool { lp_token } => pause_pool(deps, env, info, lp_token),
+        ExecuteMsg::UnpausePool { lp_token } => unpause_pool(deps, env, info, lp_token),
+        ExecuteMsg::SetRewardMetadata { reward, metadata } => {
+            set_reward_metadata(deps, info, reward, metadata)
+        }
         ExecuteMsg::DeactivatePool { lp_token } => deactivate_pool(deps, info, env, lp_token),
         ExecuteMsg::DeactivateBlockedPools {} => deactivate_blocked_pools(deps, env),
+        ExecuteMsg::UpdatePools { limit } => update_pools(deps, env, limit),
+        ExecuteMsg::BuildStakersIndex { limit } => continue_stakers_index(deps, limit),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
 
@@ -166,6 +203,24 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::UpdateOwnershipTimelock { timelock } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            update_ownership_timelock(deps, info, env, timelock, config.owner).map_err(Into::into)
+        }
+        ExecuteMsg::UpdateStakingHooks {
+            lp_token,
+            add,
+            remove,
+        } => update_staking_hooks(deps, info, lp_token, add, remove),
+        ExecuteMsg::ApproveClaimOperator {
+            operator,
+            expires_at,
+        } => approve_claim_operator(deps, env, info, operator, expires_at),
+        ExecuteMsg::RevokeClaimOperator { operator } => revoke_claim_operator(deps, info, operator),
+        ExecuteMsg::SetRewardReceiver { lp_token, receiver } => {
+            set_reward_receiver(deps, info, lp_token, receiver)
+        }
     }
 }
 
@@ -175,8 +230,20 @@ fn deposit(
     maybe_lp: Asset,
     sender: Addr,
     recipient: Option<String>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
     let staker = addr_opt_validate(deps.api, &recipient)?.unwrap_or(sender);
+    let referrer = addr_opt_validate(deps.api, &referrer)?;
+    ensure!(
+        referrer.as_ref() != Some(&staker),
+        ContractError::SelfReferral {}
+    );
+
+    if PAUSED_POOLS.has(deps.storage, &maybe_lp.info) {
+        return Err(ContractError::PoolPaused {
+            lp_token: maybe_lp.info.to_string(),
+        });
+    }
 
     let pair_info = query_pair_info(deps.as_ref(), &maybe_lp.info)?;
     let config = CONFIG.load(deps.storage)?;
@@ -187,28 +254,68 @@ fn deposit(
         &maybe_lp.info.to_string(),
     )?;
 
-    let mut pool_info = PoolInfo::may_load(deps.storage, &maybe_lp.info)?.unwrap_or_default();
-    let mut user_info = UserInfo::may_load_position(deps.storage, &staker, &maybe_lp.info)?
-        .unwrap_or_else(|| UserInfo::new(&env));
+    let mut pool_info = PoolInfo::load_or_new(deps.storage, &maybe_lp.info)?;
+    let mut attrs = vec![
+        attr("action", "deposit"),
+        attr("lp_token", maybe_lp.info.to_string()),
+        attr("user", staker.as_str()),
+        attr("amount", maybe_lp.amount),
+    ];
+    let mut user_info = match UserInfo::may_load_position(deps.storage, &staker, &maybe_lp.info)? {
+        Some(user_info) => user_info,
+        None => {
+            // Referrer is recorded only when a position is opened and never changes afterwards
+            if let Some(referrer) = referrer {
+                REFERRERS.save(deps.storage, (&maybe_lp.info, &staker), &referrer)?;
+                attrs.push(attr("referrer", referrer));
+            }
+            UserInfo::new(&env)
+        }
+    };
 
+    let contract_address = env.contract.address.clone();
     let response = claim_rewards(
         deps.storage,
+        deps.querier,
         Some(config.vesting_contract),
         env,
         &staker,
         vec![(&maybe_lp.info, &mut pool_info, &mut user_info)],
+        None,
+        false,
     )?;
 
     user_info.update_and_sync_position(Op::Add(maybe_lp.amount), &mut pool_info);
+    let staked_amount = user_info.amount;
     pool_info.save(deps.storage, &maybe_lp.info)?;
     user_info.save(deps.storage, &staker, &maybe_lp.info)?;
 
-    Ok(response.add_attributes([
-        attr("action", "deposit"),
-        attr("lp_token", maybe_lp.info.to_string()),
-        attr("user", staker.as_str()),
-        attr("amount", maybe_lp.amount),
-    ]))
+    let receipt_msgs = if config.stake_receipts {
+        mint_receipts_msgs(
+            deps.storage,
+            &contract_address,
+            &maybe_lp.info,
+            &staker,
+            maybe_lp.amount,
+        )?
+    } else {
+        vec![]
+    };
+
+    let hook_msgs = staking_hook_msgs(
+        deps.storage,
+        &maybe_lp.info,
+        &StakingHookMsg::PositionChanged {
+            lp_token: maybe_lp.info.to_string(),
+            user: staker.to_string(),
+            amount: staked_amount,
+        },
+    )?;
+
+    Ok(response
+        .add_messages(receipt_msgs)
+        .add_submessages(hook_msgs)
+        .add_attributes(attrs))
 }
 
 fn withdraw(
@@ -230,31 +337,128 @@ fn withdraw(
     } else {
         let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
 
+        let mut burn_msgs: Vec<CosmosMsg> = vec![];
+        if let Some(receipt_denom) = RECEIPT_DENOMS.may_load(deps.storage, &lp_token_asset)? {
+            let attached = may_pay(&info, &receipt_denom)?;
+            // Receipts sent to this contract outside of withdrawals make up for missing attached receipts.
+            // The rest of them is returned to the sender
+            let stray = RECEIVED_RECEIPTS
+                .may_load(deps.storage, (&info.sender, &receipt_denom))?
+                .unwrap_or_default()
+                .saturating_sub(attached);
+            RECEIVED_RECEIPTS.remove(deps.storage, (&info.sender, &receipt_denom));
+            let held = deps
+                .querier
+                .query_balance(&info.sender, &receipt_denom)?
+                .amount;
+            // Positions opened before receipts were enabled are only partially backed by receipts.
+            // The unbacked part is withdrawn first
+            let unbacked = user_info.amount.saturating_sub(held + attached + stray);
+            let expected = amount.saturating_sub(unbacked);
+            let from_stray = expected.saturating_sub(attached);
+            ensure!(
+                attached <= expected && from_stray <= stray,
+                ContractError::InvalidStakeReceipts {
+                    expected,
+                    actual: attached
+                }
+            );
+
+            if !expected.is_zero() {
+                burn_msgs.push(tf_burn_msg(
+                    &env.contract.address,
+                    coin(expected.u128(), &receipt_denom),
+                ));
+            }
+            let returned = stray - from_stray;
+            if !returned.is_zero() {
+                burn_msgs.push(
+                    BankMsg::Send {
+                        to_address: info.sender.to_string(),
+                        amount: coins(returned.u128(), receipt_denom),
+                    }
+                    .into(),
+                );
+            }
+        }
+
         let response = claim_rewards(
             deps.storage,
+            deps.querier,
             None,
             env,
             &info.sender,
             vec![(&lp_token_asset, &mut pool_info, &mut user_info)],
+            None,
+            false,
         )?;
 
         user_info.update_and_sync_position(Op::Sub(amount), &mut pool_info);
+        let staked_amount = user_info.amount;
         pool_info.save(deps.storage, &lp_token_asset)?;
         if user_info.amount.is_zero() {
             // If user has withdrawn all LP tokens, we can remove his position
-            user_info.remove(deps.storage, &info.sender, &lp_token_asset);
+            user_info.remove(deps.storage, &info.sender, &lp_token_asset)?;
         } else {
             user_info.save(deps.storage, &info.sender, &lp_token_asset)?;
         }
 
+        let hook_msgs = staking_hook_msgs(
+            deps.storage,
+            &lp_token_asset,
+            &StakingHookMsg::PositionChanged {
+                lp_token: lp_token_asset.to_string(),
+                user: info.sender.to_string(),
+                amount: staked_amount,
+            },
+        )?;
+
         let transfer_msg = lp_token_asset.with_balance(amount).into_msg(info.sender)?;
 
-        Ok(response.add_message(transfer_msg).add_attributes([
-            attr("action", "withdraw"),
-            attr("lp_token", lp_token_asset.to_string()),
-            attr("amount", amount),
-        ]))
+        Ok(response
+            .add_messages(burn_msgs)
+            .add_message(transfer_msg)
+            .add_submessages(hook_msgs)
+            .add_attributes([
+                attr("action", "withdraw"),
+                attr("lp_token", lp_token_asset.to_string()),
+                attr("amount", amount),
+            ]))
+    }
+}
+
+fn claim_vested(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let block_ts = env.block.time.seconds();
+    let positions = VESTING_REWARDS
+        .prefix(&info.sender)
+        .range_raw(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, position)| position))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut attrs = vec![attr("action", "claim_vested"), attr("user", &info.sender)];
+    let mut messages = vec![];
+    for mut position in positions {
+        position.update(block_ts)?;
+
+        // Vested rewards are sent without a reply on error.
+        // If the transfer fails, the whole claim is reverted and the position is kept.
+        if !position.vested.is_zero() {
+            let reward = position.reward.with_balance(position.vested);
+            attrs.push(attr("claimed_reward", reward.to_string()));
+            messages.push(reward.into_msg(&info.sender)?);
+            position.vested = Uint128::zero();
+        }
+
+        if position.tranches.is_empty() {
+            VESTING_REWARDS.remove(deps.storage, (&info.sender, &position.reward));
+        } else {
+            VESTING_REWARDS.save(deps.storage, (&info.sender, &position.reward), &position)?;
+        }
     }
+
+    ensure!(!messages.is_empty(), ContractError::NoVestedRewards {});
+
+    Ok(Response::new().add_attributes(attrs).add_messages(messages))
 }
 
 pub fn setup_pools(
@@ -313,22 +517,22 @@ pub fn setup_pools(
         })
         .collect::<Result<Vec<_>, ContractError>>()?;
 
-    // Update all reward indexes and remove astro rewards from old active pools
-    for (lp_token_asset, _) in ACTIVE_POOLS.load(deps.storage)? {
-        let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
-        pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
-        pool_info.disable_astro_rewards();
-        pool_info.save(deps.storage, &lp_token_asset)?;
-    }
+    // Pools which have never been initialized must exist before the checkpoint
+    // so they start accruing ASTRO rewards right away
+    let new_pools = setup_pools
+        .iter()
+        .filter(|(lp_asset, _)| !POOLS.has(deps.storage, lp_asset))
+        .map(|(lp_asset, _)| Ok((lp_asset, PoolInfo::load_or_new(deps.storage, lp_asset)?)))
+        .collect::<StdResult<Vec<_>>>()?;
 
     config.total_alloc_points = setup_pools.iter().map(|(_, alloc)| alloc).sum();
 
-    // Set astro rewards for new active pools
-    for (active_pool, alloc_points) in &setup_pools {
-        let mut pool_info = PoolInfo::may_load(deps.storage, active_pool)?.unwrap_or_default();
-        pool_info.update_rewards(deps.storage, &env, active_pool)?;
-        pool_info.set_astro_rewards(&config, *alloc_points);
-        pool_info.save(deps.storage, active_pool)?;
+    // Existing pools pick up new emissions lazily
+    save_emission_checkpoint(deps.storage, &env, &config, &setup_pools)?;
+
+    for (lp_asset, mut pool_info) in new_pools {
+        pool_info.update_rewards(deps.storage, &env, lp_asset)?;
+        pool_info.save(deps.storage, lp_asset)?;
     }
 
     ACTIVE_POOLS.save(deps.storage, &setup_pools)?;
@@ -350,26 +554,660 @@ fn set_tokens_per_second(
         return Err(ContractError::Unauthorized {});
     }
 
-    let pool_infos = ACTIVE_POOLS
-        .load(deps.storage)?
-        .into_iter()
-        .map(|(lp_token, alloc_points)| {
-            let mut pool_info = PoolInfo::load(deps.storage, &lp_token)?;
-            pool_info.update_rewards(deps.storage, &env, &lp_token)?;
-            Ok((pool_info, lp_token, alloc_points))
+    config.astro_per_second = amount;
+
+    let active_pools = ACTIVE_POOLS.load(deps.storage)?;
+    save_emission_checkpoint(deps.storage, &env, &config, &active_pools)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "set_tokens_per_second"))
+}
+
+/// Apply pending emission checkpoints to the most outdated pools.
+fn update_pools(deps: DepsMut, env: Env, limit: Option<u8>) -> Result<Response, ContractError> {
+    let limit = limit
+        .unwrap_or(MAX_POOL_UPDATES_LIMIT)
+        .min(MAX_POOL_UPDATES_LIMIT) as usize;
+    let last_checkpoint = last_emission_checkpoint(deps.storage)?;
+
+    let api = deps.api;
+    let stale_pools = POOLS_BY_CHECKPOINT
+        .prefix_range_raw(
+            deps.storage,
+            None,
+            Some(PrefixBound::exclusive(last_checkpoint)),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (key, _) = item?;
+            // Skip length-prefixed checkpoint id
+            let lp_token = String::from_utf8(key[10..].to_vec()).map_err(StdError::invalid_utf8)?;
+            determine_asset_info(&lp_token, api)
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    config.astro_per_second = amount;
+    for lp_asset in &stale_pools {
+        let mut pool_info = PoolInfo::load(deps.storage, lp_asset)?;
+        pool_info.update_rewards(deps.storage, &env, lp_asset)?;
+        pool_info.save(deps.storage, lp_asset)?;
+    }
 
-    for (mut pool_info, lp_token, alloc_points) in pool_infos {
-        pool_info.set_astro_rewards(&config, alloc_points);
-        pool_info.save(deps.storage, &lp_token)?;
+    Ok(Response::new().add_attributes([
+        attr("action", "update_pools"),
+        attr("updated_pools", stale_pools.len().to_string()),
+    ]))
+}
+
+fn continue_stakers_index(deps: DepsMut, limit: Option<u32>) -> Result<Response, ContractError> {
+    ensure!(
+        STAKERS_INDEX_CURSOR.exists(deps.storage),
+        ContractError::StakersIndexBuilt {}
+    );
+    let limit = limit
+        .unwrap_or(MAX_STAKERS_INDEX_LIMIT)
+        .min(MAX_STAKERS_INDEX_LIMIT);
+
+    let (indexed, finished) = build_stakers_index(deps.storage, deps.api, limit)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "build_stakers_index"),
+        attr("indexed_positions", indexed.to_string()),
+        attr("stakers_index_built", finished.to_string()),
+    ]))
+}
+
+/// Claims rewards of the user from the specified pools. Rewards are always sent to the user.
+/// If `into_xastro` is set, claimed ASTRO is staked with the user as the xASTRO receiver.
+fn claim(
+    deps: DepsMut,
+    env: Env,
+    user: Addr,
+    lp_tokens: Vec<String>,
+    into_xastro: bool,
+    stake_lp_rewards: bool,
+) -> Result<Response, ContractError> {
+    // Check for duplicated pools
+    ensure!(
+        lp_tokens.iter().all_unique(),
+        ContractError::DuplicatedPoolFound {}
+    );
+
+    let xastro_staking = if into_xastro {
+        Some(
+            CONFIG
+                .load(deps.storage)?
+                .xastro_staking
+                .ok_or(ContractError::XastroStakingNotSet {})?,
+        )
+    } else {
+        None
+    };
+
+    // Collect in-memory mutable objects
+    let mut tuples = lp_tokens
+        .into_iter()
+        .map(|lp_token| {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
+            let user_pos = UserInfo::load_position(deps.storage, &user, &lp_asset)?;
+            Ok((lp_asset, pool_info, user_pos))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    // Convert to mutable references
+    let mut_tuples = tuples
+        .iter_mut()
+        .map(|(lp_asset, pool_info, user_pos)| (&*lp_asset, pool_info, user_pos))
+        .collect_vec();
+
+    // Compose response. Return early in case of error
+    let response = claim_rewards(
+        deps.storage,
+        deps.querier,
+        None,
+        env,
+        &user,
+        mut_tuples,
+        xastro_staking.as_ref(),
+        stake_lp_rewards,
+    )?;
+
+    // Save updates in state
+    let mut hook_msgs = vec![];
+    for (lp_asset, pool_info, user_pos) in tuples {
+        pool_info.save(deps.storage, &lp_asset)?;
+        user_pos.save(deps.storage, &user, &lp_asset)?;
+
+        hook_msgs.extend(staking_hook_msgs(
+            deps.storage,
+            &lp_asset,
+            &StakingHookMsg::RewardsClaimed {
+                lp_token: lp_asset.to_string(),
+                user: user.to_string(),
+            },
+        )?);
+    }
+
+    Ok(response.add_submessages(hook_msgs))
+}
+
+/// Checks that the operator is allowed to claim rewards on behalf of the user.
+/// Returns the validated user address.
+fn check_claim_operator(
+    deps: Deps,
+    env: &Env,
+    operator: &Addr,
+    user: String,
+) -> Result<Addr, ContractError> {
+    let user = deps.api.addr_validate(&user)?;
+    if user == operator {
+        return Ok(user);
     }
 
+    let approved = CLAIM_OPERATORS
+        .may_load(deps.storage, (&user, operator))?
+        .filter(|approval| approval.is_active(env.block.time.seconds()))
+        .is_some();
+    ensure!(
+        approved,
+        ContractError::ClaimOperatorNotApproved {
+            user: user.to_string(),
+            operator: operator.to_string(),
+        }
+    );
+
+    Ok(user)
+}
+
+fn approve_claim_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires_at: Option<u64>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    if let Some(expires_at) = expires_at {
+        ensure!(
+            expires_at > env.block.time.seconds(),
+            ContractError::ClaimOperatorExpiryInPast { expires_at }
+        );
+    }
+
+    CLAIM_OPERATORS.save(
+        deps.storage,
+        (&info.sender, &operator),
+        &ClaimOperator {
+            operator: operator.clone(),
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "approve_claim_operator"),
+        attr("user", info.sender),
+        attr("operator", operator),
+        attr(
+            "expires_at",
+            expires_at
+                .map(|ts| ts.to_string())
+                .unwrap_or_else(|| "never".to_string()),
+        ),
+    ]))
+}
+
+fn revoke_claim_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    CLAIM_OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new().add_attributes([
+        attr("action", "revoke_claim_operator"),
+        attr("user", info.sender),
+        attr("operator", operator),
+    ]))
+}
+
+fn set_reward_receiver(
+    deps: DepsMut,
+    info: MessageInfo,
+    lp_token: String,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+    let receiver = addr_opt_validate(deps.api, &receiver)?.filter(|addr| *addr != info.sender);
+
+    match &receiver {
+        Some(receiver) => {
+            REWARD_RECEIVERS.save(deps.storage, (&info.sender, &lp_asset), receiver)?
+        }
+        None => REWARD_RECEIVERS.remove(deps.storage, (&info.sender, &lp_asset)),
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_reward_receiver"),
+        attr("user", info.sender),
+        attr("lp_token", lp_asset.to_string()),
+        attr(
+            "receiver",
+            receiver
+                .map(|addr| addr.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn update_xastro_staking(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.xastro_staking = staking_contract
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_attribute("action", "set_tokens_per_second"))
+    Ok(Response::new().add_attributes([
+        attr("action", "update_xastro_staking"),
+        attr(
+            "xastro_staking",
+            config
+                .xastro_staking
+                .map(String::from)
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn update_stake_receipts(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.stake_receipts = enabled;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_stake_receipts"),
+        attr("stake_receipts", enabled.to_string()),
+    ]))
+}
+
+fn update_astro_pull_batching(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.astro_pull_batching = enabled;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_astro_pull_batching"),
+        attr("astro_pull_batching", enabled.to_string()),
+    ]))
+}
+
+fn update_emission_controller(
+    deps: DepsMut,
+    info: MessageInfo,
+    controller: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.emission_controller = controller
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_emission_controller"),
+        attr(
+            "emission_controller",
+            config
+                .emission_controller
+                .map(String::from)
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
+
+fn update_schedule_cancellation(
+    deps: DepsMut,
+    info: MessageInfo,
+    cancellation_config: Option<ScheduleCancellationConfig>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![attr("action", "update_schedule_cancellation")];
+    match &cancellation_config {
+        Some(cancellation_config) => attrs.extend([
+            attr(
+                "notice_period",
+                cancellation_config.notice_period.to_string(),
+            ),
+            attr(
+                "locked_epochs",
+                cancellation_config.locked_epochs.to_string(),
+            ),
+        ]),
+        None => attrs.push(attr("schedule_cancellation", "disabled")),
+    }
+
+    config.schedule_cancellation = cancellation_config;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+fn cancel_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    reward: String,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let cancellation_config = CONFIG
+        .load(deps.storage)?
+        .schedule_cancellation
+        .ok_or(ContractError::ScheduleCancellationDisabled {})?;
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+
+    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+    let reward_asset = determine_asset_info(&reward, deps.api)?;
+    let creator_key = (&lp_asset, &reward_asset, &info.sender);
+    let schedules = CREATOR_SCHEDULES
+        .may_load(deps.storage, creator_key)?
+        .unwrap_or_default();
+
+    let block_ts = env.block.time.seconds();
+    // Schedules stop at the first epoch start after the notice period
+    let notice_end = block_ts + cancellation_config.notice_period;
+    let stop_ts = EPOCHS_START
+        + (notice_end.saturating_sub(EPOCHS_START) + EPOCH_LENGTH - 1) / EPOCH_LENGTH
+            * EPOCH_LENGTH;
+
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_asset)?;
+
+    let mut refund = Uint128::zero();
+    let mut remaining_schedules = vec![];
+    for mut schedule in schedules {
+        let unlock_ts =
+            schedule.next_epoch_start_ts + cancellation_config.locked_epochs * EPOCH_LENGTH;
+
+        if schedule.end_ts > stop_ts && unlock_ts <= block_ts {
+            pool_info.cancel_schedule(
+                deps.storage,
+                &lp_asset,
+                &reward_asset,
+                &schedule,
+                stop_ts,
+            )?;
+
+            let undistributed =
+                schedule.rps * Decimal256::from_ratio(schedule.end_ts - stop_ts, 1u8);
+            let schedule_refund = Uint128::try_from(undistributed.to_uint_floor())?;
+            refund += schedule_refund;
+            schedule.end_ts = stop_ts;
+
+            // Record the refund against the funder's contribution to the pooled schedule
+            if let Some(schedule_id) = schedule.schedule_id {
+                POOLED_SCHEDULES.update::<_, StdError>(deps.storage, schedule_id, |pooled| {
+                    let mut pooled = pooled.ok_or_else(|| {
+                        StdError::not_found(format!("Pooled schedule {schedule_id}"))
+                    })?;
+                    pooled.refunded += schedule_refund;
+                    Ok(pooled)
+                })?;
+                SCHEDULE_FUNDERS.update::<_, StdError>(
+                    deps.storage,
+                    (schedule_id, &info.sender),
+                    |contribution| {
+                        let (funded, refunded) = contribution.unwrap_or_default();
+                        Ok((funded, refunded + schedule_refund))
+                    },
+                )?;
+            }
+        }
+
+        if schedule.end_ts > block_ts {
+            remaining_schedules.push(schedule);
+        }
+    }
+
+    if refund.is_zero() {
+        return Err(ContractError::NothingToCancel { lp_token, reward });
+    }
+
+    pool_info.save(deps.storage, &lp_asset)?;
+
+    if remaining_schedules.is_empty() {
+        CREATOR_SCHEDULES.remove(deps.storage, creator_key);
+    } else {
+        CREATOR_SCHEDULES.save(deps.storage, creator_key, &remaining_schedules)?;
+    }
+
+    let transfer_msg = reward_asset
+        .with_balance(refund)
+        .into_submsg(&receiver, Some((ReplyOn::Error, POST_TRANSFER_REPLY_ID)))?;
+
+    Ok(Response::new()
+        .add_submessage(transfer_msg)
+        .add_attributes([
+            attr("action", "cancel_schedule"),
+            attr("lp_token", lp_token),
+            attr("reward", reward),
+            attr("stop_ts", stop_ts.to_string()),
+            attr("refund", refund),
+            attr("receiver", receiver),
+        ]))
+}
+
+fn scale_emissions(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    factor: Decimal,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if Some(&info.sender) != config.emission_controller.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let scale = EmissionScale::new(&env, factor, duration)?;
+
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    let (_, alloc_points) = ACTIVE_POOLS
+        .load(deps.storage)?
+        .into_iter()
+        .find(|(lp_asset, _)| lp_asset == &lp_token_asset)
+        .ok_or_else(|| ContractError::PoolIsNotActive {
+            lp_token: lp_token.clone(),
+        })?;
+
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
+    EMISSION_SCALES.save(deps.storage, &lp_token_asset, &scale)?;
+    let alloc_points = scaled_alloc_points(deps.storage, &env, &lp_token_asset, alloc_points)?;
+    pool_info.set_astro_rewards(&config, alloc_points);
+    pool_info.save(deps.storage, &lp_token_asset)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "scale_emissions"),
+        attr("lp_token", lp_token),
+        attr("factor", scale.factor.to_string()),
+        attr("scaled_alloc_points", alloc_points),
+        attr("expires_at", scale.expires_at.to_string()),
+    ]))
+}
+
+fn restore_emissions(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner && Some(&info.sender) != config.emission_controller.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    match EMISSION_SCALES.may_load(deps.storage, &lp_token_asset)? {
+        Some(scale) if scale.is_active(env.block.time.seconds()) => {}
+        _ => return Err(ContractError::EmissionsNotScaled { lp_token }),
+    }
+
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
+    EMISSION_SCALES.remove(deps.storage, &lp_token_asset);
+
+    if let Some((_, alloc_points)) = ACTIVE_POOLS
+        .load(deps.storage)?
+        .into_iter()
+        .find(|(lp_asset, _)| lp_asset == &lp_token_asset)
+    {
+        pool_info.set_astro_rewards(&config, alloc_points);
+    }
+    pool_info.save(deps.storage, &lp_token_asset)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "restore_emissions"),
+        attr("lp_token", lp_token),
+        attr("restored_by", info.sender),
+    ]))
+}
+
+fn pause_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner && Some(&info.sender) != config.guardian.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    if PAUSED_POOLS.has(deps.storage, &lp_token_asset) {
+        return Err(ContractError::PoolPaused { lp_token });
+    }
+
+    // Checkpoint reward indexes before freezing them
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
+    pool_info.save(deps.storage, &lp_token_asset)?;
+
+    PAUSED_POOLS.save(deps.storage, &lp_token_asset, &env.block.time.seconds())?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "pause_pool"),
+        attr("lp_token", lp_token),
+        attr("paused_by", info.sender),
+    ]))
+}
+
+fn unpause_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    if !PAUSED_POOLS.has(deps.storage, &lp_token_asset) {
+        return Err(ContractError::PoolNotPaused { lp_token });
+    }
+
+    // Move schedules forward and forfeit external rewards of the paused period
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
+    pool_info.save(deps.storage, &lp_token_asset)?;
+
+    PAUSED_POOLS.remove(deps.storage, &lp_token_asset);
+
+    Ok(
+        Response::new()
+            .add_attributes([attr("action", "unpause_pool"), attr("lp_token", lp_token)]),
+    )
+}
+
+fn set_reward_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    reward: String,
+    metadata: Option<RewardTokenMetadata>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let reward_asset = determine_asset_info(&reward, deps.api)?;
+    let reward_key = asset_info_key(&reward_asset);
+
+    let is_moderator =
+        info.sender == config.owner || Some(&info.sender) == config.guardian.as_ref();
+    let is_creator = REWARD_CREATORS.may_load(deps.storage, &reward_key)? == Some(info.sender);
+    ensure!(is_moderator || is_creator, ContractError::Unauthorized {});
+
+    if let Some(metadata) = metadata {
+        metadata.validate()?;
+        // Metadata set by the reward creator is marked unverified
+        REWARD_METADATA.save(
+            deps.storage,
+            &reward_key,
+            &RewardMetadata {
+                metadata,
+                verified: is_moderator,
+            },
+        )?;
+    } else {
+        REWARD_METADATA.remove(deps.storage, &reward_key);
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_reward_metadata"),
+        attr("reward", reward),
+    ]))
 }
 
 fn update_config(
@@ -395,20 +1233,18 @@ fn update_config(
         attrs.push(attr("new_astro_token", astro_token.to_string()));
         config.astro_token = astro_token;
 
-        // Loop through all active pools and update astro asset info
+        // Loop through all active pools and update astro asset info.
+        // Pools which haven't applied emission checkpoint yet will use the new token right away.
         for (lp_token, _) in ACTIVE_POOLS.load(deps.storage)? {
             let mut pool_info = PoolInfo::load(deps.storage, &lp_token)?;
-            let protocol_reward = pool_info
+            if let Some(protocol_reward) = pool_info
                 .rewards
                 .iter_mut()
                 .find(|r| !r.reward.is_external())
-                .ok_or_else(|| {
-                    StdError::generic_err(format!(
-                        "Protocol ASTRO reward not found in active pool {lp_token}",
-                    ))
-                })?;
-            protocol_reward.reward = RewardType::Int(config.astro_token.clone());
-            pool_info.save(deps.storage, &lp_token)?;
+            {
+                protocol_reward.reward = RewardType::Int(config.astro_token.clone());
+                pool_info.save(deps.storage, &lp_token)?;
+            }
         }
     }
 
@@ -444,6 +1280,58 @@ fn update_config(
     Ok(Response::new().add_attributes(attrs))
 }
 
+fn update_staking_hooks(
+    deps: DepsMut,
+    info: MessageInfo,
+    lp_token: String,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+    let mut hooks = STAKING_HOOKS
+        .may_load(deps.storage, &lp_asset)?
+        .unwrap_or_default();
+
+    let remove = remove
+        .unwrap_or_default()
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    hooks.retain(|hook| !remove.contains(hook));
+
+    for addr in add.unwrap_or_default() {
+        let hook = deps.api.addr_validate(&addr)?;
+        if !hooks.contains(&hook) {
+            hooks.push(hook);
+        }
+    }
+
+    ensure!(
+        hooks.len() <= MAX_STAKING_HOOKS,
+        ContractError::TooManyStakingHooks {
+            lp_token: lp_asset.to_string()
+        }
+    );
+
+    if hooks.is_empty() {
+        STAKING_HOOKS.remove(deps.storage, &lp_asset);
+    } else {
+        STAKING_HOOKS.save(deps.storage, &lp_asset, &hooks)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_staking_hooks"),
+        attr("lp_token", lp_asset.to_string()),
+        attr("hooks", hooks.iter().join(",")),
+    ]))
+}
+
 fn update_blocked_pool_tokens(
     deps: DepsMut,
     env: Env,
@@ -516,16 +1404,10 @@ fn update_blocked_pool_tokens(
         }
 
         if !to_disable.is_empty() {
-            let mut reduce_total_alloc_points = Uint128::zero();
-
-            // Update all reward indexes and remove astro rewards from disabled pools
-            for (lp_token_asset, alloc_points) in &to_disable {
-                let mut pool_info = PoolInfo::load(deps.storage, lp_token_asset)?;
-                pool_info.update_rewards(deps.storage, &env, lp_token_asset)?;
-                pool_info.disable_astro_rewards();
-                pool_info.save(deps.storage, lp_token_asset)?;
-                reduce_total_alloc_points += *alloc_points;
-            }
+            let reduce_total_alloc_points: Uint128 = to_disable
+                .iter()
+                .map(|(_, alloc_points)| **alloc_points)
+                .sum();
 
             let new_active_pools = active_pools
                 .iter()
@@ -545,13 +1427,8 @@ fn update_blocked_pool_tokens(
                 .total_alloc_points
                 .checked_sub(reduce_total_alloc_points)?;
 
-            for (lp_asset, alloc_points) in &new_active_pools {
-                let mut pool_info = PoolInfo::load(deps.storage, lp_asset)?;
-                pool_info.update_rewards(deps.storage, &env, lp_asset)?;
-                pool_info.set_astro_rewards(&config, *alloc_points);
-                pool_info.save(deps.storage, lp_asset)?;
-            }
-
+            // Disabled and remaining active pools pick up new emissions lazily
+            save_emission_checkpoint(deps.storage, &env, &config, &new_active_pools)?;
             ACTIVE_POOLS.save(deps.storage, &new_active_pools)?;
         }
     }
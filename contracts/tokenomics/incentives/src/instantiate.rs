@@ -3,10 +3,11 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
 
 use astroport::asset::{addr_opt_validate, validate_native_denom};
+use astroport::common::init_ownership_timelock;
 use astroport::incentives::{Config, InstantiateMsg};
 
 use crate::error::ContractError;
-use crate::state::{ACTIVE_POOLS, CONFIG};
+use crate::state::{save_emission_checkpoint, ACTIVE_POOLS, CONFIG};
 
 /// Contract name that is used for migration.
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -16,7 +17,7 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -29,21 +30,26 @@ pub fn instantiate(
         validate_native_denom(&fee_info.fee.denom)?;
     }
 
-    CONFIG.save(
-        deps.storage,
-        &Config {
-            owner: deps.api.addr_validate(&msg.owner)?,
-            factory: deps.api.addr_validate(&msg.factory)?,
-            generator_controller: None,
-            astro_token: msg.astro_token,
-            astro_per_second: Uint128::zero(),
-            total_alloc_points: Uint128::zero(),
-            vesting_contract: deps.api.addr_validate(&msg.vesting_contract)?,
-            guardian: addr_opt_validate(deps.api, &msg.guardian)?,
-            incentivization_fee_info: msg.incentivization_fee_info,
-        },
-    )?;
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        factory: deps.api.addr_validate(&msg.factory)?,
+        generator_controller: None,
+        astro_token: msg.astro_token,
+        astro_per_second: Uint128::zero(),
+        total_alloc_points: Uint128::zero(),
+        vesting_contract: deps.api.addr_validate(&msg.vesting_contract)?,
+        guardian: addr_opt_validate(deps.api, &msg.guardian)?,
+        incentivization_fee_info: msg.incentivization_fee_info,
+        emission_controller: None,
+        schedule_cancellation: None,
+        xastro_staking: None,
+        stake_receipts: false,
+        astro_pull_batching: false,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    init_ownership_timelock(deps.storage, msg.ownership_timelock)?;
     ACTIVE_POOLS.save(deps.storage, &vec![])?;
+    save_emission_checkpoint(deps.storage, &env, &config, &[])?;
 
     Ok(Response::new())
 }
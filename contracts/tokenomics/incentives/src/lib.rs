@@ -5,5 +5,6 @@ pub mod migrate;
 pub mod query;
 pub mod reply;
 pub mod state;
+pub mod sudo;
 pub mod traits;
 pub mod utils;
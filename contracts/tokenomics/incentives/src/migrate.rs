@@ -2,18 +2,55 @@
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Empty, Env, Response};
+use cosmwasm_std::{attr, DepsMut, Empty, Env, Order, Response, StdError, StdResult};
+
+use astroport::asset::determine_asset_info;
+use astroport::incentives::MAX_STAKERS_INDEX_LIMIT;
 
 use crate::error::ContractError;
 use crate::instantiate::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::state::{
+    build_stakers_index, save_emission_checkpoint, ACTIVE_POOLS, CONFIG, POOLS,
+    POOLS_BY_CHECKPOINT, STAKERS_INDEX_CURSOR,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> Result<Response, ContractError> {
     let contract_version = cw2::get_contract_version(deps.storage)?;
+    let mut attrs = vec![];
 
     match contract_version.contract.as_ref() {
         "astroport-incentives" => match contract_version.version.as_ref() {
-            "1.0.0" | "1.0.1" | "1.1.0" => {}
+            "1.0.0" | "1.0.1" | "1.1.0" | "1.2.0" => {
+                let api = deps.api;
+                let lp_tokens = POOLS
+                    .keys_raw(deps.storage, None, None, Order::Ascending)
+                    .map(|item| {
+                        let lp_token = String::from_utf8(item).map_err(StdError::invalid_utf8)?;
+                        determine_asset_info(&lp_token, api)
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+
+                // Indexing all existing positions at once might not fit into a single transaction.
+                // Index the first batch here, the rest is indexed via permissionless BuildStakersIndex.
+                if let Some(first_pool) = lp_tokens.first() {
+                    STAKERS_INDEX_CURSOR.save(deps.storage, &(first_pool.clone(), None))?;
+                    let (indexed, finished) =
+                        build_stakers_index(deps.storage, api, MAX_STAKERS_INDEX_LIMIT)?;
+                    attrs.push(attr("indexed_positions", indexed.to_string()));
+                    attrs.push(attr("stakers_index_built", finished.to_string()));
+                }
+
+                // Existing pools are in sync with current emission parameters.
+                // Save them as the initial checkpoint which all pools have already applied.
+                let config = CONFIG.load(deps.storage)?;
+                let active_pools = ACTIVE_POOLS.load(deps.storage)?;
+                let checkpoint_id =
+                    save_emission_checkpoint(deps.storage, &env, &config, &active_pools)?;
+                for lp_token in &lp_tokens {
+                    POOLS_BY_CHECKPOINT.save(deps.storage, (checkpoint_id, lp_token), &())?;
+                }
+            }
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -25,5 +62,6 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
         .add_attribute("previous_contract_name", &contract_version.contract)
         .add_attribute("previous_contract_version", &contract_version.version)
         .add_attribute("new_contract_name", CONTRACT_NAME)
-        .add_attribute("new_contract_version", CONTRACT_VERSION))
+        .add_attribute("new_contract_version", CONTRACT_VERSION)
+        .add_attributes(attrs))
 }
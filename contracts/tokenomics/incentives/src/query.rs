@@ -6,13 +6,21 @@ use cosmwasm_std::{
 use cw_storage_plus::Bound;
 use itertools::Itertools;
 
-use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt};
-use astroport::incentives::{QueryMsg, RewardType, ScheduleResponse, MAX_PAGE_LIMIT};
+use astroport::asset::{addr_opt_validate, determine_asset_info, Asset, AssetInfo, AssetInfoExt};
+use astroport::incentives::{
+    BlockScheduleResponse, ClaimOperator, ClaimSimulationResponse, PendingRewardResponse,
+    PoolStakersByAmountResponse, QueryMsg, RewardType, ScheduleFunder, ScheduleResponse,
+    VestingRewardResponse, MAX_PAGE_LIMIT,
+};
 
 use crate::error::ContractError;
 use crate::state::{
-    list_pool_stakers, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CONFIG,
-    EXTERNAL_REWARD_SCHEDULES, POOLS,
+    list_pool_stakers, list_pool_stakers_by_amount, PoolInfo, UserInfo, ACTIVE_POOLS,
+    BLOCKED_TOKENS, BLOCK_REWARD_SCHEDULES, CLAIM_OPERATORS, CONFIG, CREATOR_SCHEDULES,
+    EMISSION_SCALES, EXTERNAL_REWARD_SCHEDULES, PAUSED_POOLS, POOLED_SCHEDULES, POOLS,
+    PULLED_ASTRO, RECEIPT_DENOMS, REFERRED_TVL, REFERRERS, REWARD_METADATA, REWARD_RECEIVERS,
+    REWARD_VESTING_DURATIONS, SCHEDULE_FUNDERS, STAKERS_INDEX_CURSOR, STAKING_HOOKS,
+    UNSETTLED_REWARDS, VESTING_REWARDS,
 };
 use crate::utils::{asset_info_key, from_key_to_asset_info};
 
@@ -31,6 +39,76 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::PendingRewards { lp_token, user } => Ok(to_json_binary(&query_pending_rewards(
             deps, env, user, lp_token,
         )?)?),
+        QueryMsg::SimulateClaim { user, lp_tokens } => Ok(to_json_binary(&query_simulate_claim(
+            deps, env, user, lp_tokens,
+        )?)?),
+        QueryMsg::PendingRewardsWithMetadata { lp_token, user } => {
+            let rewards = query_pending_rewards(deps, env, user, lp_token)?
+                .into_iter()
+                .map(|reward| {
+                    let metadata =
+                        REWARD_METADATA.may_load(deps.storage, &asset_info_key(&reward.info))?;
+                    Ok(PendingRewardResponse { reward, metadata })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&rewards)?)
+        }
+        QueryMsg::RewardMetadata { reward } => {
+            let reward_asset = determine_asset_info(&reward, deps.api)?;
+            Ok(to_json_binary(
+                &REWARD_METADATA.may_load(deps.storage, &asset_info_key(&reward_asset))?,
+            )?)
+        }
+        QueryMsg::CreatorSchedules {
+            lp_token,
+            reward,
+            creator,
+        } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let reward_asset = determine_asset_info(&reward, deps.api)?;
+            let creator = deps.api.addr_validate(&creator)?;
+            let block_ts = env.block.time.seconds();
+            let schedules = CREATOR_SCHEDULES
+                .may_load(deps.storage, (&lp_asset, &reward_asset, &creator))?
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|schedule| schedule.end_ts > block_ts)
+                .collect_vec();
+            Ok(to_json_binary(&schedules)?)
+        }
+        QueryMsg::PooledSchedule { schedule_id } => Ok(to_json_binary(
+            &POOLED_SCHEDULES
+                .may_load(deps.storage, schedule_id)?
+                .ok_or(ContractError::ScheduleNotFound { schedule_id })?,
+        )?),
+        QueryMsg::ScheduleFunders {
+            schedule_id,
+            start_after,
+            limit,
+        } => {
+            let start_after = start_after
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?;
+            let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+            let funders = SCHEDULE_FUNDERS
+                .prefix(schedule_id)
+                .range(
+                    deps.storage,
+                    start_after.as_ref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit as usize)
+                .map(|item| {
+                    item.map(|(funder, (funded, refunded))| ScheduleFunder {
+                        funder,
+                        funded,
+                        refunded,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&funders)?)
+        }
         QueryMsg::RewardInfo { lp_token } => {
             let lp_asset = determine_asset_info(&lp_token, deps.api)?;
             let mut pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
@@ -58,6 +136,31 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             let stakers = list_pool_stakers(deps.storage, &lp_asset, start_after, limit)?;
             Ok(to_json_binary(&stakers)?)
         }
+        QueryMsg::PoolStakersByAmount {
+            lp_token,
+            start_after,
+            limit,
+        } => {
+            ensure!(
+                !STAKERS_INDEX_CURSOR.exists(deps.storage),
+                ContractError::StakersIndexNotBuilt {}
+            );
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let start_after = start_after
+                .map(|staker| -> StdResult<_> {
+                    Ok((
+                        deps.api.addr_validate(&staker.user)?,
+                        staker.amount,
+                        staker.cumulative_amount,
+                    ))
+                })
+                .transpose()?;
+            let stakers = list_pool_stakers_by_amount(deps.storage, &lp_asset, start_after, limit)?;
+            Ok(to_json_binary(&PoolStakersByAmountResponse {
+                block_height: env.block.height,
+                stakers,
+            })?)
+        }
         QueryMsg::IsFeeExpected { lp_token, reward } => {
             let reward_asset = determine_asset_info(&reward, deps.api)?;
             let config = CONFIG.load(deps.storage)?;
@@ -101,6 +204,19 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             start_after,
             limit,
         )?)?),
+        QueryMsg::BlockRewardSchedules {
+            reward,
+            lp_token,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_block_reward_schedules(
+            deps,
+            env,
+            reward,
+            lp_token,
+            start_after,
+            limit,
+        )?)?),
         QueryMsg::ListPools { start_after, limit } => {
             Ok(to_json_binary(&list_pools(deps, start_after, limit)?)?)
         }
@@ -112,9 +228,151 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
                 .collect_vec();
             Ok(to_json_binary(&pools)?)
         }
+        QueryMsg::VestingRewards { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            let block_ts = env.block.time.seconds();
+            let positions = VESTING_REWARDS
+                .prefix(&user)
+                .range_raw(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    let (_, mut position) = item?;
+                    position.update(block_ts)?;
+                    Ok(VestingRewardResponse {
+                        claimable: position.vested,
+                        locked: position.locked(),
+                        end_ts: position.end_ts(),
+                        reward: position.reward,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&positions)?)
+        }
+        QueryMsg::RewardVestingDuration { lp_token, reward } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let reward_asset = determine_asset_info(&reward, deps.api)?;
+            let duration = REWARD_VESTING_DURATIONS
+                .may_load(deps.storage, (&lp_asset, &reward_asset))?
+                .unwrap_or_default();
+            Ok(to_json_binary(&duration)?)
+        }
+        QueryMsg::EmissionScale { lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let scale = EMISSION_SCALES
+                .may_load(deps.storage, &lp_asset)?
+                .filter(|scale| scale.is_active(env.block.time.seconds()));
+            Ok(to_json_binary(&scale)?)
+        }
+        QueryMsg::PoolPausedAt { lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            Ok(to_json_binary(
+                &PAUSED_POOLS.may_load(deps.storage, &lp_asset)?,
+            )?)
+        }
+        QueryMsg::Referrer { lp_token, user } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let user = deps.api.addr_validate(&user)?;
+            Ok(to_json_binary(
+                &REFERRERS.may_load(deps.storage, (&lp_asset, &user))?,
+            )?)
+        }
+        QueryMsg::StakeReceiptDenom { lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            Ok(to_json_binary(
+                &RECEIPT_DENOMS.may_load(deps.storage, &lp_asset)?,
+            )?)
+        }
+        QueryMsg::StakingHooks { lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            Ok(to_json_binary(
+                &STAKING_HOOKS
+                    .may_load(deps.storage, &lp_asset)?
+                    .unwrap_or_default(),
+            )?)
+        }
+        QueryMsg::PulledAstro {} => Ok(to_json_binary(
+            &PULLED_ASTRO.may_load(deps.storage)?.unwrap_or_default(),
+        )?),
+        QueryMsg::ReferredTvl {
+            referrer,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_referred_tvl(
+            deps,
+            referrer,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::ClaimOperators {
+            user,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_claim_operators(
+            deps,
+            user,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::RewardReceiver { user, lp_token } => {
+            let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+            let user_addr = deps.api.addr_validate(&user)?;
+            Ok(to_json_binary(
+                &REWARD_RECEIVERS.may_load(deps.storage, (&user_addr, &lp_asset))?,
+            )?)
+        }
     }
 }
 
+fn query_claim_operators(
+    deps: Deps,
+    user: String,
+    start_after: Option<String>,
+    limit: Option<u8>,
+) -> StdResult<Vec<ClaimOperator>> {
+    let user = deps.api.addr_validate(&user)?;
+    let start_after = addr_opt_validate(deps.api, &start_after)?;
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    CLAIM_OPERATORS
+        .prefix(&user)
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, operator)| operator))
+        .collect()
+}
+
+fn query_referred_tvl(
+    deps: Deps,
+    referrer: String,
+    start_after: Option<String>,
+    limit: Option<u8>,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    REFERRED_TVL
+        .prefix(&referrer)
+        .range_raw(
+            deps.storage,
+            start_after
+                .map(|lp_token| determine_asset_info(&lp_token, deps.api))
+                .transpose()?
+                .as_ref()
+                .map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (lp_token, amount) = item?;
+            let lp_token = String::from_utf8(lp_token).map_err(StdError::invalid_utf8)?;
+            Ok((lp_token, amount))
+        })
+        .collect()
+}
+
 fn list_pools(
     deps: Deps,
     start_after: Option<String>,
@@ -186,7 +444,7 @@ pub fn query_pending_rewards(
 
     outstanding_rewards.extend(active_rewards);
 
-    let aggregated = outstanding_rewards
+    let mut aggregated: Vec<Asset> = outstanding_rewards
         .into_iter()
         .group_by(|asset| asset.info.clone())
         .into_iter()
@@ -196,9 +454,110 @@ pub fn query_pending_rewards(
         })
         .collect();
 
+    // Rewards accrued before the position was moved with stake receipts
+    for (_, reward) in UNSETTLED_REWARDS
+        .may_load(deps.storage, (&lp_asset, &user_addr))?
+        .unwrap_or_default()
+    {
+        match aggregated
+            .iter_mut()
+            .find(|asset| asset.info == reward.info)
+        {
+            Some(asset) => asset.amount += reward.amount,
+            None => aggregated.push(reward),
+        }
+    }
+
     Ok(aggregated)
 }
 
+/// Repeats the reward calculation of [`astroport::incentives::ExecuteMsg::ClaimRewards`]
+/// without changing the state.
+pub fn query_simulate_claim(
+    deps: Deps,
+    env: Env,
+    user: String,
+    lp_tokens: Vec<String>,
+) -> Result<ClaimSimulationResponse, ContractError> {
+    ensure!(
+        lp_tokens.iter().all_unique(),
+        ContractError::DuplicatedPoolFound {}
+    );
+
+    let user_addr = deps.api.addr_validate(&user)?;
+
+    let mut transferred: Vec<Asset> = vec![];
+    let mut vesting: Vec<Asset> = vec![];
+    let mut compounded: Vec<Asset> = vec![];
+    let mut protocol_reward_amount = Uint128::zero();
+    for lp_token in lp_tokens {
+        let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+
+        let mut pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
+        pool_info.update_rewards(deps.storage, &env, &lp_asset)?;
+
+        let mut pos = UserInfo::load_position(deps.storage, &user_addr, &lp_asset)?;
+
+        let mut claimed_external =
+            pos.claim_finished_rewards(deps.storage, &lp_asset, &pool_info)?;
+
+        // Reset user reward index for all finished schedules
+        pos.reset_user_index(deps.storage, &lp_asset, &pool_info)?;
+
+        let unsettled = UNSETTLED_REWARDS
+            .may_load(deps.storage, (&lp_asset, &user_addr))?
+            .unwrap_or_default();
+        for (is_external, reward_asset) in unsettled
+            .into_iter()
+            .chain(pool_info.calculate_rewards(&mut pos)?)
+        {
+            if is_external {
+                claimed_external.push(reward_asset);
+            } else {
+                protocol_reward_amount += reward_asset.amount;
+            }
+        }
+
+        for reward_asset in claimed_external {
+            if reward_asset.amount.is_zero() {
+                continue;
+            }
+
+            let target = if reward_asset.info == lp_asset {
+                &mut compounded
+            } else if REWARD_VESTING_DURATIONS.has(deps.storage, (&lp_asset, &reward_asset.info)) {
+                &mut vesting
+            } else {
+                &mut transferred
+            };
+            match target
+                .iter_mut()
+                .find(|asset| asset.info == reward_asset.info)
+            {
+                Some(asset) => asset.amount += reward_asset.amount,
+                None => target.push(reward_asset),
+            }
+        }
+    }
+
+    if !protocol_reward_amount.is_zero() {
+        let astro_token = CONFIG.load(deps.storage)?.astro_token;
+        match transferred
+            .iter_mut()
+            .find(|asset| asset.info == astro_token)
+        {
+            Some(asset) => asset.amount += protocol_reward_amount,
+            None => transferred.push(astro_token.with_balance(protocol_reward_amount)),
+        }
+    }
+
+    Ok(ClaimSimulationResponse {
+        transferred,
+        vesting,
+        compounded,
+    })
+}
+
 pub fn query_external_reward_schedules(
     deps: Deps,
     env: Env,
@@ -268,3 +627,69 @@ pub fn query_external_reward_schedules(
 
     Ok(results)
 }
+
+pub fn query_block_reward_schedules(
+    deps: Deps,
+    env: Env,
+    reward: String,
+    lp_token: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> Result<Vec<BlockScheduleResponse>, ContractError> {
+    let mut limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    ensure!(limit > 0, StdError::generic_err("limit must be > 0"));
+
+    let lp_asset = determine_asset_info(&lp_token, deps.api)?;
+    let reward_asset = determine_asset_info(&reward, deps.api)?;
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_asset)?;
+
+    let active = pool_info
+        .rewards
+        .iter()
+        .find(|active| {
+            matches!(&active.reward, RewardType::Ext { info, .. } if info == &reward_asset)
+        })
+        .ok_or(ContractError::RewardNotFound {
+            pool: lp_token,
+            reward,
+        })?;
+
+    let mut start_after = start_after.unwrap_or(env.block.height);
+    let mut results = vec![];
+
+    if start_after < active.next_update_height {
+        results.push(BlockScheduleResponse {
+            rpb: active.rpb,
+            start_height: env.block.height,
+            end_height: active.next_update_height,
+        });
+        limit -= 1;
+        start_after = active.next_update_height
+    }
+    let from_state = BLOCK_REWARD_SCHEDULES
+        .prefix((&lp_asset, &reward_asset))
+        .range(
+            deps.storage,
+            Some(Bound::exclusive(start_after)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .map(|(next_update_height, rpb)| {
+            let resp = BlockScheduleResponse {
+                rpb,
+                start_height: start_after,
+                end_height: next_update_height,
+            };
+            start_after = next_update_height;
+
+            resp
+        });
+
+    results.extend(from_state);
+
+    Ok(results)
+}
@@ -1,14 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal256, Env, Order, StdError, StdResult, Storage, Uint128, Uint256};
+use cosmwasm_std::{
+    Addr, Api, Decimal256, Env, Order, StdError, StdResult, Storage, Uint128, Uint256,
+};
 use cw_storage_plus::{Bound, Item, Map};
 use itertools::Itertools;
 
-use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt};
 use astroport::common::OwnershipProposal;
-use astroport::incentives::{Config, IncentivesSchedule};
-use astroport::incentives::{PoolInfoResponse, RewardInfo, RewardType};
+use astroport::incentives::{
+    BlockIncentivesSchedule, ClaimOperator, Config, CreatorSchedule, EmissionScale,
+    IncentivesSchedule, PooledSchedule, PulledAstro, RewardMetadata,
+};
+use astroport::incentives::{PoolInfoResponse, PoolStaker, RewardInfo, RewardType};
 use astroport::incentives::{MAX_PAGE_LIMIT, MAX_REWARD_TOKENS};
 
 use crate::error::ContractError;
@@ -18,6 +23,9 @@ use crate::utils::asset_info_key;
 /// General Incentives contract settings
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// ASTRO pulled from the vesting contract in advance while pull batching is enabled
+pub const PULLED_ASTRO: Item<PulledAstro> = Item::new("pulled_astro");
+
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 /// Pools which receive ASTRO emissions
@@ -35,9 +43,185 @@ pub const FINISHED_REWARD_INDEXES: Map<(&AssetInfo, u64), Vec<(AssetInfo, Decima
 pub const POOLS: Map<&AssetInfo, PoolInfo> = Map::new("pools");
 /// key: (lp_token, user_addr), value: user info
 pub const USER_INFO: Map<(&AssetInfo, &Addr), UserInfo> = Map::new("user_info");
+/// Stakers of each pool ordered by staked amount descending.
+/// key: (LP token asset, u128::MAX - staked amount, user_addr)
+pub const STAKERS_BY_AMOUNT: Map<(&AssetInfo, u128, &Addr), ()> = Map::new("stakers_by_amount");
+/// Progress of indexing positions opened before [`STAKERS_BY_AMOUNT`] was introduced.
+/// value: (pool being indexed, last indexed user). Removed once all pools are indexed.
+pub const STAKERS_INDEX_CURSOR: Item<(AssetInfo, Option<Addr>)> = Item::new("stakers_index_cursor");
+/// Referrers of staking positions. Recorded when a position is opened and removed along with the position.
+/// key: (LP token asset, user_addr), value: referrer address
+pub const REFERRERS: Map<(&AssetInfo, &Addr), Addr> = Map::new("referrers");
+/// LP tokens staked in positions referred by each referrer.
+/// key: (referrer address, LP token asset), value: referred LP tokens amount
+pub const REFERRED_TVL: Map<(&Addr, &AssetInfo), Uint128> = Map::new("referred_tvl");
 /// key: (LP token asset, reward token asset, schedule end point), value: reward per second
 pub const EXTERNAL_REWARD_SCHEDULES: Map<(&AssetInfo, &AssetInfo, u64), Decimal256> =
     Map::new("reward_schedules");
+/// key: (LP token asset, reward token asset, schedule end height), value: reward per block
+pub const BLOCK_REWARD_SCHEDULES: Map<(&AssetInfo, &AssetInfo, u64), Decimal256> =
+    Map::new("block_reward_schedules");
+
+/// Reward schedules funded by each creator. Used to cancel the remaining part of a creator's schedules.
+/// key: (LP token asset, reward token asset, creator address), value: schedules which didn't end yet
+pub const CREATOR_SCHEDULES: Map<(&AssetInfo, &AssetInfo, &Addr), Vec<CreatorSchedule>> =
+    Map::new("creator_schedules");
+
+/// The id of the last created pooled reward schedule
+pub const LAST_SCHEDULE_ID: Item<u64> = Item::new("last_schedule_id");
+/// Reward schedules which can be co-funded by several addresses. key: schedule id
+pub const POOLED_SCHEDULES: Map<u64, PooledSchedule> = Map::new("pooled_schedules");
+/// Contributions of each funder to pooled schedules.
+/// key: (schedule id, funder address), value: (funded amount, refunded amount)
+pub const SCHEDULE_FUNDERS: Map<(u64, &Addr), (Uint128, Uint128)> = Map::new("schedule_funders");
+
+/// Vesting duration of claimed external rewards. Rewards without an entry are transferred right away.
+/// key: (LP token asset, reward token asset), value: vesting duration in seconds
+pub const REWARD_VESTING_DURATIONS: Map<(&AssetInfo, &AssetInfo), u64> =
+    Map::new("reward_vesting_durations");
+/// key: (user_addr, reward token asset), value: vesting position
+pub const VESTING_REWARDS: Map<(&Addr, &AssetInfo), VestingPosition> = Map::new("vesting_rewards");
+
+/// Temporary ASTRO emission scaling set by the emission controller.
+/// Expired entries are ignored and overwritten or removed on the next scaling or restoration.
+/// key: LP token asset, value: emission scale
+pub const EMISSION_SCALES: Map<&AssetInfo, EmissionScale> = Map::new("emission_scales");
+
+/// Pools paused by the owner or guardian. Paused pools don't accept deposits and don't accrue rewards.
+/// key: LP token asset, value: pause timestamp
+pub const PAUSED_POOLS: Map<&AssetInfo, u64> = Map::new("paused_pools");
+
+/// The number of created stake receipt denoms. Used to derive short subdenoms as LP denoms are too long.
+pub const RECEIPT_DENOMS_COUNT: Item<u64> = Item::new("receipt_denoms_count");
+/// Stake receipt denoms. key: LP token asset, value: tokenfactory denom
+pub const RECEIPT_DENOMS: Map<&AssetInfo, String> = Map::new("receipt_denoms");
+/// Reverse index of [`RECEIPT_DENOMS`]. key: tokenfactory denom, value: LP token asset
+pub const RECEIPT_POOLS: Map<&str, AssetInfo> = Map::new("receipt_pools");
+/// Rewards accrued by positions moved with stake receipts. They are paid out on the next claim.
+/// key: (LP token asset, user), value: (is external reward, reward asset)
+pub const UNSETTLED_REWARDS: Map<(&AssetInfo, &Addr), Vec<(bool, Asset)>> =
+    Map::new("unsettled_rewards");
+/// Stake receipts sent to this contract by users. They are burned by withdrawals
+/// and the rest is returned to the user. key: (user, receipt denom)
+pub const RECEIVED_RECEIPTS: Map<(&Addr, &str), Uint128> = Map::new("received_receipts");
+/// Contracts notified about position changes and claims. key: LP token asset
+pub const STAKING_HOOKS: Map<&AssetInfo, Vec<Addr>> = Map::new("staking_hooks");
+/// Addresses approved to claim rewards on behalf of users. key: (user, operator)
+pub const CLAIM_OPERATORS: Map<(&Addr, &Addr), ClaimOperator> = Map::new("claim_operators");
+/// Addresses receiving rewards of users' positions instead of them. key: (user, LP token asset)
+pub const REWARD_RECEIVERS: Map<(&Addr, &AssetInfo), Addr> = Map::new("reward_receivers");
+
+/// ASTRO emission parameters which took effect at a specific time.
+/// Changes of emission parameters don't touch pools right away. Each pool applies pending checkpoints
+/// on its next update in [`PoolInfo::update_rewards`].
+#[cw_serde]
+pub struct EmissionCheckpoint {
+    /// Time when these parameters took effect
+    pub ts: u64,
+    /// ASTRO token. Kept here so pools don't need to load the config when applying the checkpoint
+    pub astro_token: AssetInfo,
+    /// Total amount of ASTRO rewards per second
+    pub astro_per_second: Uint128,
+    /// Total allocation points of all active pools
+    pub total_alloc_points: Uint128,
+    /// Pools which receive ASTRO emissions along with their allocation points
+    pub active_pools: Vec<(AssetInfo, Uint128)>,
+}
+
+impl EmissionCheckpoint {
+    /// ASTRO reward per second of a specific pool or None if the pool is not active.
+    /// The pool emission scale is applied if it is in effect at `block_ts`.
+    pub fn pool_rps(
+        &self,
+        lp_asset: &AssetInfo,
+        scale: Option<&EmissionScale>,
+        block_ts: u64,
+    ) -> Option<Decimal256> {
+        let (_, alloc_points) = self
+            .active_pools
+            .iter()
+            .find(|(lp_token, _)| lp_token == lp_asset)?;
+
+        let alloc_points = match scale {
+            Some(scale) if scale.is_active(block_ts) => *alloc_points * scale.factor,
+            _ => *alloc_points,
+        };
+
+        Some(Decimal256::from_ratio(
+            self.astro_per_second * alloc_points,
+            self.total_alloc_points,
+        ))
+    }
+}
+
+/// History of ASTRO emission parameters.
+/// key: checkpoint id (incremented by 1 starting from 0), value: emission checkpoint
+pub const EMISSION_CHECKPOINTS: Map<u64, EmissionCheckpoint> = Map::new("emission_checkpoints");
+/// Pools ordered by the last emission checkpoint they applied. Used to find the most outdated pools.
+/// key: (checkpoint id, LP token asset)
+pub const POOLS_BY_CHECKPOINT: Map<(u64, &AssetInfo), ()> = Map::new("pools_by_checkpoint");
+
+/// Returns the id of the latest emission checkpoint.
+pub fn last_emission_checkpoint(storage: &dyn Storage) -> StdResult<u64> {
+    EMISSION_CHECKPOINTS
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Saves current ASTRO emission parameters from config and active pools as a new checkpoint.
+/// Pools pick them up lazily thus this function is O(1) regardless of the number of pools.
+pub fn save_emission_checkpoint(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    active_pools: &[(AssetInfo, Uint128)],
+) -> StdResult<u64> {
+    let id = match EMISSION_CHECKPOINTS
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+    {
+        Some(last_id) => last_id + 1,
+        None => 0,
+    };
+
+    EMISSION_CHECKPOINTS.save(
+        storage,
+        id,
+        &EmissionCheckpoint {
+            ts: env.block.time.seconds(),
+            astro_token: config.astro_token.clone(),
+            astro_per_second: config.astro_per_second,
+            total_alloc_points: config.total_alloc_points,
+            active_pools: active_pools.to_vec(),
+        },
+    )?;
+
+    Ok(id)
+}
+
+/// Returns pool allocation points with the emission scale applied if it is still in effect.
+pub fn scaled_alloc_points(
+    storage: &dyn Storage,
+    env: &Env,
+    lp_asset: &AssetInfo,
+    alloc_points: Uint128,
+) -> StdResult<Uint128> {
+    match EMISSION_SCALES.may_load(storage, lp_asset)? {
+        Some(scale) if scale.is_active(env.block.time.seconds()) => Ok(alloc_points * scale.factor),
+        _ => Ok(alloc_points),
+    }
+}
+
+/// The first address which incentivized a pool with a specific reward token.
+/// key: binary representing [`AssetInfo`] converted with [`asset_info_key`], value: creator address
+pub const REWARD_CREATORS: Map<&[u8], Addr> = Map::new("reward_creators");
+
+/// Display metadata of reward tokens.
+/// key: binary representing [`AssetInfo`] converted with [`asset_info_key`], value: reward metadata
+pub const REWARD_METADATA: Map<&[u8], RewardMetadata> = Map::new("reward_metadata");
 
 /// Accumulates all orphaned rewards i.e. those which were added to a pool
 /// but this pool never received any LP tokens deposits.
@@ -75,6 +259,29 @@ impl RewardInfoExt for RewardInfo {
     }
 }
 
+/// Applies rewards collected for a reward token since the last update.
+/// Rewards of a paused pool are forfeited, rewards of a pool without stakers are orphaned.
+fn distribute_collected_rewards(
+    reward_info: &mut RewardInfo,
+    forfeited_rewards: &mut HashMap<AssetInfo, Decimal256>,
+    paused: bool,
+    total_lp: Uint128,
+    collected_rewards: Decimal256,
+) {
+    if paused {
+        if let RewardType::Ext { info, .. } = &reward_info.reward {
+            *forfeited_rewards.entry(info.clone()).or_default() += collected_rewards;
+        }
+    } else if total_lp.is_zero() {
+        reward_info.orphaned += collected_rewards;
+    } else {
+        // Allowing the first depositor to claim orphaned rewards
+        reward_info.index +=
+            (reward_info.orphaned + collected_rewards) / Decimal256::from_ratio(total_lp, 1u8);
+        reward_info.orphaned = Decimal256::zero();
+    }
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct PoolInfo {
@@ -84,24 +291,124 @@ pub struct PoolInfo {
     pub rewards: Vec<RewardInfo>,
     /// Last time when reward indexes were updated
     pub last_update_ts: u64,
+    /// Last block height when reward indexes were updated
+    #[serde(default)]
+    pub last_update_height: u64,
+    /// The last applied ASTRO emission checkpoint. See [`EMISSION_CHECKPOINTS`]
+    #[serde(default)]
+    pub emission_checkpoint: u64,
+    /// Emission checkpoint this pool had in state. Used to keep [`POOLS_BY_CHECKPOINT`] index in sync.
+    /// NOTE: this is not part of serialized structure in state!
+    #[serde(skip)]
+    pub stored_checkpoint: Option<u64>,
     /// Rewards to remove; In-memory hash map to avoid unnecessary state writes;
     /// Key: reward type, value: (reward index, orphaned rewards)
     /// NOTE: this is not part of serialized structure in state!
     #[serde(skip)]
     pub rewards_to_remove: HashMap<RewardType, (Decimal256, Decimal256)>,
+    /// External rewards which were not accrued because the pool is paused;
+    /// In-memory hash map which is moved to ORPHANED_REWARDS on save.
+    /// Key: reward asset, value: forfeited amount
+    /// NOTE: this is not part of serialized structure in state!
+    #[serde(skip)]
+    pub forfeited_rewards: HashMap<AssetInfo, Decimal256>,
 }
 
 impl PoolInfo {
     /// Loop over all rewards and update their indexes according to the amount of LP tokens staked and rewards per second.
     /// If multiple schedules for a specific reward passed since the last update, aggregate all rewards.
     /// Move to the next schedule if it's time to do so or remove reward from pool info if there are no more schedules left.
+    /// ASTRO emission checkpoints saved since the last update are applied one by one at their timestamps
+    /// thus emission changes never need to touch all pools at once.
+    /// If the pool emission scale expired since the last update, rewards are accrued with scaled ASTRO emissions
+    /// up to the expiration time and with full emissions afterwards.
+    /// Indexes of a paused pool stay frozen. Schedules still move forward while external rewards
+    /// of the paused period are forfeited and ASTRO rewards are not accrued at all.
+    /// Block based schedules are accrued first so finished rewards are removed only when
+    /// both their time and block based schedules are over.
     pub fn update_rewards(
         &mut self,
         storage: &dyn Storage,
         env: &Env,
         lp_asset: &AssetInfo,
     ) -> StdResult<()> {
-        let block_ts = env.block.time.seconds();
+        let paused = PAUSED_POOLS.has(storage, lp_asset);
+        let scale = EMISSION_SCALES.may_load(storage, lp_asset)?;
+
+        self.accrue_block_rewards(storage, lp_asset, paused, env.block.height)?;
+
+        let checkpoints = EMISSION_CHECKPOINTS
+            .range(
+                storage,
+                Some(Bound::exclusive(self.emission_checkpoint)),
+                None,
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?;
+        for (id, checkpoint) in checkpoints {
+            self.accrue_until(storage, lp_asset, paused, scale.as_ref(), checkpoint.ts)?;
+            self.apply_emission_checkpoint(lp_asset, &checkpoint, scale.as_ref(), checkpoint.ts);
+            self.emission_checkpoint = id;
+        }
+
+        self.accrue_until(
+            storage,
+            lp_asset,
+            paused,
+            scale.as_ref(),
+            env.block.time.seconds(),
+        )
+    }
+
+    /// Accrue rewards up to `block_ts` taking into account the emission scale expiration.
+    /// The emission scale is loaded once by the caller as it is checked on every accrual.
+    fn accrue_until(
+        &mut self,
+        storage: &dyn Storage,
+        lp_asset: &AssetInfo,
+        paused: bool,
+        scale: Option<&EmissionScale>,
+        block_ts: u64,
+    ) -> StdResult<()> {
+        if let Some(scale) = scale {
+            if self.last_update_ts < scale.expires_at && scale.expires_at <= block_ts {
+                self.accrue_rewards(storage, lp_asset, paused, scale.expires_at)?;
+
+                let checkpoint = EMISSION_CHECKPOINTS.load(storage, self.emission_checkpoint)?;
+                self.apply_emission_checkpoint(
+                    lp_asset,
+                    &checkpoint,
+                    Some(scale),
+                    scale.expires_at,
+                );
+            }
+        }
+
+        self.accrue_rewards(storage, lp_asset, paused, block_ts)
+    }
+
+    /// Set ASTRO reward per second according to the emission checkpoint.
+    /// ASTRO rewards are disabled if the pool is not active at this checkpoint.
+    fn apply_emission_checkpoint(
+        &mut self,
+        lp_asset: &AssetInfo,
+        checkpoint: &EmissionCheckpoint,
+        scale: Option<&EmissionScale>,
+        block_ts: u64,
+    ) {
+        match checkpoint.pool_rps(lp_asset, scale, block_ts) {
+            Some(rps) => self.set_astro_rps(&checkpoint.astro_token, rps),
+            None => self.disable_astro_rewards(),
+        }
+    }
+
+    fn accrue_rewards(
+        &mut self,
+        storage: &dyn Storage,
+        lp_asset: &AssetInfo,
+        paused: bool,
+        block_ts: u64,
+    ) -> StdResult<()> {
         let time_passed: Uint128 = block_ts.saturating_sub(self.last_update_ts).into();
 
         if time_passed.is_zero() {
@@ -125,7 +432,10 @@ impl PoolInfo {
                 if next_update_ts <= block_ts {
                     // Schedule ended. Collect leftovers from the last update time
                     collected_rewards += reward_info.rps
-                        * Decimal256::from_ratio(next_update_ts - self.last_update_ts, 1u8);
+                        * Decimal256::from_ratio(
+                            next_update_ts.saturating_sub(self.last_update_ts),
+                            1u8,
+                        );
 
                     // Find which passed schedules should be processed (can be multiple ones)
                     let schedules = EXTERNAL_REWARD_SCHEDULES.prefix((lp_asset, info)).range(
@@ -157,23 +467,31 @@ impl PoolInfo {
 
                     // Check there are neither active nor upcoming schedules left
                     if next_update_ts <= block_ts {
-                        // Remove reward from pool info
-                        need_remove = true;
                         reward_info.rps = Decimal256::zero();
+
+                        if reward_info.next_update_height > self.last_update_height {
+                            // Block based schedules keep the reward in the pool
+                            reward_info.reward = RewardType::Ext {
+                                info: info.clone(),
+                                next_update_ts: block_ts,
+                            };
+                        } else {
+                            // Remove reward from pool info
+                            need_remove = true;
+                        }
                     }
                 }
             }
 
             collected_rewards += reward_info.rps * Decimal256::from_ratio(time_passed_inner, 1u8);
 
-            if self.total_lp.is_zero() {
-                reward_info.orphaned += collected_rewards;
-            } else {
-                // Allowing the first depositor to claim orphaned rewards
-                reward_info.index += (reward_info.orphaned + collected_rewards)
-                    / Decimal256::from_ratio(self.total_lp, 1u8);
-                reward_info.orphaned = Decimal256::zero();
-            }
+            distribute_collected_rewards(
+                reward_info,
+                &mut self.forfeited_rewards,
+                paused,
+                self.total_lp,
+                collected_rewards,
+            );
 
             if need_remove {
                 self.rewards_to_remove.insert(
@@ -187,7 +505,80 @@ impl PoolInfo {
         self.rewards
             .retain(|r| !self.rewards_to_remove.contains_key(&r.reward));
 
-        self.last_update_ts = env.block.time.seconds();
+        self.last_update_ts = block_ts;
+
+        Ok(())
+    }
+
+    /// Accrue external rewards of block based schedules up to `block_height`.
+    /// Multiple schedules which passed since the last update are aggregated.
+    /// Unlike [`Self::accrue_rewards`], rewards are never removed here as they might still have time based schedules.
+    fn accrue_block_rewards(
+        &mut self,
+        storage: &dyn Storage,
+        lp_asset: &AssetInfo,
+        paused: bool,
+        block_height: u64,
+    ) -> StdResult<()> {
+        if block_height <= self.last_update_height {
+            return Ok(());
+        }
+
+        for reward_info in self.rewards.iter_mut() {
+            // Skip rewards without active block based schedules
+            if reward_info.next_update_height <= self.last_update_height {
+                continue;
+            }
+
+            let RewardType::Ext { info, .. } = &reward_info.reward else {
+                continue;
+            };
+
+            let mut collected_rewards = Decimal256::zero();
+            let mut from_height = self.last_update_height;
+
+            if reward_info.next_update_height <= block_height {
+                // Schedule ended. Collect leftovers from the last update height
+                collected_rewards += reward_info.rpb
+                    * Decimal256::from_ratio(reward_info.next_update_height - from_height, 1u8);
+                from_height = reward_info.next_update_height;
+                reward_info.rpb = Decimal256::zero();
+
+                // Process passed schedules one by one until we find the active one
+                let schedules = BLOCK_REWARD_SCHEDULES.prefix((lp_asset, info)).range(
+                    storage,
+                    Some(Bound::exclusive(from_height)),
+                    None,
+                    Order::Ascending,
+                );
+                for period in schedules {
+                    let (update_height, period_reward_per_block) = period?;
+                    reward_info.next_update_height = update_height;
+
+                    if update_height > block_height {
+                        reward_info.rpb = period_reward_per_block;
+                        break;
+                    }
+
+                    collected_rewards += period_reward_per_block
+                        * Decimal256::from_ratio(update_height - from_height, 1u8);
+                    from_height = update_height;
+                }
+            }
+
+            collected_rewards +=
+                reward_info.rpb * Decimal256::from_ratio(block_height - from_height, 1u8);
+
+            distribute_collected_rewards(
+                reward_info,
+                &mut self.forfeited_rewards,
+                paused,
+                self.total_lp,
+                collected_rewards,
+            );
+        }
+
+        self.last_update_height = block_height;
 
         Ok(())
     }
@@ -209,31 +600,30 @@ impl PoolInfo {
 
     /// Set astro per second for this pool according to alloc points and general astro per second value
     pub fn set_astro_rewards(&mut self, config: &Config, alloc_points: Uint128) {
-        if let Some(astro_reward_info) = self.rewards.iter_mut().find(|r| !r.reward.is_external()) {
-            astro_reward_info.rps = Decimal256::from_ratio(
+        self.set_astro_rps(
+            &config.astro_token,
+            Decimal256::from_ratio(
                 config.astro_per_second * alloc_points,
                 config.total_alloc_points,
-            );
+            ),
+        )
+    }
+
+    fn set_astro_rps(&mut self, astro_token: &AssetInfo, rps: Decimal256) {
+        if let Some(astro_reward_info) = self.rewards.iter_mut().find(|r| !r.reward.is_external()) {
+            astro_reward_info.rps = rps;
         } else {
             self.rewards.push(RewardInfo {
-                reward: RewardType::Int(config.astro_token.clone()),
-                rps: Decimal256::from_ratio(
-                    config.astro_per_second * alloc_points,
-                    config.total_alloc_points,
-                ),
+                reward: RewardType::Int(astro_token.clone()),
+                rps,
                 index: Default::default(),
                 orphaned: Default::default(),
+                rpb: Default::default(),
+                next_update_height: Default::default(),
             });
         }
     }
 
-    /// Check whether this pools receiving ASTRO emissions
-    pub fn is_active_pool(&self) -> bool {
-        self.rewards
-            .iter()
-            .any(|r| !r.reward.is_external() && !r.rps.is_zero())
-    }
-
     /// This function disables ASTRO rewards in a specific pool.
     /// We must keep ASTRO schedule even tho reward per second becomes zero
     /// because users still should be able to claim outstanding rewards according to indexes.
@@ -299,7 +689,14 @@ impl PoolInfo {
 
             let mut to_save = vec![];
 
-            if next_update_ts >= schedule.end_ts {
+            if next_update_ts <= self.last_update_ts {
+                // Time based schedules are over and the reward is kept by block based ones.
+                // The new schedule becomes the active one
+                active_schedule.reward = RewardType::Ext {
+                    info: schedule.reward_info.clone(),
+                    next_update_ts: schedule.end_ts,
+                };
+            } else if next_update_ts >= schedule.end_ts {
                 // Newly added schedule is fully covered by the first schedule.
                 // Set a new break in schedule only if its end is greater
                 if next_update_ts > schedule.end_ts {
@@ -357,12 +754,127 @@ impl PoolInfo {
                 rps: schedule.rps,
                 index: Default::default(),
                 orphaned: Default::default(),
+                rpb: Default::default(),
+                next_update_height: Default::default(),
             });
         }
 
         Ok(())
     }
 
+    /// Add block based external reward to a pool. If reward already exists, update its block based schedules.
+    /// Complexity is O(m) where m - number of block based schedules that new schedule intersects.
+    /// Block based schedules are stored as (end_height, reward per block) points. Unlike time based ones they may start
+    /// in the future, thus the range of a new schedule is split into periods at existing points and
+    /// the new reward per block is added to all periods within the range.
+    /// Reward per block of the first period is kept in [`RewardInfo`] while the following periods are saved
+    /// in [`BLOCK_REWARD_SCHEDULES`].
+    pub fn incentivize_by_blocks(
+        &mut self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        lp_asset: &AssetInfo,
+        schedule: &BlockIncentivesSchedule,
+        astro_token: &AssetInfo,
+    ) -> Result<(), ContractError> {
+        let maybe_pos = self.rewards.iter().position(
+            |r| matches!(&r.reward, RewardType::Ext { info, .. } if info == &schedule.reward_info),
+        );
+
+        let pos = match maybe_pos {
+            Some(pos) => pos,
+            None => {
+                let ext_rewards_len = self
+                    .rewards
+                    .iter()
+                    .filter(|r| r.reward.is_external())
+                    .count();
+
+                // Check that we don't exceed the maximum number of reward tokens per pool.
+                // Allowing ASTRO reward to exceed this limit
+                if ext_rewards_len == MAX_REWARD_TOKENS as usize
+                    && schedule.reward_info.ne(astro_token)
+                {
+                    return Err(ContractError::TooManyRewardTokens {
+                        lp_token: lp_asset.to_string(),
+                    });
+                }
+
+                // The reward doesn't have time based schedules
+                self.rewards.push(RewardInfo {
+                    reward: RewardType::Ext {
+                        info: schedule.reward_info.clone(),
+                        next_update_ts: env.block.time.seconds(),
+                    },
+                    rps: Default::default(),
+                    index: Default::default(),
+                    orphaned: Default::default(),
+                    rpb: Default::default(),
+                    next_update_height: Default::default(),
+                });
+                self.rewards.len() - 1
+            }
+        };
+
+        // Assume update_rewards() was called before
+        let current_height = self.last_update_height;
+        let reward_info = &mut self.rewards[pos];
+
+        let mut periods = vec![];
+        if reward_info.next_update_height > current_height {
+            periods.push((reward_info.next_update_height, reward_info.rpb));
+            periods.extend(
+                BLOCK_REWARD_SCHEDULES
+                    .prefix((lp_asset, &schedule.reward_info))
+                    .range(
+                        storage,
+                        Some(Bound::exclusive(reward_info.next_update_height)),
+                        None,
+                        Order::Ascending,
+                    )
+                    .collect::<StdResult<Vec<_>>>()?,
+            );
+        }
+
+        // Split periods at the new schedule bounds and add its reward per block within them
+        let mut existing = periods.iter().peekable();
+        let new_periods = periods
+            .iter()
+            .map(|(end_height, _)| *end_height)
+            .chain([schedule.start_height, schedule.end_height])
+            .filter(|height| *height > current_height)
+            .sorted()
+            .dedup()
+            .map(|height| {
+                while matches!(existing.peek(), Some((end_height, _)) if *end_height < height) {
+                    existing.next();
+                }
+                let rpb = existing.peek().map(|(_, rpb)| *rpb).unwrap_or_default();
+
+                if schedule.start_height < height && height <= schedule.end_height {
+                    (height, rpb + schedule.rpb)
+                } else {
+                    (height, rpb)
+                }
+            })
+            .collect_vec();
+
+        if let Some(((next_update_height, rpb), upcoming)) = new_periods.split_first() {
+            reward_info.next_update_height = *next_update_height;
+            reward_info.rpb = *rpb;
+
+            for (update_height, rpb) in upcoming {
+                BLOCK_REWARD_SCHEDULES.save(
+                    storage,
+                    (lp_asset, &schedule.reward_info, *update_height),
+                    rpb,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Deregister specific reward from pool. Calculate accrued rewards at this point. Calculate remaining rewards
     /// (with those which didn't start yet) and remove upcoming schedules.
     /// Complexity is either O(1) or O(m) depending on bypass_upcoming_schedules toggle,
@@ -424,24 +936,164 @@ impl PoolInfo {
                 })
         }
 
+        // Collect rewards of active and upcoming block based schedules
+        if reward_info.next_update_height > self.last_update_height {
+            remaining += reward_info.rpb
+                * Decimal256::from_ratio(
+                    reward_info.next_update_height - self.last_update_height,
+                    1u8,
+                );
+            BLOCK_REWARD_SCHEDULES.remove(
+                storage,
+                (lp_asset, reward_asset, reward_info.next_update_height),
+            );
+
+            if !bypass_upcoming_schedules {
+                let schedules = BLOCK_REWARD_SCHEDULES
+                    .prefix((lp_asset, reward_asset))
+                    .range(
+                        storage,
+                        Some(Bound::exclusive(reward_info.next_update_height)),
+                        None,
+                        Order::Ascending,
+                    )
+                    .collect::<StdResult<Vec<_>>>()?;
+
+                let mut prev_height = reward_info.next_update_height;
+                for (update_height, period_reward_per_block) in schedules {
+                    remaining += period_reward_per_block
+                        * Decimal256::from_ratio(update_height - prev_height, 1u8);
+                    prev_height = update_height;
+
+                    BLOCK_REWARD_SCHEDULES.remove(storage, (lp_asset, reward_asset, update_height));
+                }
+            }
+        }
+
         // Take orphaned rewards as well
         remaining += reward_info.orphaned;
 
         Ok(remaining.to_uint_floor().try_into()?)
     }
 
+    /// Remove the part of a creator's schedule after `stop_ts` from the reward schedules.
+    /// Splits the period which contains `stop_ts` and subtracts the creator's reward per second
+    /// from all following periods until the end of the creator's schedule.
+    /// `stop_ts` must be an epoch start strictly before the end of the creator's schedule.
+    pub fn cancel_schedule(
+        &mut self,
+        storage: &mut dyn Storage,
+        lp_asset: &AssetInfo,
+        reward_asset: &AssetInfo,
+        schedule: &CreatorSchedule,
+        stop_ts: u64,
+    ) -> Result<(), ContractError> {
+        let reward_info = self
+            .rewards
+            .iter_mut()
+            .find(|reward| matches!(&reward.reward, RewardType::Ext { info, .. } if info == reward_asset))
+            .ok_or_else(|| ContractError::RewardNotFound { pool: lp_asset.to_string(), reward: reward_asset.to_string() })?;
+
+        let next_update_ts = match &reward_info.reward {
+            RewardType::Ext { next_update_ts, .. } => *next_update_ts,
+            RewardType::Int(_) => unreachable!("Only external rewards can be cancelled"),
+        };
+
+        let mut to_save = vec![];
+        let mut from_ts = stop_ts;
+
+        if stop_ts < next_update_ts {
+            // Split the active period. Assume update_rewards() was called before
+            to_save.push((next_update_ts, reward_info.rps.checked_sub(schedule.rps)?));
+            reward_info.reward = RewardType::Ext {
+                info: reward_asset.clone(),
+                next_update_ts: stop_ts,
+            };
+            from_ts = next_update_ts;
+        } else if stop_ts > next_update_ts
+            && !EXTERNAL_REWARD_SCHEDULES.has(storage, (lp_asset, reward_asset, stop_ts))
+        {
+            // Split the upcoming period which contains stop_ts
+            let (_, rps) = EXTERNAL_REWARD_SCHEDULES
+                .prefix((lp_asset, reward_asset))
+                .range(
+                    storage,
+                    Some(Bound::exclusive(stop_ts)),
+                    None,
+                    Order::Ascending,
+                )
+                .next()
+                .transpose()?
+                .ok_or_else(|| ContractError::RewardNotFound {
+                    pool: lp_asset.to_string(),
+                    reward: reward_asset.to_string(),
+                })?;
+            to_save.push((stop_ts, rps));
+        }
+
+        let periods = EXTERNAL_REWARD_SCHEDULES
+            .prefix((lp_asset, reward_asset))
+            .range(
+                storage,
+                Some(Bound::exclusive(from_ts)),
+                Some(Bound::inclusive(schedule.end_ts)),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?;
+        for (update_ts, rps) in periods {
+            to_save.push((update_ts, rps.checked_sub(schedule.rps)?));
+        }
+
+        for (update_ts, rps) in to_save {
+            EXTERNAL_REWARD_SCHEDULES.save(storage, (lp_asset, reward_asset, update_ts), &rps)?;
+        }
+
+        Ok(())
+    }
+
     pub fn load(storage: &dyn Storage, lp_token: &AssetInfo) -> StdResult<Self> {
-        POOLS.load(storage, lp_token)
+        POOLS
+            .load(storage, lp_token)
+            .map(Self::with_stored_checkpoint)
     }
 
     pub fn may_load(storage: &dyn Storage, lp_token: &AssetInfo) -> StdResult<Option<Self>> {
-        POOLS.may_load(storage, lp_token)
+        POOLS
+            .may_load(storage, lp_token)
+            .map(|maybe_pool| maybe_pool.map(Self::with_stored_checkpoint))
+    }
+
+    /// Loads pool info or creates a new one which is in sync with the latest emission checkpoint.
+    pub fn load_or_new(storage: &dyn Storage, lp_token: &AssetInfo) -> StdResult<Self> {
+        match Self::may_load(storage, lp_token)? {
+            Some(pool_info) => Ok(pool_info),
+            None => Ok(Self {
+                emission_checkpoint: last_emission_checkpoint(storage)?,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn with_stored_checkpoint(mut self) -> Self {
+        self.stored_checkpoint = Some(self.emission_checkpoint);
+        self
     }
 
     /// Reflect changes to pool info in state. Save finished rewards indexes from in-memory hash map.
-    /// If reward schedule has orphaned rewards accumulate them in ORPHANED_REWARDS.
+    /// If reward schedule has orphaned or forfeited rewards accumulate them in ORPHANED_REWARDS.
     /// This function consumes self just to make sure it becomes unusable after calling save().
     pub fn save(self, storage: &mut dyn Storage, lp_token: &AssetInfo) -> StdResult<()> {
+        for (reward, forfeited_amount) in &self.forfeited_rewards {
+            let forfeited_amount = Uint128::try_from(forfeited_amount.to_uint_floor())?;
+            if !forfeited_amount.is_zero() {
+                ORPHANED_REWARDS.update::<_, StdError>(
+                    storage,
+                    &asset_info_key(reward),
+                    |amount| Ok(amount.unwrap_or_default() + forfeited_amount),
+                )?;
+            }
+        }
+
         if !self.rewards_to_remove.is_empty() {
             self.rewards_to_remove
                 .iter()
@@ -476,6 +1128,13 @@ impl PoolInfo {
                 })?;
         }
 
+        if self.stored_checkpoint != Some(self.emission_checkpoint) {
+            if let Some(stored_checkpoint) = self.stored_checkpoint {
+                POOLS_BY_CHECKPOINT.remove(storage, (stored_checkpoint, lp_token));
+            }
+            POOLS_BY_CHECKPOINT.save(storage, (self.emission_checkpoint, lp_token), &())?;
+        }
+
         POOLS.save(storage, lp_token, &self)
     }
 
@@ -484,6 +1143,7 @@ impl PoolInfo {
             total_lp: self.total_lp,
             rewards: self.rewards,
             last_update_ts: self.last_update_ts,
+            last_update_height: self.last_update_height,
         }
     }
 }
@@ -505,6 +1165,106 @@ pub fn list_pool_stakers(
         .collect()
 }
 
+/// List stakers of a specific pool ordered by staked amount descending.
+/// Cumulative amount continues from the cumulative amount of the `start_after` staker.
+pub fn list_pool_stakers_by_amount(
+    storage: &dyn Storage,
+    lp_token: &AssetInfo,
+    start_after: Option<(Addr, Uint128, Uint128)>,
+    limit: Option<u8>,
+) -> StdResult<Vec<PoolStaker>> {
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    let mut cumulative_amount = start_after
+        .as_ref()
+        .map(|(_, _, cumulative)| *cumulative)
+        .unwrap_or_default();
+    let start = start_after
+        .as_ref()
+        .map(|(user, amount, _)| Bound::exclusive((u128::MAX - amount.u128(), user)));
+
+    STAKERS_BY_AMOUNT
+        .prefix(lp_token)
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|item| {
+            let (inverted_amount, user) = item?;
+            let amount = Uint128::new(u128::MAX - inverted_amount);
+            cumulative_amount = cumulative_amount.checked_add(amount)?;
+
+            Ok(PoolStaker {
+                user: user.to_string(),
+                amount,
+                cumulative_amount,
+            })
+        })
+        .collect()
+}
+
+/// Adds up to `limit` existing positions to [`STAKERS_BY_AMOUNT`] continuing from [`STAKERS_INDEX_CURSOR`].
+/// Positions changed in the meantime are already indexed by [`UserInfo::save`] thus saving them again is a no-op.
+/// Returns the number of indexed positions and whether all pools are indexed.
+pub fn build_stakers_index(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    limit: u32,
+) -> StdResult<(u32, bool)> {
+    let Some((mut lp_token, mut start_after)) = STAKERS_INDEX_CURSOR.may_load(storage)? else {
+        return Ok((0, true));
+    };
+    let mut indexed = 0;
+
+    loop {
+        let stakers = USER_INFO
+            .prefix(&lp_token)
+            .range(
+                storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take((limit - indexed) as usize)
+            .collect::<StdResult<Vec<_>>>()?;
+        indexed += stakers.len() as u32;
+
+        for (user, user_info) in &stakers {
+            if !user_info.amount.is_zero() {
+                STAKERS_BY_AMOUNT.save(
+                    storage,
+                    (&lp_token, u128::MAX - user_info.amount.u128(), user),
+                    &(),
+                )?;
+            }
+        }
+
+        if indexed == limit {
+            let last_user = stakers.last().map(|(user, _)| user.clone()).or(start_after);
+            STAKERS_INDEX_CURSOR.save(storage, &(lp_token, last_user))?;
+            return Ok((indexed, false));
+        }
+
+        // The pool is fully indexed. Move on to the next one
+        let next_pool = POOLS
+            .keys_raw(
+                storage,
+                Some(Bound::ExclusiveRaw(lp_token.as_bytes().to_vec())),
+                None,
+                Order::Ascending,
+            )
+            .next();
+        match next_pool {
+            Some(key) => {
+                let key = String::from_utf8(key).map_err(StdError::invalid_utf8)?;
+                lp_token = determine_asset_info(&key, api)?;
+                start_after = None;
+            }
+            None => {
+                STAKERS_INDEX_CURSOR.remove(storage);
+                return Ok((indexed, true));
+            }
+        }
+    }
+}
+
 /// This structure is for internal use only.
 /// Used to add/subtract LP tokens from user position and pool.
 pub enum Op<T> {
@@ -672,6 +1432,46 @@ impl UserInfo {
             .collect()
     }
 
+    /// Accrues the position rewards into [`UNSETTLED_REWARDS`] instead of paying them out.
+    /// Must be followed by [`UserInfo::update_and_sync_position`] same as a regular claim.
+    pub fn accrue_unsettled_rewards(
+        &mut self,
+        storage: &mut dyn Storage,
+        user: &Addr,
+        lp_token: &AssetInfo,
+        pool_info: &PoolInfo,
+    ) -> StdResult<()> {
+        let mut accrued: Vec<(bool, Asset)> = self
+            .claim_finished_rewards(storage, lp_token, pool_info)?
+            .into_iter()
+            .map(|reward| (true, reward))
+            .collect();
+        self.reset_user_index(storage, lp_token, pool_info)?;
+        accrued.extend(pool_info.calculate_rewards(self)?);
+
+        let mut unsettled = UNSETTLED_REWARDS
+            .may_load(storage, (lp_token, user))?
+            .unwrap_or_default();
+        for (is_external, reward) in accrued {
+            if reward.amount.is_zero() {
+                continue;
+            }
+            match unsettled
+                .iter_mut()
+                .find(|(ext, asset)| *ext == is_external && asset.info == reward.info)
+            {
+                Some((_, asset)) => asset.amount += reward.amount,
+                None => unsettled.push((is_external, reward)),
+            }
+        }
+
+        if !unsettled.is_empty() {
+            UNSETTLED_REWARDS.save(storage, (lp_token, user), &unsettled)?;
+        }
+
+        Ok(())
+    }
+
     /// Add/remove LP tokens from user position and pool info.
     /// Sync reward indexes and set last claim time.
     pub fn update_and_sync_position(&mut self, operation: Op<Uint128>, pool_info: &mut PoolInfo) {
@@ -695,7 +1495,7 @@ impl UserInfo {
         self.last_claim_time = pool_info.last_update_ts;
     }
 
-    /// Save user position to state.
+    /// Save user position to state and keep [`STAKERS_BY_AMOUNT`] index and [`REFERRED_TVL`] in sync.
     /// This function consumes self just to make sure it becomes unusable after calling save().
     pub fn save(
         self,
@@ -703,11 +1503,282 @@ impl UserInfo {
         user: &Addr,
         lp_token: &AssetInfo,
     ) -> StdResult<()> {
+        let prev_amount = remove_staker_index(storage, user, lp_token)?;
+        update_referred_tvl(storage, user, lp_token, prev_amount, self.amount)?;
+        if !self.amount.is_zero() {
+            STAKERS_BY_AMOUNT.save(
+                storage,
+                (lp_token, u128::MAX - self.amount.u128(), user),
+                &(),
+            )?;
+        }
+
         USER_INFO.save(storage, (lp_token, user), &self)
     }
 
     /// Remove user position from state.
-    pub fn remove(self, storage: &mut dyn Storage, user: &Addr, lp_token: &AssetInfo) {
-        USER_INFO.remove(storage, (lp_token, user))
+    pub fn remove(
+        self,
+        storage: &mut dyn Storage,
+        user: &Addr,
+        lp_token: &AssetInfo,
+    ) -> StdResult<()> {
+        let prev_amount = remove_staker_index(storage, user, lp_token)?;
+        update_referred_tvl(storage, user, lp_token, prev_amount, Uint128::zero())?;
+        REFERRERS.remove(storage, (lp_token, user));
+        USER_INFO.remove(storage, (lp_token, user));
+
+        Ok(())
+    }
+}
+
+/// Removes the [`STAKERS_BY_AMOUNT`] entry corresponding to the stored user position (if any).
+/// Returns the amount of the stored position or zero if the position doesn't exist.
+fn remove_staker_index(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    lp_token: &AssetInfo,
+) -> StdResult<Uint128> {
+    match USER_INFO.may_load(storage, (lp_token, user))? {
+        Some(stored) => {
+            STAKERS_BY_AMOUNT.remove(storage, (lp_token, u128::MAX - stored.amount.u128(), user));
+            Ok(stored.amount)
+        }
+        None => Ok(Uint128::zero()),
+    }
+}
+
+/// Applies the change of a user position to [`REFERRED_TVL`] of the position referrer (if any).
+fn update_referred_tvl(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    lp_token: &AssetInfo,
+    prev_amount: Uint128,
+    new_amount: Uint128,
+) -> StdResult<()> {
+    if prev_amount == new_amount {
+        return Ok(());
+    }
+
+    if let Some(referrer) = REFERRERS.may_load(storage, (lp_token, user))? {
+        let referred = REFERRED_TVL
+            .may_load(storage, (&referrer, lp_token))?
+            .unwrap_or_default()
+            .checked_add(new_amount)?
+            .checked_sub(prev_amount)?;
+
+        if referred.is_zero() {
+            REFERRED_TVL.remove(storage, (&referrer, lp_token));
+        } else {
+            REFERRED_TVL.save(storage, (&referrer, lp_token), &referred)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of steps vesting end times are rounded up to within the vesting duration.
+/// Claims ending in the same step share one tranche which bounds the number of tranches in a position.
+const VESTING_END_STEPS: u64 = 20;
+
+/// Linearly vesting rewards of a user in a specific reward token.
+/// Rewards from all pools are aggregated in one position.
+#[cw_serde]
+pub struct VestingPosition {
+    /// Reward token
+    pub reward: AssetInfo,
+    /// Vested amount which is not claimed yet
+    pub vested: Uint128,
+    /// Amounts which are still vesting, each one with its own end time
+    pub tranches: Vec<VestingTranche>,
+    /// Last time when vested amount was updated
+    pub last_update_ts: u64,
+}
+
+#[cw_serde]
+pub struct VestingTranche {
+    /// Amount which is still vesting
+    pub locked: Uint128,
+    /// Time when the locked amount is vested
+    pub end_ts: u64,
+}
+
+impl VestingPosition {
+    pub fn new(reward: &AssetInfo, block_ts: u64) -> Self {
+        Self {
+            reward: reward.clone(),
+            vested: Uint128::zero(),
+            tranches: vec![],
+            last_update_ts: block_ts,
+        }
+    }
+
+    /// Total amount which is still vesting
+    pub fn locked(&self) -> Uint128 {
+        self.tranches.iter().map(|tranche| tranche.locked).sum()
+    }
+
+    /// Time when all locked rewards are vested
+    pub fn end_ts(&self) -> u64 {
+        self.tranches
+            .iter()
+            .map(|tranche| tranche.end_ts)
+            .max()
+            .unwrap_or(self.last_update_ts)
+    }
+
+    /// Moves the part of locked rewards vested since the last update to the vested amount.
+    /// Each tranche unlocks linearly until its own end time.
+    pub fn update(&mut self, block_ts: u64) -> StdResult<()> {
+        if block_ts > self.last_update_ts {
+            for tranche in &mut self.tranches {
+                let unlocked = if block_ts >= tranche.end_ts {
+                    tranche.locked
+                } else {
+                    tranche.locked.multiply_ratio(
+                        block_ts - self.last_update_ts,
+                        tranche.end_ts - self.last_update_ts,
+                    )
+                };
+                self.vested = self.vested.checked_add(unlocked)?;
+                tranche.locked = tranche.locked.checked_sub(unlocked)?;
+            }
+            self.tranches.retain(|tranche| !tranche.locked.is_zero());
+            self.last_update_ts = block_ts;
+        }
+
+        Ok(())
+    }
+
+    /// Adds new rewards to the position.
+    /// The end time is rounded up to 1/[`VESTING_END_STEPS`] of the vesting duration
+    /// and the rewards join the tranche with the same end time if it exists.
+    /// Amounts which are already vesting keep their end times.
+    pub fn add(&mut self, amount: Uint128, duration: u64, block_ts: u64) -> StdResult<()> {
+        self.update(block_ts)?;
+
+        let step = (duration / VESTING_END_STEPS).max(1);
+        let end_ts = (block_ts + duration).div_ceil(step) * step;
+        match self
+            .tranches
+            .iter_mut()
+            .find(|tranche| tranche.end_ts == end_ts)
+        {
+            Some(tranche) => tranche.locked = tranche.locked.checked_add(amount)?,
+            None => self.tranches.push(VestingTranche {
+                locked: amount,
+                end_ts,
+            }),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use cosmwasm_std::testing::{MockApi, MockStorage};
+
+    use super::*;
+
+    #[test]
+    fn test_build_stakers_index_in_batches() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+
+        let pools = [
+            AssetInfo::cw20_unchecked("lp_token_a"),
+            AssetInfo::cw20_unchecked("lp_token_b"),
+        ];
+        for (i, lp_token) in pools.iter().enumerate() {
+            POOLS
+                .save(&mut storage, lp_token, &PoolInfo::default())
+                .unwrap();
+            for j in 0..3u128 {
+                let user = Addr::unchecked(format!("user{j}"));
+                let user_info = UserInfo {
+                    // Leave one position empty in the first pool
+                    amount: Uint128::new((i as u128) * 10 + j),
+                    last_rewards_index: vec![],
+                    last_claim_time: 0,
+                };
+                USER_INFO
+                    .save(&mut storage, (lp_token, &user), &user_info)
+                    .unwrap();
+            }
+        }
+
+        // Nothing to do without a cursor
+        assert_eq!(
+            build_stakers_index(&mut storage, &api, 4).unwrap(),
+            (0, true)
+        );
+
+        STAKERS_INDEX_CURSOR
+            .save(&mut storage, &(pools[0].clone(), None))
+            .unwrap();
+
+        // The first batch spans both pools
+        assert_eq!(
+            build_stakers_index(&mut storage, &api, 4).unwrap(),
+            (4, false)
+        );
+        assert_eq!(
+            STAKERS_INDEX_CURSOR.load(&storage).unwrap(),
+            (pools[1].clone(), Some(Addr::unchecked("user0")))
+        );
+        assert_eq!(
+            build_stakers_index(&mut storage, &api, 4).unwrap(),
+            (2, true)
+        );
+        assert!(!STAKERS_INDEX_CURSOR.exists(&storage));
+
+        let stakers = list_pool_stakers_by_amount(&storage, &pools[0], None, None).unwrap();
+        assert_eq!(
+            stakers
+                .iter()
+                .map(|staker| (staker.user.as_str(), staker.amount.u128()))
+                .collect::<Vec<_>>(),
+            [("user2", 2), ("user1", 1)]
+        );
+        let stakers = list_pool_stakers_by_amount(&storage, &pools[1], None, None).unwrap();
+        assert_eq!(
+            stakers
+                .iter()
+                .map(|staker| (staker.user.as_str(), staker.amount.u128()))
+                .collect::<Vec<_>>(),
+            [("user2", 12), ("user1", 11), ("user0", 10)]
+        );
+    }
+
+    #[test]
+    fn test_vesting_position_keeps_tranche_end_times() {
+        let reward = AssetInfo::native("reward");
+        let duration = 100;
+        let mut position = VestingPosition::new(&reward, 1001);
+
+        // End time is rounded up to the 5 seconds step
+        position.add(1000u128.into(), duration, 1001).unwrap();
+        assert_eq!(position.end_ts(), 1105);
+
+        // Claims ending in the same step join the same tranche
+        position.add(100u128.into(), duration, 1003).unwrap();
+        assert_eq!(position.tranches.len(), 1);
+        assert_eq!(position.end_ts(), 1105);
+
+        // A later claim doesn't stretch the amount which is already vesting
+        position.add(1000u128.into(), duration, 1050).unwrap();
+        assert_eq!(position.tranches.len(), 2);
+        assert_eq!(position.end_ts(), 1150);
+
+        // The first tranche is fully vested at its own end time
+        position.update(1105).unwrap();
+        assert_eq!(position.tranches.len(), 1);
+        assert_eq!(position.vested.u128(), 1100 + 550);
+
+        position.update(1150).unwrap();
+        assert_eq!(position.vested.u128(), 2100);
+        assert_eq!(position.locked(), Uint128::zero());
+        assert_eq!(position.tranches, vec![]);
     }
 }
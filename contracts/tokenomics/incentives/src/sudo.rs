@@ -0,0 +1,97 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{attr, ensure, Addr, Coin, DepsMut, Env, Response, StdResult};
+
+use astroport::tokenfactory_tracker::SudoMsg;
+
+use crate::error::ContractError;
+use crate::state::{Op, PoolInfo, UserInfo, RECEIPT_POOLS, RECEIVED_RECEIPTS, UNSETTLED_REWARDS};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        // This contract is the before send hook of all stake receipt denoms.
+        // Returning an error cancels the transfer thus positions and receipts never go out of sync.
+        SudoMsg::BlockBeforeSend { from, to, amount } => {
+            transfer_position(deps, env, from, to, amount)
+        }
+        SudoMsg::TrackBeforeSend { .. } => Ok(Response::default()),
+    }
+}
+
+/// Moves the staked position along with transferred stake receipts.
+/// The hook runs with a limited gas budget, hence rewards of both positions are only accrued into
+/// [`UNSETTLED_REWARDS`] and paid out on the next claim. Staking hooks learn about the new amounts
+/// on the next position change or claim.
+/// Receipts are minted to and burned from this contract while deposits and withdrawals account positions
+/// on their own, hence transfers from this contract are ignored. Receipts sent to this contract are recorded
+/// in [`RECEIVED_RECEIPTS`], so that those not burned by a withdrawal are returned to the sender.
+fn transfer_position(
+    deps: DepsMut,
+    env: Env,
+    from: String,
+    to: String,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    let lp_token = RECEIPT_POOLS
+        .may_load(deps.storage, &amount.denom)?
+        .ok_or_else(|| ContractError::UnknownReceiptDenom {
+            denom: amount.denom.clone(),
+        })?;
+
+    if from == to || from == env.contract.address.as_str() || amount.amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    // Both addresses come from the bank module
+    let from = Addr::unchecked(from);
+    let to = Addr::unchecked(to);
+
+    if to == env.contract.address {
+        RECEIVED_RECEIPTS.update(
+            deps.storage,
+            (&from, &amount.denom),
+            |received| -> StdResult<_> {
+                Ok(received.unwrap_or_default().checked_add(amount.amount)?)
+            },
+        )?;
+        return Ok(Response::default());
+    }
+
+    let mut sender_pos = UserInfo::load_position(deps.storage, &from, &lp_token)?;
+    ensure!(
+        sender_pos.amount >= amount.amount,
+        ContractError::AmountExceedsBalance {
+            available: sender_pos.amount,
+            withdraw_amount: amount.amount,
+        }
+    );
+    let mut recipient_pos = UserInfo::may_load_position(deps.storage, &to, &lp_token)?
+        .unwrap_or_else(|| UserInfo::new(&env));
+
+    let mut pool_info = PoolInfo::load(deps.storage, &lp_token)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token)?;
+
+    sender_pos.accrue_unsettled_rewards(deps.storage, &from, &lp_token, &pool_info)?;
+    recipient_pos.accrue_unsettled_rewards(deps.storage, &to, &lp_token, &pool_info)?;
+
+    sender_pos.update_and_sync_position(Op::Sub(amount.amount), &mut pool_info);
+    recipient_pos.update_and_sync_position(Op::Add(amount.amount), &mut pool_info);
+    pool_info.save(deps.storage, &lp_token)?;
+
+    // The emptied position is kept until its unsettled rewards are claimed
+    if sender_pos.amount.is_zero() && !UNSETTLED_REWARDS.has(deps.storage, (&lp_token, &from)) {
+        sender_pos.remove(deps.storage, &from, &lp_token)?;
+    } else {
+        sender_pos.save(deps.storage, &from, &lp_token)?;
+    }
+    recipient_pos.save(deps.storage, &to, &lp_token)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "transfer_position"),
+        attr("lp_token", lp_token.to_string()),
+        attr("from", from),
+        attr("to", to),
+        attr("amount", amount.amount),
+    ]))
+}
@@ -1,46 +1,104 @@
 use cosmwasm_std::{
-    attr, ensure, wasm_execute, Addr, BankMsg, Deps, DepsMut, Env, MessageInfo, Order,
-    QuerierWrapper, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    attr, coin, ensure, to_json_binary, wasm_execute, Addr, Attribute, BankMsg, CosmosMsg,
+    Decimal256, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, ReplyOn, Response,
+    StdError, StdResult, Storage, SubMsg, Uint128,
 };
 use itertools::Itertools;
 
 use astroport::asset::{
-    determine_asset_info, pair_info_by_pool, AssetInfo, AssetInfoExt, PairInfo,
+    determine_asset_info, pair_info_by_pool, Asset, AssetInfo, AssetInfoExt, PairInfo,
 };
 use astroport::factory::PairType;
-use astroport::incentives::{Config, IncentivesSchedule, InputSchedule, MAX_ORPHANED_REWARD_LIMIT};
-use astroport::{factory, pair, vesting};
+use astroport::incentives::{
+    BlockIncentivesSchedule, Config, CreatorSchedule, Cw20Msg, ExecuteMsg, IncentivesSchedule,
+    InputSchedule, PooledSchedule, RewardType, ScheduleKind, StakingHookMsg, EPOCHS_START,
+    EPOCH_LENGTH, MAX_ORPHANED_REWARD_LIMIT, STAKING_HOOK_GAS_LIMIT,
+};
+use astroport::token_factory::{tf_before_send_hook_msg, tf_create_denom_msg, tf_mint_msg};
+use astroport::{factory, pair, staking, vesting};
 
 use crate::error::ContractError;
-use crate::reply::POST_TRANSFER_REPLY_ID;
+use crate::reply::{POST_TRANSFER_REPLY_ID, STAKING_HOOK_REPLY_ID};
 use crate::state::{
-    Op, PoolInfo, UserInfo, ACTIVE_POOLS, BLOCKED_TOKENS, CONFIG, ORPHANED_REWARDS,
+    save_emission_checkpoint, Op, PoolInfo, UserInfo, VestingPosition, ACTIVE_POOLS,
+    BLOCKED_TOKENS, CONFIG, CREATOR_SCHEDULES, EXTERNAL_REWARD_SCHEDULES, LAST_SCHEDULE_ID,
+    ORPHANED_REWARDS, PAUSED_POOLS, POOLED_SCHEDULES, PULLED_ASTRO, RECEIPT_DENOMS,
+    RECEIPT_DENOMS_COUNT, RECEIPT_POOLS, REWARD_CREATORS, REWARD_RECEIVERS,
+    REWARD_VESTING_DURATIONS, SCHEDULE_FUNDERS, STAKING_HOOKS, UNSETTLED_REWARDS, VESTING_REWARDS,
 };
 
+/// Rewards claimed from the positions which pay out to the same receiver.
+#[derive(Default)]
+struct ReceiverRewards {
+    external: Vec<Asset>,
+    vesting: Vec<(Asset, u64)>,
+    protocol: Uint128,
+}
+
 /// Claim all rewards and compose [`Response`] object containing all attributes and messages.
-/// This function doesn't mutate the state but mutates in-memory objects.
-/// Function caller is responsible for updating the state.
+/// Rewards of each position are paid to the receiver set in [`REWARD_RECEIVERS`] or to the user if it isn't set.
+/// External rewards with vesting are added to the receiver's [`VESTING_REWARDS`] positions.
+/// Rewards paid in the pool's own LP token are added to the user's position in this pool.
+/// Apart from that and the ASTRO pulled from vesting in advance, this function doesn't mutate the state
+/// but mutates in-memory objects. Function caller is responsible for updating the state.
 /// If vesting_contract is None this function reads config from state and gets vesting address.
+/// If xastro_staking is set, all claimed ASTRO is staked there with the receiver as the xASTRO receiver.
+/// If stake_lp_rewards is set, rewards paid in LP tokens of other registered pools are deposited
+/// on behalf of the receiver.
+#[allow(clippy::too_many_arguments)]
 pub fn claim_rewards(
-    storage: &dyn Storage,
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
     vesting_contract: Option<Addr>,
     env: Env,
     user: &Addr,
     pool_tuples: Vec<(&AssetInfo, &mut PoolInfo, &mut UserInfo)>,
+    xastro_staking: Option<&Addr>,
+    stake_lp_rewards: bool,
 ) -> Result<Response, ContractError> {
     let mut attrs = vec![attr("action", "claim_rewards"), attr("user", user)];
-    let mut external_rewards = vec![];
-    let mut protocol_reward_amount = Uint128::zero();
+    let mut receivers: Vec<(Addr, ReceiverRewards)> = vec![];
     for (lp_token_asset, pool_info, pos) in pool_tuples {
         attrs.push(attr("claimed_position", lp_token_asset.to_string()));
 
+        let receiver = REWARD_RECEIVERS
+            .may_load(storage, (user, lp_token_asset))?
+            .unwrap_or_else(|| user.clone());
+        if receiver != *user {
+            attrs.push(attr("reward_receiver", &receiver));
+        }
+        let ind = receivers.iter().position(|(addr, _)| *addr == receiver);
+        let rewards = match ind {
+            Some(ind) => &mut receivers[ind].1,
+            None => {
+                receivers.push((receiver, ReceiverRewards::default()));
+                &mut receivers.last_mut().unwrap().1
+            }
+        };
+
         pool_info.update_rewards(storage, &env, lp_token_asset)?;
 
+        let mut claimed_external = vec![];
+
+        // Rewards accrued before the position was moved with stake receipts
+        let unsettled = UNSETTLED_REWARDS
+            .may_load(storage, (lp_token_asset, user))?
+            .unwrap_or_default();
+        UNSETTLED_REWARDS.remove(storage, (lp_token_asset, user));
+        for (is_external, reward_asset) in unsettled {
+            attrs.push(attr("claimed_unsettled_reward", reward_asset.to_string()));
+            if is_external {
+                claimed_external.push(reward_asset);
+            } else {
+                rewards.protocol += reward_asset.amount;
+            }
+        }
+
         // Claim outstanding rewards from finished schedules
         for finished_reward in pos.claim_finished_rewards(storage, lp_token_asset, pool_info)? {
             if !finished_reward.amount.is_zero() {
                 attrs.push(attr("claimed_finished_reward", finished_reward.to_string()));
-                external_rewards.push(finished_reward);
+                claimed_external.push(finished_reward);
             }
         }
 
@@ -52,50 +110,304 @@ pub fn claim_rewards(
 
             if !reward_asset.amount.is_zero() {
                 if is_external {
-                    external_rewards.push(reward_asset);
+                    claimed_external.push(reward_asset);
                 } else {
-                    protocol_reward_amount += reward_asset.amount;
+                    rewards.protocol += reward_asset.amount;
                 }
             }
         }
 
+        let mut compounded = Uint128::zero();
+        for reward_asset in claimed_external {
+            // Rewards paid in the pool's own LP token are staked back into the position
+            if reward_asset.info == *lp_token_asset {
+                compounded += reward_asset.amount;
+                continue;
+            }
+
+            match REWARD_VESTING_DURATIONS
+                .may_load(storage, (lp_token_asset, &reward_asset.info))?
+            {
+                Some(duration) => rewards.vesting.push((reward_asset, duration)),
+                None => rewards.external.push(reward_asset),
+            }
+        }
+
+        let operation = if compounded.is_zero() {
+            Op::Noop
+        } else {
+            attrs.push(attr(
+                "compounded_reward",
+                lp_token_asset.with_balance(compounded).to_string(),
+            ));
+            Op::Add(compounded)
+        };
+
         // Sync user index with pool index. It removes all finished schedules from user info.
-        pos.update_and_sync_position(Op::Noop, pool_info);
+        pos.update_and_sync_position(operation, pool_info);
+    }
+
+    let mut messages = vec![];
+    for (receiver, rewards) in receivers {
+        messages.extend(pay_rewards(
+            storage,
+            querier,
+            &env,
+            vesting_contract.clone(),
+            &receiver,
+            rewards,
+            xastro_staking,
+            stake_lp_rewards,
+            &mut attrs,
+        )?);
     }
 
+    Ok(Response::new()
+        .add_attributes(attrs)
+        .add_submessages(messages))
+}
+
+/// Composes messages which pay claimed rewards to the receiver.
+#[allow(clippy::too_many_arguments)]
+fn pay_rewards(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    vesting_contract: Option<Addr>,
+    receiver: &Addr,
+    rewards: ReceiverRewards,
+    xastro_staking: Option<&Addr>,
+    stake_lp_rewards: bool,
+    attrs: &mut Vec<Attribute>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let ReceiverRewards {
+        mut external,
+        vesting,
+        protocol,
+    } = rewards;
+
+    // Lock rewards with vesting in receiver's vesting positions
+    let block_ts = env.block.time.seconds();
+    for (reward_asset, duration) in vesting {
+        let mut position = VESTING_REWARDS
+            .may_load(storage, (receiver, &reward_asset.info))?
+            .unwrap_or_else(|| VestingPosition::new(&reward_asset.info, block_ts));
+        position.add(reward_asset.amount, duration, block_ts)?;
+        VESTING_REWARDS.save(storage, (receiver, &reward_asset.info), &position)?;
+
+        attrs.push(attr("vesting_reward", reward_asset.to_string()));
+    }
+
+    // External ASTRO rewards are staked along with the protocol rewards
+    let mut astro_to_stake = Uint128::zero();
+    let astro_token = match xastro_staking {
+        Some(_) => {
+            let astro_token = CONFIG.load(storage)?.astro_token;
+            external.retain(|asset| {
+                if asset.info == astro_token {
+                    astro_to_stake += asset.amount;
+                    false
+                } else {
+                    true
+                }
+            });
+            Some(astro_token)
+        }
+        None => None,
+    };
+
     // Aggregating rewards by asset info.
     // This allows to reduce number of output messages thus reducing total gas cost.
-    let mut messages = external_rewards
+    let external_rewards = external
         .into_iter()
         .group_by(|asset| asset.info.clone())
         .into_iter()
-        .map(|(info, assets)| {
-            let amount: Uint128 = assets.into_iter().map(|asset| asset.amount).sum();
-            info.with_balance(amount)
-                .into_submsg(user, Some((ReplyOn::Error, POST_TRANSFER_REPLY_ID)))
-        })
-        .collect::<StdResult<Vec<_>>>()?;
+        .map(|(info, assets)| info.with_balance(assets.map(|asset| asset.amount).sum::<Uint128>()))
+        .collect_vec();
+
+    let mut messages = vec![];
+    let config = if stake_lp_rewards {
+        Some(CONFIG.load(storage)?)
+    } else {
+        None
+    };
+    for reward_asset in external_rewards {
+        match &config {
+            Some(config) if is_stakable_lp_token(storage, querier, config, &reward_asset.info) => {
+                attrs.push(attr("staked_lp_reward", reward_asset.to_string()));
+                messages.push(SubMsg::new(deposit_for_msg(env, receiver, reward_asset)?));
+            }
+            _ => messages.push(
+                reward_asset
+                    .into_submsg(receiver, Some((ReplyOn::Error, POST_TRANSFER_REPLY_ID)))?,
+            ),
+        }
+    }
 
     // Claim Astroport rewards
-    if !protocol_reward_amount.is_zero() {
-        let vesting_contract = if let Some(vesting_contract) = vesting_contract {
-            vesting_contract
+    if !protocol.is_zero() {
+        let config = CONFIG.load(storage)?;
+        let vesting_contract = vesting_contract.unwrap_or_else(|| config.vesting_contract.clone());
+        // ASTRO to be staked is claimed to this contract first
+        let recipient = if xastro_staking.is_some() {
+            astro_to_stake += protocol;
+            &env.contract.address
         } else {
-            CONFIG.load(storage)?.vesting_contract
+            receiver
         };
+        messages.extend(protocol_reward_msgs(
+            storage,
+            querier,
+            env,
+            &config,
+            &vesting_contract,
+            recipient,
+            protocol,
+        )?);
+    }
+
+    if let (Some(staking), Some(astro_token)) = (xastro_staking, astro_token) {
+        if !astro_to_stake.is_zero() {
+            attrs.push(attr("staked_astro", astro_to_stake));
+            messages.push(SubMsg::new(wasm_execute(
+                staking,
+                &staking::ExecuteMsg::Enter {
+                    receiver: Some(receiver.to_string()),
+                },
+                vec![astro_token.with_balance(astro_to_stake).as_coin()?],
+            )?));
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Returns true if the asset is an LP token of an unpaused pool registered in the factory.
+fn is_stakable_lp_token(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    asset_info: &AssetInfo,
+) -> bool {
+    if PAUSED_POOLS.has(storage, asset_info) {
+        return false;
+    }
+
+    let pair_info = match asset_info {
+        AssetInfo::Token { contract_addr } => pair_info_by_pool(&querier, contract_addr),
+        AssetInfo::NativeToken { denom } => match denom.split('/').collect_vec()[..] {
+            ["factory", lp_minter, _, ..] => {
+                querier.query_wasm_smart(lp_minter, &pair::QueryMsg::Pair {})
+            }
+            _ => return false,
+        },
+    };
+
+    pair_info
+        .and_then(|pair_info| {
+            is_pool_registered(querier, config, &pair_info, &asset_info.to_string())
+        })
+        .is_ok()
+}
+
+/// Composes a message which deposits LP tokens held by this contract on behalf of the user.
+fn deposit_for_msg(env: &Env, user: &Addr, lp_asset: Asset) -> StdResult<CosmosMsg> {
+    let msg = match &lp_asset.info {
+        AssetInfo::NativeToken { .. } => wasm_execute(
+            &env.contract.address,
+            &ExecuteMsg::Deposit {
+                recipient: Some(user.to_string()),
+                referrer: None,
+            },
+            vec![lp_asset.as_coin()?],
+        )?,
+        AssetInfo::Token { contract_addr } => wasm_execute(
+            contract_addr,
+            &cw20::Cw20ExecuteMsg::Send {
+                contract: env.contract.address.to_string(),
+                amount: lp_asset.amount,
+                msg: to_json_binary(&Cw20Msg::DepositFor(user.to_string()))?,
+            },
+            vec![],
+        )?,
+    };
+
+    Ok(msg.into())
+}
+
+/// Composes messages which pay ASTRO rewards to the recipient.
+/// ASTRO pulled from the vesting contract in advance is used first. If pull batching is enabled
+/// and pulled ASTRO doesn't cover the rewards, the epoch budget is pulled from vesting once per epoch.
+/// The remainder is claimed from vesting directly.
+fn protocol_reward_msgs(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    vesting_contract: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    let mut messages = vec![];
+    let mut pulled = PULLED_ASTRO.may_load(storage)?.unwrap_or_default();
+
+    let block_ts = env.block.time.seconds();
+    let epoch_start =
+        EPOCHS_START + block_ts.saturating_sub(EPOCHS_START) / EPOCH_LENGTH * EPOCH_LENGTH;
+    if config.astro_pull_batching && pulled.amount < amount && pulled.epoch_start < epoch_start {
+        let available: Uint128 = querier.query_wasm_smart(
+            vesting_contract,
+            &vesting::QueryMsg::AvailableAmount {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        let budget = config.astro_per_second * Uint128::from(EPOCH_LENGTH);
+        let pull_amount = budget.max(amount - pulled.amount).min(available);
+
+        if !pull_amount.is_zero() {
+            messages.push(SubMsg::new(wasm_execute(
+                vesting_contract,
+                &vesting::ExecuteMsg::Claim {
+                    recipient: None,
+                    amount: Some(pull_amount),
+                },
+                vec![],
+            )?));
+            pulled.amount += pull_amount;
+        }
+        pulled.epoch_start = epoch_start;
+        PULLED_ASTRO.save(storage, &pulled)?;
+    }
+
+    let from_pulled = pulled.amount.min(amount);
+    if !from_pulled.is_zero() {
+        pulled.amount -= from_pulled;
+        PULLED_ASTRO.save(storage, &pulled)?;
+
+        // Pulled ASTRO is already in the contract balance
+        if recipient != env.contract.address {
+            messages.push(SubMsg::new(
+                config
+                    .astro_token
+                    .with_balance(from_pulled)
+                    .into_msg(recipient)?,
+            ));
+        }
+    }
+
+    let remaining = amount - from_pulled;
+    if !remaining.is_zero() {
         messages.push(SubMsg::new(wasm_execute(
             vesting_contract,
             &vesting::ExecuteMsg::Claim {
-                recipient: Some(user.to_string()),
-                amount: Some(protocol_reward_amount),
+                recipient: Some(recipient.to_string()),
+                amount: Some(remaining),
             },
             vec![],
         )?));
     }
 
-    Ok(Response::new()
-        .add_attributes(attrs)
-        .add_submessages(messages))
+    Ok(messages)
 }
 
 /// Only factory can set the allocation points to zero for the specified pool.
@@ -114,29 +426,19 @@ pub fn deactivate_pool(
 
     let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
 
-    match PoolInfo::may_load(deps.storage, &lp_token_asset)? {
-        Some(mut pool_info) if pool_info.is_active_pool() => {
-            let mut active_pools = ACTIVE_POOLS.load(deps.storage)?;
+    let mut active_pools = ACTIVE_POOLS.load(deps.storage)?;
 
-            let (ind, _) = active_pools
-                .iter()
-                .find_position(|(lp_asset, _)| lp_asset == &lp_token_asset)
-                .unwrap();
+    match active_pools
+        .iter()
+        .position(|(lp_asset, _)| lp_asset == &lp_token_asset)
+    {
+        Some(ind) => {
             let (_, alloc_points) = active_pools.swap_remove(ind);
 
-            pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
-            pool_info.disable_astro_rewards();
-            pool_info.save(deps.storage, &lp_token_asset)?;
-
             config.total_alloc_points = config.total_alloc_points.checked_sub(alloc_points)?;
 
-            for (lp_asset, alloc_points) in &active_pools {
-                let mut pool_info = PoolInfo::load(deps.storage, lp_asset)?;
-                pool_info.update_rewards(deps.storage, &env, lp_asset)?;
-                pool_info.set_astro_rewards(&config, *alloc_points);
-                pool_info.save(deps.storage, lp_asset)?;
-            }
-
+            // Deactivated and remaining active pools pick up new emissions lazily
+            save_emission_checkpoint(deps.storage, &env, &config, &active_pools)?;
             ACTIVE_POOLS.save(deps.storage, &active_pools)?;
             CONFIG.save(deps.storage, &config)?;
 
@@ -145,7 +447,7 @@ pub fn deactivate_pool(
                 attr("lp_token", lp_token),
             ]))
         }
-        _ => Ok(Response::new()),
+        None => Ok(Response::new()),
     }
 }
 
@@ -162,16 +464,10 @@ pub fn deactivate_blocked_pools(deps: DepsMut, env: Env) -> Result<Response, Con
     let mut to_remove = vec![];
 
     for (lp_token_asset, alloc_points) in &active_pools {
-        let mut pool_info = PoolInfo::load(deps.storage, lp_token_asset)?;
-
         let pair_info = query_pair_info(deps.as_ref(), lp_token_asset)?;
 
         // check if pair type is blocked
         if blocked_pair_types.contains(&pair_info.pair_type) {
-            pool_info.update_rewards(deps.storage, &env, lp_token_asset)?;
-            pool_info.disable_astro_rewards();
-            pool_info.save(deps.storage, lp_token_asset)?;
-
             config.total_alloc_points = config.total_alloc_points.checked_sub(*alloc_points)?;
 
             to_remove.push(lp_token_asset.clone());
@@ -186,13 +482,8 @@ pub fn deactivate_blocked_pools(deps: DepsMut, env: Env) -> Result<Response, Con
     if !to_remove.is_empty() {
         active_pools.retain(|(lp_token_asset, _)| !to_remove.contains(lp_token_asset));
 
-        for (lp_asset, alloc_points) in &active_pools {
-            let mut pool_info = PoolInfo::load(deps.storage, lp_asset)?;
-            pool_info.update_rewards(deps.storage, &env, lp_asset)?;
-            pool_info.set_astro_rewards(&config, *alloc_points);
-            pool_info.save(deps.storage, lp_asset)?;
-        }
-
+        // Deactivated and remaining active pools pick up new emissions lazily
+        save_emission_checkpoint(deps.storage, &env, &config, &active_pools)?;
         ACTIVE_POOLS.save(deps.storage, &active_pools)?;
         CONFIG.save(deps.storage, &config)?;
     }
@@ -208,6 +499,10 @@ pub fn incentivize(
     lp_token: String,
     input: InputSchedule,
 ) -> Result<Response, ContractError> {
+    if let ScheduleKind::Blocks { .. } = input.kind {
+        return incentivize_by_blocks(deps, info, env, response, lp_token, input);
+    }
+
     let schedule = IncentivesSchedule::from_input(env, &input)?;
 
     let mut response = response.add_attributes([
@@ -219,19 +514,68 @@ pub fn incentivize(
     ]);
 
     let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    let config = check_incentivized_pool(
+        deps.as_ref(),
+        &lp_token,
+        &lp_token_asset,
+        &schedule.reward_info,
+    )?;
 
-    // Prohibit reward schedules with blocked token
-    if BLOCKED_TOKENS.has(deps.storage, &asset_info_key(&schedule.reward_info)) {
-        return Err(ContractError::BlockedToken {
-            token: schedule.reward_info.to_string(),
-        });
-    }
+    // Either top up an existing pooled schedule within its funding window or start a new one
+    let block_ts = env.block.time.seconds();
+    let schedule_id = match input.schedule_id {
+        Some(schedule_id) => {
+            let mut pooled_schedule = POOLED_SCHEDULES
+                .may_load(deps.storage, schedule_id)?
+                .ok_or(ContractError::ScheduleNotFound { schedule_id })?;
+            ensure!(
+                block_ts < pooled_schedule.next_epoch_start_ts,
+                ContractError::ScheduleFundingClosed { schedule_id }
+            );
+            ensure!(
+                pooled_schedule.lp_token == lp_token_asset
+                    && pooled_schedule.reward_info == schedule.reward_info
+                    && pooled_schedule.end_ts == schedule.end_ts,
+                ContractError::ScheduleMismatch { schedule_id }
+            );
 
-    let pair_info = query_pair_info(deps.as_ref(), &lp_token_asset)?;
-    let config = CONFIG.load(deps.storage)?;
-    is_pool_registered(deps.querier, &config, &pair_info, &lp_token)?;
+            pooled_schedule.funded += input.reward.amount;
+            POOLED_SCHEDULES.save(deps.storage, schedule_id, &pooled_schedule)?;
 
-    let mut pool_info = PoolInfo::may_load(deps.storage, &lp_token_asset)?.unwrap_or_default();
+            schedule_id
+        }
+        None => {
+            let schedule_id = LAST_SCHEDULE_ID
+                .may_load(deps.storage)?
+                .map_or(0, |last_id| last_id + 1);
+            LAST_SCHEDULE_ID.save(deps.storage, &schedule_id)?;
+            POOLED_SCHEDULES.save(
+                deps.storage,
+                schedule_id,
+                &PooledSchedule {
+                    lp_token: lp_token_asset.clone(),
+                    reward_info: schedule.reward_info.clone(),
+                    next_epoch_start_ts: schedule.next_epoch_start_ts,
+                    end_ts: schedule.end_ts,
+                    funded: input.reward.amount,
+                    refunded: Uint128::zero(),
+                },
+            )?;
+
+            schedule_id
+        }
+    };
+    SCHEDULE_FUNDERS.update::<_, StdError>(
+        deps.storage,
+        (schedule_id, &info.sender),
+        |contribution| {
+            let (funded, refunded) = contribution.unwrap_or_default();
+            Ok((funded + input.reward.amount, refunded))
+        },
+    )?;
+    response = response.add_attribute("schedule_id", schedule_id.to_string());
+
+    let mut pool_info = PoolInfo::load_or_new(deps.storage, &lp_token_asset)?;
     pool_info.update_rewards(deps.storage, env, &lp_token_asset)?;
 
     let rewards_number_before = pool_info.rewards.len();
@@ -241,14 +585,182 @@ pub fn incentivize(
         &schedule,
         &config.astro_token,
     )?;
+    let is_new_reward = rewards_number_before < pool_info.rewards.len();
+
+    // Track the schedule funded by the sender so they can cancel it later. Ended schedules are pruned
+    CREATOR_SCHEDULES.update::<_, StdError>(
+        deps.storage,
+        (&lp_token_asset, &schedule.reward_info, &info.sender),
+        |schedules| {
+            let mut schedules = schedules.unwrap_or_default();
+            schedules.retain(|creator_schedule| creator_schedule.end_ts > block_ts);
+            schedules.push(CreatorSchedule {
+                next_epoch_start_ts: schedule.next_epoch_start_ts,
+                end_ts: schedule.end_ts,
+                rps: schedule.rps,
+                schedule_id: Some(schedule_id),
+            });
+            Ok(schedules)
+        },
+    )?;
+
+    fund_schedule(
+        deps,
+        info,
+        env,
+        response,
+        &config,
+        pool_info,
+        lp_token,
+        &lp_token_asset,
+        &input,
+        is_new_reward,
+    )
+}
+
+/// Adds a block based reward schedule to a pool.
+/// Block based schedules are neither pooled nor tracked for cancellation.
+fn incentivize_by_blocks(
+    deps: DepsMut,
+    info: &mut MessageInfo,
+    env: &Env,
+    response: Response,
+    lp_token: String,
+    input: InputSchedule,
+) -> Result<Response, ContractError> {
+    let schedule = BlockIncentivesSchedule::from_input(env, &input)?;
+
+    let response = response.add_attributes([
+        attr("action", "incentivize"),
+        attr("lp_token", lp_token.clone()),
+        attr("start_height", schedule.start_height.to_string()),
+        attr("end_height", schedule.end_height.to_string()),
+        attr("reward", schedule.reward_info.to_string()),
+    ]);
+
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    let config = check_incentivized_pool(
+        deps.as_ref(),
+        &lp_token,
+        &lp_token_asset,
+        &schedule.reward_info,
+    )?;
+
+    let mut pool_info = PoolInfo::load_or_new(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, env, &lp_token_asset)?;
+
+    let rewards_number_before = pool_info.rewards.len();
+    pool_info.incentivize_by_blocks(
+        deps.storage,
+        env,
+        &lp_token_asset,
+        &schedule,
+        &config.astro_token,
+    )?;
+    let is_new_reward = rewards_number_before < pool_info.rewards.len();
+
+    fund_schedule(
+        deps,
+        info,
+        env,
+        response,
+        &config,
+        pool_info,
+        lp_token,
+        &lp_token_asset,
+        &input,
+        is_new_reward,
+    )
+}
+
+/// Checks that the pool can be incentivized with the reward token and returns the contract config.
+fn check_incentivized_pool(
+    deps: Deps,
+    lp_token: &str,
+    lp_token_asset: &AssetInfo,
+    reward_info: &AssetInfo,
+) -> Result<Config, ContractError> {
+    if PAUSED_POOLS.has(deps.storage, lp_token_asset) {
+        return Err(ContractError::PoolPaused {
+            lp_token: lp_token.to_string(),
+        });
+    }
+
+    // Prohibit reward schedules with blocked token
+    if BLOCKED_TOKENS.has(deps.storage, &asset_info_key(reward_info)) {
+        return Err(ContractError::BlockedToken {
+            token: reward_info.to_string(),
+        });
+    }
+
+    let pair_info = query_pair_info(deps, lp_token_asset)?;
+    let config = CONFIG.load(deps.storage)?;
+    is_pool_registered(deps.querier, &config, &pair_info, lp_token)?;
+
+    Ok(config)
+}
+
+/// Checks vesting settings of a new schedule, takes the incentivization fee for new reward tokens
+/// and the reward itself from the sender. Saves the updated pool info.
+#[allow(clippy::too_many_arguments)]
+fn fund_schedule(
+    deps: DepsMut,
+    info: &mut MessageInfo,
+    env: &Env,
+    mut response: Response,
+    config: &Config,
+    pool_info: PoolInfo,
+    lp_token: String,
+    lp_token_asset: &AssetInfo,
+    input: &InputSchedule,
+    is_new_reward: bool,
+) -> Result<Response, ContractError> {
+    let reward_info = &input.reward.info;
+
+    // Rewards paid in the pool's own LP token are compounded into stakers' positions
+    ensure!(
+        reward_info != lp_token_asset || input.vesting_duration.is_none(),
+        ContractError::CompoundingRewardVesting {
+            lp_token: lp_token.clone(),
+        }
+    );
+
+    // Vesting duration is defined by the schedule which adds the reward to the pool.
+    // Following schedules must keep the same vesting duration.
+    let vesting_key = (lp_token_asset, reward_info);
+    if is_new_reward {
+        match input.vesting_duration {
+            Some(duration) => {
+                REWARD_VESTING_DURATIONS.save(deps.storage, vesting_key, &duration)?
+            }
+            None => REWARD_VESTING_DURATIONS.remove(deps.storage, vesting_key),
+        }
+    } else {
+        let expected = REWARD_VESTING_DURATIONS
+            .may_load(deps.storage, vesting_key)?
+            .unwrap_or_default();
+        ensure!(
+            input.vesting_duration.unwrap_or_default() == expected,
+            ContractError::VestingDurationMismatch {
+                lp_token: lp_token.clone(),
+                reward: reward_info.to_string(),
+                expected,
+            }
+        );
+    }
+
+    // The first incentivizor of an external reward token becomes its creator
+    let reward_key = asset_info_key(reward_info);
+    if reward_info != &config.astro_token && !REWARD_CREATORS.has(deps.storage, &reward_key) {
+        REWARD_CREATORS.save(deps.storage, &reward_key, &info.sender)?;
+    }
 
     // Check whether this is a new external reward token.
     // 3rd parties are encouraged to keep endless schedules without breaks even with the small rewards.
     // Otherwise, reward token will be removed from the pool info and go to outstanding rewards.
     // Next schedules with the same token will be considered as "new".
     // ASTRO rewards don't require incentivize fee.
-    if rewards_number_before < pool_info.rewards.len() && schedule.reward_info != config.astro_token
-    {
+    if is_new_reward && reward_info != &config.astro_token {
         // If fee set we expect to receive it
         if let Some(incentivization_fee_info) = &config.incentivization_fee_info {
             info.funds
@@ -264,7 +776,7 @@ pub fn incentivize(
                 .ok_or_else(|| ContractError::IncentivizationFeeExpected {
                     fee: incentivization_fee_info.fee.to_string(),
                     lp_token: lp_token.clone(),
-                    new_reward_token: schedule.reward_info.to_string(),
+                    new_reward_token: reward_info.to_string(),
                 })?;
 
             // Send fee to fee receiver
@@ -275,37 +787,156 @@ pub fn incentivize(
         }
     }
 
-    // Assert that we received reward tokens
-    match &schedule.reward_info {
-        AssetInfo::Token { contract_addr } => {
-            response = response.add_message(wasm_execute(
-                contract_addr,
-                &cw20::Cw20ExecuteMsg::TransferFrom {
-                    owner: info.sender.to_string(),
-                    recipient: env.contract.address.to_string(),
-                    amount: input.reward.amount,
-                },
-                vec![],
-            )?);
-        }
+    let response = receive_reward(info, env, response, &input.reward, lp_token)?;
+
+    pool_info.save(deps.storage, lp_token_asset)?;
+
+    Ok(response)
+}
+
+/// Takes reward tokens from the sender. Native rewards are deducted from the sent funds
+/// while cw20 rewards are transferred from the sender's allowance.
+fn receive_reward(
+    info: &mut MessageInfo,
+    env: &Env,
+    response: Response,
+    reward: &Asset,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    match &reward.info {
+        AssetInfo::Token { contract_addr } => Ok(response.add_message(wasm_execute(
+            contract_addr,
+            &cw20::Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount: reward.amount,
+            },
+            vec![],
+        )?)),
         AssetInfo::NativeToken { denom } => {
             // Mutate funds array
             info.funds
                 .iter_mut()
                 .find(|coin| coin.denom.eq(denom))
                 .and_then(|found| {
-                    found.amount = found.amount.checked_sub(input.reward.amount).ok()?;
+                    found.amount = found.amount.checked_sub(reward.amount).ok()?;
                     Some(())
                 })
                 .ok_or_else(|| ContractError::InsuffiicientRewardToken {
-                    reward: input.reward.info.to_string(),
+                    reward: reward.info.to_string(),
                     lp_token,
                 })?;
+
+            Ok(response)
         }
     }
+}
 
+/// Adds rewards to the active time based schedules of a reward token already present in the pool.
+/// The amount is spread evenly from the current block till the end of the last schedule of this token.
+/// Top-ups are tracked as regular schedules of the sender so they can be cancelled as well.
+pub fn top_up_schedule(
+    deps: DepsMut,
+    mut info: MessageInfo,
+    env: Env,
+    lp_token: String,
+    reward: Asset,
+) -> Result<Response, ContractError> {
+    let lp_token_asset = determine_asset_info(&lp_token, deps.api)?;
+    reward.info.check(deps.api)?;
+    let config = check_incentivized_pool(deps.as_ref(), &lp_token, &lp_token_asset, &reward.info)?;
+
+    let mut pool_info = PoolInfo::load_or_new(deps.storage, &lp_token_asset)?;
+    pool_info.update_rewards(deps.storage, &env, &lp_token_asset)?;
+
+    let next_update_ts = pool_info
+        .rewards
+        .iter()
+        .find_map(|r| match &r.reward {
+            RewardType::Ext {
+                info,
+                next_update_ts,
+            } if info == &reward.info => Some(*next_update_ts),
+            _ => None,
+        })
+        .ok_or_else(|| ContractError::RewardNotFound {
+            pool: lp_token.clone(),
+            reward: reward.info.to_string(),
+        })?;
+
+    // Active schedules end at the last break point of this reward
+    let block_ts = env.block.time.seconds();
+    let last_break_ts = EXTERNAL_REWARD_SCHEDULES
+        .prefix((&lp_token_asset, &reward.info))
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .unwrap_or_default();
+    let end_ts = next_update_ts.max(last_break_ts);
+    ensure!(
+        end_ts > block_ts,
+        ContractError::NoActiveSchedule {
+            lp_token: lp_token.clone(),
+            reward: reward.info.to_string(),
+        }
+    );
+
+    let rps = Decimal256::from_ratio(reward.amount, end_ts - block_ts);
+    if rps < Decimal256::one() {
+        return Err(StdError::generic_err(format!(
+            "Reward per second must be at least 1 unit but actual is {rps}",
+        ))
+        .into());
+    }
+
+    let schedule = IncentivesSchedule {
+        next_epoch_start_ts: block_ts,
+        end_ts,
+        reward_info: reward.info.clone(),
+        rps,
+    };
+    pool_info.incentivize(
+        deps.storage,
+        &lp_token_asset,
+        &schedule,
+        &config.astro_token,
+    )?;
     pool_info.save(deps.storage, &lp_token_asset)?;
 
+    CREATOR_SCHEDULES.update::<_, StdError>(
+        deps.storage,
+        (&lp_token_asset, &reward.info, &info.sender),
+        |schedules| {
+            let mut schedules = schedules.unwrap_or_default();
+            schedules.retain(|creator_schedule| creator_schedule.end_ts > block_ts);
+            schedules.push(CreatorSchedule {
+                next_epoch_start_ts: block_ts,
+                end_ts,
+                rps,
+                schedule_id: None,
+            });
+            Ok(schedules)
+        },
+    )?;
+
+    let response = Response::new().add_attributes([
+        attr("action", "top_up_schedule"),
+        attr("lp_token", lp_token.clone()),
+        attr("reward", reward.to_string()),
+        attr("end_ts", end_ts.to_string()),
+    ]);
+    let response = receive_reward(&mut info, &env, response, &reward, lp_token)?;
+
+    for coin in info.funds {
+        ensure!(
+            coin.amount.is_zero(),
+            StdError::generic_err(format!(
+                "Supplied coins contain {} that is not in the input asset vector",
+                &coin.denom
+            ))
+        );
+    }
+
     Ok(response)
 }
 
@@ -361,6 +992,15 @@ pub fn remove_reward_from_pool(
 
     pool_info.save(deps.storage, &lp_asset)?;
 
+    // Creators' schedules were removed together with the reward
+    let creators = CREATOR_SCHEDULES
+        .prefix((&lp_asset, &reward_asset))
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for creator in creators {
+        CREATOR_SCHEDULES.remove(deps.storage, (&lp_asset, &reward_asset, &creator));
+    }
+
     let mut response = Response::new();
 
     // Send unclaimed rewards
@@ -483,6 +1123,69 @@ pub fn claim_orphaned_rewards(
     Ok(Response::new().add_submessages(messages))
 }
 
+/// Builds messages minting stake receipts for the staker.
+/// The pool receipt denom is created on the first mint along with setting this contract as its before send hook,
+/// so that receipt transfers move the underlying positions. Subdenoms are numbered as LP denoms are too long.
+/// Receipts are minted to this contract first as transfers to and from this contract are ignored by the hook.
+pub fn mint_receipts_msgs(
+    storage: &mut dyn Storage,
+    contract: &Addr,
+    lp_token: &AssetInfo,
+    staker: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = vec![];
+    let denom = match RECEIPT_DENOMS.may_load(storage, lp_token)? {
+        Some(denom) => denom,
+        None => {
+            let id = RECEIPT_DENOMS_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+            RECEIPT_DENOMS_COUNT.save(storage, &id)?;
+
+            let subdenom = format!("staked_{id}");
+            let denom = format!("factory/{contract}/{subdenom}");
+            RECEIPT_DENOMS.save(storage, lp_token, &denom)?;
+            RECEIPT_POOLS.save(storage, &denom, lp_token)?;
+
+            messages.push(tf_create_denom_msg(contract, subdenom));
+            messages.push(tf_before_send_hook_msg(contract, &denom, contract));
+
+            denom
+        }
+    };
+
+    let receipts = coin(amount.u128(), denom);
+    messages.extend(tf_mint_msg(contract, receipts.clone(), contract));
+    messages.push(
+        BankMsg::Send {
+            to_address: staker.to_string(),
+            amount: vec![receipts],
+        }
+        .into(),
+    );
+
+    Ok(messages)
+}
+
+/// Builds gas-limited calls of the pool staking hooks.
+/// Failed calls are caught in the reply, so hooks can't block deposits, withdrawals or claims.
+pub fn staking_hook_msgs(
+    storage: &dyn Storage,
+    lp_token: &AssetInfo,
+    msg: &StakingHookMsg,
+) -> StdResult<Vec<SubMsg>> {
+    STAKING_HOOKS
+        .may_load(storage, lp_token)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|hook| {
+            Ok(
+                SubMsg::reply_on_error(wasm_execute(hook, msg, vec![])?, STAKING_HOOK_REPLY_ID)
+                    .with_gas_limit(STAKING_HOOK_GAS_LIMIT),
+            )
+        })
+        .collect()
+}
+
 pub fn asset_info_key(asset_info: &AssetInfo) -> Vec<u8> {
     let mut bytes = vec![];
     match asset_info {
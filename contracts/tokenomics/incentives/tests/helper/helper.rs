@@ -8,16 +8,21 @@ use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::astro_converter::OutpostBurnParams;
 use astroport::factory::{PairConfig, PairType};
 use astroport::incentives::{
-    Config, ExecuteMsg, IncentivesSchedule, IncentivizationFeeInfo, InputSchedule,
-    PoolInfoResponse, QueryMsg, RewardInfo, ScheduleResponse,
+    BlockScheduleResponse, ClaimOperator, ClaimSimulationResponse, Config, CreatorSchedule,
+    EmissionScale, ExecuteMsg, IncentivesSchedule, IncentivizationFeeInfo, InputSchedule,
+    PendingRewardResponse, PoolInfoResponse, PoolStaker, PoolStakersByAmountResponse,
+    PooledSchedule, PulledAstro, QueryMsg, RewardInfo, RewardMetadata, RewardTokenMetadata,
+    ScheduleCancellationConfig, ScheduleFunder, ScheduleKind, ScheduleResponse, StakingHookMsg,
+    VestingRewardResponse,
 };
 use astroport::pair::StablePoolParams;
 use astroport::vesting::{MigrateMsg, VestingAccount, VestingSchedule, VestingSchedulePoint};
-use astroport::{astro_converter, factory, native_coin_registry, pair, vesting};
+use astroport::{astro_converter, factory, native_coin_registry, pair, staking, vesting};
 use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
 use cosmwasm_std::{
-    coin, to_json_binary, Addr, Api, BlockInfo, CanonicalAddr, Coin, Decimal256, Empty, Env,
-    GovMsg, IbcMsg, IbcQuery, RecoverPubkeyError, StdError, StdResult, Storage, Timestamp, Uint128,
+    attr, coin, to_json_binary, Addr, Api, Binary, BlockInfo, CanonicalAddr, Coin, Decimal,
+    Decimal256, Deps, DepsMut, Empty, Env, GovMsg, IbcMsg, IbcQuery, MessageInfo,
+    RecoverPubkeyError, Response, StdError, StdResult, Storage, Timestamp, Uint128,
     VerificationError,
 };
 use cw20::MinterResponse;
@@ -80,6 +85,73 @@ fn vesting_contract() -> Box<dyn Contract<Empty>> {
     ))
 }
 
+fn mock_staking_execute(
+    _: DepsMut,
+    _: Env,
+    info: MessageInfo,
+    msg: staking::ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        staking::ExecuteMsg::Enter { receiver } => Ok(Response::new().add_attributes([
+            attr("action", "enter"),
+            attr(
+                "receiver",
+                receiver.unwrap_or_else(|| info.sender.to_string()),
+            ),
+            attr("amount", info.funds[0].amount),
+        ])),
+        _ => Err(StdError::generic_err("Not supported")),
+    }
+}
+
+fn mock_staking_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_staking_execute,
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { unimplemented!() },
+    ))
+}
+
+fn mock_hook_execute(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    msg: StakingHookMsg,
+) -> StdResult<Response> {
+    if deps.storage.get(b"fail").is_some() {
+        return Err(StdError::generic_err("Hook failed"));
+    }
+
+    match msg {
+        StakingHookMsg::PositionChanged { user, amount, .. } => {
+            Ok(Response::new().add_attributes([
+                attr("hook", "position_changed"),
+                attr("user", user),
+                attr("amount", amount),
+            ]))
+        }
+        StakingHookMsg::RewardsClaimed { user, .. } => {
+            Ok(Response::new()
+                .add_attributes([attr("hook", "rewards_claimed"), attr("user", user)]))
+        }
+    }
+}
+
+fn mock_hook_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_hook_execute,
+        |deps: DepsMut, _: Env, _: MessageInfo, fail: bool| -> StdResult<Response> {
+            if fail {
+                deps.storage.set(b"fail", b"true");
+            }
+            Ok(Response::new())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { unimplemented!() },
+    ))
+}
+
 fn vesting_contract_v131() -> Box<dyn Contract<Empty>> {
     Box::new(
         ContractWrapper::new_with_empty(
@@ -122,7 +194,8 @@ fn generator_contract() -> Box<dyn Contract<Empty>> {
             astroport_incentives::instantiate::instantiate,
             astroport_incentives::query::query,
         )
-        .with_reply_empty(astroport_incentives::reply::reply),
+        .with_reply_empty(astroport_incentives::reply::reply)
+        .with_sudo_empty(astroport_incentives::sudo::sudo),
     )
 }
 
@@ -324,6 +397,7 @@ impl Helper {
                             is_disabled: false,
                             is_generator_disabled: false,
                             permissioned: false,
+                            min_trade_notional: None,
                         },
                         PairConfig {
                             code_id: pair_stable_code,
@@ -333,6 +407,7 @@ impl Helper {
                             is_disabled: false,
                             is_generator_disabled: false,
                             permissioned: false,
+                            min_trade_notional: None,
                         },
                     ],
                     token_code_id,
@@ -342,6 +417,7 @@ impl Helper {
                     whitelist_code_id: 0,
                     coin_registry_address: coin_registry_address.to_string(),
                     tracker_config: None,
+                    ownership_timelock: None,
                 },
                 &[],
                 "Astroport Factory",
@@ -369,6 +445,7 @@ impl Helper {
                         fee: incentivization_fee.clone(),
                     }),
                     guardian: Some(TestAddr::new("guardian").to_string()),
+                    ownership_timelock: None,
                 },
                 &[],
                 "Astroport Generator",
@@ -410,6 +487,7 @@ impl Helper {
                         },
                         end_point: None,
                     }],
+                    managed: None,
                 }],
             },
             &[astro_for_vesting],
@@ -429,6 +507,19 @@ impl Helper {
     }
 
     pub fn stake(&mut self, from: &Addr, lp_asset: Asset) -> AnyResult<AppResponse> {
+        self.stake_with_referrer(from, lp_asset, None)
+    }
+
+    pub fn stake_with_referrer(
+        &mut self,
+        from: &Addr,
+        lp_asset: Asset,
+        referrer: Option<&str>,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecuteMsg::Deposit {
+            recipient: None,
+            referrer: referrer.map(ToString::to_string),
+        };
         match &lp_asset.info {
             AssetInfo::Token { contract_addr } => self.app.execute_contract(
                 from.clone(),
@@ -436,14 +527,14 @@ impl Helper {
                 &cw20::Cw20ExecuteMsg::Send {
                     contract: self.generator.to_string(),
                     amount: lp_asset.amount,
-                    msg: to_json_binary(&ExecuteMsg::Deposit { recipient: None }).unwrap(),
+                    msg: to_json_binary(&msg).unwrap(),
                 },
                 &[],
             ),
             AssetInfo::NativeToken { .. } => self.app.execute_contract(
                 from.clone(),
                 self.generator.clone(),
-                &ExecuteMsg::Deposit { recipient: None },
+                &msg,
                 &[lp_asset.as_coin().unwrap()],
             ),
         }
@@ -466,6 +557,24 @@ impl Helper {
         )
     }
 
+    pub fn unstake_with_receipts(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        amount: impl Into<Uint128>,
+        receipts: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::Withdraw {
+                lp_token: lp_token.to_string(),
+                amount: amount.into(),
+            },
+            receipts,
+        )
+    }
+
     pub fn setup_pools(&mut self, pools: Vec<(String, u128)>) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             self.owner.clone(),
@@ -582,6 +691,15 @@ impl Helper {
         )
     }
 
+    pub fn update_pools(&mut self, limit: Option<u8>) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked("permissionless"),
+            self.generator.clone(),
+            &ExecuteMsg::UpdatePools { limit },
+            &[],
+        )
+    }
+
     pub fn set_tokens_per_second(&mut self, amount: u128) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             self.owner.clone(),
@@ -593,6 +711,201 @@ impl Helper {
         )
     }
 
+    pub fn update_emission_controller(
+        &mut self,
+        from: &Addr,
+        controller: Option<&Addr>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateEmissionController {
+                controller: controller.map(|addr| addr.to_string()),
+            },
+            &[],
+        )
+    }
+
+    pub fn update_xastro_staking(
+        &mut self,
+        from: &Addr,
+        staking_contract: Option<&Addr>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateXastroStaking {
+                staking_contract: staking_contract.map(|addr| addr.to_string()),
+            },
+            &[],
+        )
+    }
+
+    pub fn update_stake_receipts(&mut self, from: &Addr, enabled: bool) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateStakeReceipts { enabled },
+            &[],
+        )
+    }
+
+    pub fn update_astro_pull_batching(
+        &mut self,
+        from: &Addr,
+        enabled: bool,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateAstroPullBatching { enabled },
+            &[],
+        )
+    }
+
+    /// Instantiates a mock staking contract which only accepts ASTRO and emits the xASTRO receiver
+    pub fn init_mock_staking(&mut self) -> Addr {
+        let code_id = self.app.store_code(mock_staking_contract());
+        self.app
+            .instantiate_contract(
+                code_id,
+                self.owner.clone(),
+                &Empty {},
+                &[],
+                "Mock staking",
+                None,
+            )
+            .unwrap()
+    }
+
+    /// Instantiates a mock staking hook which emits the received notifications or always fails if `fail` is set
+    pub fn init_mock_hook(&mut self, fail: bool) -> Addr {
+        let code_id = self.app.store_code(mock_hook_contract());
+        self.app
+            .instantiate_contract(code_id, self.owner.clone(), &fail, &[], "Mock hook", None)
+            .unwrap()
+    }
+
+    pub fn update_staking_hooks(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        add: &[&Addr],
+        remove: &[&Addr],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateStakingHooks {
+                lp_token: lp_token.to_string(),
+                add: Some(add.iter().map(|addr| addr.to_string()).collect()),
+                remove: Some(remove.iter().map(|addr| addr.to_string()).collect()),
+            },
+            &[],
+        )
+    }
+
+    pub fn update_schedule_cancellation(
+        &mut self,
+        from: &Addr,
+        config: Option<ScheduleCancellationConfig>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UpdateScheduleCancellation { config },
+            &[],
+        )
+    }
+
+    pub fn cancel_schedule(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        reward: &str,
+        receiver: Option<&Addr>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::CancelSchedule {
+                lp_token: lp_token.to_string(),
+                reward: reward.to_string(),
+                receiver: receiver.map(|addr| addr.to_string()),
+            },
+            &[],
+        )
+    }
+
+    pub fn scale_emissions(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        factor: Decimal,
+        duration: u64,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ScaleEmissions {
+                lp_token: lp_token.to_string(),
+                factor,
+                duration,
+            },
+            &[],
+        )
+    }
+
+    pub fn restore_emissions(&mut self, from: &Addr, lp_token: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::RestoreEmissions {
+                lp_token: lp_token.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn pause_pool(&mut self, from: &Addr, lp_token: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::PausePool {
+                lp_token: lp_token.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn unpause_pool(&mut self, from: &Addr, lp_token: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::UnpausePool {
+                lp_token: lp_token.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn set_reward_metadata(
+        &mut self,
+        from: &Addr,
+        reward: &str,
+        metadata: Option<RewardTokenMetadata>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::SetRewardMetadata {
+                reward: reward.to_string(),
+                metadata,
+            },
+            &[],
+        )
+    }
+
     pub fn create_schedule(
         &self,
         asset: &Asset,
@@ -606,6 +919,9 @@ impl Helper {
         let input = InputSchedule {
             reward: asset.clone(),
             duration_periods,
+            vesting_duration: None,
+            schedule_id: None,
+            kind: ScheduleKind::Time,
         };
         let sch = IncentivesSchedule::from_input(&env, &input)?;
 
@@ -764,6 +1080,42 @@ impl Helper {
         )
     }
 
+    pub fn top_up_schedule(
+        &mut self,
+        from: &Addr,
+        lp_token: &str,
+        reward: &Asset,
+    ) -> AnyResult<AppResponse> {
+        let funds = match &reward.info {
+            AssetInfo::Token { contract_addr } => {
+                self.app
+                    .execute_contract(
+                        from.clone(),
+                        contract_addr.clone(),
+                        &cw20::Cw20ExecuteMsg::IncreaseAllowance {
+                            spender: self.generator.to_string(),
+                            amount: reward.amount,
+                            expires: None,
+                        },
+                        &[],
+                    )
+                    .unwrap();
+                vec![]
+            }
+            AssetInfo::NativeToken { .. } => vec![reward.as_coin().unwrap()],
+        };
+
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::TopUpSchedule {
+                lp_token: lp_token.to_string(),
+                reward: reward.clone(),
+            },
+            &funds,
+        )
+    }
+
     pub fn remove_reward(
         &mut self,
         from: &Addr,
@@ -805,7 +1157,111 @@ impl Helper {
         self.app.execute_contract(
             from.clone(),
             self.generator.clone(),
-            &ExecuteMsg::ClaimRewards { lp_tokens },
+            &ExecuteMsg::ClaimRewards {
+                lp_tokens,
+                on_behalf_of: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn claim_rewards_on_behalf(
+        &mut self,
+        operator: &Addr,
+        user: &Addr,
+        lp_tokens: Vec<String>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            operator.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ClaimRewards {
+                lp_tokens,
+                on_behalf_of: Some(user.to_string()),
+            },
+            &[],
+        )
+    }
+
+    pub fn approve_claim_operator(
+        &mut self,
+        user: &Addr,
+        operator: &Addr,
+        expires_at: Option<u64>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            user.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ApproveClaimOperator {
+                operator: operator.to_string(),
+                expires_at,
+            },
+            &[],
+        )
+    }
+
+    pub fn revoke_claim_operator(
+        &mut self,
+        user: &Addr,
+        operator: &Addr,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            user.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::RevokeClaimOperator {
+                operator: operator.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn set_reward_receiver(
+        &mut self,
+        user: &Addr,
+        lp_token: &str,
+        receiver: Option<&Addr>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            user.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::SetRewardReceiver {
+                lp_token: lp_token.to_string(),
+                receiver: receiver.map(|addr| addr.to_string()),
+            },
+            &[],
+        )
+    }
+
+    pub fn claim_and_stake_lp_rewards(
+        &mut self,
+        from: &Addr,
+        lp_tokens: Vec<String>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ClaimAndStakeLpRewards { lp_tokens },
+            &[],
+        )
+    }
+
+    pub fn claim_into_xastro(
+        &mut self,
+        from: &Addr,
+        lp_tokens: Vec<String>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ClaimIntoXastro { lp_tokens },
+            &[],
+        )
+    }
+
+    pub fn claim_vested(&mut self, from: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            from.clone(),
+            self.generator.clone(),
+            &ExecuteMsg::ClaimVested {},
             &[],
         )
     }
@@ -881,6 +1337,49 @@ impl Helper {
             .unwrap()
     }
 
+    pub fn query_simulate_claim(
+        &self,
+        user: &Addr,
+        lp_tokens: Vec<String>,
+    ) -> StdResult<ClaimSimulationResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.generator,
+            &QueryMsg::SimulateClaim {
+                user: user.to_string(),
+                lp_tokens,
+            },
+        )
+    }
+
+    pub fn query_pending_rewards_with_metadata(
+        &self,
+        user: &Addr,
+        lp_token: &str,
+    ) -> Vec<PendingRewardResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::PendingRewardsWithMetadata {
+                    lp_token: lp_token.to_string(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_reward_metadata(&self, reward: &str) -> Option<RewardMetadata> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::RewardMetadata {
+                    reward: reward.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_config(&self) -> Config {
         self.app
             .wrap()
@@ -888,6 +1387,74 @@ impl Helper {
             .unwrap()
     }
 
+    pub fn query_emission_scale(&self, lp_token: &str) -> Option<EmissionScale> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::EmissionScale {
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_creator_schedules(
+        &self,
+        lp_token: &str,
+        reward: &str,
+        creator: &Addr,
+    ) -> Vec<CreatorSchedule> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::CreatorSchedules {
+                    lp_token: lp_token.to_string(),
+                    reward: reward.to_string(),
+                    creator: creator.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_pooled_schedule(&self, schedule_id: u64) -> StdResult<PooledSchedule> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.generator, &QueryMsg::PooledSchedule { schedule_id })
+    }
+
+    pub fn query_schedule_funders(
+        &self,
+        schedule_id: u64,
+        start_after: Option<String>,
+        limit: Option<u8>,
+    ) -> Vec<ScheduleFunder> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::ScheduleFunders {
+                    schedule_id,
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_pool_paused_at(&self, lp_token: &str) -> Option<u64> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::PoolPausedAt {
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_deposit(&self, lp_token: &str, user: &Addr) -> StdResult<u128> {
         self.app
             .wrap()
@@ -932,6 +1499,24 @@ impl Helper {
         )
     }
 
+    pub fn query_block_reward_schedules(
+        &self,
+        lp_token: &str,
+        reward: &AssetInfo,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    ) -> StdResult<Vec<BlockScheduleResponse>> {
+        self.app.wrap().query_wasm_smart(
+            &self.generator,
+            &QueryMsg::BlockRewardSchedules {
+                reward: reward.to_string(),
+                lp_token: lp_token.to_string(),
+                start_after,
+                limit,
+            },
+        )
+    }
+
     pub fn blocked_tokens(&self) -> Vec<AssetInfo> {
         self.app
             .wrap()
@@ -973,6 +1558,37 @@ impl Helper {
             .unwrap()
     }
 
+    pub fn pool_stakers_by_amount(
+        &self,
+        lp_token: &str,
+        start_after: Option<PoolStaker>,
+        limit: Option<u8>,
+    ) -> PoolStakersByAmountResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::PoolStakersByAmount {
+                    lp_token: lp_token.to_string(),
+                    start_after,
+                    limit,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_vesting_rewards(&self, user: &Addr) -> Vec<VestingRewardResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::VestingRewards {
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_reward_info(&self, lp_token: &str) -> Vec<RewardInfo> {
         self.app
             .wrap()
@@ -985,6 +1601,91 @@ impl Helper {
             .unwrap()
     }
 
+    pub fn query_referrer(&self, lp_token: &str, user: &Addr) -> Option<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::Referrer {
+                    lp_token: lp_token.to_string(),
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_stake_receipt_denom(&self, lp_token: &str) -> Option<String> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::StakeReceiptDenom {
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_staking_hooks(&self, lp_token: &str) -> Vec<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::StakingHooks {
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_claim_operators(&self, user: &Addr) -> Vec<ClaimOperator> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::ClaimOperators {
+                    user: user.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_reward_receiver(&self, user: &Addr, lp_token: &str) -> Option<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::RewardReceiver {
+                    user: user.to_string(),
+                    lp_token: lp_token.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_pulled_astro(&self) -> PulledAstro {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.generator, &QueryMsg::PulledAstro {})
+            .unwrap()
+    }
+
+    pub fn query_referred_tvl(&self, referrer: &str) -> Vec<(String, Uint128)> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.generator,
+                &QueryMsg::ReferredTvl {
+                    referrer: referrer.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn all_pools(&self) -> Vec<String> {
         self.app
             .wrap()
@@ -1049,6 +1750,8 @@ impl Helper {
                         to_json_binary(&StablePoolParams {
                             amp: 10,
                             owner: None,
+                            lp_token_metadata: None,
+                            balance_caps: None,
                         })
                         .unwrap(),
                     ),
@@ -1078,6 +1781,8 @@ impl Helper {
             auto_stake: Some(auto_stake),
             receiver: None,
             min_lp_to_receive: None,
+            staking_target: None,
+            deadline: None,
         };
 
         self.app
@@ -2,10 +2,14 @@ use std::str::FromStr;
 
 use astroport::asset::{native_asset_info, AssetInfo, AssetInfoExt};
 use astroport::incentives::{
-    ExecuteMsg, IncentivizationFeeInfo, InputSchedule, ScheduleResponse, EPOCHS_START,
-    EPOCH_LENGTH, MAX_REWARD_TOKENS,
+    BlockScheduleResponse, ClaimOperator, CreatorSchedule, ExecuteMsg, IncentivizationFeeInfo,
+    InputSchedule, PooledSchedule, RewardMetadata, RewardTokenMetadata, ScheduleCancellationConfig,
+    ScheduleFunder, ScheduleKind, ScheduleResponse, EPOCHS_START, EPOCH_LENGTH,
+    MAX_EMISSION_SCALE_DURATION, MAX_REWARD_TOKENS, MAX_STAKING_HOOKS,
+};
+use cosmwasm_std::{
+    attr, coin, coins, Addr, Decimal, Decimal256, Event, StdError, Timestamp, Uint128,
 };
-use cosmwasm_std::{coin, coins, Decimal256, Timestamp, Uint128};
 use itertools::Itertools;
 
 use astroport_incentives::error::ContractError;
@@ -278,6 +282,22 @@ fn test_claim_rewards() {
         }
     );
 
+    let err = helper
+        .query_simulate_claim(&user1, vec![pools[0].2.to_string(), pools[0].2.to_string()])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&ContractError::DuplicatedPoolFound {}.to_string()));
+
+    let simulation = helper
+        .query_simulate_claim(&user1, vec![pools[0].2.to_string(), pools[1].2.to_string()])
+        .unwrap();
+    assert_eq!(
+        simulation.transferred,
+        vec![astro.with_balance(2_500000u128)]
+    );
+    assert_eq!(simulation.vesting, vec![]);
+
     helper
         .claim_rewards(&user1, vec![pools[0].2.to_string(), pools[1].2.to_string()])
         .unwrap();
@@ -738,6 +758,9 @@ fn test_astro_can_bypass_rewards_limit() {
             InputSchedule {
                 reward: astro_reward.clone(),
                 duration_periods: 1,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
             &[],
         )
@@ -1225,6 +1248,141 @@ fn test_astro_external_reward() {
     );
 }
 
+#[test]
+fn test_claim_into_xastro() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    helper.setup_pools(vec![(lp_token.clone(), 100)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    // External rewards: 25 uastro and 10 ufoo per second
+    let bank = TestAddr::new("bank");
+    for reward in [
+        astro.with_balance(7 * 86400 * 25u128),
+        AssetInfo::native("ufoo").with_balance(7 * 86400 * 10u128),
+    ] {
+        let (schedule, _) = helper.create_schedule(&reward, 1).unwrap();
+        helper.mint_assets(&bank, &[reward.clone()]);
+        let fee = if helper.is_fee_needed(&lp_token, &reward.info) {
+            let fee = helper.incentivization_fee.clone();
+            helper.mint_coin(&bank, &fee);
+            vec![fee]
+        } else {
+            vec![]
+        };
+        helper
+            .incentivize(&bank, &lp_token, schedule, &fee)
+            .unwrap();
+    }
+
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    helper.next_block(86400);
+
+    let err = helper
+        .claim_into_xastro(&user, vec![lp_token.clone()])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::XastroStakingNotSet {}
+    );
+
+    let staking = helper.init_mock_staking();
+    let err = helper
+        .update_xastro_staking(&user, Some(&staking))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper
+        .update_xastro_staking(&owner, Some(&staking))
+        .unwrap();
+    assert_eq!(helper.query_config().xastro_staking, Some(staking.clone()));
+
+    let resp = helper
+        .claim_into_xastro(&user, vec![lp_token.clone()])
+        .unwrap();
+
+    // Protocol and external ASTRO rewards are staked on behalf of the user
+    let staked = 86400 * (100 + 25u128);
+    let enter_event = resp
+        .events
+        .iter()
+        .find(|event| {
+            event.ty == "wasm"
+                && event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == "action" && attr.value == "enter")
+        })
+        .unwrap();
+    assert!(enter_event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "receiver" && attr.value == user.as_str()));
+    assert_eq!(
+        astro
+            .query_pool(&helper.app.wrap(), &staking)
+            .unwrap()
+            .u128(),
+        staked
+    );
+    assert_eq!(
+        astro.query_pool(&helper.app.wrap(), &user).unwrap().u128(),
+        0
+    );
+
+    // Other rewards are sent to the user as usual
+    assert_eq!(
+        AssetInfo::native("ufoo")
+            .query_pool(&helper.app.wrap(), &user)
+            .unwrap()
+            .u128(),
+        86400 * 10
+    );
+
+    // Claiming again in the same block has nothing to stake
+    let resp = helper
+        .claim_into_xastro(&user, vec![lp_token.clone()])
+        .unwrap();
+    assert!(!resp.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "staked_astro")));
+
+    // Disabling the staking contract disables claiming into xASTRO
+    helper.update_xastro_staking(&owner, None).unwrap();
+    let err = helper
+        .claim_into_xastro(&user, vec![lp_token.clone()])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::XastroStakingNotSet {}
+    );
+}
+
 #[test]
 fn test_astro_protocol_reward_if_denom_changed() {
     let astro = native_asset_info("ibc/old_cw20_astro".to_string());
@@ -1903,6 +2061,87 @@ fn test_long_unclaimed_rewards() {
     }
 }
 
+#[test]
+fn test_pool_stakers_by_amount() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    // Owner provides liquidity first so that following users receive exactly provided amount of LP tokens
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    // user_0 and user_2 have equal stakes
+    for (i, amount) in [20_000u64, 10_000, 20_000, 30_000, 40_000]
+        .into_iter()
+        .enumerate()
+    {
+        let user = TestAddr::new(&format!("user_{i}"));
+        let provide_assets = [
+            asset_infos[0].with_balance(amount),
+            asset_infos[1].with_balance(amount),
+        ];
+        helper
+            .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+            .unwrap();
+    }
+
+    // user_4 withdraws most of the stake and drops to the end of the list
+    helper
+        .unstake(&TestAddr::new("user_4"), &lp_token, 35_000u128)
+        .unwrap();
+
+    let resp = helper.pool_stakers_by_amount(&lp_token, None, None);
+    assert_eq!(resp.block_height, helper.app.block_info().height);
+    let stakers = resp
+        .stakers
+        .iter()
+        .map(|s| (s.user.as_str(), s.amount.u128(), s.cumulative_amount.u128()))
+        .collect_vec();
+    assert_eq!(
+        stakers,
+        [
+            ("wasm1_user_3", 30_000, 30_000),
+            ("wasm1_user_0", 20_000, 50_000),
+            ("wasm1_user_2", 20_000, 70_000),
+            ("wasm1_user_1", 10_000, 80_000),
+            ("wasm1_user_4", 5_000, 85_000),
+        ]
+    );
+    assert_eq!(helper.pool_info(&lp_token).unwrap().total_lp.u128(), 85_000);
+
+    // Paginate through the same list
+    let first_page = helper.pool_stakers_by_amount(&lp_token, None, Some(2));
+    assert_eq!(first_page.stakers, resp.stakers[..2]);
+    let second_page =
+        helper.pool_stakers_by_amount(&lp_token, first_page.stakers.last().cloned(), Some(2));
+    assert_eq!(second_page.stakers, resp.stakers[2..4]);
+    let last_page =
+        helper.pool_stakers_by_amount(&lp_token, second_page.stakers.last().cloned(), None);
+    assert_eq!(last_page.stakers, resp.stakers[4..]);
+
+    // Fully withdrawn stakers are removed from the list
+    helper
+        .unstake(&TestAddr::new("user_1"), &lp_token, 10_000u128)
+        .unwrap();
+    let resp = helper.pool_stakers_by_amount(&lp_token, None, None);
+    assert_eq!(resp.stakers.len(), 4);
+    assert!(resp.stakers.iter().all(|s| s.user != "wasm1_user_1"));
+    assert_eq!(
+        resp.stakers.last().unwrap().cumulative_amount.u128(),
+        75_000
+    );
+}
+
 #[test]
 fn test_queries() {
     let astro = native_asset_info("astro".to_string());
@@ -2669,3 +2908,2193 @@ fn test_orphaned_rewards() {
         ContractError::NoOrphanedRewards {}
     );
 }
+
+#[test]
+fn test_reward_vesting() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward_asset_info = AssetInfo::native("reward");
+    let reward = reward_asset_info.with_balance(1000_000000u128);
+    let vesting_duration = 10 * 86400;
+    let schedule = InputSchedule {
+        reward: reward.clone(),
+        duration_periods: 1,
+        vesting_duration: Some(vesting_duration),
+        schedule_id: None,
+        kind: ScheduleKind::Time,
+    };
+
+    helper.mint_assets(&bank, &[reward.clone(), reward.clone()]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            schedule.clone(),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    // Next schedules must keep the same vesting duration
+    let err = helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            InputSchedule {
+                vesting_duration: None,
+                schedule_id: None,
+                ..schedule.clone()
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::VestingDurationMismatch {
+            lp_token: lp_token.clone(),
+            reward: reward_asset_info.to_string(),
+            expected: vesting_duration,
+        }
+    );
+
+    // Nothing to claim yet
+    let err = helper.claim_vested(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoVestedRewards {}
+    );
+
+    helper.next_block(86400);
+
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    let pending_reward = pending
+        .iter()
+        .find(|asset| asset.info == reward_asset_info)
+        .unwrap()
+        .amount;
+    assert!(!pending_reward.is_zero());
+
+    let simulation = helper
+        .query_simulate_claim(&user, vec![lp_token.clone()])
+        .unwrap();
+    assert_eq!(simulation.transferred, vec![]);
+    assert_eq!(
+        simulation.vesting,
+        vec![reward_asset_info.with_balance(pending_reward)]
+    );
+
+    // Claimed rewards are locked in the vesting position
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let vesting_start = helper.app.block_info().time.seconds();
+    assert_eq!(
+        helper
+            .app
+            .wrap()
+            .query_balance(&user, "reward")
+            .unwrap()
+            .amount,
+        Uint128::zero()
+    );
+    let positions = helper.query_vesting_rewards(&user);
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].reward, reward_asset_info);
+    assert_eq!(positions[0].claimable, Uint128::zero());
+    assert_eq!(positions[0].locked, pending_reward);
+    // Vesting end is rounded up to 1/20 of the vesting duration
+    let step = vesting_duration / 20;
+    let vesting_end = (vesting_start + vesting_duration).div_ceil(step) * step;
+    assert_eq!(positions[0].end_ts, vesting_end);
+
+    // Half of the locked rewards are vested
+    helper.next_block((vesting_end - vesting_start) / 2);
+    let positions = helper.query_vesting_rewards(&user);
+    let claimable = positions[0].claimable;
+    assert_eq!(claimable, pending_reward.multiply_ratio(1u8, 2u8));
+    assert_eq!(positions[0].locked, pending_reward - claimable);
+
+    helper.claim_vested(&user).unwrap();
+    assert_eq!(
+        helper
+            .app
+            .wrap()
+            .query_balance(&user, "reward")
+            .unwrap()
+            .amount,
+        claimable
+    );
+
+    // Nothing vested in the same block
+    let err = helper.claim_vested(&user).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoVestedRewards {}
+    );
+
+    // Once vesting is over, the whole claimed amount is transferred and the position is removed
+    helper.next_block(vesting_duration);
+    helper.claim_vested(&user).unwrap();
+    assert_eq!(
+        helper
+            .app
+            .wrap()
+            .query_balance(&user, "reward")
+            .unwrap()
+            .amount,
+        pending_reward
+    );
+    assert_eq!(helper.query_vesting_rewards(&user), vec![]);
+}
+
+#[test]
+fn test_emission_scaling() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+    let controller = TestAddr::new("controller");
+    let random = TestAddr::new("random");
+
+    let tokens = [
+        AssetInfo::native("usd"),
+        AssetInfo::native("foo"),
+        AssetInfo::native("bar"),
+    ];
+    let pair1_info = helper
+        .create_pair(&[tokens[0].clone(), tokens[1].clone()])
+        .unwrap();
+    let pair2_info = helper
+        .create_pair(&[tokens[0].clone(), tokens[2].clone()])
+        .unwrap();
+    let inactive_pair_info = helper
+        .create_pair(&[tokens[1].clone(), tokens[2].clone()])
+        .unwrap();
+    let lp_token1 = pair1_info.liquidity_token.to_string();
+    let lp_token2 = pair2_info.liquidity_token.to_string();
+
+    helper
+        .setup_pools(vec![(lp_token1.clone(), 1), (lp_token2.clone(), 1)])
+        .unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    // Emission controller is not set yet
+    let err = helper
+        .scale_emissions(&owner, &lp_token1, Decimal::percent(50), 1000)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = helper
+        .update_emission_controller(&random, Some(&controller))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper
+        .update_emission_controller(&owner, Some(&controller))
+        .unwrap();
+    assert_eq!(
+        helper.query_config().emission_controller,
+        Some(controller.clone())
+    );
+
+    let err = helper
+        .scale_emissions(&controller, &lp_token1, Decimal::percent(5), 1000)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "Emission scale factor must be within [0.1, 1)"
+        ))
+    );
+    let err = helper
+        .scale_emissions(&controller, &lp_token1, Decimal::one(), 1000)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "Emission scale factor must be within [0.1, 1)"
+        ))
+    );
+    let err = helper
+        .scale_emissions(
+            &controller,
+            &lp_token1,
+            Decimal::percent(50),
+            MAX_EMISSION_SCALE_DURATION + 1,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(format!(
+            "Emission scale duration must be more than 0 and less than or equal to {MAX_EMISSION_SCALE_DURATION}"
+        )))
+    );
+    let err = helper
+        .scale_emissions(
+            &controller,
+            inactive_pair_info.liquidity_token.as_str(),
+            Decimal::percent(50),
+            1000,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolIsNotActive {
+            lp_token: inactive_pair_info.liquidity_token.to_string()
+        }
+    );
+
+    helper.next_block(100);
+
+    // Halve emissions of the first pool for 1000 seconds
+    helper
+        .scale_emissions(&controller, &lp_token1, Decimal::percent(50), 1000)
+        .unwrap();
+    let scale = helper.query_emission_scale(&lp_token1).unwrap();
+    assert_eq!(scale.factor, Decimal::percent(50));
+    assert_eq!(
+        scale.expires_at,
+        helper.app.block_info().time.seconds() + 1000
+    );
+    assert_eq!(helper.query_emission_scale(&lp_token2), None);
+
+    // There are no stakers thus all rewards are accrued as orphaned
+    helper.next_block(500);
+    let reward_info = helper.query_reward_info(&lp_token1);
+    assert_eq!(
+        dec256_to_u128_floor(reward_info[0].orphaned),
+        50 * 100 + 25 * 500
+    );
+    // Scaled down emissions are not redistributed to other pools
+    let reward_info = helper.query_reward_info(&lp_token2);
+    assert_eq!(dec256_to_u128_floor(reward_info[0].orphaned), 50 * 600);
+
+    // Scale survives pools reconfiguration
+    helper
+        .setup_pools(vec![(lp_token1.clone(), 1), (lp_token2.clone(), 1)])
+        .unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    // Scale expires in the middle of this period; full emissions are restored automatically
+    helper.next_block(1000);
+    assert_eq!(helper.query_emission_scale(&lp_token1), None);
+    let reward_info = helper.query_reward_info(&lp_token1);
+    assert_eq!(
+        dec256_to_u128_floor(reward_info[0].orphaned),
+        50 * 100 + 25 * 1000 + 50 * 500
+    );
+
+    let err = helper.restore_emissions(&owner, &lp_token1).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::EmissionsNotScaled {
+            lp_token: lp_token1.clone()
+        }
+    );
+
+    // Scale again and restore early
+    helper
+        .scale_emissions(&controller, &lp_token2, Decimal::percent(10), 1000)
+        .unwrap();
+    let orphaned_before = dec256_to_u128_floor(helper.query_reward_info(&lp_token2)[0].orphaned);
+
+    helper.next_block(100);
+
+    let err = helper.restore_emissions(&random, &lp_token2).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper.restore_emissions(&owner, &lp_token2).unwrap();
+    assert_eq!(helper.query_emission_scale(&lp_token2), None);
+
+    helper.next_block(100);
+
+    let reward_info = helper.query_reward_info(&lp_token2);
+    assert_eq!(
+        dec256_to_u128_floor(reward_info[0].orphaned),
+        orphaned_before + 5 * 100 + 50 * 100
+    );
+
+    // Owner can remove the controller
+    helper.update_emission_controller(&owner, None).unwrap();
+    let err = helper
+        .scale_emissions(&controller, &lp_token1, Decimal::percent(50), 1000)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+}
+
+#[test]
+fn test_pool_pause() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+    let guardian = TestAddr::new("guardian");
+    let random = TestAddr::new("random");
+    let user = TestAddr::new("user");
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    helper.setup_pools(vec![(lp_token.clone(), 1)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    let reward = AssetInfo::native("reward").with_balance(1000_000000u128);
+    let (schedule, _) = helper.create_schedule(&reward, 2).unwrap();
+    helper.mint_assets(&owner, &[reward.clone()]);
+    helper.mint_coin(&owner, &incentivization_fee);
+    helper
+        .incentivize(
+            &owner,
+            &lp_token,
+            schedule.clone(),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    let lp_coin = coin(10000u128, &lp_token);
+    helper.mint_coin(&user, &lp_coin);
+    helper
+        .stake(&user, AssetInfo::native(&lp_token).with_balance(5000u128))
+        .unwrap();
+
+    helper.next_block(1000);
+
+    let err = helper.pause_pool(&random, &lp_token).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    helper.pause_pool(&guardian, &lp_token).unwrap();
+    assert_eq!(
+        helper.query_pool_paused_at(&lp_token),
+        Some(helper.app.block_info().time.seconds())
+    );
+
+    let err = helper.pause_pool(&owner, &lp_token).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolPaused {
+            lp_token: lp_token.clone()
+        }
+    );
+
+    let pending_at_pause = helper.query_pending_rewards(&user, &lp_token);
+    let ext_rps = helper
+        .query_reward_info(&lp_token)
+        .into_iter()
+        .find(|r| r.reward.is_external())
+        .unwrap()
+        .rps;
+
+    // Reward indexes are frozen
+    helper.next_block(1000);
+    assert_eq!(
+        helper.query_pending_rewards(&user, &lp_token),
+        pending_at_pause
+    );
+
+    // Deposits and new schedules are blocked
+    let err = helper
+        .stake(&user, AssetInfo::native(&lp_token).with_balance(5000u128))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolPaused {
+            lp_token: lp_token.clone()
+        }
+    );
+    helper.mint_assets(&owner, &[reward.clone()]);
+    let err = helper
+        .incentivize(&owner, &lp_token, schedule, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolPaused {
+            lp_token: lp_token.clone()
+        }
+    );
+
+    // Rewards accrued before the pause can be claimed and withdrawals are allowed
+    let balances_before = helper.snapshot_balances(&user, &pending_at_pause);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let balances_after = helper.snapshot_balances(&user, &pending_at_pause);
+    assert_rewards(&balances_before, &balances_after, &pending_at_pause);
+    helper.unstake(&user, &lp_token, 1000u128).unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user).unwrap(), 4000);
+
+    let err = helper.unpause_pool(&guardian, &lp_token).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper.unpause_pool(&owner, &lp_token).unwrap();
+    assert_eq!(helper.query_pool_paused_at(&lp_token), None);
+
+    let err = helper.unpause_pool(&owner, &lp_token).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolNotPaused {
+            lp_token: lp_token.clone()
+        }
+    );
+
+    // Rewards are accrued again after unpausing
+    helper.next_block(100);
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    assert_eq!(
+        pending
+            .iter()
+            .find(|asset| asset.info == astro)
+            .unwrap()
+            .amount
+            .u128(),
+        100 * 100
+    );
+    helper
+        .stake(&user, AssetInfo::native(&lp_token).with_balance(1000u128))
+        .unwrap();
+
+    // External rewards of the paused period become orphaned
+    let orph_receiver = TestAddr::new("orphaned_rewards_receiver");
+    helper.claim_orphaned_rewards(None, &orph_receiver).unwrap();
+    let orphaned = reward
+        .info
+        .query_pool(&helper.app.wrap(), &orph_receiver)
+        .unwrap();
+    assert_eq!(
+        orphaned.u128(),
+        dec256_to_u128_floor(ext_rps * Decimal256::from_ratio(1000u128, 1u8))
+    );
+}
+
+#[test]
+fn test_reward_metadata() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+    let guardian = TestAddr::new("guardian");
+    let creator = TestAddr::new("creator");
+    let random = TestAddr::new("random");
+    let user = TestAddr::new("user");
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let metadata = RewardTokenMetadata {
+        symbol: "RWD".to_string(),
+        decimals: 6,
+        logo_uri: Some("https://example.com/rwd.png".to_string()),
+        project_url: Some("https://example.com".to_string()),
+    };
+
+    // Reward creator is not known until the first incentivization
+    let err = helper
+        .set_reward_metadata(&creator, "reward", Some(metadata.clone()))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let reward = AssetInfo::native("reward").with_balance(1000_000000u128);
+    let (schedule, _) = helper.create_schedule(&reward, 2).unwrap();
+    helper.mint_assets(&creator, &[reward.clone()]);
+    helper.mint_coin(&creator, &incentivization_fee);
+    helper
+        .incentivize(
+            &creator,
+            &lp_token,
+            schedule.clone(),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    // The next incentivizor doesn't become a creator
+    helper.mint_assets(&random, &[reward.clone()]);
+    helper
+        .incentivize(&random, &lp_token, schedule, &[])
+        .unwrap();
+    let err = helper
+        .set_reward_metadata(&random, "reward", Some(metadata.clone()))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = helper
+        .set_reward_metadata(
+            &creator,
+            "reward",
+            Some(RewardTokenMetadata {
+                symbol: "".to_string(),
+                ..metadata.clone()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: symbol length must be within [1, 32]"
+    );
+
+    helper
+        .set_reward_metadata(&creator, "reward", Some(metadata.clone()))
+        .unwrap();
+    assert_eq!(
+        helper.query_reward_metadata("reward"),
+        Some(RewardMetadata {
+            metadata: metadata.clone(),
+            verified: false
+        })
+    );
+
+    // Guardian moderates metadata
+    let verified_metadata = RewardTokenMetadata {
+        symbol: "REWARD".to_string(),
+        ..metadata.clone()
+    };
+    helper
+        .set_reward_metadata(&guardian, "reward", Some(verified_metadata.clone()))
+        .unwrap();
+    let expected = RewardMetadata {
+        metadata: verified_metadata,
+        verified: true,
+    };
+    assert_eq!(
+        helper.query_reward_metadata("reward"),
+        Some(expected.clone())
+    );
+
+    // Metadata is returned alongside pending rewards
+    let lp_coin = coin(10000u128, &lp_token);
+    helper.mint_coin(&user, &lp_coin);
+    helper
+        .stake(&user, AssetInfo::native(&lp_token).with_balance(10000u128))
+        .unwrap();
+    helper.next_block(1000);
+
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    let pending_with_metadata = helper.query_pending_rewards_with_metadata(&user, &lp_token);
+    assert_eq!(
+        pending_with_metadata
+            .iter()
+            .map(|item| item.reward.clone())
+            .collect_vec(),
+        pending
+    );
+    let reward_item = pending_with_metadata
+        .iter()
+        .find(|item| item.reward.info == reward.info)
+        .unwrap();
+    assert_eq!(reward_item.metadata, Some(expected));
+
+    helper.set_reward_metadata(&owner, "reward", None).unwrap();
+    assert_eq!(helper.query_reward_metadata("reward"), None);
+}
+
+#[test]
+fn test_cancel_schedule() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let start_ts = EPOCHS_START + EPOCH_LENGTH;
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(start_ts));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    let reward_asset_info = AssetInfo::native("reward");
+    let reward = reward_asset_info.to_string();
+
+    // 1000 reward per second during 4 weeks
+    let creator = TestAddr::new("creator");
+    let creator_reward = reward_asset_info.with_balance(1000 * 4 * EPOCH_LENGTH);
+    let (schedule, creator_schedule) = helper.create_schedule(&creator_reward, 3).unwrap();
+    helper.mint_assets(&creator, &[creator_reward]);
+    helper.mint_coin(&creator, &incentivization_fee);
+    helper
+        .incentivize(&creator, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+
+    // 500 reward per second during 2 weeks
+    let other = TestAddr::new("other");
+    let other_reward = reward_asset_info.with_balance(500 * 2 * EPOCH_LENGTH);
+    let (schedule, _) = helper.create_schedule(&other_reward, 1).unwrap();
+    helper.mint_assets(&other, &[other_reward]);
+    helper
+        .incentivize(&other, &lp_token, schedule, &[])
+        .unwrap();
+
+    assert_eq!(
+        helper.query_creator_schedules(&lp_token, &reward, &creator),
+        vec![CreatorSchedule {
+            next_epoch_start_ts: start_ts + EPOCH_LENGTH,
+            end_ts: start_ts + 4 * EPOCH_LENGTH,
+            rps: creator_schedule.rps,
+            schedule_id: Some(0),
+        }]
+    );
+
+    let err = helper
+        .cancel_schedule(&creator, &lp_token, &reward, None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ScheduleCancellationDisabled {}
+    );
+
+    let cancellation_config = ScheduleCancellationConfig {
+        notice_period: 3 * 86400,
+        locked_epochs: 1,
+    };
+    let err = helper
+        .update_schedule_cancellation(&user, Some(cancellation_config.clone()))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper
+        .update_schedule_cancellation(&owner, Some(cancellation_config.clone()))
+        .unwrap();
+    assert_eq!(
+        helper.query_config().schedule_cancellation,
+        Some(cancellation_config)
+    );
+
+    // The schedule is locked during the first partial and the first full epoch
+    helper.next_block(EPOCH_LENGTH + 86400);
+    let err = helper
+        .cancel_schedule(&creator, &lp_token, &reward, None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToCancel {
+            lp_token: lp_token.clone(),
+            reward: reward.clone(),
+        }
+    );
+
+    // Only own schedules can be cancelled
+    helper.next_block(EPOCH_LENGTH);
+    let err = helper
+        .cancel_schedule(&user, &lp_token, &reward, None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToCancel {
+            lp_token: lp_token.clone(),
+            reward: reward.clone(),
+        }
+    );
+
+    // The schedule stops at the first epoch start after the notice period i.e. in 6 days.
+    // The last week of the schedule is refunded
+    let receiver = TestAddr::new("receiver");
+    helper
+        .cancel_schedule(&creator, &lp_token, &reward, Some(&receiver))
+        .unwrap();
+    let refund = reward_asset_info
+        .query_pool(&helper.app.wrap(), &receiver)
+        .unwrap()
+        .u128();
+    assert_eq!(refund as u64, 1000 * EPOCH_LENGTH);
+
+    assert_eq!(
+        helper.query_creator_schedules(&lp_token, &reward, &creator),
+        vec![CreatorSchedule {
+            next_epoch_start_ts: start_ts + EPOCH_LENGTH,
+            end_ts: start_ts + 3 * EPOCH_LENGTH,
+            rps: creator_schedule.rps,
+            schedule_id: Some(0),
+        }]
+    );
+
+    let err = helper
+        .cancel_schedule(&creator, &lp_token, &reward, None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToCancel {
+            lp_token: lp_token.clone(),
+            reward: reward.clone(),
+        }
+    );
+
+    // Stakers receive all rewards until the stop time and nothing afterwards
+    helper.next_block(2 * EPOCH_LENGTH);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let claimed = reward_asset_info
+        .query_pool(&helper.app.wrap(), &user)
+        .unwrap()
+        .u128();
+    let expected = (1000 * 3 * EPOCH_LENGTH + 500 * 2 * EPOCH_LENGTH) as u128;
+    assert!(
+        expected - claimed < 10,
+        "claimed {claimed}, expected {expected}"
+    );
+
+    helper.next_block(EPOCH_LENGTH);
+    assert!(helper
+        .query_pending_rewards(&user, &lp_token)
+        .iter()
+        .all(|asset| asset.amount.is_zero()));
+    assert_eq!(
+        helper.query_creator_schedules(&lp_token, &reward, &creator),
+        vec![]
+    );
+}
+
+#[test]
+fn test_top_up_schedule() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let start_ts = EPOCHS_START + EPOCH_LENGTH;
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(start_ts));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    let reward_asset_info = AssetInfo::native("reward");
+    let reward = reward_asset_info.to_string();
+
+    // Only rewards already present in the pool can be topped up
+    let other = TestAddr::new("other");
+    let top_up = reward_asset_info.with_balance(1000 * 3 * EPOCH_LENGTH);
+    helper.mint_assets(&other, &[top_up.clone()]);
+    let err = helper
+        .top_up_schedule(&other, &lp_token, &top_up)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RewardNotFound {
+            pool: lp_token.clone(),
+            reward: reward.clone(),
+        }
+    );
+
+    // 1000 reward per second during 4 weeks
+    let creator = TestAddr::new("creator");
+    let creator_reward = reward_asset_info.with_balance(1000 * 4 * EPOCH_LENGTH);
+    let (schedule, _) = helper.create_schedule(&creator_reward, 3).unwrap();
+    helper.mint_assets(&creator, &[creator_reward]);
+    helper.mint_coin(&creator, &incentivization_fee);
+    helper
+        .incentivize(&creator, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+
+    // The top-up is spread over the remaining 3 weeks and doesn't require the incentivization fee
+    helper.next_block(EPOCH_LENGTH);
+    helper.top_up_schedule(&other, &lp_token, &top_up).unwrap();
+
+    let reward_info = helper
+        .query_reward_info(&lp_token)
+        .into_iter()
+        .find(|info| info.reward.asset_info() == &reward_asset_info)
+        .unwrap();
+    assert_eq!(reward_info.rps, Decimal256::from_ratio(2000u16, 1u8));
+
+    assert_eq!(
+        helper.query_creator_schedules(&lp_token, &reward, &other),
+        vec![CreatorSchedule {
+            next_epoch_start_ts: start_ts + EPOCH_LENGTH,
+            end_ts: start_ts + 4 * EPOCH_LENGTH,
+            rps: Decimal256::from_ratio(1000u16, 1u8),
+            schedule_id: None,
+        }]
+    );
+
+    helper.next_block(3 * EPOCH_LENGTH);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let claimed = reward_asset_info
+        .query_pool(&helper.app.wrap(), &user)
+        .unwrap()
+        .u128();
+    let expected = (1000 * EPOCH_LENGTH + 2000 * 3 * EPOCH_LENGTH) as u128;
+    assert!(
+        expected - claimed < 10,
+        "claimed {claimed}, expected {expected}"
+    );
+
+    // The reward left the pool once its schedules ended
+    helper.mint_assets(&other, &[top_up.clone()]);
+    let err = helper
+        .top_up_schedule(&other, &lp_token, &top_up)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RewardNotFound {
+            pool: lp_token.clone(),
+            reward: reward.clone(),
+        }
+    );
+}
+
+#[test]
+fn test_pooled_schedule_funding() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let start_ts = EPOCHS_START + EPOCH_LENGTH;
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(start_ts));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    let reward_asset_info = AssetInfo::native("reward");
+    let reward = reward_asset_info.to_string();
+
+    // The first DAO starts a 3-week schedule
+    let dao1 = TestAddr::new("dao1");
+    let dao1_reward = reward_asset_info.with_balance(1000 * 3 * EPOCH_LENGTH);
+    let (schedule, dao1_schedule) = helper.create_schedule(&dao1_reward, 2).unwrap();
+    helper.mint_assets(&dao1, &[dao1_reward.clone()]);
+    helper.mint_coin(&dao1, &incentivization_fee);
+    let resp = helper
+        .incentivize(&dao1, &lp_token, schedule, &[incentivization_fee])
+        .unwrap();
+    let schedule_id: u64 = resp
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "schedule_id")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+
+    // The second DAO tops up the same schedule
+    let dao2 = TestAddr::new("dao2");
+    let dao2_reward = reward_asset_info.with_balance(500 * 3 * EPOCH_LENGTH);
+    let (mut schedule, dao2_schedule) = helper.create_schedule(&dao2_reward, 2).unwrap();
+    helper.mint_assets(&dao2, &[dao2_reward.clone()]);
+
+    schedule.schedule_id = Some(schedule_id + 1);
+    let err = helper
+        .incentivize(&dao2, &lp_token, schedule.clone(), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ScheduleNotFound {
+            schedule_id: schedule_id + 1
+        }
+    );
+
+    let (mut longer_schedule, _) = helper.create_schedule(&dao2_reward, 3).unwrap();
+    longer_schedule.schedule_id = Some(schedule_id);
+    let err = helper
+        .incentivize(&dao2, &lp_token, longer_schedule, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ScheduleMismatch { schedule_id }
+    );
+
+    schedule.schedule_id = Some(schedule_id);
+    helper.incentivize(&dao2, &lp_token, schedule, &[]).unwrap();
+
+    assert_eq!(
+        helper.query_pooled_schedule(schedule_id).unwrap(),
+        PooledSchedule {
+            lp_token: AssetInfo::native(&lp_token),
+            reward_info: reward_asset_info.clone(),
+            next_epoch_start_ts: start_ts + EPOCH_LENGTH,
+            end_ts: start_ts + 3 * EPOCH_LENGTH,
+            funded: dao1_reward.amount + dao2_reward.amount,
+            refunded: Uint128::zero(),
+        }
+    );
+    let mut expected_funders = vec![
+        ScheduleFunder {
+            funder: dao1.clone(),
+            funded: dao1_reward.amount,
+            refunded: Uint128::zero(),
+        },
+        ScheduleFunder {
+            funder: dao2.clone(),
+            funded: dao2_reward.amount,
+            refunded: Uint128::zero(),
+        },
+    ];
+    expected_funders.sort_by(|a, b| a.funder.cmp(&b.funder));
+    assert_eq!(
+        helper.query_schedule_funders(schedule_id, None, None),
+        expected_funders
+    );
+    assert_eq!(
+        helper.query_creator_schedules(&lp_token, &reward, &dao2),
+        vec![CreatorSchedule {
+            next_epoch_start_ts: start_ts + EPOCH_LENGTH,
+            end_ts: start_ts + 3 * EPOCH_LENGTH,
+            rps: dao2_schedule.rps,
+            schedule_id: Some(schedule_id),
+        }]
+    );
+
+    // The pool has a single schedule with the combined reward per second
+    let reward_info = helper
+        .query_reward_info(&lp_token)
+        .into_iter()
+        .find(|info| info.reward.asset_info() == &reward_asset_info)
+        .unwrap();
+    assert_eq!(reward_info.rps, dao1_schedule.rps + dao2_schedule.rps);
+
+    // Funding window closes when the first full epoch starts
+    helper.next_block(EPOCH_LENGTH);
+    let late = TestAddr::new("late");
+    let late_reward = reward_asset_info.with_balance(1000 * 2 * EPOCH_LENGTH);
+    let (mut schedule, _) = helper.create_schedule(&late_reward, 1).unwrap();
+    schedule.schedule_id = Some(schedule_id);
+    helper.mint_assets(&late, &[late_reward]);
+    let err = helper
+        .incentivize(&late, &lp_token, schedule, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ScheduleFundingClosed { schedule_id }
+    );
+
+    // Refunds on cancellation are tracked per funder. The last week of the second DAO's part is refunded
+    helper
+        .update_schedule_cancellation(
+            &owner,
+            Some(ScheduleCancellationConfig {
+                notice_period: 0,
+                locked_epochs: 0,
+            }),
+        )
+        .unwrap();
+    helper.next_block(1);
+    helper
+        .cancel_schedule(&dao2, &lp_token, &reward, None)
+        .unwrap();
+    let dao2_refund = reward_asset_info
+        .query_pool(&helper.app.wrap(), &dao2)
+        .unwrap();
+    assert_eq!(dao2_refund.u128() as u64, 500 * EPOCH_LENGTH);
+
+    let pooled_schedule = helper.query_pooled_schedule(schedule_id).unwrap();
+    assert_eq!(pooled_schedule.refunded, dao2_refund);
+    let funders = helper.query_schedule_funders(schedule_id, None, None);
+    let dao2_funder = funders.iter().find(|f| f.funder == dao2).unwrap();
+    assert_eq!(dao2_funder.refunded, dao2_refund);
+    let dao1_funder = funders.iter().find(|f| f.funder == dao1).unwrap();
+    assert_eq!(dao1_funder.refunded, Uint128::zero());
+
+    // Pagination
+    let first_page = helper.query_schedule_funders(schedule_id, None, Some(1));
+    assert_eq!(first_page, funders[..1]);
+    let second_page =
+        helper.query_schedule_funders(schedule_id, Some(first_page[0].funder.to_string()), Some(1));
+    assert_eq!(second_page, funders[1..]);
+}
+
+#[test]
+fn test_lp_token_rewards_compounding() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+    let initial_deposit = helper.query_deposit(&lp_token, &user).unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward = native_asset_info(lp_token.clone()).with_balance(1000_000000u128);
+    let schedule = InputSchedule {
+        reward: reward.clone(),
+        duration_periods: 1,
+        vesting_duration: None,
+        schedule_id: None,
+        kind: ScheduleKind::Time,
+    };
+    helper.mint_assets(&bank, &[reward.clone()]);
+    helper.mint_coin(&bank, &incentivization_fee);
+
+    // Compounded rewards can't vest
+    let err = helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            InputSchedule {
+                vesting_duration: Some(10 * 86400),
+                schedule_id: None,
+                ..schedule.clone()
+            },
+            &[incentivization_fee.clone()],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::CompoundingRewardVesting {
+            lp_token: lp_token.clone()
+        }
+    );
+
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            schedule.clone(),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    helper.next_block(86400);
+
+    let simulation = helper
+        .query_simulate_claim(&user, vec![lp_token.clone()])
+        .unwrap();
+    assert_eq!(simulation.compounded.len(), 1);
+    assert_eq!(simulation.compounded[0].info, reward.info);
+    let compounded = simulation.compounded[0].amount.u128();
+    assert!(compounded > 0);
+
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+
+    // Rewards were added to the position instead of being sent to the user
+    assert_eq!(
+        helper.query_deposit(&lp_token, &user).unwrap(),
+        initial_deposit + compounded
+    );
+    let lp_balance = helper
+        .app
+        .wrap()
+        .query_balance(&user, &lp_token)
+        .unwrap()
+        .amount;
+    assert_eq!(lp_balance, Uint128::zero());
+
+    // Compounded LP tokens can be withdrawn as any other deposit
+    helper
+        .unstake(&user, &lp_token, initial_deposit + compounded)
+        .unwrap();
+    let lp_balance = helper
+        .app
+        .wrap()
+        .query_balance(&user, &lp_token)
+        .unwrap()
+        .amount;
+    assert_eq!(lp_balance.u128(), initial_deposit + compounded);
+}
+
+#[test]
+fn test_lazy_emission_updates() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+    let user = TestAddr::new("user");
+
+    let mut lp_tokens = vec![];
+    for asset_infos in [
+        [AssetInfo::native("foo"), AssetInfo::native("bar")],
+        [AssetInfo::native("foo"), AssetInfo::native("baz")],
+    ] {
+        let pair_info = helper.create_pair(&asset_infos).unwrap();
+        let provide_assets = [
+            asset_infos[0].with_balance(100000u64),
+            asset_infos[1].with_balance(100000u64),
+        ];
+        // Owner provides liquidity first to make following calculations easier
+        helper
+            .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+            .unwrap();
+        helper
+            .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+            .unwrap();
+        lp_tokens.push(pair_info.liquidity_token.to_string());
+    }
+
+    let updated_pools = |events: &[cosmwasm_std::Event]| {
+        events
+            .iter()
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == "updated_pools")
+            .map(|attr| attr.value.clone())
+            .unwrap()
+    };
+
+    helper
+        .setup_pools(vec![(lp_tokens[0].clone(), 50), (lp_tokens[1].clone(), 50)])
+        .unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    helper.next_block(1000);
+
+    // Pools pick up emission changes lazily
+    for lp_token in &lp_tokens {
+        assert_eq!(
+            helper.query_pending_rewards(&user, lp_token),
+            [astro.with_balance(50_000u128)]
+        );
+    }
+
+    // The second pool is not active anymore
+    helper
+        .setup_pools(vec![(lp_tokens[0].clone(), 100)])
+        .unwrap();
+
+    helper.next_block(1000);
+
+    assert_eq!(
+        helper.query_pending_rewards(&user, &lp_tokens[0]),
+        [astro.with_balance(150_000u128)]
+    );
+    assert_eq!(
+        helper.query_pending_rewards(&user, &lp_tokens[1]),
+        [astro.with_balance(50_000u128)]
+    );
+
+    // Keepers advance outdated pools in batches
+    let resp = helper.update_pools(Some(1)).unwrap();
+    assert_eq!(updated_pools(&resp.events), "1");
+    let resp = helper.update_pools(None).unwrap();
+    assert_eq!(updated_pools(&resp.events), "1");
+    let resp = helper.update_pools(None).unwrap();
+    assert_eq!(updated_pools(&resp.events), "0");
+
+    // Updates don't affect accrued rewards
+    helper.next_block(1000);
+    assert_eq!(
+        helper.query_pending_rewards(&user, &lp_tokens[0]),
+        [astro.with_balance(250_000u128)]
+    );
+    assert_eq!(
+        helper.query_pending_rewards(&user, &lp_tokens[1]),
+        [astro.with_balance(50_000u128)]
+    );
+
+    helper.claim_rewards(&user, lp_tokens.clone()).unwrap();
+    for lp_token in &lp_tokens {
+        assert_eq!(
+            helper.query_pending_rewards(&user, lp_token),
+            [astro.with_balance(0u128)]
+        );
+    }
+}
+
+#[test]
+fn test_deposit_referrals() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+    let lp_asset = native_asset_info(lp_token.clone());
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    let referrer = TestAddr::new("referrer");
+    let user1 = TestAddr::new("user1");
+    let user2 = TestAddr::new("user2");
+    for user in [&user1, &user2] {
+        helper
+            .provide_liquidity(user, &provide_assets, &pair_info.contract_addr, false)
+            .unwrap();
+    }
+
+    // Staker can't refer themselves
+    let err = helper
+        .stake_with_referrer(
+            &user1,
+            lp_asset.with_balance(10_000u128),
+            Some(user1.as_str()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::SelfReferral {}
+    );
+
+    helper
+        .stake_with_referrer(
+            &user1,
+            lp_asset.with_balance(10_000u128),
+            Some(referrer.as_str()),
+        )
+        .unwrap();
+    // The second deposit adds to the referred position even without the referrer
+    helper
+        .stake(&user1, lp_asset.with_balance(5_000u128))
+        .unwrap();
+    assert_eq!(
+        helper.query_referrer(&lp_token, &user1),
+        Some(referrer.clone())
+    );
+    assert_eq!(
+        helper.query_referred_tvl(referrer.as_str()),
+        [(lp_token.clone(), Uint128::new(15_000))]
+    );
+
+    // Referrer of an existing position never changes
+    helper
+        .stake(&user2, lp_asset.with_balance(10_000u128))
+        .unwrap();
+    helper
+        .stake_with_referrer(
+            &user2,
+            lp_asset.with_balance(10_000u128),
+            Some(referrer.as_str()),
+        )
+        .unwrap();
+    assert_eq!(helper.query_referrer(&lp_token, &user2), None);
+    assert_eq!(
+        helper.query_referred_tvl(referrer.as_str()),
+        [(lp_token.clone(), Uint128::new(15_000))]
+    );
+
+    helper.unstake(&user1, &lp_token, 7_000u128).unwrap();
+    assert_eq!(
+        helper.query_referred_tvl(referrer.as_str()),
+        [(lp_token.clone(), Uint128::new(8_000))]
+    );
+
+    // Closing the position removes the referral
+    helper.unstake(&user1, &lp_token, 8_000u128).unwrap();
+    assert_eq!(helper.query_referrer(&lp_token, &user1), None);
+    assert!(helper.query_referred_tvl(referrer.as_str()).is_empty());
+}
+
+#[test]
+fn test_block_based_schedules() {
+    let astro = native_asset_info("astro".to_string());
+
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    let user = TestAddr::new("user");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward_asset_info = AssetInfo::native("reward");
+    helper.mint_assets(&bank, &[reward_asset_info.with_balance(1000_000000u128)]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper.mint_coin(&bank, &incentivization_fee);
+
+    let block_schedule = |start_height: u64, end_height: u64| InputSchedule {
+        reward: reward_asset_info.with_balance(100_000u128),
+        duration_periods: 0,
+        vesting_duration: None,
+        schedule_id: None,
+        kind: ScheduleKind::Blocks {
+            start_height,
+            end_height,
+        },
+    };
+
+    let height = helper.app.block_info().height;
+
+    let err = helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            block_schedule(height - 1, height + 100),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "Schedule must start not earlier than the current block and last from 1 to 5184000 blocks"
+        ))
+    );
+
+    let err = helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            InputSchedule {
+                schedule_id: Some(0),
+                ..block_schedule(height, height + 100)
+            },
+            &[incentivization_fee.clone()],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "Block based schedules can't be pooled"
+        ))
+    );
+
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            block_schedule(height + 10, height + 110),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    let schedules = helper
+        .query_block_reward_schedules(&lp_token, &reward_asset_info, None, None)
+        .unwrap();
+    assert_eq!(
+        schedules,
+        [
+            BlockScheduleResponse {
+                rpb: Decimal256::zero(),
+                start_height: height,
+                end_height: height + 10,
+            },
+            BlockScheduleResponse {
+                rpb: Decimal256::from_ratio(1000u16, 1u8),
+                start_height: height + 10,
+                end_height: height + 110,
+            }
+        ]
+    );
+
+    // Emissions don't depend on time passed between blocks
+    helper.app.update_block(|block| block.height += 60);
+
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    assert_eq!(pending.len(), 1);
+    assert!(pending[0].amount.u128().abs_diff(50_000) <= 1);
+
+    helper.app.update_block(|block| {
+        block.height += 100;
+        block.time = block.time.plus_seconds(5)
+    });
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+
+    let claimed = reward_asset_info
+        .query_pool(&helper.app.wrap(), &user)
+        .unwrap()
+        .u128();
+    assert!(claimed.abs_diff(100_000) <= 1);
+    // Finished block based reward is removed from the pool
+    assert_eq!(helper.query_reward_info(&lp_token), vec![]);
+
+    // Block and time based schedules of the same reward are distributed simultaneously
+    let height = helper.app.block_info().height;
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            block_schedule(height, height + 100),
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+    let (schedule, _) = helper
+        .create_schedule(&reward_asset_info.with_balance(100_000_000u128), 1)
+        .unwrap();
+    helper.incentivize(&bank, &lp_token, schedule, &[]).unwrap();
+
+    let rps = helper.query_reward_info(&lp_token)[0].rps;
+
+    helper.next_block(100);
+
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    let expected = 1000 + dec256_to_u128_floor(rps * Decimal256::from_ratio(100u8, 1u8));
+    assert!(pending[0].amount.u128().abs_diff(expected) <= 1);
+
+    let receiver = TestAddr::new("receiver");
+    helper
+        .remove_reward(
+            &owner,
+            &lp_token,
+            &reward_asset_info.to_string(),
+            false,
+            &receiver,
+        )
+        .unwrap();
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+
+    let outstanding = reward_asset_info
+        .query_pool(&helper.app.wrap(), &receiver)
+        .unwrap()
+        .u128();
+    let claimed_total = reward_asset_info
+        .query_pool(&helper.app.wrap(), &user)
+        .unwrap()
+        .u128();
+    assert!((outstanding + claimed_total - claimed).abs_diff(100_100_000) <= 3);
+    assert!(helper
+        .query_block_reward_schedules(&lp_token, &reward_asset_info, None, None)
+        .is_err());
+}
+
+#[test]
+fn test_stake_receipts() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+    let lp_asset = native_asset_info(lp_token.clone());
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    let user1 = TestAddr::new("user1");
+    let user2 = TestAddr::new("user2");
+    helper
+        .provide_liquidity(&user1, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    // Position opened before receipts were enabled isn't backed by receipts
+    helper
+        .stake(&user1, lp_asset.with_balance(10_000u128))
+        .unwrap();
+
+    let err = helper.update_stake_receipts(&user1, true).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper.update_stake_receipts(&owner, true).unwrap();
+    assert!(helper.query_config().stake_receipts);
+    assert_eq!(helper.query_stake_receipt_denom(&lp_token), None);
+
+    helper
+        .stake(&user1, lp_asset.with_balance(20_000u128))
+        .unwrap();
+    let receipt_denom = helper.query_stake_receipt_denom(&lp_token).unwrap();
+    assert_eq!(
+        receipt_denom,
+        format!("factory/{}/staked_1", helper.generator)
+    );
+    let receipts_of = |helper: &Helper, user: &Addr| {
+        helper
+            .app
+            .wrap()
+            .query_balance(user, &receipt_denom)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(receipts_of(&helper, &user1), 20_000);
+
+    let bank = TestAddr::new("bank");
+    let reward = native_asset_info("reward".to_string()).with_balance(1000_000000u128);
+    helper.mint_assets(&bank, &[reward.clone()]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            InputSchedule {
+                reward,
+                duration_periods: 2,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
+            },
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    helper.next_block(86400);
+
+    // Transferring receipts moves the position. Rewards accrued before the transfer stay with the sender
+    // and are paid out on the next claim
+    let pending = helper.query_pending_rewards(&user1, &lp_token);
+    let bal_before = helper.snapshot_balances(&user1, &pending);
+    helper
+        .app
+        .send_tokens(user1.clone(), user2.clone(), &coins(15_000, &receipt_denom))
+        .unwrap();
+    assert_eq!(helper.snapshot_balances(&user1, &pending), bal_before);
+    assert_eq!(helper.query_pending_rewards(&user1, &lp_token), pending);
+
+    helper
+        .claim_rewards(&user1, vec![lp_token.clone()])
+        .unwrap();
+    let bal_after = helper.snapshot_balances(&user1, &pending);
+    assert_rewards(&bal_before, &bal_after, &pending);
+
+    assert_eq!(helper.query_deposit(&lp_token, &user1).unwrap(), 15_000);
+    assert_eq!(helper.query_deposit(&lp_token, &user2).unwrap(), 15_000);
+    assert_eq!(receipts_of(&helper, &user1), 5_000);
+    assert_eq!(receipts_of(&helper, &user2), 15_000);
+
+    // Equal positions accrue equal rewards after the transfer
+    helper.next_block(86400);
+    assert_eq!(
+        helper.query_pending_rewards(&user1, &lp_token),
+        helper.query_pending_rewards(&user2, &lp_token)
+    );
+
+    // Withdrawals of positions backed by receipts require burning them
+    let err = helper.unstake(&user2, &lp_token, 5_000u128).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidStakeReceipts {
+            expected: Uint128::new(5_000),
+            actual: Uint128::zero()
+        }
+    );
+    helper
+        .unstake_with_receipts(&user2, &lp_token, 5_000u128, &coins(5_000, &receipt_denom))
+        .unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user2).unwrap(), 10_000);
+    assert_eq!(receipts_of(&helper, &user2), 10_000);
+
+    // The unbacked part of the position is withdrawn first
+    helper.unstake(&user1, &lp_token, 10_000u128).unwrap();
+    let err = helper.unstake(&user1, &lp_token, 1_000u128).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidStakeReceipts {
+            expected: Uint128::new(1_000),
+            actual: Uint128::zero()
+        }
+    );
+    helper
+        .unstake_with_receipts(&user1, &lp_token, 5_000u128, &coins(5_000, &receipt_denom))
+        .unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user1).unwrap(), 0);
+    assert_eq!(receipts_of(&helper, &user1), 0);
+
+    // Receipts sent to the contract outside of withdrawals don't move the position
+    // and are returned on the next withdrawal
+    helper
+        .app
+        .send_tokens(
+            user2.clone(),
+            helper.generator.clone(),
+            &coins(4_000, &receipt_denom),
+        )
+        .unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user2).unwrap(), 10_000);
+    assert_eq!(receipts_of(&helper, &user2), 6_000);
+
+    helper
+        .unstake_with_receipts(&user2, &lp_token, 5_000u128, &coins(5_000, &receipt_denom))
+        .unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user2).unwrap(), 5_000);
+    assert_eq!(receipts_of(&helper, &user2), 5_000);
+}
+
+#[test]
+fn test_stake_receipt_transfer_of_whole_position() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+    let lp_asset = native_asset_info(lp_token.clone());
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    let user1 = TestAddr::new("user1");
+    let user2 = TestAddr::new("user2");
+    helper
+        .provide_liquidity(&user1, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+    helper.update_stake_receipts(&owner, true).unwrap();
+    helper
+        .stake(&user1, lp_asset.with_balance(10_000u128))
+        .unwrap();
+    let receipt_denom = helper.query_stake_receipt_denom(&lp_token).unwrap();
+
+    let bank = TestAddr::new("bank");
+    let reward = native_asset_info("reward".to_string()).with_balance(1000_000000u128);
+    helper.mint_assets(&bank, &[reward.clone()]);
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(
+            &bank,
+            &lp_token,
+            InputSchedule {
+                reward,
+                duration_periods: 2,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
+            },
+            &[incentivization_fee.clone()],
+        )
+        .unwrap();
+
+    helper.next_block(86400);
+
+    // The emptied position is kept until the sender claims the rewards accrued before the transfer
+    let pending = helper.query_pending_rewards(&user1, &lp_token);
+    helper
+        .app
+        .send_tokens(user1.clone(), user2.clone(), &coins(10_000, &receipt_denom))
+        .unwrap();
+    assert_eq!(helper.query_deposit(&lp_token, &user1).unwrap(), 0);
+    assert_eq!(helper.query_deposit(&lp_token, &user2).unwrap(), 10_000);
+    assert_eq!(helper.query_pending_rewards(&user1, &lp_token), pending);
+
+    let bal_before = helper.snapshot_balances(&user1, &pending);
+    helper
+        .claim_rewards(&user1, vec![lp_token.clone()])
+        .unwrap();
+    let bal_after = helper.snapshot_balances(&user1, &pending);
+    assert_rewards(&bal_before, &bal_after, &pending);
+}
+
+#[test]
+fn test_staking_hooks() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    let owner = helper.owner.clone();
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let hook = helper.init_mock_hook(false);
+    let broken_hook = helper.init_mock_hook(true);
+    let user = TestAddr::new("user");
+
+    let err = helper
+        .update_staking_hooks(&user, &lp_token, &[&hook], &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    helper
+        .update_staking_hooks(&owner, &lp_token, &[&hook, &broken_hook], &[])
+        .unwrap();
+    assert_eq!(
+        helper.query_staking_hooks(&lp_token),
+        vec![hook.clone(), broken_hook.clone()]
+    );
+
+    let extra_hooks = (0..MAX_STAKING_HOOKS)
+        .map(|i| TestAddr::new(&format!("hook{i}")))
+        .collect_vec();
+    let err = helper
+        .update_staking_hooks(&owner, &lp_token, &extra_hooks.iter().collect_vec(), &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::TooManyStakingHooks {
+            lp_token: lp_token.clone()
+        }
+    );
+
+    // The broken hook doesn't block user actions
+    let native_lp = native_asset_info(lp_token.to_string()).with_balance(10000u16);
+    helper.mint_coin(&user, &native_lp.as_coin().unwrap());
+    let resp = helper.stake(&user, native_lp).unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attributes([
+        attr("hook", "position_changed"),
+        attr("user", user.as_str()),
+        attr("amount", "10000"),
+    ])));
+    assert!(resp
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .any(|attr| attr.key == "staking_hook_error"));
+
+    let resp = helper.unstake(&user, &lp_token, 4000u128).unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attributes([
+        attr("hook", "position_changed"),
+        attr("user", user.as_str()),
+        attr("amount", "6000"),
+    ])));
+
+    let resp = helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attributes([attr("hook", "rewards_claimed"), attr("user", user.as_str()),])
+    ));
+
+    helper
+        .update_staking_hooks(&owner, &lp_token, &[], &[&hook, &broken_hook])
+        .unwrap();
+    assert_eq!(helper.query_staking_hooks(&lp_token), Vec::<Addr>::new());
+
+    let resp = helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    assert!(!resp.has_event(&Event::new("wasm").add_attribute("hook", "rewards_claimed")));
+}
+
+#[test]
+fn test_astro_pull_batching() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+    let owner = helper.owner.clone();
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let user = TestAddr::new("user");
+    let native_lp = native_asset_info(lp_token.to_string()).with_balance(10000u16);
+    helper.mint_coin(&user, &native_lp.as_coin().unwrap());
+    helper.stake(&user, native_lp).unwrap();
+
+    helper.setup_pools(vec![(lp_token.clone(), 100)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    let err = helper.update_astro_pull_batching(&user, true).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    helper.update_astro_pull_batching(&owner, true).unwrap();
+    assert!(helper.query_config().astro_pull_batching);
+
+    let astro_balance =
+        |helper: &Helper, addr: &Addr| astro.query_pool(&helper.app.wrap(), addr).unwrap().u128();
+
+    // The first claim in the epoch pulls the whole epoch budget from vesting
+    helper.next_block(1000);
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    assert_eq!(pending, vec![astro.with_balance(100_000u128)]);
+    let bal_before = helper.snapshot_balances(&user, &pending);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let bal_after = helper.snapshot_balances(&user, &pending);
+    assert_rewards(&bal_before, &bal_after, &pending);
+
+    let epoch_budget = 100 * EPOCH_LENGTH as u128;
+    let pulled = helper.query_pulled_astro();
+    assert_eq!(pulled.epoch_start, EPOCHS_START + EPOCH_LENGTH);
+    assert_eq!(pulled.amount.u128(), epoch_budget - 100_000);
+    assert_eq!(
+        astro_balance(&helper, &helper.generator),
+        pulled.amount.u128()
+    );
+
+    // Further claims in the same epoch are paid from the pulled ASTRO
+    helper.next_block(1000);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    let pulled = helper.query_pulled_astro();
+    assert_eq!(pulled.epoch_start, EPOCHS_START + EPOCH_LENGTH);
+    assert_eq!(pulled.amount.u128(), epoch_budget - 200_000);
+    assert_eq!(astro_balance(&helper, &user), 200_000);
+
+    // Rewards exceeding pulled ASTRO trigger a new pull in the next epoch
+    helper.next_block(EPOCH_LENGTH);
+    let pending = helper.query_pending_rewards(&user, &lp_token);
+    assert_eq!(pending, vec![astro.with_balance(epoch_budget)]);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    assert_eq!(astro_balance(&helper, &user), 200_000 + epoch_budget);
+    let pulled = helper.query_pulled_astro();
+    assert_eq!(pulled.epoch_start, EPOCHS_START + 2 * EPOCH_LENGTH);
+    assert_eq!(pulled.amount.u128(), epoch_budget - 200_000);
+    assert_eq!(
+        astro_balance(&helper, &helper.generator),
+        pulled.amount.u128()
+    );
+
+    // Disabling batching still spends ASTRO pulled earlier
+    helper.update_astro_pull_batching(&owner, false).unwrap();
+    helper.next_block(1000);
+    helper.claim_rewards(&user, vec![lp_token.clone()]).unwrap();
+    assert_eq!(
+        helper.query_pulled_astro().amount.u128(),
+        epoch_budget - 300_000
+    );
+}
+
+#[test]
+fn test_lp_token_rewards() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+    let incentivization_fee = helper.incentivization_fee.clone();
+
+    let pair_a = helper
+        .create_pair(&[AssetInfo::native("foo"), AssetInfo::native("bar")])
+        .unwrap();
+    let lp_a = pair_a.liquidity_token.to_string();
+    let pair_b = helper
+        .create_pair(&[AssetInfo::native("foo"), AssetInfo::native("baz")])
+        .unwrap();
+    let lp_b = native_asset_info(pair_b.liquidity_token.to_string());
+
+    let user = TestAddr::new("user");
+    let native_lp = native_asset_info(lp_a.clone()).with_balance(10000u16);
+    helper.mint_coin(&user, &native_lp.as_coin().unwrap());
+    helper.stake(&user, native_lp).unwrap();
+
+    // Pool A is incentivized with LP tokens of pool B
+    let bank = TestAddr::new("bank");
+    let reward = lp_b.with_balance(2 * EPOCH_LENGTH as u128);
+    helper.mint_coin(&bank, &reward.as_coin().unwrap());
+    helper.mint_coin(&bank, &incentivization_fee);
+    helper
+        .incentivize(
+            &bank,
+            &lp_a,
+            InputSchedule {
+                reward,
+                duration_periods: 1,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
+            },
+            &[incentivization_fee],
+        )
+        .unwrap();
+
+    helper.next_block(86400);
+
+    // Regular claim transfers LP rewards to the user
+    let pending = helper.query_pending_rewards(&user, &lp_a);
+    let bal_before = helper.snapshot_balances(&user, &pending);
+    helper.claim_rewards(&user, vec![lp_a.clone()]).unwrap();
+    let bal_after = helper.snapshot_balances(&user, &pending);
+    assert_rewards(&bal_before, &bal_after, &pending);
+
+    helper.next_block(86400);
+
+    let pending = helper.query_pending_rewards(&user, &lp_a);
+    let staked_reward = pending
+        .iter()
+        .find(|asset| asset.info == lp_b)
+        .cloned()
+        .unwrap();
+    assert!(!staked_reward.amount.is_zero());
+
+    let lp_b_balance_before = lp_b.query_pool(&helper.app.wrap(), &user).unwrap();
+    let resp = helper
+        .claim_and_stake_lp_rewards(&user, vec![lp_a.clone()])
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm").add_attribute("staked_lp_reward", staked_reward.to_string())
+    ));
+
+    // LP rewards are deposited into the user's position in pool B
+    assert_eq!(
+        lp_b.query_pool(&helper.app.wrap(), &user).unwrap(),
+        lp_b_balance_before
+    );
+    assert_eq!(
+        helper.query_deposit(&lp_b.to_string(), &user).unwrap(),
+        staked_reward.amount.u128()
+    );
+}
+
+#[test]
+fn test_claim_on_behalf() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let asset_infos = [AssetInfo::native("foo"), AssetInfo::native("bar")];
+    let pair_info = helper.create_pair(&asset_infos).unwrap();
+    let lp_token = pair_info.liquidity_token.to_string();
+
+    let provide_assets = [
+        asset_infos[0].with_balance(100000u64),
+        asset_infos[1].with_balance(100000u64),
+    ];
+    helper
+        .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+        .unwrap();
+
+    helper.setup_pools(vec![(lp_token.clone(), 100)]).unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    let user = TestAddr::new("user");
+    let bot = TestAddr::new("bot");
+    helper
+        .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+        .unwrap();
+
+    helper.next_block(86400);
+
+    let not_approved = ContractError::ClaimOperatorNotApproved {
+        user: user.to_string(),
+        operator: bot.to_string(),
+    };
+    let err = helper
+        .claim_rewards_on_behalf(&bot, &user, vec![lp_token.clone()])
+        .unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), not_approved);
+
+    let now = helper.app.block_info().time.seconds();
+    let err = helper
+        .approve_claim_operator(&user, &bot, Some(now))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ClaimOperatorExpiryInPast { expires_at: now }
+    );
+
+    let expires_at = now + 2 * 86400;
+    helper
+        .approve_claim_operator(&user, &bot, Some(expires_at))
+        .unwrap();
+    assert_eq!(
+        helper.query_claim_operators(&user),
+        vec![ClaimOperator {
+            operator: bot.clone(),
+            expires_at: Some(expires_at),
+        }]
+    );
+
+    // Rewards are sent to the position owner
+    helper
+        .claim_rewards_on_behalf(&bot, &user, vec![lp_token.clone()])
+        .unwrap();
+    assert_eq!(
+        astro.query_pool(&helper.app.wrap(), &user).unwrap().u128(),
+        86400 * 100
+    );
+    assert_eq!(
+        astro.query_pool(&helper.app.wrap(), &bot).unwrap().u128(),
+        0
+    );
+
+    // The approval expires
+    helper.next_block(2 * 86400);
+    let err = helper
+        .claim_rewards_on_behalf(&bot, &user, vec![lp_token.clone()])
+        .unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), not_approved);
+
+    helper.approve_claim_operator(&user, &bot, None).unwrap();
+    helper
+        .claim_rewards_on_behalf(&bot, &user, vec![lp_token.clone()])
+        .unwrap();
+    assert_eq!(
+        astro.query_pool(&helper.app.wrap(), &user).unwrap().u128(),
+        3 * 86400 * 100
+    );
+
+    helper.revoke_claim_operator(&user, &bot).unwrap();
+    assert_eq!(helper.query_claim_operators(&user), vec![]);
+    let err = helper
+        .claim_rewards_on_behalf(&bot, &user, vec![lp_token])
+        .unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), not_approved);
+}
+
+#[test]
+fn test_reward_receiver() {
+    let astro = native_asset_info("astro".to_string());
+    let mut helper = Helper::new("owner", &astro, false).unwrap();
+    helper
+        .app
+        .update_block(|block| block.time = Timestamp::from_seconds(EPOCHS_START + EPOCH_LENGTH));
+
+    let owner = helper.owner.clone();
+    let user = TestAddr::new("user");
+    let cold_wallet = TestAddr::new("cold_wallet");
+
+    let mut lp_tokens = vec![];
+    for denom in ["bar", "baz"] {
+        let asset_infos = [AssetInfo::native("foo"), AssetInfo::native(denom)];
+        let pair_info = helper.create_pair(&asset_infos).unwrap();
+        let provide_assets = [
+            asset_infos[0].with_balance(100000u64),
+            asset_infos[1].with_balance(100000u64),
+        ];
+        helper
+            .provide_liquidity(&owner, &provide_assets, &pair_info.contract_addr, false)
+            .unwrap();
+        helper
+            .provide_liquidity(&user, &provide_assets, &pair_info.contract_addr, true)
+            .unwrap();
+        lp_tokens.push(pair_info.liquidity_token.to_string());
+    }
+
+    helper
+        .setup_pools(vec![
+            (lp_tokens[0].clone(), 100),
+            (lp_tokens[1].clone(), 100),
+        ])
+        .unwrap();
+    helper.set_tokens_per_second(100).unwrap();
+
+    helper
+        .set_reward_receiver(&user, &lp_tokens[0], Some(&cold_wallet))
+        .unwrap();
+    assert_eq!(
+        helper.query_reward_receiver(&user, &lp_tokens[0]),
+        Some(cold_wallet.clone())
+    );
+    assert_eq!(helper.query_reward_receiver(&user, &lp_tokens[1]), None);
+
+    let astro_balance =
+        |helper: &Helper, addr: &Addr| astro.query_pool(&helper.app.wrap(), addr).unwrap().u128();
+
+    // Rewards of the first pool go to the receiver while the second pool pays to the user
+    helper.next_block(86400);
+    helper.claim_rewards(&user, lp_tokens.clone()).unwrap();
+    assert_eq!(astro_balance(&helper, &cold_wallet), 86400 * 50);
+    assert_eq!(astro_balance(&helper, &user), 86400 * 50);
+
+    // Rewards claimed on withdrawal go to the receiver as well
+    helper.next_block(86400);
+    let deposit = helper.query_deposit(&lp_tokens[0], &user).unwrap();
+    helper.unstake(&user, &lp_tokens[0], deposit).unwrap();
+    assert_eq!(astro_balance(&helper, &cold_wallet), 2 * 86400 * 50);
+    assert_eq!(astro_balance(&helper, &user), 86400 * 50);
+    // LP tokens are returned to the user
+    let lp_balance = native_asset_info(lp_tokens[0].clone())
+        .query_pool(&helper.app.wrap(), &user)
+        .unwrap()
+        .u128();
+    assert_eq!(lp_balance, deposit);
+
+    // Setting the user as the receiver clears it
+    helper
+        .set_reward_receiver(&user, &lp_tokens[1], Some(&cold_wallet))
+        .unwrap();
+    helper
+        .set_reward_receiver(&user, &lp_tokens[1], Some(&user))
+        .unwrap();
+    assert_eq!(helper.query_reward_receiver(&user, &lp_tokens[1]), None);
+
+    helper
+        .set_reward_receiver(&user, &lp_tokens[0], None)
+        .unwrap();
+    assert_eq!(helper.query_reward_receiver(&user, &lp_tokens[0]), None);
+
+    helper.next_block(86400);
+    helper
+        .claim_rewards(&user, vec![lp_tokens[1].clone()])
+        .unwrap();
+    assert_eq!(astro_balance(&helper, &cold_wallet), 2 * 86400 * 50);
+    // Rewards of the second pool accrued since the first claim
+    assert_eq!(astro_balance(&helper, &user), 3 * 86400 * 50);
+}
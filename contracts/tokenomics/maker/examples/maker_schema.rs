@@ -1,12 +1,13 @@
 use cosmwasm_schema::write_api;
 
-use astroport::maker::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use astroport::maker::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         query: QueryMsg,
         execute: ExecuteMsg,
-        migrate: MigrateMsg
+        migrate: MigrateMsg,
+        sudo: SudoMsg
     }
 }
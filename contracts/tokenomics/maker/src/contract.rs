@@ -3,27 +3,48 @@ use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, entry_point, to_json_binary, Addr, Attribute, Binary, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdError, StdResult, SubMsg, Uint128, Uint64,
+    attr, coins, entry_point, to_json_binary, wasm_execute, Addr, Attribute, Binary, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, Uint128,
+    Uint64,
 };
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use osmosis_std::types::ibc::applications::transfer::v1::MsgTransferResponse;
 
-use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
-use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::common::{
+    claim_ownership, drop_ownership_proposal, init_ownership_timelock, propose_new_owner,
+    update_ownership_timelock,
+};
 use astroport::factory::UpdateAddr;
 use astroport::maker::{
-    AssetWithLimit, BalancesResponse, Config, ConfigResponse, ExecuteMsg, InstantiateMsg,
-    MigrateMsg, QueryMsg, SecondReceiverConfig, SecondReceiverParams,
+    AssetWithLimit, BalancesResponse, Config, ConfigResponse, DistributionWindowResponse,
+    DistributionWindowsConfig, ExecuteMsg, GovernanceVestingConfig, GovernanceVestingResponse,
+    IBCLifecycleComplete, IbcDistributionConfig, IbcTransferInfo, InstantiateMsg, MigrateMsg,
+    QueryMsg, SecondReceiverConfig, SecondReceiverParams, SimulateCollectResponse, SudoMsg,
+    SwapLimit, SwapLimitParams, UnwrapRoute, UnwrapRouteParams,
+};
+use astroport::pair::{QueryMsg as PairQueryMsg, SimulationResponse, MAX_ALLOWED_SLIPPAGE};
+use astroport::querier::query_pair_info;
+use astroport::route::{HopKind, Route, RouteError};
+use astroport::staking::{
+    Config as StakingConfig, ExecuteMsg as StakingExecuteMsg, QueryMsg as StakingQueryMsg,
 };
-use astroport::pair::MAX_ALLOWED_SLIPPAGE;
 
 use crate::error::ContractError;
-use crate::migration::migrate_from_v120_plus;
-use crate::state::{BRIDGES, CONFIG, LAST_COLLECT_TS, OWNERSHIP_PROPOSAL};
+use crate::migration::{migrate_bridges, migrate_from_v120_plus};
+use crate::state::{
+    BRIDGES, CONFIG, DISTRIBUTION_WINDOWS, FEE_SHARE_STATS, GOVERNANCE_VESTING,
+    GOVERNANCE_VESTING_PENDING, IBC_DISTRIBUTION, IBC_TRANSFER_BUFFER, LAST_COLLECT_TS,
+    LAST_DISTRIBUTION_WINDOW, LAST_GOVERNANCE_VESTING_TS, OWNERSHIP_PROPOSAL,
+    PENDING_IBC_TRANSFERS, SWAP_LIMITS, UNWRAP_ROUTES,
+};
 use crate::utils::{
-    build_distribute_msg, build_send_msg, build_swap_msg, try_build_swap_msg,
-    update_second_receiver_cfg, validate_bridge, validate_cooldown, BRIDGES_EXECUTION_MAX_DEPTH,
-    BRIDGES_INITIAL_DEPTH,
+    build_distribute_msg, build_governance_vesting_msg, build_ibc_transfer_msg, build_send_msg,
+    build_swap_msg, get_pool, get_swap_limits, min_collect_amount, next_distribution_window,
+    update_second_receiver_cfg, validate_bridge, validate_cooldown, validate_distribution_windows,
+    validate_governance_vesting, validate_ibc_distribution, BRIDGES_EXECUTION_MAX_DEPTH,
+    BRIDGES_INITIAL_DEPTH, IBC_TRANSFER_REPLY_ID,
 };
 
 /// Contract name that is used for migration.
@@ -32,6 +53,10 @@ const CONTRACT_NAME: &str = "astroport-maker";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Sets the default maximum spread (as a percentage) used when swapping fee tokens to ASTRO.
 const DEFAULT_MAX_SPREAD: u64 = 5; // 5%
+/// Default limit for pending IBC transfers query
+const DEFAULT_PAGINATION_LIMIT: u32 = 10;
+/// Max limit for pending IBC transfers query
+const MAX_PAGINATION_LIMIT: u32 = 30;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -103,6 +128,7 @@ pub fn instantiate(
     }
 
     CONFIG.save(deps.storage, &cfg)?;
+    init_ownership_timelock(deps.storage, msg.ownership_timelock)?;
 
     let (second_fee_receiver, second_receiver_cut) = if let Some(SecondReceiverConfig {
         second_fee_receiver,
@@ -170,7 +196,23 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
 ///
+/// * **ExecuteMsg::UpdateOwnershipTimelock { timelock }** Sets the delay before a proposed owner can claim ownership.
+///
 /// * **ExecuteMsg::EnableRewards** Enables collected ASTRO (pre Maker upgrade) to be distributed to xASTRO stakers.
+///
+/// * **ExecuteMsg::UpdateIbcDistribution { config }** Enables or disables transfers of the staking share of ASTRO to the hub.
+///
+/// * **ExecuteMsg::FeeShareReceived { asset }** Records fees shared by a factory pair.
+///
+/// * **ExecuteMsg::UpdateGovernanceVesting { config }** Enables or disables vesting of the governance share.
+///
+/// * **ExecuteMsg::UpdateDistributionWindows { config }** Enables or disables scheduled distributions.
+///
+/// * **ExecuteMsg::Distribute {}** Distributes accumulated ASTRO within a distribution window.
+///
+/// * **ExecuteMsg::UpdateSwapLimits { set, remove }** Sets or removes per-asset swap limits.
+///
+/// * **ExecuteMsg::UpdateUnwrapRoutes { set, remove }** Sets or removes routes used to unwrap wrapped fee tokens.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -239,6 +281,11 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::UpdateOwnershipTimelock { timelock } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            update_ownership_timelock(deps, info, env, timelock, config.owner).map_err(Into::into)
+        }
         ExecuteMsg::EnableRewards { blocks } => {
             let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -265,7 +312,61 @@ pub fn execute(
 
             Ok(Response::default().add_attribute("action", "enable_rewards"))
         }
+        ExecuteMsg::UpdateIbcDistribution { config } => update_ibc_distribution(deps, info, config),
+        ExecuteMsg::FeeShareReceived { asset } => fee_share_received(deps, info, asset),
+        ExecuteMsg::UpdateGovernanceVesting { config } => {
+            update_governance_vesting(deps, env, info, config)
+        }
+        ExecuteMsg::UpdateDistributionWindows { config } => {
+            update_distribution_windows(deps, info, config)
+        }
+        ExecuteMsg::Distribute {} => scheduled_distribute(deps, env, info),
+        ExecuteMsg::UpdateSwapLimits { set, remove } => update_swap_limits(deps, info, set, remove),
+        ExecuteMsg::UpdateUnwrapRoutes { set, remove } => {
+            update_unwrap_routes(deps, info, set, remove)
+        }
+    }
+}
+
+/// Accumulates fees shared by a pair. Only pairs registered in the factory are allowed to report shared fees.
+///
+/// * **asset** fee asset transferred to the Maker right before this callback.
+fn fee_share_received(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // The sender must be a pair contract registered in the factory
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&info.sender, &PairQueryMsg::Pair {})
+        .map_err(|_| ContractError::Unauthorized {})?;
+    let registered = query_pair_info(
+        &deps.querier,
+        &config.factory_contract,
+        &pair_info.asset_infos,
+    )
+    .map_err(|_| ContractError::Unauthorized {})?;
+    if registered.contract_addr != info.sender || !pair_info.asset_infos.contains(&asset.info) {
+        return Err(ContractError::Unauthorized {});
     }
+
+    FEE_SHARE_STATS.update::<_, StdError>(deps.storage, &info.sender, |stats| {
+        let mut stats = stats.unwrap_or_default();
+        match stats.iter_mut().find(|total| total.info == asset.info) {
+            Some(total) => *total = total.checked_add(&asset)?,
+            None => stats.push(asset.clone()),
+        }
+        Ok(stats)
+    })?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "fee_share_received"),
+        attr("pair", info.sender),
+        attr("asset", asset.to_string()),
+    ]))
 }
 
 /// Swaps fee tokens to ASTRO and distribute the resulting ASTRO to xASTRO and vxASTRO stakers.
@@ -300,17 +401,26 @@ fn collect(
         return Err(ContractError::DuplicatedAsset {});
     }
 
+    // Unwrap wrapped fee tokens (e.g. xASTRO) to ASTRO
+    let (unwrap_msgs, assets) = unwrap_assets(deps.as_ref(), &env.contract.address, assets)?;
+
     // Swap all non ASTRO tokens
     let (mut response, bridge_assets) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &cfg,
         assets.into_iter().filter(|a| a.info.ne(&astro)).collect(),
+        true,
     )?;
 
-    // If no swap messages - send ASTRO directly to x/vxASTRO stakers
-    if response.messages.is_empty() {
-        let (mut distribute_msg, attributes) = distribute(deps, env, &mut cfg)?;
+    // If no swap or unwrap messages - send ASTRO directly to x/vxASTRO stakers.
+    // Scheduled distributions keep ASTRO in the Maker until the next distribution window
+    if response.messages.is_empty() && unwrap_msgs.is_empty() {
+        if DISTRIBUTION_WINDOWS.exists(deps.storage) {
+            return Ok(response.add_attribute("action", "collect"));
+        }
+
+        let (mut distribute_msg, attributes) = distribute(deps, env, &mut cfg, None)?;
         if !distribute_msg.is_empty() {
             response.messages.append(&mut distribute_msg);
             response = response.add_attributes(attributes);
@@ -323,9 +433,60 @@ fn collect(
         )?);
     }
 
+    // Unwrapped ASTRO must be received before the distribution
+    response.messages.splice(0..0, unwrap_msgs);
+
     Ok(response.add_attribute("action", "collect"))
 }
 
+/// Builds messages unwrapping fee tokens which have an unwrap route configured.
+/// Returns the unwrap messages along with the remaining fee tokens.
+///
+/// * **contract_addr** maker contract address.
+///
+/// * **assets** array with fee tokens being collected.
+fn unwrap_assets(
+    deps: Deps,
+    contract_addr: &Addr,
+    assets: Vec<AssetWithLimit>,
+) -> Result<(Vec<SubMsg>, Vec<AssetWithLimit>), ContractError> {
+    let mut messages = vec![];
+    let mut remaining = vec![];
+
+    for a in assets {
+        let route = match UNWRAP_ROUTES.may_load(deps.storage, a.info.to_string())? {
+            Some(route) => route,
+            None => {
+                remaining.push(a);
+                continue;
+            }
+        };
+
+        let mut balance = a.info.query_pool(&deps.querier, contract_addr)?;
+        if let Some(limit) = a.limit {
+            if limit < balance && limit > Uint128::zero() {
+                balance = limit;
+            }
+        }
+
+        if balance.is_zero() {
+            continue;
+        }
+
+        match route {
+            UnwrapRoute::StakingLeave { staking_contract } => {
+                messages.push(SubMsg::new(wasm_execute(
+                    staking_contract,
+                    &StakingExecuteMsg::Leave { receiver: None },
+                    coins(balance.u128(), a.info.to_string()),
+                )?));
+            }
+        }
+    }
+
+    Ok((messages, remaining))
+}
+
 /// This enum describes available token types that can be used as a SwapTarget.
 enum SwapTarget {
     Astro(SubMsg),
@@ -338,12 +499,13 @@ enum SwapTarget {
 ///
 /// * **assets** array with assets to swap to ASTRO.
 ///
-/// * **with_validation** whether the swap operation should be validated or not.
+/// * **check_thresholds** whether balances below the per-asset minimum collect amount are skipped.
 fn swap_assets(
     deps: Deps,
     contract_addr: &Addr,
     cfg: &Config,
     assets: Vec<AssetWithLimit>,
+    check_thresholds: bool,
 ) -> Result<(Response, Vec<AssetInfo>), ContractError> {
     let mut response = Response::default();
     let mut bridge_assets = HashMap::new();
@@ -357,6 +519,11 @@ fn swap_assets(
             }
         }
 
+        if check_thresholds && balance < min_collect_amount(deps.storage, &a.info)? {
+            response = response.add_attribute("skipped_asset", a.info.to_string());
+            continue;
+        }
+
         if !balance.is_zero() {
             match swap(deps, cfg, a.info, balance)? {
                 SwapTarget::Astro(msg) => {
@@ -384,59 +551,105 @@ fn swap(
     from_token: AssetInfo,
     amount_in: Uint128,
 ) -> Result<SwapTarget, ContractError> {
+    let (pool, to_token) = find_swap_pool(deps, cfg, &from_token)?;
+    let (max_spread, belief_price) = get_swap_limits(deps, cfg, &from_token, &to_token, amount_in)?;
+    let msg = build_swap_msg(
+        max_spread,
+        belief_price,
+        &pool,
+        &from_token,
+        Some(&to_token),
+        amount_in,
+    )?;
+
+    if to_token == cfg.astro_token {
+        Ok(SwapTarget::Astro(msg))
+    } else {
+        Ok(SwapTarget::Bridge {
+            asset: to_token,
+            msg,
+        })
+    }
+}
+
+/// Returns the pool used to swap a fee token on its way to ASTRO along with the asset received from it.
+///
+/// * **from_token** token to swap to ASTRO.
+fn find_swap_pool(
+    deps: Deps,
+    cfg: &Config,
+    from_token: &AssetInfo,
+) -> Result<(PairInfo, AssetInfo), ContractError> {
     // 1. Check if bridge tokens exist
-    let bridge_token = BRIDGES.load(deps.storage, from_token.to_string());
-    if let Ok(bridge_token) = bridge_token {
+    if let Some(bridge) = BRIDGES.may_load(deps.storage, from_token.to_string())? {
         let bridge_pool = validate_bridge(
             deps,
             &cfg.factory_contract,
-            &from_token,
-            &bridge_token,
+            &bridge,
             &cfg.astro_token,
             BRIDGES_INITIAL_DEPTH,
         )?;
+        let bridge_token = bridge.ask_asset().ok_or(RouteError::EmptyRoute {})?;
 
-        let msg = build_swap_msg(
-            cfg.max_spread,
-            &bridge_pool,
-            &from_token,
-            Some(&bridge_token),
-            amount_in,
-        )?;
-
-        let swap_msg = if bridge_token == cfg.astro_token {
-            SwapTarget::Astro(msg)
-        } else {
-            SwapTarget::Bridge {
-                asset: bridge_token,
-                msg,
-            }
-        };
-        return Ok(swap_msg);
+        return Ok((bridge_pool, bridge_token.clone()));
     }
 
     // 2. Check for a pair with a default bridge
     if let Some(default_bridge) = &cfg.default_bridge {
         if from_token.ne(default_bridge) {
-            let swap_to_default =
-                try_build_swap_msg(&deps.querier, cfg, &from_token, default_bridge, amount_in);
-            if let Ok(msg) = swap_to_default {
-                return Ok(SwapTarget::Bridge {
-                    asset: default_bridge.clone(),
-                    msg,
-                });
+            let pool = get_pool(
+                &deps.querier,
+                &cfg.factory_contract,
+                from_token,
+                default_bridge,
+            );
+            if let Ok(pool) = pool {
+                return Ok((pool, default_bridge.clone()));
             }
         }
     }
 
     // 3. Check for a direct pair with ASTRO
-    let swap_to_astro =
-        try_build_swap_msg(&deps.querier, cfg, &from_token, &cfg.astro_token, amount_in);
-    if let Ok(msg) = swap_to_astro {
-        return Ok(SwapTarget::Astro(msg));
+    let pool = get_pool(
+        &deps.querier,
+        &cfg.factory_contract,
+        from_token,
+        &cfg.astro_token,
+    );
+    if let Ok(pool) = pool {
+        return Ok((pool, cfg.astro_token.clone()));
     }
 
-    Err(ContractError::CannotSwap(from_token))
+    Err(ContractError::CannotSwap(from_token.clone()))
+}
+
+/// Estimates the amount of ASTRO received for a fee token following the same route as [`collect`].
+///
+/// * **offer_asset** fee token and the amount to swap.
+fn simulate_swap_to_astro(
+    deps: Deps,
+    cfg: &Config,
+    mut offer_asset: Asset,
+) -> Result<Uint128, ContractError> {
+    // The first swap is done by collect itself and the following ones by swap_bridge_assets
+    for _ in 0..=BRIDGES_EXECUTION_MAX_DEPTH {
+        let (pool, to_token) = find_swap_pool(deps, cfg, &offer_asset.info)?;
+        let simulation: SimulationResponse = deps.querier.query_wasm_smart(
+            &pool.contract_addr,
+            &PairQueryMsg::Simulation {
+                offer_asset,
+                ask_asset_info: Some(to_token.clone()),
+            },
+        )?;
+
+        if to_token == cfg.astro_token {
+            return Ok(simulation.return_amount);
+        }
+
+        offer_asset = to_token.with_balance(simulation.return_amount);
+    }
+
+    Err(ContractError::MaxBridgeDepth(BRIDGES_EXECUTION_MAX_DEPTH))
 }
 
 /// Swaps collected fees using bridge assets.
@@ -478,7 +691,7 @@ fn swap_bridge_assets(
         .collect();
 
     let (response, bridge_assets) =
-        swap_assets(deps.as_ref(), &env.contract.address, &cfg, bridges)?;
+        swap_assets(deps.as_ref(), &env.contract.address, &cfg, bridges, false)?;
 
     // There should always be some messages, if there are none - something went wrong
     if response.messages.is_empty() {
@@ -501,8 +714,13 @@ fn distribute_astro(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
         return Err(ContractError::Unauthorized {});
     }
 
+    // ASTRO is distributed only within distribution windows if they are enabled
+    if DISTRIBUTION_WINDOWS.exists(deps.storage) {
+        return Ok(Response::default());
+    }
+
     let mut cfg = CONFIG.load(deps.storage)?;
-    let (distribute_msg, attributes) = distribute(deps, env, &mut cfg)?;
+    let (distribute_msg, attributes) = distribute(deps, env, &mut cfg, None)?;
     if distribute_msg.is_empty() {
         return Ok(Response::default());
     }
@@ -512,20 +730,73 @@ fn distribute_astro(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
         .add_attributes(attributes))
 }
 
+/// Distributes accumulated ASTRO within a distribution window.
+/// The caller receives a share of the distributed ASTRO as a bounty.
+/// ASTRO can be distributed only once per window.
+fn scheduled_distribute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let windows_cfg = DISTRIBUTION_WINDOWS
+        .may_load(deps.storage)?
+        .ok_or(ContractError::DistributionWindowsDisabled {})?;
+
+    let now = env.block.time.seconds();
+    let (window, window_start) = next_distribution_window(
+        &windows_cfg,
+        now,
+        LAST_DISTRIBUTION_WINDOW.may_load(deps.storage)?,
+    );
+    if now < window_start {
+        return Err(ContractError::DistributionWindowClosed {
+            next_window_start: window_start,
+        });
+    }
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let (distribute_msg, attributes) = distribute(
+        deps.branch(),
+        env,
+        &mut cfg,
+        Some((&info.sender, windows_cfg.caller_bounty)),
+    )?;
+
+    // The window is not consumed if there is nothing to distribute yet
+    if distribute_msg.is_empty() {
+        return Ok(Response::default().add_attribute("action", "distribute"));
+    }
+
+    LAST_DISTRIBUTION_WINDOW.save(deps.storage, &window)?;
+
+    Ok(Response::default()
+        .add_submessages(distribute_msg)
+        .add_attributes(attributes)
+        .add_attribute("distribution_window", window.to_string()))
+}
+
 type DistributeMsgParts = (Vec<SubMsg>, Vec<Attribute>);
 
 /// Private function that performs the ASTRO token distribution to x/vxASTRO.
+///
+/// * **bounty** caller and their share of the distributed ASTRO.
 fn distribute(
     deps: DepsMut,
     env: Env,
     cfg: &mut Config,
+    bounty: Option<(&Addr, Decimal)>,
 ) -> Result<DistributeMsgParts, ContractError> {
     let mut result = vec![];
     let mut attributes = vec![];
 
+    // The governance share awaiting vesting is not distributed again
+    let governance_pending = GOVERNANCE_VESTING_PENDING
+        .may_load(deps.storage)?
+        .unwrap_or_default();
     let mut amount = cfg
         .astro_token
-        .query_pool(&deps.querier, &env.contract.address)?;
+        .query_pool(&deps.querier, &env.contract.address)?
+        .checked_sub(governance_pending)?;
     if amount.is_zero() {
         return Ok((result, attributes));
     }
@@ -567,6 +838,19 @@ fn distribute(
         CONFIG.save(deps.storage, cfg)?;
     }
 
+    let mut bounty_amount = Uint128::zero();
+    if let Some((caller, caller_bounty)) = bounty {
+        bounty_amount = amount * caller_bounty;
+        if !bounty_amount.is_zero() {
+            result.push(SubMsg::new(
+                cfg.astro_token
+                    .with_balance(bounty_amount)
+                    .into_msg(caller)?,
+            ));
+            amount = amount.checked_sub(bounty_amount)?;
+        }
+    }
+
     let second_receiver_amount = if let Some(second_receiver_cfg) = &cfg.second_receiver_cfg {
         let amount = amount.multiply_ratio(
             Uint128::from(second_receiver_cfg.second_receiver_cut),
@@ -589,12 +873,34 @@ fn distribute(
         Uint128::zero()
     };
 
+    let mut governance_vested_amount = Uint128::zero();
     let governance_amount = if let Some(governance_contract) = &cfg.governance_contract {
         let amount = amount
             .checked_sub(second_receiver_amount)?
             .multiply_ratio(Uint128::from(cfg.governance_percent), Uint128::new(100));
 
-        if !amount.is_zero() {
+        if let Some(vesting_cfg) = GOVERNANCE_VESTING.may_load(deps.storage)? {
+            let pending = governance_pending.checked_add(amount)?;
+            let last_vesting_ts = LAST_GOVERNANCE_VESTING_TS.load(deps.storage)?;
+            let now = env.block.time.seconds();
+
+            // At most one vesting schedule per period is registered to keep the number of
+            // governance schedules in the vesting contract bounded
+            if !pending.is_zero() && now >= last_vesting_ts + vesting_cfg.duration {
+                result.push(SubMsg::new(build_governance_vesting_msg(
+                    &cfg.astro_token.with_balance(pending),
+                    &vesting_cfg.vesting_contract,
+                    governance_contract,
+                    now,
+                    vesting_cfg.duration,
+                )?));
+                GOVERNANCE_VESTING_PENDING.save(deps.storage, &Uint128::zero())?;
+                LAST_GOVERNANCE_VESTING_TS.save(deps.storage, &now)?;
+                governance_vested_amount = pending;
+            } else {
+                GOVERNANCE_VESTING_PENDING.save(deps.storage, &pending)?;
+            }
+        } else if !amount.is_zero() {
             result.push(SubMsg::new(build_send_msg(
                 &Asset {
                     info: cfg.astro_token.clone(),
@@ -610,7 +916,43 @@ fn distribute(
         Uint128::zero()
     };
 
-    if let Some(staking_contract) = &cfg.staking_contract {
+    attributes = vec![
+        attr("action", "distribute_astro"),
+        attr("astro_distribution", pure_astro_reward),
+    ];
+
+    if !governance_vested_amount.is_zero() {
+        attributes.push(attr("governance_vested_amount", governance_vested_amount));
+    }
+
+    if !bounty_amount.is_zero() {
+        attributes.push(attr("caller_bounty", bounty_amount));
+    }
+
+    if let Some(ibc_cfg) = IBC_DISTRIBUTION.may_load(deps.storage)? {
+        let amount = amount.checked_sub(governance_amount + second_receiver_amount)?;
+        if !amount.is_zero() {
+            let to_hub_asset = Asset {
+                info: cfg.astro_token.clone(),
+                amount,
+            };
+            let transfer_msg = build_ibc_transfer_msg(&env, &ibc_cfg, &to_hub_asset)?;
+            IBC_TRANSFER_BUFFER.save(
+                deps.storage,
+                &IbcTransferInfo {
+                    channel: ibc_cfg.channel,
+                    receiver: ibc_cfg.receiver,
+                    amount,
+                    sent_at: env.block.time.seconds(),
+                },
+            )?;
+            result.push(SubMsg::reply_on_success(
+                transfer_msg,
+                IBC_TRANSFER_REPLY_ID,
+            ));
+            attributes.push(attr("ibc_transfer_amount", amount));
+        }
+    } else if let Some(staking_contract) = &cfg.staking_contract {
         let amount = amount.checked_sub(governance_amount + second_receiver_amount)?;
         if !amount.is_zero() {
             let to_staking_asset = Asset {
@@ -621,10 +963,6 @@ fn distribute(
         }
     }
 
-    attributes = vec![
-        attr("action", "distribute_astro"),
-        attr("astro_distribution", pure_astro_reward),
-    ];
     if !current_preupgrade_distribution.is_zero() {
         attributes.push(attr(
             "preupgrade_astro_distribution",
@@ -699,6 +1037,12 @@ fn update_config(
                     )
                     .into());
                 }
+                if GOVERNANCE_VESTING.exists(deps.storage) {
+                    return Err(StdError::generic_err(
+                        "Cannot remove governance contract while governance vesting is enabled",
+                    )
+                    .into());
+                }
                 attributes.push(attr("governance_contract", "removed"));
                 config.governance_contract = None;
             }
@@ -709,7 +1053,10 @@ fn update_config(
         if governance_percent > Uint64::new(100) {
             return Err(ContractError::IncorrectGovernancePercent {});
         };
-        if config.staking_contract.is_none() && governance_percent != Uint64::new(100) {
+        if config.staking_contract.is_none()
+            && !IBC_DISTRIBUTION.exists(deps.storage)
+            && governance_percent != Uint64::new(100)
+        {
             return Err(ContractError::GovernancePercentMustBe100 {});
         }
 
@@ -762,18 +1109,166 @@ fn update_config(
     Ok(Response::new().add_attributes(attributes))
 }
 
-/// Adds or removes bridge tokens used to swap fee tokens to ASTRO.
+/// Enables or disables the IBC distribution mode.
 ///
-/// * **add** array of bridge tokens added to swap fee tokens with.
+/// * **config** IBC distribution parameters. None disables the mode.
 ///
-/// * **remove** array of bridge tokens removed from being used to swap certain fee tokens.
+/// ## Executor
+/// Only the owner can execute this.
+fn update_ibc_distribution(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<IbcDistributionConfig>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_ibc_distribution")];
+
+    if let Some(ibc_cfg) = config {
+        validate_ibc_distribution(&ibc_cfg, &cfg.astro_token)?;
+        IBC_DISTRIBUTION.save(deps.storage, &ibc_cfg)?;
+        attributes.extend([
+            attr("channel", ibc_cfg.channel),
+            attr("receiver", ibc_cfg.receiver),
+            attr("timeout", ibc_cfg.timeout.to_string()),
+        ]);
+    } else {
+        // The staking share must have a destination once the mode is disabled
+        if cfg.staking_contract.is_none() && cfg.governance_percent != Uint64::new(100) {
+            return Err(ContractError::GovernancePercentMustBe100 {});
+        }
+        IBC_DISTRIBUTION.remove(deps.storage);
+        attributes.push(attr("ibc_distribution", "disabled"));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Enables or disables vesting of the governance share.
+/// Disabling sends the accumulated governance share straight to the governance contract.
+///
+/// * **config** governance vesting parameters. None disables vesting.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_governance_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<GovernanceVestingConfig>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut response = Response::new().add_attribute("action", "update_governance_vesting");
+
+    if let Some(vesting_cfg) = config {
+        if cfg.governance_contract.is_none() {
+            return Err(ContractError::GovernanceVestingWithoutGovernance {});
+        }
+        validate_governance_vesting(
+            deps.as_ref(),
+            &env.contract.address,
+            &vesting_cfg,
+            &cfg.astro_token,
+        )?;
+
+        if !GOVERNANCE_VESTING.exists(deps.storage) {
+            GOVERNANCE_VESTING_PENDING.save(deps.storage, &Uint128::zero())?;
+            // The first schedule is registered on the next distribution
+            LAST_GOVERNANCE_VESTING_TS.save(deps.storage, &0)?;
+        }
+        GOVERNANCE_VESTING.save(deps.storage, &vesting_cfg)?;
+
+        response = response.add_attributes([
+            attr("vesting_contract", vesting_cfg.vesting_contract),
+            attr("duration", vesting_cfg.duration.to_string()),
+        ]);
+    } else {
+        if let Some(governance_contract) = &cfg.governance_contract {
+            let pending = GOVERNANCE_VESTING_PENDING
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            if !pending.is_zero() {
+                response = response.add_message(build_send_msg(
+                    &cfg.astro_token.with_balance(pending),
+                    governance_contract.to_string(),
+                    None,
+                )?);
+            }
+        }
+
+        GOVERNANCE_VESTING.remove(deps.storage);
+        GOVERNANCE_VESTING_PENDING.remove(deps.storage);
+        LAST_GOVERNANCE_VESTING_TS.remove(deps.storage);
+        response = response.add_attribute("governance_vesting", "disabled");
+    }
+
+    Ok(response)
+}
+
+/// Enables or disables scheduled distributions.
+/// Disabling lets the next collect call distribute the accumulated ASTRO.
+///
+/// * **config** distribution windows parameters. None disables the schedule.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn update_distribution_windows(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<DistributionWindowsConfig>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr("action", "update_distribution_windows")];
+
+    // Window indexes are relative to the schedule thus they are reset on any change
+    LAST_DISTRIBUTION_WINDOW.remove(deps.storage);
+
+    if let Some(windows_cfg) = config {
+        validate_distribution_windows(&windows_cfg)?;
+        DISTRIBUTION_WINDOWS.save(deps.storage, &windows_cfg)?;
+        attributes.extend([
+            attr("start_ts", windows_cfg.start_ts.to_string()),
+            attr("period", windows_cfg.period.to_string()),
+            attr("duration", windows_cfg.duration.to_string()),
+            attr("caller_bounty", windows_cfg.caller_bounty.to_string()),
+        ]);
+    } else {
+        DISTRIBUTION_WINDOWS.remove(deps.storage);
+        attributes.push(attr("distribution_windows", "disabled"));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Adds or removes bridge routes used to swap fee tokens to ASTRO.
+///
+/// * **add** array of routes. Every hop is saved as the bridge of its offer asset.
+///
+/// * **remove** array of fee tokens which bridges are removed.
 ///
 /// ## Executor
 /// Only the owner can execute this.
 fn update_bridges(
     deps: DepsMut,
     info: MessageInfo,
-    add: Option<Vec<(AssetInfo, AssetInfo)>>,
+    add: Option<Vec<Route>>,
     remove: Option<Vec<AssetInfo>>,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
@@ -792,29 +1287,137 @@ fn update_bridges(
 
     // Add new bridges
     let astro = cfg.astro_token.clone();
-    if let Some(add_bridges) = add {
-        for (asset, bridge) in add_bridges {
-            if asset.equal(&bridge) {
-                return Err(ContractError::InvalidBridge(asset, bridge));
+    if let Some(add_routes) = add {
+        for route in add_routes {
+            route.validate(deps.api)?;
+
+            // The Maker swaps through Astroport pairs only
+            let bridges = route
+                .iter_hops()
+                .map(|(asset, bridge, hop)| {
+                    if hop.kind != HopKind::Astro {
+                        return Err(ContractError::InvalidBridge(asset.clone(), bridge.clone()));
+                    }
+
+                    Ok(Route {
+                        assets: vec![asset.clone(), bridge.clone()],
+                        hops: vec![hop.clone()],
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for bridge in &bridges {
+                let asset = bridge.offer_asset().ok_or(RouteError::EmptyRoute {})?;
+                BRIDGES.save(deps.storage, asset.to_string(), bridge)?;
             }
 
             // Check that bridge tokens can be swapped to ASTRO
-            validate_bridge(
-                deps.as_ref(),
-                &cfg.factory_contract,
-                &asset,
-                &bridge,
-                &astro,
-                BRIDGES_INITIAL_DEPTH,
-            )?;
-
-            BRIDGES.save(deps.storage, asset.to_string(), &bridge)?;
+            for bridge in &bridges {
+                validate_bridge(
+                    deps.as_ref(),
+                    &cfg.factory_contract,
+                    bridge,
+                    &astro,
+                    BRIDGES_INITIAL_DEPTH,
+                )?;
+            }
         }
     }
 
     Ok(Response::default().add_attribute("action", "update_bridges"))
 }
 
+/// Sets or removes per-asset limits applied when swapping fee tokens.
+///
+/// * **set** fee tokens along with their new swap limits.
+///
+/// * **remove** fee tokens whose swap limits are removed.
+fn update_swap_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    set: Option<Vec<(AssetInfo, SwapLimitParams)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for asset in remove.unwrap_or_default() {
+        SWAP_LIMITS.remove(deps.storage, asset.to_string());
+    }
+
+    for (asset, params) in set.unwrap_or_default() {
+        asset.check(deps.api)?;
+
+        if let Some(max_spread) = params.max_spread {
+            if max_spread.is_zero() || max_spread > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+                return Err(ContractError::IncorrectMaxSpread {});
+            }
+        }
+
+        let limit = SwapLimit {
+            max_spread: params.max_spread,
+            oracle: addr_opt_validate(deps.api, &params.oracle)?,
+            min_collect_amount: params.min_collect_amount,
+        };
+        SWAP_LIMITS.save(deps.storage, asset.to_string(), &limit)?;
+    }
+
+    Ok(Response::default().add_attribute("action", "update_swap_limits"))
+}
+
+/// Sets or removes routes used to unwrap wrapped fee tokens before distribution.
+///
+/// * **set** wrapped fee tokens along with the instructions to unwrap them.
+///
+/// * **remove** wrapped fee tokens whose unwrap routes are removed.
+fn update_unwrap_routes(
+    deps: DepsMut,
+    info: MessageInfo,
+    set: Option<Vec<(AssetInfo, UnwrapRouteParams)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for asset in remove.unwrap_or_default() {
+        UNWRAP_ROUTES.remove(deps.storage, asset.to_string());
+    }
+
+    for (asset, params) in set.unwrap_or_default() {
+        let route = match params {
+            UnwrapRouteParams::StakingLeave { staking_contract } => {
+                let staking_contract = deps.api.addr_validate(&staking_contract)?;
+
+                // The staking contract must burn the asset for ASTRO
+                let staking_cfg: StakingConfig = deps
+                    .querier
+                    .query_wasm_smart(&staking_contract, &StakingQueryMsg::Config {})
+                    .map_err(|_| ContractError::InvalidUnwrapRoute(asset.clone()))?;
+                if !asset.is_native_token()
+                    || staking_cfg.xastro_denom != asset.to_string()
+                    || staking_cfg.astro_denom != cfg.astro_token.to_string()
+                {
+                    return Err(ContractError::InvalidUnwrapRoute(asset));
+                }
+
+                UnwrapRoute::StakingLeave { staking_contract }
+            }
+        };
+
+        UNWRAP_ROUTES.save(deps.storage, asset.to_string(), &route)?;
+    }
+
+    Ok(Response::default().add_attribute("action", "update_unwrap_routes"))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -825,15 +1428,99 @@ fn update_bridges(
 ///
 /// * **QueryMsg::Bridges {}** Returns the bridges used for swapping fee tokens
 /// using a vector of [`(String, String)`] denoting Asset -> Bridge connections.
+///
+/// * **QueryMsg::IbcDistribution {}** Returns the IBC distribution parameters if the mode is enabled.
+///
+/// * **QueryMsg::PendingIbcTransfers { start_after, limit }** Returns in-flight IBC transfers to the hub
+/// using a vector of [`((String, u64), IbcTransferInfo)`] keyed by channel and packet sequence.
+///
+/// * **QueryMsg::SimulateCollect { assets }** Returns the estimated ASTRO output of a collect call
+/// using a [`SimulateCollectResponse`] object.
+///
+/// * **QueryMsg::FeeShareStats { pair }** Returns the total amount of fees shared by the specified pair.
+///
+/// * **QueryMsg::GovernanceVesting {}** Returns the governance vesting parameters and the accumulated
+/// governance share using a [`GovernanceVestingResponse`] object if vesting is enabled.
+///
+/// * **QueryMsg::NextDistributionWindow {}** Returns the current or the next distribution window
+/// using a [`DistributionWindowResponse`] object if scheduled distributions are enabled.
+///
+/// * **QueryMsg::SwapLimits {}** Returns per-asset swap limits
+/// using a vector of [`(String, SwapLimit)`] keyed by asset.
+///
+/// * **QueryMsg::UnwrapRoutes {}** Returns routes used to unwrap wrapped fee tokens
+/// using a vector of [`(String, UnwrapRoute)`] keyed by wrapped asset.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&query_get_config(deps)?),
         QueryMsg::Balances { assets } => to_json_binary(&query_get_balances(deps, env, assets)?),
         QueryMsg::Bridges {} => to_json_binary(&query_bridges(deps)?),
+        QueryMsg::IbcDistribution {} => to_json_binary(&IBC_DISTRIBUTION.may_load(deps.storage)?),
+        QueryMsg::PendingIbcTransfers { start_after, limit } => {
+            to_json_binary(&query_pending_ibc_transfers(deps, start_after, limit)?)
+        }
+        QueryMsg::FeeShareStats { pair } => to_json_binary(&query_fee_share_stats(deps, pair)?),
+        QueryMsg::SimulateCollect { assets } => {
+            to_json_binary(&query_simulate_collect(deps, env, assets)?)
+        }
+        QueryMsg::GovernanceVesting {} => to_json_binary(&query_governance_vesting(deps)?),
+        QueryMsg::NextDistributionWindow {} => {
+            to_json_binary(&query_next_distribution_window(deps, env)?)
+        }
+        QueryMsg::SwapLimits {} => to_json_binary(
+            &SWAP_LIMITS
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        ),
+        QueryMsg::UnwrapRoutes {} => to_json_binary(
+            &UNWRAP_ROUTES
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?,
+        ),
     }
 }
 
+/// Returns the current distribution window if ASTRO wasn't distributed in it yet, otherwise the next one.
+fn query_next_distribution_window(
+    deps: Deps,
+    env: Env,
+) -> StdResult<Option<DistributionWindowResponse>> {
+    DISTRIBUTION_WINDOWS
+        .may_load(deps.storage)?
+        .map(|config| {
+            let now = env.block.time.seconds();
+            let (_, window_start) = next_distribution_window(
+                &config,
+                now,
+                LAST_DISTRIBUTION_WINDOW.may_load(deps.storage)?,
+            );
+
+            Ok(DistributionWindowResponse {
+                window_start,
+                window_end: window_start + config.duration,
+                is_open: now >= window_start,
+                countdown: window_start.saturating_sub(now),
+                config,
+            })
+        })
+        .transpose()
+}
+
+/// Returns the governance vesting state using a [`GovernanceVestingResponse`] object if vesting is enabled.
+fn query_governance_vesting(deps: Deps) -> StdResult<Option<GovernanceVestingResponse>> {
+    GOVERNANCE_VESTING
+        .may_load(deps.storage)?
+        .map(|config| {
+            Ok(GovernanceVestingResponse {
+                config,
+                pending_amount: GOVERNANCE_VESTING_PENDING.load(deps.storage)?,
+                last_vesting_ts: LAST_GOVERNANCE_VESTING_TS.load(deps.storage)?,
+            })
+        })
+        .transpose()
+}
+
 /// Returns information about the Maker configuration using a [`ConfigResponse`] object.
 fn query_get_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
@@ -872,17 +1559,154 @@ fn query_get_balances(deps: Deps, env: Env, assets: Vec<AssetInfo>) -> StdResult
     Ok(resp)
 }
 
-/// Returns bridge tokens used for swapping fee tokens to ASTRO.
-fn query_bridges(deps: Deps) -> StdResult<Vec<(String, String)>> {
+/// Returns single-hop bridge routes used for swapping fee tokens to ASTRO.
+fn query_bridges(deps: Deps) -> StdResult<Vec<Route>> {
     BRIDGES
         .range(deps.storage, None, None, Order::Ascending)
-        .map(|bridge| {
-            let (bridge, asset) = bridge?;
-            Ok((bridge, asset.to_string()))
-        })
+        .map(|bridge| Ok(bridge?.1))
+        .collect()
+}
+
+/// Returns IBC transfers to the hub which are still in flight.
+fn query_pending_ibc_transfers(
+    deps: Deps,
+    start_after: Option<(String, u64)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<((String, u64), IbcTransferInfo)>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(MAX_PAGINATION_LIMIT) as usize;
+
+    PENDING_IBC_TRANSFERS
+        .range(
+            deps.storage,
+            start_after
+                .as_ref()
+                .map(|(channel, sequence)| Bound::exclusive((channel.as_str(), *sequence))),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
         .collect()
 }
 
+/// Estimates the amount of ASTRO which a [`ExecuteMsg::Collect`] call with the same assets would produce
+/// using a [`SimulateCollectResponse`] object.
+///
+/// * **assets** array with fee tokens being swapped to ASTRO.
+fn query_simulate_collect(
+    deps: Deps,
+    env: Env,
+    assets: Vec<AssetWithLimit>,
+) -> StdResult<SimulateCollectResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let mut uniq = HashSet::new();
+    if !assets.iter().all(|a| uniq.insert(a.info.to_string())) {
+        return Err(StdError::generic_err(
+            ContractError::DuplicatedAsset {}.to_string(),
+        ));
+    }
+
+    let mut response = SimulateCollectResponse {
+        astro_amount: Uint128::zero(),
+        swaps: vec![],
+        failed_assets: vec![],
+    };
+
+    for a in assets.into_iter().filter(|a| a.info.ne(&cfg.astro_token)) {
+        let mut balance = a.info.query_pool(&deps.querier, &env.contract.address)?;
+        if let Some(limit) = a.limit {
+            if limit < balance && limit > Uint128::zero() {
+                balance = limit;
+            }
+        }
+
+        if balance.is_zero() || balance < min_collect_amount(deps.storage, &a.info)? {
+            continue;
+        }
+
+        let offer_asset = a.info.with_balance(balance);
+        match simulate_swap_to_astro(deps, &cfg, offer_asset.clone()) {
+            Ok(astro_amount) => {
+                response.astro_amount += astro_amount;
+                response.swaps.push((offer_asset, astro_amount));
+            }
+            Err(_) => response.failed_assets.push(a.info),
+        }
+    }
+
+    Ok(response)
+}
+
+/// Returns the total amount of fees shared with the Maker by the specified pair.
+fn query_fee_share_stats(deps: Deps, pair: String) -> StdResult<Vec<Asset>> {
+    let pair = deps.api.addr_validate(&pair)?;
+
+    Ok(FEE_SHARE_STATS
+        .may_load(deps.storage, &pair)?
+        .unwrap_or_default())
+}
+
+/// Saves the sent IBC transfer under its channel and packet sequence.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        IBC_TRANSFER_REPLY_ID => {
+            let MsgTransferResponse { sequence } = msg.result.try_into()?;
+            let transfer = IBC_TRANSFER_BUFFER.load(deps.storage)?;
+            IBC_TRANSFER_BUFFER.remove(deps.storage);
+            PENDING_IBC_TRANSFERS.save(deps.storage, (&transfer.channel, sequence), &transfer)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "ibc_transfer"),
+                attr("channel", transfer.channel),
+                attr("sequence", sequence.to_string()),
+                attr("amount", transfer.amount),
+            ]))
+        }
+        _ => Err(StdError::generic_err(format!("Unsupported reply id {}", msg.id)).into()),
+    }
+}
+
+/// Processes IBC hooks callbacks for transfers to the hub.
+/// If the transfer failed or timed out, ICS20 refunds ASTRO to the Maker
+/// and it is distributed again on the next collect.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let (channel, sequence, outcome) = match msg {
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => (
+            channel,
+            sequence,
+            if success { "acknowledged" } else { "failed" },
+        ),
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout { channel, sequence }) => {
+            (channel, sequence, "timed_out")
+        }
+    };
+
+    let transfer = PENDING_IBC_TRANSFERS
+        .may_load(deps.storage, (&channel, sequence))?
+        .ok_or_else(|| ContractError::UnknownIbcTransfer {
+            channel: channel.clone(),
+            sequence,
+        })?;
+    PENDING_IBC_TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    Ok(Response::new().add_attributes([
+        attr("action", "ibc_transfer_callback"),
+        attr("channel", channel),
+        attr("sequence", sequence.to_string()),
+        attr("amount", transfer.amount),
+        attr("outcome", outcome),
+    ]))
+}
+
 /// Manages contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
@@ -896,12 +1720,14 @@ pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response,
                 migrate_from_v120_plus(deps.branch(), msg)?;
                 LAST_COLLECT_TS.save(deps.storage, &env.block.time.seconds())?;
             }
-            "1.4.0" => {}
+            "1.4.0" | "1.5.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     };
 
+    migrate_bridges(deps.branch())?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
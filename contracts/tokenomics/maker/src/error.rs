@@ -1,5 +1,6 @@
 use astroport::asset::AssetInfo;
-use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
+use astroport::route::RouteError;
+use cosmwasm_std::{Decimal, DivideByZeroError, OverflowError, StdError};
 use thiserror::Error;
 
 /// This enum describes maker contract errors
@@ -8,6 +9,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Route(#[from] RouteError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -35,6 +39,13 @@ pub enum ContractError {
     #[error("Incorrect max spread")]
     IncorrectMaxSpread {},
 
+    #[error("Oracle {oracle} has no price for {offer_asset} to {ask_asset}")]
+    OraclePriceNotFound {
+        oracle: String,
+        offer_asset: String,
+        ask_asset: String,
+    },
+
     #[error("Cannot collect. Remove duplicate asset")]
     DuplicatedAsset {},
 
@@ -49,6 +60,47 @@ pub enum ContractError {
 
     #[error("Incorrect cooldown. Min: {min}, Max: {max}")]
     IncorrectCooldown { min: u64, max: u64 },
+
+    #[error("Incorrect IBC timeout. Min: {min}, Max: {max}")]
+    IncorrectIbcTimeout { min: u64, max: u64 },
+
+    #[error("Invalid IBC channel {0}")]
+    InvalidIbcChannel(String),
+
+    #[error("IBC receiver must not be empty")]
+    EmptyIbcReceiver {},
+
+    #[error("IBC distribution requires ASTRO to be a native token")]
+    IbcDistributionNonNativeAstro {},
+
+    #[error("Unknown IBC transfer with sequence {sequence} on channel {channel}")]
+    UnknownIbcTransfer { channel: String, sequence: u64 },
+
+    #[error("Incorrect governance vesting duration. Min: {min}, Max: {max}")]
+    IncorrectGovernanceVestingDuration { min: u64, max: u64 },
+
+    #[error("Governance vesting requires the governance contract to be set")]
+    GovernanceVestingWithoutGovernance {},
+
+    #[error(
+        "Invalid vesting contract. The Maker must be its owner and its vesting token must be ASTRO"
+    )]
+    InvalidGovernanceVestingContract {},
+
+    #[error("Incorrect distribution windows. Period: {min}..={max} seconds, duration must be positive and not exceed the period")]
+    IncorrectDistributionWindows { min: u64, max: u64 },
+
+    #[error("Distribution bounty must not exceed {max}")]
+    DistributionBountyTooHigh { max: Decimal },
+
+    #[error("Scheduled distributions are disabled")]
+    DistributionWindowsDisabled {},
+
+    #[error("Distribution window is closed. Next window opens at {next_window_start}")]
+    DistributionWindowClosed { next_window_start: u64 },
+
+    #[error("Invalid unwrap route for {0}. The asset must be the native xASTRO of a staking contract returning ASTRO")]
+    InvalidUnwrapRoute(AssetInfo),
 }
 
 impl From<OverflowError> for ContractError {
@@ -1,12 +1,13 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, DepsMut, Uint128, Uint64};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, DepsMut, Order, StdResult, Uint128, Uint64};
+use cw_storage_plus::{Item, Map};
 
-use astroport::asset::AssetInfo;
+use astroport::asset::{determine_asset_info, AssetInfo};
 use astroport::maker::{Config, MigrateMsg, SecondReceiverConfig};
+use astroport::route::Route;
 
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{BRIDGES, CONFIG};
 use crate::utils::{update_second_receiver_cfg, validate_cooldown};
 
 pub(crate) fn migrate_from_v120_plus(deps: DepsMut, msg: MigrateMsg) -> Result<(), ContractError> {
@@ -53,3 +54,22 @@ pub(crate) fn migrate_from_v120_plus(deps: DepsMut, msg: MigrateMsg) -> Result<(
 
     Ok(CONFIG.save(deps.storage, &new_config)?)
 }
+
+/// Converts bridge assets stored before v1.6.0 into single-hop bridge routes.
+pub(crate) fn migrate_bridges(deps: DepsMut) -> Result<(), ContractError> {
+    let bridges_v150: Map<String, AssetInfo> = Map::new("bridges");
+    let bridges = bridges_v150
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (asset, bridge) in bridges {
+        let asset = determine_asset_info(&asset, deps.api)?;
+        BRIDGES.save(
+            deps.storage,
+            asset.to_string(),
+            &Route::from_assets(vec![asset, bridge]),
+        )?;
+    }
+
+    Ok(())
+}
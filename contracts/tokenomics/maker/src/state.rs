@@ -1,6 +1,11 @@
-use astroport::asset::AssetInfo;
+use astroport::asset::Asset;
 use astroport::common::OwnershipProposal;
-use astroport::maker::Config;
+use astroport::maker::{
+    Config, DistributionWindowsConfig, GovernanceVestingConfig, IbcDistributionConfig,
+    IbcTransferInfo, SwapLimit, UnwrapRoute,
+};
+use astroport::route::Route;
+use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 
 /// Stores the contract configuration at the given key
@@ -9,7 +14,37 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
-/// Stores bridge tokens used to swap fee tokens to ASTRO
-pub const BRIDGES: Map<String, AssetInfo> = Map::new("bridges");
+/// Stores single-hop bridge routes used to swap fee tokens to ASTRO. key: fee token
+pub const BRIDGES: Map<String, Route> = Map::new("bridges");
+/// Stores per-asset limits applied when swapping fee tokens. key: fee token
+pub const SWAP_LIMITS: Map<String, SwapLimit> = Map::new("swap_limits");
+/// Stores routes used to unwrap wrapped fee tokens before distribution. key: wrapped asset
+pub const UNWRAP_ROUTES: Map<String, UnwrapRoute> = Map::new("unwrap_routes");
 /// Stores the latest timestamp when fees were collected
 pub const LAST_COLLECT_TS: Item<u64> = Item::new("last_collect_ts");
+
+/// Stores IBC distribution parameters. If set, the staking share of ASTRO is transferred to the hub
+pub const IBC_DISTRIBUTION: Item<IbcDistributionConfig> = Item::new("ibc_distribution");
+/// Temporarily stores the IBC transfer until its packet sequence is known in the reply
+pub const IBC_TRANSFER_BUFFER: Item<IbcTransferInfo> = Item::new("ibc_transfer_buffer");
+/// Stores IBC transfers awaiting acknowledgement or timeout.
+/// key: (source channel, IBC packet sequence), value: transfer info.
+/// Packet sequences are tracked per channel thus the channel is part of the key
+pub const PENDING_IBC_TRANSFERS: Map<(&str, u64), IbcTransferInfo> =
+    Map::new("pending_ibc_transfers");
+
+/// Stores the total amount of fees shared with the Maker by PCL pairs with fee share callbacks enabled.
+/// key: pair address, value: accumulated amount per asset
+pub const FEE_SHARE_STATS: Map<&Addr, Vec<Asset>> = Map::new("fee_share_stats");
+
+/// Stores governance vesting parameters. If set, the governance share of ASTRO is vested via the vesting contract
+pub const GOVERNANCE_VESTING: Item<GovernanceVestingConfig> = Item::new("governance_vesting");
+/// Stores the governance share of ASTRO accumulated since the last vesting schedule was registered
+pub const GOVERNANCE_VESTING_PENDING: Item<Uint128> = Item::new("governance_vesting_pending");
+/// Stores the timestamp when the last governance vesting schedule was registered
+pub const LAST_GOVERNANCE_VESTING_TS: Item<u64> = Item::new("last_governance_vesting_ts");
+
+/// Stores distribution windows parameters. If set, ASTRO is distributed only within the windows
+pub const DISTRIBUTION_WINDOWS: Item<DistributionWindowsConfig> = Item::new("distribution_windows");
+/// Stores the index of the last window in which ASTRO was distributed
+pub const LAST_DISTRIBUTION_WINDOW: Item<u64> = Item::new("last_distribution_window");
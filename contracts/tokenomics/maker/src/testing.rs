@@ -1,10 +1,19 @@
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_json, Addr, Decimal, Uint128, Uint64};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_json, Addr, CosmosMsg, Decimal, Reply, SubMsgResponse, SubMsgResult, Uint128,
+    Uint64,
+};
+use osmosis_std::types::ibc::applications::transfer::v1::{MsgTransfer, MsgTransferResponse};
 
-use crate::contract::{execute, instantiate, query};
-use crate::state::CONFIG;
+use crate::contract::{execute, instantiate, query, reply, sudo};
+use crate::error::ContractError;
+use crate::state::{CONFIG, IBC_TRANSFER_BUFFER};
+use crate::utils::IBC_TRANSFER_REPLY_ID;
 use astroport::asset::{native_asset_info, token_asset_info};
-use astroport::maker::{Config, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use astroport::maker::{
+    Config, ConfigResponse, ExecuteMsg, IBCLifecycleComplete, IbcDistributionConfig,
+    IbcTransferInfo, InstantiateMsg, QueryMsg, SudoMsg,
+};
 use std::str::FromStr;
 
 #[test]
@@ -31,6 +40,7 @@ fn proper_initialization() {
         max_spread: None,
         second_receiver_params: None,
         collect_cooldown: None,
+        ownership_timelock: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
     assert_eq!(0, res.messages.len());
@@ -81,6 +91,7 @@ fn update_owner() {
         max_spread: None,
         second_receiver_params: None,
         collect_cooldown: None,
+        ownership_timelock: None,
     };
 
     let env = mock_env();
@@ -145,3 +156,265 @@ fn update_owner() {
         from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!(new_owner, config.owner);
 }
+
+#[test]
+fn ibc_distribution() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let owner = mock_info("owner", &[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        factory_contract: "factory".to_string(),
+        staking_contract: None,
+        governance_contract: Some("governance".to_string()),
+        governance_percent: Some(Uint64::new(100)),
+        astro_token: native_asset_info("astro".to_string()),
+        default_bridge: None,
+        max_spread: None,
+        second_receiver_params: None,
+        collect_cooldown: None,
+        ownership_timelock: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::EnableRewards { blocks: 1 },
+    )
+    .unwrap();
+
+    let ibc_cfg = IbcDistributionConfig {
+        channel: "channel-1".to_string(),
+        receiver: "hub_staking".to_string(),
+        timeout: 600,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        ExecuteMsg::UpdateIbcDistribution {
+            config: Some(ibc_cfg.clone()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::UpdateIbcDistribution {
+            config: Some(IbcDistributionConfig {
+                channel: "connection-1".to_string(),
+                ..ibc_cfg.clone()
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidIbcChannel("connection-1".to_string())
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::UpdateIbcDistribution {
+            config: Some(IbcDistributionConfig {
+                timeout: 10,
+                ..ibc_cfg.clone()
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::IncorrectIbcTimeout {
+            min: 120,
+            max: 86400
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::UpdateIbcDistribution {
+            config: Some(ibc_cfg.clone()),
+        },
+    )
+    .unwrap();
+    let resp: Option<IbcDistributionConfig> =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::IbcDistribution {}).unwrap())
+            .unwrap();
+    assert_eq!(resp, Some(ibc_cfg.clone()));
+
+    // Staking share has a destination now even though staking contract is not set
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::UpdateConfig {
+            factory_contract: None,
+            staking_contract: None,
+            governance_contract: None,
+            governance_percent: Some(Uint64::new(50)),
+            basic_asset: None,
+            max_spread: None,
+            second_receiver_params: None,
+            collect_cooldown: None,
+            astro_token: None,
+        },
+    )
+    .unwrap();
+
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(1000, "astro"));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("permissionless", &[]),
+        ExecuteMsg::Collect { assets: vec![] },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[1].id, IBC_TRANSFER_REPLY_ID);
+    match &res.messages[1].msg {
+        CosmosMsg::Stargate { type_url, value } => {
+            assert_eq!(type_url, MsgTransfer::TYPE_URL);
+            let transfer = MsgTransfer::try_from(value.clone()).unwrap();
+            assert_eq!(transfer.source_channel, "channel-1");
+            assert_eq!(transfer.receiver, "hub_staking");
+            assert_eq!(transfer.token.unwrap().amount, "500");
+            assert_eq!(
+                transfer.memo,
+                format!(r#"{{"ibc_callback":"{MOCK_CONTRACT_ADDR}"}}"#)
+            );
+        }
+        msg => panic!("Unexpected message {msg:?}"),
+    }
+
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: IBC_TRANSFER_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(MsgTransferResponse { sequence: 7 }.into()),
+            }),
+        },
+    )
+    .unwrap();
+
+    let pending: Vec<((String, u64), IbcTransferInfo)> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingIbcTransfers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pending,
+        vec![(
+            ("channel-1".to_string(), 7),
+            IbcTransferInfo {
+                channel: "channel-1".to_string(),
+                receiver: "hub_staking".to_string(),
+                amount: Uint128::new(500),
+                sent_at: env.block.time.seconds(),
+            }
+        )]
+    );
+
+    // Packet sequences are tracked per channel thus the same sequence on another channel is a different transfer
+    let other_transfer = IbcTransferInfo {
+        channel: "channel-2".to_string(),
+        receiver: "hub_staking".to_string(),
+        amount: Uint128::new(300),
+        sent_at: env.block.time.seconds(),
+    };
+    IBC_TRANSFER_BUFFER
+        .save(deps.as_mut().storage, &other_transfer)
+        .unwrap();
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: IBC_TRANSFER_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(MsgTransferResponse { sequence: 7 }.into()),
+            }),
+        },
+    )
+    .unwrap();
+
+    let err = sudo(
+        deps.as_mut(),
+        env.clone(),
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout {
+            channel: "channel-3".to_string(),
+            sequence: 7,
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownIbcTransfer {
+            channel: "channel-3".to_string(),
+            sequence: 7
+        }
+    );
+
+    // Timed out ASTRO is refunded to the Maker and distributed on the next collect
+    let res = sudo(
+        deps.as_mut(),
+        env.clone(),
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout {
+            channel: "channel-1".to_string(),
+            sequence: 7,
+        }),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "outcome" && attr.value == "timed_out"));
+
+    let pending: Vec<((String, u64), IbcTransferInfo)> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PendingIbcTransfers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pending,
+        vec![(("channel-2".to_string(), 7), other_transfer)]
+    );
+
+    // Staking share must have a destination after disabling IBC distribution
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner.clone(),
+        ExecuteMsg::UpdateIbcDistribution { config: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::GovernancePercentMustBe100 {});
+}
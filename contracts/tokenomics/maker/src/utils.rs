@@ -1,19 +1,26 @@
 use cosmwasm_std::{
     coins, to_json_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Deps, Empty, Env,
-    QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    QuerierWrapper, StdError, StdResult, Storage, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+use osmosis_std::types::ibc::applications::transfer::v1::MsgTransfer;
 
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::maker::{
-    Config, ExecuteMsg, SecondReceiverConfig, SecondReceiverParams, COOLDOWN_LIMITS,
+    Config, DistributionWindowsConfig, ExecuteMsg, GovernanceVestingConfig, IbcDistributionConfig,
+    SecondReceiverConfig, SecondReceiverParams, COOLDOWN_LIMITS, DISTRIBUTION_PERIOD_LIMITS,
+    GOVERNANCE_VESTING_DURATION_LIMITS, IBC_TIMEOUT_LIMITS, MAX_DISTRIBUTION_BOUNTY,
     MAX_SECOND_RECEIVER_CUT,
 };
-use astroport::pair::Cw20HookMsg;
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::pair::{Cw20HookMsg, QueryMsg as PairQueryMsg};
 use astroport::querier::query_pair_info;
+use astroport::route::{Route, RouteError, RouteHop};
+use astroport::vesting::{self, VestingAccount, VestingSchedule, VestingSchedulePoint};
 
 use crate::error::ContractError;
-use crate::state::BRIDGES;
+use crate::state::{BRIDGES, SWAP_LIMITS};
 
 /// The default bridge depth for a fee token
 pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
@@ -21,30 +28,15 @@ pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
 pub const BRIDGES_MAX_DEPTH: u64 = 2;
 /// Swap execution depth limit
 pub const BRIDGES_EXECUTION_MAX_DEPTH: u64 = 5;
-
-/// The function checks from<>to pool exists and creates swap message.
-///
-/// * **from** asset we want to swap.
-///
-/// * **to** asset we want to swap to.
-///
-/// * **amount_in** amount of tokens to swap.
-pub fn try_build_swap_msg(
-    querier: &QuerierWrapper,
-    cfg: &Config,
-    from: &AssetInfo,
-    to: &AssetInfo,
-    amount_in: Uint128,
-) -> Result<SubMsg, ContractError> {
-    let pool = get_pool(querier, &cfg.factory_contract, from, to)?;
-    let msg = build_swap_msg(cfg.max_spread, &pool, from, Some(to), amount_in)?;
-    Ok(msg)
-}
+/// Reply ID used to save the sequence of the IBC transfer to the hub
+pub const IBC_TRANSFER_REPLY_ID: u64 = 1;
 
 /// This function creates swap message.
 ///
 /// * **max_spread** max allowed spread.
 ///
+/// * **belief_price** expected price used by the pool to check the spread.
+///
 /// * **pool** pool's information.
 ///
 /// * **from**  asset we want to swap.
@@ -54,6 +46,7 @@ pub fn try_build_swap_msg(
 /// * **amount_in** amount of tokens to swap.
 pub fn build_swap_msg(
     max_spread: Decimal,
+    belief_price: Option<Decimal>,
     pool: &PairInfo,
     from: &AssetInfo,
     to: Option<&AssetInfo>,
@@ -70,9 +63,11 @@ pub fn build_swap_msg(
             msg: to_json_binary(&astroport::pair::ExecuteMsg::Swap {
                 offer_asset: offer_asset.clone(),
                 ask_asset_info: to.cloned(),
-                belief_price: None,
+                belief_price,
                 max_spread: Some(max_spread),
                 to: None,
+                deadline: None,
+                to_commitment: None,
             })?,
             funds: vec![offer_asset.as_coin()?],
         }))
@@ -84,9 +79,11 @@ pub fn build_swap_msg(
                 amount: amount_in,
                 msg: to_json_binary(&Cw20HookMsg::Swap {
                     ask_asset_info: to.cloned(),
-                    belief_price: None,
+                    belief_price,
                     max_spread: Some(max_spread),
                     to: None,
+                    deadline: None,
+                    to_commitment: None,
                 })?,
             })?,
             funds: vec![],
@@ -131,25 +128,29 @@ pub fn build_distribute_msg(
 ///
 /// * **factory_contract** address of the factory contract.
 ///
-/// * **from_token** asset we want to swap.
-///
-/// * **bridge_token** asset we want to swap through.
+/// * **bridge** single-hop route from the asset we want to swap to the asset we want to swap through.
 ///
 /// * **astro_token** represents $ASTRO.
 ///
 /// * **depth** current recursion depth of the validation.
-///
-/// * **amount** is an amount of from_token.
 pub fn validate_bridge(
     deps: Deps,
     factory_contract: &Addr,
-    from_token: &AssetInfo,
-    bridge_token: &AssetInfo,
+    bridge: &Route,
     astro_token: &AssetInfo,
     depth: u64,
 ) -> Result<PairInfo, ContractError> {
+    let (from_token, bridge_token, hop) =
+        bridge.iter_hops().next().ok_or(RouteError::EmptyRoute {})?;
+
     // Check if the bridge pool exists
-    let bridge_pool = get_pool(&deps.querier, factory_contract, from_token, bridge_token)?;
+    let bridge_pool = get_hop_pool(
+        &deps.querier,
+        factory_contract,
+        from_token,
+        bridge_token,
+        hop,
+    )?;
 
     // If bridge token is astro itself we don't need to check further
     if bridge_token != astro_token {
@@ -161,18 +162,11 @@ pub fn validate_bridge(
             }
 
             // Check if next level of bridge exists
-            let next_bridge_token = BRIDGES
+            let next_bridge = BRIDGES
                 .load(deps.storage, bridge_token.to_string())
                 .map_err(|_| ContractError::InvalidBridgeDestination(from_token.to_string()))?;
 
-            validate_bridge(
-                deps,
-                factory_contract,
-                bridge_token,
-                &next_bridge_token,
-                astro_token,
-                depth + 1,
-            )?;
+            validate_bridge(deps, factory_contract, &next_bridge, astro_token, depth + 1)?;
         }
     }
 
@@ -201,6 +195,100 @@ pub fn get_pool(
     .map_err(|_| ContractError::InvalidBridgeNoPool(from.to_string(), to.to_string()))
 }
 
+/// Returns the pool of a bridge hop. If the hop pins a pair, the pair must be created by the factory
+/// and hold both hop assets. Otherwise the pool is resolved through the factory.
+///
+/// * **from** source asset.
+///
+/// * **to** destination asset.
+pub fn get_hop_pool(
+    querier: &QuerierWrapper,
+    factory_contract: &Addr,
+    from: &AssetInfo,
+    to: &AssetInfo,
+    hop: &RouteHop,
+) -> Result<PairInfo, ContractError> {
+    let Some(pair) = &hop.pair else {
+        return get_pool(querier, factory_contract, from, to);
+    };
+
+    let pair_info = querier
+        .query_wasm_smart::<PairInfo>(pair, &PairQueryMsg::Pair {})
+        .ok()
+        .filter(|pair_info| {
+            pair_info.asset_infos.contains(from) && pair_info.asset_infos.contains(to)
+        })
+        .ok_or_else(|| ContractError::InvalidBridgeNoPool(from.to_string(), to.to_string()))?;
+    if querier.query_wasm_contract_info(pair)?.creator != factory_contract.as_str() {
+        return Err(ContractError::InvalidBridgeNoPool(
+            from.to_string(),
+            to.to_string(),
+        ));
+    }
+
+    Ok(pair_info)
+}
+
+/// Returns the minimum balance of a fee token required to swap it in a collect call.
+/// Balances below the threshold are dust which would be swapped at a poor rate.
+pub fn min_collect_amount(storage: &dyn Storage, asset: &AssetInfo) -> StdResult<Uint128> {
+    Ok(SWAP_LIMITS
+        .may_load(storage, asset.to_string())?
+        .and_then(|limit| limit.min_collect_amount)
+        .unwrap_or_default())
+}
+
+/// Returns the max spread and the belief price used to swap a fee token.
+/// If the token has an oracle configured, the belief price is derived from the oracle TWAP
+/// so the swap can't return less than the TWAP converted amount reduced by the max spread.
+///
+/// * **from** fee token to swap.
+///
+/// * **to** asset received from the swap.
+///
+/// * **amount_in** amount of tokens to swap.
+pub fn get_swap_limits(
+    deps: Deps,
+    cfg: &Config,
+    from: &AssetInfo,
+    to: &AssetInfo,
+    amount_in: Uint128,
+) -> Result<(Decimal, Option<Decimal>), ContractError> {
+    let Some(limit) = SWAP_LIMITS.may_load(deps.storage, from.to_string())? else {
+        return Ok((cfg.max_spread, None));
+    };
+
+    let belief_price = limit
+        .oracle
+        .map(|oracle| {
+            let prices: Vec<(AssetInfo, Uint256)> = deps.querier.query_wasm_smart(
+                &oracle,
+                &OracleQueryMsg::Consult {
+                    token: from.clone(),
+                    amount: amount_in,
+                },
+            )?;
+
+            let expected_return = prices
+                .into_iter()
+                .find(|(asset, _)| asset == to)
+                .map(|(_, amount)| Uint128::try_from(amount))
+                .transpose()
+                .map_err(StdError::from)?
+                .filter(|amount| !amount.is_zero())
+                .ok_or_else(|| ContractError::OraclePriceNotFound {
+                    oracle: oracle.to_string(),
+                    offer_asset: from.to_string(),
+                    ask_asset: to.to_string(),
+                })?;
+
+            Ok::<_, ContractError>(Decimal::from_ratio(amount_in, expected_return))
+        })
+        .transpose()?;
+
+    Ok((limit.max_spread.unwrap_or(cfg.max_spread), belief_price))
+}
+
 /// For native tokens of type [`AssetInfo`] uses method [`astro_satellite_package::ExecuteMsg::TransferAstro`]
 /// to send a token amount to a recipient.
 ///
@@ -272,3 +360,186 @@ pub fn validate_cooldown(maybe_cooldown: Option<u64>) -> Result<(), ContractErro
 
     Ok(())
 }
+
+/// Validates distribution windows parameters
+pub fn validate_distribution_windows(
+    windows_cfg: &DistributionWindowsConfig,
+) -> Result<(), ContractError> {
+    if !DISTRIBUTION_PERIOD_LIMITS.contains(&windows_cfg.period)
+        || windows_cfg.duration == 0
+        || windows_cfg.duration > windows_cfg.period
+    {
+        return Err(ContractError::IncorrectDistributionWindows {
+            min: *DISTRIBUTION_PERIOD_LIMITS.start(),
+            max: *DISTRIBUTION_PERIOD_LIMITS.end(),
+        });
+    }
+
+    if windows_cfg.caller_bounty > MAX_DISTRIBUTION_BOUNTY {
+        return Err(ContractError::DistributionBountyTooHigh {
+            max: MAX_DISTRIBUTION_BOUNTY,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the index and the start of the first distribution window which hasn't ended yet
+/// and in which ASTRO wasn't distributed.
+///
+/// * **now** current block time.
+///
+/// * **last_window** index of the last window used for distribution.
+pub fn next_distribution_window(
+    windows_cfg: &DistributionWindowsConfig,
+    now: u64,
+    last_window: Option<u64>,
+) -> (u64, u64) {
+    let mut index = now.saturating_sub(windows_cfg.start_ts) / windows_cfg.period;
+    if now >= windows_cfg.start_ts + index * windows_cfg.period + windows_cfg.duration {
+        index += 1;
+    }
+    if let Some(last_window) = last_window {
+        index = index.max(last_window + 1);
+    }
+
+    (index, windows_cfg.start_ts + index * windows_cfg.period)
+}
+
+/// Validates IBC distribution parameters
+pub fn validate_ibc_distribution(
+    ibc_cfg: &IbcDistributionConfig,
+    astro_token: &AssetInfo,
+) -> Result<(), ContractError> {
+    if !astro_token.is_native_token() {
+        return Err(ContractError::IbcDistributionNonNativeAstro {});
+    }
+
+    let is_valid_channel = ibc_cfg
+        .channel
+        .strip_prefix("channel-")
+        .map(|id| id.parse::<u64>().is_ok())
+        .unwrap_or(false);
+    if !is_valid_channel {
+        return Err(ContractError::InvalidIbcChannel(ibc_cfg.channel.clone()));
+    }
+
+    if ibc_cfg.receiver.trim().is_empty() {
+        return Err(ContractError::EmptyIbcReceiver {});
+    }
+
+    if !IBC_TIMEOUT_LIMITS.contains(&ibc_cfg.timeout) {
+        return Err(ContractError::IncorrectIbcTimeout {
+            min: *IBC_TIMEOUT_LIMITS.start(),
+            max: *IBC_TIMEOUT_LIMITS.end(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Builds an ICS20 transfer of ASTRO to the hub.
+/// The memo requests IBC hooks to call back the Maker once the packet is acknowledged or timed out.
+pub fn build_ibc_transfer_msg(
+    env: &Env,
+    ibc_cfg: &IbcDistributionConfig,
+    asset: &Asset,
+) -> Result<CosmosMsg, ContractError> {
+    let denom = match &asset.info {
+        AssetInfo::NativeToken { denom } => denom.clone(),
+        AssetInfo::Token { .. } => return Err(ContractError::IbcDistributionNonNativeAstro {}),
+    };
+
+    Ok(MsgTransfer {
+        source_port: "transfer".to_string(),
+        source_channel: ibc_cfg.channel.clone(),
+        token: Some(ProtoCoin {
+            denom,
+            amount: asset.amount.to_string(),
+        }),
+        sender: env.contract.address.to_string(),
+        receiver: ibc_cfg.receiver.clone(),
+        timeout_height: None,
+        timeout_timestamp: env.block.time.plus_seconds(ibc_cfg.timeout).nanos(),
+        memo: format!(r#"{{"ibc_callback":"{}"}}"#, env.contract.address),
+    }
+    .into())
+}
+
+/// Validates governance vesting parameters.
+/// The vesting contract must be owned by the Maker and vest ASTRO.
+pub fn validate_governance_vesting(
+    deps: Deps,
+    maker: &Addr,
+    vesting_cfg: &GovernanceVestingConfig,
+    astro_token: &AssetInfo,
+) -> Result<(), ContractError> {
+    if !GOVERNANCE_VESTING_DURATION_LIMITS.contains(&vesting_cfg.duration) {
+        return Err(ContractError::IncorrectGovernanceVestingDuration {
+            min: *GOVERNANCE_VESTING_DURATION_LIMITS.start(),
+            max: *GOVERNANCE_VESTING_DURATION_LIMITS.end(),
+        });
+    }
+
+    let vesting_contract = deps.api.addr_validate(&vesting_cfg.vesting_contract)?;
+    let vesting_config: vesting::ConfigResponse = deps
+        .querier
+        .query_wasm_smart(&vesting_contract, &vesting::QueryMsg::Config {})
+        .map_err(|_| ContractError::InvalidGovernanceVestingContract {})?;
+    if vesting_config.owner != *maker || !vesting_config.vesting_token.equal(astro_token) {
+        return Err(ContractError::InvalidGovernanceVestingContract {});
+    }
+
+    Ok(())
+}
+
+/// Builds a message which registers a linear vesting schedule of ASTRO for the governance contract.
+///
+/// * **asset** ASTRO to vest.
+///
+/// * **vesting_contract** vesting contract owned by the Maker.
+///
+/// * **governance_contract** beneficiary of the vesting schedule.
+///
+/// * **start_time** vesting start timestamp. Tokens are fully vested at start_time + duration.
+pub fn build_governance_vesting_msg(
+    asset: &Asset,
+    vesting_contract: impl Into<String>,
+    governance_contract: &Addr,
+    start_time: u64,
+    duration: u64,
+) -> StdResult<CosmosMsg> {
+    let vesting_accounts = vec![VestingAccount {
+        address: governance_contract.to_string(),
+        schedules: vec![VestingSchedule {
+            start_point: VestingSchedulePoint {
+                time: start_time,
+                amount: Uint128::zero(),
+            },
+            end_point: Some(VestingSchedulePoint {
+                time: start_time + duration,
+                amount: asset.amount,
+            }),
+        }],
+        managed: None,
+    }];
+
+    match &asset.info {
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: vesting_contract.into(),
+                amount: asset.amount,
+                msg: to_json_binary(&vesting::Cw20HookMsg::RegisterVestingAccounts {
+                    vesting_accounts,
+                })?,
+            })?,
+            funds: vec![],
+        })),
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(wasm_execute(
+            vesting_contract,
+            &vesting::ExecuteMsg::RegisterVestingAccounts { vesting_accounts },
+            coins(asset.amount.u128(), denom),
+        )?)),
+    }
+}
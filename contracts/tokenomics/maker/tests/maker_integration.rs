@@ -5,19 +5,30 @@ use std::str::FromStr;
 use astroport_test::cw_multi_test::{next_block, AppBuilder, Contract, ContractWrapper, Executor};
 use astroport_test::modules::stargate::{MockStargate, StargateApp as TestApp};
 use cosmwasm_std::{
-    attr, coin, to_json_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
-    MessageInfo, QueryRequest, Response, StdResult, Uint128, Uint64, WasmQuery,
+    attr, coin, to_json_binary, wasm_execute, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty,
+    Env, MessageInfo, QueryRequest, Response, StdResult, Timestamp, Uint128, Uint256, Uint64,
+    WasmQuery,
 };
 use cw20::{BalanceResponse, Cw20QueryMsg, MinterResponse};
+use cw_storage_plus::Item;
 
 use astroport::asset::{
     native_asset, native_asset_info, token_asset, token_asset_info, Asset, AssetInfo, PairInfo,
 };
 use astroport::factory::{PairConfig, PairType, UpdateAddr};
 use astroport::maker::{
-    AssetWithLimit, BalancesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SecondReceiverConfig, SecondReceiverParams, COOLDOWN_LIMITS,
+    AssetWithLimit, BalancesResponse, ConfigResponse, DistributionWindowResponse,
+    DistributionWindowsConfig, ExecuteMsg, GovernanceVestingConfig, GovernanceVestingResponse,
+    InstantiateMsg, QueryMsg, SecondReceiverConfig, SecondReceiverParams, SimulateCollectResponse,
+    SwapLimit, SwapLimitParams, UnwrapRoute, UnwrapRouteParams, COOLDOWN_LIMITS,
+    DISTRIBUTION_PERIOD_LIMITS, GOVERNANCE_VESTING_DURATION_LIMITS, MAX_DISTRIBUTION_BOUNTY,
 };
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::route::{HopKind, Route, RouteHop};
+use astroport::staking::{
+    Config as StakingConfig, ExecuteMsg as StakingExecuteMsg, QueryMsg as StakingQueryMsg,
+};
+use astroport::vesting::{VestingAccountResponse, VestingSchedule, VestingSchedulePoint};
 use astroport_maker::error::ContractError;
 use cw20_base::msg::InstantiateMsg as TokenInstantiateMsg;
 
@@ -201,6 +212,7 @@ fn instantiate_contracts(
             is_disabled: false,
             is_generator_disabled: false,
             permissioned: false,
+            min_trade_notional: None,
         }],
         token_code_id: 1u64,
         fee_address: None,
@@ -208,6 +220,8 @@ fn instantiate_contracts(
         generator_address: Some(String::from("generator")),
         whitelist_code_id: 234u64,
         coin_registry_address: coin_registry_address.to_string(),
+        tracker_config: None,
+        ownership_timelock: None,
     };
 
     let factory_instance = router
@@ -253,6 +267,7 @@ fn instantiate_contracts(
         max_spread,
         second_receiver_params,
         collect_cooldown,
+        ownership_timelock: None,
     };
     let maker_instance = router
         .instantiate_contract(
@@ -463,6 +478,9 @@ fn create_pair(
                 slippage_tolerance: None,
                 auto_stake: None,
                 receiver: None,
+                min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
             },
             &funds,
         )
@@ -712,7 +730,12 @@ fn test_maker_collect(
             owner.clone(),
             maker_instance.clone(),
             &ExecuteMsg::UpdateBridges {
-                add: Some(bridges),
+                add: Some(
+                    bridges
+                        .into_iter()
+                        .map(|(asset, bridge)| Route::from_assets(vec![asset, bridge]))
+                        .collect(),
+                ),
                 remove: None,
             },
             &[],
@@ -1065,22 +1088,22 @@ fn collect_maxdepth_test() {
             maker_instance.clone(),
             &ExecuteMsg::UpdateBridges {
                 add: Some(vec![
-                    (
+                    Route::from_assets(vec![
                         token_asset_info(test_token_instance.clone()),
                         token_asset_info(bridge2_token_instance.clone()),
-                    ),
-                    (
+                    ]),
+                    Route::from_assets(vec![
                         token_asset_info(usdc_token_instance.clone()),
                         token_asset_info(test_token_instance.clone()),
-                    ),
-                    (
+                    ]),
+                    Route::from_assets(vec![
                         native_asset_info(uluna_asset.clone()),
                         token_asset_info(usdc_token_instance.clone()),
-                    ),
-                    (
+                    ]),
+                    Route::from_assets(vec![
                         native_asset_info(uusd_asset.clone()),
                         native_asset_info(uluna_asset.clone()),
-                    ),
+                    ]),
                 ]),
                 remove: None,
             },
@@ -1261,14 +1284,14 @@ fn update_bridges() {
 
     let msg = ExecuteMsg::UpdateBridges {
         add: Some(vec![
-            (
+            Route::from_assets(vec![
                 native_asset_info(String::from("uluna")),
                 native_asset_info(String::from("uusd")),
-            ),
-            (
+            ]),
+            Route::from_assets(vec![
                 native_asset_info(String::from("ukrt")),
                 native_asset_info(String::from("uusd")),
-            ),
+            ]),
         ]),
         remove: None,
     };
@@ -1336,7 +1359,7 @@ fn update_bridges() {
         .execute_contract(owner.clone(), maker_instance.clone(), &msg, &[])
         .unwrap();
 
-    let resp: Vec<(String, String)> = router
+    let resp: Vec<Route> = router
         .wrap()
         .query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: maker_instance.to_string(),
@@ -1347,8 +1370,14 @@ fn update_bridges() {
     assert_eq!(
         resp,
         vec![
-            (String::from("ukrt"), String::from("uusd")),
-            (String::from("uluna"), String::from("uusd")),
+            Route::from_assets(vec![
+                native_asset_info(String::from("ukrt")),
+                native_asset_info(String::from("uusd")),
+            ]),
+            Route::from_assets(vec![
+                native_asset_info(String::from("uluna")),
+                native_asset_info(String::from("uusd")),
+            ]),
         ]
     );
 
@@ -1362,7 +1391,7 @@ fn update_bridges() {
         .execute_contract(owner.clone(), maker_instance.clone(), &msg, &[])
         .unwrap();
 
-    let resp: Vec<(String, String)> = router
+    let resp: Vec<Route> = router
         .wrap()
         .query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: maker_instance.to_string(),
@@ -1370,7 +1399,125 @@ fn update_bridges() {
         }))
         .unwrap();
 
-    assert_eq!(resp, vec![(String::from("uluna"), String::from("uusd"))]);
+    assert_eq!(
+        resp,
+        vec![Route::from_assets(vec![
+            native_asset_info(String::from("uluna")),
+            native_asset_info(String::from("uusd")),
+        ])]
+    );
+
+    // Multi-hop routes are split into bridges, a hop can pin the pair to swap through
+    let krt_luna_pair = create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            native_asset(String::from("ukrt"), Uint128::from(100_000_u128)),
+            native_asset(String::from("uluna"), Uint128::from(100_000_u128)),
+        ],
+        None,
+    )
+    .contract_addr;
+    let route_assets = vec![
+        native_asset_info(String::from("ukrt")),
+        native_asset_info(String::from("uluna")),
+        native_asset_info(String::from("uusd")),
+    ];
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![Route {
+                    assets: route_assets.clone(),
+                    hops: vec![
+                        RouteHop::astro(),
+                        RouteHop {
+                            kind: HopKind::Astro,
+                            pair: Some(krt_luna_pair.to_string()),
+                        },
+                    ],
+                }]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBridgeNoPool("uluna".to_string(), "uusd".to_string())
+    );
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![Route {
+                    assets: route_assets.clone(),
+                    hops: vec![
+                        RouteHop::astro(),
+                        RouteHop {
+                            kind: HopKind::Native,
+                            pair: None,
+                        },
+                    ],
+                }]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidBridge(
+            native_asset_info(String::from("uluna")),
+            native_asset_info(String::from("uusd"))
+        )
+    );
+
+    let pinned_route = Route {
+        assets: route_assets.clone(),
+        hops: vec![
+            RouteHop {
+                kind: HopKind::Astro,
+                pair: Some(krt_luna_pair.to_string()),
+            },
+            RouteHop::astro(),
+        ],
+    };
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![pinned_route]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let resp: Vec<Route> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Bridges {})
+        .unwrap();
+    assert_eq!(
+        resp,
+        vec![
+            Route {
+                assets: route_assets[..2].to_vec(),
+                hops: vec![RouteHop {
+                    kind: HopKind::Astro,
+                    pair: Some(krt_luna_pair.to_string()),
+                }],
+            },
+            Route::from_assets(route_assets[1..].to_vec()),
+        ]
+    );
 }
 
 #[test]
@@ -1501,14 +1648,14 @@ fn collect_with_asset_limit() {
             maker_instance.clone(),
             &ExecuteMsg::UpdateBridges {
                 add: Some(vec![
-                    (
+                    Route::from_assets(vec![
                         token_asset_info(test_token_instance.clone()),
                         token_asset_info(bridge2_token_instance.clone()),
-                    ),
-                    (
+                    ]),
+                    Route::from_assets(vec![
                         token_asset_info(usdc_token_instance.clone()),
                         token_asset_info(test_token_instance.clone()),
-                    ),
+                    ]),
                 ]),
                 remove: None,
             },
@@ -1805,14 +1952,14 @@ fn collect_with_second_receiver() {
             maker_instance.clone(),
             &ExecuteMsg::UpdateBridges {
                 add: Some(vec![
-                    (
+                    Route::from_assets(vec![
                         token_asset_info(test_token_instance.clone()),
                         token_asset_info(bridge2_token_instance.clone()),
-                    ),
-                    (
+                    ]),
+                    Route::from_assets(vec![
                         token_asset_info(usdc_token_instance.clone()),
                         token_asset_info(test_token_instance.clone()),
-                    ),
+                    ]),
                 ]),
                 remove: None,
             },
@@ -1982,772 +2129,2184 @@ fn collect_with_second_receiver() {
     );
 }
 
+fn instantiate_vesting(router: &mut TestApp, owner: &Addr, astro_token: &Addr) -> Addr {
+    let vesting_contract = Box::new(ContractWrapper::new_with_empty(
+        astroport_vesting::contract::execute,
+        astroport_vesting::contract::instantiate,
+        astroport_vesting::contract::query,
+    ));
+    let vesting_code_id = router.store_code(vesting_contract);
+
+    router
+        .instantiate_contract(
+            vesting_code_id,
+            Addr::unchecked(OWNER),
+            &astroport::vesting::InstantiateMsg {
+                owner: owner.to_string(),
+                vesting_token: token_asset_info(astro_token.clone()),
+            },
+            &[],
+            "Vesting",
+            None,
+        )
+        .unwrap()
+}
+
 #[test]
-fn test_collect_cooldown() {
-    let asset0 = "asset0";
-    let asset1 = "asset1";
-    let owner = Addr::unchecked("owner");
-    let mut router = mock_app(
-        owner.clone(),
-        vec![
-            coin(100_000_000_000u128, asset0),
-            coin(100_000_000_000u128, asset1),
-        ],
-    );
+fn collect_with_governance_vesting() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(owner.clone(), vec![]);
+    let staking = Addr::unchecked("staking");
+    let governance_percent = Uint64::new(10);
 
-    let (_, factory_instance, maker_instance, _) = instantiate_contracts(
+    let (astro_token_instance, _, maker_instance, governance_instance) = instantiate_contracts(
         &mut router,
         owner.clone(),
-        Addr::unchecked("staking"),
-        10u64.into(),
-        Some(Decimal::from_str("0.5").unwrap()),
+        staking.clone(),
+        governance_percent,
+        None,
+        None,
         None,
         None,
-        Some(300),
     );
 
-    // Moving block time to be able to collect
-    router.update_block(|block| block.time = block.time.plus_seconds(300));
-
-    let asset_infos = [AssetInfo::native(asset0), AssetInfo::native(asset1)];
-
-    // Create pair in factory
     router
         .execute_contract(
             owner.clone(),
-            factory_instance.clone(),
-            &astroport::factory::ExecuteMsg::CreatePair {
-                pair_type: PairType::Xyk {},
-                asset_infos: asset_infos.to_vec(),
-                init_params: None,
-            },
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
             &[],
         )
         .unwrap();
 
-    // Set assets to swap
-    let assets = vec![AssetWithLimit {
-        info: AssetInfo::native(asset0),
-        limit: None,
-    }];
+    let vesting_instance = instantiate_vesting(&mut router, &maker_instance, &astro_token_instance);
+    let duration = *GOVERNANCE_VESTING_DURATION_LIMITS.start();
 
-    // First collect works
-    router
+    let err = router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+            &ExecuteMsg::UpdateGovernanceVesting {
+                config: Some(GovernanceVestingConfig {
+                    vesting_contract: vesting_instance.to_string(),
+                    duration: 100,
+                }),
             },
             &[],
         )
-        .unwrap();
-    let next_collect_ts = router.block_info().time.plus_seconds(300).seconds();
-
-    // Cooldown is 300 sec. We can't collect again
-    router.update_block(|block| block.time = block.time.plus_seconds(100));
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::IncorrectGovernanceVestingDuration {
+            min: *GOVERNANCE_VESTING_DURATION_LIMITS.start(),
+            max: *GOVERNANCE_VESTING_DURATION_LIMITS.end(),
+        }
+    );
 
+    // The Maker must own the vesting contract
+    let foreign_vesting = instantiate_vesting(&mut router, &owner, &astro_token_instance);
     let err = router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+            &ExecuteMsg::UpdateGovernanceVesting {
+                config: Some(GovernanceVestingConfig {
+                    vesting_contract: foreign_vesting.to_string(),
+                    duration,
+                }),
             },
             &[],
         )
         .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidGovernanceVestingContract {}
+    );
 
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateGovernanceVesting { config: None },
+            &[],
+        )
+        .unwrap_err();
     assert_eq!(
         err.downcast::<ContractError>().unwrap(),
-        ContractError::Cooldown { next_collect_ts }
+        ContractError::Unauthorized {}
     );
 
-    // In 200 seconds cooldown should be expired
-    router.update_block(|block| block.time = block.time.plus_seconds(200));
     router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+            &ExecuteMsg::UpdateGovernanceVesting {
+                config: Some(GovernanceVestingConfig {
+                    vesting_contract: vesting_instance.to_string(),
+                    duration,
+                }),
             },
             &[],
         )
         .unwrap();
-}
-
-struct CheckDistributedAstro {
-    maker_amount: Uint128,
-    governance_amount: Uint128,
-    staking_amount: Uint128,
-    governance_percent: Uint64,
-    maker: Addr,
-    astro_token: Addr,
-    governance: Addr,
-    staking: Addr,
-}
-
-impl CheckDistributedAstro {
-    fn check(&mut self, router: &mut TestApp, distributed_amount: u32) {
-        let distributed_amount = Uint128::from(distributed_amount as u128);
-        let cur_governance_amount = distributed_amount
-            .multiply_ratio(Uint128::from(self.governance_percent), Uint128::new(100));
-        self.governance_amount += cur_governance_amount;
-        self.staking_amount += distributed_amount - cur_governance_amount;
-        self.maker_amount -= distributed_amount;
-
-        check_balance(
-            router,
-            self.maker.clone(),
-            self.astro_token.clone(),
-            self.maker_amount,
-        );
-
-        check_balance(
-            router,
-            self.governance.clone(),
-            self.astro_token.clone(),
-            self.governance_amount,
-        );
-
-        check_balance(
-            router,
-            self.staking.clone(),
-            self.astro_token.clone(),
-            self.staking_amount,
-        );
-    }
-}
-
-#[test]
-fn distribute_initially_accrued_fees() {
-    let uluna_asset = String::from("uluna");
-    let owner = Addr::unchecked("owner");
 
-    let mut router = mock_app(
-        owner.clone(),
-        vec![Coin {
-            denom: uluna_asset.clone(),
-            amount: Uint128::new(100_000_000_000_000000u128),
-        }],
+    // Governance contract can't be removed while its share is vested
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateConfig {
+                factory_contract: None,
+                staking_contract: None,
+                governance_contract: Some(UpdateAddr::Remove {}),
+                governance_percent: None,
+                basic_asset: None,
+                max_spread: None,
+                second_receiver_params: None,
+                collect_cooldown: None,
+                astro_token: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Cannot remove governance contract while governance vesting is enabled"
     );
 
-    let staking = Addr::unchecked("staking");
-    let governance_percent = Uint64::new(10);
-    let user = Addr::unchecked("user0000");
-
-    let (astro_token_instance, factory_instance, maker_instance, governance_instance) =
-        instantiate_contracts(
-            &mut router,
+    let collect = |router: &mut TestApp| {
+        router.update_block(next_block);
+        mint_some_token(
+            router,
             owner.clone(),
-            staking.clone(),
-            governance_percent,
-            None,
-            None,
-            None,
-            None,
+            astro_token_instance.clone(),
+            maker_instance.clone(),
+            Uint128::new(100),
         );
+        router
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                maker_instance.clone(),
+                &ExecuteMsg::Collect {
+                    assets: vec![AssetWithLimit {
+                        info: token_asset_info(astro_token_instance.clone()),
+                        limit: None,
+                    }],
+                },
+                &[],
+            )
+            .unwrap();
+    };
+    let query_vesting_state = |router: &TestApp| -> GovernanceVestingResponse {
+        router
+            .wrap()
+            .query_wasm_smart::<Option<GovernanceVestingResponse>>(
+                &maker_instance,
+                &QueryMsg::GovernanceVesting {},
+            )
+            .unwrap()
+            .unwrap()
+    };
 
-    let usdc_token_instance = instantiate_token(
+    // The first governance share is vested right away
+    collect(&mut router);
+    let start_time = router.block_info().time.seconds();
+    check_balance(
         &mut router,
-        owner.clone(),
-        "Usdc token".to_string(),
-        "USDC".to_string(),
+        vesting_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(10),
     );
-
-    let test_token_instance = instantiate_token(
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(90),
+    );
+    let vesting_account: VestingAccountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &astroport::vesting::QueryMsg::VestingAccount {
+                address: governance_instance.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        vesting_account.info.schedules,
+        vec![VestingSchedule {
+            start_point: VestingSchedulePoint {
+                time: start_time,
+                amount: Uint128::zero(),
+            },
+            end_point: Some(VestingSchedulePoint {
+                time: start_time + duration,
+                amount: Uint128::new(10),
+            }),
+        }]
+    );
+
+    // The governance share is accumulated in the Maker until the vesting period ends
+    collect(&mut router);
+    let state = query_vesting_state(&router);
+    assert_eq!(state.pending_amount, Uint128::new(10));
+    assert_eq!(state.last_vesting_ts, start_time);
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(10),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(180),
+    );
+
+    router.update_block(|block| block.time = block.time.plus_seconds(duration));
+    collect(&mut router);
+    let state = query_vesting_state(&router);
+    assert_eq!(state.pending_amount, Uint128::zero());
+    assert_eq!(state.last_vesting_ts, router.block_info().time.seconds());
+    check_balance(
+        &mut router,
+        vesting_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(30),
+    );
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::zero(),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(270),
+    );
+
+    // Disabling vesting sends the accumulated share to the governance contract
+    collect(&mut router);
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateGovernanceVesting { config: None },
+            &[],
+        )
+        .unwrap();
+    check_balance(
+        &mut router,
+        governance_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(10),
+    );
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::zero(),
+    );
+    let state: Option<GovernanceVestingResponse> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::GovernanceVesting {})
+        .unwrap();
+    assert_eq!(state, None);
+
+    // The governance share is sent directly again
+    collect(&mut router);
+    check_balance(
+        &mut router,
+        governance_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(20),
+    );
+}
+
+#[test]
+fn scheduled_distribution_windows() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(owner.clone(), vec![]);
+    let staking = Addr::unchecked("staking");
+    let keeper = Addr::unchecked("keeper");
+
+    let (astro_token_instance, _, maker_instance, governance_instance) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Uint64::new(10),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Distribute {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DistributionWindowsDisabled {}
+    );
+
+    let start_ts = router.block_info().time.seconds() + 1000;
+    let period = 86400;
+    let windows_cfg = DistributionWindowsConfig {
+        start_ts,
+        period,
+        duration: 3600,
+        caller_bounty: Decimal::percent(1),
+    };
+
+    let update_windows = |router: &mut TestApp, sender: &Addr, config| {
+        router.execute_contract(
+            sender.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateDistributionWindows { config },
+            &[],
+        )
+    };
+
+    let err = update_windows(&mut router, &keeper, Some(windows_cfg.clone())).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = update_windows(
+        &mut router,
+        &owner,
+        Some(DistributionWindowsConfig {
+            duration: period + 1,
+            ..windows_cfg.clone()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::IncorrectDistributionWindows {
+            min: *DISTRIBUTION_PERIOD_LIMITS.start(),
+            max: *DISTRIBUTION_PERIOD_LIMITS.end(),
+        }
+    );
+
+    let err = update_windows(
+        &mut router,
+        &owner,
+        Some(DistributionWindowsConfig {
+            caller_bounty: Decimal::percent(2),
+            ..windows_cfg.clone()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DistributionBountyTooHigh {
+            max: MAX_DISTRIBUTION_BOUNTY
+        }
+    );
+
+    update_windows(&mut router, &owner, Some(windows_cfg.clone())).unwrap();
+
+    let query_window = |router: &TestApp| -> DistributionWindowResponse {
+        router
+            .wrap()
+            .query_wasm_smart::<Option<DistributionWindowResponse>>(
+                &maker_instance,
+                &QueryMsg::NextDistributionWindow {},
+            )
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(
+        query_window(&router),
+        DistributionWindowResponse {
+            config: windows_cfg.clone(),
+            window_start: start_ts,
+            window_end: start_ts + 3600,
+            is_open: false,
+            countdown: 1000,
+        }
+    );
+
+    // Collected ASTRO stays in the Maker until the window opens
+    router.update_block(next_block);
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+    router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(astro_token_instance.clone()),
+                    limit: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(1000),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::zero(),
+    );
+
+    let err = router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Distribute {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DistributionWindowClosed {
+            next_window_start: start_ts
+        }
+    );
+
+    router.update_block(|block| {
+        block.height += 1;
+        block.time = Timestamp::from_seconds(start_ts + 100);
+    });
+    let window = query_window(&router);
+    assert!(window.is_open);
+    assert_eq!(window.countdown, 0);
+
+    // Anyone can distribute within the window and receive the bounty
+    router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Distribute {},
+            &[],
+        )
+        .unwrap();
+    check_balance(
+        &mut router,
+        keeper.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(10),
+    );
+    check_balance(
+        &mut router,
+        governance_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(99),
+    );
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(891),
+    );
+
+    // Only one distribution per window is allowed
+    let err = router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Distribute {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::DistributionWindowClosed {
+            next_window_start: start_ts + period
+        }
+    );
+    let window = query_window(&router);
+    assert!(!window.is_open);
+    assert_eq!(window.window_start, start_ts + period);
+    assert_eq!(window.countdown, period - 100);
+
+    // Disabling the schedule brings back distributions on collect
+    update_windows(&mut router, &owner, None).unwrap();
+    let window: Option<DistributionWindowResponse> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::NextDistributionWindow {})
+        .unwrap();
+    assert_eq!(window, None);
+
+    router.update_block(next_block);
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::new(1000),
+    );
+    router
+        .execute_contract(
+            keeper.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(astro_token_instance.clone()),
+                    limit: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(1791),
+    );
+}
+
+#[test]
+fn test_collect_cooldown() {
+    let asset0 = "asset0";
+    let asset1 = "asset1";
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            coin(100_000_000_000u128, asset0),
+            coin(100_000_000_000u128, asset1),
+        ],
+    );
+
+    let (_, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        Addr::unchecked("staking"),
+        10u64.into(),
+        Some(Decimal::from_str("0.5").unwrap()),
+        None,
+        None,
+        Some(300),
+    );
+
+    // Moving block time to be able to collect
+    router.update_block(|block| block.time = block.time.plus_seconds(300));
+
+    let asset_infos = [AssetInfo::native(asset0), AssetInfo::native(asset1)];
+
+    // Create pair in factory
+    router
+        .execute_contract(
+            owner.clone(),
+            factory_instance.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type: PairType::Xyk {},
+                asset_infos: asset_infos.to_vec(),
+                init_params: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Set assets to swap
+    let assets = vec![AssetWithLimit {
+        info: AssetInfo::native(asset0),
+        limit: None,
+    }];
+
+    // First collect works
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+    let next_collect_ts = router.block_info().time.plus_seconds(300).seconds();
+
+    // Cooldown is 300 sec. We can't collect again
+    router.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cooldown { next_collect_ts }
+    );
+
+    // In 200 seconds cooldown should be expired
+    router.update_block(|block| block.time = block.time.plus_seconds(200));
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+struct CheckDistributedAstro {
+    maker_amount: Uint128,
+    governance_amount: Uint128,
+    staking_amount: Uint128,
+    governance_percent: Uint64,
+    maker: Addr,
+    astro_token: Addr,
+    governance: Addr,
+    staking: Addr,
+}
+
+impl CheckDistributedAstro {
+    fn check(&mut self, router: &mut TestApp, distributed_amount: u32) {
+        let distributed_amount = Uint128::from(distributed_amount as u128);
+        let cur_governance_amount = distributed_amount
+            .multiply_ratio(Uint128::from(self.governance_percent), Uint128::new(100));
+        self.governance_amount += cur_governance_amount;
+        self.staking_amount += distributed_amount - cur_governance_amount;
+        self.maker_amount -= distributed_amount;
+
+        check_balance(
+            router,
+            self.maker.clone(),
+            self.astro_token.clone(),
+            self.maker_amount,
+        );
+
+        check_balance(
+            router,
+            self.governance.clone(),
+            self.astro_token.clone(),
+            self.governance_amount,
+        );
+
+        check_balance(
+            router,
+            self.staking.clone(),
+            self.astro_token.clone(),
+            self.staking_amount,
+        );
+    }
+}
+
+#[test]
+fn distribute_initially_accrued_fees() {
+    let uluna_asset = String::from("uluna");
+    let owner = Addr::unchecked("owner");
+
+    let mut router = mock_app(
+        owner.clone(),
+        vec![Coin {
+            denom: uluna_asset.clone(),
+            amount: Uint128::new(100_000_000_000_000000u128),
+        }],
+    );
+
+    let staking = Addr::unchecked("staking");
+    let governance_percent = Uint64::new(10);
+    let user = Addr::unchecked("user0000");
+
+    let (astro_token_instance, factory_instance, maker_instance, governance_instance) =
+        instantiate_contracts(
+            &mut router,
+            owner.clone(),
+            staking.clone(),
+            governance_percent,
+            None,
+            None,
+            None,
+            None,
+        );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Test token".to_string(),
+        "TEST".to_string(),
+    );
+
+    let bridge2_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Bridge 2 depth token".to_string(),
+        "BRIDGE".to_string(),
+    );
+
+    // Create pairs
+    for t in vec![
+        vec![
+            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(bridge2_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(bridge2_token_instance.clone(), Uint128::from(100_000_u128)),
+            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
+        ],
+    ] {
+        create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            t,
+            None,
+        );
+    }
+
+    // Set assets to swap
+    let assets = vec![
+        AssetWithLimit {
+            info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+    ];
+
+    // Setup bridge to withdraw USDC via the USDC -> TEST -> ASTRO route
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::UpdateBridges {
+                add: Some(vec![
+                    Route::from_assets(vec![
+                        token_asset_info(test_token_instance.clone()),
+                        token_asset_info(bridge2_token_instance.clone()),
+                    ]),
+                    Route::from_assets(vec![
+                        token_asset_info(usdc_token_instance.clone()),
+                        token_asset_info(test_token_instance.clone()),
+                    ]),
+                    Route::from_assets(vec![
+                        native_asset_info(uluna_asset.clone()),
+                        token_asset_info(usdc_token_instance.clone()),
+                    ]),
+                ]),
+                remove: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Mint all tokens for Maker
+    for t in vec![
+        (astro_token_instance.clone(), 10u128),
+        (usdc_token_instance, 20u128),
+        (test_token_instance, 30u128),
+    ] {
+        let (token, amount) = t;
+        mint_some_token(
+            &mut router,
+            owner.clone(),
+            token.clone(),
+            maker_instance.clone(),
+            Uint128::from(amount),
+        );
+
+        // Check initial balance
+        check_balance(
+            &mut router,
+            maker_instance.clone(),
+            token,
+            Uint128::from(amount),
+        );
+    }
+
+    router
+        .send_tokens(
+            owner.clone(),
+            maker_instance.clone(),
+            &[coin(100, uluna_asset.clone())],
+        )
+        .unwrap();
+
+    // Unauthorized check
+    let err = router
+        .execute_contract(
+            user.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    // Check pre_update_blocks = 0
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 0 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Generic error: Number of blocks should be > 0"
+    );
+
+    // Check that collect does not distribute ASTRO until rewards are enabled
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect { assets },
+            &[],
+        )
+        .unwrap();
+
+    // Balances checker
+    let mut checker = CheckDistributedAstro {
+        maker_amount: Uint128::new(151_u128),
+        governance_amount: Uint128::zero(),
+        staking_amount: Uint128::zero(),
+        maker: maker_instance.clone(),
+        astro_token: astro_token_instance.clone(),
+        governance_percent,
+        governance: governance_instance,
+        staking,
+    };
+    checker.check(&mut router, 0);
+
+    // Enable rewards distribution
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 10 },
+            &[],
+        )
+        .unwrap();
+
+    // Try to enable again
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Rewards collecting is already enabled"
+    );
+
+    let astro_asset = AssetWithLimit {
+        info: token_asset_info(astro_token_instance.clone()),
+        limit: None,
+    };
+    let assets = vec![astro_asset];
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Since the block number is the same, nothing happened
+    checker.check(&mut router, 0);
+
+    router.update_block(next_block);
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    checker.check(&mut router, 15);
+
+    // Let's try to collect again within the same block
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // But no ASTRO were distributed
+    checker.check(&mut router, 0);
+
+    router.update_block(next_block);
+
+    // Imagine that we received new fees the while pre-ugrade ASTRO is being distributed
+    mint_some_token(
+        &mut router,
+        owner.clone(),
+        astro_token_instance.clone(),
+        maker_instance.clone(),
+        Uint128::from(30_u128),
+    );
+
+    let resp = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    checker.maker_amount += Uint128::from(30_u128);
+    // 45 = 30 minted astro + 15 distributed astro
+    checker.check(&mut router, 45);
+
+    // Checking that attributes are set properly
+    for (attr, value) in [
+        ("astro_distribution", 30_u128),
+        ("preupgrade_astro_distribution", 15_u128),
+    ] {
+        let a = resp.events[1]
+            .attributes
+            .iter()
+            .find(|a| a.key == attr)
+            .unwrap();
+        assert_eq!(a.value, value.to_string());
+    }
+
+    // Increment 8 blocks
+    for _ in 0..8 {
+        router.update_block(next_block);
+    }
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // 120 = 15 * 8
+    checker.check(&mut router, 120);
+
+    // Check remainder reward
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .unwrap();
+
+    assert_eq!(res.remainder_reward.u128(), 1_u128);
+
+    // Check remainder reward distribution
+    router.update_block(next_block);
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    checker.check(&mut router, 1);
+
+    // Check that the pre-upgrade ASTRO was fully distributed
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .unwrap();
+
+    assert_eq!(res.remainder_reward.u128(), 0_u128);
+    assert_eq!(res.pre_upgrade_astro_amount.u128(), 151_u128);
+
+    // Check usual collecting works
+    mint_some_token(
+        &mut router,
+        owner,
+        astro_token_instance,
+        maker_instance.clone(),
+        Uint128::from(115_u128),
+    );
+
+    let resp = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect { assets },
+            &[],
+        )
+        .unwrap();
+
+    checker.maker_amount += Uint128::from(115_u128);
+    checker.check(&mut router, 115);
+
+    // Check that attributes are set properly
+    let a = resp.events[1]
+        .attributes
+        .iter()
+        .find(|a| a.key == "astro_distribution")
+        .unwrap();
+    assert_eq!(a.value, 115_u128.to_string());
+    assert!(!resp.events[1]
+        .attributes
+        .iter()
+        .any(|a| a.key == "preupgrade_astro_distribution"));
+}
+
+#[ignore]
+#[test]
+fn collect_3pools() {
+    let uusd_asset = String::from("uusd");
+    let uluna_asset = String::from("uluna");
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: uusd_asset.clone(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+            Coin {
+                denom: uluna_asset.clone(),
+                amount: Uint128::new(100_000_000_000u128),
+            },
+        ],
+    );
+    let user = Addr::unchecked("user0000");
+    let staking = Addr::unchecked("staking");
+    let max_spread = Decimal::from_str("0.5").unwrap();
+
+    let (astro_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        Default::default(),
+        Some(max_spread),
+        Some(PairType::Stable {}),
+        None,
+        None,
+    );
+
+    let usdc_token_instance = instantiate_token(
+        &mut router,
+        owner.clone(),
+        "Usdc token".to_string(),
+        "USDC".to_string(),
+    );
+
+    let test_token = instantiate_token(
         &mut router,
         owner.clone(),
         "Test token".to_string(),
         "TEST".to_string(),
     );
 
-    let bridge2_token_instance = instantiate_token(
+    // Create pairs
+    // There are 2 routes to swap USDC -> LUNA: through (USDC, TEST, LUNA) or (USDC, LUNA)
+    for t in vec![
+        vec![
+            // intentionally providing less usdc thus this pool will be selected to swap USDC -> LUNA
+            token_asset(usdc_token_instance.clone(), Uint128::from(80_000_u128)),
+            token_asset(test_token.clone(), Uint128::from(100_000_u128)),
+            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
+            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
+            native_asset(uusd_asset.clone(), Uint128::from(100_000_u128)),
+        ],
+        vec![
+            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
+            native_asset(uusd_asset.clone(), Uint128::from(100_000_u128)),
+        ],
+    ] {
+        create_pair(
+            &mut router,
+            owner.clone(),
+            user.clone(),
+            &factory_instance,
+            t,
+            Some(PairType::Stable {}),
+        );
+    }
+
+    // Set assets to swap
+    let assets = vec![
+        AssetWithLimit {
+            info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_asset(test_token.clone(), Uint128::zero()).info,
+            limit: None,
+        },
+    ];
+
+    // Enable rewards distribution
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    // Mint all tokens for Maker
+    for t in vec![
+        (astro_token_instance.clone(), 10u128),
+        (usdc_token_instance.clone(), 20u128),
+        (test_token.clone(), 30u128),
+    ] {
+        let (token, amount) = t;
+        mint_some_token(
+            &mut router,
+            owner.clone(),
+            token.clone(),
+            maker_instance.clone(),
+            Uint128::from(amount),
+        );
+
+        // Check initial balance
+        check_balance(
+            &mut router,
+            maker_instance.clone(),
+            token,
+            Uint128::from(amount),
+        );
+    }
+
+    let expected_balances = vec![
+        token_asset(astro_token_instance.clone(), Uint128::new(10)),
+        token_asset(usdc_token_instance.clone(), Uint128::new(20)),
+        token_asset(test_token.clone(), Uint128::new(30)),
+    ];
+
+    let balances_resp: BalancesResponse = router
+        .wrap()
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: maker_instance.to_string(),
+            msg: to_json_binary(&QueryMsg::Balances {
+                assets: expected_balances.iter().map(|a| a.info.clone()).collect(),
+            })
+            .unwrap(),
+        }))
+        .unwrap();
+
+    for b in expected_balances {
+        let found = balances_resp
+            .balances
+            .iter()
+            .find(|n| n.info.equal(&b.info))
+            .unwrap();
+
+        assert_eq!(found, &b);
+    }
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: assets.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Check Maker's balance of ASTRO tokens
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        astro_token_instance.clone(),
+        Uint128::zero(),
+    );
+
+    // Check Maker's balance of USDC tokens
+    check_balance(
         &mut router,
-        owner.clone(),
-        "Bridge 2 depth token".to_string(),
-        "BRIDGE".to_string(),
+        maker_instance.clone(),
+        usdc_token_instance.clone(),
+        Uint128::zero(),
     );
 
-    // Create pairs
-    for t in vec![
+    // Check Maker's balance of test tokens
+    check_balance(
+        &mut router,
+        maker_instance.clone(),
+        test_token.clone(),
+        Uint128::zero(),
+    );
+
+    // Check the staking contract's balance for the ASTRO token
+    check_balance(
+        &mut router,
+        staking.clone(),
+        astro_token_instance.clone(),
+        Uint128::new(62u128),
+    );
+
+    // Check that USDC -> LUNA swap was not executed through pair (usdc, luna) but through (usdc, luna, test).
+    let pair_info: PairInfo = router
+        .wrap()
+        .query_wasm_smart(
+            &factory_instance,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: vec![
+                    token_asset_info(usdc_token_instance),
+                    native_asset_info(uluna_asset),
+                ],
+            },
+        )
+        .unwrap();
+    let balances = pair_info
+        .query_pools(&router.wrap(), &pair_info.contract_addr)
+        .unwrap();
+    assert_eq!(balances[0].amount.u128(), 100_000);
+    assert_eq!(balances[1].amount.u128(), 100_000);
+}
+
+#[test]
+fn fee_share_stats() {
+    let asset0 = "asset0";
+    let asset1 = "asset1";
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
         vec![
-            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
-            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
+            coin(100_000_000_000u128, asset0),
+            coin(100_000_000_000u128, asset1),
         ],
+    );
+
+    let (_, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        Addr::unchecked("staking"),
+        10u64.into(),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let pair_info = create_pair(
+        &mut router,
+        owner.clone(),
+        Addr::unchecked("user"),
+        &factory_instance,
         vec![
-            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
-            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
+            Asset::native(asset0, 100_000u128),
+            Asset::native(asset1, 100_000u128),
         ],
+        None,
+    );
+
+    // Only factory pairs can report shared fees
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            maker_instance.clone(),
+            &ExecuteMsg::FeeShareReceived {
+                asset: Asset::native(asset0, 100u128),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    // The asset must belong to the reporting pair
+    let err = router
+        .execute_contract(
+            pair_info.contract_addr.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::FeeShareReceived {
+                asset: Asset::native("uusd", 100u128),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    for asset in [
+        Asset::native(asset0, 100u128),
+        Asset::native(asset1, 50u128),
+        Asset::native(asset0, 20u128),
+    ] {
+        router
+            .execute_contract(
+                pair_info.contract_addr.clone(),
+                maker_instance.clone(),
+                &ExecuteMsg::FeeShareReceived { asset },
+                &[],
+            )
+            .unwrap();
+    }
+
+    let stats: Vec<Asset> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::FeeShareStats {
+                pair: pair_info.contract_addr.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        stats,
         vec![
-            token_asset(test_token_instance.clone(), Uint128::from(100_000_u128)),
-            token_asset(bridge2_token_instance.clone(), Uint128::from(100_000_u128)),
-        ],
+            Asset::native(asset0, 120u128),
+            Asset::native(asset1, 50u128)
+        ]
+    );
+
+    let stats: Vec<Asset> = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::FeeShareStats {
+                pair: "random".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(stats.is_empty());
+}
+
+#[test]
+fn simulate_collect() {
+    let asset0 = "asset0";
+    let asset1 = "asset1";
+    let asset2 = "asset2";
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(
+        owner.clone(),
         vec![
-            token_asset(bridge2_token_instance.clone(), Uint128::from(100_000_u128)),
-            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
+            coin(100_000_000_000u128, asset0),
+            coin(100_000_000_000u128, asset1),
+            coin(100_000_000_000u128, asset2),
         ],
-    ] {
-        create_pair(
+    );
+    let staking = Addr::unchecked("staking");
+
+    let (astro_token_instance, factory_instance, maker_instance, governance_instance) =
+        instantiate_contracts(
             &mut router,
             owner.clone(),
-            user.clone(),
-            &factory_instance,
-            t,
+            staking.clone(),
+            50u64.into(),
+            Some(Decimal::percent(50)),
+            None,
+            None,
             None,
         );
-    }
 
-    // Set assets to swap
-    let assets = vec![
-        AssetWithLimit {
-            info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: native_asset(uluna_asset.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: token_asset(test_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: token_asset(bridge2_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-    ];
+    let user = Addr::unchecked("user");
+    create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            Asset::native(asset0, 100_000_000u128),
+            token_asset(astro_token_instance.clone(), 100_000_000u128.into()),
+        ],
+        None,
+    );
+    create_pair(
+        &mut router,
+        owner.clone(),
+        user.clone(),
+        &factory_instance,
+        vec![
+            Asset::native(asset1, 100_000_000u128),
+            Asset::native(asset0, 100_000_000u128),
+        ],
+        None,
+    );
 
-    // Setup bridge to withdraw USDC via the USDC -> TEST -> ASTRO route
+    // asset1 is swapped to ASTRO via asset0
     router
         .execute_contract(
             owner.clone(),
             maker_instance.clone(),
             &ExecuteMsg::UpdateBridges {
-                add: Some(vec![
-                    (
-                        token_asset_info(test_token_instance.clone()),
-                        token_asset_info(bridge2_token_instance.clone()),
-                    ),
-                    (
-                        token_asset_info(usdc_token_instance.clone()),
-                        token_asset_info(test_token_instance.clone()),
-                    ),
-                    (
-                        native_asset_info(uluna_asset.clone()),
-                        token_asset_info(usdc_token_instance.clone()),
-                    ),
-                ]),
+                add: Some(vec![Route::from_assets(vec![
+                    AssetInfo::native(asset1),
+                    AssetInfo::native(asset0),
+                ])]),
                 remove: None,
             },
             &[],
         )
         .unwrap();
 
-    // Mint all tokens for Maker
-    for t in vec![
-        (astro_token_instance.clone(), 10u128),
-        (usdc_token_instance, 20u128),
-        (test_token_instance, 30u128),
-    ] {
-        let (token, amount) = t;
-        mint_some_token(
-            &mut router,
-            owner.clone(),
-            token.clone(),
-            maker_instance.clone(),
-            Uint128::from(amount),
-        );
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
+
+    validate_and_send_funds(
+        &mut router,
+        &owner,
+        &maker_instance,
+        vec![
+            coin(1_000_000u128, asset0),
+            coin(1_000_000u128, asset1),
+            coin(1_000_000u128, asset2),
+        ],
+    );
+
+    let simulation: SimulateCollectResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::SimulateCollect {
+                assets: vec![
+                    AssetWithLimit {
+                        info: AssetInfo::native(asset0),
+                        limit: Some(500_000u128.into()),
+                    },
+                    AssetWithLimit {
+                        info: AssetInfo::native(asset1),
+                        limit: None,
+                    },
+                    AssetWithLimit {
+                        info: AssetInfo::native(asset2),
+                        limit: None,
+                    },
+                    AssetWithLimit {
+                        info: token_asset_info(astro_token_instance.clone()),
+                        limit: None,
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    assert_eq!(simulation.swaps.len(), 2);
+    assert_eq!(simulation.swaps[0].0, Asset::native(asset0, 500_000u128),);
+    assert_eq!(simulation.swaps[1].0, Asset::native(asset1, 1_000_000u128),);
+    assert!(simulation.swaps.iter().all(|(_, amount)| !amount.is_zero()));
+    assert_eq!(
+        simulation.astro_amount,
+        simulation.swaps[0].1 + simulation.swaps[1].1
+    );
+    // There is no route for asset2
+    assert_eq!(simulation.failed_assets, vec![AssetInfo::native(asset2)]);
+
+    // Duplicated assets are rejected as in collect
+    let err = router
+        .wrap()
+        .query_wasm_smart::<SimulateCollectResponse>(
+            &maker_instance,
+            &QueryMsg::SimulateCollect {
+                assets: vec![
+                    AssetWithLimit {
+                        info: AssetInfo::native(asset0),
+                        limit: None,
+                    },
+                    AssetWithLimit {
+                        info: AssetInfo::native(asset0),
+                        limit: None,
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&ContractError::DuplicatedAsset {}.to_string()));
+
+    // A single hop estimate matches the actual collect outcome
+    let simulation: SimulateCollectResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::SimulateCollect {
+                assets: vec![AssetWithLimit {
+                    info: AssetInfo::native(asset0),
+                    limit: None,
+                }],
+            },
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            maker_instance.clone(),
+            &ExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: AssetInfo::native(asset0),
+                    limit: None,
+                }],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let governance_amount = simulation.astro_amount.multiply_ratio(50u128, 100u128);
+    check_balance(
+        &mut router,
+        governance_instance,
+        astro_token_instance.clone(),
+        governance_amount,
+    );
+    check_balance(
+        &mut router,
+        staking,
+        astro_token_instance,
+        simulation.astro_amount - governance_amount,
+    );
+}
 
-        // Check initial balance
-        check_balance(
-            &mut router,
-            maker_instance.clone(),
-            token,
-            Uint128::from(amount),
-        );
-    }
+fn mock_oracle_contract() -> Box<dyn Contract<Empty>> {
+    // Ask asset along with the TWAP price of the offer asset denominated in it
+    const PRICE: Item<(AssetInfo, Decimal)> = Item::new("price");
+
+    let instantiate =
+        |deps: DepsMut, _: Env, _: MessageInfo, msg: (AssetInfo, Decimal)| -> StdResult<Response> {
+            PRICE.save(deps.storage, &msg)?;
+            Ok(Default::default())
+        };
+    let execute =
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { unimplemented!() };
+    let query = |deps: Deps, _: Env, msg: OracleQueryMsg| -> StdResult<Binary> {
+        let (ask_asset, price) = PRICE.load(deps.storage)?;
+        match msg {
+            OracleQueryMsg::Consult { amount, .. } => {
+                to_json_binary(&vec![(ask_asset, Uint256::from(amount * price))])
+            }
+            _ => unimplemented!(),
+        }
+    };
+
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+#[test]
+fn swap_limits() {
+    let asset0 = "asset0";
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![coin(100_000_000_000u128, asset0)]);
+    let staking = Addr::unchecked("staking");
+
+    let (astro_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+        &mut router,
+        owner.clone(),
+        staking.clone(),
+        50u64.into(),
+        Some(Decimal::percent(50)),
+        None,
+        None,
+        None,
+    );
+
+    create_pair(
+        &mut router,
+        owner.clone(),
+        Addr::unchecked("user"),
+        &factory_instance,
+        vec![
+            Asset::native(asset0, 100_000_000u128),
+            token_asset(astro_token_instance.clone(), 100_000_000u128.into()),
+        ],
+        None,
+    );
 
     router
-        .send_tokens(
+        .execute_contract(
             owner.clone(),
             maker_instance.clone(),
-            &[coin(100, uluna_asset.clone())],
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
         )
         .unwrap();
 
-    // Unauthorized check
+    validate_and_send_funds(
+        &mut router,
+        &owner,
+        &maker_instance,
+        vec![coin(1_000_000u128, asset0)],
+    );
+
+    let update_limits = |max_spread: Decimal, oracle: Option<&Addr>| ExecuteMsg::UpdateSwapLimits {
+        set: Some(vec![(
+            AssetInfo::native(asset0),
+            SwapLimitParams {
+                max_spread: Some(max_spread),
+                oracle: oracle.map(Addr::to_string),
+                min_collect_amount: None,
+            },
+        )]),
+        remove: None,
+    };
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::native(asset0),
+            limit: None,
+        }],
+    };
+
     let err = router
         .execute_contract(
-            user.clone(),
+            Addr::unchecked("anyone"),
             maker_instance.clone(),
-            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &update_limits(Decimal::percent(1), None),
             &[],
         )
         .unwrap_err();
-    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
 
-    // Check pre_update_blocks = 0
     let err = router
         .execute_contract(
             owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::EnableRewards { blocks: 0 },
+            &update_limits(Decimal::zero(), None),
             &[],
         )
         .unwrap_err();
     assert_eq!(
-        err.root_cause().to_string(),
-        "Generic error: Number of blocks should be > 0"
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::IncorrectMaxSpread {}
     );
 
-    // Check that collect does not distribute ASTRO until rewards are enabled
+    // Swapping 1% of the pool doesn't fit into 0.5% spread
     router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect { assets },
+            &update_limits(Decimal::permille(5), None),
             &[],
         )
         .unwrap();
 
-    // Balances checker
-    let mut checker = CheckDistributedAstro {
-        maker_amount: Uint128::new(151_u128),
-        governance_amount: Uint128::zero(),
-        staking_amount: Uint128::zero(),
-        maker: maker_instance.clone(),
-        astro_token: astro_token_instance.clone(),
-        governance_percent,
-        governance: governance_instance,
-        staking,
+    let limits: Vec<(String, SwapLimit)> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::SwapLimits {})
+        .unwrap();
+    assert_eq!(
+        limits,
+        vec![(
+            asset0.to_string(),
+            SwapLimit {
+                max_spread: Some(Decimal::permille(5)),
+                oracle: None,
+                min_collect_amount: None,
+            }
+        )]
+    );
+
+    let err = router
+        .execute_contract(owner.clone(), maker_instance.clone(), &collect, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Operation exceeds max spread limit"
+    );
+
+    // The oracle prices asset0 twice as high as the pool does
+    let oracle_code_id = router.store_code(mock_oracle_contract());
+    let mut instantiate_oracle = |price: Decimal| {
+        router
+            .instantiate_contract(
+                oracle_code_id,
+                owner.clone(),
+                &(token_asset_info(astro_token_instance.clone()), price),
+                &[],
+                "oracle",
+                None,
+            )
+            .unwrap()
     };
-    checker.check(&mut router, 0);
+    let manipulated_oracle = instantiate_oracle(Decimal::percent(200));
+    let oracle = instantiate_oracle(Decimal::one());
 
-    // Enable rewards distribution
     router
         .execute_contract(
             owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::EnableRewards { blocks: 10 },
+            &update_limits(Decimal::percent(10), Some(&manipulated_oracle)),
             &[],
         )
         .unwrap();
 
-    // Try to enable again
     let err = router
-        .execute_contract(
-            owner.clone(),
-            maker_instance.clone(),
-            &ExecuteMsg::EnableRewards { blocks: 1 },
-            &[],
-        )
+        .execute_contract(owner.clone(), maker_instance.clone(), &collect, &[])
         .unwrap_err();
     assert_eq!(
         err.root_cause().to_string(),
-        "Rewards collecting is already enabled"
+        "Operation exceeds max spread limit"
     );
 
-    let astro_asset = AssetWithLimit {
-        info: token_asset_info(astro_token_instance.clone()),
-        limit: None,
-    };
-    let assets = vec![astro_asset];
-
+    // The swap output is within 2% of the oracle converted amount including the pool fee
     router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
-            },
+            &update_limits(Decimal::percent(2), Some(&oracle)),
             &[],
         )
         .unwrap();
 
-    // Since the block number is the same, nothing happened
-    checker.check(&mut router, 0);
-
-    router.update_block(next_block);
-
     router
-        .execute_contract(
-            Addr::unchecked("anyone"),
-            maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+        .execute_contract(owner.clone(), maker_instance.clone(), &collect, &[])
+        .unwrap();
+
+    let staking_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: staking.to_string(),
             },
-            &[],
         )
         .unwrap();
+    assert!(!staking_balance.balance.is_zero());
 
-    checker.check(&mut router, 15);
-
-    // Let's try to collect again within the same block
     router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+            &ExecuteMsg::UpdateSwapLimits {
+                set: None,
+                remove: Some(vec![AssetInfo::native(asset0)]),
             },
             &[],
         )
         .unwrap();
 
-    // But no ASTRO were distributed
-    checker.check(&mut router, 0);
+    let limits: Vec<(String, SwapLimit)> = router
+        .wrap()
+        .query_wasm_smart(&maker_instance, &QueryMsg::SwapLimits {})
+        .unwrap();
+    assert!(limits.is_empty());
+}
 
-    router.update_block(next_block);
+#[test]
+fn collect_skips_dust() {
+    let asset0 = "asset0";
+    let owner = Addr::unchecked("owner");
+    let mut router = mock_app(owner.clone(), vec![coin(100_000_000_000u128, asset0)]);
+    let staking = Addr::unchecked("staking");
 
-    // Imagine that we received new fees the while pre-ugrade ASTRO is being distributed
-    mint_some_token(
+    let (astro_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
         &mut router,
         owner.clone(),
-        astro_token_instance.clone(),
-        maker_instance.clone(),
-        Uint128::from(30_u128),
+        staking.clone(),
+        50u64.into(),
+        Some(Decimal::percent(50)),
+        None,
+        None,
+        None,
     );
 
-    let resp = router
-        .execute_contract(
-            Addr::unchecked("anyone"),
-            maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
-            },
-            &[],
-        )
-        .unwrap();
-
-    checker.maker_amount += Uint128::from(30_u128);
-    // 45 = 30 minted astro + 15 distributed astro
-    checker.check(&mut router, 45);
-
-    // Checking that attributes are set properly
-    for (attr, value) in [
-        ("astro_distribution", 30_u128),
-        ("preupgrade_astro_distribution", 15_u128),
-    ] {
-        let a = resp.events[1]
-            .attributes
-            .iter()
-            .find(|a| a.key == attr)
-            .unwrap();
-        assert_eq!(a.value, value.to_string());
-    }
-
-    // Increment 8 blocks
-    for _ in 0..8 {
-        router.update_block(next_block);
-    }
+    create_pair(
+        &mut router,
+        owner.clone(),
+        Addr::unchecked("user"),
+        &factory_instance,
+        vec![
+            Asset::native(asset0, 100_000_000u128),
+            token_asset(astro_token_instance.clone(), 100_000_000u128.into()),
+        ],
+        None,
+    );
 
     router
         .execute_contract(
-            Addr::unchecked("anyone"),
+            owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
+            &ExecuteMsg::UpdateSwapLimits {
+                set: Some(vec![(
+                    AssetInfo::native(asset0),
+                    SwapLimitParams {
+                        max_spread: None,
+                        oracle: None,
+                        min_collect_amount: Some(10_000u128.into()),
+                    },
+                )]),
+                remove: None,
             },
             &[],
         )
         .unwrap();
 
-    // 120 = 15 * 8
-    checker.check(&mut router, 120);
-
-    // Check remainder reward
-    let res: ConfigResponse = router
+    let limits: Vec<(String, SwapLimit)> = router
         .wrap()
-        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .query_wasm_smart(&maker_instance, &QueryMsg::SwapLimits {})
         .unwrap();
+    assert_eq!(
+        limits[0].1.min_collect_amount,
+        Some(Uint128::new(10_000u128))
+    );
 
-    assert_eq!(res.remainder_reward.u128(), 1_u128);
+    validate_and_send_funds(
+        &mut router,
+        &owner,
+        &maker_instance,
+        vec![coin(9_999u128, asset0)],
+    );
 
-    // Check remainder reward distribution
-    router.update_block(next_block);
+    let collect = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::native(asset0),
+            limit: None,
+        }],
+    };
 
-    router
+    // Dust balance stays in the Maker
+    let resp = router
         .execute_contract(
             Addr::unchecked("anyone"),
             maker_instance.clone(),
-            &ExecuteMsg::Collect {
-                assets: assets.clone(),
-            },
+            &collect,
             &[],
         )
         .unwrap();
+    assert!(resp.events[1]
+        .attributes
+        .contains(&attr("skipped_asset", asset0)));
+    assert_eq!(
+        router
+            .wrap()
+            .query_balance(&maker_instance, asset0)
+            .unwrap()
+            .amount
+            .u128(),
+        9_999
+    );
 
-    checker.check(&mut router, 1);
-
-    // Check that the pre-upgrade ASTRO was fully distributed
-    let res: ConfigResponse = router
+    let simulation: SimulateCollectResponse = router
         .wrap()
-        .query_wasm_smart(&maker_instance, &QueryMsg::Config {})
+        .query_wasm_smart(
+            &maker_instance,
+            &QueryMsg::SimulateCollect {
+                assets: vec![AssetWithLimit {
+                    info: AssetInfo::native(asset0),
+                    limit: None,
+                }],
+            },
+        )
         .unwrap();
+    assert!(simulation.swaps.is_empty());
 
-    assert_eq!(res.remainder_reward.u128(), 0_u128);
-    assert_eq!(res.pre_upgrade_astro_amount.u128(), 151_u128);
-
-    // Check usual collecting works
-    mint_some_token(
+    validate_and_send_funds(
         &mut router,
-        owner,
-        astro_token_instance,
-        maker_instance.clone(),
-        Uint128::from(115_u128),
+        &owner,
+        &maker_instance,
+        vec![coin(1u128, asset0)],
     );
 
-    let resp = router
+    router
         .execute_contract(
             Addr::unchecked("anyone"),
             maker_instance.clone(),
-            &ExecuteMsg::Collect { assets },
+            &collect,
             &[],
         )
         .unwrap();
+    assert!(router
+        .wrap()
+        .query_balance(&maker_instance, asset0)
+        .unwrap()
+        .amount
+        .is_zero());
+}
 
-    checker.maker_amount += Uint128::from(115_u128);
-    checker.check(&mut router, 115);
+fn mock_staking_contract() -> Box<dyn Contract<Empty>> {
+    const CONFIG: Item<StakingConfig> = Item::new("config");
+
+    let instantiate =
+        |deps: DepsMut, _: Env, _: MessageInfo, msg: StakingConfig| -> StdResult<Response> {
+            CONFIG.save(deps.storage, &msg)?;
+            Ok(Default::default())
+        };
+    // Every xASTRO is worth 2 ASTRO
+    let execute =
+        |deps: DepsMut, _: Env, info: MessageInfo, msg: StakingExecuteMsg| -> StdResult<Response> {
+            let config = CONFIG.load(deps.storage)?;
+            match msg {
+                StakingExecuteMsg::Leave { .. } => {
+                    let amount = info
+                        .funds
+                        .iter()
+                        .find(|coin| coin.denom == config.xastro_denom)
+                        .unwrap()
+                        .amount;
+                    Ok(Response::new().add_message(wasm_execute(
+                        config.astro_denom,
+                        &cw20::Cw20ExecuteMsg::Transfer {
+                            recipient: info.sender.to_string(),
+                            amount: amount * Uint128::new(2),
+                        },
+                        vec![],
+                    )?))
+                }
+                _ => unimplemented!(),
+            }
+        };
+    let query = |deps: Deps, _: Env, msg: StakingQueryMsg| -> StdResult<Binary> {
+        match msg {
+            StakingQueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+            _ => unimplemented!(),
+        }
+    };
 
-    // Check that attributes are set properly
-    let a = resp.events[1]
-        .attributes
-        .iter()
-        .find(|a| a.key == "astro_distribution")
-        .unwrap();
-    assert_eq!(a.value, 115_u128.to_string());
-    assert!(!resp.events[1]
-        .attributes
-        .iter()
-        .any(|a| a.key == "preupgrade_astro_distribution"));
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
 }
 
-#[ignore]
 #[test]
-fn collect_3pools() {
-    let uusd_asset = String::from("uusd");
-    let uluna_asset = String::from("uluna");
+fn collect_unwraps_xastro() {
+    let xastro = "xastro";
     let owner = Addr::unchecked("owner");
-    let mut router = mock_app(
-        owner.clone(),
-        vec![
-            Coin {
-                denom: uusd_asset.clone(),
-                amount: Uint128::new(100_000_000_000u128),
-            },
-            Coin {
-                denom: uluna_asset.clone(),
-                amount: Uint128::new(100_000_000_000u128),
-            },
-        ],
-    );
-    let user = Addr::unchecked("user0000");
+    let mut router = mock_app(owner.clone(), vec![coin(100_000_000_000u128, xastro)]);
     let staking = Addr::unchecked("staking");
-    let max_spread = Decimal::from_str("0.5").unwrap();
 
-    let (astro_token_instance, factory_instance, maker_instance, _) = instantiate_contracts(
+    let (astro_token_instance, _, maker_instance, _) = instantiate_contracts(
         &mut router,
         owner.clone(),
         staking.clone(),
-        Default::default(),
-        Some(max_spread),
-        Some(PairType::Stable {}),
+        0u64.into(),
+        None,
+        None,
         None,
         None,
     );
 
-    let usdc_token_instance = instantiate_token(
+    let staking_code_id = router.store_code(mock_staking_contract());
+    let xastro_staking = router
+        .instantiate_contract(
+            staking_code_id,
+            owner.clone(),
+            &StakingConfig {
+                astro_denom: astro_token_instance.to_string(),
+                xastro_denom: xastro.to_string(),
+            },
+            &[],
+            "xASTRO staking",
+            None,
+        )
+        .unwrap();
+    mint_some_token(
         &mut router,
         owner.clone(),
-        "Usdc token".to_string(),
-        "USDC".to_string(),
+        astro_token_instance.clone(),
+        xastro_staking.clone(),
+        Uint128::new(1_000_000),
     );
 
-    let test_token = instantiate_token(
-        &mut router,
-        owner.clone(),
-        "Test token".to_string(),
-        "TEST".to_string(),
+    let update_routes = |asset: &str| ExecuteMsg::UpdateUnwrapRoutes {
+        set: Some(vec![(
+            AssetInfo::native(asset),
+            UnwrapRouteParams::StakingLeave {
+                staking_contract: xastro_staking.to_string(),
+            },
+        )]),
+        remove: None,
+    };
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            maker_instance.clone(),
+            &update_routes(xastro),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
     );
 
-    // Create pairs
-    // There are 2 routes to swap USDC -> LUNA: through (USDC, TEST, LUNA) or (USDC, LUNA)
-    for t in vec![
-        vec![
-            // intentionally providing less usdc thus this pool will be selected to swap USDC -> LUNA
-            token_asset(usdc_token_instance.clone(), Uint128::from(80_000_u128)),
-            token_asset(test_token.clone(), Uint128::from(100_000_u128)),
-            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
-        ],
-        vec![
-            token_asset(usdc_token_instance.clone(), Uint128::from(100_000_u128)),
-            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
-        ],
-        vec![
-            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
-            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
-        ],
-        vec![
-            token_asset(astro_token_instance.clone(), Uint128::from(100_000_u128)),
-            native_asset(uusd_asset.clone(), Uint128::from(100_000_u128)),
-        ],
-        vec![
-            native_asset(uluna_asset.clone(), Uint128::from(100_000_u128)),
-            native_asset(uusd_asset.clone(), Uint128::from(100_000_u128)),
-        ],
-    ] {
-        create_pair(
-            &mut router,
+    // The staking contract doesn't mint the asset
+    let err = router
+        .execute_contract(
             owner.clone(),
-            user.clone(),
-            &factory_instance,
-            t,
-            Some(PairType::Stable {}),
-        );
-    }
-
-    // Set assets to swap
-    let assets = vec![
-        AssetWithLimit {
-            info: token_asset(astro_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: token_asset(usdc_token_instance.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-        AssetWithLimit {
-            info: token_asset(test_token.clone(), Uint128::zero()).info,
-            limit: None,
-        },
-    ];
+            maker_instance.clone(),
+            &update_routes("uusd"),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidUnwrapRoute(AssetInfo::native("uusd"))
+    );
 
-    // Enable rewards distribution
     router
         .execute_contract(
             owner.clone(),
             maker_instance.clone(),
-            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &update_routes(xastro),
             &[],
         )
         .unwrap();
 
-    // Mint all tokens for Maker
-    for t in vec![
-        (astro_token_instance.clone(), 10u128),
-        (usdc_token_instance.clone(), 20u128),
-        (test_token.clone(), 30u128),
-    ] {
-        let (token, amount) = t;
-        mint_some_token(
-            &mut router,
-            owner.clone(),
-            token.clone(),
-            maker_instance.clone(),
-            Uint128::from(amount),
-        );
-
-        // Check initial balance
-        check_balance(
-            &mut router,
-            maker_instance.clone(),
-            token,
-            Uint128::from(amount),
-        );
-    }
-
-    let expected_balances = vec![
-        token_asset(astro_token_instance.clone(), Uint128::new(10)),
-        token_asset(usdc_token_instance.clone(), Uint128::new(20)),
-        token_asset(test_token.clone(), Uint128::new(30)),
-    ];
-
-    let balances_resp: BalancesResponse = router
+    let routes: Vec<(String, UnwrapRoute)> = router
         .wrap()
-        .query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: maker_instance.to_string(),
-            msg: to_json_binary(&QueryMsg::Balances {
-                assets: expected_balances.iter().map(|a| a.info.clone()).collect(),
-            })
-            .unwrap(),
-        }))
+        .query_wasm_smart(&maker_instance, &QueryMsg::UnwrapRoutes {})
         .unwrap();
+    assert_eq!(
+        routes,
+        vec![(
+            xastro.to_string(),
+            UnwrapRoute::StakingLeave {
+                staking_contract: xastro_staking.clone()
+            }
+        )]
+    );
 
-    for b in expected_balances {
-        let found = balances_resp
-            .balances
-            .iter()
-            .find(|n| n.info.equal(&b.info))
-            .unwrap();
+    router
+        .execute_contract(
+            owner.clone(),
+            maker_instance.clone(),
+            &ExecuteMsg::EnableRewards { blocks: 1 },
+            &[],
+        )
+        .unwrap();
 
-        assert_eq!(found, &b);
-    }
+    validate_and_send_funds(
+        &mut router,
+        &owner,
+        &maker_instance,
+        vec![coin(100_000u128, xastro)],
+    );
 
     router
         .execute_contract(
             Addr::unchecked("anyone"),
             maker_instance.clone(),
             &ExecuteMsg::Collect {
-                assets: assets.clone(),
+                assets: vec![AssetWithLimit {
+                    info: AssetInfo::native(xastro),
+                    limit: None,
+                }],
             },
             &[],
         )
         .unwrap();
 
-    // Check Maker's balance of ASTRO tokens
-    check_balance(
-        &mut router,
-        maker_instance.clone(),
-        astro_token_instance.clone(),
-        Uint128::zero(),
-    );
-
-    // Check Maker's balance of USDC tokens
-    check_balance(
-        &mut router,
-        maker_instance.clone(),
-        usdc_token_instance.clone(),
-        Uint128::zero(),
-    );
-
-    // Check Maker's balance of test tokens
-    check_balance(
-        &mut router,
-        maker_instance.clone(),
-        test_token.clone(),
-        Uint128::zero(),
-    );
-
-    // Check the staking contract's balance for the ASTRO token
-    check_balance(
-        &mut router,
-        staking.clone(),
-        astro_token_instance.clone(),
-        Uint128::new(62u128),
-    );
-
-    // Check that USDC -> LUNA swap was not executed through pair (usdc, luna) but through (usdc, luna, test).
-    let pair_info: PairInfo = router
+    // xASTRO is unwrapped and the resulting ASTRO is distributed to stakers
+    assert!(router
+        .wrap()
+        .query_balance(&maker_instance, xastro)
+        .unwrap()
+        .amount
+        .is_zero());
+    let staking_balance: BalanceResponse = router
         .wrap()
         .query_wasm_smart(
-            &factory_instance,
-            &astroport::factory::QueryMsg::Pair {
-                asset_infos: vec![
-                    token_asset_info(usdc_token_instance),
-                    native_asset_info(uluna_asset),
-                ],
+            &astro_token_instance,
+            &Cw20QueryMsg::Balance {
+                address: staking.to_string(),
             },
         )
         .unwrap();
-    let balances = pair_info
-        .query_pools(&router.wrap(), &pair_info.contract_addr)
-        .unwrap();
-    assert_eq!(balances[0].amount.u128(), 100_000);
-    assert_eq!(balances[1].amount.u128(), 100_000);
+    assert_eq!(staking_balance.balance.u128(), 200_000);
 }
@@ -1,23 +1,36 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, coin, ensure, to_json_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    attr, coin, ensure, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw_utils::{must_pay, parse_reply_instantiate_data, MsgInstantiateContractResponse};
+use cw_storage_plus::Bound;
+use cw_utils::{
+    must_pay, parse_reply_instantiate_data, MsgInstantiateContractResponse, PaymentError,
+};
 use osmosis_std::types::cosmos::bank::v1beta1::{DenomUnit, Metadata};
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
     MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint, MsgSetBeforeSendHook,
     MsgSetDenomMetadata,
 };
 
+use astroport::asset::{addr_opt_validate, validate_native_denom};
+use astroport::common::{
+    claim_ownership, drop_ownership_proposal, init_ownership_timelock, propose_new_owner,
+    update_ownership_timelock,
+};
 use astroport::staking::{
-    Config, ExecuteMsg, InstantiateMsg, QueryMsg, StakingResponse, TrackerData,
+    validate_absorb_interval, AbsorbConfig, AbsorbInfoResponse, Config, ExecuteMsg, InstantiateMsg,
+    QueryMsg, RewardsConfig, StakingResponse, TrackerData, DEFAULT_ABSORB_INTERVAL,
+    DEFAULT_CLAIM_LIMIT, MAX_CLAIM_LIMIT, REWARD_CLAIM_PERIOD,
 };
 
 use crate::error::ContractError;
-use crate::state::{CONFIG, TRACKER_DATA};
+use crate::state::{
+    ABSORB_CONFIG, CONFIG, OWNERSHIP_PROPOSAL, REWARDS_CONFIG, REWARD_CHECKPOINTS,
+    REWARD_INJECTIONS, REWARD_REMAINDERS, TOTAL_DEPOSIT, TRACKER_DATA,
+};
 
 /// Contract name that is used for migration.
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -71,6 +84,33 @@ pub fn instantiate(
     // Validate addresses
     deps.api.addr_validate(&msg.token_factory_addr)?;
     deps.api.addr_validate(&msg.tracking_admin)?;
+    let owner = deps
+        .api
+        .addr_validate(msg.owner.as_ref().unwrap_or(&msg.tracking_admin))?;
+
+    REWARDS_CONFIG.save(
+        deps.storage,
+        &RewardsConfig {
+            owner,
+            injectors: vec![],
+            reward_denoms: vec![],
+        },
+    )?;
+    init_ownership_timelock(deps.storage, msg.ownership_timelock)?;
+
+    let absorb_interval = msg.absorb_interval.unwrap_or(DEFAULT_ABSORB_INTERVAL);
+    validate_absorb_interval(absorb_interval)?;
+    ABSORB_CONFIG.save(
+        deps.storage,
+        &AbsorbConfig {
+            interval: absorb_interval,
+            last_absorb_ts: env.block.time.seconds(),
+            releasing_amount: Uint128::zero(),
+            released_ts: env.block.time.seconds(),
+            release_end_ts: env.block.time.seconds(),
+        },
+    )?;
+    TOTAL_DEPOSIT.save(deps.storage, &Uint128::zero())?;
 
     CONFIG.save(
         deps.storage,
@@ -107,6 +147,15 @@ pub fn instantiate(
 /// ## Variants
 /// * **ExecuteMsg::Enter** Stake the provided ASTRO tokens for xASTRO
 /// * **ExecuteMsg::Leave** Unstake the provided xASTRO tokens for ASTRO
+/// * **ExecuteMsg::Absorb** Fold ASTRO transferred directly to the contract into the exchange rate
+/// * **ExecuteMsg::InjectRewards** Escrow whitelisted coins for xASTRO holders
+/// * **ExecuteMsg::ClaimRewards** Claim the sender's share of injected rewards
+/// * **ExecuteMsg::SweepRewards** Send rewards unclaimed within the claim period to the receiver
+/// * **ExecuteMsg::UpdateRewardsConfig** Update reward injectors and reward denoms
+/// * **ExecuteMsg::ProposeNewOwner** Create a request to change contract ownership
+/// * **ExecuteMsg::DropOwnershipProposal** Remove a request to change contract ownership
+/// * **ExecuteMsg::ClaimOwnership** Claim contract ownership
+/// * **ExecuteMsg::UpdateOwnershipTimelock** Set the delay before a proposed owner can claim ownership
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -118,7 +167,7 @@ pub fn execute(
         ExecuteMsg::Enter { receiver } => {
             // xASTRO is minted to the receiver if provided or to the sender.
             let recipient = receiver.unwrap_or_else(|| info.sender.to_string());
-            execute_enter(deps, env, info).map(|(resp, minted_coins)| {
+            execute_enter(deps, env, info, &recipient).map(|(resp, minted_coins)| {
                 resp.add_message(BankMsg::Send {
                     to_address: recipient.clone(),
                     amount: vec![minted_coins],
@@ -129,7 +178,7 @@ pub fn execute(
         ExecuteMsg::EnterWithHook {
             contract_address,
             msg,
-        } => execute_enter(deps, env, info).map(|(resp, minted_coins)| {
+        } => execute_enter(deps, env, info, &contract_address).map(|(resp, minted_coins)| {
             resp.add_message(WasmMsg::Execute {
                 contract_addr: contract_address.clone(),
                 msg,
@@ -145,6 +194,64 @@ pub fn execute(
             let recipient = receiver.unwrap_or_else(|| info.sender.to_string());
             execute_leave(deps, env, info, recipient)
         }
+        ExecuteMsg::Absorb {} => execute_absorb(deps, env),
+        ExecuteMsg::InjectRewards {} => execute_inject_rewards(deps, env, info),
+        ExecuteMsg::ClaimRewards { receiver, limit } => {
+            execute_claim_rewards(deps, env, info, receiver, limit)
+        }
+        ExecuteMsg::SweepRewards { receiver, limit } => {
+            execute_sweep_rewards(deps, env, info, receiver, limit)
+        }
+        ExecuteMsg::UpdateRewardsConfig {
+            add_injectors,
+            remove_injectors,
+            add_denoms,
+            remove_denoms,
+        } => update_rewards_config(
+            deps,
+            info,
+            add_injectors,
+            remove_injectors,
+            add_denoms,
+            remove_denoms,
+        ),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                rewards_config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(Into::into)
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, rewards_config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(Into::into)
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                REWARDS_CONFIG
+                    .update::<_, StdError>(deps.storage, |mut rewards_config| {
+                        rewards_config.owner = new_owner;
+                        Ok(rewards_config)
+                    })
+                    .map(|_| ())
+            })
+            .map_err(Into::into)
+        }
+        ExecuteMsg::UpdateOwnershipTimelock { timelock } => {
+            let rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+
+            update_ownership_timelock(deps, info, env, timelock, rewards_config.owner)
+                .map_err(Into::into)
+        }
     }
 }
 
@@ -239,19 +346,28 @@ fn execute_enter(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    recipient: &str,
 ) -> Result<(Response, Coin), ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     // Ensure that the correct denom is sent. Sending zero tokens is prohibited on chain level
     let amount = must_pay(&info, &config.astro_denom)?;
 
+    // The recipient had no xASTRO at any earlier injection thus its first claim can start from this block
+    let recipient = deps.api.addr_validate(recipient)?;
+    if !REWARD_CHECKPOINTS.has(deps.storage, &recipient)
+        && deps
+            .querier
+            .query_balance(&recipient, &config.xastro_denom)?
+            .amount
+            .is_zero()
+    {
+        REWARD_CHECKPOINTS.save(deps.storage, &recipient, &env.block.time.seconds())?;
+    }
+
     // Get the current deposits and shares held in the contract.
-    // Amount sent along with the message already included. Subtract it from the total deposit
-    let total_deposit = deps
-        .querier
-        .query_balance(&env.contract.address, &config.astro_denom)?
-        .amount
-        - amount;
+    // ASTRO transferred directly to the contract is accounted only as it is released after absorption
+    let total_deposit = release_absorbed(deps.storage, env.block.time.seconds())?;
     let total_shares = deps.querier.query_supply(&config.xastro_denom)?.amount;
 
     let mut messages: Vec<CosmosMsg> = vec![];
@@ -282,6 +398,8 @@ fn execute_enter(
         return Err(ContractError::StakeAmountTooSmall {});
     }
 
+    TOTAL_DEPOSIT.save(deps.storage, &(total_deposit + amount))?;
+
     let minted_coins = coin(mint_amount.u128(), config.xastro_denom);
 
     // Mint new xASTRO tokens to the staking contract
@@ -327,15 +445,13 @@ fn execute_leave(
     let amount = must_pay(&info, &config.xastro_denom)?;
 
     // Get the current deposits and shares held in the contract
-    let total_deposit = deps
-        .querier
-        .query_balance(&env.contract.address, &config.astro_denom)?
-        .amount;
+    let total_deposit = release_absorbed(deps.storage, env.block.time.seconds())?;
     let total_shares = deps.querier.query_supply(&config.xastro_denom)?.amount;
 
     // Calculate the amount of ASTRO to return based on the ratios of
     // deposit and shares
     let return_amount = amount.multiply_ratio(total_deposit, total_shares);
+    TOTAL_DEPOSIT.save(deps.storage, &(total_deposit - return_amount))?;
 
     let messages: Vec<CosmosMsg> = vec![
         // Burn the received xASTRO tokens
@@ -377,6 +493,375 @@ fn execute_leave(
         ]))
 }
 
+/// Folds ASTRO transferred directly to the contract into the xASTRO exchange rate.
+/// Absorbed ASTRO is released linearly over the absorb interval so that Enter -> Absorb -> Leave
+/// in one block doesn't capture the donation at the expense of long-term stakers.
+fn execute_absorb(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let block_ts = env.block.time.seconds();
+    let total_deposit = release_absorbed(deps.storage, block_ts)?;
+    let mut absorb_config = ABSORB_CONFIG.load(deps.storage)?;
+
+    let next_absorb_ts = absorb_config.last_absorb_ts + absorb_config.interval;
+    ensure!(
+        block_ts >= next_absorb_ts,
+        ContractError::AbsorbCooldown { next_absorb_ts }
+    );
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.astro_denom)?
+        .amount;
+    let absorbed_amount = balance
+        .saturating_sub(total_deposit)
+        .saturating_sub(absorb_config.releasing_amount);
+    let total_shares = deps.querier.query_supply(&config.xastro_denom)?.amount;
+
+    // Donations can't be absorbed while there are no stakers
+    ensure!(
+        !absorbed_amount.is_zero() && !total_shares.is_zero(),
+        ContractError::NothingToAbsorb {}
+    );
+
+    // ASTRO which is not released yet is merged into the new release period
+    absorb_config.releasing_amount += absorbed_amount;
+    absorb_config.released_ts = block_ts;
+    absorb_config.release_end_ts = block_ts + absorb_config.interval;
+    absorb_config.last_absorb_ts = block_ts;
+    ABSORB_CONFIG.save(deps.storage, &absorb_config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "absorb"),
+        attr("absorbed_amount", absorbed_amount),
+        attr("releasing_amount", absorb_config.releasing_amount),
+        attr("release_end_ts", absorb_config.release_end_ts.to_string()),
+        attr("total_deposit", total_deposit),
+        attr("total_shares", total_shares),
+    ]))
+}
+
+/// Moves absorbed ASTRO released by the given timestamp into [`TOTAL_DEPOSIT`].
+/// Returns the updated total deposit.
+fn release_absorbed(storage: &mut dyn Storage, block_ts: u64) -> StdResult<Uint128> {
+    let mut absorb_config = ABSORB_CONFIG.load(storage)?;
+    let mut total_deposit = TOTAL_DEPOSIT.load(storage)?;
+
+    let released_amount = absorb_config.released_amount(block_ts);
+    if !released_amount.is_zero() {
+        total_deposit += released_amount;
+        absorb_config.releasing_amount -= released_amount;
+        absorb_config.released_ts = block_ts;
+        TOTAL_DEPOSIT.save(storage, &total_deposit)?;
+        ABSORB_CONFIG.save(storage, &absorb_config)?;
+    }
+
+    Ok(total_deposit)
+}
+
+/// Escrows whitelisted coins sent by a whitelisted injector. Injections within one block are merged.
+/// Rewards are split according to xASTRO balances at the beginning of the block
+/// so that staking in the same block as the injection doesn't give a share.
+fn execute_inject_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+    ensure!(
+        rewards_config.injectors.contains(&info.sender),
+        ContractError::Unauthorized {}
+    );
+    ensure!(!info.funds.is_empty(), PaymentError::NoFunds {});
+    if let Some(coin) = info
+        .funds
+        .iter()
+        .find(|coin| !rewards_config.reward_denoms.contains(&coin.denom))
+    {
+        return Err(ContractError::InvalidRewardDenom {
+            denom: coin.denom.clone(),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let total_shares = deps.querier.query_supply(&config.xastro_denom)?.amount;
+    ensure!(!total_shares.is_zero(), ContractError::NoStakers {});
+
+    let block_ts = env.block.time.seconds();
+    let mut injected = REWARD_INJECTIONS
+        .may_load(deps.storage, block_ts)?
+        .unwrap_or_default();
+    for coin in &info.funds {
+        add_coin(&mut injected, coin.clone());
+    }
+    REWARD_INJECTIONS.save(deps.storage, block_ts, &injected)?;
+
+    let mut remainders = REWARD_REMAINDERS
+        .may_load(deps.storage, block_ts)?
+        .unwrap_or_default();
+    for coin in &info.funds {
+        add_coin(&mut remainders, coin.clone());
+    }
+    REWARD_REMAINDERS.save(deps.storage, block_ts, &remainders)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "inject_rewards"),
+        attr("injector", info.sender),
+        attr("rewards", coins_to_string(&info.funds)),
+    ]))
+}
+
+/// Sends the sender's share of rewards injected since their last claim.
+/// At most `limit` injections are processed at once thus claiming may require several transactions.
+fn execute_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+
+    let injection_rewards = collect_rewards(deps.as_ref(), &env, &info.sender, limit)?;
+    let (last_claimed_ts, _) = injection_rewards
+        .last()
+        .ok_or(ContractError::NothingToClaim {})?;
+    REWARD_CHECKPOINTS.save(deps.storage, &info.sender, last_claimed_ts)?;
+
+    let mut rewards = vec![];
+    for (injection_ts, injection_rewards) in &injection_rewards {
+        if injection_rewards.is_empty() {
+            continue;
+        }
+
+        let mut remainders = REWARD_REMAINDERS.load(deps.storage, *injection_ts)?;
+        for reward in injection_rewards {
+            sub_coin(&mut remainders, reward)?;
+            add_coin(&mut rewards, reward.clone());
+        }
+        REWARD_REMAINDERS.save(deps.storage, *injection_ts, &remainders)?;
+    }
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "claim_rewards"),
+        attr("receiver", &receiver),
+        attr("rewards", coins_to_string(&rewards)),
+        attr("last_claimed_ts", last_claimed_ts.to_string()),
+    ]);
+    if !rewards.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: rewards,
+        });
+    }
+
+    Ok(response)
+}
+
+/// Sends rewards of injections older than [`REWARD_CLAIM_PERIOD`] which weren't claimed to the receiver.
+/// Swept injections are removed thus they can't be claimed anymore.
+fn execute_sweep_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == rewards_config.owner,
+        ContractError::Unauthorized {}
+    );
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender);
+    let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+
+    let expired = REWARD_REMAINDERS
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(
+                env.block.time.seconds().saturating_sub(REWARD_CLAIM_PERIOD),
+            )),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    ensure!(!expired.is_empty(), ContractError::NothingToSweep {});
+
+    let mut swept = vec![];
+    for (injection_ts, remainders) in expired {
+        REWARD_INJECTIONS.remove(deps.storage, injection_ts);
+        REWARD_REMAINDERS.remove(deps.storage, injection_ts);
+        for remainder in remainders {
+            if !remainder.amount.is_zero() {
+                add_coin(&mut swept, remainder);
+            }
+        }
+    }
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "sweep_rewards"),
+        attr("receiver", &receiver),
+        attr("rewards", coins_to_string(&swept)),
+    ]);
+    if !swept.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: swept,
+        });
+    }
+
+    Ok(response)
+}
+
+/// Updates the lists of reward injectors and reward denoms.
+/// ASTRO and xASTRO can't be rewards since ASTRO backs xASTRO and is distributed via [`ExecuteMsg::Absorb`].
+fn update_rewards_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    add_injectors: Option<Vec<String>>,
+    remove_injectors: Option<Vec<String>>,
+    add_denoms: Option<Vec<String>>,
+    remove_denoms: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let mut rewards_config = REWARDS_CONFIG.load(deps.storage)?;
+    ensure!(
+        info.sender == rewards_config.owner,
+        ContractError::Unauthorized {}
+    );
+
+    let mut attrs = vec![attr("action", "update_rewards_config")];
+
+    if let Some(remove_injectors) = remove_injectors {
+        rewards_config
+            .injectors
+            .retain(|injector| !remove_injectors.contains(&injector.to_string()));
+        attrs.push(attr("removed_injectors", remove_injectors.join(",")));
+    }
+
+    if let Some(add_injectors) = add_injectors {
+        for injector in &add_injectors {
+            let injector = deps.api.addr_validate(injector)?;
+            if !rewards_config.injectors.contains(&injector) {
+                rewards_config.injectors.push(injector);
+            }
+        }
+        attrs.push(attr("added_injectors", add_injectors.join(",")));
+    }
+
+    if let Some(remove_denoms) = remove_denoms {
+        rewards_config
+            .reward_denoms
+            .retain(|denom| !remove_denoms.contains(denom));
+        attrs.push(attr("removed_denoms", remove_denoms.join(",")));
+    }
+
+    if let Some(add_denoms) = add_denoms {
+        let config = CONFIG.load(deps.storage)?;
+        for denom in &add_denoms {
+            validate_native_denom(denom)?;
+            ensure!(
+                *denom != config.astro_denom && *denom != config.xastro_denom,
+                ContractError::InvalidRewardDenom {
+                    denom: denom.clone()
+                }
+            );
+            if !rewards_config.reward_denoms.contains(denom) {
+                rewards_config.reward_denoms.push(denom.clone());
+            }
+        }
+        attrs.push(attr("added_denoms", add_denoms.join(",")));
+    }
+
+    REWARDS_CONFIG.save(deps.storage, &rewards_config)?;
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Calculates the user's share of rewards injected after their last claim.
+/// Injections of the current block are skipped since the tracker can't return balances
+/// at the beginning of the block until it ends.
+/// Returns the user's rewards of each processed injection by injection timestamp.
+fn collect_rewards(
+    deps: Deps,
+    env: &Env,
+    user: &Addr,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Vec<Coin>)>> {
+    let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let tracker_addr = TRACKER_DATA.load(deps.storage)?.tracker_addr;
+    let checkpoint = REWARD_CHECKPOINTS.may_load(deps.storage, user)?;
+
+    let injections = REWARD_INJECTIONS
+        .range(
+            deps.storage,
+            checkpoint.map(Bound::exclusive),
+            Some(Bound::exclusive(env.block.time.seconds())),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut rewards = vec![];
+    for (injection_ts, injected) in injections {
+        let mut injection_rewards = vec![];
+        let balance: Uint128 = deps.querier.query_wasm_smart(
+            &tracker_addr,
+            &astroport_v4::tokenfactory_tracker::QueryMsg::BalanceAt {
+                address: user.to_string(),
+                timestamp: Some(injection_ts),
+            },
+        )?;
+        if balance.is_zero() {
+            rewards.push((injection_ts, injection_rewards));
+            continue;
+        }
+
+        let total_shares: Uint128 = deps.querier.query_wasm_smart(
+            &tracker_addr,
+            &astroport_v4::tokenfactory_tracker::QueryMsg::TotalSupplyAt {
+                timestamp: Some(injection_ts),
+            },
+        )?;
+
+        for reward in injected {
+            let amount = reward.amount.multiply_ratio(balance, total_shares);
+            if !amount.is_zero() {
+                add_coin(&mut injection_rewards, coin(amount.u128(), &reward.denom));
+            }
+        }
+        rewards.push((injection_ts, injection_rewards));
+    }
+
+    Ok(rewards)
+}
+
+/// Adds the coin to the list keeping the list sorted by denom.
+fn add_coin(coins: &mut Vec<Coin>, coin: Coin) {
+    match coins.binary_search_by(|existing| existing.denom.cmp(&coin.denom)) {
+        Ok(ind) => coins[ind].amount += coin.amount,
+        Err(ind) => coins.insert(ind, coin),
+    }
+}
+
+/// Subtracts the coin from the matching coin of the list.
+fn sub_coin(coins: &mut [Coin], coin: &Coin) -> StdResult<()> {
+    let existing = coins
+        .iter_mut()
+        .find(|existing| existing.denom == coin.denom)
+        .ok_or_else(|| StdError::generic_err(format!("{} not found", coin.denom)))?;
+    existing.amount = existing.amount.checked_sub(coin.amount)?;
+
+    Ok(())
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// * **QueryMsg::Config {}** Returns the staking contract configuration
@@ -390,6 +875,14 @@ fn execute_leave(
 /// * **QueryMsg::BalanceAt { address, timestamp }** Returns the xASTRO balance of the given address at the given timestamp
 ///
 /// * **QueryMsg::TotalSupplyAt { timestamp }** Returns xASTRO total supply at the given timestamp
+///
+/// * **QueryMsg::AbsorbInfo {}** Returns the state of donated ASTRO absorption
+///
+/// * **QueryMsg::RewardsConfig {}** Returns the reward injection settings
+///
+/// * **QueryMsg::PendingRewards { address, limit }** Returns rewards claimable by the given address
+///
+/// * **QueryMsg::RewardInjections { start_after, limit }** Returns injected rewards by injection timestamp
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -401,12 +894,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&total_supply)
         }
         QueryMsg::TotalDeposit {} => {
-            let config = CONFIG.load(deps.storage)?;
+            let absorb_config = ABSORB_CONFIG.load(deps.storage)?;
+            let total_deposit = TOTAL_DEPOSIT.load(deps.storage)?
+                + absorb_config.released_amount(env.block.time.seconds());
 
-            let total_deposit = deps
-                .querier
-                .query_balance(env.contract.address, config.astro_denom)?
-                .amount;
             to_json_binary(&total_deposit)
         }
         QueryMsg::TrackerConfig {} => to_json_binary(&TRACKER_DATA.load(deps.storage)?),
@@ -440,5 +931,52 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 
             to_json_binary(&amount)
         }
+        QueryMsg::AbsorbInfo {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let absorb_config = ABSORB_CONFIG.load(deps.storage)?;
+            let released_amount = absorb_config.released_amount(env.block.time.seconds());
+            let total_deposit = TOTAL_DEPOSIT.load(deps.storage)? + released_amount;
+            let releasing_amount = absorb_config.releasing_amount - released_amount;
+            let balance = deps
+                .querier
+                .query_balance(env.contract.address, config.astro_denom)?
+                .amount;
+
+            to_json_binary(&AbsorbInfoResponse {
+                interval: absorb_config.interval,
+                last_absorb_ts: absorb_config.last_absorb_ts,
+                next_absorb_ts: absorb_config.last_absorb_ts + absorb_config.interval,
+                pending_amount: balance
+                    .saturating_sub(total_deposit)
+                    .saturating_sub(releasing_amount),
+                releasing_amount,
+                release_end_ts: absorb_config.release_end_ts,
+            })
+        }
+        QueryMsg::RewardsConfig {} => to_json_binary(&REWARDS_CONFIG.load(deps.storage)?),
+        QueryMsg::PendingRewards { address, limit } => {
+            let address = deps.api.addr_validate(&address)?;
+            let mut rewards = vec![];
+            for (_, injection_rewards) in collect_rewards(deps, &env, &address, limit)? {
+                for reward in injection_rewards {
+                    add_coin(&mut rewards, reward);
+                }
+            }
+            to_json_binary(&rewards)
+        }
+        QueryMsg::RewardInjections { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+            let injections = REWARD_INJECTIONS
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+
+            to_json_binary(&injections)
+        }
     }
 }
@@ -22,6 +22,27 @@ pub enum ContractError {
     #[error("Insufficient amount of Stake")]
     StakeAmountTooSmall {},
 
+    #[error("Absorb is not available until {next_absorb_ts}")]
+    AbsorbCooldown { next_absorb_ts: u64 },
+
+    #[error("Nothing to absorb")]
+    NothingToAbsorb {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("{denom} can't be injected as a reward")]
+    InvalidRewardDenom { denom: String },
+
+    #[error("Rewards can't be injected while there are no stakers")]
+    NoStakers {},
+
+    #[error("No rewards to claim")]
+    NothingToClaim {},
+
+    #[error("No unclaimed rewards older than the claim period")]
+    NothingToSweep {},
+
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
 
@@ -1,25 +1,63 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, Response};
+use cosmwasm_std::{DepsMut, Env, Response, Uint128};
 use cw2::{get_contract_version, set_contract_version};
 
-use crate::contract::{CONTRACT_NAME, CONTRACT_VERSION};
-use astroport::pair::MigrateMsg;
+use astroport::staking::{
+    validate_absorb_interval, AbsorbConfig, MigrateMsg, RewardsConfig, DEFAULT_ABSORB_INTERVAL,
+};
 
+use crate::contract::{CONTRACT_NAME, CONTRACT_VERSION};
 use crate::error::ContractError;
+use crate::state::{ABSORB_CONFIG, CONFIG, REWARDS_CONFIG, TOTAL_DEPOSIT, TRACKER_DATA};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "astroport-staking" => match contract_version.version.as_ref() {
-            "2.0.0" | "2.1.0" => {}
+            "2.0.0" | "2.1.0" | "2.2.0" | "2.3.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    // Previous versions absorbed donations implicitly thus the whole balance is already accounted
+    let config = CONFIG.load(deps.storage)?;
+    let total_deposit = deps
+        .querier
+        .query_balance(&env.contract.address, config.astro_denom)?
+        .amount;
+    TOTAL_DEPOSIT.save(deps.storage, &total_deposit)?;
+
+    let absorb_interval = msg.absorb_interval.unwrap_or(DEFAULT_ABSORB_INTERVAL);
+    validate_absorb_interval(absorb_interval)?;
+    ABSORB_CONFIG.save(
+        deps.storage,
+        &AbsorbConfig {
+            interval: absorb_interval,
+            last_absorb_ts: env.block.time.seconds(),
+            releasing_amount: Uint128::zero(),
+            released_ts: env.block.time.seconds(),
+            release_end_ts: env.block.time.seconds(),
+        },
+    )?;
+
+    // Reward injection is managed by the tracking admin unless another owner is set
+    let owner = match msg.owner {
+        Some(owner) => owner,
+        None => TRACKER_DATA.load(deps.storage)?.admin,
+    };
+    REWARDS_CONFIG.save(
+        deps.storage,
+        &RewardsConfig {
+            owner: deps.api.addr_validate(&owner)?,
+            injectors: vec![],
+            reward_denoms: vec![],
+        },
+    )?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
@@ -1,9 +1,33 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_storage_plus::{Item, Map};
 
-use astroport::staking::{Config, TrackerData};
+use astroport::common::OwnershipProposal;
+use astroport::staking::{AbsorbConfig, Config, RewardsConfig, TrackerData};
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
 /// Stores the tracker contract instantiate data at the given key
 pub const TRACKER_DATA: Item<TrackerData> = Item::new("tracker_data");
+
+/// Total ASTRO backing xASTRO. It excludes ASTRO transferred directly to the contract until it is absorbed
+pub const TOTAL_DEPOSIT: Item<Uint128> = Item::new("total_deposit");
+
+/// Stores the donated ASTRO absorption state
+pub const ABSORB_CONFIG: Item<AbsorbConfig> = Item::new("absorb_config");
+
+/// Stores the reward injection settings
+pub const REWARDS_CONFIG: Item<RewardsConfig> = Item::new("rewards_config");
+
+/// Contains a proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Rewards escrowed for xASTRO holders. key: injection timestamp
+pub const REWARD_INJECTIONS: Map<u64, Vec<Coin>> = Map::new("reward_injections");
+
+/// Rewards of each injection which are not claimed yet. key: injection timestamp
+pub const REWARD_REMAINDERS: Map<u64, Vec<Coin>> = Map::new("reward_remainders");
+
+/// Timestamp of the last injection claimed by a user.
+/// Set at the first stake of an address without xASTRO thus it doesn't page through older injections
+pub const REWARD_CHECKPOINTS: Map<&Addr, u64> = Map::new("reward_checkpoints");
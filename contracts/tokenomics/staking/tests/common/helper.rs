@@ -12,7 +12,9 @@ use cw_multi_test::{
     Executor, FailingModule, StakeKeeper, WasmKeeper, TOKEN_FACTORY_MODULE,
 };
 
-use astroport::staking::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, TrackerData};
+use astroport::staking::{
+    AbsorbInfoResponse, Config, ExecuteMsg, InstantiateMsg, QueryMsg, TrackerData,
+};
 
 use crate::common::stargate::StargateKeeper;
 
@@ -82,6 +84,9 @@ impl Helper {
             tracking_admin: owner.to_string(),
             tracking_code_id: tracker_code_id,
             token_factory_addr: TOKEN_FACTORY_MODULE.to_string(),
+            absorb_interval: None,
+            owner: None,
+            ownership_timelock: None,
         };
         let staking = app
             .instantiate_contract(
@@ -158,6 +163,85 @@ impl Helper {
         )
     }
 
+    pub fn absorb(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.staking.clone(),
+            &ExecuteMsg::Absorb {},
+            &[],
+        )
+    }
+
+    pub fn query_absorb_info(&self) -> StdResult<AbsorbInfoResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.staking, &QueryMsg::AbsorbInfo {})
+    }
+
+    pub fn inject_rewards(&mut self, sender: &Addr, funds: &[Coin]) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.staking.clone(),
+            &ExecuteMsg::InjectRewards {},
+            funds,
+        )
+    }
+
+    pub fn claim_rewards(&mut self, sender: &Addr, limit: Option<u32>) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.staking.clone(),
+            &ExecuteMsg::ClaimRewards {
+                receiver: None,
+                limit,
+            },
+            &[],
+        )
+    }
+
+    pub fn sweep_rewards(
+        &mut self,
+        sender: &Addr,
+        receiver: Option<String>,
+        limit: Option<u32>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.staking.clone(),
+            &ExecuteMsg::SweepRewards { receiver, limit },
+            &[],
+        )
+    }
+
+    pub fn update_rewards_config(
+        &mut self,
+        sender: &Addr,
+        add_injectors: Option<Vec<String>>,
+        add_denoms: Option<Vec<String>>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.staking.clone(),
+            &ExecuteMsg::UpdateRewardsConfig {
+                add_injectors,
+                remove_injectors: None,
+                add_denoms,
+                remove_denoms: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn query_pending_rewards(&self, address: &Addr) -> StdResult<Vec<Coin>> {
+        self.app.wrap().query_wasm_smart(
+            &self.staking,
+            &QueryMsg::PendingRewards {
+                address: address.to_string(),
+                limit: None,
+            },
+        )
+    }
+
     pub fn query_balance(&self, sender: &Addr, denom: &str) -> StdResult<Uint128> {
         self.app
             .wrap()
@@ -11,7 +11,10 @@ use cw_multi_test::{Contract, ContractWrapper, Executor, TOKEN_FACTORY_MODULE};
 use cw_utils::PaymentError;
 use itertools::Itertools;
 
-use astroport::staking::{Config, ExecuteMsg, QueryMsg, StakingResponse, TrackerData};
+use astroport::staking::{
+    Config, ExecuteMsg, QueryMsg, StakingResponse, TrackerData, DEFAULT_ABSORB_INTERVAL,
+    REWARD_CLAIM_PERIOD,
+};
 use astroport_staking::error::ContractError;
 
 use crate::common::helper::{Helper, ASTRO_DENOM};
@@ -82,6 +85,16 @@ fn check_deflate_liquidity() {
         )
         .unwrap();
 
+    // Donated ASTRO doesn't affect the exchange rate until it is absorbed
+    let resp_data = helper.stake(&victim, 5).unwrap().data.unwrap();
+    let staking_resp: StakingResponse = from_json(&resp_data).unwrap();
+    assert_eq!(staking_resp.xastro_amount.u128(), 5);
+
+    helper.next_block(DEFAULT_ABSORB_INTERVAL);
+    helper.absorb(&attacker).unwrap();
+    // Absorbed ASTRO is released over the absorb interval
+    helper.next_block(DEFAULT_ABSORB_INTERVAL);
+
     let err = helper.stake(&victim, 5).unwrap_err();
     assert_eq!(
         err.downcast::<ContractError>().unwrap(),
@@ -612,3 +625,295 @@ fn test_hooks() {
         .stake_with_hook(&alice, 10000, absorber.to_string(), &())
         .unwrap_err();
 }
+
+#[test]
+fn test_absorb() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner).unwrap();
+    let start_ts = helper.app.block_info().time.seconds();
+
+    let alice = Addr::unchecked("alice");
+    helper.give_astro(10000, &alice);
+    helper.stake(&alice, 2000).unwrap();
+
+    // Absorb is rate-limited
+    let err = helper.absorb(&alice).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::AbsorbCooldown {
+            next_absorb_ts: start_ts + DEFAULT_ABSORB_INTERVAL
+        }
+    );
+
+    helper.next_block(DEFAULT_ABSORB_INTERVAL);
+    let err = helper.absorb(&alice).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToAbsorb {}
+    );
+
+    // Donate ASTRO directly to the staking contract
+    let staking = helper.staking.clone();
+    helper
+        .app
+        .send_tokens(owner.clone(), staking.clone(), &coins(2000, ASTRO_DENOM))
+        .unwrap();
+
+    let absorb_info = helper.query_absorb_info().unwrap();
+    assert_eq!(absorb_info.pending_amount.u128(), 2000);
+    assert_eq!(absorb_info.last_absorb_ts, start_ts);
+    let total_deposit: Uint128 = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&staking, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(total_deposit.u128(), 2000);
+
+    // Anyone can absorb. Entering right before the absorption and leaving right after it yields nothing
+    let random = Addr::unchecked("random");
+    helper.give_astro(2000, &random);
+    helper.stake(&random, 2000).unwrap();
+    let resp = helper.absorb(&random).unwrap();
+    let absorbed_amount = resp.events.iter().find_map(|event| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "absorbed_amount")
+            .map(|attr| attr.value.clone())
+    });
+    assert_eq!(absorbed_amount.as_deref(), Some("2000"));
+    let resp_data = helper.unstake(&random, 2000).unwrap().data.unwrap();
+    let staking_resp: StakingResponse = from_json(&resp_data).unwrap();
+    assert_eq!(staking_resp.astro_amount.u128(), 2000);
+
+    let block_ts = helper.app.block_info().time.seconds();
+    let absorb_info = helper.query_absorb_info().unwrap();
+    assert_eq!(absorb_info.pending_amount.u128(), 0);
+    assert_eq!(absorb_info.releasing_amount.u128(), 2000);
+    assert_eq!(absorb_info.last_absorb_ts, block_ts);
+    assert_eq!(
+        absorb_info.next_absorb_ts,
+        block_ts + DEFAULT_ABSORB_INTERVAL
+    );
+    assert_eq!(
+        absorb_info.release_end_ts,
+        block_ts + DEFAULT_ABSORB_INTERVAL
+    );
+
+    // Absorbed ASTRO is released linearly
+    helper.next_block(DEFAULT_ABSORB_INTERVAL / 2);
+    let total_deposit: Uint128 = helper
+        .app
+        .wrap()
+        .query_wasm_smart(&staking, &QueryMsg::TotalDeposit {})
+        .unwrap();
+    assert_eq!(total_deposit.u128(), 3000);
+    let absorb_info = helper.query_absorb_info().unwrap();
+    assert_eq!(absorb_info.pending_amount.u128(), 0);
+    assert_eq!(absorb_info.releasing_amount.u128(), 1000);
+
+    helper.next_block(DEFAULT_ABSORB_INTERVAL / 2);
+
+    // Alice owns 1000 xASTRO out of 2000 thus receives half of the donation
+    let resp_data = helper.unstake(&alice, 1000).unwrap().data.unwrap();
+    let staking_resp: StakingResponse = from_json(&resp_data).unwrap();
+    assert_eq!(staking_resp.astro_amount.u128(), 2000);
+
+    let absorb_info = helper.query_absorb_info().unwrap();
+    assert_eq!(absorb_info.releasing_amount.u128(), 0);
+}
+
+#[test]
+fn test_reward_injection() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner).unwrap();
+
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let maker = Addr::unchecked("maker");
+    helper.give_astro(10000, &alice);
+    helper.give_astro(10000, &bob);
+    helper.give_astro(10000, &carol);
+
+    // 1000 xASTRO is locked in the contract, alice and bob receive 1000 xASTRO each
+    helper.stake(&alice, 2000).unwrap();
+    helper.stake(&bob, 1000).unwrap();
+    helper.next_block(10);
+
+    let rewards = coins(3000, "uusdc");
+    helper.mint_coin(&maker, coin(6000, "uusdc"));
+    helper.mint_coin(&maker, coin(1000, "uatom"));
+
+    let err = helper.inject_rewards(&maker, &rewards).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = helper
+        .update_rewards_config(&alice, Some(vec![maker.to_string()]), None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let err = helper
+        .update_rewards_config(&owner, None, Some(vec![ASTRO_DENOM.to_string()]))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidRewardDenom {
+            denom: ASTRO_DENOM.to_string()
+        }
+    );
+
+    helper
+        .update_rewards_config(
+            &owner,
+            Some(vec![maker.to_string()]),
+            Some(vec!["uusdc".to_string()]),
+        )
+        .unwrap();
+
+    let err = helper
+        .inject_rewards(&maker, &coins(1000, "uatom"))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidRewardDenom {
+            denom: "uatom".to_string()
+        }
+    );
+
+    helper.inject_rewards(&maker, &rewards).unwrap();
+
+    // Rewards injected in the current block can't be claimed yet
+    let err = helper.claim_rewards(&alice, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToClaim {}
+    );
+
+    // Staking in the same block as the injection doesn't give a share
+    helper.stake(&carol, 1000).unwrap();
+    helper.next_block(10);
+
+    assert_eq!(
+        helper.query_pending_rewards(&alice).unwrap(),
+        coins(1000, "uusdc")
+    );
+    assert_eq!(helper.query_pending_rewards(&carol).unwrap(), vec![]);
+
+    helper.claim_rewards(&alice, None).unwrap();
+    assert_eq!(helper.query_balance(&alice, "uusdc").unwrap().u128(), 1000);
+    let err = helper.claim_rewards(&alice, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToClaim {}
+    );
+
+    // Bob leaves but is still able to claim rewards injected while he was staking
+    helper.unstake(&bob, 1000).unwrap();
+    helper.next_block(10);
+    helper.inject_rewards(&maker, &rewards).unwrap();
+    helper.next_block(10);
+
+    helper.claim_rewards(&bob, None).unwrap();
+    assert_eq!(helper.query_balance(&bob, "uusdc").unwrap().u128(), 1000);
+    helper.claim_rewards(&carol, None).unwrap();
+    assert_eq!(helper.query_balance(&carol, "uusdc").unwrap().u128(), 1000);
+
+    // Claims are limited by the number of processed injections
+    assert_eq!(
+        helper.query_pending_rewards(&alice).unwrap(),
+        coins(1000, "uusdc")
+    );
+    helper.claim_rewards(&alice, Some(1)).unwrap();
+    assert_eq!(helper.query_balance(&alice, "uusdc").unwrap().u128(), 2000);
+    assert_eq!(helper.query_pending_rewards(&alice).unwrap(), vec![]);
+}
+
+#[test]
+fn test_reward_checkpoint_and_sweep() {
+    let owner = Addr::unchecked("owner");
+    let mut helper = Helper::new(&owner).unwrap();
+
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let maker = Addr::unchecked("maker");
+    helper.give_astro(10000, &alice);
+    helper.give_astro(10000, &bob);
+    helper.mint_coin(&maker, coin(6000, "uusdc"));
+
+    helper
+        .update_rewards_config(
+            &owner,
+            Some(vec![maker.to_string()]),
+            Some(vec!["uusdc".to_string()]),
+        )
+        .unwrap();
+
+    // 1000 xASTRO is locked in the contract, alice receives 1000 xASTRO
+    helper.stake(&alice, 2000).unwrap();
+    helper.next_block(10);
+    helper
+        .inject_rewards(&maker, &coins(3000, "uusdc"))
+        .unwrap();
+    helper.next_block(10);
+    helper
+        .inject_rewards(&maker, &coins(3000, "uusdc"))
+        .unwrap();
+    helper.next_block(10);
+
+    // Bob's first stake starts his checkpoint thus earlier injections aren't processed on his claims
+    helper.stake(&bob, 1000).unwrap();
+    helper.next_block(10);
+    assert_eq!(helper.query_pending_rewards(&bob).unwrap(), vec![]);
+    let err = helper.claim_rewards(&bob, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToClaim {}
+    );
+
+    helper.claim_rewards(&alice, None).unwrap();
+    assert_eq!(helper.query_balance(&alice, "uusdc").unwrap().u128(), 3000);
+
+    // The share of the locked xASTRO can't be claimed by anyone
+    let err = helper.sweep_rewards(&owner, None, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToSweep {}
+    );
+
+    helper.next_block(REWARD_CLAIM_PERIOD);
+
+    let err = helper.sweep_rewards(&alice, None, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    let treasury = Addr::unchecked("treasury");
+    helper
+        .sweep_rewards(&owner, Some(treasury.to_string()), Some(1))
+        .unwrap();
+    assert_eq!(
+        helper.query_balance(&treasury, "uusdc").unwrap().u128(),
+        1500
+    );
+    helper
+        .sweep_rewards(&owner, Some(treasury.to_string()), None)
+        .unwrap();
+    assert_eq!(
+        helper.query_balance(&treasury, "uusdc").unwrap().u128(),
+        3000
+    );
+
+    let err = helper.sweep_rewards(&owner, None, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToSweep {}
+    );
+}
@@ -10,13 +10,16 @@ use astroport::asset::{addr_opt_validate, token_asset_info, AssetInfo, AssetInfo
 use astroport::astro_converter;
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::vesting::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy, QueryMsg,
-    VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo, VestingSchedule,
-    VestingSchedulePoint,
+    AccountMigration, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy,
+    QueryMsg, VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo,
+    VestingManagement, VestingSchedule, VestingSchedulePoint, ACCOUNT_MIGRATION_DISPUTE_PERIOD,
 };
 
 use crate::error::ContractError;
-use crate::state::{read_vesting_infos, Config, CONFIG, OWNERSHIP_PROPOSAL, VESTING_INFO};
+use crate::state::{
+    move_claim_history, read_claim_history, read_vesting_infos, record_claim, Config,
+    ACCOUNT_MIGRATIONS, CONFIG, OWNERSHIP_PROPOSAL, VESTING_INFO,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-vesting";
@@ -67,6 +70,18 @@ pub fn instantiate(
 ///
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
 ///
+/// * **ExecuteMsg::ProposeAccountMigration { new_address }** Requests to move the sender's vesting account
+/// to a new address after the dispute period.
+///
+/// * **ExecuteMsg::ApproveAccountMigration { account, new_address, proof }** Approves moving a compromised
+/// vesting account to a new address.
+///
+/// * **ExecuteMsg::CancelAccountMigration { account }** Cancels a pending account migration.
+///
+/// * **ExecuteMsg::ExecuteAccountMigration { account }** Moves the vesting account to the new address.
+///
+/// * **ExecuteMsg::Clawback { account }** Sends the unvested tokens of a managed vesting account to its treasury.
+///
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -124,6 +139,29 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::ProposeAccountMigration { new_address } => {
+            let new_address = deps.api.addr_validate(&new_address)?;
+            request_account_migration(deps, env, info.sender, new_address, None)
+        }
+        ExecuteMsg::ApproveAccountMigration {
+            account,
+            new_address,
+            proof,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            ensure!(info.sender == config.owner, ContractError::Unauthorized {});
+
+            let account = deps.api.addr_validate(&account)?;
+            let new_address = deps.api.addr_validate(&new_address)?;
+            request_account_migration(deps, env, account, new_address, Some(proof))
+        }
+        ExecuteMsg::CancelAccountMigration { account } => {
+            cancel_account_migration(deps, env, info, account)
+        }
+        ExecuteMsg::ExecuteAccountMigration { account } => {
+            execute_account_migration(deps, env, account)
+        }
+        ExecuteMsg::Clawback { account } => clawback(deps, env, info, account),
     }
 }
 
@@ -171,6 +209,16 @@ pub fn register_vesting_accounts(
 
         assert_vesting_schedules(&env, &account_address, &vesting_account.schedules)?;
 
+        let managed = vesting_account
+            .managed
+            .map(|params| {
+                StdResult::Ok(VestingManagement {
+                    manager: deps.api.addr_validate(&params.manager)?,
+                    treasury: deps.api.addr_validate(&params.treasury)?,
+                })
+            })
+            .transpose()?;
+
         for sch in &vesting_account.schedules {
             let amount = if let Some(end_point) = &sch.end_point {
                 end_point.amount
@@ -181,6 +229,15 @@ pub fn register_vesting_accounts(
         }
 
         if let Some(mut old_info) = VESTING_INFO.may_load(deps.storage, &account_address)? {
+            // Otherwise the manager could clawback tokens granted without management or vice versa
+            if old_info.managed != managed {
+                return Err(ContractError::VestingManagementMismatch(
+                    vesting_account.address,
+                ));
+            }
+            if old_info.schedules.len() + 1 > SCHEDULES_LIMIT {
+                prune_finished_schedules(env.block.time.seconds(), &mut old_info)?;
+            }
             if old_info.schedules.len() + 1 > SCHEDULES_LIMIT {
                 return Err(ContractError::ExceedSchedulesMaximumLimit(
                     vesting_account.address,
@@ -196,6 +253,7 @@ pub fn register_vesting_accounts(
             &VestingInfo {
                 schedules: vesting_account.schedules,
                 released_amount,
+                managed,
             },
         )?;
     }
@@ -236,6 +294,31 @@ fn assert_vesting_schedules(
     Ok(())
 }
 
+/// Drops schedules which are fully vested if all their tokens were already claimed.
+/// The amount of dropped schedules is deducted from the released amount thus the available amount stays the same.
+///
+/// * **current_time** timestamp at which schedules are checked.
+///
+/// * **vesting_info** vesting schedules of the recipient.
+fn prune_finished_schedules(current_time: u64, vesting_info: &mut VestingInfo) -> StdResult<()> {
+    let (finished, active): (Vec<_>, Vec<_>) = vesting_info
+        .schedules
+        .iter()
+        .cloned()
+        .partition(|sch| sch.end_point.unwrap_or(sch.start_point).time <= current_time);
+
+    let finished_amount = finished.iter().try_fold(Uint128::zero(), |acc, sch| {
+        acc.checked_add(sch.end_point.unwrap_or(sch.start_point).amount)
+    })?;
+
+    if !finished.is_empty() && vesting_info.released_amount >= finished_amount {
+        vesting_info.released_amount -= finished_amount;
+        vesting_info.schedules = active;
+    }
+
+    Ok(())
+}
+
 /// Claims vested tokens and transfers them to the vesting recipient.
 ///
 /// * **recipient** vesting recipient for which to claim tokens.
@@ -248,6 +331,12 @@ pub fn claim(
     recipient: Option<String>,
     amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    if ACCOUNT_MIGRATIONS.has(deps.storage, &info.sender) {
+        return Err(ContractError::AccountMigrationPending(
+            info.sender.to_string(),
+        ));
+    }
+
     let config = CONFIG.load(deps.storage)?;
     let mut vesting_info = VESTING_INFO.load(deps.storage, &info.sender)?;
 
@@ -272,6 +361,13 @@ pub fn claim(
 
         vesting_info.released_amount = vesting_info.released_amount.checked_add(claim_amount)?;
         VESTING_INFO.save(deps.storage, &info.sender, &vesting_info)?;
+
+        record_claim(
+            deps.storage,
+            &info.sender,
+            env.block.time.seconds(),
+            claim_amount,
+        )?;
     };
 
     Ok(response.add_attributes(vec![
@@ -417,6 +513,187 @@ fn withdraw_from_active_schedule(
     ]))
 }
 
+/// Sends the unvested tokens of a managed vesting account to its treasury.
+/// Every schedule is cut at the current block time: started schedules become fully vested
+/// with their currently unlocked amount while schedules that haven't started yet are dropped.
+/// Thus vested but unclaimed tokens stay claimable by the account.
+///
+/// * **account** managed vesting account to clawback from.
+fn clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account: String,
+) -> Result<Response, ContractError> {
+    let acc = deps.api.addr_validate(&account)?;
+    let mut vesting_info = VESTING_INFO.load(deps.storage, &acc)?;
+    let managed = vesting_info
+        .managed
+        .clone()
+        .ok_or_else(|| ContractError::AccountNotManaged(account.clone()))?;
+    ensure!(
+        info.sender == managed.manager,
+        ContractError::Unauthorized {}
+    );
+
+    let block_time = env.block.time.seconds();
+    let mut unvested_amount = Uint128::zero();
+    let mut schedules = vec![];
+
+    for sch in &vesting_info.schedules {
+        let total_amount = sch.end_point.unwrap_or(sch.start_point).amount;
+
+        if sch.start_point.time > block_time {
+            unvested_amount = unvested_amount.checked_add(total_amount)?;
+            continue;
+        }
+
+        let unlocked_amount = calc_schedule_unlocked_amount(sch, block_time)?;
+        unvested_amount =
+            unvested_amount.checked_add(total_amount.checked_sub(unlocked_amount)?)?;
+        schedules.push(VestingSchedule {
+            start_point: VestingSchedulePoint {
+                time: sch.start_point.time,
+                amount: unlocked_amount,
+            },
+            end_point: None,
+        });
+    }
+
+    if unvested_amount.is_zero() {
+        return Err(ContractError::NothingToClawback(account));
+    }
+
+    vesting_info.schedules = schedules;
+    VESTING_INFO.save(deps.storage, &acc, &vesting_info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let transfer_msg = config
+        .vesting_token
+        .with_balance(unvested_amount)
+        .into_msg(managed.treasury.clone())?;
+
+    Ok(Response::new().add_message(transfer_msg).add_attributes([
+        attr("action", "clawback"),
+        attr("account", account),
+        attr("amount", unvested_amount),
+        attr("treasury", managed.treasury),
+    ]))
+}
+
+/// Requests to move a vesting account to a new address.
+/// Claims from the account are paused until the migration is executed or cancelled.
+///
+/// * **proof** reference to the proof of the key compromise. Set if the owner approves the migration.
+/// The owner's approval overrides the migration requested by the account itself.
+fn request_account_migration(
+    deps: DepsMut,
+    env: Env,
+    account: Addr,
+    new_address: Addr,
+    proof: Option<String>,
+) -> Result<Response, ContractError> {
+    if !VESTING_INFO.has(deps.storage, &account) {
+        return Err(StdError::generic_err(format!("Vesting account {account} not found")).into());
+    }
+    if account == new_address || VESTING_INFO.has(deps.storage, &new_address) {
+        return Err(ContractError::InvalidMigrationAddress(account.to_string()));
+    }
+    if proof.is_none() && ACCOUNT_MIGRATIONS.has(deps.storage, &account) {
+        return Err(ContractError::AccountMigrationPending(account.to_string()));
+    }
+
+    let requested_at = env.block.time.seconds();
+    let migration = AccountMigration {
+        new_address,
+        requested_at,
+        executable_at: requested_at + ACCOUNT_MIGRATION_DISPUTE_PERIOD,
+        proof,
+    };
+    ACCOUNT_MIGRATIONS.save(deps.storage, &account, &migration)?;
+
+    let mut attrs = vec![
+        attr("action", "request_account_migration"),
+        attr("account", account),
+        attr("new_address", migration.new_address),
+        attr("executable_at", migration.executable_at.to_string()),
+    ];
+    if let Some(proof) = migration.proof {
+        attrs.push(attr("proof", proof));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Cancels a pending account migration.
+/// The account can cancel the migration it has requested until the dispute period is over.
+/// The owner can cancel any pending migration.
+fn cancel_account_migration(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account: String,
+) -> Result<Response, ContractError> {
+    let account = deps.api.addr_validate(&account)?;
+    let migration = ACCOUNT_MIGRATIONS
+        .may_load(deps.storage, &account)?
+        .ok_or_else(|| ContractError::NoAccountMigration(account.to_string()))?;
+
+    if info.sender != CONFIG.load(deps.storage)?.owner {
+        ensure!(
+            info.sender == account && migration.proof.is_none(),
+            ContractError::Unauthorized {}
+        );
+        ensure!(
+            env.block.time.seconds() < migration.executable_at,
+            ContractError::AccountMigrationDisputeOver {}
+        );
+    }
+
+    ACCOUNT_MIGRATIONS.remove(deps.storage, &account);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_account_migration"),
+        attr("account", account),
+        attr("new_address", migration.new_address),
+    ]))
+}
+
+/// Moves the vesting account together with its claim history to the new address
+/// once the dispute period has passed.
+fn execute_account_migration(
+    deps: DepsMut,
+    env: Env,
+    account: String,
+) -> Result<Response, ContractError> {
+    let account = deps.api.addr_validate(&account)?;
+    let migration = ACCOUNT_MIGRATIONS
+        .may_load(deps.storage, &account)?
+        .ok_or_else(|| ContractError::NoAccountMigration(account.to_string()))?;
+
+    ensure!(
+        env.block.time.seconds() >= migration.executable_at,
+        ContractError::AccountMigrationTimelocked(migration.executable_at)
+    );
+    // The new address could have received its own vesting schedules in the meantime
+    ensure!(
+        !VESTING_INFO.has(deps.storage, &migration.new_address),
+        ContractError::InvalidMigrationAddress(account.to_string())
+    );
+
+    let vesting_info = VESTING_INFO.load(deps.storage, &account)?;
+    VESTING_INFO.remove(deps.storage, &account);
+    VESTING_INFO.save(deps.storage, &migration.new_address, &vesting_info)?;
+    move_claim_history(deps.storage, &account, &migration.new_address)?;
+    ACCOUNT_MIGRATIONS.remove(deps.storage, &account);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "execute_account_migration"),
+        attr("account", account),
+        attr("new_address", migration.new_address),
+    ]))
+}
+
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
@@ -431,6 +708,14 @@ fn withdraw_from_active_schedule(
 ///         }** Returns a list of vesting schedules together with their vesting recipients.
 ///
 /// * **QueryMsg::AvailableAmount { address }** Returns the available amount of tokens that can be claimed by a specific vesting recipient.
+///
+/// * **QueryMsg::ClaimHistory {
+///             address,
+///             start_after,
+///             limit,
+///         }** Returns the latest claims of a specific vesting recipient.
+///
+/// * **QueryMsg::AccountMigration { address }** Returns the pending migration of a vesting account.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -452,6 +737,25 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             &query_vesting_available_amount(deps, env, address)?,
         )?),
         QueryMsg::Timestamp {} => Ok(to_json_binary(&query_timestamp(env)?)?),
+        QueryMsg::ClaimHistory {
+            address,
+            start_after,
+            limit,
+        } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_json_binary(&read_claim_history(
+                deps.storage,
+                &address,
+                start_after,
+                limit,
+            )?)?)
+        }
+        QueryMsg::AccountMigration { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_json_binary(
+                &ACCOUNT_MIGRATIONS.may_load(deps.storage, &address)?,
+            )?)
+        }
     }
 }
 
@@ -531,12 +835,16 @@ pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, Con
             // phoenix-1 1.3.0
             // neutron-1, pion-1 1.3.1
             "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" => {
+                let converter_contract = msg.converter_contract.ok_or_else(|| {
+                    StdError::generic_err(
+                        "Converter contract is required to migrate from this version",
+                    )
+                })?;
                 let mut config = CONFIG.load(deps.storage)?;
 
-                let converter_config: astro_converter::Config = deps.querier.query_wasm_smart(
-                    &msg.converter_contract,
-                    &astro_converter::QueryMsg::Config {},
-                )?;
+                let converter_config: astro_converter::Config = deps
+                    .querier
+                    .query_wasm_smart(&converter_contract, &astro_converter::QueryMsg::Config {})?;
 
                 ensure!(
                     converter_config.old_astro_asset_info == config.vesting_token,
@@ -554,14 +862,17 @@ pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, Con
                     AssetInfo::Token { contract_addr } => wasm_execute(
                         contract_addr,
                         &cw20::Cw20ExecuteMsg::Send {
-                            contract: msg.converter_contract,
+                            contract: converter_contract,
                             amount: total_amount,
-                            msg: to_json_binary(&astro_converter::Cw20HookMsg { receiver: None })?,
+                            msg: to_json_binary(&astro_converter::Cw20HookMsg {
+                                receiver: None,
+                                ibc_forward: None,
+                            })?,
                         },
                         vec![],
                     )?,
                     AssetInfo::NativeToken { denom } => wasm_execute(
-                        &msg.converter_contract,
+                        &converter_contract,
                         &astro_converter::ExecuteMsg::Convert { receiver: None },
                         coins(total_amount.u128(), denom.to_string()),
                     )?,
@@ -571,6 +882,7 @@ pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, Con
                 config.vesting_token = AssetInfo::native(&converter_config.new_astro_denom);
                 CONFIG.save(deps.storage, &config)?;
             }
+            "1.4.0" => {}
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
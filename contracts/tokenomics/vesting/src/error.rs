@@ -42,6 +42,30 @@ pub enum ContractError {
 
     #[error("Failed to withdraw from active schedule: amount left {0}")]
     NotEnoughTokens(Uint128),
+
+    #[error("Account {0} has a pending migration")]
+    AccountMigrationPending(String),
+
+    #[error("Account {0} has no pending migration")]
+    NoAccountMigration(String),
+
+    #[error("Account migration can be executed after {0}")]
+    AccountMigrationTimelocked(u64),
+
+    #[error("Account migration dispute period is over")]
+    AccountMigrationDisputeOver {},
+
+    #[error("Can't migrate account {0} to an address which already has a vesting account")]
+    InvalidMigrationAddress(String),
+
+    #[error("Management parameters of account {0} don't match the registered ones")]
+    VestingManagementMismatch(String),
+
+    #[error("Account {0} is not managed")]
+    AccountNotManaged(String),
+
+    #[error("Account {0} has no unvested tokens to clawback")]
+    NothingToClawback(String),
 }
 
 impl From<OverflowError> for ContractError {
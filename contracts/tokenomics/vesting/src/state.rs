@@ -2,8 +2,8 @@ use cosmwasm_schema::cw_serde;
 
 use astroport::asset::AssetInfo;
 use astroport::common::OwnershipProposal;
-use astroport::vesting::{OrderBy, VestingInfo};
-use cosmwasm_std::{Addr, Deps, StdResult};
+use astroport::vesting::{AccountMigration, ClaimRecord, OrderBy, VestingInfo, MAX_CLAIM_HISTORY};
+use cosmwasm_std::{Addr, Deps, StdResult, Storage, Uint128};
 use cw_storage_plus::{Bound, Item, Map};
 
 /// This structure stores the main parameters for the generator vesting contract.
@@ -24,6 +24,16 @@ pub const VESTING_INFO: Map<&Addr, VestingInfo> = Map::new("vesting_info");
 /// Contains a proposal to change contract ownership.
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// Circular buffer with the latest claims of a vesting account.
+/// key: (account address, claim id % [`MAX_CLAIM_HISTORY`]), value: claim record
+pub const CLAIM_HISTORY: Map<(&Addr, u64), ClaimRecord> = Map::new("claim_history");
+
+/// Total number of claims made by a vesting account. The next claim gets this number as its id.
+pub const CLAIMS_COUNT: Map<&Addr, u64> = Map::new("claims_count");
+
+/// Pending moves of vesting accounts to new addresses.
+pub const ACCOUNT_MIGRATIONS: Map<&Addr, AccountMigration> = Map::new("account_migrations");
+
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
 
@@ -63,6 +73,65 @@ pub fn read_vesting_infos(
     Ok(info)
 }
 
+/// Appends a claim to the account's history overwriting the oldest record once the history is full.
+pub fn record_claim(
+    storage: &mut dyn Storage,
+    account: &Addr,
+    timestamp: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    let id = CLAIMS_COUNT.may_load(storage, account)?.unwrap_or_default();
+
+    CLAIM_HISTORY.save(
+        storage,
+        (account, id % MAX_CLAIM_HISTORY),
+        &ClaimRecord {
+            id,
+            timestamp,
+            amount,
+        },
+    )?;
+    CLAIMS_COUNT.save(storage, account, &(id + 1))
+}
+
+/// Returns the account's claims which are still kept in history in ascending order.
+///
+/// * **start_after** claim id from which to start reading.
+///
+/// * **limit** amount of claims to read.
+pub fn read_claim_history(
+    storage: &dyn Storage,
+    account: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ClaimRecord>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let count = CLAIMS_COUNT.may_load(storage, account)?.unwrap_or_default();
+
+    let oldest = count.saturating_sub(MAX_CLAIM_HISTORY);
+    let start = start_after.map_or(oldest, |id| (id + 1).max(oldest));
+
+    (start..count.min(start.saturating_add(limit)))
+        .map(|id| CLAIM_HISTORY.load(storage, (account, id % MAX_CLAIM_HISTORY)))
+        .collect()
+}
+
+/// Moves the claim history of a vesting account to a new address.
+pub fn move_claim_history(storage: &mut dyn Storage, from: &Addr, to: &Addr) -> StdResult<()> {
+    let Some(count) = CLAIMS_COUNT.may_load(storage, from)? else {
+        return Ok(());
+    };
+
+    for id in count.saturating_sub(MAX_CLAIM_HISTORY)..count {
+        let key = id % MAX_CLAIM_HISTORY;
+        let record = CLAIM_HISTORY.load(storage, (from, key))?;
+        CLAIM_HISTORY.remove(storage, (from, key));
+        CLAIM_HISTORY.save(storage, (to, key), &record)?;
+    }
+    CLAIMS_COUNT.remove(storage, from);
+    CLAIMS_COUNT.save(storage, to, &count)
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
@@ -75,6 +144,7 @@ mod testing {
 
         let vi_mock = VestingInfo {
             released_amount: Uint128::zero(),
+            managed: None,
             schedules: vec![],
         };
 
@@ -134,4 +204,58 @@ mod testing {
         .unwrap();
         assert_eq!(res, vec![(Addr::unchecked("address2"), vi_mock.clone())]);
     }
+
+    #[test]
+    fn claim_history_is_bounded() {
+        use cosmwasm_std::testing::mock_dependencies;
+
+        let mut deps = mock_dependencies();
+        let account = Addr::unchecked("account");
+
+        assert_eq!(
+            read_claim_history(&deps.storage, &account, None, None).unwrap(),
+            vec![]
+        );
+
+        for i in 0..MAX_CLAIM_HISTORY + 5 {
+            record_claim(
+                &mut deps.storage,
+                &account,
+                1000 + i,
+                Uint128::new(i as u128),
+            )
+            .unwrap();
+        }
+
+        // The oldest 5 claims were overwritten
+        let res = read_claim_history(&deps.storage, &account, None, Some(2)).unwrap();
+        assert_eq!(
+            res,
+            vec![
+                ClaimRecord {
+                    id: 5,
+                    timestamp: 1005,
+                    amount: Uint128::new(5),
+                },
+                ClaimRecord {
+                    id: 6,
+                    timestamp: 1006,
+                    amount: Uint128::new(6),
+                },
+            ]
+        );
+
+        // Pagination skips overwritten claims
+        let res = read_claim_history(&deps.storage, &account, Some(2), Some(1)).unwrap();
+        assert_eq!(res[0].id, 5);
+
+        let res = read_claim_history(&deps.storage, &account, Some(100), None).unwrap();
+        assert_eq!(
+            res.iter().map(|record| record.id).collect::<Vec<_>>(),
+            vec![101, 102, 103, 104]
+        );
+
+        let res = read_claim_history(&deps.storage, &account, Some(104), None).unwrap();
+        assert_eq!(res, vec![]);
+    }
 }
@@ -11,10 +11,13 @@ use astroport::astro_converter;
 use astroport::astro_converter::OutpostBurnParams;
 use astroport::querier::query_balance;
 use astroport::vesting::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, VestingAccount, VestingSchedule,
-    VestingSchedulePoint,
+    AccountMigration, ClaimRecord, QueryMsg, VestingAccountResponse, VestingAccountsResponse,
+    VestingInfo, ACCOUNT_MIGRATION_DISPUTE_PERIOD,
+};
+use astroport::vesting::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, VestingAccount, VestingManagement,
+    VestingManagementParams, VestingSchedule, VestingSchedulePoint,
 };
-use astroport::vesting::{QueryMsg, VestingAccountResponse, VestingAccountsResponse, VestingInfo};
 use astroport_vesting::error::ContractError;
 use astroport_vesting::state::Config;
 
@@ -78,6 +81,7 @@ fn claim() {
                         }),
                     },
                 ],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -129,6 +133,7 @@ fn claim() {
                         }),
                     },
                 ],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -217,6 +222,51 @@ fn claim() {
         .unwrap();
 
     assert_eq!(user1_vesting_amount.clone(), Uint128::new(0u128));
+
+    // The claim is recorded in history
+    let history: Vec<ClaimRecord> = app
+        .wrap()
+        .query_wasm_smart(
+            vesting_instance.clone(),
+            &QueryMsg::ClaimHistory {
+                address: user1.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        history,
+        vec![ClaimRecord {
+            id: 0,
+            timestamp: app.block_info().time.seconds(),
+            amount: Uint128::new(300),
+        }]
+    );
+
+    // Claims of zero amount are not recorded
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::Claim {
+            recipient: None,
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let history: Vec<ClaimRecord> = app
+        .wrap()
+        .query_wasm_smart(
+            vesting_instance.clone(),
+            &QueryMsg::ClaimHistory {
+                address: user1.to_string(),
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(history, vec![]);
 }
 
 #[test]
@@ -252,6 +302,7 @@ fn claim_native() {
                         amount: Uint128::new(200),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -298,6 +349,7 @@ fn claim_native() {
                     }),
                 },
             ],
+            managed: None,
         }],
     };
 
@@ -408,6 +460,7 @@ fn claim_after_migration() {
                     amount: Uint128::new(100_000),
                 }),
             }],
+            managed: None,
         }],
     };
 
@@ -534,6 +587,7 @@ fn register_vesting_accounts() {
                         amount: Uint128::new(100),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -560,6 +614,7 @@ fn register_vesting_accounts() {
                         amount: Uint128::new(100),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -598,6 +653,7 @@ fn register_vesting_accounts() {
                     amount: Uint128::new(100),
                 }),
             }],
+            managed: None,
         }],
     };
 
@@ -661,6 +717,7 @@ fn register_vesting_accounts() {
                         amount: Uint128::new(200),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -720,6 +777,7 @@ fn register_vesting_accounts() {
                         amount: Uint128::new(10),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -818,6 +876,7 @@ fn register_vesting_accounts() {
                             }),
                         }],
                         released_amount: Uint128::zero(),
+                        managed: None,
                     }
                 },
                 VestingAccountResponse {
@@ -846,6 +905,7 @@ fn register_vesting_accounts() {
                             }
                         ],
                         released_amount: Uint128::new(110),
+                        managed: None,
                     }
                 }
             ]
@@ -892,6 +952,7 @@ fn register_vesting_accounts_native() {
                         amount: Uint128::new(100),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -917,6 +978,7 @@ fn register_vesting_accounts_native() {
                     amount: Uint128::new(100),
                 }),
             }],
+            managed: None,
         }],
     };
 
@@ -982,6 +1044,7 @@ fn register_vesting_accounts_native() {
                     amount: Uint128::new(200),
                 }),
             }],
+            managed: None,
         }],
     };
 
@@ -1037,6 +1100,7 @@ fn register_vesting_accounts_native() {
                     amount: Uint128::new(10),
                 }),
             }],
+            managed: None,
         }],
     };
 
@@ -1105,6 +1169,123 @@ fn register_vesting_accounts_native() {
     assert_eq!(bal, TOKEN_INITIAL_AMOUNT - 310u128);
 }
 
+#[test]
+fn register_prunes_finished_schedules() {
+    let user1 = Addr::unchecked(USER1);
+    let owner = Addr::unchecked(OWNER1);
+
+    let mut app = mock_app(&owner);
+    let vesting_instance = instantiate_vesting_remote_chain(&mut app);
+
+    let register_msg = |current_time: u64| ExecuteMsg::RegisterVestingAccounts {
+        vesting_accounts: vec![VestingAccount {
+            address: user1.to_string(),
+            schedules: vec![VestingSchedule {
+                start_point: VestingSchedulePoint {
+                    time: current_time,
+                    amount: Uint128::zero(),
+                },
+                end_point: Some(VestingSchedulePoint {
+                    time: current_time + 100,
+                    amount: Uint128::new(10),
+                }),
+            }],
+            managed: None,
+        }],
+    };
+
+    let current_time = app.block_info().time.seconds();
+    for _ in 0..8 {
+        app.execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &register_msg(current_time),
+            &coins(10, IBC_ASTRO),
+        )
+        .unwrap();
+    }
+
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &register_msg(current_time),
+            &coins(10, IBC_ASTRO),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExceedSchedulesMaximumLimit(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.update_block(|b| {
+        b.time = b.time.plus_seconds(100);
+        b.height += 100 / 5
+    });
+
+    // Finished schedules are not dropped until they are claimed
+    let current_time = app.block_info().time.seconds();
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            vesting_instance.clone(),
+            &register_msg(current_time),
+            &coins(10, IBC_ASTRO),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ExceedSchedulesMaximumLimit(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &ExecuteMsg::Claim {
+            recipient: None,
+            amount: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        vesting_instance.clone(),
+        &register_msg(current_time),
+        &coins(10, IBC_ASTRO),
+    )
+    .unwrap();
+
+    let vesting_res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(vesting_res.info.schedules.len(), 1);
+    assert_eq!(vesting_res.info.released_amount, Uint128::zero());
+
+    app.update_block(|b| {
+        b.time = b.time.plus_seconds(50);
+        b.height += 50 / 5
+    });
+
+    let available: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::AvailableAmount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(available.u128(), 5);
+}
+
 #[test]
 fn withdraw_from_active_schedule() {
     let owner = Addr::unchecked(OWNER1);
@@ -1136,6 +1317,7 @@ fn withdraw_from_active_schedule() {
                         amount: Uint128::new(100_000_000_000000),
                     }),
                 }],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -1241,6 +1423,7 @@ fn withdraw_overlapping_schedules() {
                         end_point: None,
                     },
                 ],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -1341,6 +1524,7 @@ fn withdraw_overlapping_schedules2() {
                         }),
                     },
                 ],
+                managed: None,
             }],
         })
         .unwrap(),
@@ -1430,6 +1614,409 @@ fn withdraw_overlapping_schedules2() {
     );
 }
 
+#[test]
+fn account_migration() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let new_address = Addr::unchecked("user1_new");
+    let start_time = app.block_info().time.seconds();
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_json_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: user1.to_string(),
+                schedules: vec![VestingSchedule {
+                    start_point: VestingSchedulePoint {
+                        time: start_time,
+                        amount: Uint128::zero(),
+                    },
+                    end_point: Some(VestingSchedulePoint {
+                        time: start_time + 100 * 86400,
+                        amount: Uint128::new(100_000000),
+                    }),
+                }],
+                managed: None,
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(100_000000),
+    };
+    app.execute_contract(owner.clone(), astro_token.clone(), &msg, &[])
+        .unwrap();
+
+    app.update_block(|b| b.time = b.time.plus_seconds(10 * 86400));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 10_000000);
+
+    let propose_msg = |new_address: &Addr| ExecuteMsg::ProposeAccountMigration {
+        new_address: new_address.to_string(),
+    };
+    let cancel_msg = ExecuteMsg::CancelAccountMigration {
+        account: user1.to_string(),
+    };
+    let execute_msg = ExecuteMsg::ExecuteAccountMigration {
+        account: user1.to_string(),
+    };
+    let query_migration = |app: &App| -> Option<AccountMigration> {
+        app.wrap()
+            .query_wasm_smart(
+                &vesting_instance,
+                &QueryMsg::AccountMigration {
+                    address: user1.to_string(),
+                },
+            )
+            .unwrap()
+    };
+
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &propose_msg(&user1),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidMigrationAddress(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &propose_msg(&new_address),
+        &[],
+    )
+    .unwrap();
+    let now = app.block_info().time.seconds();
+    assert_eq!(
+        query_migration(&app),
+        Some(AccountMigration {
+            new_address: new_address.clone(),
+            requested_at: now,
+            executable_at: now + ACCOUNT_MIGRATION_DISPUTE_PERIOD,
+            proof: None,
+        })
+    );
+
+    // Claims are paused while the migration is pending
+    let err = app
+        .execute_contract(
+            user1.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::Claim {
+                recipient: None,
+                amount: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AccountMigrationPending(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    let err = app
+        .execute_contract(owner.clone(), vesting_instance.clone(), &execute_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AccountMigrationTimelocked(now + ACCOUNT_MIGRATION_DISPUTE_PERIOD),
+        err.downcast().unwrap()
+    );
+
+    // The old address disputes the migration
+    app.execute_contract(user1.clone(), vesting_instance.clone(), &cancel_msg, &[])
+        .unwrap();
+    assert_eq!(query_migration(&app), None);
+
+    let err = app
+        .execute_contract(user1.clone(), vesting_instance.clone(), &cancel_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoAccountMigration(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // The compromised key requests a migration while the owner approves the one backed by proof
+    app.execute_contract(
+        user1.clone(),
+        vesting_instance.clone(),
+        &propose_msg(&Addr::unchecked("attacker")),
+        &[],
+    )
+    .unwrap();
+
+    let approve_msg = ExecuteMsg::ApproveAccountMigration {
+        account: user1.to_string(),
+        new_address: new_address.to_string(),
+        proof: "incident-1".to_string(),
+    };
+    let err = app
+        .execute_contract(user1.clone(), vesting_instance.clone(), &approve_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.execute_contract(owner.clone(), vesting_instance.clone(), &approve_msg, &[])
+        .unwrap();
+    let migration = query_migration(&app).unwrap();
+    assert_eq!(migration.new_address, new_address);
+    assert_eq!(migration.proof, Some("incident-1".to_string()));
+
+    // Only the owner can cancel the approved migration
+    let err = app
+        .execute_contract(user1.clone(), vesting_instance.clone(), &cancel_msg, &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    app.update_block(|b| b.time = b.time.plus_seconds(ACCOUNT_MIGRATION_DISPUTE_PERIOD));
+    app.execute_contract(
+        Addr::unchecked("anyone"),
+        vesting_instance.clone(),
+        &execute_msg,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_migration(&app), None);
+
+    app.wrap()
+        .query_wasm_smart::<VestingAccountResponse>(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap_err();
+
+    let res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: new_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.info.released_amount, Uint128::new(10_000000));
+
+    let res: Vec<ClaimRecord> = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::ClaimHistory {
+                address: new_address.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.len(), 1);
+
+    // 17 days have passed since the start
+    claim_and_check(
+        &mut app,
+        &new_address,
+        &vesting_instance,
+        &astro_token,
+        7_000000,
+    );
+}
+
+#[test]
+fn clawback_managed_account() {
+    let owner = Addr::unchecked(OWNER1);
+    let mut app = mock_app(&owner);
+    let token_code_id = store_token_code(&mut app);
+    let astro_token = instantiate_token(&mut app, token_code_id, "Astro", None);
+    let vesting_instance = instantiate_vesting(&mut app, &astro_token);
+
+    let user1 = Addr::unchecked(USER1);
+    let user2 = Addr::unchecked(USER2);
+    let manager = Addr::unchecked("manager");
+    let treasury = Addr::unchecked("treasury");
+    let start_time = app.block_info().time.seconds();
+
+    let linear_schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: start_time,
+            amount: Uint128::zero(),
+        },
+        end_point: Some(VestingSchedulePoint {
+            time: start_time + 100 * 86400,
+            amount: Uint128::new(100_000000),
+        }),
+    };
+    let managed = VestingManagementParams {
+        manager: manager.to_string(),
+        treasury: treasury.to_string(),
+    };
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_json_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![
+                VestingAccount {
+                    address: user1.to_string(),
+                    schedules: vec![
+                        linear_schedule.clone(),
+                        // Cliff which is not reached before the clawback
+                        VestingSchedule {
+                            start_point: VestingSchedulePoint {
+                                time: start_time + 200 * 86400,
+                                amount: Uint128::new(50_000000),
+                            },
+                            end_point: None,
+                        },
+                    ],
+                    managed: Some(managed.clone()),
+                },
+                VestingAccount {
+                    address: user2.to_string(),
+                    schedules: vec![linear_schedule.clone()],
+                    managed: None,
+                },
+            ],
+        })
+        .unwrap(),
+        amount: Uint128::new(250_000000),
+    };
+    app.execute_contract(owner.clone(), astro_token.clone(), &msg, &[])
+        .unwrap();
+
+    let res: VestingAccountResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::VestingAccount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.info.managed,
+        Some(VestingManagement {
+            manager: manager.clone(),
+            treasury: treasury.clone(),
+        })
+    );
+
+    app.update_block(|b| b.time = b.time.plus_seconds(10 * 86400));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 10_000000);
+    app.update_block(|b| b.time = b.time.plus_seconds(10 * 86400));
+
+    let clawback_msg = ExecuteMsg::Clawback {
+        account: user1.to_string(),
+    };
+
+    // Neither the owner nor the account can clawback
+    for sender in [&owner, &user1] {
+        let err = app
+            .execute_contract(sender.clone(), vesting_instance.clone(), &clawback_msg, &[])
+            .unwrap_err();
+        assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    }
+
+    let err = app
+        .execute_contract(
+            manager.clone(),
+            vesting_instance.clone(),
+            &ExecuteMsg::Clawback {
+                account: user2.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AccountNotManaged(user2.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        manager.clone(),
+        vesting_instance.clone(),
+        &clawback_msg,
+        &[],
+    )
+    .unwrap();
+
+    // 80 ASTRO left in the linear schedule and the whole cliff went to the treasury
+    check_token_balance(&mut app, &astro_token, &treasury, 130_000000);
+
+    // Tokens vested before the clawback are still claimable
+    let available: Uint128 = app
+        .wrap()
+        .query_wasm_smart(
+            &vesting_instance,
+            &QueryMsg::AvailableAmount {
+                address: user1.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(available.u128(), 10_000000);
+
+    // Nothing vests after the clawback
+    app.update_block(|b| b.time = b.time.plus_seconds(300 * 86400));
+    claim_and_check(&mut app, &user1, &vesting_instance, &astro_token, 20_000000);
+
+    let err = app
+        .execute_contract(
+            manager.clone(),
+            vesting_instance.clone(),
+            &clawback_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NothingToClawback(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    // New schedules must have the same management parameters
+    let current_time = app.block_info().time.seconds();
+    let new_schedule = VestingSchedule {
+        start_point: VestingSchedulePoint {
+            time: current_time,
+            amount: Uint128::zero(),
+        },
+        end_point: Some(VestingSchedulePoint {
+            time: current_time + 86400,
+            amount: Uint128::new(1_000000),
+        }),
+    };
+    let register_msg = |managed: Option<VestingManagementParams>| Cw20ExecuteMsg::Send {
+        contract: vesting_instance.to_string(),
+        msg: to_json_binary(&Cw20HookMsg::RegisterVestingAccounts {
+            vesting_accounts: vec![VestingAccount {
+                address: user1.to_string(),
+                schedules: vec![new_schedule.clone()],
+                managed,
+            }],
+        })
+        .unwrap(),
+        amount: Uint128::new(1_000000),
+    };
+
+    let err = app
+        .execute_contract(owner.clone(), astro_token.clone(), &register_msg(None), &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::VestingManagementMismatch(user1.to_string()),
+        err.downcast().unwrap()
+    );
+
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &register_msg(Some(managed)),
+        &[],
+    )
+    .unwrap();
+}
+
 fn mock_app(owner: &Addr) -> App {
     App::new(|app, _, storage| {
         app.bank
@@ -1635,7 +2222,7 @@ fn migrate_vesting(app: &mut App, vesting: &Addr) {
         Addr::unchecked(OWNER1),
         vesting.clone(),
         &MigrateMsg {
-            converter_contract: converter_contract.to_string(),
+            converter_contract: Some(converter_contract.to_string()),
         },
         vesting_code_id,
     )
@@ -10,7 +10,10 @@ use cw20_base::allowances::{
     deduct_allowance, execute_decrease_allowance, execute_increase_allowance, query_allowance,
 };
 
-use crate::state::{capture_total_supply_history, check_minter, get_total_supply_at, BALANCES};
+use crate::state::{
+    capture_total_supply_history, check_minter, get_balance_at_timestamp, get_total_supply_at,
+    get_total_supply_at_timestamp, init_timestamp_history, update_balance, BALANCES,
+};
 use astroport::asset::addr_opt_validate;
 use astroport::xastro_token::{InstantiateMsg, MigrateMsg, QueryMsg};
 use cw2::{get_contract_version, set_contract_version};
@@ -109,6 +112,8 @@ pub fn instantiate(
     // Check valid token info
     msg.validate()?;
 
+    init_timestamp_history(deps.storage, &env, Uint128::zero())?;
+
     // Create initial accounts
     let total_supply = create_accounts(&mut deps, &env, &msg.initial_balances)?;
 
@@ -177,7 +182,7 @@ pub fn create_accounts(deps: &mut DepsMut, env: &Env, accounts: &[Cw20Coin]) ->
 
     for row in accounts {
         let address = deps.api.addr_validate(&row.address)?;
-        BALANCES.save(deps.storage, &address, &row.amount, env.block.height)?;
+        update_balance(deps.storage, env, &address, |_| Ok(row.amount))?;
         total_supply += row.amount;
     }
 
@@ -272,18 +277,12 @@ pub fn execute_transfer(
 
     let rcpt_addr = deps.api.addr_validate(&recipient)?;
 
-    BALANCES.update(
-        deps.storage,
-        &info.sender,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-    )?;
+    update_balance(deps.storage, &env, &info.sender, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    update_balance(deps.storage, &env, &rcpt_addr, |balance| {
+        Ok(balance.unwrap_or_default() + amount)
+    })?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "transfer"),
@@ -310,12 +309,9 @@ pub fn execute_burn(
     check_minter(&info.sender, &config)?;
 
     // Lower the sender's balance
-    BALANCES.update(
-        deps.storage,
-        &info.sender,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
+    update_balance(deps.storage, &env, &info.sender, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
 
     // Reduce total_supply
     let token_info = TOKEN_INFO.update(deps.storage, |mut info| -> StdResult<_> {
@@ -365,12 +361,9 @@ pub fn execute_mint(
 
     // Add amount to recipient balance
     let rcpt_addr = deps.api.addr_validate(&recipient)?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-    )?;
+    update_balance(deps.storage, &env, &rcpt_addr, |balance| {
+        Ok(balance.unwrap_or_default() + amount)
+    })?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "mint"),
@@ -401,18 +394,12 @@ pub fn execute_send(
     let rcpt_addr = deps.api.addr_validate(&contract)?;
 
     // Move the tokens to the contract
-    BALANCES.update(
-        deps.storage,
-        &info.sender,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
-    )?;
+    update_balance(deps.storage, &env, &info.sender, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    update_balance(deps.storage, &env, &rcpt_addr, |balance| {
+        Ok(balance.unwrap_or_default() + amount)
+    })?;
 
     let res = Response::new()
         .add_attributes(vec![
@@ -453,18 +440,12 @@ pub fn execute_transfer_from(
     // Deduct allowance before doing anything else
     deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
 
-    BALANCES.update(
-        deps.storage,
-        &owner_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_add(amount)?) },
-    )?;
+    update_balance(deps.storage, &env, &owner_addr, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    update_balance(deps.storage, &env, &rcpt_addr, |balance| {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
 
     let res = Response::new().add_attributes(vec![
         attr("action", "transfer_from"),
@@ -497,12 +478,9 @@ pub fn execute_burn_from(
     deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
 
     // Lower balance
-    BALANCES.update(
-        deps.storage,
-        &owner_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
+    update_balance(deps.storage, &env, &owner_addr, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
 
     // Reduce total_supply
     let token_info = TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
@@ -546,18 +524,12 @@ pub fn execute_send_from(
     deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
 
     // Move the tokens to the contract
-    BALANCES.update(
-        deps.storage,
-        &owner_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_sub(amount)?) },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        env.block.height,
-        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_add(amount)?) },
-    )?;
+    update_balance(deps.storage, &env, &owner_addr, |balance| {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    update_balance(deps.storage, &env, &rcpt_addr, |balance| {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
 
     let res = Response::new()
         .add_attributes(vec![
@@ -589,6 +561,11 @@ pub fn execute_send_from(
 ///
 /// * **TotalSupplyAt { block }** Returns the total supply at the given block.
 ///
+/// * **BalanceAtTimestamp { address, timestamp }** Returns the balance of the given address at the given timestamp
+/// using a [`BalanceResponse`] object.
+///
+/// * **TotalSupplyAtTimestamp { timestamp }** Returns the total supply at the given timestamp.
+///
 /// * **TokenInfo {}** Returns the token metadata - name, decimals, supply, etc
 /// using a [`cw20::TokenInfoResponse`] object.
 ///
@@ -619,6 +596,12 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::TotalSupplyAt { block } => {
             to_json_binary(&get_total_supply_at(deps.storage, block)?)
         }
+        QueryMsg::BalanceAtTimestamp { address, timestamp } => {
+            to_json_binary(&query_balance_at_timestamp(deps, address, timestamp)?)
+        }
+        QueryMsg::TotalSupplyAtTimestamp { timestamp } => {
+            to_json_binary(&get_total_supply_at_timestamp(deps.storage, timestamp)?)
+        }
         QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
         QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
         QueryMsg::Allowance { owner, spender } => {
@@ -655,6 +638,17 @@ pub fn query_balance_at(deps: Deps, address: String, block: u64) -> StdResult<Ba
     Ok(BalanceResponse { balance })
 }
 
+/// Returns the balance of the given address at the given timestamp.
+pub fn query_balance_at_timestamp(
+    deps: Deps,
+    address: String,
+    timestamp: u64,
+) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = get_balance_at_timestamp(deps.storage, &address, timestamp)?;
+    Ok(BalanceResponse { balance })
+}
+
 /// Returns the current balances of multiple accounts.
 ///
 /// * **start_after** account from which to start querying for balances.
@@ -679,12 +673,15 @@ pub fn query_all_accounts(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "astroport-xastro-token" => match contract_version.version.as_ref() {
-            "1.0.0" | "1.0.1" | "1.0.2" => {}
+            "1.0.0" | "1.0.1" | "1.0.2" | "1.1.0" => {
+                let total_supply = TOKEN_INFO.load(deps.storage)?.total_supply;
+                init_timestamp_history(deps.storage, &env, total_supply)?;
+            }
             _ => {
                 return Err(StdError::generic_err(
                     "Cannot migrate. Unsupported contract version",
@@ -1,7 +1,7 @@
-use cosmwasm_std::{Addr, Env, Order, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Env, Order, StdError, StdResult, Storage, Uint128};
 use cw20_base::state::TokenInfo;
 use cw20_base::ContractError;
-use cw_storage_plus::{Bound, Map, SnapshotMap, Strategy};
+use cw_storage_plus::{Bound, Item, Map, SnapshotItem, SnapshotMap, Strategy};
 
 /// Contains snapshotted coins balances at every block.
 pub const BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
@@ -14,6 +14,64 @@ pub const BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
 /// Contains the history of the xASTRO total supply.
 pub const TOTAL_SUPPLY_HISTORY: Map<u64, Uint128> = Map::new("total_supply_history");
 
+/// Contains snapshotted coins balances at every timestamp (in seconds).
+/// Accounts which were not changed since [`TIMESTAMP_HISTORY_START`] may be missing.
+pub const BALANCES_BY_TIMESTAMP: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "balance_by_timestamp",
+    "balance_by_timestamp__checkpoints",
+    "balance_by_timestamp__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Contains snapshotted xASTRO total supply at every timestamp (in seconds).
+pub const TOTAL_SUPPLY_BY_TIMESTAMP: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_supply_by_timestamp",
+    "total_supply_by_timestamp__checkpoints",
+    "total_supply_by_timestamp__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Timestamp (in seconds) when the contract started to snapshot balances and total supply by time
+pub const TIMESTAMP_HISTORY_START: Item<u64> = Item::new("timestamp_history_start");
+
+/// Starts tracking the history by timestamp with the current total supply.
+pub fn init_timestamp_history(
+    storage: &mut dyn Storage,
+    env: &Env,
+    total_supply: Uint128,
+) -> StdResult<()> {
+    TIMESTAMP_HISTORY_START.save(storage, &env.block.time.seconds())?;
+    TOTAL_SUPPLY_BY_TIMESTAMP.save(storage, &total_supply, env.block.time.seconds())
+}
+
+/// Updates an account balance and snapshots it by block height and timestamp.
+///
+/// * **action** closure which calculates the new balance from the current one.
+pub fn update_balance<A>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+    action: A,
+) -> StdResult<Uint128>
+where
+    A: FnOnce(Option<Uint128>) -> StdResult<Uint128>,
+{
+    let prev_balance = BALANCES.may_load(storage, address)?;
+    let new_balance = action(prev_balance)?;
+    BALANCES.save(storage, address, &new_balance, env.block.height)?;
+
+    // The account balance hasn't changed since the timestamp history start thus it is backfilled first
+    if let Some(prev_balance) = prev_balance {
+        if !BALANCES_BY_TIMESTAMP.has(storage, address) {
+            let start = TIMESTAMP_HISTORY_START.load(storage)?;
+            BALANCES_BY_TIMESTAMP.save(storage, address, &prev_balance, start)?;
+        }
+    }
+    BALANCES_BY_TIMESTAMP.save(storage, address, &new_balance, env.block.time.seconds())?;
+
+    Ok(new_balance)
+}
+
 /// Snapshots the total token supply at current block.
 ///
 /// * **total_supply** current token total supply.
@@ -22,7 +80,8 @@ pub fn capture_total_supply_history(
     env: &Env,
     total_supply: Uint128,
 ) -> StdResult<()> {
-    TOTAL_SUPPLY_HISTORY.save(storage, env.block.height, &total_supply)
+    TOTAL_SUPPLY_HISTORY.save(storage, env.block.height, &total_supply)?;
+    TOTAL_SUPPLY_BY_TIMESTAMP.save(storage, &total_supply, env.block.time.seconds())
 }
 
 /// Returns the total token supply at the given block.
@@ -41,6 +100,45 @@ pub fn get_total_supply_at(storage: &dyn Storage, block: u64) -> StdResult<Uint1
     Ok(Uint128::zero())
 }
 
+/// Checks that balances and total supply were snapshotted by time at the given timestamp.
+pub fn assert_timestamp_tracked(storage: &dyn Storage, timestamp: u64) -> StdResult<()> {
+    let start = TIMESTAMP_HISTORY_START.load(storage)?;
+    if timestamp <= start {
+        return Err(StdError::generic_err(format!(
+            "Timestamp history is available only after {start}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the total token supply at the given timestamp.
+pub fn get_total_supply_at_timestamp(storage: &dyn Storage, timestamp: u64) -> StdResult<Uint128> {
+    assert_timestamp_tracked(storage, timestamp)?;
+
+    Ok(TOTAL_SUPPLY_BY_TIMESTAMP
+        .may_load_at_height(storage, timestamp)?
+        .unwrap_or_default())
+}
+
+/// Returns the balance of the given account at the given timestamp.
+pub fn get_balance_at_timestamp(
+    storage: &dyn Storage,
+    address: &Addr,
+    timestamp: u64,
+) -> StdResult<Uint128> {
+    assert_timestamp_tracked(storage, timestamp)?;
+
+    let balance = if BALANCES_BY_TIMESTAMP.has(storage, address) {
+        BALANCES_BY_TIMESTAMP.may_load_at_height(storage, address, timestamp)?
+    } else {
+        // The balance hasn't changed since the timestamp history start
+        BALANCES.may_load(storage, address)?
+    };
+
+    Ok(balance.unwrap_or_default())
+}
+
 pub fn check_minter(sender: &Addr, config: &TokenInfo) -> Result<(), ContractError> {
     if let Some(ref mint_data) = config.mint {
         if mint_data.minter != sender {
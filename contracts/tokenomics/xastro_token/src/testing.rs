@@ -1,9 +1,10 @@
 use crate::contract::{
-    execute, execute_burn_from, execute_send_from, execute_transfer_from, instantiate,
-    query_all_accounts, query_balance, query_balance_at,
+    execute, execute_burn_from, execute_send_from, execute_transfer_from, instantiate, migrate,
+    query_all_accounts, query_balance, query_balance_at, query_balance_at_timestamp,
 };
-use crate::state::get_total_supply_at;
+use crate::state::{get_total_supply_at, get_total_supply_at_timestamp, BALANCES};
 use astroport::xastro_token::InstantiateMsg;
+use astroport::xastro_token::MigrateMsg;
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     Addr, Binary, BlockInfo, ContractInfo, CosmosMsg, Deps, DepsMut, Env, StdError, SubMsg,
@@ -16,6 +17,7 @@ use cw20::{
 use cw20_base::allowances::execute_increase_allowance;
 use cw20_base::contract::{query_minter, query_token_info};
 use cw20_base::msg::ExecuteMsg;
+use cw20_base::state::{TokenInfo, TOKEN_INFO};
 use cw20_base::ContractError;
 
 pub struct MockEnvParams {
@@ -982,3 +984,208 @@ fn test_balance_history() {
         }
     );
 }
+
+#[test]
+fn test_balance_history_by_timestamp() {
+    let mut deps = mock_dependencies();
+    let user1 = mock_info("user1", &[]);
+    let minter = mock_info("minter", &[]);
+    do_instantiate_with_minter(
+        deps.as_mut(),
+        user1.sender.as_str(),
+        Uint128::new(1_000),
+        minter.sender.as_str(),
+        None,
+    );
+    let start = mock_env().block.time.seconds();
+
+    let err =
+        query_balance_at_timestamp(deps.as_ref(), user1.sender.to_string(), start).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Timestamp history is available only after {start}"))
+    );
+    let err = get_total_supply_at_timestamp(&deps.storage, start).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Timestamp history is available only after {start}"))
+    );
+
+    // Several changes happen in different blocks at the same timestamp
+    let mut env = mock_env();
+    env.block.height += 1;
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        user1.clone(),
+        ExecuteMsg::Transfer {
+            recipient: "user2".to_string(),
+            amount: Uint128::new(100),
+        },
+    )
+    .unwrap();
+    env.block.height += 1;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        minter.clone(),
+        ExecuteMsg::Mint {
+            recipient: "user2".to_string(),
+            amount: Uint128::new(50),
+        },
+    )
+    .unwrap();
+
+    let ts = env.block.time.seconds();
+    for (timestamp, user1_balance, user2_balance, total_supply) in [
+        (start + 1, 1000, 0, 1000),
+        (ts, 1000, 0, 1000),
+        (ts + 1, 900, 150, 1050),
+    ] {
+        assert_eq!(
+            query_balance_at_timestamp(deps.as_ref(), user1.sender.to_string(), timestamp)
+                .unwrap()
+                .balance
+                .u128(),
+            user1_balance
+        );
+        assert_eq!(
+            query_balance_at_timestamp(deps.as_ref(), "user2".to_string(), timestamp)
+                .unwrap()
+                .balance
+                .u128(),
+            user2_balance
+        );
+        assert_eq!(
+            get_total_supply_at_timestamp(&deps.storage, timestamp)
+                .unwrap()
+                .u128(),
+            total_supply
+        );
+    }
+
+    // Burn is tracked as well
+    env.block.height += 1;
+    env.block.time = env.block.time.plus_seconds(100);
+    execute_increase_allowance(
+        deps.as_mut(),
+        env.clone(),
+        user1.clone(),
+        minter.sender.to_string(),
+        Uint128::new(400),
+        None,
+    )
+    .unwrap();
+    execute_burn_from(
+        deps.as_mut(),
+        env.clone(),
+        minter.clone(),
+        user1.sender.to_string(),
+        Uint128::new(400),
+    )
+    .unwrap();
+
+    let ts_after_burn = env.block.time.seconds() + 1;
+    assert_eq!(
+        query_balance_at_timestamp(deps.as_ref(), user1.sender.to_string(), ts_after_burn)
+            .unwrap()
+            .balance
+            .u128(),
+        500
+    );
+    assert_eq!(
+        get_total_supply_at_timestamp(&deps.storage, ts_after_burn)
+            .unwrap()
+            .u128(),
+        650
+    );
+    assert_eq!(
+        get_total_supply_at_timestamp(&deps.storage, ts + 1)
+            .unwrap()
+            .u128(),
+        1050
+    );
+}
+
+#[test]
+fn test_timestamp_history_after_migration() {
+    let mut deps = mock_dependencies();
+
+    // Legacy state without timestamp snapshots
+    cw2::set_contract_version(&mut deps.storage, "astroport-xastro-token", "1.1.0").unwrap();
+    TOKEN_INFO
+        .save(
+            &mut deps.storage,
+            &TokenInfo {
+                name: "Staked Astroport".to_string(),
+                symbol: "xASTRO".to_string(),
+                decimals: 6,
+                total_supply: Uint128::new(1_000),
+                mint: None,
+            },
+        )
+        .unwrap();
+    BALANCES
+        .save(
+            &mut deps.storage,
+            &Addr::unchecked("user1"),
+            &Uint128::new(1_000),
+            1,
+        )
+        .unwrap();
+
+    let mut env = mock_env();
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    let start = env.block.time.seconds();
+
+    query_balance_at_timestamp(deps.as_ref(), "user1".to_string(), start).unwrap_err();
+
+    // Balances which were not changed since the migration are taken from the current state
+    assert_eq!(
+        query_balance_at_timestamp(deps.as_ref(), "user1".to_string(), start + 1)
+            .unwrap()
+            .balance
+            .u128(),
+        1000
+    );
+
+    env.block.height += 1;
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user1", &[]),
+        ExecuteMsg::Transfer {
+            recipient: "user2".to_string(),
+            amount: Uint128::new(300),
+        },
+    )
+    .unwrap();
+
+    let ts = env.block.time.seconds();
+    for (timestamp, user1_balance, user2_balance) in
+        [(start + 1, 1000, 0), (ts, 1000, 0), (ts + 1, 700, 300)]
+    {
+        assert_eq!(
+            query_balance_at_timestamp(deps.as_ref(), "user1".to_string(), timestamp)
+                .unwrap()
+                .balance
+                .u128(),
+            user1_balance
+        );
+        assert_eq!(
+            query_balance_at_timestamp(deps.as_ref(), "user2".to_string(), timestamp)
+                .unwrap()
+                .balance
+                .u128(),
+            user2_balance
+        );
+        assert_eq!(
+            get_total_supply_at_timestamp(&deps.storage, timestamp)
+                .unwrap()
+                .u128(),
+            1000
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+
+use crate::asset::Asset;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The contract owner
+    pub owner: String,
+    /// The factory contract address. Arbitrage profit is sent to the factory's fee address (Maker)
+    pub factory_addr: String,
+    /// Addresses which are allowed to trigger arbitrage
+    pub keepers: Vec<String>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Closes the price gap between two pools of the same asset pair without any inventory.
+    /// The contract flash swaps `offer_asset` in the pool where it is priced higher, buys it back
+    /// in the other pool and repays the flash swap within the same transaction.
+    /// Reverts unless at least `min_profit` is left after the repayment. The profit is sent to the Maker.
+    /// Only the owner or a keeper can execute this.
+    Arbitrage {
+        /// The first pair contract address
+        pair_a: String,
+        /// The second pair contract address
+        pair_b: String,
+        /// The inventory asset used to close the gap
+        offer_asset: Asset,
+        /// The minimum profit denominated in the offer asset. Any non-zero profit is accepted by default
+        min_profit: Option<Uint128>,
+        /// The maximum spread of the swap in the pool where the offer asset is bought back.
+        /// The pair's default slippage is used if not set
+        max_spread: Option<Decimal>,
+    },
+    /// Swaps the ask asset received from the flash swap back to the offer asset and repays the flash swap.
+    /// Can be called only by the pair where the offer asset is flash swapped during an arbitrage
+    FlashSwapCallback {
+        ask: Asset,
+        offer: Asset,
+        msg: Binary,
+    },
+    /// Updates the list of keepers. Only the owner can execute this.
+    UpdateConfig {
+        add_keepers: Option<Vec<String>>,
+        remove_keepers: Option<Vec<String>>,
+    },
+    /// Withdraws assets stuck in the contract. Only the owner can execute this.
+    Withdraw {
+        assets: Vec<Asset>,
+        /// The receiver of withdrawn assets. Defaults to the owner
+        receiver: Option<String>,
+    },
+    /// Creates a request to change contract ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Simulates both swap directions and returns the more profitable one
+    #[returns(ArbitrageSimulation)]
+    SimulateArbitrage {
+        pair_a: String,
+        pair_b: String,
+        offer_asset: Asset,
+    },
+}
+
+/// This structure stores the main contract parameters.
+#[cw_serde]
+pub struct Config {
+    /// The contract owner
+    pub owner: Addr,
+    /// The factory contract address
+    pub factory_addr: Addr,
+    /// Addresses which are allowed to trigger arbitrage
+    pub keepers: Vec<Addr>,
+}
+
+/// The outcome of an arbitrage simulation.
+#[cw_serde]
+pub struct ArbitrageSimulation {
+    /// The pair where the offer asset is sold
+    pub first_pair: Addr,
+    /// The pair where the offer asset is bought back
+    pub second_pair: Addr,
+    /// The amount of the offer asset received after both swaps
+    pub return_amount: Uint128,
+    /// The amount received above the offered amount. Zero if the route is unprofitable.
+    /// The flash swap fee of the first pair is not deducted
+    pub profit: Uint128,
+}
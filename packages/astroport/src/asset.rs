@@ -3,8 +3,8 @@ use std::fmt;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
     coin, coins, ensure, to_json_binary, wasm_execute, Addr, Api, BankMsg, Coin,
-    ConversionOverflowError, CosmosMsg, CustomMsg, CustomQuery, Decimal256, Fraction, MessageInfo,
-    QuerierWrapper, ReplyOn, StdError, StdResult, SubMsg, Uint128, Uint256, WasmMsg,
+    ConversionOverflowError, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256, Fraction,
+    MessageInfo, QuerierWrapper, ReplyOn, StdError, StdResult, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Denom, MinterResponse};
 use cw_asset::{Asset as CwAsset, AssetInfo as CwAssetInfo};
@@ -23,7 +23,10 @@ use crate::querier::{
 pub const UUSD_DENOM: &str = "uusd";
 /// LUNA token denomination
 pub const ULUNA_DENOM: &str = "uluna";
-/// Minimum initial LP share
+/// Minimum initial LP share. All pairs which mint LP tokens proportionally to the pool invariant
+/// deduct this amount from the first provider's share, mint it to the pair contract itself and lock it forever.
+/// Locked tokens are never burned so LP supply can't drop back to zero
+/// which protects the pool from first deposit (share inflation) attacks.
 pub const MINIMUM_LIQUIDITY_AMOUNT: Uint128 = Uint128::new(1_000);
 /// Maximum denom length
 pub const DENOM_MAX_LENGTH: usize = 128;
@@ -274,6 +277,56 @@ impl Asset {
         })
     }
 
+    /// Same as [`Asset::to_decimal_asset`] but the precision is looked up in the native coin registry
+    /// (or in the token contract for CW20 tokens) configured in the factory.
+    pub fn query_decimal_asset<C>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        factory_addr: &Addr,
+    ) -> StdResult<DecimalAsset>
+    where
+        C: CustomQuery,
+    {
+        self.to_decimal_asset(self.info.decimals(querier, factory_addr)?)
+    }
+
+    /// Returns the sum of two amounts of the same asset.
+    pub fn checked_add(&self, other: &Asset) -> StdResult<Asset> {
+        self.assert_same_info(other)?;
+        Ok(self
+            .info
+            .with_balance(self.amount.checked_add(other.amount)?))
+    }
+
+    /// Subtracts an amount of the same asset.
+    pub fn checked_sub(&self, other: &Asset) -> StdResult<Asset> {
+        self.assert_same_info(other)?;
+        Ok(self
+            .info
+            .with_balance(self.amount.checked_sub(other.amount)?))
+    }
+
+    /// Multiplies the amount by a decimal rounding down.
+    pub fn checked_mul_decimal(&self, ratio: Decimal) -> StdResult<Asset> {
+        let amount = self
+            .amount
+            .checked_multiply_ratio(ratio.numerator(), ratio.denominator())
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        Ok(self.info.with_balance(amount))
+    }
+
+    fn assert_same_info(&self, other: &Asset) -> StdResult<()> {
+        ensure!(
+            self.info == other.info,
+            StdError::generic_err(format!(
+                "Asset info mismatch: {} and {}",
+                self.info, other.info
+            ))
+        );
+
+        Ok(())
+    }
+
     pub fn as_coin(&self) -> StdResult<Coin> {
         match &self.info {
             AssetInfo::Token { .. } => {
@@ -900,6 +953,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn asset_checked_math() {
+        let uusd = AssetInfo::native("uusd");
+
+        assert_eq!(
+            uusd.with_balance(100u8)
+                .checked_add(&uusd.with_balance(50u8))
+                .unwrap(),
+            uusd.with_balance(150u8)
+        );
+        assert_eq!(
+            uusd.with_balance(100u8)
+                .checked_sub(&uusd.with_balance(50u8))
+                .unwrap(),
+            uusd.with_balance(50u8)
+        );
+        assert_eq!(
+            uusd.with_balance(99u8)
+                .checked_mul_decimal(Decimal::percent(50))
+                .unwrap(),
+            uusd.with_balance(49u8)
+        );
+
+        let err = uusd
+            .with_balance(50u8)
+            .checked_sub(&uusd.with_balance(100u8))
+            .unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+
+        let err = uusd
+            .with_balance(u128::MAX)
+            .checked_add(&uusd.with_balance(1u8))
+            .unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+
+        let err = uusd
+            .with_balance(u128::MAX)
+            .checked_mul_decimal(Decimal::percent(200))
+            .unwrap_err();
+        assert!(err.to_string().contains("overflow"));
+
+        let err = uusd
+            .with_balance(100u8)
+            .checked_add(&mock_cw20())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Asset info mismatch: uusd and mock_token")
+        );
+    }
+
     #[test]
     fn test_native_coins_sent() {
         let asset = native_asset_info("uusd".to_string()).with_balance(1000u16);
@@ -1,4 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 use std::ops::RangeInclusive;
 
@@ -8,6 +9,13 @@ use crate::asset::AssetInfo;
 pub const DEFAULT_TIMEOUT: u64 = 300;
 /// Timeout limits for IBC transfer (from 2 to 10 minutes)
 pub const TIMEOUT_LIMITS: RangeInclusive<u64> = 120..=600;
+/// Conversion statistics are aggregated by epochs of this length (1 week).
+/// Epoch number is block time divided by epoch length.
+pub const CONVERSION_EPOCH_LENGTH: u64 = 86400 * 7;
+/// Max items per page in epoch conversions query
+pub const MAX_EPOCHS_LIMIT: u8 = 50;
+/// Max items per page in migrations query
+pub const MAX_MIGRATIONS_LIMIT: u8 = 30;
 
 /// Defines parameters for sending old IBCed ASTRO to the Hub for burning.
 #[cw_serde]
@@ -38,6 +46,38 @@ pub struct InstantiateMsg {
 #[cw_serde]
 pub struct Cw20HookMsg {
     pub receiver: Option<String>,
+    /// Forward converted tokens over IBC. Only supported by migrations registered with `SetMigration`.
+    /// Skipped when unset so the hook stays compatible with converters which don't know this field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ibc_forward: Option<IbcForward>,
+}
+
+/// Token migration registered by the contract admin in addition to the old ASTRO conversion.
+/// `from` can be either cw20 contract or native denom. `to_denom` is always native coin
+/// which must be supplied to the contract beforehand.
+#[cw_serde]
+pub struct Migration {
+    /// Asset accepted by the migration
+    pub from: AssetInfo,
+    /// Native denom released in exchange
+    pub to_denom: String,
+    /// Amount of `to_denom` released for one unit of `from`
+    pub rate: Decimal,
+    /// Timestamp after which the migration is closed. None means no deadline
+    pub deadline: Option<u64>,
+    /// Whether received tokens are burned right away. Otherwise they are kept in the contract
+    pub burn_source: bool,
+}
+
+/// Defines where converted tokens are sent over IBC.
+#[cw_serde]
+pub struct IbcForward {
+    /// IBC transfer channel on this chain
+    pub channel: String,
+    /// Receiver address on the counterparty chain
+    pub receiver: String,
+    /// Transfer timeout in seconds. Defaults to [`DEFAULT_TIMEOUT`]
+    pub timeout: Option<u64>,
 }
 
 /// Available contract execute messages.
@@ -46,17 +86,96 @@ pub struct Cw20HookMsg {
 /// Custom `receiver` is forwarded within Cw20HookMsg.
 /// - `TransferForBurning` is used to send old ASTRO to the old Hub for burning. Is meant to be used by outposts.
 /// - `Burn` is used to burn old cw20 ASTRO on the old Hub.
+/// - `UpdateEpochCap` sets or removes (if None) the per-epoch conversion cap. Only the contract admin can execute it.
+/// - `MigrateTokens` converts native tokens according to the migration registered for the sent denom.
+/// Converted tokens are sent to `receiver` or forwarded over IBC if `ibc_forward` is specified.
+/// Cw20 tokens are migrated via `Receive` with the same parameters in Cw20HookMsg.
+/// - `SetMigration` registers or updates a migration. Only the contract admin can execute it.
+/// - `RemoveMigration` closes a migration. Only the contract admin can execute it.
+/// - `SweepMigrated` sends the whole contract balance of source tokens kept by migrations
+/// without `burn_source` to `recipient` (sender by default). Only the contract admin can execute it.
 #[cw_serde]
 pub enum ExecuteMsg {
-    Convert { receiver: Option<String> },
+    Convert {
+        receiver: Option<String>,
+    },
     Receive(Cw20ReceiveMsg),
-    TransferForBurning { timeout: Option<u64> },
+    TransferForBurning {
+        timeout: Option<u64>,
+    },
     Burn {},
+    UpdateEpochCap {
+        epoch_cap: Option<Uint128>,
+    },
+    MigrateTokens {
+        receiver: Option<String>,
+        ibc_forward: Option<IbcForward>,
+    },
+    SetMigration {
+        migration: Migration,
+    },
+    RemoveMigration {
+        from: AssetInfo,
+    },
+    SweepMigrated {
+        from: AssetInfo,
+        recipient: Option<String>,
+    },
 }
 
+/// Available contract queries.
+/// - `ConversionStats` returns total converted amount along with the current epoch progress.
+/// - `EpochConversions` returns converted amounts of past epochs in ascending order.
+/// Epochs without conversions are omitted.
+/// - `Migrations` returns registered migrations along with the migrated amounts.
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(Config)]
     Config {},
+    #[returns(ConversionStatsResponse)]
+    ConversionStats {},
+    #[returns(Vec<EpochConversion>)]
+    EpochConversions {
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    #[returns(Vec<MigrationInfo>)]
+    Migrations {
+        start_after: Option<AssetInfo>,
+        limit: Option<u8>,
+    },
+}
+
+#[cw_serde]
+pub struct ConversionStatsResponse {
+    /// Total amount of converted old ASTRO
+    pub total_converted: Uint128,
+    /// Current epoch number
+    pub epoch: u64,
+    /// Current epoch start time
+    pub epoch_start: u64,
+    /// Amount of old ASTRO converted in the current epoch
+    pub epoch_converted: Uint128,
+    /// Max amount of old ASTRO which can be converted within one epoch. None means no limit
+    pub epoch_cap: Option<Uint128>,
+    /// Amount which can still be converted in the current epoch. None if there is no cap
+    pub epoch_remaining: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct EpochConversion {
+    /// Epoch number
+    pub epoch: u64,
+    /// Epoch start time
+    pub epoch_start: u64,
+    /// Amount of old ASTRO converted in this epoch
+    pub converted: Uint128,
+}
+
+#[cw_serde]
+pub struct MigrationInfo {
+    pub migration: Migration,
+    /// Total amount of `from` tokens migrated
+    pub total_migrated: Uint128,
 }
@@ -1,10 +1,19 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    attr, Addr, Api, CustomQuery, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    attr, Addr, Api, CustomQuery, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage,
 };
 use cw_storage_plus::Item;
 
-const MAX_PROPOSAL_TTL: u64 = 1209600;
+/// The maximum validity period of an ownership proposal
+pub const MAX_PROPOSAL_TTL: u64 = 1209600;
+/// The maximum delay between an ownership proposal and the moment it can be claimed
+pub const MAX_OWNERSHIP_TIMELOCK: u64 = 2592000;
+/// Stores the delay (in seconds) between an ownership proposal and the moment it can be claimed.
+/// Ownership can be claimed right after the proposal if it is not set
+pub const OWNERSHIP_TIMELOCK: Item<u64> = Item::new("ownership_timelock");
+/// Stores a decrease of [`OWNERSHIP_TIMELOCK`] which is not effective yet
+pub const PENDING_OWNERSHIP_TIMELOCK: Item<PendingOwnershipTimelock> =
+    Item::new("pending_ownership_timelock");
 /// Tokenfactory LP token subdenom
 pub const LP_SUBDENOM: &str = "astroport/share";
 
@@ -15,6 +24,18 @@ pub struct OwnershipProposal {
     pub owner: Addr,
     /// Time until the proposal to change ownership expires
     pub ttl: u64,
+    /// Time starting from which the proposal can be claimed
+    #[serde(default)]
+    pub claimable_from: u64,
+}
+
+/// This structure describes a scheduled decrease of the ownership timelock.
+#[cw_serde]
+pub struct PendingOwnershipTimelock {
+    /// The new delay in seconds
+    pub timelock: u64,
+    /// Time starting from which the new delay applies
+    pub effective_from: u64,
 }
 
 /// Creates a new request to change contract ownership.
@@ -22,6 +43,8 @@ pub struct OwnershipProposal {
 /// `new_owner` is the newly proposed owner.
 ///
 /// `expires_in` is the time during which the ownership change proposal is still valid.
+/// If the contract has an [`OWNERSHIP_TIMELOCK`], the proposal can be claimed only after the timelock
+/// and stays valid for `expires_in` seconds afterwards.
 ///
 /// `owner` is the current owner.
 ///
@@ -57,17 +80,22 @@ where
         )));
     }
 
+    let claimable_from =
+        env.block.time.seconds() + load_ownership_timelock(deps.storage, env.block.time.seconds())?;
+
     proposal.save(
         deps.storage,
         &OwnershipProposal {
             owner: new_owner.clone(),
-            ttl: env.block.time.seconds() + expires_in,
+            ttl: claimable_from + expires_in,
+            claimable_from,
         },
     )?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "propose_new_owner"),
         attr("new_owner", new_owner),
+        attr("claimable_from", claimable_from.to_string()),
     ]))
 }
 
@@ -120,6 +148,13 @@ where
         return Err(StdError::generic_err("Unauthorized"));
     }
 
+    if env.block.time.seconds() < p.claimable_from {
+        return Err(StdError::generic_err(format!(
+            "Ownership proposal can't be claimed until {}",
+            p.claimable_from
+        )));
+    }
+
     if env.block.time.seconds() > p.ttl {
         return Err(StdError::generic_err("Ownership proposal expired"));
     }
@@ -135,6 +170,92 @@ where
     ]))
 }
 
+/// Sets the delay between an ownership proposal and the moment it can be claimed.
+/// Pending proposals keep their claim time.
+/// An increase applies right away while a decrease applies only once the current timelock has passed,
+/// so that a compromised owner key can't shorten the timelock and transfer ownership at once.
+///
+/// `timelock` is the new delay in seconds. Zero disables the timelock.
+///
+/// `owner` is the current owner.
+///
+/// ## Executor
+/// Only the current owner can execute this.
+pub fn update_ownership_timelock<C, T>(
+    deps: DepsMut<C>,
+    info: MessageInfo,
+    env: Env,
+    timelock: u64,
+    owner: Addr,
+) -> StdResult<Response<T>>
+where
+    C: CustomQuery,
+{
+    // Permission check
+    if info.sender != owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    validate_ownership_timelock(timelock)?;
+
+    let block_ts = env.block.time.seconds();
+    let current_timelock = load_ownership_timelock(deps.storage, block_ts)?;
+
+    let mut attrs = vec![
+        attr("action", "update_ownership_timelock"),
+        attr("timelock", timelock.to_string()),
+    ];
+
+    if timelock >= current_timelock {
+        OWNERSHIP_TIMELOCK.save(deps.storage, &timelock)?;
+        PENDING_OWNERSHIP_TIMELOCK.remove(deps.storage);
+    } else {
+        let effective_from = block_ts + current_timelock;
+        PENDING_OWNERSHIP_TIMELOCK.save(
+            deps.storage,
+            &PendingOwnershipTimelock {
+                timelock,
+                effective_from,
+            },
+        )?;
+        attrs.push(attr("effective_from", effective_from.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Validates and saves the ownership timelock set on contract instantiation.
+pub fn init_ownership_timelock(storage: &mut dyn Storage, timelock: Option<u64>) -> StdResult<()> {
+    if let Some(timelock) = timelock {
+        validate_ownership_timelock(timelock)?;
+        OWNERSHIP_TIMELOCK.save(storage, &timelock)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the current ownership timelock. Applies a pending decrease once it is effective.
+pub fn load_ownership_timelock(storage: &mut dyn Storage, block_ts: u64) -> StdResult<u64> {
+    if let Some(pending) = PENDING_OWNERSHIP_TIMELOCK.may_load(storage)? {
+        if block_ts >= pending.effective_from {
+            OWNERSHIP_TIMELOCK.save(storage, &pending.timelock)?;
+            PENDING_OWNERSHIP_TIMELOCK.remove(storage);
+        }
+    }
+
+    Ok(OWNERSHIP_TIMELOCK.may_load(storage)?.unwrap_or_default())
+}
+
+fn validate_ownership_timelock(timelock: u64) -> StdResult<()> {
+    if timelock > MAX_OWNERSHIP_TIMELOCK {
+        return Err(StdError::generic_err(format!(
+            "Ownership timelock cannot be higher than {MAX_OWNERSHIP_TIMELOCK}"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Bulk validation and conversion between [`String`] -> [`Addr`] for an array of addresses.
 /// If any address is invalid, the function returns [`StdError`].
 pub fn validate_addresses(api: &dyn Api, admins: &[String]) -> StdResult<Vec<Addr>> {
@@ -0,0 +1,175 @@
+use cosmwasm_std::{attr, Addr, Attribute, Decimal, Uint128};
+use itertools::Itertools;
+
+use crate::asset::Asset;
+
+/// Version of the attribute schema emitted by pair contracts.
+/// Must be bumped whenever an attribute is renamed, removed or changes its meaning.
+pub const EVENTS_SCHEMA_VERSION: &str = "1";
+
+/// Attributes of a swap. Every pair type emits the same set of attributes in the same order.
+/// The trade direction is given by `offer_asset` -> `ask_asset`. All fee amounts are denominated
+/// in the ask asset. Pair specific attributes are appended after the common ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapEvent {
+    /// The pair contract address
+    pub pool: Addr,
+    pub sender: Addr,
+    pub receiver: Addr,
+    pub offer_asset: Asset,
+    /// The ask asset info and the amount sent to the receiver
+    pub return_asset: Asset,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+    pub maker_fee_amount: Uint128,
+    pub fee_share_amount: Uint128,
+}
+
+impl SwapEvent {
+    /// Share of the ideal (zero-slippage) output lost to the curve:
+    /// spread / (return + spread + commission).
+    pub fn price_impact(&self) -> Decimal {
+        let ideal_return = self.return_asset.amount + self.spread_amount + self.commission_amount;
+        if ideal_return.is_zero() {
+            Decimal::zero()
+        } else {
+            Decimal::from_ratio(self.spread_amount, ideal_return)
+        }
+    }
+
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        let price_impact = self.price_impact();
+
+        vec![
+            attr("action", "swap"),
+            attr("sender", self.sender),
+            attr("receiver", self.receiver),
+            attr("offer_asset", self.offer_asset.info.to_string()),
+            attr("ask_asset", self.return_asset.info.to_string()),
+            attr("offer_amount", self.offer_asset.amount),
+            attr("return_amount", self.return_asset.amount),
+            attr("spread_amount", self.spread_amount),
+            attr("commission_amount", self.commission_amount),
+            attr("maker_fee_amount", self.maker_fee_amount),
+            attr("fee_share_amount", self.fee_share_amount),
+            attr("price_impact", price_impact.to_string()),
+            attr("pool", self.pool),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+        ]
+    }
+}
+
+/// Attributes of a liquidity provision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvideLiquidityEvent {
+    /// The pair contract address
+    pub pool: Addr,
+    pub sender: Addr,
+    /// The receiver of LP tokens
+    pub receiver: Addr,
+    /// Deposited assets
+    pub assets: Vec<Asset>,
+    /// The amount of minted LP tokens
+    pub share: Uint128,
+}
+
+impl ProvideLiquidityEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "provide_liquidity"),
+            attr("sender", self.sender),
+            attr("receiver", self.receiver),
+            attr("assets", self.assets.iter().join(", ")),
+            attr("share", self.share),
+            attr("pool", self.pool),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+        ]
+    }
+}
+
+/// Attributes of a liquidity withdrawal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawLiquidityEvent {
+    /// The pair contract address
+    pub pool: Addr,
+    pub sender: Addr,
+    /// The amount of burned LP tokens
+    pub withdrawn_share: Uint128,
+    /// Assets sent to the sender
+    pub refund_assets: Vec<Asset>,
+}
+
+impl WithdrawLiquidityEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "withdraw_liquidity"),
+            attr("sender", self.sender),
+            attr("withdrawn_share", self.withdrawn_share),
+            attr("refund_assets", self.refund_assets.iter().join(", ")),
+            attr("pool", self.pool),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+        ]
+    }
+}
+
+/// Attributes of a flash loan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashLoanEvent {
+    /// The pair contract address
+    pub pool: Addr,
+    pub borrower: Addr,
+    /// The borrowed asset
+    pub loan: Asset,
+    /// The fee which must be returned on top of the loan
+    pub fee_amount: Uint128,
+}
+
+impl FlashLoanEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "flash_loan"),
+            attr("borrower", self.borrower),
+            attr("loan_asset", self.loan.info.to_string()),
+            attr("loan_amount", self.loan.amount),
+            attr("fee_amount", self.fee_amount),
+            attr("pool", self.pool),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+        ]
+    }
+}
+
+/// Attributes of a flash swap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashSwapEvent {
+    /// The pair contract address
+    pub pool: Addr,
+    pub borrower: Addr,
+    /// The asset sent to the borrower
+    pub ask_asset: Asset,
+    /// The payment due including the swap commission and the flash loan fee
+    pub offer_asset: Asset,
+    pub commission_amount: Uint128,
+    pub maker_fee_amount: Uint128,
+    pub fee_share_amount: Uint128,
+    /// The flash loan fee included in the payment
+    pub fee_amount: Uint128,
+}
+
+impl FlashSwapEvent {
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("action", "flash_swap"),
+            attr("borrower", self.borrower),
+            attr("ask_asset", self.ask_asset.info.to_string()),
+            attr("ask_amount", self.ask_asset.amount),
+            attr("offer_asset", self.offer_asset.info.to_string()),
+            attr("offer_amount", self.offer_asset.amount),
+            attr("commission_amount", self.commission_amount),
+            attr("maker_fee_amount", self.maker_fee_amount),
+            attr("fee_share_amount", self.fee_share_amount),
+            attr("fee_amount", self.fee_amount),
+            attr("pool", self.pool),
+            attr("schema_version", EVENTS_SCHEMA_VERSION),
+        ]
+    }
+}
@@ -1,12 +1,29 @@
 use crate::asset::{AssetInfo, PairInfo};
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{Addr, Binary, Decimal, StdError, StdResult};
 use std::fmt::{Display, Formatter, Result};
 
 const MAX_TOTAL_FEE_BPS: u16 = 10_000;
 const MAX_MAKER_FEE_BPS: u16 = 10_000;
 
+/// Maximum length of a pair display name
+pub const MAX_PAIR_NAME_LENGTH: usize = 64;
+/// Maximum length of a pair description
+pub const MAX_PAIR_DESCRIPTION_LENGTH: usize = 512;
+/// Maximum length of a pair logo URI
+pub const MAX_PAIR_LOGO_URI_LENGTH: usize = 256;
+/// Maximum number of tags attached to a pair
+pub const MAX_PAIR_TAGS: usize = 10;
+/// Maximum length of a single pair tag
+pub const MAX_PAIR_TAG_LENGTH: usize = 32;
+
+/// Factory storage key of the flag pausing all pairs. Other contracts read it with a raw query
+pub const PAIRS_PAUSED_KEY: &str = "pairs_paused";
+/// Factory storage namespace of individually paused pairs keyed by pair address.
+/// Other contracts read it with a raw query
+pub const PAUSED_PAIRS_NAMESPACE: &str = "paused_pairs";
+
 /// This structure holds the main contract parameters.
 #[cw_serde]
 pub struct Config {
@@ -16,6 +33,10 @@ pub struct Config {
     pub token_code_id: u64,
     /// Incentives contract address
     pub generator_address: Option<Addr>,
+    /// Staking contract which receives auto-staked LP tokens by default instead of `generator_address`.
+    /// Liquidity providers can still choose either of them explicitly.
+    #[serde(default)]
+    pub auto_stake_target: Option<Addr>,
     /// Contract address to send governance fees to (the Maker contract)
     pub fee_address: Option<Addr>,
     /// CW1 whitelist contract code id used to store 3rd party incentives staking rewards
@@ -75,10 +96,17 @@ pub struct PairConfig {
     /// Default is false.
     #[serde(default)]
     pub is_generator_disabled: bool,
-    /// If pool type is permissioned, only factory owner can create pairs of this type.
+    /// If pool type is permissioned, only factory owner and allowlisted pair creators
+    /// can create pairs of this type.
     /// Default is false.
     #[serde(default)]
     pub permissioned: bool,
+    /// Minimum swap size in whole tokens, i.e. with the offer asset precision applied.
+    /// Concentrated pairs convert the offer amount to the first pool asset using the price scale.
+    /// Stable and concentrated pairs of this type read it at instantiation and reject smaller swaps.
+    /// Default is None.
+    #[serde(default)]
+    pub min_trade_notional: Option<Decimal>,
 }
 
 impl PairConfig {
@@ -107,6 +135,9 @@ pub struct InstantiateMsg {
     pub coin_registry_address: String,
     /// Config for the tracking contract
     pub tracker_config: Option<TrackerConfig>,
+    /// Delay in seconds between an ownership proposal and the moment it can be claimed. Disabled if not set
+    #[serde(default)]
+    pub ownership_timelock: Option<u64>,
 }
 
 /// This structure describes the execute messages of the contract.
@@ -162,6 +193,95 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Used to claim contract ownership.
     ClaimOwnership {},
+    /// Sets the delay (in seconds) between an ownership proposal and the moment it can be claimed.
+    /// Only the owner can execute this.
+    UpdateOwnershipTimelock { timelock: u64 },
+    /// Proposes the current factory owner as a new owner on all registered pairs which keep their own owner.
+    /// Pairs are processed in batches. Every subsequent call continues from the last processed pair.
+    /// Only the owner can execute this.
+    PropagateOwnership {
+        /// The date after which pair ownership proposals expire
+        expires_in: u64,
+        /// The number of pairs to process in this batch
+        limit: Option<u32>,
+    },
+    /// Sets or removes (if `metadata` is None) display metadata of a registered pair.
+    /// Only the owner can execute this.
+    UpdatePairMetadata {
+        /// Pair contract address
+        pair_addr: String,
+        /// New pair metadata which fully replaces the previous one
+        metadata: Option<PairMetadata>,
+    },
+    /// Schedules code migrations of registered pairs. This is the first step of a two-step migration.
+    /// Scheduled pairs report the upcoming migration via their `migration_status` query,
+    /// so frontends can warn users in advance. The factory must be the admin of every pair.
+    /// Scheduling an already scheduled pair overrides its target code.
+    /// Only the owner can execute this.
+    SchedulePairMigrations {
+        /// Pair contract addresses
+        pairs: Vec<String>,
+        /// The code ID the pairs are migrated to
+        new_code_id: u64,
+        /// The message passed to the `migrate` entry point of the pairs
+        migrate_msg: Binary,
+    },
+    /// Removes pairs from the migration schedule. Only the owner can execute this.
+    CancelPairMigrations {
+        /// Pair contract addresses
+        pairs: Vec<String>,
+    },
+    /// Migrates the next batch of scheduled pairs in the scheduling order.
+    /// This is the second step of a two-step migration. Only the owner can execute this.
+    MigratePairs {
+        /// The number of pairs to migrate in this batch
+        limit: Option<u32>,
+    },
+    /// Enables or disables flash loans in all pairs. Pairs must also opt in individually.
+    /// Only the owner can execute this.
+    UpdateFlashLoans { enabled: bool },
+    /// Sets or removes (if `decimals` is None) the decimals override of an asset.
+    /// Pairs which rely on asset precisions use the override instead of resolving decimals
+    /// from the coin registry or cw20 token info. Only the owner can execute this.
+    SetDecimalsOverride {
+        /// The asset to override decimals for
+        asset_info: AssetInfo,
+        /// The decimals pairs must use for this asset
+        decimals: Option<u8>,
+    },
+    /// Adds or removes addresses allowed to create pairs of a permissioned pair type along with the owner.
+    /// Adding an already allowed address overrides its expiry.
+    /// Only the owner or the pair creators guardian can execute this.
+    UpdatePairCreators {
+        /// The pair type the allowlist belongs to
+        pair_type: PairType,
+        /// Addresses to allow along with optional expiry timestamps (in seconds)
+        add: Option<Vec<(String, Option<u64>)>>,
+        /// Addresses to remove from the allowlist
+        remove: Option<Vec<String>>,
+    },
+    /// Sets or removes (if `guardian` is None) the address which manages pair creator allowlists
+    /// along with the owner. Only the owner can execute this.
+    UpdatePairCreatorsGuardian { guardian: Option<String> },
+    /// Sets or removes (if `guardian` is None) the address which can pause pair creation and pairs
+    /// along with the owner. Only the owner can execute this.
+    UpdatePauseGuardian { guardian: Option<String> },
+    /// Sets or removes (if `auto_stake_target` is None) the staking contract which receives auto-staked LP tokens
+    /// instead of `generator_address`. Only the owner can execute this.
+    UpdateAutoStakeTarget { auto_stake_target: Option<String> },
+    /// Pauses or unpauses pair creation, all pairs at once or specific registered pairs.
+    /// Paused pairs reject swaps and liquidity provision while withdrawals are still allowed.
+    /// Only the owner or the pause guardian can execute this.
+    UpdatePauseState {
+        /// Whether pair creation is paused
+        pair_creation: Option<bool>,
+        /// Whether all pairs are paused regardless of individually paused pairs
+        all_pairs: Option<bool>,
+        /// Pair contract addresses to pause
+        pause: Option<Vec<String>>,
+        /// Pair contract addresses to unpause
+        unpause: Option<Vec<String>>,
+    },
 }
 
 /// This structure describes the available query messages for the factory contract.
@@ -196,6 +316,106 @@ pub enum QueryMsg {
     BlacklistedPairTypes {},
     #[returns(TrackerConfig)]
     TrackerConfig {},
+    /// Returns display metadata of a specific pair
+    #[returns(Option<PairMetadata>)]
+    PairMetadata {
+        /// Pair contract address
+        pair_addr: String,
+    },
+    /// Same as [`QueryMsg::Pairs`] but each pair is accompanied with its metadata
+    #[returns(PairsWithMetadataResponse)]
+    PairsWithMetadata {
+        /// The pair item to start reading from
+        start_after: Option<Vec<AssetInfo>>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+    },
+    /// Returns pairs scheduled for migration in the scheduling order
+    #[returns(Vec<PairMigrationInfo>)]
+    PairMigrations {
+        /// The pair contract address to start reading from
+        start_after: Option<String>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+    },
+    /// Returns whether flash loans are enabled in the factory
+    #[returns(bool)]
+    FlashLoansEnabled {},
+    /// Returns the decimals override of an asset
+    #[returns(Option<u8>)]
+    DecimalsOverride { asset_info: AssetInfo },
+    /// Returns addresses allowed to create pairs of a pair type including expired ones
+    #[returns(Vec<PairCreator>)]
+    PairCreators {
+        /// The pair type the allowlist belongs to
+        pair_type: PairType,
+        /// The address to start reading from
+        start_after: Option<String>,
+        /// The number of addresses to read and return
+        limit: Option<u32>,
+    },
+    /// Returns the address which manages pair creator allowlists along with the owner
+    #[returns(Option<Addr>)]
+    PairCreatorsGuardian {},
+    /// Returns the pause guardian and whether pair creation and all pairs are paused
+    #[returns(PauseStateResponse)]
+    PauseState {},
+    /// Returns individually paused pairs
+    #[returns(Vec<Addr>)]
+    PausedPairs {
+        /// The pair contract address to start reading from
+        start_after: Option<String>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+    },
+}
+
+/// This structure describes the factory pause state.
+#[cw_serde]
+pub struct PauseStateResponse {
+    /// The address which can pause pair creation and pairs along with the owner
+    pub guardian: Option<Addr>,
+    /// Whether pair creation is paused
+    pub pair_creation_paused: bool,
+    /// Whether all pairs are paused
+    pub all_pairs_paused: bool,
+}
+
+/// An address allowed to create pairs of a permissioned pair type.
+#[cw_serde]
+pub struct PairCreator {
+    /// The allowed address
+    pub address: Addr,
+    /// Timestamp (in seconds) starting from which the address can't create pairs anymore.
+    /// The address never expires if not set
+    pub expires_at: Option<u64>,
+}
+
+impl PairCreator {
+    /// Returns whether the address can still create pairs at the specified time.
+    pub fn is_active(&self, block_ts: u64) -> bool {
+        !matches!(self.expires_at, Some(expires_at) if block_ts >= expires_at)
+    }
+}
+
+/// A scheduled pair code migration.
+#[cw_serde]
+pub struct PairMigration {
+    /// The code ID the pair is migrated to
+    pub new_code_id: u64,
+    /// The message passed to the `migrate` entry point of the pair
+    pub migrate_msg: Binary,
+}
+
+/// The progress of a scheduled pair migration.
+#[cw_serde]
+pub struct PairMigrationInfo {
+    /// Pair contract address
+    pub pair_addr: Addr,
+    /// The code ID the pair is currently running
+    pub current_code_id: u64,
+    /// The code ID the pair is migrated to
+    pub new_code_id: u64,
 }
 
 #[cw_serde]
@@ -216,6 +436,8 @@ pub struct ConfigResponse {
     pub fee_address: Option<Addr>,
     /// Address of contract used to auto_stake LP tokens for Astroport pairs that are incentivized
     pub generator_address: Option<Addr>,
+    /// Staking contract which receives auto-staked LP tokens by default instead of `generator_address`
+    pub auto_stake_target: Option<Addr>,
     /// CW1 whitelist contract code id used to store 3rd party rewards for staking Astroport LP tokens
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins and their accuracy
@@ -229,6 +451,78 @@ pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
 }
 
+/// Display metadata of a pair maintained by the factory owner.
+#[cw_serde]
+#[derive(Default)]
+pub struct PairMetadata {
+    /// Human readable pair name
+    pub display_name: Option<String>,
+    /// Pair description
+    pub description: Option<String>,
+    /// Pair logo URI
+    pub logo_uri: Option<String>,
+    /// Pair tags, e.g. "stable" or "bluechip". Only lowercase alphanumeric characters and '-' are allowed
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl PairMetadata {
+    /// Checks metadata fields lengths and tags format.
+    pub fn validate(&self) -> StdResult<()> {
+        let check_len = |field: &str, value: &Option<String>, max_len: usize| match value {
+            Some(value) if value.is_empty() || value.len() > max_len => Err(StdError::generic_err(
+                format!("{field} length must be within [1, {max_len}]"),
+            )),
+            _ => Ok(()),
+        };
+        check_len("display_name", &self.display_name, MAX_PAIR_NAME_LENGTH)?;
+        check_len(
+            "description",
+            &self.description,
+            MAX_PAIR_DESCRIPTION_LENGTH,
+        )?;
+        check_len("logo_uri", &self.logo_uri, MAX_PAIR_LOGO_URI_LENGTH)?;
+
+        if self.tags.len() > MAX_PAIR_TAGS {
+            return Err(StdError::generic_err(format!(
+                "Pair can't have more than {MAX_PAIR_TAGS} tags"
+            )));
+        }
+
+        for (i, tag) in self.tags.iter().enumerate() {
+            let valid_chars = tag
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+            if tag.is_empty() || tag.len() > MAX_PAIR_TAG_LENGTH || !valid_chars {
+                return Err(StdError::generic_err(format!(
+                    "Invalid tag {tag}. Tag must be 1-{MAX_PAIR_TAG_LENGTH} characters long and contain only lowercase alphanumeric characters or '-'"
+                )));
+            }
+            if self.tags[..i].contains(tag) {
+                return Err(StdError::generic_err(format!("Duplicated tag {tag}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pair information accompanied with its metadata.
+#[cw_serde]
+pub struct PairWithMetadata {
+    /// Pair information
+    pub pair_info: PairInfo,
+    /// Pair metadata if it was set by the factory owner
+    pub metadata: Option<PairMetadata>,
+}
+
+/// A custom struct for each query response that returns an array of objects of type [`PairWithMetadata`].
+#[cw_serde]
+pub struct PairsWithMetadataResponse {
+    /// Arrays of structs containing information about multiple pairs and their metadata
+    pub pairs: Vec<PairWithMetadata>,
+}
+
 /// A custom struct for each query response that returns an object of type [`FeeInfoResponse`].
 #[cw_serde]
 pub struct FeeInfoResponse {
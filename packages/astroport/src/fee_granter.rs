@@ -1,5 +1,13 @@
+use cosmos_sdk_proto::cosmos::feegrant::v1beta1::QueryAllowanceRequest;
+use cosmwasm_schema::serde::Deserialize;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{
+    Addr, Binary, Coin, CustomQuery, QuerierWrapper, QueryRequest, StdResult, Uint128,
+};
+use prost::Message;
+
+/// Stargate path of the feegrant module query returning the allowance granted to a grantee.
+pub const FEE_ALLOWANCE_QUERY_PATH: &str = "/cosmos.feegrant.v1beta1.Query/Allowance";
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -21,6 +29,22 @@ pub enum ExecuteMsg {
         #[serde(default)]
         bypass_amount_check: bool,
     },
+    /// Create renewable grant for a contract. Grantee receives `amount` allowance every `period` seconds
+    /// (previous allowance leftovers are not carried over). The first allowance is granted right away.
+    /// Fee granter must hold enough coins to cover renewals.
+    /// Executor: owner or admin.
+    GrantRenewable {
+        grantee_contract: String,
+        amount: Uint128,
+        /// Renewal period in seconds
+        period: u64,
+        /// Same as in [`ExecuteMsg::Grant`]. Applies only to the first allowance.
+        #[serde(default)]
+        bypass_amount_check: bool,
+    },
+    /// Reset allowance of a renewable grant to its full amount once the renewal period has lapsed.
+    /// Executor: grantee.
+    Renew {},
     /// Revoke grant for a contract. Some coins may be left in fee_granter account.
     /// Executor: owner or admin.
     Revoke { grantee_contract: String },
@@ -64,6 +88,17 @@ pub enum QueryMsg {
     },
     #[returns(GrantResponse)]
     GrantFor { grantee_contract: String },
+    #[returns(Vec<RenewableGrantResponse>)]
+    RenewableGrantsList {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(RenewableGrantResponse)]
+    RenewableGrantFor { grantee_contract: String },
+    /// Returns the unspent part of the allowance granted to a contract as tracked by the feegrant module.
+    /// Returns zero if the allowance was fully spent or revoked.
+    #[returns(Uint128)]
+    RemainingAllowance { grantee_contract: String },
 }
 
 #[cw_serde]
@@ -78,3 +113,88 @@ pub struct GrantResponse {
     pub grantee_contract: String,
     pub amount: Uint128,
 }
+
+#[cw_serde]
+pub struct RenewableGrant {
+    /// Allowance granted every period
+    pub amount: Uint128,
+    /// Renewal period in seconds
+    pub period: u64,
+    /// Timestamp (in seconds) of the last renewal
+    pub last_renewal: u64,
+}
+
+#[cw_serde]
+pub struct RenewableGrantResponse {
+    pub grantee_contract: String,
+    /// Allowance granted for the current period.
+    /// Unspent part is returned by [`QueryMsg::RemainingAllowance`].
+    pub amount: Uint128,
+    /// Renewal period in seconds
+    pub period: u64,
+    /// Timestamp (in seconds) of the last renewal
+    pub last_renewal: u64,
+    /// Timestamp (in seconds) after which the grant can be renewed
+    pub next_renewal: u64,
+}
+
+/// Feegrant module basic allowance as returned by the Allowance stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct FeeAllowance {
+    #[serde(default)]
+    pub spend_limit: Vec<Coin>,
+}
+
+/// Feegrant module grant as returned by the Allowance stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct FeeGrant {
+    pub allowance: Option<FeeAllowance>,
+}
+
+/// Response of the Allowance stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct FeeAllowanceResponse {
+    pub allowance: Option<FeeGrant>,
+}
+
+/// Queries the feegrant module for the remaining `denom` spend limit granted by `granter` to `grantee`.
+/// Returns `None` if there is no allowance. The feegrant module prunes an allowance once it is fully spent.
+pub fn query_fee_allowance<C>(
+    querier: &QuerierWrapper<C>,
+    granter: impl Into<String>,
+    grantee: impl Into<String>,
+    denom: &str,
+) -> StdResult<Option<Uint128>>
+where
+    C: CustomQuery,
+{
+    let request = QueryAllowanceRequest {
+        granter: granter.into(),
+        grantee: grantee.into(),
+    };
+    let response: StdResult<FeeAllowanceResponse> = querier.query(&QueryRequest::Stargate {
+        path: FEE_ALLOWANCE_QUERY_PATH.to_string(),
+        data: Binary(request.encode_to_vec()),
+    });
+
+    match response {
+        Ok(FeeAllowanceResponse { allowance }) => Ok(allowance.map(|grant| {
+            grant
+                .allowance
+                .and_then(|allowance| {
+                    allowance
+                        .spend_limit
+                        .into_iter()
+                        .find(|coin| coin.denom == denom)
+                })
+                .map(|coin| coin.amount)
+                .unwrap_or_default()
+        })),
+        // The feegrant module returns an error if the allowance doesn't exist
+        Err(err) if err.to_string().contains("not found") => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -1,7 +1,7 @@
 use std::hash::{Hash, Hasher};
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Decimal256, Env, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Env, StdError, StdResult, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::asset::{Asset, AssetInfo};
@@ -12,15 +12,43 @@ pub const EPOCH_LENGTH: u64 = 86400 * 7;
 pub const EPOCHS_START: u64 = 1696809600;
 /// Maximum allowed reward schedule duration (~6 month)
 pub const MAX_PERIODS: u64 = 25;
+/// Maximum allowed block based reward schedule duration (~6 month with 3 seconds blocks)
+pub const MAX_SCHEDULE_BLOCKS: u64 = 5_184_000;
 /// Maximum allowed external reward tokens per pool
 pub const MAX_REWARD_TOKENS: u8 = 5;
 
 /// Max items per page in queries
 pub const MAX_PAGE_LIMIT: u8 = 50;
 
+/// Max number of stale pools updated by a single UpdatePools call
+pub const MAX_POOL_UPDATES_LIMIT: u8 = 30;
+
+/// Max number of positions indexed by a single BuildStakersIndex call
+pub const MAX_STAKERS_INDEX_LIMIT: u32 = 200;
+
 /// Max number of orphaned rewards to claim at a time
 pub const MAX_ORPHANED_REWARD_LIMIT: u8 = 10;
 
+/// Maximum allowed vesting duration of claimed external rewards (1 year)
+pub const MAX_VESTING_DURATION: u64 = 86400 * 365;
+
+/// The lowest factor the emission controller can scale pool ASTRO emissions down to
+pub const MIN_EMISSION_SCALE: Decimal = Decimal::percent(10);
+/// Maximum duration of emission scaling set by the emission controller (2 weeks)
+pub const MAX_EMISSION_SCALE_DURATION: u64 = 86400 * 14;
+
+/// Maximum length of a reward token symbol
+pub const MAX_REWARD_SYMBOL_LENGTH: usize = 32;
+/// Maximum length of reward token logo URI and project URL
+pub const MAX_REWARD_URI_LENGTH: usize = 256;
+/// Maximum reward token decimals
+pub const MAX_REWARD_DECIMALS: u8 = 18;
+
+/// Maximum number of staking hooks per pool
+pub const MAX_STAKING_HOOKS: usize = 5;
+/// Gas limit of a single staking hook call
+pub const STAKING_HOOK_GAS_LIMIT: u64 = 300_000;
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
@@ -29,12 +57,50 @@ pub struct InstantiateMsg {
     pub vesting_contract: String,
     pub incentivization_fee_info: Option<IncentivizationFeeInfo>,
     pub guardian: Option<String>,
+    /// Delay in seconds between an ownership proposal and the moment it can be claimed. Disabled if not set
+    #[serde(default)]
+    pub ownership_timelock: Option<u64>,
 }
 
 #[cw_serde]
 pub struct InputSchedule {
     pub reward: Asset,
     pub duration_periods: u64,
+    /// If set, claimed rewards vest linearly over this period (in seconds) instead of being transferred right away.
+    /// Must match the vesting duration of the same reward token if it is already active in the pool.
+    pub vesting_duration: Option<u64>,
+    /// If set, tops up the pooled schedule with this id instead of creating a new one.
+    /// Top-ups are accepted until the first full epoch of the schedule starts.
+    /// The pool, reward token and `duration_periods` must match the pooled schedule.
+    #[serde(default)]
+    pub schedule_id: Option<u64>,
+    /// Defines whether rewards are distributed per second or per block.
+    /// Block based schedules ignore `duration_periods` and can't be pooled
+    #[serde(default)]
+    pub kind: ScheduleKind,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum ScheduleKind {
+    /// Rewards are distributed per second over whole epochs
+    #[default]
+    Time,
+    /// Rewards are distributed per block from `start_height` till `end_height`.
+    /// Intended for chains with irregular block time
+    Blocks { start_height: u64, end_height: u64 },
+}
+
+/// Checks that vesting duration of claimed rewards is within allowed limits.
+fn validate_vesting_duration(vesting_duration: Option<u64>) -> StdResult<()> {
+    if matches!(vesting_duration, Some(duration) if duration > MAX_VESTING_DURATION || duration == 0)
+    {
+        return Err(StdError::generic_err(format!(
+            "Vesting duration must be between 1 and {MAX_VESTING_DURATION} seconds",
+        )));
+    }
+
+    Ok(())
 }
 
 #[cw_serde]
@@ -58,6 +124,8 @@ impl IncentivesSchedule {
             )));
         }
 
+        validate_vesting_duration(input.vesting_duration)?;
+
         let block_ts = env.block.time.seconds();
 
         let rem = block_ts % EPOCHS_START;
@@ -85,6 +153,63 @@ impl IncentivesSchedule {
     }
 }
 
+#[cw_serde]
+pub struct BlockIncentivesSchedule {
+    /// Block height when the schedule starts
+    pub start_height: u64,
+    /// Block height when the schedule ends
+    pub end_height: u64,
+    /// Reward asset info
+    pub reward_info: AssetInfo,
+    /// Reward per block for the whole schedule
+    pub rpb: Decimal256,
+}
+
+impl BlockIncentivesSchedule {
+    /// Creates a new block based incentives schedule from the block range set in the input.
+    pub fn from_input(env: &Env, input: &InputSchedule) -> StdResult<Self> {
+        let ScheduleKind::Blocks {
+            start_height,
+            end_height,
+        } = input.kind
+        else {
+            return Err(StdError::generic_err("Schedule must be block based"));
+        };
+
+        if input.schedule_id.is_some() {
+            return Err(StdError::generic_err(
+                "Block based schedules can't be pooled",
+            ));
+        }
+
+        validate_vesting_duration(input.vesting_duration)?;
+
+        if start_height < env.block.height
+            || end_height <= start_height
+            || end_height - start_height > MAX_SCHEDULE_BLOCKS
+        {
+            return Err(StdError::generic_err(format!(
+                "Schedule must start not earlier than the current block and last from 1 to {MAX_SCHEDULE_BLOCKS} blocks",
+            )));
+        }
+
+        let rpb = Decimal256::from_ratio(input.reward.amount, end_height - start_height);
+
+        if rpb < Decimal256::one() {
+            return Err(StdError::generic_err(format!(
+                "Reward per block must be at least 1 unit but actual is {rpb}",
+            )));
+        }
+
+        Ok(Self {
+            start_height,
+            end_height,
+            reward_info: input.reward.info.clone(),
+            rpb,
+        })
+    }
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Setup generators with their respective allocation points.
@@ -94,15 +219,39 @@ pub enum ExecuteMsg {
         pools: Vec<(String, Uint128)>,
     },
     /// Update rewards and return it to user.
+    /// Rewards with vesting are added to the user's vesting positions instead.
     ClaimRewards {
         /// The LP token cw20 address or token factory denom
         lp_tokens: Vec<String>,
+        /// The position owner who approved the sender via [`ExecuteMsg::ApproveClaimOperator`].
+        /// Rewards are always sent to the position owner or its reward receiver.
+        #[serde(default)]
+        on_behalf_of: Option<String>,
     },
+    /// Same as [`ExecuteMsg::ClaimRewards`] but all claimed ASTRO is staked in the staking contract
+    /// with the sender or its reward receiver as the xASTRO receiver. Other rewards are sent as usual.
+    ClaimIntoXastro {
+        /// The LP token cw20 address or token factory denom
+        lp_tokens: Vec<String>,
+    },
+    /// Same as [`ExecuteMsg::ClaimRewards`] but rewards paid in LP tokens of other pools registered
+    /// in the factory are deposited on behalf of the sender or its reward receiver. Other rewards are sent as usual.
+    ClaimAndStakeLpRewards {
+        /// The LP token cw20 address or token factory denom
+        lp_tokens: Vec<String>,
+    },
+    /// Claim all vested rewards.
+    ClaimVested {},
     /// Receives a message of type [`Cw20ReceiveMsg`]. Handles cw20 LP token deposits.
     Receive(Cw20ReceiveMsg),
     /// Stake LP tokens in the Generator. LP tokens staked on behalf of recipient if recipient is set.
     /// Otherwise LP tokens are staked on behalf of message sender.
-    Deposit { recipient: Option<String> },
+    /// The referrer is recorded only when a new position is opened and stays with the position until it is closed.
+    Deposit {
+        recipient: Option<String>,
+        #[serde(default)]
+        referrer: Option<String>,
+    },
     /// Withdraw LP tokens from the Generator
     Withdraw {
         /// The LP token cw20 address or token factory denom
@@ -130,6 +279,16 @@ pub enum ExecuteMsg {
     },
     /// Same as Incentivize endpoint but for multiple pools in one go.
     IncentivizeMany(Vec<(String, InputSchedule)>),
+    /// Adds rewards to the active time based schedules of a reward token already present in the pool.
+    /// The amount is spread evenly from now till the end of the last schedule of this token
+    /// thus neither a new reward token slot nor the incentivization fee is required.
+    /// NOTE: Sender must approve allowance for cw20 reward tokens to this contract.
+    TopUpSchedule {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+        /// Reward asset added to the active schedules
+        reward: Asset,
+    },
     /// Remove specific reward token from the pool.
     /// Only the owner can execute this.
     RemoveRewardFromPool {
@@ -181,11 +340,97 @@ pub enum ExecuteMsg {
         #[serde(default)]
         remove: Vec<AssetInfo>,
     },
+    /// Set or remove the emission controller.
+    /// Only the owner can execute this.
+    UpdateEmissionController {
+        /// The new emission controller address. None removes the controller
+        controller: Option<String>,
+    },
+    /// Set or remove the ASTRO staking contract used by [`ExecuteMsg::ClaimIntoXastro`].
+    /// Only the owner can execute this.
+    UpdateXastroStaking {
+        /// The staking contract address. None disables claiming into xASTRO
+        staking_contract: Option<String>,
+    },
+    /// Enable or disable minting of stake receipts on deposits.
+    /// Receipts which were already minted must still be burned to withdraw the positions they represent.
+    /// Only the owner can execute this.
+    UpdateStakeReceipts { enabled: bool },
+    /// Enable or disable pulling ASTRO rewards from the vesting contract once per epoch.
+    /// While enabled, the first claim which can't be covered by already pulled ASTRO pulls the whole epoch budget
+    /// (or as much as the vesting contract has available). Claims exceeding pulled ASTRO claim the remainder
+    /// from vesting directly. Pulled ASTRO left after disabling is still paid out first.
+    /// Only the owner can execute this.
+    UpdateAstroPullBatching { enabled: bool },
+    /// Set or disable (if `config` is None) reward schedule cancellation by schedule creators.
+    /// Only the owner can execute this.
+    UpdateScheduleCancellation {
+        config: Option<ScheduleCancellationConfig>,
+    },
+    /// Cancel the remaining part of the reward schedules funded by the sender in a specific pool.
+    /// Schedules stop at the first epoch start after the notice period set in [`ScheduleCancellationConfig`].
+    /// Undistributed rewards are sent to the receiver. Schedules which are still locked are not affected.
+    CancelSchedule {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+        /// The reward token cw20 address or token factory denom
+        reward: String,
+        /// The receiver of undistributed rewards. Defaults to the sender
+        receiver: Option<String>,
+    },
+    /// Temporarily scale down ASTRO emissions of an active pool, e.g. during an exploit investigation.
+    /// Scaling expires automatically after the specified duration.
+    /// Only the emission controller can execute this.
+    ScaleEmissions {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+        /// The factor applied to the pool allocation points.
+        /// Must be within [`MIN_EMISSION_SCALE`] (inclusive) and 1 (exclusive)
+        factor: Decimal,
+        /// Scaling duration in seconds. Must not exceed [`MAX_EMISSION_SCALE_DURATION`]
+        duration: u64,
+    },
+    /// Restore full ASTRO emissions of a pool before the scaling expires.
+    /// Only the owner or the emission controller can execute this.
+    RestoreEmissions {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+    },
+    /// Pause a pool in case its underlying pair is compromised.
+    /// Paused pool doesn't accept deposits and new reward schedules. Reward indexes are frozen at pause time:
+    /// ASTRO rewards are not accrued and external rewards of the paused period go to orphaned rewards.
+    /// Withdrawals and claims of rewards accrued before the pause are always allowed.
+    /// Only the owner or guardian can execute this.
+    PausePool {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+    },
+    /// Resume deposits and reward accrual in a paused pool.
+    /// Only the owner can execute this.
+    UnpausePool {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+    },
     /// Only factory can set the allocation points to zero for the specified pool.
     /// Initiated from deregistration context in factory.
     DeactivatePool { lp_token: String },
     /// Go through active pools and deactivate the ones which pair type is blocked
     DeactivateBlockedPools {},
+    /// Apply pending ASTRO emission changes to the most outdated pools.
+    /// Emission changes are picked up lazily by each pool on its next update
+    /// thus keepers can use this endpoint to keep rarely touched pools in sync.
+    /// Anyone can execute this.
+    UpdatePools {
+        /// Max number of pools to update. Default and max: [`MAX_POOL_UPDATES_LIMIT`]
+        limit: Option<u8>,
+    },
+    /// Continue indexing positions opened before the stakers by amount index was introduced.
+    /// Migration indexes only the first batch, the rest is picked up from the stored cursor.
+    /// Anyone can execute this.
+    BuildStakersIndex {
+        /// Max number of positions to index. Default and max: [`MAX_STAKERS_INDEX_LIMIT`]
+        limit: Option<u32>,
+    },
     /// Creates a request to change contract ownership
     /// Only the current owner can execute this.
     ProposeNewOwner {
@@ -200,6 +445,66 @@ pub enum ExecuteMsg {
     /// Claims contract ownership
     /// Only the newly proposed owner can execute this
     ClaimOwnership {},
+    /// Sets the delay (in seconds) between an ownership proposal and the moment it can be claimed.
+    /// Only the owner can execute this.
+    UpdateOwnershipTimelock { timelock: u64 },
+    /// Sets or removes (if `metadata` is None) display metadata of a reward token.
+    /// The reward creator (the first address which incentivized a pool with this token) can set unverified metadata.
+    /// The owner or guardian can set verified metadata or remove metadata of any reward token.
+    SetRewardMetadata {
+        /// The reward token cw20 address or token factory denom
+        reward: String,
+        /// New reward metadata which fully replaces the previous one
+        metadata: Option<RewardTokenMetadata>,
+    },
+    /// Add or remove contracts notified about position changes and claims in a pool.
+    /// Hooks are called with [`StakingHookMsg`] on a best-effort basis:
+    /// each call is gas-limited and its failure doesn't revert the user action.
+    /// Only the owner can execute this.
+    UpdateStakingHooks {
+        lp_token: String,
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    /// Allows the operator to claim the sender's rewards via [`ExecuteMsg::ClaimRewards`].
+    /// Approving an already approved operator overrides its expiry.
+    ApproveClaimOperator {
+        operator: String,
+        /// Timestamp (in seconds) starting from which the approval is no longer valid.
+        /// The approval never expires if not set
+        expires_at: Option<u64>,
+    },
+    /// Revokes the operator approval given by the sender
+    RevokeClaimOperator { operator: String },
+    /// Sets the address receiving the sender's rewards from the specified pool,
+    /// including rewards with vesting, staked ASTRO and staked LP rewards.
+    /// The position itself stays with the sender. Rewards are sent to the sender if the receiver is not set
+    SetRewardReceiver {
+        /// The LP token cw20 address or token factory denom
+        lp_token: String,
+        receiver: Option<String>,
+    },
+}
+
+/// The callback message which staking hook contracts must implement.
+#[cw_serde]
+pub enum StakingHookMsg {
+    /// Sent after a user's position changed due to a deposit, withdrawal or stake receipt transfer
+    PositionChanged {
+        lp_token: String,
+        user: String,
+        /// The staked amount after the change
+        amount: Uint128,
+    },
+    /// Sent after a user claimed rewards from the pool
+    RewardsClaimed { lp_token: String, user: String },
+}
+
+/// Deposit message of the legacy generator which runs side by side with the incentives contract during migration.
+/// Unlike [`ExecuteMsg::Deposit`], it has no referrer.
+#[cw_serde]
+pub enum LegacyGeneratorExecuteMsg {
+    Deposit { recipient: Option<String> },
 }
 
 #[cw_serde]
@@ -207,6 +512,8 @@ pub enum ExecuteMsg {
 pub enum Cw20Msg {
     Deposit {
         recipient: Option<String>,
+        #[serde(default)]
+        referrer: Option<String>,
     },
     /// Besides this enum variant is redundant we keep this for backward compatibility with old pair contracts
     DepositFor(String),
@@ -237,6 +544,15 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u8>,
     },
+    /// Returns a list of pool stakers ordered by staked amount descending (ties are ordered by address).
+    /// Pass the last item of the previous page as `start_after` to continue paging.
+    /// Fails until positions opened before the index was introduced are indexed (see BuildStakersIndex).
+    #[returns(PoolStakersByAmountResponse)]
+    PoolStakersByAmount {
+        lp_token: String,
+        start_after: Option<PoolStaker>,
+        limit: Option<u8>,
+    },
     /// Returns paginated list of blocked tokens
     #[returns(Vec<AssetInfo>)]
     BlockedTokensList {
@@ -257,6 +573,17 @@ pub enum QueryMsg {
         /// Limit number of returned schedules.
         limit: Option<u8>,
     },
+    /// Returns the list of block based reward schedules for the specified LP token
+    #[returns(Vec<BlockScheduleResponse>)]
+    BlockRewardSchedules {
+        /// Reward cw20 addr/denom
+        reward: String,
+        lp_token: String,
+        /// Start after specified block height
+        start_after: Option<u64>,
+        /// Limit number of returned schedules.
+        limit: Option<u8>,
+    },
     #[returns(Vec<String>)]
     /// Returns the list of all ever incentivized pools
     ListPools {
@@ -268,6 +595,82 @@ pub enum QueryMsg {
     #[returns(Vec<(String, Uint128)>)]
     /// Returns the list of all pools receiving astro emissions
     ActivePools {},
+    /// Returns vesting positions of a user
+    #[returns(Vec<VestingRewardResponse>)]
+    VestingRewards { user: String },
+    /// Returns active ASTRO emission scaling of the specified pool or None if emissions are not scaled
+    #[returns(Option<EmissionScale>)]
+    EmissionScale { lp_token: String },
+    /// Returns the time when the specified pool was paused or None if the pool is not paused
+    #[returns(Option<u64>)]
+    PoolPausedAt { lp_token: String },
+    /// Returns vesting duration of claimed rewards for the specified pool and reward token.
+    /// Zero means rewards are transferred right away.
+    #[returns(u64)]
+    RewardVestingDuration { lp_token: String, reward: String },
+    /// Returns display metadata of the specified reward token
+    #[returns(Option<RewardMetadata>)]
+    RewardMetadata { reward: String },
+    /// Same as [`QueryMsg::PendingRewards`] but each reward is accompanied with its display metadata
+    #[returns(Vec<PendingRewardResponse>)]
+    PendingRewardsWithMetadata { lp_token: String, user: String },
+    /// Returns reward schedules funded by the specified creator which didn't end yet
+    #[returns(Vec<CreatorSchedule>)]
+    CreatorSchedules {
+        lp_token: String,
+        reward: String,
+        creator: String,
+    },
+    /// Returns the pooled reward schedule with the specified id
+    #[returns(PooledSchedule)]
+    PooledSchedule { schedule_id: u64 },
+    /// Returns the funders of the pooled reward schedule with the specified id
+    #[returns(Vec<ScheduleFunder>)]
+    ScheduleFunders {
+        schedule_id: u64,
+        /// The funder address to start reading from
+        start_after: Option<String>,
+        limit: Option<u8>,
+    },
+    /// Returns the outcome of [`ExecuteMsg::ClaimRewards`] for the specified user and pools at the current block
+    #[returns(ClaimSimulationResponse)]
+    SimulateClaim {
+        user: String,
+        /// The LP token cw20 address or token factory denom
+        lp_tokens: Vec<String>,
+    },
+    /// Returns the referrer of the user position in the specified pool or None if the position wasn't referred
+    #[returns(Option<Addr>)]
+    Referrer { lp_token: String, user: String },
+    /// Returns a list of tuples with LP tokens and the amount staked in positions referred by the specified referrer
+    #[returns(Vec<(String, Uint128)>)]
+    ReferredTvl {
+        referrer: String,
+        /// Start after specified LP token
+        start_after: Option<String>,
+        limit: Option<u8>,
+    },
+    /// Returns the stake receipt denom of the specified pool or None if receipts were never minted for it
+    #[returns(Option<String>)]
+    StakeReceiptDenom { lp_token: String },
+    /// Returns contracts notified about position changes and claims in the specified pool
+    #[returns(Vec<Addr>)]
+    StakingHooks { lp_token: String },
+    /// Returns ASTRO pulled from the vesting contract which is not paid out yet
+    #[returns(PulledAstro)]
+    PulledAstro {},
+    /// Returns operators approved to claim rewards on behalf of the user including expired ones
+    #[returns(Vec<ClaimOperator>)]
+    ClaimOperators {
+        user: String,
+        /// Start after specified operator
+        start_after: Option<String>,
+        limit: Option<u8>,
+    },
+    /// Returns the address receiving the user's rewards from the specified pool.
+    /// None if rewards are sent to the user
+    #[returns(Option<Addr>)]
+    RewardReceiver { user: String, lp_token: String },
 }
 
 #[cw_serde]
@@ -294,11 +697,143 @@ pub struct Config {
     pub total_alloc_points: Uint128,
     /// The vesting contract which distributes internal (ASTRO) rewards
     pub vesting_contract: Addr,
-    /// The guardian address which can add or remove tokens from blacklist
+    /// The guardian address which can add or remove tokens from blacklist and pause pools
     pub guardian: Option<Addr>,
     /// Defines native fee along with fee receiver.
     /// Fee is paid on adding NEW external reward to a specific pool
     pub incentivization_fee_info: Option<IncentivizationFeeInfo>,
+    /// The address which can temporarily scale down ASTRO emissions of active pools
+    #[serde(default)]
+    pub emission_controller: Option<Addr>,
+    /// Restrictions on reward schedule cancellation by schedule creators.
+    /// Creators can't cancel their schedules if not set
+    #[serde(default)]
+    pub schedule_cancellation: Option<ScheduleCancellationConfig>,
+    /// The ASTRO staking contract. Claiming into xASTRO is disabled if not set
+    #[serde(default)]
+    pub xastro_staking: Option<Addr>,
+    /// Whether deposits mint transferable stake receipts. Each pool gets its own tokenfactory denom
+    /// and transferring receipts moves the underlying staked position
+    #[serde(default)]
+    pub stake_receipts: bool,
+    /// Whether ASTRO rewards are pulled from the vesting contract once per epoch
+    /// and paid out of the contract balance instead of claiming them from vesting on every claim
+    #[serde(default)]
+    pub astro_pull_batching: bool,
+}
+
+/// ASTRO pulled from the vesting contract in advance which is not paid out yet.
+#[cw_serde]
+#[derive(Default)]
+pub struct PulledAstro {
+    /// The start of the epoch when ASTRO was pulled last time
+    pub epoch_start: u64,
+    /// The amount of pulled ASTRO left in the contract
+    pub amount: Uint128,
+}
+
+/// This structure describes when reward schedule creators can cancel their schedules.
+#[cw_serde]
+pub struct ScheduleCancellationConfig {
+    /// Minimum time in seconds between the cancellation and the moment the schedule stops.
+    /// Cancelled schedules stop at the first epoch start after the notice period
+    pub notice_period: u64,
+    /// Number of full epochs since the schedule start during which it can't be cancelled.
+    /// Schedules can never be cancelled before their first full epoch starts
+    pub locked_epochs: u64,
+}
+
+/// This structure describes the part of a pool reward schedule funded by a specific creator.
+#[cw_serde]
+pub struct CreatorSchedule {
+    /// The first full epoch start of the schedule
+    pub next_epoch_start_ts: u64,
+    /// Schedule end time
+    pub end_ts: u64,
+    /// Reward per second funded by the creator
+    pub rps: Decimal256,
+    /// The pooled schedule this part belongs to. Not set for schedules created before pooled funding
+    #[serde(default)]
+    pub schedule_id: Option<u64>,
+}
+
+/// This structure describes a reward schedule which can be co-funded by several addresses.
+#[cw_serde]
+pub struct PooledSchedule {
+    /// The LP token of the incentivized pool
+    pub lp_token: AssetInfo,
+    /// Reward asset info
+    pub reward_info: AssetInfo,
+    /// The first full epoch start of the schedule. Top-ups are accepted until this moment
+    pub next_epoch_start_ts: u64,
+    /// Schedule end time
+    pub end_ts: u64,
+    /// Total amount of rewards funded by all funders
+    pub funded: Uint128,
+    /// Total amount of rewards refunded to funders on cancellation
+    pub refunded: Uint128,
+}
+
+/// This structure describes the contribution of a specific funder to a pooled schedule.
+#[cw_serde]
+pub struct ScheduleFunder {
+    pub funder: Addr,
+    /// Amount of rewards funded by the funder
+    pub funded: Uint128,
+    /// Amount of rewards refunded to the funder on cancellation
+    pub refunded: Uint128,
+}
+
+/// An address approved to claim rewards on behalf of a user.
+#[cw_serde]
+pub struct ClaimOperator {
+    pub operator: Addr,
+    /// Timestamp (in seconds) starting from which the approval is no longer valid.
+    /// The approval never expires if not set
+    pub expires_at: Option<u64>,
+}
+
+impl ClaimOperator {
+    /// Whether the approval is still valid at the given timestamp
+    pub fn is_active(&self, block_ts: u64) -> bool {
+        !matches!(self.expires_at, Some(expires_at) if block_ts >= expires_at)
+    }
+}
+
+/// This structure describes temporary scaling of pool ASTRO emissions.
+#[cw_serde]
+pub struct EmissionScale {
+    /// The factor applied to the pool allocation points
+    pub factor: Decimal,
+    /// Timestamp when full emissions are restored
+    pub expires_at: u64,
+}
+
+impl EmissionScale {
+    /// Validates the scaling parameters and creates a scale starting from the current block.
+    pub fn new(env: &Env, factor: Decimal, duration: u64) -> StdResult<Self> {
+        if factor < MIN_EMISSION_SCALE || factor >= Decimal::one() {
+            return Err(StdError::generic_err(format!(
+                "Emission scale factor must be within [{MIN_EMISSION_SCALE}, 1)"
+            )));
+        }
+
+        if duration == 0 || duration > MAX_EMISSION_SCALE_DURATION {
+            return Err(StdError::generic_err(format!(
+                "Emission scale duration must be more than 0 and less than or equal to {MAX_EMISSION_SCALE_DURATION}"
+            )));
+        }
+
+        Ok(Self {
+            factor,
+            expires_at: env.block.time.seconds() + duration,
+        })
+    }
+
+    /// Whether the scale is still in effect at the given timestamp
+    pub fn is_active(&self, block_ts: u64) -> bool {
+        self.expires_at > block_ts
+    }
 }
 
 #[cw_serde]
@@ -375,6 +910,12 @@ pub struct RewardInfo {
     /// Orphaned rewards might appear between the time when pool
     /// gets incentivized and the time when first user stakes
     pub orphaned: Decimal256,
+    /// Reward tokens per block for the whole pool. Set by block based schedules
+    #[serde(default)]
+    pub rpb: Decimal256,
+    /// Block height when the next block based schedule should start
+    #[serde(default)]
+    pub next_update_height: u64,
 }
 
 #[cw_serde]
@@ -385,6 +926,109 @@ pub struct PoolInfoResponse {
     pub rewards: Vec<RewardInfo>,
     /// Last time when reward indexes were updated
     pub last_update_ts: u64,
+    /// Last block height when reward indexes were updated
+    #[serde(default)]
+    pub last_update_height: u64,
+}
+
+#[cw_serde]
+pub struct PoolStaker {
+    /// Staker address
+    pub user: String,
+    /// Amount of LP tokens staked
+    pub amount: Uint128,
+    /// Sum of staked amounts of this staker and all stakers ranked above
+    pub cumulative_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolStakersByAmountResponse {
+    /// Block height at which stakers were read
+    pub block_height: u64,
+    /// Stakers ordered by staked amount descending
+    pub stakers: Vec<PoolStaker>,
+}
+
+/// Display metadata of a reward token.
+#[cw_serde]
+pub struct RewardTokenMetadata {
+    /// Token symbol
+    pub symbol: String,
+    /// Token decimals
+    pub decimals: u8,
+    /// Token logo URI
+    pub logo_uri: Option<String>,
+    /// Project website
+    pub project_url: Option<String>,
+}
+
+impl RewardTokenMetadata {
+    /// Checks metadata fields lengths and decimals.
+    pub fn validate(&self) -> StdResult<()> {
+        let check_len = |field: &str, value: &str, max_len: usize| {
+            if value.is_empty() || value.len() > max_len {
+                Err(StdError::generic_err(format!(
+                    "{field} length must be within [1, {max_len}]"
+                )))
+            } else {
+                Ok(())
+            }
+        };
+        check_len("symbol", &self.symbol, MAX_REWARD_SYMBOL_LENGTH)?;
+        if let Some(logo_uri) = &self.logo_uri {
+            check_len("logo_uri", logo_uri, MAX_REWARD_URI_LENGTH)?;
+        }
+        if let Some(project_url) = &self.project_url {
+            check_len("project_url", project_url, MAX_REWARD_URI_LENGTH)?;
+        }
+
+        if self.decimals > MAX_REWARD_DECIMALS {
+            return Err(StdError::generic_err(format!(
+                "Decimals must not exceed {MAX_REWARD_DECIMALS}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Stored reward token metadata.
+#[cw_serde]
+pub struct RewardMetadata {
+    pub metadata: RewardTokenMetadata,
+    /// Whether the metadata was set by the owner or guardian
+    pub verified: bool,
+}
+
+#[cw_serde]
+pub struct PendingRewardResponse {
+    /// Pending reward
+    pub reward: Asset,
+    /// Display metadata of the reward token if it was set
+    pub metadata: Option<RewardMetadata>,
+}
+
+#[cw_serde]
+pub struct VestingRewardResponse {
+    /// Reward token
+    pub reward: AssetInfo,
+    /// Vested amount which can be claimed with [`ExecuteMsg::ClaimVested`]
+    pub claimable: Uint128,
+    /// Amount which is still vesting
+    pub locked: Uint128,
+    /// Time when all locked rewards are vested
+    pub end_ts: u64,
+}
+
+#[cw_serde]
+pub struct ClaimSimulationResponse {
+    /// Rewards which are transferred to the user right away aggregated by reward token.
+    /// Includes ASTRO emissions paid out by the vesting contract
+    pub transferred: Vec<Asset>,
+    /// Rewards which are added to the user's vesting positions aggregated by reward token
+    pub vesting: Vec<Asset>,
+    /// Rewards paid in the pool's own LP token which are added to the user's staked positions
+    pub compounded: Vec<Asset>,
 }
 
 #[cw_serde]
@@ -394,6 +1038,13 @@ pub struct ScheduleResponse {
     pub end_ts: u64,
 }
 
+#[cw_serde]
+pub struct BlockScheduleResponse {
+    pub rpb: Decimal256,
+    pub start_height: u64,
+    pub end_height: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::testing::mock_env;
@@ -413,6 +1064,9 @@ mod tests {
             &InputSchedule {
                 reward: AssetInfo::native("test").with_balance(2 * EPOCH_LENGTH),
                 duration_periods: 1,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
         )
         .unwrap();
@@ -426,6 +1080,9 @@ mod tests {
             &InputSchedule {
                 reward: AssetInfo::native("test").with_balance(100000000u128),
                 duration_periods: 0,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
         )
         .unwrap_err();
@@ -441,6 +1098,9 @@ mod tests {
             &InputSchedule {
                 reward: AssetInfo::native("test").with_balance(100000000u128),
                 duration_periods: MAX_PERIODS + 1,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
         )
         .unwrap_err();
@@ -456,6 +1116,9 @@ mod tests {
             &InputSchedule {
                 reward: AssetInfo::native("test").with_balance(100000u128),
                 duration_periods: MAX_PERIODS,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
         )
         .unwrap_err();
@@ -473,6 +1136,9 @@ mod tests {
                 // 4 days from current week + 21 days more
                 reward: AssetInfo::native("test").with_balance(25 * 86400u64),
                 duration_periods: 3,
+                vesting_duration: None,
+                schedule_id: None,
+                kind: ScheduleKind::Time,
             },
         )
         .unwrap();
@@ -483,5 +1149,92 @@ mod tests {
             schedule.next_epoch_start_ts + 3 * EPOCH_LENGTH
         );
         assert_eq!(schedule.rps, Decimal256::one());
+
+        let err = IncentivesSchedule::from_input(
+            &env,
+            &InputSchedule {
+                reward: AssetInfo::native("test").with_balance(25 * 86400u64),
+                duration_periods: 3,
+                vesting_duration: Some(MAX_VESTING_DURATION + 1),
+                schedule_id: None,
+                kind: ScheduleKind::Time,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Generic error: Vesting duration must be between 1 and {MAX_VESTING_DURATION} seconds"
+            )
+        );
+    }
+
+    #[test]
+    fn test_block_schedules() {
+        let env = mock_env();
+        let height = env.block.height;
+        let input = |start_height: u64, end_height: u64, amount: u128| InputSchedule {
+            reward: AssetInfo::native("test").with_balance(amount),
+            duration_periods: 0,
+            vesting_duration: None,
+            schedule_id: None,
+            kind: ScheduleKind::Blocks {
+                start_height,
+                end_height,
+            },
+        };
+
+        let schedule =
+            BlockIncentivesSchedule::from_input(&env, &input(height, height + 100, 1000)).unwrap();
+        assert_eq!(schedule.rpb, Decimal256::from_ratio(10u8, 1u8));
+
+        for (start_height, end_height) in [
+            (height - 1, height + 100),
+            (height + 100, height + 100),
+            (height, height + MAX_SCHEDULE_BLOCKS + 1),
+        ] {
+            let err = BlockIncentivesSchedule::from_input(
+                &env,
+                &input(start_height, end_height, 100_000_000),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Generic error: Schedule must start not earlier than the current block and last from 1 to {MAX_SCHEDULE_BLOCKS} blocks")
+            );
+        }
+
+        let err = BlockIncentivesSchedule::from_input(&env, &input(height, height + 100, 99))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Reward per block must be at least 1 unit but actual is 0.99"
+        );
+
+        let err = BlockIncentivesSchedule::from_input(
+            &env,
+            &InputSchedule {
+                schedule_id: Some(1),
+                ..input(height, height + 100, 1000)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Block based schedules can't be pooled"
+        );
+
+        let err = BlockIncentivesSchedule::from_input(
+            &env,
+            &InputSchedule {
+                kind: ScheduleKind::Time,
+                ..input(height, height + 100, 1000)
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Schedule must be block based"
+        );
     }
 }
@@ -3,13 +3,18 @@ use cosmwasm_std::{Decimal, Decimal256, StdError, StdResult, Uint128};
 pub use decimal_checked_ops::DecimalCheckedOps;
 pub use uints::U256;
 
+pub mod arb_executor;
 pub mod asset;
 pub mod common;
 pub mod cosmwasm_ext;
+pub mod events;
 pub mod factory;
 pub mod fee_granter;
 #[cfg(feature = "injective")]
 pub mod injective_ext;
+pub mod limit_order;
+pub mod liquidity_manager;
+pub mod lp_pricing;
 pub mod maker;
 pub mod native_coin_registry;
 pub mod observation;
@@ -17,9 +22,13 @@ pub mod oracle;
 pub mod pair;
 pub mod pair_concentrated;
 pub mod pair_concentrated_inj;
+pub mod pair_red_bank;
 pub mod pair_xyk_sale_tax;
+pub mod price_hub;
 pub mod querier;
+pub mod red_bank;
 pub mod restricted_vector;
+pub mod route;
 pub mod router;
 pub mod staking;
 pub mod token;
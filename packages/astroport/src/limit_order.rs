@@ -0,0 +1,154 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128, Uint256};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::{Asset, AssetInfo};
+use crate::router::SwapOperation;
+
+/// The maximum keeper tip in bps of the order amount the owner can allow
+pub const MAX_TIP_BPS: u16 = 1000;
+
+/// The maximum number of orders returned in one page
+pub const MAX_PAGE_LIMIT: u32 = 30;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The contract owner
+    pub owner: String,
+    /// The router contract address
+    pub router: String,
+    /// The maximum keeper tip in bps of the order amount
+    pub max_tip_bps: u16,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`] and places an order with the received cw20 tokens
+    Receive(Cw20ReceiveMsg),
+    /// Places an order selling the attached native tokens
+    PlaceOrder {
+        /// The asset being sold
+        offer_asset: Asset,
+        params: OrderParams,
+    },
+    /// Cancels an order and returns the escrowed assets to its owner. Only the order owner can execute this.
+    CancelOrder { order_id: u64 },
+    /// Executes an order once its trigger is reached. The keeper receives the order tip.
+    /// This endpoint is permissionless.
+    ExecuteOrder { order_id: u64 },
+    /// Updates the contract configuration. Only the owner can execute this.
+    UpdateConfig {
+        router: Option<String>,
+        max_tip_bps: Option<u16>,
+    },
+    /// Creates a request to change contract ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Places an order selling the received tokens
+    PlaceOrder(OrderParams),
+}
+
+/// Order parameters chosen by the order owner.
+#[cw_serde]
+pub struct OrderParams {
+    /// The swap operations executed through the router. They must start with the offer asset
+    pub operations: Vec<SwapOperation>,
+    /// The minimum amount of the ask asset received for the offer amount less the tip.
+    /// The order is triggered once the oracle TWAP value of the swapped amount reaches it
+    pub min_receive: Uint128,
+    /// The oracle contract which provides the TWAP between the offer and ask assets
+    pub oracle: String,
+    /// The tip paid to the keeper in the offer asset
+    pub tip: Uint128,
+    /// Max spread enforced by the router for every swap operation
+    pub max_spread: Option<Decimal>,
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns an order by its id
+    #[returns(Order)]
+    Order { order_id: u64 },
+    /// Lists orders ordered by their id. If `owner` is set only orders of this address are returned
+    #[returns(Vec<Order>)]
+    Orders {
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the current oracle TWAP value of an order and whether the order can be executed
+    #[returns(OrderTrigger)]
+    OrderTrigger { order_id: u64 },
+}
+
+/// This structure stores the main contract parameters.
+#[cw_serde]
+pub struct Config {
+    /// The contract owner
+    pub owner: Addr,
+    /// The router contract address
+    pub router: Addr,
+    /// The maximum keeper tip in bps of the order amount
+    pub max_tip_bps: u16,
+}
+
+/// This structure describes a limit order.
+#[cw_serde]
+pub struct Order {
+    /// The order id
+    pub id: u64,
+    /// The order owner which receives the ask asset
+    pub owner: Addr,
+    /// The escrowed asset being sold including the tip
+    pub offer_asset: Asset,
+    /// The asset being bought
+    pub ask_asset_info: AssetInfo,
+    /// The swap operations executed through the router
+    pub operations: Vec<SwapOperation>,
+    /// The minimum amount of the ask asset received
+    pub min_receive: Uint128,
+    /// The oracle contract which provides the TWAP between the offer and ask assets
+    pub oracle: Addr,
+    /// The tip paid to the keeper in the offer asset
+    pub tip: Uint128,
+    /// Max spread enforced by the router for every swap operation
+    pub max_spread: Option<Decimal>,
+    /// The timestamp when the order was placed
+    pub created_at: u64,
+}
+
+impl Order {
+    /// Returns the amount of the offer asset which is swapped on execution
+    pub fn swap_amount(&self) -> Uint128 {
+        self.offer_asset.amount - self.tip
+    }
+}
+
+/// The trigger state of an order.
+#[cw_serde]
+pub struct OrderTrigger {
+    /// The oracle TWAP value of the swapped amount denominated in the ask asset
+    pub twap_return: Uint256,
+    /// Whether the TWAP value reached the minimum receive amount
+    pub triggered: bool,
+}
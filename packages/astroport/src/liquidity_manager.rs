@@ -0,0 +1,88 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::Asset;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The factory contract address. Only pairs registered in this factory are served
+    pub astroport_factory: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Provides liquidity to a pair on behalf of the sender.
+    /// Native assets must be attached to the message. The contract must be allowed to spend cw20 assets.
+    /// The amount of minted LP tokens is checked against `min_lp_to_receive`.
+    /// Unused assets are returned to the sender.
+    ProvideLiquidity {
+        /// The pair contract address
+        pair_addr: String,
+        /// The assets to provide
+        assets: Vec<Asset>,
+        /// Forwarded to the pair contract
+        slippage_tolerance: Option<Decimal>,
+        /// Stake minted LP tokens in the factory's staking contract on behalf of the receiver
+        auto_stake: Option<bool>,
+        /// The receiver of LP tokens. Defaults to the sender
+        receiver: Option<String>,
+        /// The minimum amount of LP tokens to receive. Must be greater than zero.
+        /// Use [`QueryMsg::SimulateProvide`] off-chain to calculate it
+        min_lp_to_receive: Uint128,
+    },
+    /// Withdraws liquidity from a pair. Token factory LP tokens must be attached to the message.
+    /// The received assets are checked against the contract's own simulation and `min_assets_to_receive`.
+    WithdrawLiquidity {
+        /// The pair contract address
+        pair_addr: String,
+        /// The minimum amounts of assets to receive
+        min_assets_to_receive: Option<Vec<Asset>>,
+        /// The receiver of withdrawn assets. Defaults to the sender
+        receiver: Option<String>,
+    },
+    /// Receives a message of type [`Cw20ReceiveMsg`]. Used to withdraw liquidity with cw20 LP tokens
+    Receive(Cw20ReceiveMsg),
+}
+
+/// This structure describes a CW20 hook message.
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Withdraws liquidity with the sent cw20 LP tokens. Same as [`ExecuteMsg::WithdrawLiquidity`]
+    WithdrawLiquidity {
+        pair_addr: String,
+        min_assets_to_receive: Option<Vec<Asset>>,
+        receiver: Option<String>,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration
+    #[returns(Config)]
+    Config {},
+    /// Returns the amount of LP tokens minted for the given assets
+    #[returns(Uint128)]
+    SimulateProvide {
+        pair_addr: String,
+        assets: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Returns the assets received for the given amount of LP tokens
+    #[returns(Vec<Asset>)]
+    SimulateWithdraw {
+        pair_addr: String,
+        lp_amount: Uint128,
+    },
+}
+
+/// This structure stores the main contract parameters.
+#[cw_serde]
+pub struct Config {
+    /// The factory contract address
+    pub astroport_factory: Addr,
+}
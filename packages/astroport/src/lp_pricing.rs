@@ -0,0 +1,70 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CustomQuery, Decimal256, QuerierWrapper, StdError, StdResult, Uint128};
+
+use crate::asset::Decimal256Ext;
+use crate::pair::QueryMsg;
+
+/// Number of decimals of the virtual price returned by [`QueryMsg::VirtualPrice`]
+pub const VIRTUAL_PRICE_PRECISION: u8 = 6;
+
+/// This structure describes the virtual price of an LP token.
+#[cw_serde]
+pub struct VirtualPriceResponse {
+    /// Pool invariant D per one whole LP token scaled by 10^[`VIRTUAL_PRICE_PRECISION`].
+    /// It starts at 1 and only grows with collected fees unless the amplification changes
+    pub virtual_price: Uint128,
+}
+
+impl VirtualPriceResponse {
+    /// Returns the virtual price as a decimal
+    pub fn to_decimal(&self) -> StdResult<Decimal256> {
+        Decimal256::with_precision(self.virtual_price, VIRTUAL_PRICE_PRECISION)
+    }
+}
+
+/// Queries the virtual price of the pair LP token.
+pub fn query_virtual_price<C>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+) -> StdResult<Decimal256>
+where
+    C: CustomQuery,
+{
+    querier
+        .query_wasm_smart::<VirtualPriceResponse>(pair_contract, &QueryMsg::VirtualPrice {})?
+        .to_decimal()
+}
+
+/// Returns the price of one whole LP token as the lowest pool asset price multiplied by the virtual price.
+/// Unlike pricing by pool reserves, the result can't be skewed by imbalancing swaps,
+/// while the lowest asset price covers a depeg of any pool asset.
+///
+/// * **virtual_price** is the LP token virtual price.
+///
+/// * **asset_prices** are prices of one whole token of each pool asset in the same quote asset.
+pub fn lp_token_price(
+    virtual_price: Decimal256,
+    asset_prices: &[Decimal256],
+) -> StdResult<Decimal256> {
+    let min_price = asset_prices
+        .iter()
+        .min()
+        .ok_or_else(|| StdError::generic_err("Asset prices must not be empty"))?;
+
+    min_price
+        .checked_mul(virtual_price)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+/// Queries the virtual price of the pair LP token and returns the price of one whole LP token.
+/// See [`lp_token_price`] for details.
+pub fn query_lp_token_price<C>(
+    querier: &QuerierWrapper<C>,
+    pair_contract: impl Into<String>,
+    asset_prices: &[Decimal256],
+) -> StdResult<Decimal256>
+where
+    C: CustomQuery,
+{
+    lp_token_price(query_virtual_price(querier, pair_contract)?, asset_prices)
+}
@@ -1,11 +1,20 @@
 use crate::asset::{Asset, AssetInfo};
 use crate::factory::UpdateAddr;
+use crate::route::Route;
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Decimal, Uint128, Uint64};
 use std::ops::RangeInclusive;
 
 /// Validations limits for cooldown period. From 30 to 600 seconds.
 pub const COOLDOWN_LIMITS: RangeInclusive<u64> = 30..=600;
+/// Validation limits for IBC transfer timeout. From 2 minutes to 1 day.
+pub const IBC_TIMEOUT_LIMITS: RangeInclusive<u64> = 120..=86400;
+/// Validation limits for the governance share vesting duration. From 1 day to 1 year.
+pub const GOVERNANCE_VESTING_DURATION_LIMITS: RangeInclusive<u64> = 86400..=31536000;
+/// Validation limits for the period between distribution windows. From 1 hour to 30 days.
+pub const DISTRIBUTION_PERIOD_LIMITS: RangeInclusive<u64> = 3600..=2592000;
+/// The maximum share of distributed ASTRO paid to the caller of a scheduled distribution, 1%
+pub const MAX_DISTRIBUTION_BOUNTY: Decimal = Decimal::percent(1);
 
 /// This structure stores the main parameters for the Maker contract.
 #[cw_serde]
@@ -65,6 +74,9 @@ pub struct InstantiateMsg {
     pub second_receiver_params: Option<SecondReceiverParams>,
     /// If set defines the period when maker collect can be called
     pub collect_cooldown: Option<u64>,
+    /// Delay in seconds between an ownership proposal and the moment it can be claimed. Disabled if not set
+    #[serde(default)]
+    pub ownership_timelock: Option<u64>,
 }
 
 /// This structure describes the functions that can be executed in this contract.
@@ -96,9 +108,11 @@ pub enum ExecuteMsg {
         /// The ASTRO token asset info
         astro_token: Option<AssetInfo>,
     },
-    /// Add bridge tokens used to swap specific fee tokens to ASTRO (effectively declaring a swap route)
+    /// Add routes used to swap specific fee tokens to ASTRO. Every hop is saved as the bridge of its offer asset,
+    /// the last asset must have a pool with ASTRO or its own bridge. Only Astroport hops are supported
     UpdateBridges {
-        add: Option<Vec<(AssetInfo, AssetInfo)>>,
+        add: Option<Vec<Route>>,
+        /// Fee tokens which bridges are removed
         remove: Option<Vec<AssetInfo>>,
     },
     /// Swap fee tokens via bridge assets
@@ -116,8 +130,51 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Claims contract ownership
     ClaimOwnership {},
+    /// Sets the delay (in seconds) between an ownership proposal and the moment it can be claimed.
+    /// Only the owner can execute this.
+    UpdateOwnershipTimelock { timelock: u64 },
     /// Enables the distribution of current fees accrued in the contract over "blocks" number of blocks
     EnableRewards { blocks: u64 },
+    /// Enables or disables the IBC distribution mode.
+    /// When enabled, the staking share of ASTRO is transferred over IBC to the hub instead of the local staking contract.
+    UpdateIbcDistribution {
+        /// IBC distribution parameters. None disables the mode
+        config: Option<IbcDistributionConfig>,
+    },
+    /// Records fees shared by a factory pair. Sent by PCL pairs right after the fee transfer
+    /// when the Maker is set as the fee share recipient with callbacks enabled.
+    /// Mirrors [`crate::pair::FeeShareCallbackMsg`].
+    FeeShareReceived { asset: Asset },
+    /// Enables or disables vesting of the governance share.
+    /// When enabled, the governance share of ASTRO is accumulated in the Maker and periodically
+    /// registered as a linear vesting schedule for the governance contract in the vesting contract.
+    UpdateGovernanceVesting {
+        /// Governance vesting parameters. None disables vesting and sends the accumulated share to the governance contract
+        config: Option<GovernanceVestingConfig>,
+    },
+    /// Enables or disables scheduled distributions.
+    /// When enabled, ASTRO received from fee swaps is kept in the Maker until the next distribution window.
+    UpdateDistributionWindows {
+        /// Distribution windows parameters. None disables the schedule
+        config: Option<DistributionWindowsConfig>,
+    },
+    /// Distributes accumulated ASTRO to stakers and to governance.
+    /// Permissionless. Can be executed once per distribution window, the caller receives a bounty
+    Distribute {},
+    /// Sets or removes per-asset limits applied when the Maker swaps fee tokens on their way to ASTRO
+    UpdateSwapLimits {
+        /// Assets along with their new swap limits
+        set: Option<Vec<(AssetInfo, SwapLimitParams)>>,
+        /// Assets whose swap limits are removed. Swaps fall back to the global max spread
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Sets or removes routes used to unwrap wrapped fee tokens (e.g. xASTRO) before distribution
+    UpdateUnwrapRoutes {
+        /// Wrapped assets along with the instructions to unwrap them
+        set: Option<Vec<(AssetInfo, UnwrapRouteParams)>>,
+        /// Wrapped assets whose unwrap routes are removed
+        remove: Option<Vec<AssetInfo>>,
+    },
 }
 
 /// This structure describes the query functions available in the contract.
@@ -130,8 +187,80 @@ pub enum QueryMsg {
     /// Returns the balance for each asset in the specified input parameters
     #[returns(BalancesResponse)]
     Balances { assets: Vec<AssetInfo> },
-    #[returns(Vec<(String, String)>)]
+    /// Returns single-hop bridge routes by fee token
+    #[returns(Vec<Route>)]
     Bridges {},
+    /// Returns the IBC distribution parameters if the mode is enabled
+    #[returns(Option<IbcDistributionConfig>)]
+    IbcDistribution {},
+    /// Returns IBC transfers which were neither acknowledged nor timed out yet.
+    /// Transfers are keyed by source channel and IBC packet sequence.
+    #[returns(Vec<((String, u64), IbcTransferInfo)>)]
+    PendingIbcTransfers {
+        start_after: Option<(String, u64)>,
+        limit: Option<u32>,
+    },
+    /// Returns the total amount of fees shared with the Maker by the specified pair, per asset
+    #[returns(Vec<Asset>)]
+    FeeShareStats { pair: String },
+    /// Estimates the amount of ASTRO produced by a collect call with the specified assets
+    /// based on current balances, bridges and pool simulations
+    #[returns(SimulateCollectResponse)]
+    SimulateCollect { assets: Vec<AssetWithLimit> },
+    /// Returns the governance vesting parameters along with the accumulated governance share if vesting is enabled
+    #[returns(Option<GovernanceVestingResponse>)]
+    GovernanceVesting {},
+    /// Returns the current or the next distribution window if scheduled distributions are enabled
+    #[returns(Option<DistributionWindowResponse>)]
+    NextDistributionWindow {},
+    /// Returns per-asset swap limits keyed by asset
+    #[returns(Vec<(String, SwapLimit)>)]
+    SwapLimits {},
+    /// Returns routes used to unwrap wrapped fee tokens keyed by wrapped asset
+    #[returns(Vec<(String, UnwrapRoute)>)]
+    UnwrapRoutes {},
+}
+
+/// This structure describes the estimated outcome of a collect call.
+#[cw_serde]
+pub struct SimulateCollectResponse {
+    /// Estimated total amount of ASTRO received from all swaps
+    pub astro_amount: Uint128,
+    /// Fee tokens offered along with the estimated amount of ASTRO received for each of them
+    pub swaps: Vec<(Asset, Uint128)>,
+    /// Fee tokens which can't be swapped to ASTRO
+    pub failed_assets: Vec<AssetInfo>,
+}
+
+/// This enum describes IBC hooks callbacks supported by the Maker contract.
+#[cw_serde]
+pub enum SudoMsg {
+    #[serde(rename = "ibc_lifecycle_complete")]
+    IBCLifecycleComplete(IBCLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IBCLifecycleComplete {
+    /// Called by the chain when an acknowledgement for the packet sent by the contract is received
+    #[serde(rename = "ibc_ack")]
+    IBCAck {
+        /// The source channel of the IBC packet
+        channel: String,
+        /// The sequence number of the IBC packet
+        sequence: u64,
+        /// String encoded version of the ack as seen by OnAcknowledgementPacket(..)
+        ack: String,
+        /// Whether the ack is a success or a failure
+        success: bool,
+    },
+    /// Called by the chain when the packet sent by the contract timed out
+    #[serde(rename = "ibc_timeout")]
+    IBCTimeout {
+        /// The source channel of the IBC packet
+        channel: String,
+        /// The sequence number of the IBC packet
+        sequence: u64,
+    },
 }
 
 /// A custom struct that holds contract parameters and is used to retrieve them.
@@ -183,6 +312,45 @@ pub struct AssetWithLimit {
     pub limit: Option<Uint128>,
 }
 
+/// This structure describes the parameters for setting swap limits of a fee token.
+#[cw_serde]
+pub struct SwapLimitParams {
+    /// Max spread used to swap the token instead of the global one
+    pub max_spread: Option<Decimal>,
+    /// Astroport oracle contract tracking the pool the token is swapped in.
+    /// If set, the swap is reverted when it returns less than the oracle price allows within the max spread
+    pub oracle: Option<String>,
+    /// Minimum balance of the token required to swap it in a collect call. Smaller balances are skipped
+    #[serde(default)]
+    pub min_collect_amount: Option<Uint128>,
+}
+
+/// This structure stores swap limits of a fee token.
+#[cw_serde]
+pub struct SwapLimit {
+    /// Max spread used to swap the token instead of the global one
+    pub max_spread: Option<Decimal>,
+    /// Astroport oracle contract used to derive the minimum swap output
+    pub oracle: Option<Addr>,
+    /// Minimum balance of the token required to swap it in a collect call
+    #[serde(default)]
+    pub min_collect_amount: Option<Uint128>,
+}
+
+/// This enum describes the instructions for unwrapping a wrapped fee token.
+#[cw_serde]
+pub enum UnwrapRouteParams {
+    /// Unwraps xASTRO to ASTRO via the Leave endpoint of the staking contract
+    StakingLeave { staking_contract: String },
+}
+
+/// This enum stores the instructions for unwrapping a wrapped fee token.
+#[cw_serde]
+pub enum UnwrapRoute {
+    /// Unwraps xASTRO to ASTRO via the Leave endpoint of the staking contract
+    StakingLeave { staking_contract: Addr },
+}
+
 /// This structure describes the parameters for updating the second receiver of fees.
 #[cw_serde]
 pub struct SecondReceiverParams {
@@ -201,5 +369,81 @@ pub struct SecondReceiverConfig {
     pub second_receiver_cut: Uint64,
 }
 
+/// This structure describes the parameters of ASTRO transfers over IBC to the hub.
+#[cw_serde]
+pub struct IbcDistributionConfig {
+    /// Outpost channel connected to the hub
+    pub channel: String,
+    /// ASTRO receiver on the hub (usually the hub staking contract)
+    pub receiver: String,
+    /// IBC transfer timeout in seconds
+    pub timeout: u64,
+}
+
+/// This structure describes the parameters of the governance share vesting.
+#[cw_serde]
+pub struct GovernanceVestingConfig {
+    /// Vesting contract which receives the governance share.
+    /// The Maker must be its owner and its vesting token must be ASTRO
+    pub vesting_contract: String,
+    /// Vesting duration in seconds. The governance share is accumulated for this period
+    /// and then vested over the same period
+    pub duration: u64,
+}
+
+/// This structure describes the governance vesting state.
+#[cw_serde]
+pub struct GovernanceVestingResponse {
+    /// Governance vesting parameters
+    pub config: GovernanceVestingConfig,
+    /// Governance share of ASTRO held by the Maker until the next vesting schedule is registered
+    pub pending_amount: Uint128,
+    /// Timestamp when the last vesting schedule was registered
+    pub last_vesting_ts: u64,
+}
+
+/// This structure describes the schedule of ASTRO distributions.
+/// Windows start at `start_ts + n * period` and last for `duration` seconds.
+/// For instance, weekly windows on Thursdays at 12:00 UTC are anchored at any Thursday 12:00 UTC with a period of 604800.
+#[cw_serde]
+pub struct DistributionWindowsConfig {
+    /// Start of the first distribution window (unix timestamp in seconds)
+    pub start_ts: u64,
+    /// Seconds between the starts of two consecutive windows
+    pub period: u64,
+    /// Window duration in seconds. Must not exceed the period
+    pub duration: u64,
+    /// Share of distributed ASTRO paid to the caller of [`ExecuteMsg::Distribute`]
+    pub caller_bounty: Decimal,
+}
+
+/// This structure describes a distribution window.
+#[cw_serde]
+pub struct DistributionWindowResponse {
+    /// Distribution windows parameters
+    pub config: DistributionWindowsConfig,
+    /// Start of the window (unix timestamp in seconds)
+    pub window_start: u64,
+    /// End of the window (unix timestamp in seconds)
+    pub window_end: u64,
+    /// Whether the distribution can be executed right now
+    pub is_open: bool,
+    /// Seconds left until the window opens. Zero if the window is open
+    pub countdown: u64,
+}
+
+/// This structure describes an ASTRO transfer to the hub which is still in flight.
+#[cw_serde]
+pub struct IbcTransferInfo {
+    /// Channel the transfer was sent through
+    pub channel: String,
+    /// ASTRO receiver on the hub
+    pub receiver: String,
+    /// Amount of transferred ASTRO
+    pub amount: Uint128,
+    /// Timestamp when the transfer was sent
+    pub sent_at: u64,
+}
+
 /// The maximum allowed second receiver share (percents)
 pub const MAX_SECOND_RECEIVER_CUT: Uint64 = Uint64::new(50);
@@ -1,6 +1,14 @@
+use cosmos_sdk_proto::cosmos::bank::v1beta1::QueryDenomMetadataRequest;
+use cosmwasm_schema::serde::Deserialize;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, CustomQuery, QuerierWrapper, QueryRequest, StdError, StdResult};
 use cw_storage_plus::Map;
+use prost::Message;
+
+/// Prefix of the denoms issued by the tokenfactory module.
+pub const TOKEN_FACTORY_PREFIX: &str = "factory/";
+/// Stargate path of the bank module query returning denom metadata.
+pub const DENOM_METADATA_QUERY_PATH: &str = "/cosmos.bank.v1beta1.Query/DenomMetadata";
 
 /// This structure stores the main parameters for the native coin registry contract.
 #[cw_serde]
@@ -28,6 +36,10 @@ pub enum ExecuteMsg {
     /// All funds will be returned to the sender.
     /// Permissionless
     Register { native_coins: Vec<(String, u8)> },
+    /// Resolves decimals of tokenfactory denoms from the bank module metadata
+    /// and caches them in the registry. Already registered denoms are left untouched.
+    /// Permissionless
+    RegisterTokenFactory { denoms: Vec<String> },
     /// Removes the native assets by specified parameters
     /// Only the current owner can execute this
     Remove { native_coins: Vec<String> },
@@ -55,6 +67,7 @@ pub enum QueryMsg {
     #[returns(Config)]
     Config {},
     /// Returns the information about Asset by specified denominator.
+    /// Tokenfactory denoms which are not registered yet are resolved from the bank module metadata.
     #[returns(CoinResponse)]
     NativeToken { denom: String },
     /// Returns a vector which contains the native assets.
@@ -75,3 +88,66 @@ pub struct CoinResponse {
 
 /// The first key is denom, the second key is a precision.
 pub const COINS_INFO: Map<String, u8> = Map::new("coins_info");
+
+/// Bank module denom unit as returned by the DenomMetadata stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct DenomUnit {
+    pub denom: String,
+    #[serde(default)]
+    pub exponent: u32,
+}
+
+/// Bank module denom metadata as returned by the DenomMetadata stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct DenomMetadata {
+    #[serde(default)]
+    pub denom_units: Vec<DenomUnit>,
+    #[serde(default)]
+    pub display: String,
+}
+
+/// Response of the DenomMetadata stargate query.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct DenomMetadataResponse {
+    pub metadata: DenomMetadata,
+}
+
+/// Returns true if the denom was issued by the tokenfactory module.
+pub fn is_token_factory_denom(denom: &str) -> bool {
+    denom.starts_with(TOKEN_FACTORY_PREFIX)
+}
+
+/// Resolves the number of decimals of a native denom from its bank module metadata.
+/// Decimals are the exponent of the display unit. If the display unit is not set,
+/// the largest exponent among the denom units is used.
+pub fn query_denom_metadata_decimals<C>(
+    querier: &QuerierWrapper<C>,
+    denom: impl Into<String>,
+) -> StdResult<u8>
+where
+    C: CustomQuery,
+{
+    let denom = denom.into();
+    let request = QueryDenomMetadataRequest {
+        denom: denom.clone(),
+    };
+    let DenomMetadataResponse { metadata } = querier.query(&QueryRequest::Stargate {
+        path: DENOM_METADATA_QUERY_PATH.to_string(),
+        data: Binary(request.encode_to_vec()),
+    })?;
+
+    let exponent = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display)
+        .or_else(|| metadata.denom_units.iter().max_by_key(|unit| unit.exponent))
+        .map(|unit| unit.exponent)
+        .ok_or_else(|| StdError::generic_err(format!("No denom units found for {denom}")))?;
+
+    u8::try_from(exponent).map_err(|_| {
+        StdError::generic_err(format!("Invalid exponent {exponent} in {denom} metadata"))
+    })
+}
@@ -7,10 +7,16 @@ use cw_storage_plus::Item;
 
 use astroport_circular_buffer::{BufferManager, CircularBuffer};
 
+use crate::asset::{Asset, AssetInfoExt};
 use crate::cosmwasm_ext::AbsDiff;
+use crate::pair::FeeAprResponse;
 
 /// Circular buffer size which stores observations
 pub const OBSERVATIONS_SIZE: u32 = 3000;
+/// The maximum number of the latest observations used to calculate the median price
+pub const MAX_SAFE_PRICE_OBSERVATIONS: u32 = 100;
+/// The period over which recent observations are counted in [`ObservationsInfoResponse`] (24 hours)
+pub const RECENT_OBSERVATIONS_PERIOD: u64 = 86400;
 
 /// Stores trade size observations. We use it in orderbook integration
 /// and derive prices for external contracts/users.
@@ -31,6 +37,149 @@ pub struct OracleObservation {
     pub price: Decimal,
 }
 
+/// The median price derived from observations.
+#[cw_serde]
+pub struct SafePriceResponse {
+    /// Median of the observed prices
+    pub price: Decimal,
+    /// The number of observations used
+    pub observations: u32,
+    /// Timestamp of the oldest used observation
+    pub oldest_ts: u64,
+}
+
+/// Describes how dense the observations buffer is.
+#[cw_serde]
+pub struct ObservationsInfoResponse {
+    /// Circular buffer capacity
+    pub capacity: u32,
+    /// The number of stored observations
+    pub observations: u32,
+    /// The ratio of stored observations to the buffer capacity
+    pub fill_ratio: Decimal,
+    /// Timestamp of the oldest stored observation. None if the buffer is empty
+    pub oldest_ts: Option<u64>,
+    /// Timestamp of the newest stored observation. None if the buffer is empty
+    pub newest_ts: Option<u64>,
+    /// The number of observations within the last [`RECENT_OBSERVATIONS_PERIOD`] seconds
+    pub recent_observations: u32,
+}
+
+/// Returns the observations buffer metadata.
+/// Oracle consumers can use it to check the buffer is dense enough before trusting prices derived from it.
+pub fn query_observations_info<C>(
+    deps: Deps<C>,
+    env: Env,
+    observations: CircularBuffer<Observation>,
+) -> StdResult<ObservationsInfoResponse>
+where
+    C: CustomQuery,
+{
+    let buffer = BufferManager::new(deps.storage, observations)?;
+    let capacity = buffer.capacity();
+
+    // Once the buffer is full, the head points to the oldest observation
+    let (oldest_ind, stored) = if buffer.exists(deps.storage, buffer.head()) {
+        (buffer.head(), capacity)
+    } else {
+        (0, buffer.head())
+    };
+
+    let mut response = ObservationsInfoResponse {
+        capacity,
+        observations: stored,
+        fill_ratio: Decimal::from_ratio(stored, capacity),
+        oldest_ts: None,
+        newest_ts: None,
+        recent_observations: 0,
+    };
+
+    if stored == 0 {
+        return Ok(response);
+    }
+
+    let read_ts = |i: u32| -> StdResult<u64> {
+        buffer
+            .read_single(deps.storage, (oldest_ind + i) % capacity)?
+            .map(|obs| obs.ts)
+            .ok_or_else(|| StdError::generic_err(format!("Observation {i} not found")))
+    };
+
+    let newest_ts = read_ts(stored - 1)?;
+    response.oldest_ts = Some(read_ts(0)?);
+    response.newest_ts = Some(newest_ts);
+
+    // Binary search for the first observation within the recent period
+    let start_ts = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(RECENT_OBSERVATIONS_PERIOD);
+    if newest_ts >= start_ts {
+        let (mut low, mut high) = (0, stored - 1);
+        while low < high {
+            let mid = (low + high) / 2;
+            if read_ts(mid)? >= start_ts {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        response.recent_observations = stored - low;
+    }
+
+    Ok(response)
+}
+
+/// Returns the median of the prices observed within the last `window` seconds.
+/// Unlike the moving average, the median can't be skewed by a single manipulated block.
+/// At most [`MAX_SAFE_PRICE_OBSERVATIONS`] latest observations are used.
+pub fn query_safe_price<C>(
+    deps: Deps<C>,
+    env: Env,
+    observations: CircularBuffer<Observation>,
+    window: u64,
+) -> StdResult<SafePriceResponse>
+where
+    C: CustomQuery,
+{
+    let buffer = BufferManager::new(deps.storage, observations)?;
+    let start_ts = env.block.time.seconds().saturating_sub(window);
+
+    let mut prices = vec![];
+    let mut oldest_ts = 0;
+    let capacity = buffer.capacity();
+    for i in 1..=capacity.min(MAX_SAFE_PRICE_OBSERVATIONS) {
+        match buffer.read_single(deps.storage, (buffer.head() + capacity - i) % capacity)? {
+            Some(obs) if obs.ts >= start_ts => {
+                prices.push(obs.price);
+                oldest_ts = obs.ts;
+            }
+            _ => break,
+        }
+    }
+
+    if prices.is_empty() {
+        return Err(StdError::generic_err(format!(
+            "No observations within the last {window} seconds"
+        )));
+    }
+
+    prices.sort();
+    let mid = prices.len() / 2;
+    let price = if prices.len() % 2 == 1 {
+        prices[mid]
+    } else {
+        prices[mid - 1].checked_add(prices[mid])? * Decimal::percent(50)
+    };
+
+    Ok(SafePriceResponse {
+        price,
+        observations: prices.len() as u32,
+        oldest_ts,
+    })
+}
+
 /// Returns price observation at point that was 'seconds_ago' seconds ago.
 pub fn query_observation<C>(
     deps: Deps<C>,
@@ -234,6 +383,267 @@ pub fn safe_sma_buffer_not_full(
     try_dec256_into_dec(res)
 }
 
+/// Circular buffer size which stores volume observations
+pub const VOLUME_OBSERVATIONS_SIZE: u32 = 3000;
+
+/// Stores the swap volumes traded within a block.
+/// Base is the first pair asset and quote is the second one.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub struct VolumeObservation {
+    /// Timestamp of the observation
+    pub ts: u64,
+    /// Traded amount of the base asset
+    pub base_volume: Uint128,
+    /// Traded amount of the quote asset
+    pub quote_volume: Uint128,
+}
+
+/// Swap volumes observed within a time window.
+#[cw_serde]
+pub struct VolumeSmaResponse {
+    /// Total traded amount of the base asset
+    pub base_volume: Uint128,
+    /// Total traded amount of the quote asset
+    pub quote_volume: Uint128,
+    /// Base asset volume simple moving average (mean per observation)
+    pub base_volume_sma: Uint128,
+    /// Quote asset volume simple moving average (mean per observation)
+    pub quote_volume_sma: Uint128,
+    /// The number of observations used
+    pub observations: u32,
+    /// Timestamp of the oldest used observation. None if there are no observations within the window
+    pub oldest_ts: Option<u64>,
+}
+
+/// Commits the observation captured in a previous block to the price and volume buffers.
+/// Observations of the current block are still being accumulated, thus they are skipped.
+/// Buffers are initialized on the first call, so pairs don't need a migration to start recording observations.
+pub fn accumulate_swap_sizes(
+    storage: &mut dyn Storage,
+    env: &Env,
+    observations: CircularBuffer<Observation>,
+    volumes: CircularBuffer<VolumeObservation>,
+) -> StdResult<()> {
+    let Some(PrecommitObservation {
+        base_amount,
+        quote_amount,
+        precommit_ts,
+    }) = PrecommitObservation::may_load(storage)?
+    else {
+        return Ok(());
+    };
+
+    if precommit_ts >= env.block.time.seconds() {
+        return Ok(());
+    }
+
+    if observations.state().may_load(storage)?.is_none() {
+        BufferManager::init(storage, observations, OBSERVATIONS_SIZE)?;
+    }
+    if volumes.state().may_load(storage)?.is_none() {
+        BufferManager::init(storage, volumes, VOLUME_OBSERVATIONS_SIZE)?;
+    }
+
+    let mut buffer = BufferManager::new(storage, observations)?;
+    let observed_price = Decimal::from_ratio(base_amount, quote_amount);
+
+    let price_sma = match buffer.read_last(storage)? {
+        // Skip saving observation if it has been already saved
+        Some(last_obs) if last_obs.ts >= precommit_ts => return Ok(()),
+        Some(last_obs) => {
+            // Once the buffer is full, the head points to the oldest observation
+            if let Some(oldest_obs) = buffer.read_single(storage, buffer.head())? {
+                safe_sma_calculation(
+                    last_obs.price_sma,
+                    oldest_obs.price,
+                    buffer.capacity(),
+                    observed_price,
+                )?
+            } else {
+                safe_sma_buffer_not_full(last_obs.price_sma, buffer.head(), observed_price)?
+            }
+        }
+        None => observed_price,
+    };
+
+    buffer.instant_push(
+        storage,
+        &Observation {
+            ts: precommit_ts,
+            price: observed_price,
+            price_sma,
+        },
+    )?;
+
+    BufferManager::new(storage, volumes)?.instant_push(
+        storage,
+        &VolumeObservation {
+            ts: precommit_ts,
+            base_volume: base_amount,
+            quote_volume: quote_amount,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Returns the swap volumes observed within the last `window` seconds.
+/// Volumes of the current block are not committed yet, thus they are not counted.
+pub fn query_volume_sma<C>(
+    deps: Deps<C>,
+    env: Env,
+    volumes: CircularBuffer<VolumeObservation>,
+    window: u64,
+) -> StdResult<VolumeSmaResponse>
+where
+    C: CustomQuery,
+{
+    let start_ts = env.block.time.seconds().saturating_sub(window);
+
+    let mut response = VolumeSmaResponse {
+        base_volume: Uint128::zero(),
+        quote_volume: Uint128::zero(),
+        base_volume_sma: Uint128::zero(),
+        quote_volume_sma: Uint128::zero(),
+        observations: 0,
+        oldest_ts: None,
+    };
+
+    if volumes.state().may_load(deps.storage)?.is_none() {
+        return Ok(response);
+    }
+
+    let buffer = BufferManager::new(deps.storage, volumes)?;
+    let capacity = buffer.capacity();
+    for i in 1..=capacity {
+        match buffer.read_single(deps.storage, (buffer.head() + capacity - i) % capacity)? {
+            Some(obs) if obs.ts >= start_ts => {
+                response.base_volume = response.base_volume.checked_add(obs.base_volume)?;
+                response.quote_volume = response.quote_volume.checked_add(obs.quote_volume)?;
+                response.observations += 1;
+                response.oldest_ts = Some(obs.ts);
+            }
+            _ => break,
+        }
+    }
+
+    if response.observations > 0 {
+        response.base_volume_sma = response.base_volume / Uint128::from(response.observations);
+        response.quote_volume_sma = response.quote_volume / Uint128::from(response.observations);
+    }
+
+    Ok(response)
+}
+
+/// Circular buffer size which stores fee observations. Together with [`FEE_OBSERVATION_PERIOD`] it covers 30 days
+pub const FEE_OBSERVATIONS_SIZE: u32 = 720;
+/// The minimum time between two consecutive fee observations (1 hour)
+pub const FEE_OBSERVATION_PERIOD: u64 = 3600;
+/// The number of seconds in a year used to annualize fees
+pub const SECONDS_PER_YEAR: u64 = 31536000;
+
+/// Snapshot of the cumulative LP fees. We use it to estimate the fee APR over a window.
+#[cw_serde]
+pub struct FeeObservation {
+    /// Timestamp of the observation
+    pub ts: u64,
+    /// Cumulative LP fees in the pair assets order
+    pub lp_fees: Vec<Uint128>,
+}
+
+/// Stores the cumulative LP fees if at least [`FEE_OBSERVATION_PERIOD`] seconds passed since the last observation.
+/// Must be called before the fees of the current swap are accrued.
+/// The buffer is initialized on the first call, so pairs don't need a migration to start recording fees.
+pub fn store_fee_observation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    observations: CircularBuffer<FeeObservation>,
+    lp_fees: Vec<Uint128>,
+) -> StdResult<()> {
+    if observations.state().may_load(storage)?.is_none() {
+        BufferManager::init(storage, observations, FEE_OBSERVATIONS_SIZE)?;
+    }
+    let mut buffer = BufferManager::new(storage, observations)?;
+
+    let ts = env.block.time.seconds();
+    if let Some(last) = buffer.read_last(storage)? {
+        if last.ts + FEE_OBSERVATION_PERIOD > ts {
+            return Ok(());
+        }
+    }
+
+    buffer.instant_push(storage, &FeeObservation { ts, lp_fees })?;
+
+    Ok(())
+}
+
+/// Estimates the fee APR from the LP fees accrued since the oldest observation within the last `window` seconds.
+/// The pool is assumed to hold equal value in each asset, which is exact for constant product and PCL pairs
+/// and close to it for balanced stableswap pairs, so fees are valued against the reserves of the same asset.
+///
+/// * **lp_fees** the current cumulative LP fees in the pair assets order.
+///
+/// * **reserves** the current pool reserves in the pair assets order.
+pub fn query_fee_apr<C>(
+    deps: Deps<C>,
+    env: Env,
+    observations: CircularBuffer<FeeObservation>,
+    lp_fees: Vec<Uint128>,
+    reserves: Vec<Asset>,
+    window: u64,
+) -> StdResult<FeeAprResponse>
+where
+    C: CustomQuery,
+{
+    let now = env.block.time.seconds();
+    let start_ts = now.saturating_sub(window);
+
+    let mut oldest = None;
+    if observations.state().may_load(deps.storage)?.is_some() {
+        let buffer = BufferManager::new(deps.storage, observations)?;
+        let capacity = buffer.capacity();
+        for i in 1..=capacity {
+            match buffer.read_single(deps.storage, (buffer.head() + capacity - i) % capacity)? {
+                Some(obs) if obs.ts >= start_ts => oldest = Some(obs),
+                _ => break,
+            }
+        }
+    }
+
+    let (period, lp_fees) = match oldest {
+        Some(obs) if obs.ts < now => (
+            now - obs.ts,
+            lp_fees
+                .iter()
+                .zip(&obs.lp_fees)
+                .map(|(current, observed)| current.saturating_sub(*observed))
+                .collect(),
+        ),
+        _ => (0, vec![Uint128::zero(); reserves.len()]),
+    };
+
+    let mut apr = Decimal256::zero();
+    if period > 0 && !reserves.is_empty() {
+        for (fee, reserve) in lp_fees.iter().zip(reserves.iter()) {
+            if !reserve.amount.is_zero() {
+                apr += Decimal256::from_ratio(*fee, reserve.amount);
+            }
+        }
+        apr = apr * Decimal256::from_ratio(SECONDS_PER_YEAR, period * reserves.len() as u64);
+    }
+
+    Ok(FeeAprResponse {
+        apr: try_dec256_into_dec(apr)?,
+        period,
+        lp_fees: reserves
+            .into_iter()
+            .zip(lp_fees)
+            .map(|(reserve, fee)| reserve.info.with_balance(fee))
+            .collect(),
+    })
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::to_json_binary;
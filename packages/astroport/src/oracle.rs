@@ -1,6 +1,6 @@
 use crate::asset::AssetInfo;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Uint128, Uint256};
+use cosmwasm_std::{Addr, Uint128, Uint256};
 
 /// This structure stores general parameters for the contract.
 #[cw_serde]
@@ -9,6 +9,9 @@ pub struct InstantiateMsg {
     pub factory_contract: String,
     /// The assets that have a pool for which this contract provides price feeds
     pub asset_infos: Vec<AssetInfo>,
+    /// If set, the oracle publishes average prices to this [`crate::price_hub`] contract on every update
+    #[serde(default)]
+    pub price_hub: Option<String>,
 }
 
 /// This structure describes the execute functions available in the contract.
@@ -16,6 +19,9 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Update/accumulate prices
     Update {},
+    /// Sets the price hub which receives average prices on every update.
+    /// Publishing is disabled if not set. Only the owner can execute this
+    SetPriceHub { price_hub: Option<String> },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -30,6 +36,23 @@ pub enum QueryMsg {
         /// The amount of tokens for which to compute the token price
         amount: Uint128,
     },
+    /// Same as [`QueryMsg::Consult`] but for a batch of assets in one call.
+    /// Results are returned in the same order as the assets in the request
+    #[returns(Vec<(AssetInfo, Vec<(AssetInfo, Uint256)>)>)]
+    ConsultMany {
+        /// Pairs of (asset, amount) for which to compute TWAP converted amounts
+        assets: Vec<(AssetInfo, Uint128)>,
+    },
+    /// Returns TWAP converted amounts for one whole unit (10^decimals) of each asset.
+    /// Results are returned in the same order as the assets in the request
+    #[returns(Vec<(AssetInfo, Vec<(AssetInfo, Uint256)>)>)]
+    ConsultManyPerUnit {
+        /// The assets for which to compute the price of one whole unit
+        assets: Vec<AssetInfo>,
+    },
+    /// Returns the price hub which receives average prices on every update
+    #[returns(Option<Addr>)]
+    PriceHub {},
 }
 
 /// This structure describes a migration message.
@@ -1,11 +1,21 @@
-use crate::observation::OracleObservation;
+use crate::observation::{
+    ObservationsInfoResponse, OracleObservation, SafePriceResponse, VolumeSmaResponse,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::asset::{Asset, AssetInfo, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
 
 use crate::factory::PairType;
-use cosmwasm_std::{Addr, Binary, Decimal, Decimal256, StdError, Uint128, Uint64};
+use crate::lp_pricing::VirtualPriceResponse;
+use crate::token::is_valid_symbol;
+use crate::token_factory::{tf_set_denom_metadata_msg, DenomUnit, Metadata};
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, CosmosMsg, CustomMsg, Decimal, Decimal256, StdError, StdResult,
+    Storage, Uint128, Uint256, Uint64,
+};
 use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Map;
+use sha2::{Digest, Sha256};
 
 /// The default swap slippage
 pub const DEFAULT_SLIPPAGE: &str = "0.005";
@@ -13,14 +23,38 @@ pub const DEFAULT_SLIPPAGE: &str = "0.005";
 pub const MAX_ALLOWED_SLIPPAGE: &str = "0.5";
 /// The maximum fee share allowed, 10%
 pub const MAX_FEE_SHARE_BPS: u16 = 1000;
+/// The maximum flash loan fee allowed, 10%
+pub const MAX_FLASH_LOAN_FEE_BPS: u16 = 1000;
+/// The maximum swap fee allowed with dynamic fees in stableswap pools, 10%
+pub const MAX_DYNAMIC_FEE_BPS: u16 = 1000;
 
 /// Decimal precision for TWAP results
 pub const TWAP_PRECISION: u8 = 6;
 
+/// Number of decimals of token factory LP tokens
+pub const LP_TOKEN_DECIMALS: u32 = 6;
+
+/// The maximum number of asset decimals supported by pairs which rely on asset precisions
+pub const MAX_ASSET_DECIMALS: u8 = 18;
+
 /// Min safe trading size (0.00001) to calculate a price. This value considers
 /// amount in decimal form with respective token precision.
 pub const MIN_TRADE_SIZE: Decimal256 = Decimal256::raw(10000000000000);
 
+/// The side of a swap on which a pair charges its commission.
+#[cw_serde]
+pub enum CommissionAsset {
+    /// Commission is deducted from the offer asset before the swap
+    Offer,
+    /// Commission is deducted from the ask asset after the swap
+    Ask,
+}
+
+/// Every Astroport pair takes its commission from the ask asset. Both `commission_amount` and
+/// `spread_amount` in [`SimulationResponse`] and [`ReverseSimulationResponse`] are denominated
+/// in ask asset units regardless of the pair type. Pairs return it in [`QueryMsg::CommissionAsset`].
+pub const COMMISSION_ASSET: CommissionAsset = CommissionAsset::Ask;
+
 /// This structure describes the parameters used for creating a contract.
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -52,12 +86,20 @@ pub enum ExecuteMsg {
         /// The receiver of LP tokens
         receiver: Option<String>,
         min_lp_to_receive: Option<Uint128>,
+        /// Staking contract which receives auto-staked LP tokens.
+        /// Must be one of the staking contracts registered in the factory. Factory default is used if not set.
+        staking_target: Option<String>,
+        /// Unix timestamp in seconds after which the message reverts
+        deadline: Option<u64>,
     },
     /// WithdrawLiquidity allows someone to withdraw liquidity from the pool
     WithdrawLiquidity {
+        /// Exact amounts of assets to withdraw (imbalanced withdrawal). Supported by stableswap pairs only
         #[serde(default)]
         assets: Vec<Asset>,
         min_assets_to_receive: Option<Vec<Asset>>,
+        /// Unix timestamp in seconds after which the message reverts
+        deadline: Option<u64>,
     },
     /// Swap performs a swap in the pool
     Swap {
@@ -66,6 +108,12 @@ pub enum ExecuteMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        /// Unix timestamp in seconds after which the message reverts
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline: Option<u64>,
+        /// Binds the swap to a recipient committed in advance. If set, `to` must match the commitment
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_commitment: Option<RecipientCommitment>,
     },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
@@ -81,6 +129,95 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Used to claim contract ownership.
     ClaimOwnership {},
+    /// Lends a pool asset to the sender which must be a contract implementing [`FlashLoanReceiverMsg`].
+    /// The loan plus fee must be returned to the pair within the callback, otherwise the whole transaction reverts.
+    /// The fee stays in the pool. Available only if flash loans are enabled in the factory and in the pair.
+    /// Supported by constant product and stableswap pairs only.
+    FlashLoan {
+        /// The borrowed asset
+        asset: Asset,
+        /// Passed to the borrower's callback as is
+        msg: Binary,
+    },
+    /// Sends `ask_asset` to the sender which must be a contract implementing [`FlashLoanReceiverMsg`].
+    /// The sender pays for it in `offer_asset_info` within the callback. The swap is priced as a regular one
+    /// plus the flash loan fee, otherwise the whole transaction reverts.
+    /// Available only if flash loans are enabled in the factory and in the pair.
+    /// Supported by constant product and stableswap pairs only.
+    FlashSwap {
+        /// The asset received before paying for it
+        ask_asset: Asset,
+        /// The asset used to pay for the swap
+        offer_asset_info: AssetInfo,
+        /// Passed to the borrower's callback as is
+        msg: Binary,
+    },
+    /// Verifies that the flash loan or flash swap was repaid. Can be called only by the pair itself
+    FinalizeFlashLoan {},
+    /// Updates the internal oracle price and repegs the pool if the price scale is stale.
+    /// Permissionless. Meant to be called by keepers for pools that have been idle for a while.
+    /// Supported by concentrated pairs only.
+    Poke {},
+    /// Halts swaps and liquidity provision. Withdrawals are still allowed.
+    /// Only the circuit breaker guardian or the pair owner can execute this.
+    /// Supported by constant product and stableswap pairs only.
+    HaltPair {},
+    /// Resumes swaps and liquidity provision after the pair was halted.
+    /// Only the circuit breaker guardian or the pair owner can execute this.
+    /// Supported by constant product and stableswap pairs only.
+    ResumePair {},
+    /// Settles swaps queued in previous blocks at a uniform clearing price.
+    /// Permissionless. Meant to be called by keepers or a chain cron at the end of the block.
+    /// Supported by constant product pairs with batch swaps enabled only.
+    SettleBatch {},
+    /// Sends the sender the batch outputs and refunds which failed to reach them during the settlement.
+    /// Supported by constant product pairs only.
+    ClaimBatchPayouts {},
+    /// Sends the sender the liquidity refund parts which couldn't be withdrawn from the lending market
+    /// during the liquidity withdrawal. Supported by the Red Bank constant product pair only.
+    ClaimLentRefunds {},
+}
+
+/// The callback message which a flash loan borrower must implement.
+#[cw_serde]
+pub enum FlashLoanReceiverMsg {
+    /// Sent to the borrower right after the loan is transferred.
+    /// The borrower must send `loan` plus `fee` back to the pair before the callback ends
+    FlashLoanCallback {
+        loan: Asset,
+        fee: Asset,
+        msg: Binary,
+    },
+    /// Sent to the borrower right after the ask asset of a flash swap is transferred.
+    /// The borrower must send `offer` to the pair before the callback ends.
+    /// `offer` includes the swap commission and the flash loan fee
+    FlashSwapCallback {
+        ask: Asset,
+        offer: Asset,
+        msg: Binary,
+    },
+}
+
+/// Returns the flash loan fee charged on the given amount.
+/// The fee is rounded up so that small loans are not free.
+pub fn flash_loan_fee(amount: Uint128, fee_bps: u16) -> StdResult<Uint128> {
+    let fee = Uint256::from(amount)
+        .checked_mul(Uint256::from(fee_bps))?
+        .checked_add(Uint256::from(9999u16))?
+        .checked_div(Uint256::from(10000u16))?;
+
+    Ok(fee.try_into()?)
+}
+
+/// Commitment to the swap recipient.
+/// Used in meta-transaction flows where the signed message is completed by a relayer:
+/// the user signs only the hash so the relayer can't redirect swap outputs to another address.
+#[cw_serde]
+pub struct RecipientCommitment {
+    /// sha256 hash of the recipient address bytes concatenated with the salt
+    pub hash: Binary,
+    /// Salt revealed at execution
+    pub salt: Binary,
 }
 
 /// This structure describes a CW20 hook message.
@@ -92,6 +229,12 @@ pub enum Cw20HookMsg {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
+        /// Unix timestamp in seconds after which the message reverts
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline: Option<u64>,
+        /// Binds the swap to a recipient committed in advance. If set, `to` must match the commitment
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_commitment: Option<RecipientCommitment>,
     },
 }
 
@@ -138,6 +281,18 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Returns the median of the prices observed within the last `window` seconds.
+    /// Supported by stableswap and constant product pairs only
+    #[returns(SafePriceResponse)]
+    SafePrice { window: u64 },
+    /// Returns the observations buffer capacity, fill ratio and the number of recent observations.
+    /// Supported by stableswap and constant product pairs only
+    #[returns(ObservationsInfoResponse)]
+    ObservationsInfo {},
+    /// Returns the total and average swap volumes observed within the last `window` seconds.
+    /// Supported by constant product pairs only
+    #[returns(VolumeSmaResponse)]
+    VolumeSma { window: u64 },
     /// Returns an estimation of assets received for the given amount of LP tokens
     #[returns(Vec<Asset>)]
     SimulateWithdraw { lp_amount: Uint128 },
@@ -147,6 +302,372 @@ pub enum QueryMsg {
         assets: Vec<Asset>,
         slippage_tolerance: Option<Decimal>,
     },
+    /// Returns the amount of LP tokens locked in the pair contract on the first liquidity provision
+    #[returns(MinimumLiquidityResponse)]
+    MinimumLiquidity {},
+    /// Returns the asset in which the pair charges its commission and denominates
+    /// `commission_amount` and `spread_amount` of swap simulations
+    #[returns(CommissionAsset)]
+    CommissionAsset {},
+    /// Returns the most recent pool invariant checkpoints, newest first.
+    /// Supported by constant product pairs only.
+    #[returns(Vec<InvariantCheckpoint>)]
+    InvariantCheckpoints { limit: Option<u32> },
+    /// Returns whether the factory has scheduled a code migration of the pair.
+    /// Frontends use it to warn users about the upcoming migration
+    #[returns(MigrationStatusResponse)]
+    MigrationStatus {},
+    /// Returns swap fees collected in each pool asset since inception.
+    /// Supported by constant product, stableswap and PCL pairs.
+    /// Swaps made before the pair was upgraded to a version with fee accounting are not counted
+    #[returns(Vec<AccruedFeesResponse>)]
+    AccruedFees {},
+    /// Returns the liquidity providers' fee APR estimated from the swap fees accrued within the last `window` seconds.
+    /// Supported by constant product, stableswap and PCL pairs
+    #[returns(FeeAprResponse)]
+    FeeApr { window: u64 },
+    /// Returns the swaps waiting for the batch settlement.
+    /// Supported by constant product pairs only.
+    #[returns(Option<SwapBatch>)]
+    PendingBatch {},
+    /// Returns the batch outputs and refunds which failed to reach the given address and wait to be claimed.
+    /// Supported by constant product pairs only.
+    #[returns(Vec<Asset>)]
+    UnclaimedBatchPayouts { address: String },
+    /// Returns the liquidity refund parts of the given address which wait to be withdrawn from the lending market.
+    /// Supported by the Red Bank constant product pair only.
+    #[returns(Vec<Asset>)]
+    UnclaimedLentRefunds { address: String },
+    /// Returns the pool invariant per LP token. Lending protocols use it to price LP collateral,
+    /// see [`crate::lp_pricing`]. Supported by stableswap pairs only
+    #[returns(VirtualPriceResponse)]
+    VirtualPrice {},
+}
+
+impl QueryMsg {
+    /// Returns true if the query result depends on the current pool reserves.
+    /// Pairs reject such queries while a flash loan is in progress since the borrowed amount is out of the pool.
+    pub fn depends_on_reserves(&self) -> bool {
+        matches!(
+            self,
+            QueryMsg::Pool {}
+                | QueryMsg::Share { .. }
+                | QueryMsg::Simulation { .. }
+                | QueryMsg::ReverseSimulation { .. }
+                | QueryMsg::CumulativePrices {}
+                | QueryMsg::QueryComputeD {}
+                | QueryMsg::Observe { .. }
+                | QueryMsg::SafePrice { .. }
+                | QueryMsg::ObservationsInfo {}
+                | QueryMsg::VolumeSma { .. }
+                | QueryMsg::SimulateWithdraw { .. }
+                | QueryMsg::SimulateProvide { .. }
+                | QueryMsg::FeeApr { .. }
+                | QueryMsg::VirtualPrice {}
+        )
+    }
+}
+
+/// Cumulative swap fees collected by a pair in a specific asset.
+#[cw_serde]
+#[derive(Default)]
+pub struct AccruedFees {
+    /// Total commission charged on swaps
+    pub total: Uint128,
+    /// The part of the commission sent to the Maker
+    pub maker: Uint128,
+    /// The part of the commission sent to the fee share recipient
+    pub fee_share: Uint128,
+    /// The part of the commission left in the pool for liquidity providers
+    pub lp: Uint128,
+}
+
+impl AccruedFees {
+    /// Records the fees of a single swap. The LP share is the commission left after the maker fee and the fee share.
+    /// Counters saturate rather than fail as fee accounting must never block swaps.
+    pub fn add(&mut self, commission: Uint128, maker_fee: Uint128, fee_share: Uint128) {
+        let lp = commission.saturating_sub(maker_fee + fee_share);
+
+        self.total = self.total.saturating_add(commission);
+        self.maker = self.maker.saturating_add(maker_fee);
+        self.fee_share = self.fee_share.saturating_add(fee_share);
+        self.lp = self.lp.saturating_add(lp);
+    }
+}
+
+/// This structure describes the swap fees collected by a pair in a specific asset.
+#[cw_serde]
+pub struct AccruedFeesResponse {
+    pub asset_info: AssetInfo,
+    pub fees: AccruedFees,
+}
+
+/// Loads the LP part of the accrued swap fees in the given assets order.
+pub fn load_lp_fees(
+    storage: &dyn Storage,
+    accrued_fees: Map<&AssetInfo, AccruedFees>,
+    asset_infos: &[AssetInfo],
+) -> StdResult<Vec<Uint128>> {
+    asset_infos
+        .iter()
+        .map(|asset_info| {
+            Ok(accrued_fees
+                .may_load(storage, asset_info)?
+                .map(|fees| fees.lp)
+                .unwrap_or_default())
+        })
+        .collect()
+}
+
+/// The estimated fee APR of a pair.
+#[cw_serde]
+pub struct FeeAprResponse {
+    /// Annualized LP fees relative to the pool reserves
+    pub apr: Decimal,
+    /// The number of seconds the estimation is based on. It is shorter than the requested window
+    /// if the pair has no fee observations that old. Zero if there is no observation within the window
+    pub period: u64,
+    /// LP fees accrued within the period
+    pub lp_fees: Vec<Asset>,
+}
+
+/// This structure describes the pair migration status.
+#[cw_serde]
+pub struct MigrationStatusResponse {
+    /// Whether the pair is scheduled for a code migration in the factory
+    pub migration_scheduled: bool,
+}
+
+/// Pool state recorded after every swap, liquidity provision and withdrawal.
+/// Lets indexers verify reserve continuity between consecutive operations.
+#[cw_serde]
+pub struct InvariantCheckpoint {
+    /// Block height of the operation
+    pub block_height: u64,
+    /// Block timestamp (in seconds) of the operation
+    pub block_time: u64,
+    /// Pool reserves after the operation in the pair assets order
+    pub reserves: Vec<Asset>,
+    /// Constant product invariant: the product of the reserves
+    pub k: Uint256,
+}
+
+/// This structure describes the LP tokens locked in the pair contract on the first liquidity provision.
+#[cw_serde]
+pub struct MinimumLiquidityResponse {
+    /// Amount of LP tokens deducted from the first provider's share
+    pub amount: Uint128,
+    /// Address which holds these LP tokens forever (the pair contract itself)
+    pub locked_in: Addr,
+}
+
+impl MinimumLiquidityResponse {
+    pub fn new(pair_addr: Addr) -> Self {
+        Self {
+            amount: MINIMUM_LIQUIDITY_AMOUNT,
+            locked_in: pair_addr,
+        }
+    }
+}
+
+/// Returns an error if the current block time is past the specified deadline.
+///
+/// * **deadline** unix timestamp in seconds. No check is performed if not set.
+pub fn check_deadline(block: &BlockInfo, deadline: Option<u64>) -> StdResult<()> {
+    match deadline {
+        Some(deadline) if block.time.seconds() > deadline => Err(StdError::generic_err(format!(
+            "Deadline {deadline} has passed. Current block time: {}",
+            block.time.seconds()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if the swap recipient doesn't match the commitment.
+///
+/// * **to** revealed swap recipient.
+///
+/// * **commitment** recipient commitment. No check is performed if not set.
+pub fn check_recipient_commitment(
+    to: Option<&str>,
+    commitment: Option<&RecipientCommitment>,
+) -> StdResult<()> {
+    let Some(commitment) = commitment else {
+        return Ok(());
+    };
+
+    let to = to.ok_or_else(|| {
+        StdError::generic_err("Swap recipient must be set when the recipient commitment is used")
+    })?;
+
+    let hash = Sha256::new()
+        .chain_update(to.as_bytes())
+        .chain_update(commitment.salt.as_slice())
+        .finalize();
+
+    if hash.as_slice() != commitment.hash.as_slice() {
+        return Err(StdError::generic_err(
+            "Swap recipient doesn't match the commitment",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Applies balance cap updates on top of the current caps and returns the resulting list.
+/// A zero amount removes the cap for the respective asset.
+///
+/// * **pair_assets** assets of the pair. Caps can be set only for these assets.
+pub fn update_balance_caps(
+    mut caps: Vec<Asset>,
+    updates: Vec<Asset>,
+    pair_assets: &[AssetInfo],
+) -> StdResult<Vec<Asset>> {
+    for (i, update) in updates.iter().enumerate() {
+        if !pair_assets.contains(&update.info) {
+            return Err(StdError::generic_err(format!(
+                "Asset {} is not in the pair",
+                update.info
+            )));
+        }
+
+        if updates[..i].iter().any(|other| other.info == update.info) {
+            return Err(StdError::generic_err(format!(
+                "Duplicated balance cap for {}",
+                update.info
+            )));
+        }
+    }
+
+    for update in updates {
+        caps.retain(|cap| cap.info != update.info);
+        if !update.amount.is_zero() {
+            caps.push(update);
+        }
+    }
+
+    Ok(caps)
+}
+
+/// Returns an error if any pool balance exceeds its cap.
+///
+/// * **pools** pool balances after the liquidity provision.
+pub fn check_balance_caps(caps: &[Asset], pools: &[Asset]) -> StdResult<()> {
+    for cap in caps {
+        let balance = pools
+            .iter()
+            .find(|pool| pool.info == cap.info)
+            .map(|pool| pool.amount)
+            .unwrap_or_default();
+
+        if balance > cap.amount {
+            return Err(StdError::generic_err(format!(
+                "Pool balance of {} would exceed its cap of {}",
+                cap.info, cap.amount
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// A swap queued in the batch mode until the batch is settled.
+#[cw_serde]
+pub struct BatchedSwap {
+    pub sender: Addr,
+    pub receiver: Addr,
+    pub offer_asset: Asset,
+    /// Checked against the clearing price. The swap is refunded if the check fails
+    pub belief_price: Option<Decimal>,
+    pub max_spread: Option<Decimal>,
+}
+
+/// Swaps collected within a single block. All of them are settled at the same price.
+#[cw_serde]
+pub struct SwapBatch {
+    /// The block at which the swaps were queued
+    pub height: u64,
+    pub swaps: Vec<BatchedSwap>,
+}
+
+impl SwapBatch {
+    /// Returns the total amount offered in the given asset.
+    pub fn escrow(&self, asset_info: &AssetInfo) -> Uint128 {
+        self.swaps
+            .iter()
+            .filter(|swap| swap.offer_asset.info.equal(asset_info))
+            .map(|swap| swap.offer_asset.amount)
+            .sum()
+    }
+}
+
+/// Rejects swaps and liquidity provisions which would shift the pool reserve ratio too much.
+/// The guardian can also halt the pair so that only withdrawals are allowed until it is resumed.
+#[cw_serde]
+pub struct CircuitBreaker {
+    /// The address which can halt and resume the pair
+    pub guardian: Addr,
+    /// Max relative change of the reserve ratio caused by a single swap or liquidity provision
+    pub max_ratio_change: Decimal,
+    /// The time when the guardian halted the pair. None while the pair operates normally
+    pub tripped_at: Option<u64>,
+}
+
+impl CircuitBreaker {
+    /// Returns the reserve ratio change between `old_reserves` and `new_reserves`
+    /// if it exceeds the limit, i.e. the operation must be rejected.
+    pub fn check(
+        &self,
+        old_reserves: &[Asset],
+        new_reserves: &[Asset],
+    ) -> StdResult<Option<Decimal256>> {
+        let change = reserve_ratio_change(old_reserves, new_reserves)?;
+        Ok((change > Decimal256::from(self.max_ratio_change)).then_some(change))
+    }
+}
+
+/// Returns the largest relative change of reserve ratios between the first pool asset and every other asset.
+/// Returns zero if any reserve is empty since the ratio is not defined.
+pub fn reserve_ratio_change(
+    old_reserves: &[Asset],
+    new_reserves: &[Asset],
+) -> StdResult<Decimal256> {
+    if old_reserves.len() != new_reserves.len()
+        || old_reserves
+            .iter()
+            .chain(new_reserves)
+            .any(|reserve| reserve.amount.is_zero())
+    {
+        return Ok(Decimal256::zero());
+    }
+
+    let (old_base, new_base) = (old_reserves[0].amount, new_reserves[0].amount);
+    let mut max_change = Decimal256::zero();
+    for (old, new) in old_reserves.iter().zip(new_reserves).skip(1) {
+        // (new / new_base) / (old / old_base)
+        let ratio = Decimal256::checked_from_ratio(
+            new.amount.full_mul(old_base),
+            old.amount.full_mul(new_base),
+        )
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let change = if ratio > Decimal256::one() {
+            ratio - Decimal256::one()
+        } else {
+            Decimal256::one() - ratio
+        };
+        max_change = max_change.max(change);
+    }
+
+    Ok(max_change)
+}
+
+/// Deducts [`MINIMUM_LIQUIDITY_AMOUNT`] from the initial LP share.
+/// Returns None if nothing is left for the first liquidity provider.
+pub fn initial_share_after_lockup(share: Uint128) -> Option<Uint128> {
+    share
+        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
+        .ok()
+        .filter(|share| !share.is_zero())
 }
 
 /// This struct is used to return a query result with the total amount of LP tokens and assets in a specific pool.
@@ -171,6 +692,28 @@ pub struct ConfigResponse {
     pub factory_addr: Addr,
     /// Tracker contract address
     pub tracker_addr: Option<Addr>,
+    /// The pair type. Absent in responses of pairs deployed before this field was introduced
+    pub pair_type: Option<PairType>,
+    /// Pair creation metadata. Not recorded for pairs created before this field was introduced
+    pub created_at: Option<PairCreationInfo>,
+}
+
+/// This structure describes when a pair was created.
+#[cw_serde]
+pub struct PairCreationInfo {
+    /// Block height at which the pair was instantiated
+    pub height: u64,
+    /// Block timestamp (in seconds) at which the pair was instantiated
+    pub time: u64,
+}
+
+impl From<&BlockInfo> for PairCreationInfo {
+    fn from(block: &BlockInfo) -> Self {
+        Self {
+            height: block.height,
+            time: block.time.seconds(),
+        }
+    }
 }
 
 /// Holds the configuration for fee sharing
@@ -180,6 +723,21 @@ pub struct FeeShareConfig {
     pub bps: u16,
     /// The share is sent to this address on every swap
     pub recipient: Addr,
+    /// Whether the recipient is notified with [`FeeShareCallbackMsg`] after every fee transfer.
+    /// Only supported by PCL and stableswap pairs
+    #[serde(default)]
+    pub callback: bool,
+}
+
+/// This enum describes the callback which a pair sends to the fee share recipient contract
+/// right after the shared fee transfer. The message sender is the pair the fee came from.
+#[cw_serde]
+pub enum FeeShareCallbackMsg {
+    /// Notifies the recipient about the received fee share
+    FeeShareReceived {
+        /// The shared fee sent to the recipient
+        asset: Asset,
+    },
 }
 
 /// This structure holds the parameters that are returned from a swap simulation response
@@ -187,9 +745,9 @@ pub struct FeeShareConfig {
 pub struct SimulationResponse {
     /// The amount of ask assets returned by the swap
     pub return_amount: Uint128,
-    /// The spread used in the swap operation
+    /// The spread used in the swap operation denominated in the ask asset
     pub spread_amount: Uint128,
-    /// The amount of fees charged by the transaction
+    /// The amount of fees charged by the transaction denominated in the ask asset
     pub commission_amount: Uint128,
 }
 
@@ -198,9 +756,9 @@ pub struct SimulationResponse {
 pub struct ReverseSimulationResponse {
     /// The amount of offer assets returned by the reverse swap
     pub offer_amount: Uint128,
-    /// The spread used in the swap operation
+    /// The spread used in the swap operation denominated in the ask asset
     pub spread_amount: Uint128,
-    /// The amount of fees charged by the transaction
+    /// The amount of fees charged by the transaction denominated in the ask asset
     pub commission_amount: Uint128,
 }
 
@@ -220,6 +778,71 @@ pub struct CumulativePricesResponse {
 #[cw_serde]
 pub struct MigrateMsg {}
 
+/// Custom metadata of a token factory LP token.
+/// If not set, the LP denom is created without bank metadata.
+#[cw_serde]
+pub struct LpTokenMetadata {
+    /// LP token name (3-50 UTF-8 bytes)
+    pub name: String,
+    /// LP token ticker ([a-zA-Z0-9\-]{3,12})
+    pub symbol: String,
+}
+
+impl LpTokenMetadata {
+    pub fn validate(&self) -> StdResult<()> {
+        if self.name.len() < 3 || self.name.len() > 50 {
+            return Err(StdError::generic_err(
+                "LP token name is not in the expected format (3-50 UTF-8 bytes)",
+            ));
+        }
+        if !is_valid_symbol(&self.symbol) {
+            return Err(StdError::generic_err(
+                "LP token symbol is not in expected format [a-zA-Z0-9\\-]{3,12}",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds token factory message which sets bank metadata for the LP denom.
+    /// LP token is displayed with [`LP_TOKEN_DECIMALS`] under its symbol.
+    pub fn into_denom_metadata_msg<T>(
+        self,
+        sender: impl Into<String>,
+        lp_denom: impl Into<String>,
+    ) -> CosmosMsg<T>
+    where
+        T: CustomMsg,
+    {
+        let lp_denom = lp_denom.into();
+
+        tf_set_denom_metadata_msg(
+            sender,
+            Metadata {
+                description: format!("Astroport LP token {}", self.name),
+                denom_units: vec![
+                    DenomUnit {
+                        denom: lp_denom.clone(),
+                        exponent: 0,
+                        aliases: vec![],
+                    },
+                    DenomUnit {
+                        denom: self.symbol.clone(),
+                        exponent: LP_TOKEN_DECIMALS,
+                        aliases: vec![],
+                    },
+                ],
+                base: lp_denom,
+                display: self.symbol.clone(),
+                name: self.name,
+                symbol: self.symbol,
+                uri: String::new(),
+                uri_hash: String::new(),
+            },
+        )
+    }
+}
+
 /// This structure holds XYK pool parameters.
 #[cw_serde]
 pub struct XYKPoolParams {
@@ -227,6 +850,13 @@ pub struct XYKPoolParams {
     /// They will not be tracked if the parameter is ignored.
     /// It can not be disabled later once enabled.
     pub track_asset_balances: Option<bool>,
+    /// Custom LP token name and symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lp_token_metadata: Option<LpTokenMetadata>,
+    /// Per-asset caps on pool balances for a guarded launch phase.
+    /// Liquidity provision fails if any capped balance would be exceeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_caps: Option<Vec<Asset>>,
 }
 
 /// This structure stores a XYK pool's configuration.
@@ -236,6 +866,21 @@ pub struct XYKPoolConfig {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// Flash loan fee in bps. Not set if the pair doesn't provide flash loans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flash_loan_fee_bps: Option<u16>,
+    /// Current caps on pool balances. Empty if liquidity provision is not capped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_caps: Vec<Asset>,
+    /// The circuit breaker settings and state. Not set if the circuit breaker is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// Whether swaps are collected within a block and settled at a uniform clearing price
+    #[serde(default)]
+    pub batch_swaps: bool,
+    /// Minimum amounts which can be offered in a batched swap. Empty if there is no minimum
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batch_min_offer: Vec<Asset>,
 }
 
 /// This enum stores the option available to enable asset balances tracking over blocks.
@@ -249,6 +894,36 @@ pub enum XYKPoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Enables flash loans in the pair. They are available only if also enabled in the factory.
+    EnableFlashLoans {
+        /// The fee charged on the borrowed amount
+        fee_bps: u16,
+    },
+    DisableFlashLoans,
+    /// Sets caps on pool balances. A zero amount removes the cap for the respective asset.
+    /// Assets not mentioned keep their current caps.
+    UpdateBalanceCaps {
+        caps: Vec<Asset>,
+    },
+    /// Enables the circuit breaker or updates its settings. The halted state is kept as is.
+    SetCircuitBreaker {
+        /// The address which can halt and resume the pair
+        guardian: String,
+        /// Max relative change of the reserve ratio caused by a single swap or liquidity provision
+        max_ratio_change: Decimal,
+    },
+    /// Disables the circuit breaker and resumes the pair if it was halted.
+    RemoveCircuitBreaker,
+    /// Queues swaps instead of executing them right away. Swaps queued within a block
+    /// are settled at a single clearing price in the next block or by [`ExecuteMsg::SettleBatch`].
+    EnableBatchSwaps,
+    /// Settles the pending batch and switches back to instant swaps.
+    DisableBatchSwaps,
+    /// Sets minimum amounts which can be offered in a batched swap. A zero amount removes the minimum
+    /// for the respective asset. Assets not mentioned keep their current minimums.
+    UpdateBatchMinOffer {
+        min_offer: Vec<Asset>,
+    },
 }
 
 /// This structure holds stableswap pool parameters.
@@ -258,6 +933,13 @@ pub struct StablePoolParams {
     pub amp: u64,
     /// The contract owner
     pub owner: Option<String>,
+    /// Custom LP token name and symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lp_token_metadata: Option<LpTokenMetadata>,
+    /// Per-asset caps on pool balances for a guarded launch phase.
+    /// Liquidity provision fails if any capped balance would be exceeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_caps: Option<Vec<Asset>>,
 }
 
 /// This structure stores a stableswap pool's configuration.
@@ -267,6 +949,47 @@ pub struct StablePoolConfig {
     pub amp: Decimal,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// Flash loan fee in bps. Not set if the pair doesn't provide flash loans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flash_loan_fee_bps: Option<u16>,
+    /// Current caps on pool balances. Empty if liquidity provision is not capped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_caps: Vec<Asset>,
+    /// Maker fee override in bps. The factory setting applies if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maker_fee_bps: Option<u16>,
+    /// The circuit breaker settings and state. Not set if the circuit breaker is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// Swap fee tiers based on the pool imbalance. The factory fee applies if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dynamic_fees: Option<DynamicFees>,
+}
+
+/// Swap fees of a stableswap pool which depend on the pool imbalance.
+/// The imbalance is measured as `1 - min_reserve / max_reserve` using reserves normalized to the same precision.
+/// Swaps which increase the imbalance are charged `base + (max - base) * imbalance_after`.
+/// Swaps which reduce it get the base fee discounted by the removed imbalance: `base * (1 - (imbalance_before - imbalance_after))`.
+#[cw_serde]
+pub struct DynamicFees {
+    /// The swap fee in bps for swaps which don't change the pool imbalance
+    pub base_fee_bps: u16,
+    /// The swap fee in bps for swaps which leave the pool completely imbalanced
+    pub max_fee_bps: u16,
+}
+
+impl DynamicFees {
+    /// Returns the swap fee rate for the pool imbalance before and after the swap.
+    pub fn fee_rate(&self, imbalance_before: Decimal, imbalance_after: Decimal) -> Decimal {
+        let base = Decimal::from_ratio(self.base_fee_bps, 10000u16);
+        let max = Decimal::from_ratio(self.max_fee_bps, 10000u16);
+
+        if imbalance_after > imbalance_before {
+            base + (max - base) * imbalance_after.min(Decimal::one())
+        } else {
+            base * (Decimal::one() - (imbalance_before - imbalance_after).min(Decimal::one()))
+        }
+    }
 }
 
 /// This enum stores the options available to start and stop changing a stableswap pool's amplification.
@@ -283,8 +1006,52 @@ pub enum StablePoolUpdateParams {
         fee_share_bps: u16,
         /// The fee_share_bps is sent to this address on every swap
         fee_share_address: String,
+        /// Whether to notify the recipient contract with [`FeeShareCallbackMsg`]
+        /// after every fee transfer. Default: false
+        #[serde(default)]
+        callback: bool,
     },
     DisableFeeShare,
+    /// Enables flash loans in the pair. They are available only if also enabled in the factory.
+    EnableFlashLoans {
+        /// The fee charged on the borrowed amount
+        fee_bps: u16,
+    },
+    DisableFlashLoans,
+    /// Sets caps on pool balances. A zero amount removes the cap for the respective asset.
+    /// Assets not mentioned keep their current caps.
+    UpdateBalanceCaps {
+        caps: Vec<Asset>,
+    },
+    /// Overrides the maker share of the swap commission for this pool.
+    /// It can't exceed the maker fee set in the factory for this pair type.
+    /// The remainder of the commission goes to LPs.
+    SetMakerFee {
+        /// The maker fee in bps. None resets it to the factory setting
+        maker_fee_bps: Option<u16>,
+    },
+    /// Enables the circuit breaker or updates its settings. The halted state is kept as is.
+    SetCircuitBreaker {
+        /// The address which can halt and resume the pair
+        guardian: String,
+        /// Max relative change of the reserve ratio caused by a single swap or liquidity provision
+        max_ratio_change: Decimal,
+    },
+    /// Disables the circuit breaker and resumes the pair if it was halted.
+    RemoveCircuitBreaker,
+    /// Enables swap fees based on the pool imbalance or updates their settings.
+    /// They replace the factory fee for swaps while liquidity provision keeps using the factory fee.
+    SetDynamicFees {
+        /// The swap fee in bps for swaps which don't change the pool imbalance
+        base_fee_bps: u16,
+        /// The swap fee in bps for swaps which leave the pool completely imbalanced
+        max_fee_bps: u16,
+    },
+    /// Disables dynamic fees. Swaps are charged the factory fee again.
+    DisableDynamicFees,
+    /// Queries asset precisions again and overwrites the cached values.
+    /// Needed only if a precision was registered incorrectly in the native coin registry.
+    RefreshPrecisions {},
 }
 
 /// A `reply` call code ID used for sub-messages.
@@ -292,6 +1059,7 @@ pub enum StablePoolUpdateParams {
 pub enum ReplyIds {
     CreateDenom = 1,
     InstantiateTrackingContract = 2,
+    BatchPayout = 3,
 }
 
 impl TryFrom<u64> for ReplyIds {
@@ -301,6 +1069,7 @@ impl TryFrom<u64> for ReplyIds {
         match value {
             1 => Ok(ReplyIds::CreateDenom),
             2 => Ok(ReplyIds::InstantiateTrackingContract),
+            3 => Ok(ReplyIds::BatchPayout),
             _ => Err(StdError::ParseErr {
                 target_type: "ReplyIds".to_string(),
                 msg: "Failed to parse reply".to_string(),
@@ -330,6 +1099,10 @@ mod tests {
                 to_json_binary(&StablePoolConfig {
                     amp: Decimal::one(),
                     fee_share: None,
+                    flash_loan_fee_bps: None,
+                    balance_caps: vec![],
+                    maker_fee_bps: None,
+                    circuit_breaker: None,
                 })
                 .unwrap(),
             ),
@@ -338,6 +1111,191 @@ mod tests {
         })
         .unwrap();
 
-        let _: ConfigResponse = from_json(&ser_msg).unwrap();
+        let config: ConfigResponse = from_json(&ser_msg).unwrap();
+        assert_eq!(config.pair_type, None);
+        assert_eq!(config.created_at, None);
+    }
+    #[test]
+    fn test_recipient_commitment() {
+        let salt = Binary::from(b"salt".to_vec());
+        let hash = Sha256::new()
+            .chain_update(b"recipient")
+            .chain_update(b"salt")
+            .finalize();
+        let commitment = RecipientCommitment {
+            hash: Binary::from(hash.to_vec()),
+            salt,
+        };
+
+        check_recipient_commitment(None, None).unwrap();
+        check_recipient_commitment(Some("anyone"), None).unwrap();
+        check_recipient_commitment(Some("recipient"), Some(&commitment)).unwrap();
+
+        let err = check_recipient_commitment(None, Some(&commitment)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Swap recipient must be set when the recipient commitment is used"
+        );
+        let err = check_recipient_commitment(Some("relayer"), Some(&commitment)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Swap recipient doesn't match the commitment"
+        );
+        let wrong_salt = RecipientCommitment {
+            salt: Binary::from(b"other".to_vec()),
+            ..commitment
+        };
+        check_recipient_commitment(Some("recipient"), Some(&wrong_salt)).unwrap_err();
+    }
+
+    #[test]
+    fn test_balance_caps() {
+        use crate::asset::{native_asset, native_asset_info};
+
+        let pair_assets = [
+            native_asset_info("uusd".to_string()),
+            native_asset_info("uluna".to_string()),
+        ];
+
+        let err = update_balance_caps(
+            vec![],
+            vec![native_asset("uatom".to_string(), 100u128.into())],
+            &pair_assets,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Asset uatom is not in the pair"
+        );
+
+        let err = update_balance_caps(
+            vec![],
+            vec![
+                native_asset("uusd".to_string(), 100u128.into()),
+                native_asset("uusd".to_string(), 200u128.into()),
+            ],
+            &pair_assets,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Duplicated balance cap for uusd"
+        );
+
+        let caps = update_balance_caps(
+            vec![],
+            vec![
+                native_asset("uusd".to_string(), 100u128.into()),
+                native_asset("uluna".to_string(), 0u128.into()),
+            ],
+            &pair_assets,
+        )
+        .unwrap();
+        assert_eq!(caps, vec![native_asset("uusd".to_string(), 100u128.into())]);
+
+        check_balance_caps(
+            &caps,
+            &[
+                native_asset("uusd".to_string(), 100u128.into()),
+                native_asset("uluna".to_string(), 1_000_000u128.into()),
+            ],
+        )
+        .unwrap();
+        let err = check_balance_caps(
+            &caps,
+            &[
+                native_asset("uusd".to_string(), 101u128.into()),
+                native_asset("uluna".to_string(), 0u128.into()),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Pool balance of uusd would exceed its cap of 100"
+        );
+
+        // Raise one cap and remove the other
+        let caps = update_balance_caps(
+            caps,
+            vec![native_asset("uluna".to_string(), 500u128.into())],
+            &pair_assets,
+        )
+        .unwrap();
+        let caps = update_balance_caps(
+            caps,
+            vec![native_asset("uusd".to_string(), 0u128.into())],
+            &pair_assets,
+        )
+        .unwrap();
+        assert_eq!(
+            caps,
+            vec![native_asset("uluna".to_string(), 500u128.into())]
+        );
+    }
+
+    #[test]
+    fn test_reserve_ratio_change() {
+        use crate::asset::native_asset;
+
+        let reserves = |uusd: u128, uluna: u128| {
+            vec![
+                native_asset("uusd".to_string(), uusd.into()),
+                native_asset("uluna".to_string(), uluna.into()),
+            ]
+        };
+
+        // Balanced provision keeps the ratio
+        assert_eq!(
+            reserve_ratio_change(&reserves(100, 200), &reserves(150, 300)).unwrap(),
+            Decimal256::zero()
+        );
+        // The ratio is undefined for an empty pool
+        assert_eq!(
+            reserve_ratio_change(&reserves(0, 0), &reserves(100, 100)).unwrap(),
+            Decimal256::zero()
+        );
+        assert_eq!(
+            reserve_ratio_change(&reserves(100, 100), &reserves(125, 80)).unwrap(),
+            Decimal256::percent(36)
+        );
+        assert_eq!(
+            reserve_ratio_change(&reserves(100, 100), &reserves(80, 125)).unwrap(),
+            Decimal256::from_ratio(5625u128, 10000u128)
+        );
+
+        let circuit_breaker = CircuitBreaker {
+            guardian: Addr::unchecked("guardian"),
+            max_ratio_change: Decimal::percent(50),
+            tripped_at: None,
+        };
+        assert_eq!(
+            circuit_breaker
+                .check(&reserves(100, 100), &reserves(125, 80))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            circuit_breaker
+                .check(&reserves(100, 100), &reserves(80, 125))
+                .unwrap(),
+            Some(Decimal256::from_ratio(5625u128, 10000u128))
+        );
+    }
+
+    #[test]
+    fn test_accrued_fees() {
+        let mut fees = AccruedFees::default();
+        fees.add(1000u128.into(), 250u128.into(), 100u128.into());
+        fees.add(10u128.into(), 0u128.into(), 0u128.into());
+
+        assert_eq!(
+            fees,
+            AccruedFees {
+                total: 1010u128.into(),
+                maker: 250u128.into(),
+                fee_share: 100u128.into(),
+                lp: 660u128.into(),
+            }
+        );
     }
 }
@@ -3,10 +3,11 @@ use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint64};
 
 use crate::asset::PairInfo;
 use crate::asset::{Asset, AssetInfo};
-use crate::observation::OracleObservation;
+use crate::observation::{ObservationsInfoResponse, OracleObservation, SafePriceResponse};
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, FeeShareConfig, PoolResponse,
-    ReverseSimulationResponse, SimulationResponse,
+    AccruedFeesResponse, CommissionAsset, ConfigResponse, CumulativePricesResponse, FeeAprResponse,
+    FeeShareConfig, LpTokenMetadata, MigrationStatusResponse, MinimumLiquidityResponse,
+    PoolResponse, ReverseSimulationResponse, SimulationResponse,
 };
 
 /// This structure holds concentrated pool parameters.
@@ -38,6 +39,17 @@ pub struct ConcentratedPoolParams {
     pub track_asset_balances: Option<bool>,
     /// The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// Custom LP token name and symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lp_token_metadata: Option<LpTokenMetadata>,
+    /// Per-asset caps on pool balances for a guarded launch phase.
+    /// Liquidity provision fails if any capped balance would be exceeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_caps: Option<Vec<Asset>>,
+    /// The maximum relative price scale change per hour.
+    /// Repegs are not rate limited if the parameter is ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_price_scale_change_per_hour: Option<Decimal>,
 }
 
 /// This structure holds concentrated pool parameters which can be changed immediately.
@@ -49,6 +61,9 @@ pub struct UpdatePoolParams {
     pub repeg_profit_threshold: Option<Decimal>,
     pub min_price_scale_delta: Option<Decimal>,
     pub ma_half_time: Option<u64>,
+    /// Zero disables the repeg rate limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_price_scale_change_per_hour: Option<Decimal>,
 }
 
 /// Amp and gamma should be changed gradually. This structure holds all necessary parameters.
@@ -62,7 +77,8 @@ pub struct PromoteParams {
 /// This enum intended for parameters update.
 #[cw_serde]
 pub enum ConcentratedPoolUpdateParams {
-    /// Allows to update fee parameters as well as repeg_profit_threshold, min_price_scale_delta and EMA interval.
+    /// Allows to update fee parameters as well as repeg_profit_threshold, min_price_scale_delta,
+    /// EMA interval and the repeg rate limit.
     Update(UpdatePoolParams),
     /// Starts gradual (de/in)crease of Amp or Gamma parameters. Can handle an update of both of them.
     Promote(PromoteParams),
@@ -74,8 +90,20 @@ pub enum ConcentratedPoolUpdateParams {
         fee_share_bps: u16,
         /// The fee_share_bps is sent to this address on every swap
         fee_share_address: String,
+        /// Whether to notify the recipient contract with [`crate::pair::FeeShareCallbackMsg`]
+        /// after every fee transfer. Default: false
+        #[serde(default)]
+        callback: bool,
     },
     DisableFeeShare,
+    /// Sets caps on pool balances. A zero amount removes the cap for the respective asset.
+    /// Assets not mentioned keep their current caps.
+    UpdateBalanceCaps {
+        caps: Vec<Asset>,
+    },
+    /// Queries asset precisions again and overwrites the cached values.
+    /// Needed only if a precision was registered incorrectly in the native coin registry.
+    RefreshPrecisions {},
 }
 
 /// This structure stores a CL pool's configuration.
@@ -105,6 +133,12 @@ pub struct ConcentratedPoolConfig {
     pub track_asset_balances: bool,
     /// The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// Current caps on pool balances. Empty if liquidity provision is not capped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_caps: Vec<Asset>,
+    /// The maximum relative price scale change per hour. Zero if repegs are not rate limited
+    #[serde(default)]
+    pub max_price_scale_change_per_hour: Decimal,
 }
 
 /// This structure describes the query messages available in the contract.
@@ -154,6 +188,12 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Returns the median of the prices observed within the last `window` seconds
+    #[returns(SafePriceResponse)]
+    SafePrice { window: u64 },
+    /// Returns the observations buffer capacity, fill ratio and the number of recent observations
+    #[returns(ObservationsInfoResponse)]
+    ObservationsInfo {},
     /// Returns an estimation of shares received for the given amount of assets
     #[returns(Uint128)]
     SimulateProvide {
@@ -163,6 +203,63 @@ pub enum QueryMsg {
     /// Returns an estimation of assets received for the given amount of LP tokens
     #[returns(Vec<Asset>)]
     SimulateWithdraw { lp_amount: Uint128 },
+    /// Returns the amount of LP tokens locked in the pair contract on the first liquidity provision
+    #[returns(MinimumLiquidityResponse)]
+    MinimumLiquidity {},
+    /// Returns the asset in which the pair charges its commission and denominates
+    /// `commission_amount` and `spread_amount` of swap simulations
+    #[returns(CommissionAsset)]
+    CommissionAsset {},
+    /// Returns whether the factory has scheduled a code migration of the pair
+    #[returns(MigrationStatusResponse)]
+    MigrationStatus {},
+    /// Returns whether [`crate::pair::ExecuteMsg::Poke`] would change the price scale right now
+    #[returns(PokeSimulationResponse)]
+    SimulatePoke {},
+    /// Returns swap fees collected in each pool asset since inception
+    #[returns(Vec<AccruedFeesResponse>)]
+    AccruedFees {},
+    /// Returns the liquidity providers' fee APR estimated from the swap fees accrued within the last `window` seconds
+    #[returns(FeeAprResponse)]
+    FeeApr { window: u64 },
+    /// Returns the last price scale change and the repeg rate limit
+    #[returns(LastRepegResponse)]
+    LastRepeg {},
+}
+
+/// This structure holds the result of a poke simulation.
+#[cw_serde]
+pub struct PokeSimulationResponse {
+    /// Whether a poke executed in the current block would change the price scale
+    pub would_change: bool,
+    /// Current price scale
+    pub price_scale: Decimal256,
+    /// Price scale after the poke
+    pub new_price_scale: Decimal256,
+    /// Absolute difference between the new and the current price scale
+    pub change: Decimal256,
+}
+
+/// This structure holds the last price scale change.
+#[cw_serde]
+pub struct LastRepegResponse {
+    /// Timestamp of the last repeg. Zero if the price scale has never changed
+    pub timestamp: u64,
+    /// Current price scale
+    pub price_scale: Decimal256,
+    /// Relative price scale change applied by the last repeg
+    pub delta: Decimal256,
+    /// The maximum relative price scale change per hour. Zero if repegs are not rate limited
+    pub max_price_scale_change_per_hour: Decimal,
+}
+
+/// This enum describes sudo messages chain modules with protocol level hooks (e.g. Neutron cron)
+/// can send to a concentrated pair. Supported only by pairs built with the `sudo` feature.
+#[cw_serde]
+pub enum SudoMsg {
+    /// Same as [`crate::pair::ExecuteMsg::Poke`] but doesn't fail if the price scale stays the same
+    /// so periodic hooks can trigger it unconditionally
+    Poke {},
 }
 
 #[cw_serde]
@@ -41,6 +41,9 @@ pub enum ExecuteMsg {
         auto_stake: Option<bool>,
         /// The receiver of LP tokens
         receiver: Option<String>,
+        /// Staking contract which receives auto-staked LP tokens.
+        /// Must be one of the staking contracts registered in the factory. Factory default is used if not set.
+        staking_target: Option<String>,
     },
     /// Swap performs a swap in the pool
     Swap {
@@ -139,6 +142,8 @@ pub struct OrderbookStateResponse {
     pub ready: bool,
     /// Whether the begin blocker execution is allowed or not. Default: true
     pub enabled: bool,
+    /// Whether the owner has deactivated orderbook integration
+    pub deactivated: bool,
 }
 
 #[cw_serde]
@@ -157,5 +162,17 @@ pub enum ConcentratedObPoolUpdateParams {
     /// Stops Amp and Gamma update and stores current values.
     StopChangingAmpGamma {},
     /// Update orderbook params.
-    UpdateOrderbookParams { orders_number: u8 },
+    /// Changing the market or deactivating orderbook integration cancels all outstanding orders
+    /// and withdraws the subaccount liquidity first.
+    UpdateOrderbookParams {
+        /// The number of orders on each side of the orderbook
+        orders_number: Option<u8>,
+        /// Minimum number of trades to accumulate average trade size.
+        /// Lowering it enables orderbook integration right away if enough trades were accumulated
+        min_trades_to_avg: Option<u32>,
+        /// Deactivates (false) or reactivates (true) orderbook integration
+        active: Option<bool>,
+        /// New market to deploy liquidity to
+        market_id: Option<String>,
+    },
 }
@@ -0,0 +1,29 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal};
+
+/// This structure holds the Red Bank pair parameters passed on instantiation.
+#[cw_serde]
+pub struct RedBankPoolParams {
+    /// The Mars Red Bank contract which idle reserves are lent to
+    pub red_bank: String,
+    /// Share of every reserve kept in the pair to serve swaps and withdrawals.
+    /// Must be in the (0, 1] range. 1 disables lending
+    pub buffer: Decimal,
+}
+
+/// This structure is returned as the pool parameters in the pair config query.
+#[cw_serde]
+pub struct RedBankPoolConfig {
+    /// The Mars Red Bank contract which idle reserves are lent to
+    pub red_bank: Addr,
+    /// Share of every reserve kept in the pair to serve swaps and withdrawals
+    pub buffer: Decimal,
+}
+
+/// This enum stores the options available to update the Red Bank pair parameters.
+#[cw_serde]
+pub enum RedBankPoolUpdateParams {
+    /// Sets a new buffer and rebalances the reserves to it right away.
+    /// Setting the buffer to 1 withdraws all reserves from the Red Bank
+    UpdateBuffer { buffer: Decimal },
+}
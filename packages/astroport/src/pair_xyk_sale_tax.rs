@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::asset::{validate_native_denom, AssetInfo};
+use crate::pair::LpTokenMetadata;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{from_json, Addr, Api, Binary, Decimal, StdError, StdResult};
 
@@ -155,6 +156,9 @@ pub struct SaleTaxInitParams {
     /// They will not be tracked if the parameter is ignored.
     /// It can not be disabled later once enabled.
     pub track_asset_balances: bool,
+    /// Custom LP token name and symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lp_token_metadata: Option<LpTokenMetadata>,
 }
 
 impl Default for SaleTaxInitParams {
@@ -163,6 +167,7 @@ impl Default for SaleTaxInitParams {
             tax_config_admin: "addr0000".to_string(),
             tax_configs: TaxConfigs::default(),
             track_asset_balances: false,
+            lp_token_metadata: None,
         }
     }
 }
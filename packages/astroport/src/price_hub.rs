@@ -0,0 +1,45 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal256};
+
+use crate::asset::AssetInfo;
+
+/// This structure describes a price published to the price hub
+#[cw_serde]
+pub struct PriceFeed {
+    /// The priced asset
+    pub base: AssetInfo,
+    /// The asset in which the price is expressed
+    pub quote: AssetInfo,
+    /// Amount of the quote asset units per one unit of the base asset (not adjusted to decimals)
+    pub price: Decimal256,
+    /// Timestamp when the price was computed
+    pub updated_at: u64,
+}
+
+/// This structure describes the execute messages a price hub accepts from publishers.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Publishes prices computed by the sender. The hub decides which publishers it trusts
+    PublishPrices { prices: Vec<PriceFeed> },
+}
+
+/// This structure describes the query messages a price hub exposes to subscribers.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the latest published price of the base asset in the quote asset
+    #[returns(PriceResponse)]
+    Price { base: AssetInfo, quote: AssetInfo },
+    /// Returns the latest published prices of the base asset in all known quote assets
+    #[returns(Vec<PriceResponse>)]
+    Prices { base: AssetInfo },
+}
+
+/// This structure describes a price stored in the price hub
+#[cw_serde]
+pub struct PriceResponse {
+    /// The published price
+    pub feed: PriceFeed,
+    /// The contract which published the price
+    pub publisher: Addr,
+}
@@ -1,14 +1,17 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::factory::{
-    Config as FactoryConfig, FeeInfoResponse, PairType, PairsResponse, QueryMsg as FactoryQueryMsg,
-    TrackerConfig,
+    Config as FactoryConfig, FeeInfoResponse, PairConfig, PairType, PairsResponse,
+    QueryMsg as FactoryQueryMsg, TrackerConfig, PAIRS_PAUSED_KEY, PAUSED_PAIRS_NAMESPACE,
+};
+use crate::pair::{
+    QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse, MAX_ASSET_DECIMALS,
 };
-use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
 
 use cosmwasm_std::{
-    from_json, Addr, AllBalanceResponse, BankQuery, Coin, CustomQuery, Decimal, QuerierWrapper,
-    QueryRequest, StdError, StdResult, Uint128,
+    ensure, from_json, Addr, AllBalanceResponse, BankQuery, Coin, CustomQuery, Decimal,
+    QuerierWrapper, QueryRequest, StdError, StdResult, Uint128,
 };
+use cw_storage_plus::Map;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
@@ -113,6 +116,7 @@ where
 }
 
 /// Returns the number of decimals that a token has.
+/// Tokenfactory denoms missing in the native coin registry are resolved by the registry on the fly.
 ///
 /// * **asset_info** is an object of type [`AssetInfo`] and contains the asset details for a specific token.
 pub fn query_token_precision<C>(
@@ -128,12 +132,20 @@ where
             let res = query_factory_config(querier, factory_addr)?;
             let result = crate::native_coin_registry::COINS_INFO.query(
                 querier,
-                res.coin_registry_address,
+                res.coin_registry_address.clone(),
                 denom.to_string(),
             )?;
 
             if let Some(decimals) = result {
                 decimals
+            } else if crate::native_coin_registry::is_token_factory_denom(denom) {
+                // The registry resolves tokenfactory denoms from the bank module metadata
+                querier.query_wasm_smart(
+                    res.coin_registry_address,
+                    &crate::native_coin_registry::QueryMsg::NativeToken {
+                        denom: denom.to_string(),
+                    },
+                )?
             } else {
                 return Err(StdError::generic_err(format!(
                     "The {denom} precision was not found"
@@ -149,6 +161,40 @@ where
     })
 }
 
+/// Returns the number of decimals of an asset in a newly created pair.
+/// The decimals override set in the factory takes priority. Otherwise decimals must be resolvable
+/// from the coin registry or cw20 token info and must not exceed [`MAX_ASSET_DECIMALS`].
+pub fn query_checked_precision<C>(
+    querier: &QuerierWrapper<C>,
+    asset_info: &AssetInfo,
+    factory_addr: &Addr,
+) -> StdResult<u8>
+where
+    C: CustomQuery,
+{
+    if let Some(decimals) = Map::<String, u8>::new("decimals_overrides").query(
+        querier,
+        factory_addr.clone(),
+        asset_info.to_string(),
+    )? {
+        return Ok(decimals);
+    }
+
+    let decimals = query_token_precision(querier, asset_info, factory_addr).map_err(|err| {
+        StdError::generic_err(format!("Failed to resolve decimals of {asset_info}: {err}"))
+    })?;
+
+    ensure!(
+        decimals <= MAX_ASSET_DECIMALS,
+        StdError::generic_err(format!(
+            "{asset_info} has {decimals} decimals which exceeds the maximum of {MAX_ASSET_DECIMALS}. \
+             The factory owner may set a decimals override for it"
+        ))
+    );
+
+    Ok(decimals)
+}
+
 /// Returns the configuration for the factory contract.
 pub fn query_factory_config<C>(
     querier: &QuerierWrapper<C>,
@@ -165,6 +211,136 @@ where
     }
 }
 
+/// Returns true if the pair is scheduled for migration in the factory.
+/// The factory's `pairs_to_migrate` list is read with a raw query, thus it is available
+/// for pairs of any version.
+pub fn migration_check<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: &Addr,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    if let Some(res) = querier.query_wasm_raw(factory_contract, b"pairs_to_migrate".as_slice())? {
+        let pairs_to_migrate: Vec<Addr> = from_json(res)?;
+        Ok(pairs_to_migrate.contains(pair_contract))
+    } else {
+        Ok(false)
+    }
+}
+
+/// Returns true if flash loans are enabled in the factory.
+pub fn query_flash_loans_enabled<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    if let Some(res) =
+        querier.query_wasm_raw(factory_contract, b"flash_loans_enabled".as_slice())?
+    {
+        from_json(res)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Returns true if the pair is paused in the factory either individually or along with all pairs.
+/// The factory's pause state is read with raw queries, thus it is available for pairs of any version.
+pub fn query_pair_paused<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: &Addr,
+    pair_contract: &Addr,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    if let Some(res) = querier.query_wasm_raw(factory_contract, PAIRS_PAUSED_KEY.as_bytes())? {
+        if from_json(res)? {
+            return Ok(true);
+        }
+    }
+
+    let key = Map::<&Addr, ()>::new(PAUSED_PAIRS_NAMESPACE).key(pair_contract);
+
+    Ok(querier
+        .query_wasm_raw(factory_contract, key.to_vec())?
+        .is_some())
+}
+
+/// Returns the minimum swap notional configured in the factory for the pair type.
+/// The factory's pair configs are read with a raw query, thus it is available for factories of any version.
+pub fn query_min_trade_notional<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_type: &PairType,
+) -> StdResult<Option<Decimal>>
+where
+    C: CustomQuery,
+{
+    let key = Map::<String, PairConfig>::new("pair_configs").key(pair_type.to_string());
+
+    Ok(querier
+        .query_wasm_raw(factory_contract, key.to_vec())?
+        .map(from_json::<PairConfig>)
+        .transpose()?
+        .and_then(|pair_config| pair_config.min_trade_notional))
+}
+
+/// Staking contract which receives auto-staked LP tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StakingTarget {
+    /// The incentives contract
+    Incentives(Addr),
+    /// The legacy generator which is still registered as `generator_address` while `auto_stake_target` is set.
+    /// It only accepts [`crate::incentives::LegacyGeneratorExecuteMsg`].
+    LegacyGenerator(Addr),
+}
+
+impl StakingTarget {
+    pub fn addr(&self) -> &Addr {
+        match self {
+            StakingTarget::Incentives(addr) | StakingTarget::LegacyGenerator(addr) => addr,
+        }
+    }
+}
+
+/// Returns the staking contract which receives auto-staked LP tokens.
+/// If `staking_target` is specified, it must be either `generator_address` or `auto_stake_target`
+/// from the factory config. Otherwise `auto_stake_target` is preferred over `generator_address`.
+/// `generator_address` is considered the legacy generator if `auto_stake_target` is set.
+pub fn query_staking_target<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    staking_target: Option<&str>,
+) -> StdResult<Option<StakingTarget>>
+where
+    C: CustomQuery,
+{
+    let config = query_factory_config(querier, factory_contract)?;
+
+    let (incentives, legacy_generator) = match config.auto_stake_target {
+        Some(auto_stake_target) => (Some(auto_stake_target), config.generator_address),
+        None => (config.generator_address, None),
+    };
+    let mut targets = incentives
+        .map(StakingTarget::Incentives)
+        .into_iter()
+        .chain(legacy_generator.map(StakingTarget::LegacyGenerator));
+
+    match staking_target {
+        Some(target) => targets
+            .find(|staking| staking.addr().as_str() == target)
+            .map(Some)
+            .ok_or_else(|| {
+                StdError::generic_err(format!("{target} is not a registered staking contract"))
+            }),
+        None => Ok(targets.next()),
+    }
+}
+
 /// Returns the tracker configuration from the factory contract.
 pub fn query_tracker_config<C>(
     querier: &QuerierWrapper<C>,
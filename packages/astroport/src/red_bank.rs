@@ -0,0 +1,70 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, CustomQuery, QuerierWrapper, StdResult, Uint128};
+
+/// Subset of the Mars Protocol Red Bank execute messages used by Astroport contracts.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Deposits the attached coin as collateral. Exactly one coin must be sent
+    Deposit {
+        /// Credit Manager account id. Not used by Astroport contracts
+        account_id: Option<String>,
+        /// Address which receives the collateral. Defaults to the sender
+        on_behalf_of: Option<String>,
+    },
+    /// Withdraws collateral. Withdraws the full position if `amount` is not set
+    Withdraw {
+        denom: String,
+        amount: Option<Uint128>,
+        /// Address which receives the withdrawn coins. Defaults to the sender
+        recipient: Option<String>,
+        /// Credit Manager account id. Not used by Astroport contracts
+        account_id: Option<String>,
+        liquidation_related: Option<bool>,
+    },
+}
+
+/// Subset of the Mars Protocol Red Bank queries used by Astroport contracts.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the collateral of a user in a specific denom
+    #[returns(UserCollateralResponse)]
+    UserCollateral {
+        user: String,
+        account_id: Option<String>,
+        denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct UserCollateralResponse {
+    pub denom: String,
+    /// Scaled collateral amount stored in the Red Bank
+    pub amount_scaled: Uint128,
+    /// Underlying collateral amount including accrued interest
+    pub amount: Uint128,
+    /// Whether the collateral is used to back borrowings
+    pub enabled: bool,
+}
+
+/// Returns the underlying amount of `denom` deposited by `user` into the Red Bank.
+pub fn query_collateral<C>(
+    querier: &QuerierWrapper<C>,
+    red_bank: &Addr,
+    user: impl Into<String>,
+    denom: impl Into<String>,
+) -> StdResult<Uint128>
+where
+    C: CustomQuery,
+{
+    querier
+        .query_wasm_smart::<UserCollateralResponse>(
+            red_bank,
+            &QueryMsg::UserCollateral {
+                user: user.into(),
+                account_id: None,
+                denom: denom.into(),
+            },
+        )
+        .map(|resp| resp.amount)
+}
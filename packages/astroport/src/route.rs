@@ -0,0 +1,393 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Api, StdError};
+use thiserror::Error;
+
+use crate::asset::AssetInfo;
+use crate::router::{SwapOperation, MAX_SWAP_OPERATIONS};
+
+/// Maximum number of hops in one route
+pub const MAX_ROUTE_HOPS: usize = MAX_SWAP_OPERATIONS;
+
+/// This enum describes how a single hop is executed.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum HopKind {
+    /// Swap through an Astroport pair
+    Astro,
+    /// Swap through the chain native market module. Both hop assets must be native tokens.
+    Native,
+}
+
+/// This structure describes a single hop of a [`Route`].
+#[cw_serde]
+pub struct RouteHop {
+    /// How the hop is executed
+    pub kind: HopKind,
+    /// Pair contract address. If not set, the pair is resolved through the factory by the hop assets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pair: Option<String>,
+}
+
+impl RouteHop {
+    /// Astroport hop resolved through the factory
+    pub fn astro() -> Self {
+        Self {
+            kind: HopKind::Astro,
+            pair: None,
+        }
+    }
+}
+
+/// Canonical swap route shared between contracts.
+/// Asset `assets[i]` is swapped to `assets[i + 1]` through `hops[i]`.
+/// This encoding makes it impossible to describe a disconnected path.
+#[cw_serde]
+pub struct Route {
+    /// All assets on the path starting with the offer asset and ending with the ask asset
+    pub assets: Vec<AssetInfo>,
+    /// Hops between neighbouring assets
+    pub hops: Vec<RouteHop>,
+}
+
+/// This enum describes route validation errors.
+#[derive(Error, Debug, PartialEq)]
+pub enum RouteError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Route must contain at least one hop")]
+    EmptyRoute {},
+
+    #[error("Route can't contain more than {max} hops")]
+    TooManyHops { max: usize },
+
+    #[error("Number of route assets must be one more than number of hops; got {assets} assets and {hops} hops")]
+    HopsMismatch { assets: usize, hops: usize },
+
+    #[error("Doubling assets in hop {hop}; {offer_asset} --> {ask_asset}")]
+    DoublingAssets {
+        hop: usize,
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("Native hop {hop} {offer_asset} --> {ask_asset} must swap between native tokens")]
+    InvalidNativeHop {
+        hop: usize,
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error(
+        "Hop {hop} is disconnected. The next offer asset must be the same as the previous ask asset; \
+    {prev_ask_asset} --> {next_offer_asset} --> {next_ask_asset}"
+    )]
+    DisconnectedPath {
+        hop: usize,
+        prev_ask_asset: String,
+        next_offer_asset: String,
+        next_ask_asset: String,
+    },
+}
+
+impl Route {
+    /// Creates a route through Astroport pairs resolved by the factory.
+    /// Identical consecutive assets are collapsed into one thus no hop swaps an asset to itself.
+    pub fn from_assets(mut assets: Vec<AssetInfo>) -> Self {
+        assets.dedup();
+        let hops = vec![RouteHop::astro(); assets.len().saturating_sub(1)];
+        Self { assets, hops }
+    }
+
+    /// Returns the asset which is offered in the first hop.
+    pub fn offer_asset(&self) -> Option<&AssetInfo> {
+        self.assets.first()
+    }
+
+    /// Returns the asset which is received in the last hop.
+    pub fn ask_asset(&self) -> Option<&AssetInfo> {
+        self.assets.last()
+    }
+
+    /// Iterates over (offer asset, ask asset, hop) tuples.
+    pub fn iter_hops(&self) -> impl Iterator<Item = (&AssetInfo, &AssetInfo, &RouteHop)> {
+        self.assets
+            .iter()
+            .zip(self.assets.iter().skip(1))
+            .zip(self.hops.iter())
+            .map(|((offer, ask), hop)| (offer, ask, hop))
+    }
+
+    /// Validates the route structure, assets and pair addresses.
+    pub fn validate(&self, api: &dyn Api) -> Result<(), RouteError> {
+        if self.hops.is_empty() {
+            return Err(RouteError::EmptyRoute {});
+        }
+
+        if self.hops.len() > MAX_ROUTE_HOPS {
+            return Err(RouteError::TooManyHops {
+                max: MAX_ROUTE_HOPS,
+            });
+        }
+
+        if self.assets.len() != self.hops.len() + 1 {
+            return Err(RouteError::HopsMismatch {
+                assets: self.assets.len(),
+                hops: self.hops.len(),
+            });
+        }
+
+        for (ind, (offer_asset, ask_asset, hop)) in self.iter_hops().enumerate() {
+            offer_asset.check(api)?;
+            ask_asset.check(api)?;
+
+            if offer_asset.equal(ask_asset) {
+                return Err(RouteError::DoublingAssets {
+                    hop: ind,
+                    offer_asset: offer_asset.to_string(),
+                    ask_asset: ask_asset.to_string(),
+                });
+            }
+
+            if hop.kind == HopKind::Native
+                && !(offer_asset.is_native_token() && ask_asset.is_native_token())
+            {
+                return Err(RouteError::InvalidNativeHop {
+                    hop: ind,
+                    offer_asset: offer_asset.to_string(),
+                    ask_asset: ask_asset.to_string(),
+                });
+            }
+
+            if let Some(pair) = &hop.pair {
+                api.addr_validate(pair)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts the route into router swap operations.
+    /// Pair addresses are not part of [`SwapOperation`] thus the router always resolves pairs through the factory.
+    pub fn to_swap_operations(&self) -> Result<Vec<SwapOperation>, RouteError> {
+        self.iter_hops()
+            .enumerate()
+            .map(|(ind, (offer_asset, ask_asset, hop))| match hop.kind {
+                HopKind::Astro => Ok(SwapOperation::AstroSwap {
+                    offer_asset_info: offer_asset.clone(),
+                    ask_asset_info: ask_asset.clone(),
+                    minimum_receive: None,
+                }),
+                HopKind::Native => match (offer_asset, ask_asset) {
+                    (
+                        AssetInfo::NativeToken { denom: offer_denom },
+                        AssetInfo::NativeToken { denom: ask_denom },
+                    ) => Ok(SwapOperation::NativeSwap {
+                        offer_denom: offer_denom.clone(),
+                        ask_denom: ask_denom.clone(),
+                    }),
+                    _ => Err(RouteError::InvalidNativeHop {
+                        hop: ind,
+                        offer_asset: offer_asset.to_string(),
+                        ask_asset: ask_asset.to_string(),
+                    }),
+                },
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&[SwapOperation]> for Route {
+    type Error = RouteError;
+
+    /// Builds a canonical route from router swap operations. Per-hop minimum receive amounts are dropped.
+    fn try_from(operations: &[SwapOperation]) -> Result<Self, Self::Error> {
+        if operations.is_empty() {
+            return Err(RouteError::EmptyRoute {});
+        }
+
+        if operations.len() > MAX_ROUTE_HOPS {
+            return Err(RouteError::TooManyHops {
+                max: MAX_ROUTE_HOPS,
+            });
+        }
+
+        let mut assets: Vec<AssetInfo> = Vec::with_capacity(operations.len() + 1);
+        let mut hops = Vec::with_capacity(operations.len());
+        for (ind, operation) in operations.iter().enumerate() {
+            let (offer_asset, ask_asset, kind) = match operation {
+                SwapOperation::NativeSwap {
+                    offer_denom,
+                    ask_denom,
+                } => (
+                    AssetInfo::native(offer_denom),
+                    AssetInfo::native(ask_denom),
+                    HopKind::Native,
+                ),
+                SwapOperation::AstroSwap {
+                    offer_asset_info,
+                    ask_asset_info,
+                    ..
+                } => (
+                    offer_asset_info.clone(),
+                    ask_asset_info.clone(),
+                    HopKind::Astro,
+                ),
+            };
+
+            if let Some(prev_ask_asset) = assets.last() {
+                if *prev_ask_asset != offer_asset {
+                    return Err(RouteError::DisconnectedPath {
+                        hop: ind,
+                        prev_ask_asset: prev_ask_asset.to_string(),
+                        next_offer_asset: offer_asset.to_string(),
+                        next_ask_asset: ask_asset.to_string(),
+                    });
+                }
+            } else {
+                assets.push(offer_asset);
+            }
+
+            assets.push(ask_asset);
+            hops.push(RouteHop { kind, pair: None });
+        }
+
+        Ok(Self { assets, hops })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockApi;
+
+    use super::*;
+
+    #[test]
+    fn test_route_from_operations() {
+        let operations = vec![
+            SwapOperation::NativeSwap {
+                offer_denom: "ukrw".to_string(),
+                ask_denom: "uusd".to_string(),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: AssetInfo::native("uusd"),
+                ask_asset_info: AssetInfo::cw20_unchecked("asset0001"),
+                minimum_receive: None,
+            },
+        ];
+
+        let route = Route::try_from(operations.as_slice()).unwrap();
+        assert_eq!(
+            route.assets,
+            vec![
+                AssetInfo::native("ukrw"),
+                AssetInfo::native("uusd"),
+                AssetInfo::cw20_unchecked("asset0001"),
+            ]
+        );
+        assert_eq!(
+            route.hops.iter().map(|hop| hop.kind).collect::<Vec<_>>(),
+            vec![HopKind::Native, HopKind::Astro]
+        );
+        route.validate(&MockApi::default()).unwrap();
+        assert_eq!(route.to_swap_operations().unwrap(), operations);
+
+        let err = Route::try_from(&operations[..0]).unwrap_err();
+        assert_eq!(err, RouteError::EmptyRoute {});
+
+        let err = Route::try_from(
+            operations
+                .iter()
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            RouteError::DisconnectedPath {
+                hop: 1,
+                prev_ask_asset: "asset0001".to_string(),
+                next_offer_asset: "ukrw".to_string(),
+                next_ask_asset: "uusd".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_route_validation() {
+        let api = MockApi::default();
+
+        let route = Route::from_assets(vec![AssetInfo::native("uusd")]);
+        assert_eq!(route.validate(&api).unwrap_err(), RouteError::EmptyRoute {});
+
+        let route = Route::from_assets(
+            (0..MAX_ROUTE_HOPS + 2)
+                .map(|i| AssetInfo::native(format!("denom{i}")))
+                .collect(),
+        );
+        assert_eq!(
+            route.validate(&api).unwrap_err(),
+            RouteError::TooManyHops {
+                max: MAX_ROUTE_HOPS
+            }
+        );
+
+        let mut route =
+            Route::from_assets(vec![AssetInfo::native("uusd"), AssetInfo::native("uluna")]);
+        route.hops.push(RouteHop::astro());
+        assert_eq!(
+            route.validate(&api).unwrap_err(),
+            RouteError::HopsMismatch { assets: 2, hops: 2 }
+        );
+
+        let route = Route {
+            assets: vec![AssetInfo::native("uusd"), AssetInfo::native("uusd")],
+            hops: vec![RouteHop::astro()],
+        };
+        assert_eq!(
+            route.validate(&api).unwrap_err(),
+            RouteError::DoublingAssets {
+                hop: 0,
+                offer_asset: "uusd".to_string(),
+                ask_asset: "uusd".to_string(),
+            }
+        );
+
+        let mut route = Route::from_assets(vec![
+            AssetInfo::native("uusd"),
+            AssetInfo::cw20_unchecked("asset0001"),
+        ]);
+        route.hops[0].kind = HopKind::Native;
+        let err = RouteError::InvalidNativeHop {
+            hop: 0,
+            offer_asset: "uusd".to_string(),
+            ask_asset: "asset0001".to_string(),
+        };
+        assert_eq!(route.validate(&api).unwrap_err(), err);
+        assert_eq!(route.to_swap_operations().unwrap_err(), err);
+    }
+
+    #[test]
+    fn test_route_from_assets_dedup() {
+        let route = Route::from_assets(vec![
+            AssetInfo::native("uusd"),
+            AssetInfo::native("uusd"),
+            AssetInfo::native("uluna"),
+            AssetInfo::native("uluna"),
+            AssetInfo::native("uluna"),
+            AssetInfo::cw20_unchecked("asset0001"),
+        ]);
+        assert_eq!(
+            route.assets,
+            vec![
+                AssetInfo::native("uusd"),
+                AssetInfo::native("uluna"),
+                AssetInfo::cw20_unchecked("asset0001"),
+            ]
+        );
+        assert_eq!(route.hops.len(), 2);
+        route.validate(&MockApi::default()).unwrap();
+    }
+}
@@ -4,6 +4,8 @@ use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 use crate::asset::AssetInfo;
+use crate::pair::RecipientCommitment;
+use crate::route::Route;
 
 pub const MAX_SWAP_OPERATIONS: usize = 50;
 
@@ -30,6 +32,11 @@ pub enum SwapOperation {
         offer_asset_info: AssetInfo,
         /// Information about the asset we swap to
         ask_asset_info: AssetInfo,
+        /// The minimum amount of ask assets this hop must return.
+        /// The hop receiver's balance change is checked right after the swap
+        /// and the whole route fails without executing the remaining hops.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum_receive: Option<Uint128>,
     },
 }
 
@@ -55,6 +62,8 @@ pub enum ExecuteMsg {
         minimum_receive: Option<Uint128>,
         to: Option<String>,
         max_spread: Option<Decimal>,
+        /// Binds the swap to a recipient committed in advance. If set, `to` must match the commitment
+        to_commitment: Option<RecipientCommitment>,
     },
 
     /// Internal use
@@ -65,6 +74,24 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         single: bool,
     },
+    /// Swaps the offered native coins along the route registered for the (offer, ask) pair.
+    /// CW20 tokens are swapped via [`Cw20HookMsg::SwapByRegisteredRoute`].
+    SwapByRegisteredRoute {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+        to_commitment: Option<RecipientCommitment>,
+    },
+    /// Stores the canonical route between its offer and ask assets replacing the previous one.
+    /// Only the factory owner can execute this.
+    RegisterRoute { route: Route },
+    /// Removes the route registered for the (offer, ask) pair. Only the factory owner can execute this.
+    DeregisterRoute {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
 }
 
 #[cw_serde]
@@ -83,6 +110,16 @@ pub enum Cw20HookMsg {
         to: Option<String>,
         /// Max spread
         max_spread: Option<Decimal>,
+        /// Binds the swap to a recipient committed in advance. If set, `to` must match the commitment
+        to_commitment: Option<RecipientCommitment>,
+    },
+    /// Swaps the sent tokens along the route registered for the (sent token, ask) pair
+    SwapByRegisteredRoute {
+        ask_asset_info: AssetInfo,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+        to_commitment: Option<RecipientCommitment>,
     },
 }
 
@@ -101,6 +138,26 @@ pub enum QueryMsg {
         /// The swap operations to perform, each swap involving a specific pool
         operations: Vec<SwapOperation>,
     },
+    /// Returns the canonical [`Route`] built from the given swap operations.
+    /// Identical consecutive operations are deduplicated the same way the router does before executing a swap.
+    #[returns(Route)]
+    Route { operations: Vec<SwapOperation> },
+    /// Returns the route registered for the (offer, ask) pair
+    #[returns(Route)]
+    RegisteredRoute {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+    /// Lists registered routes ordered by their (offer, ask) key
+    #[returns(Vec<Route>)]
+    RegisteredRoutes {
+        start_after: Option<(AssetInfo, AssetInfo)>,
+        limit: Option<u32>,
+    },
+    /// Returns the canonical [`Route`] with every hop pinned to its factory pair.
+    /// Fails naming the first hop whose pair is deregistered or disabled in the factory
+    #[returns(Route)]
+    ValidateRoute { operations: Vec<SwapOperation> },
 }
 
 /// This structure describes a custom struct to return a query response containing the base contract configuration.
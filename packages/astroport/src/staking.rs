@@ -1,5 +1,29 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, StdError, StdResult, Uint128};
+
+/// Default minimal interval between two absorptions of donated ASTRO (1 day)
+pub const DEFAULT_ABSORB_INTERVAL: u64 = 86400;
+/// Allowed range of the absorb interval (1 hour - 30 days)
+pub const ABSORB_INTERVAL_LIMITS: (u64, u64) = (3600, 86400 * 30);
+
+/// Default number of reward injections processed by one claim
+pub const DEFAULT_CLAIM_LIMIT: u32 = 30;
+/// Maximum number of reward injections processed by one claim
+pub const MAX_CLAIM_LIMIT: u32 = 100;
+/// Period after which unclaimed rewards of an injection can be swept by the owner (180 days)
+pub const REWARD_CLAIM_PERIOD: u64 = 86400 * 180;
+
+/// Validates the interval between two absorptions of donated ASTRO.
+pub fn validate_absorb_interval(interval: u64) -> StdResult<()> {
+    let (min, max) = ABSORB_INTERVAL_LIMITS;
+    if !(min..=max).contains(&interval) {
+        return Err(StdError::generic_err(format!(
+            "absorb_interval must be within [{min}, {max}] seconds"
+        )));
+    }
+
+    Ok(())
+}
 
 /// This structure describes the parameters used for creating a contract.
 #[cw_serde]
@@ -12,6 +36,16 @@ pub struct InstantiateMsg {
     pub tracking_code_id: u64,
     /// Token factory module address. Contract creator must ensure that the address is exact token factory module address.
     pub token_factory_addr: String,
+    /// Minimal interval in seconds between two absorptions of ASTRO transferred directly to the contract.
+    /// Defaults to [`DEFAULT_ABSORB_INTERVAL`]
+    #[serde(default)]
+    pub absorb_interval: Option<u64>,
+    /// Address allowed to manage reward injection whitelists. Defaults to `tracking_admin`
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Delay in seconds between an ownership proposal and the moment it can be claimed. Disabled if not set
+    #[serde(default)]
+    pub ownership_timelock: Option<u64>,
 }
 
 /// This structure describes the execute messages available in the contract.
@@ -29,6 +63,48 @@ pub enum ExecuteMsg {
     /// Burns xASTRO in exchange for ASTRO.
     /// The receiver is optional. If not set, the sender will receive the ASTRO.
     Leave { receiver: Option<String> },
+    /// Permissionless endpoint which folds ASTRO transferred directly to the contract (outside of Enter)
+    /// into the xASTRO exchange rate. Can be executed at most once per absorb interval.
+    /// Absorbed ASTRO is released into the exchange rate linearly over the absorb interval.
+    Absorb {},
+    /// Escrows non-ASTRO revenue which is distributed pro-rata to xASTRO holders
+    /// according to their balances at the beginning of the current block.
+    /// Only whitelisted injectors can send whitelisted coins.
+    InjectRewards {},
+    /// Claims the sender's share of injected rewards.
+    /// Processes at most `limit` injections since the last claim. Defaults to [`DEFAULT_CLAIM_LIMIT`]
+    ClaimRewards {
+        receiver: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Sends the unclaimed rewards of injections older than [`REWARD_CLAIM_PERIOD`] to the receiver
+    /// and removes these injections. Covers shares of holders which can't claim (e.g. xASTRO held by contracts)
+    /// and rounding dust. Processes at most `limit` injections. Only the owner can execute this.
+    SweepRewards {
+        receiver: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Updates the lists of reward injectors and reward denoms. Only the owner can execute this.
+    UpdateRewardsConfig {
+        add_injectors: Option<Vec<String>>,
+        remove_injectors: Option<Vec<String>>,
+        add_denoms: Option<Vec<String>>,
+        remove_denoms: Option<Vec<String>>,
+    },
+    /// Creates a request to change contract ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+    /// Sets the delay (in seconds) between an ownership proposal and the moment it can be claimed.
+    /// Only the owner can execute this.
+    UpdateOwnershipTimelock { timelock: u64 },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -41,7 +117,8 @@ pub enum QueryMsg {
     /// Returns xASTRO total supply. Duplicates TotalSupplyAt { timestamp: None } logic but kept for backward compatibility.
     #[returns(Uint128)]
     TotalShares {},
-    /// Returns total ASTRO staked in the contract
+    /// Returns total ASTRO staked in the contract.
+    /// ASTRO transferred directly to the contract is included only as it is released after absorption
     #[returns(Uint128)]
     TotalDeposit {},
     #[returns(TrackerData)]
@@ -57,6 +134,33 @@ pub enum QueryMsg {
     /// Returns current total supply if timestamp unset.
     #[returns(Uint128)]
     TotalSupplyAt { timestamp: Option<u64> },
+    /// Returns the state of donated ASTRO absorption
+    #[returns(AbsorbInfoResponse)]
+    AbsorbInfo {},
+    /// Returns the reward injection whitelists and the owner
+    #[returns(RewardsConfig)]
+    RewardsConfig {},
+    /// Returns rewards claimable by the address from at most `limit` injections since its last claim
+    #[returns(Vec<Coin>)]
+    PendingRewards { address: String, limit: Option<u32> },
+    /// Returns injected rewards by injection timestamp
+    #[returns(Vec<(u64, Vec<Coin>)>)]
+    RewardInjections {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure describes a migration message.
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Minimal interval in seconds between two absorptions of donated ASTRO.
+    /// Defaults to [`DEFAULT_ABSORB_INTERVAL`]
+    #[serde(default)]
+    pub absorb_interval: Option<u64>,
+    /// Address allowed to manage reward injection whitelists. Required when migrating from versions without it
+    #[serde(default)]
+    pub owner: Option<String>,
 }
 
 /// This structure stores the main parameters for the staking contract.
@@ -81,6 +185,66 @@ pub struct TrackerData {
     pub tracker_addr: String,
 }
 
+/// This structure stores the reward injection settings.
+#[cw_serde]
+pub struct RewardsConfig {
+    /// Address which manages the whitelists
+    pub owner: Addr,
+    /// Contracts allowed to inject rewards
+    pub injectors: Vec<Addr>,
+    /// Native coins accepted as rewards
+    pub reward_denoms: Vec<String>,
+}
+
+/// This structure stores the donated ASTRO absorption state.
+#[cw_serde]
+pub struct AbsorbConfig {
+    /// Minimal interval in seconds between two absorptions
+    pub interval: u64,
+    /// Timestamp of the last absorption
+    pub last_absorb_ts: u64,
+    /// Absorbed ASTRO which is not released into the exchange rate yet
+    #[serde(default)]
+    pub releasing_amount: Uint128,
+    /// Timestamp up to which absorbed ASTRO has been released
+    #[serde(default)]
+    pub released_ts: u64,
+    /// Timestamp when all absorbed ASTRO is released
+    #[serde(default)]
+    pub release_end_ts: u64,
+}
+
+impl AbsorbConfig {
+    /// Returns the part of [`AbsorbConfig::releasing_amount`] released by the given timestamp.
+    /// Absorbed ASTRO is released linearly until [`AbsorbConfig::release_end_ts`].
+    pub fn released_amount(&self, block_ts: u64) -> Uint128 {
+        if block_ts >= self.release_end_ts {
+            return self.releasing_amount;
+        }
+
+        let elapsed = block_ts.saturating_sub(self.released_ts);
+        self.releasing_amount
+            .multiply_ratio(elapsed, self.release_end_ts - self.released_ts)
+    }
+}
+
+/// This structure describes the response of [`QueryMsg::AbsorbInfo`].
+#[cw_serde]
+pub struct AbsorbInfoResponse {
+    /// Minimal interval in seconds between two absorptions
+    pub interval: u64,
+    /// Timestamp of the last absorption
+    pub last_absorb_ts: u64,
+    /// Earliest timestamp when the next absorption is possible
+    pub next_absorb_ts: u64,
+    /// Amount of ASTRO which is not absorbed yet
+    pub pending_amount: Uint128,
+    /// Amount of absorbed ASTRO which is not released into the exchange rate yet
+    pub releasing_amount: Uint128,
+    /// Timestamp when all absorbed ASTRO is released
+    pub release_end_ts: u64,
+}
+
 /// The structure returned as part of set_data when staking or unstaking
 #[cw_serde]
 pub struct StakingResponse {
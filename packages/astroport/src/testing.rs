@@ -4,6 +4,7 @@ use crate::querier::{
     query_all_balances, query_balance, query_pair_info, query_supply, query_token_balance,
 };
 
+use crate::events::{SwapEvent, EVENTS_SCHEMA_VERSION};
 use crate::factory::PairType;
 use crate::DecimalCheckedOps;
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
@@ -326,3 +327,61 @@ fn test_decimal_checked_ops() {
             .is_err()
     );
 }
+
+#[test]
+fn test_swap_event_attributes() {
+    let event = SwapEvent {
+        pool: Addr::unchecked("pair"),
+        sender: Addr::unchecked("sender"),
+        receiver: Addr::unchecked("receiver"),
+        offer_asset: Asset::native("uusd", 1000u128),
+        return_asset: Asset::native("uluna", 900u128),
+        spread_amount: Uint128::new(70),
+        commission_amount: Uint128::new(30),
+        maker_fee_amount: Uint128::new(10),
+        fee_share_amount: Uint128::zero(),
+    };
+    assert_eq!(event.price_impact(), Decimal::percent(7));
+
+    let attrs = event.into_attributes();
+    let keys = attrs
+        .iter()
+        .map(|attr| attr.key.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec![
+            "action",
+            "sender",
+            "receiver",
+            "offer_asset",
+            "ask_asset",
+            "offer_amount",
+            "return_amount",
+            "spread_amount",
+            "commission_amount",
+            "maker_fee_amount",
+            "fee_share_amount",
+            "price_impact",
+            "pool",
+            "schema_version"
+        ]
+    );
+    assert_eq!(attrs[4].value, "uluna");
+    assert_eq!(attrs[11].value, "0.07");
+    assert_eq!(attrs[13].value, EVENTS_SCHEMA_VERSION);
+
+    // Nothing returned and nothing lost
+    let event = SwapEvent {
+        pool: Addr::unchecked("pair"),
+        sender: Addr::unchecked("sender"),
+        receiver: Addr::unchecked("receiver"),
+        offer_asset: Asset::native("uusd", 0u128),
+        return_asset: Asset::native("uluna", 0u128),
+        spread_amount: Uint128::zero(),
+        commission_amount: Uint128::zero(),
+        maker_fee_amount: Uint128::zero(),
+        fee_share_amount: Uint128::zero(),
+    };
+    assert_eq!(event.price_impact(), Decimal::zero());
+}
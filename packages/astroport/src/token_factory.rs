@@ -1,3 +1,4 @@
+pub use cosmos_sdk_proto::cosmos::bank::v1beta1::{DenomUnit, Metadata};
 pub use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
 use cosmwasm_std::{Binary, Coin, CosmosMsg, CustomMsg, StdError};
 
@@ -211,6 +212,38 @@ impl TryFrom<Binary> for MsgSetBeforeSendHook {
     }
 }
 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetDenomMetadata {
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub metadata: ::core::option::Option<Metadata>,
+}
+
+impl MsgSetDenomMetadata {
+    #[cfg(not(any(feature = "injective", feature = "sei")))]
+    pub const TYPE_URL: &'static str = "/osmosis.tokenfactory.v1beta1.MsgSetDenomMetadata";
+    #[cfg(feature = "injective")]
+    pub const TYPE_URL: &'static str = "/injective.tokenfactory.v1beta1.MsgSetDenomMetadata";
+    #[cfg(feature = "sei")]
+    pub const TYPE_URL: &'static str =
+        "/seiprotocol.seichain.tokenfactory.v1beta1.MsgSetDenomMetadata";
+}
+
+impl TryFrom<Binary> for MsgSetDenomMetadata {
+    type Error = StdError;
+    fn try_from(binary: Binary) -> Result<Self, Self::Error> {
+        Self::decode(binary.as_slice()).map_err(|e| {
+            StdError::generic_err(format!(
+                "MsgSetDenomMetadata Unable to decode binary: \n  - base64: {}\n  - bytes array: {:?}\n\n{:?}",
+                binary,
+                binary.to_vec(),
+                e
+            ))
+        })
+    }
+}
+
 pub fn tf_create_denom_msg<T>(sender: impl Into<String>, denom: impl Into<String>) -> CosmosMsg<T>
 where
     T: CustomMsg,
@@ -353,3 +386,18 @@ where
         value: Binary::from(msg.encode_to_vec()),
     }
 }
+
+pub fn tf_set_denom_metadata_msg<T>(sender: impl Into<String>, metadata: Metadata) -> CosmosMsg<T>
+where
+    T: CustomMsg,
+{
+    let msg = MsgSetDenomMetadata {
+        sender: sender.into(),
+        metadata: Some(metadata),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MsgSetDenomMetadata::TYPE_URL.to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    }
+}
@@ -13,6 +13,11 @@ pub struct InstantiateMsg {
     /// Default is false.
     #[serde(default)]
     pub track_over_seconds: bool,
+    /// Enables the leaderboard of the largest holders.
+    /// Holders with balance of at least this amount are indexed in descending order.
+    /// Leaderboard is disabled if not set.
+    #[serde(default)]
+    pub leaderboard_min_balance: Option<Uint128>,
 }
 
 #[cw_serde]
@@ -57,6 +62,15 @@ pub enum QueryMsg {
     },
     #[returns(ConfigResponse)]
     Config {},
+    /// Return the largest holders at the current time unit ordered by balance descending (ties are ordered by address).
+    /// Only holders with balance of at least `leaderboard_min_balance` are listed.
+    /// Fails if the leaderboard is disabled.
+    #[returns(Vec<(String, Uint128)>)]
+    TopHolders {
+        /// Start after the specified holder
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -69,4 +83,6 @@ pub struct ConfigResponse {
     /// If true, tracking over seconds is enabled.
     /// If false, tracking over blocks is enabled.
     pub track_over_seconds: bool,
+    /// Minimum balance of holders listed in the leaderboard. None if the leaderboard is disabled
+    pub leaderboard_min_balance: Option<Uint128>,
 }
@@ -55,6 +55,32 @@ pub enum ExecuteMsg {
     /// ## Executor
     /// Only the newly proposed owner can execute this
     ClaimOwnership {},
+    /// Proposes to move the sender's vesting account to a new address.
+    /// The migration can be executed once [`ACCOUNT_MIGRATION_DISPUTE_PERIOD`] has passed.
+    /// Claims from the account are paused until the migration is executed or cancelled.
+    ProposeAccountMigration { new_address: String },
+    /// Approves moving a vesting account to a new address after its key was compromised.
+    /// Overrides the migration proposed by the account itself if any.
+    /// ## Executor
+    /// Only the current owner can execute this
+    ApproveAccountMigration {
+        account: String,
+        new_address: String,
+        /// Reference to the off-chain proof of the key compromise
+        proof: String,
+    },
+    /// Cancels a pending migration of a vesting account.
+    /// The account itself can cancel the migration it has proposed during the dispute period.
+    /// The owner can cancel any pending migration.
+    CancelAccountMigration { account: String },
+    /// Moves the vesting account to the new address once the dispute period has passed.
+    /// Anyone can execute this
+    ExecuteAccountMigration { account: String },
+    /// Stops a managed vesting account and sends its unvested remainder to the treasury.
+    /// Tokens vested before the clawback stay claimable by the account.
+    /// ## Executor
+    /// Only the manager of the vesting account can execute this
+    Clawback { account: String },
 }
 
 /// This structure stores vesting information for a specific address that is getting tokens.
@@ -64,6 +90,28 @@ pub struct VestingAccount {
     pub address: String,
     /// The vesting schedules targeted at the `address`
     pub schedules: Vec<VestingSchedule>,
+    /// Makes the unvested tokens of the account revocable by a manager.
+    /// Registering more schedules for an existing account requires the same management parameters
+    #[serde(default)]
+    pub managed: Option<VestingManagementParams>,
+}
+
+/// This structure describes who manages a vesting account, e.g., a contributor grant.
+#[cw_serde]
+pub struct VestingManagementParams {
+    /// The address allowed to clawback unvested tokens
+    pub manager: String,
+    /// The address that receives clawed back tokens
+    pub treasury: String,
+}
+
+/// This structure stores the management parameters of a vesting account.
+#[cw_serde]
+pub struct VestingManagement {
+    /// The address allowed to clawback unvested tokens
+    pub manager: Addr,
+    /// The address that receives clawed back tokens
+    pub treasury: Addr,
 }
 
 /// This structure stores parameters for a batch of vesting schedules.
@@ -73,6 +121,9 @@ pub struct VestingInfo {
     pub schedules: Vec<VestingSchedule>,
     /// The total amount of ASTRO already claimed
     pub released_amount: Uint128,
+    /// Management parameters if the account is managed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managed: Option<VestingManagement>,
 }
 
 /// This structure stores parameters for a specific vesting schedule
@@ -117,6 +168,48 @@ pub enum QueryMsg {
     /// Timestamp returns the current timestamp
     #[returns(u64)]
     Timestamp {},
+    /// Returns the latest claims of a vesting account in ascending order.
+    /// Only the last [`MAX_CLAIM_HISTORY`] claims are kept.
+    #[returns(Vec<ClaimRecord>)]
+    ClaimHistory {
+        address: String,
+        /// Claim id to start reading after
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the pending migration of a vesting account if any.
+    #[returns(Option<AccountMigration>)]
+    AccountMigration { address: String },
+}
+
+/// Period in seconds during which a pending account migration can be cancelled (7 days)
+pub const ACCOUNT_MIGRATION_DISPUTE_PERIOD: u64 = 7 * 86400;
+
+/// This structure describes a pending move of a vesting account to a new address.
+#[cw_serde]
+pub struct AccountMigration {
+    /// The address which will receive the vesting account
+    pub new_address: Addr,
+    /// Timestamp when the migration was requested
+    pub requested_at: u64,
+    /// Timestamp after which the migration can be executed
+    pub executable_at: u64,
+    /// Reference to the proof of the key compromise. Set if the migration was approved by the owner
+    pub proof: Option<String>,
+}
+
+/// Number of the latest claims kept in history per vesting account
+pub const MAX_CLAIM_HISTORY: u64 = 100;
+
+/// This structure describes a single claim of vested tokens.
+#[cw_serde]
+pub struct ClaimRecord {
+    /// Sequential claim id of the vesting account starting from 0
+    pub id: u64,
+    /// Block timestamp of the claim
+    pub timestamp: u64,
+    /// The amount of tokens claimed
+    pub amount: Uint128,
 }
 
 /// This structure describes a custom struct used to return the contract configuration.
@@ -169,7 +262,8 @@ impl Into<Order> for OrderBy {
 pub struct MigrateMsg {
     /// Special migration message needed during the Hub move.
     /// Cw admin must be very cautious supplying correct converter contract.
-    pub converter_contract: String,
+    /// Required only when migrating from versions prior to 1.4.0.
+    pub converter_contract: Option<String>,
 }
 
 /// This structure describes a CW20 hook message.
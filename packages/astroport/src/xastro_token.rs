@@ -50,6 +50,14 @@ pub enum QueryMsg {
     /// TotalSupplyAt returns the total token supply at the given block.
     #[returns(Uint128)]
     TotalSupplyAt { block: u64 },
+    /// BalanceAtTimestamp returns balance of the given address at the given timestamp (in seconds), 0 if unset.
+    /// Fails if the timestamp history was not tracked yet at that time.
+    #[returns(BalanceResponse)]
+    BalanceAtTimestamp { address: String, timestamp: u64 },
+    /// TotalSupplyAtTimestamp returns the total token supply at the given timestamp (in seconds).
+    /// Fails if the timestamp history was not tracked yet at that time.
+    #[returns(Uint128)]
+    TotalSupplyAtTimestamp { timestamp: u64 },
     /// TokenInfo returns the contract's metadata - name, decimals, supply, etc.
     #[returns(TokenInfoResponse)]
     TokenInfo {},
@@ -0,0 +1,29 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+/// This enum describes errors of the common pair liquidity helpers.
+/// Pair contracts map them to their own errors.
+#[derive(Error, Debug, PartialEq)]
+pub enum PairCommonError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Doubling assets in asset infos")]
+    DoublingAssets {},
+
+    #[error("Incentives address is not set in factory. Cannot auto-stake")]
+    AutoStakeError {},
+
+    #[error("Wrong asset length: expected {expected}, actual {actual}")]
+    WrongAssetLength { expected: usize, actual: usize },
+
+    #[error("Asset mismatch between the requested and the stored asset in contract")]
+    AssetMismatch {},
+
+    #[error("Received {received} {asset_name} but expected {expected}")]
+    WithdrawSlippageViolation {
+        asset_name: String,
+        received: Uint128,
+        expected: Uint128,
+    },
+}
@@ -0,0 +1,331 @@
+use cosmwasm_std::{
+    coin, wasm_execute, Addr, Api, Coin, CosmosMsg, CustomMsg, CustomQuery, Decimal,
+    QuerierWrapper, StdResult, Uint128,
+};
+
+use itertools::Itertools;
+
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
+use astroport::incentives::{ExecuteMsg as IncentiveExecuteMsg, LegacyGeneratorExecuteMsg};
+use astroport::querier::{query_native_supply, query_staking_target, StakingTarget};
+use astroport::token_factory::tf_mint_msg;
+
+use crate::error::PairCommonError;
+
+/// Checks that the asset infos are valid and unique.
+pub fn check_asset_infos(api: &dyn Api, asset_infos: &[AssetInfo]) -> Result<(), PairCommonError> {
+    if !asset_infos.iter().all_unique() {
+        return Err(PairCommonError::DoublingAssets {});
+    }
+
+    asset_infos
+        .iter()
+        .try_for_each(|asset_info| asset_info.check(api))
+        .map_err(Into::into)
+}
+
+/// Checks that the infos of the given assets are valid and unique.
+pub fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), PairCommonError> {
+    let asset_infos = assets.iter().map(|asset| asset.info.clone()).collect_vec();
+    check_asset_infos(api, &asset_infos)
+}
+
+/// Returns the LP token receiver. The sender receives LP tokens if `receiver` is not set.
+pub fn resolve_receiver(
+    api: &dyn Api,
+    receiver: &Option<String>,
+    sender: &Addr,
+) -> StdResult<Addr> {
+    Ok(addr_opt_validate(api, receiver)?.unwrap_or_else(|| sender.clone()))
+}
+
+/// Returns the pool reserves as well as the total amount of LP tokens currently minted.
+pub fn pool_info(
+    querier: QuerierWrapper,
+    pair_info: &PairInfo,
+) -> StdResult<(Vec<Asset>, Uint128)> {
+    let pools = pair_info.query_pools(&querier, &pair_info.contract_addr)?;
+    let total_share = query_native_supply(&querier, &pair_info.liquidity_token)?;
+
+    Ok((pools, total_share))
+}
+
+/// Mints LP tokens for a beneficiary and auto stakes them in the incentives contract if `auto_stake` is set.
+///
+/// * **contract_address** the pair address which is the LP token admin.
+///
+/// * **staking_target** staking contract to use instead of the factory default.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_liquidity_token_message<T, C>(
+    querier: QuerierWrapper<C>,
+    factory_addr: &Addr,
+    lp_denom: &str,
+    contract_address: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+    auto_stake: bool,
+    staking_target: Option<&str>,
+) -> Result<Vec<CosmosMsg<T>>, PairCommonError>
+where
+    C: CustomQuery,
+    T: CustomMsg,
+{
+    let lp_coin = coin(amount.into(), lp_denom);
+
+    if !auto_stake {
+        return lp_mint_msgs(contract_address, lp_coin, recipient, None).map_err(Into::into);
+    }
+
+    let staking = query_staking_target(&querier, factory_addr, staking_target)?
+        .ok_or(PairCommonError::AutoStakeError {})?;
+
+    lp_mint_msgs(contract_address, lp_coin, recipient, Some(&staking)).map_err(Into::into)
+}
+
+/// Builds messages minting LP tokens for the recipient. If `staking_contract` is set,
+/// LP tokens are minted for the pair and deposited into the staking contract on behalf of the recipient.
+pub fn lp_mint_msgs<T>(
+    contract_address: &Addr,
+    lp_coin: Coin,
+    recipient: &Addr,
+    staking_contract: Option<&StakingTarget>,
+) -> StdResult<Vec<CosmosMsg<T>>>
+where
+    T: CustomMsg,
+{
+    match staking_contract {
+        None => Ok(tf_mint_msg(contract_address, lp_coin, recipient)),
+        Some(staking_contract) => {
+            let mut msgs = tf_mint_msg(contract_address, lp_coin.clone(), contract_address);
+            let recipient = Some(recipient.to_string());
+            let deposit_msg = match staking_contract {
+                StakingTarget::Incentives(addr) => wasm_execute(
+                    addr,
+                    &IncentiveExecuteMsg::Deposit {
+                        recipient,
+                        referrer: None,
+                    },
+                    vec![lp_coin],
+                )?,
+                StakingTarget::LegacyGenerator(addr) => wasm_execute(
+                    addr,
+                    &LegacyGeneratorExecuteMsg::Deposit { recipient },
+                    vec![lp_coin],
+                )?,
+            };
+            msgs.push(deposit_msg.into());
+            Ok(msgs)
+        }
+    }
+}
+
+/// Returns the amount of pool assets that correspond to the given amount of LP tokens.
+///
+/// * **total_share** total amount of LP tokens currently minted.
+pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint128) -> Vec<Asset> {
+    let share_ratio = if total_share.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(amount, total_share)
+    };
+
+    pools
+        .iter()
+        .map(|pool| Asset {
+            info: pool.info.clone(),
+            amount: pool.amount * share_ratio,
+        })
+        .collect()
+}
+
+/// Checks that a withdrawal returns at least `min_assets_to_receive`. The order of assets doesn't matter.
+///
+/// * **asset_infos** the pair assets.
+///
+/// * **refund_assets** the assets returned by the withdrawal.
+pub fn ensure_min_assets_to_receive(
+    asset_infos: &[AssetInfo],
+    refund_assets: Vec<Asset>,
+    min_assets_to_receive: Option<Vec<Asset>>,
+) -> Result<(), PairCommonError> {
+    let Some(min_assets_to_receive) = min_assets_to_receive else {
+        return Ok(());
+    };
+
+    if refund_assets.len() != min_assets_to_receive.len() {
+        return Err(PairCommonError::WrongAssetLength {
+            expected: refund_assets.len(),
+            actual: min_assets_to_receive.len(),
+        });
+    }
+
+    if min_assets_to_receive
+        .iter()
+        .any(|asset| !asset_infos.contains(&asset.info))
+    {
+        return Err(PairCommonError::AssetMismatch {});
+    }
+
+    for min in &min_assets_to_receive {
+        let refund = refund_assets
+            .iter()
+            .find(|refund| refund.info == min.info)
+            .ok_or(PairCommonError::AssetMismatch {})?;
+        if refund.amount < min.amount {
+            return Err(PairCommonError::WithdrawSlippageViolation {
+                asset_name: refund.info.to_string(),
+                received: refund.amount,
+                expected: min.amount,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::{from_json, Empty, WasmMsg};
+
+    use astroport::asset::{native_asset_info, AssetInfoExt};
+
+    use super::*;
+
+    fn assets(uusd: u128, uluna: u128) -> Vec<Asset> {
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(uusd),
+            native_asset_info("uluna".to_string()).with_balance(uluna),
+        ]
+    }
+
+    #[test]
+    fn duplicated_assets_are_rejected() {
+        let api = MockApi::default();
+
+        check_assets(&api, &assets(100, 50)).unwrap();
+        assert_eq!(
+            check_assets(&api, &[assets(100, 50), assets(1, 1)].concat()).unwrap_err(),
+            PairCommonError::DoublingAssets {}
+        );
+    }
+
+    #[test]
+    fn receiver_defaults_to_sender() {
+        let api = MockApi::default();
+        let sender = Addr::unchecked("sender");
+
+        assert_eq!(resolve_receiver(&api, &None, &sender).unwrap(), sender);
+        assert_eq!(
+            resolve_receiver(&api, &Some("receiver".to_string()), &sender).unwrap(),
+            Addr::unchecked("receiver")
+        );
+    }
+
+    #[test]
+    fn auto_staked_lp_is_deposited_for_recipient() {
+        let pair = Addr::unchecked("pair");
+        let recipient = Addr::unchecked("recipient");
+        let staking = StakingTarget::Incentives(Addr::unchecked("incentives"));
+        let lp_coin = coin(100, "factory/pair/astroport/share");
+
+        let msgs = lp_mint_msgs::<Empty>(&pair, lp_coin.clone(), &recipient, None).unwrap();
+        assert_eq!(msgs.len(), 1);
+
+        let msgs =
+            lp_mint_msgs::<Empty>(&pair, lp_coin.clone(), &recipient, Some(&staking)).unwrap();
+        assert_eq!(msgs.len(), 2);
+        match &msgs[1] {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                assert_eq!(contract_addr, staking.addr().as_str());
+                assert_eq!(funds, &vec![lp_coin.clone()]);
+                assert_eq!(
+                    from_json::<IncentiveExecuteMsg>(msg).unwrap(),
+                    IncentiveExecuteMsg::Deposit {
+                        recipient: Some(recipient.to_string()),
+                        referrer: None,
+                    }
+                );
+            }
+            _ => panic!("unexpected message {:?}", msgs[1]),
+        }
+
+        // The legacy generator doesn't know about referrers
+        let generator = StakingTarget::LegacyGenerator(Addr::unchecked("generator"));
+        let msgs = lp_mint_msgs::<Empty>(&pair, lp_coin, &recipient, Some(&generator)).unwrap();
+        match &msgs[1] {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(contract_addr, "generator");
+                assert_eq!(
+                    from_json::<LegacyGeneratorExecuteMsg>(msg).unwrap(),
+                    LegacyGeneratorExecuteMsg::Deposit {
+                        recipient: Some(recipient.to_string()),
+                    }
+                );
+            }
+            _ => panic!("unexpected message {:?}", msgs[1]),
+        }
+    }
+
+    #[test]
+    fn share_in_assets() {
+        assert_eq!(
+            get_share_in_assets(&assets(1000, 500), 10u128.into(), 100u128.into()),
+            assets(100, 50)
+        );
+        assert_eq!(
+            get_share_in_assets(&assets(1000, 500), 10u128.into(), Uint128::zero()),
+            assets(0, 0)
+        );
+    }
+
+    #[test]
+    fn min_assets_to_receive() {
+        let asset_infos = assets(0, 0)
+            .into_iter()
+            .map(|asset| asset.info)
+            .collect::<Vec<_>>();
+
+        ensure_min_assets_to_receive(&asset_infos, assets(100, 50), None).unwrap();
+
+        // The order of min assets doesn't matter
+        let mut min_assets = assets(100, 50);
+        min_assets.reverse();
+        ensure_min_assets_to_receive(&asset_infos, assets(100, 50), Some(min_assets)).unwrap();
+
+        assert_eq!(
+            ensure_min_assets_to_receive(&asset_infos, assets(100, 50), Some(assets(100, 51)))
+                .unwrap_err(),
+            PairCommonError::WithdrawSlippageViolation {
+                asset_name: "uluna".to_string(),
+                received: 50u128.into(),
+                expected: 51u128.into(),
+            }
+        );
+        assert_eq!(
+            ensure_min_assets_to_receive(&asset_infos, assets(100, 50), Some(vec![])).unwrap_err(),
+            PairCommonError::WrongAssetLength {
+                expected: 2,
+                actual: 0,
+            }
+        );
+        assert_eq!(
+            ensure_min_assets_to_receive(
+                &asset_infos,
+                assets(100, 50),
+                Some(vec![
+                    native_asset_info("uusd".to_string()).with_balance(1u8),
+                    native_asset_info("uatom".to_string()).with_balance(1u8),
+                ])
+            )
+            .unwrap_err(),
+            PairCommonError::AssetMismatch {}
+        );
+    }
+}
@@ -43,6 +43,10 @@ pub const REPEG_PROFIT_THRESHOLD_MAX: Decimal = Decimal::raw(10000000000000000);
 pub const PRICE_SCALE_DELTA_MIN: Decimal = Decimal::zero();
 pub const PRICE_SCALE_DELTA_MAX: Decimal = Decimal::one();
 
+pub const PRICE_SCALE_CHANGE_PER_HOUR_MIN: Decimal = Decimal::zero();
+/// 0.5 (50%)
+pub const PRICE_SCALE_CHANGE_PER_HOUR_MAX: Decimal = Decimal::raw(5e17 as u128);
+
 pub const MA_HALF_TIME_LIMITS: RangeInclusive<u64> = 1..=(7 * 86400);
 
 /// 0.1
@@ -11,10 +11,12 @@ use astroport::asset::{AssetInfo, PairInfo};
 use astroport::cosmwasm_ext::{AbsDiff, IntegerToDecimal};
 use astroport::pair::FeeShareConfig;
 use astroport::pair_concentrated::{PromoteParams, UpdatePoolParams};
+use astroport::querier::query_checked_precision;
 
 use crate::consts::{
     AMP_MAX, AMP_MIN, FEE_GAMMA_MAX, FEE_GAMMA_MIN, FEE_TOL, GAMMA_MAX, GAMMA_MIN, MAX_CHANGE,
-    MAX_FEE, MA_HALF_TIME_LIMITS, MIN_AMP_CHANGING_TIME, MIN_FEE, N_POW2, PRICE_SCALE_DELTA_MAX,
+    MAX_FEE, MA_HALF_TIME_LIMITS, MIN_AMP_CHANGING_TIME, MIN_FEE, N_POW2,
+    PRICE_SCALE_CHANGE_PER_HOUR_MAX, PRICE_SCALE_CHANGE_PER_HOUR_MIN, PRICE_SCALE_DELTA_MAX,
     PRICE_SCALE_DELTA_MIN, REPEG_PROFIT_THRESHOLD_MAX, REPEG_PROFIT_THRESHOLD_MIN, TWO,
 };
 use crate::error::PclError;
@@ -43,6 +45,10 @@ pub struct Config {
     pub fee_share: Option<FeeShareConfig>,
     /// The tracker contract address
     pub tracker_addr: Option<Addr>,
+    /// Minimum swap size in whole tokens of the first pool asset set by the factory pair config
+    /// at instantiation
+    #[serde(default)]
+    pub min_trade_notional: Option<Decimal>,
 }
 
 /// This structure stores the pool parameters which may be adjusted via the `update_pool_params`.
@@ -62,6 +68,9 @@ pub struct PoolParams {
     pub min_price_scale_delta: Decimal,
     /// Half-time used for calculating the price oracle
     pub ma_half_time: u64,
+    /// The maximum relative price scale change per hour. Zero means repegs are not rate limited
+    #[serde(default)]
+    pub max_price_scale_change_per_hour: Decimal,
 }
 
 /// Validates input value against its limits.
@@ -154,6 +163,20 @@ impl PoolParams {
             attributes.push(attr("ma_half_time", ma_half_time.to_string()));
         }
 
+        if let Some(max_change) = update_params.max_price_scale_change_per_hour {
+            validate_param(
+                "max_price_scale_change_per_hour",
+                max_change,
+                PRICE_SCALE_CHANGE_PER_HOUR_MIN,
+                PRICE_SCALE_CHANGE_PER_HOUR_MAX,
+            )?;
+            self.max_price_scale_change_per_hour = max_change;
+            attributes.push(attr(
+                "max_price_scale_change_per_hour",
+                max_change.to_string(),
+            ));
+        }
+
         Ok(attributes)
     }
 
@@ -208,6 +231,12 @@ pub struct PriceState {
     pub xcp_profit: Decimal256,
     /// Profits due to fees inclusive of realized losses from rebalancing
     pub xcp_profit_real: Decimal256,
+    /// Timestamp of the last price scale change
+    #[serde(default)]
+    pub last_repeg: u64,
+    /// Relative price scale change applied by the last repeg
+    #[serde(default)]
+    pub last_repeg_delta: Decimal256,
 }
 
 /// Internal structure which stores the pool's state.
@@ -303,6 +332,8 @@ impl PoolState {
 
     /// The function is responsible for repegging mechanism.
     /// It updates internal oracle price and adjusts price scale.
+    /// If repegs are rate limited, price scale moves by at most max_price_scale_change_per_hour
+    /// pro rata to the time passed since the last repeg. At most one hour of the limit is accumulated.
     ///
     /// * **total_lp** total LP tokens were minted
     /// * **cur_xs** - internal representation of pool volumes
@@ -362,21 +393,40 @@ impl PoolState {
         {
             let numerator = price_state.price_scale * (norm - scale_delta)
                 + scale_delta * price_state.oracle_price;
-            let price_scale_new = numerator / norm;
-
-            let xs = [
-                cur_xs[0],
-                cur_xs[1] * price_scale_new / price_state.price_scale,
-            ];
-            let new_d = calc_d(&xs, &amp_gamma)?;
-
-            let new_xcp = get_xcp(new_d, price_scale_new);
-            let new_xcp_profit_real = new_xcp / total_lp;
+            let mut price_scale_new = numerator / norm;
+
+            let mut delta = price_scale_new.diff(price_state.price_scale) / price_state.price_scale;
+            if !pool_params.max_price_scale_change_per_hour.is_zero() {
+                let elapsed = (block_time - price_state.last_repeg).min(3600);
+                let max_delta = Decimal256::from(pool_params.max_price_scale_change_per_hour)
+                    * Decimal256::from_ratio(elapsed, 3600u64);
+                if delta > max_delta {
+                    delta = max_delta;
+                    price_scale_new = if price_scale_new > price_state.price_scale {
+                        price_state.price_scale * (Decimal256::one() + max_delta)
+                    } else {
+                        price_state.price_scale * (Decimal256::one() - max_delta)
+                    };
+                }
+            }
 
-            if TWO * new_xcp_profit_real > xcp_profit + Decimal256::one() {
-                price_state.price_scale = price_scale_new;
-                price_state.xcp_profit_real = new_xcp_profit_real;
-            };
+            if !delta.is_zero() {
+                let xs = [
+                    cur_xs[0],
+                    cur_xs[1] * price_scale_new / price_state.price_scale,
+                ];
+                let new_d = calc_d(&xs, &amp_gamma)?;
+
+                let new_xcp = get_xcp(new_d, price_scale_new);
+                let new_xcp_profit_real = new_xcp / total_lp;
+
+                if TWO * new_xcp_profit_real > xcp_profit + Decimal256::one() {
+                    price_state.price_scale = price_scale_new;
+                    price_state.xcp_profit_real = new_xcp_profit_real;
+                    price_state.last_repeg = block_time;
+                    price_state.last_repeg_delta = delta;
+                };
+            }
         }
 
         Ok(())
@@ -403,7 +453,7 @@ impl<'a> Precisions {
         factory_addr: &Addr,
     ) -> StdResult<()> {
         for asset_info in asset_infos {
-            let precision = asset_info.decimals(&deps.querier, factory_addr)?;
+            let precision = query_checked_precision(&deps.querier, asset_info, factory_addr)?;
             Self::PRECISIONS.save(deps.storage, asset_info.to_string(), &precision)?;
         }
 
@@ -684,6 +734,7 @@ mod test {
             repeg_profit_threshold: Default::default(),
             min_price_scale_delta: Default::default(),
             ma_half_time: 0,
+            max_price_scale_change_per_hour: Default::default(),
         };
 
         let xp = vec![f64_to_dec256(1_000_000f64), f64_to_dec256(1_000_000f64)];
@@ -799,6 +850,7 @@ mod test {
             repeg_profit_threshold: f64_to_dec(0.000002),
             min_price_scale_delta: f64_to_dec(0.000146),
             ma_half_time: 600,
+            max_price_scale_change_per_hour: Decimal::zero(),
         };
 
         let mut pool_state = PoolState {
@@ -813,6 +865,7 @@ mod test {
                 last_price_update: env.block.time.seconds(),
                 xcp_profit: Decimal256::one(),
                 xcp_profit_real: Decimal256::one(),
+                ..Default::default()
             },
         };
 
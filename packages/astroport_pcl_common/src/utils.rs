@@ -1,14 +1,10 @@
 use cosmwasm_std::{
-    coin, wasm_execute, Addr, Api, CosmosMsg, CustomMsg, CustomQuery, Decimal, Decimal256, Env,
-    Fraction, QuerierWrapper, StdError, StdResult, Uint128,
+    Addr, CustomQuery, Decimal, Decimal256, Env, Fraction, QuerierWrapper, StdError, StdResult,
+    Uint128,
 };
-use itertools::Itertools;
 
 use astroport::asset::{Asset, AssetInfo, Decimal256Ext, DecimalAsset};
 use astroport::cosmwasm_ext::AbsDiff;
-use astroport::incentives::ExecuteMsg as IncentiveExecuteMsg;
-use astroport::querier::query_factory_config;
-use astroport::token_factory::tf_mint_msg;
 use astroport_factory::state::pair_key;
 
 use crate::consts::{
@@ -21,24 +17,6 @@ use crate::{calc_d, calc_y};
 #[cfg(any(feature = "injective", feature = "sei"))]
 use cosmwasm_std::BankMsg;
 
-/// Helper function to check the given asset infos are valid.
-pub fn check_asset_infos(api: &dyn Api, asset_infos: &[AssetInfo]) -> Result<(), PclError> {
-    if !asset_infos.iter().all_unique() {
-        return Err(PclError::DoublingAssets {});
-    }
-
-    asset_infos
-        .iter()
-        .try_for_each(|asset_info| asset_info.check(api))
-        .map_err(Into::into)
-}
-
-/// Helper function to check that the assets in a given array are valid.
-pub fn check_assets(api: &dyn Api, assets: &[Asset]) -> Result<(), PclError> {
-    let asset_infos = assets.iter().map(|asset| asset.info.clone()).collect_vec();
-    check_asset_infos(api, &asset_infos)
-}
-
 /// Checks that cw20 token is part of the pool.
 ///
 /// * **cw20_sender** is cw20 token address which is being checked.
@@ -53,54 +31,6 @@ pub fn check_cw20_in_pool(config: &Config, cw20_sender: &Addr) -> Result<(), Pcl
     Err(PclError::Unauthorized {})
 }
 
-/// Mint LP tokens for a beneficiary and auto stake the tokens in the Incentive contract (if auto staking is specified).
-///
-/// * **recipient** LP token recipient.
-///
-/// * **coin** denom and amount of LP tokens that will be minted for the recipient.
-///
-/// * **auto_stake** determines whether the newly minted LP tokens will
-/// be automatically staked in the Incentives Contract on behalf of the recipient.
-pub fn mint_liquidity_token_message<T, C>(
-    querier: QuerierWrapper<C>,
-    config: &Config,
-    contract_address: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-    auto_stake: bool,
-) -> Result<Vec<CosmosMsg<T>>, PclError>
-where
-    C: CustomQuery,
-    T: CustomMsg,
-{
-    let coin = coin(amount.into(), config.pair_info.liquidity_token.to_string());
-
-    // If no auto-stake - just mint to recipient
-    if !auto_stake {
-        return Ok(tf_mint_msg(contract_address, coin, recipient));
-    }
-
-    // Mint for the pair contract and stake into the Incentives contract
-    let incentives_addr = query_factory_config(&querier, &config.factory_addr)?.generator_address;
-
-    if let Some(address) = incentives_addr {
-        let mut msgs = tf_mint_msg(contract_address, coin.clone(), contract_address);
-        msgs.push(
-            wasm_execute(
-                address,
-                &IncentiveExecuteMsg::Deposit {
-                    recipient: Some(recipient.to_string()),
-                },
-                vec![coin],
-            )?
-            .into(),
-        );
-        Ok(msgs)
-    } else {
-        Err(PclError::AutoStakeError {})
-    }
-}
-
 /// Return the amount of tokens that a specific amount of LP tokens would withdraw.
 ///
 /// * **pools** assets available in the pool.
@@ -307,6 +237,7 @@ pub fn compute_swap(
 }
 
 /// Returns an amount of offer assets for a specified amount of ask assets.
+/// Spread and fee are returned in ask asset units.
 pub fn compute_offer_amount(
     xs: &[Decimal256],
     mut want_amount: Decimal256,
@@ -341,6 +272,8 @@ pub fn compute_offer_amount(
     let mut spread_fee = dy.saturating_sub(before_fee);
     if offer_ind == 1 {
         dy /= config.pool_state.price_state.price_scale;
+    } else {
+        // Spread and fee are denominated in the ask asset as in forward swap simulation
         spread_fee /= config.pool_state.price_state.price_scale;
         fee /= config.pool_state.price_state.price_scale;
     }
@@ -5,3 +5,4 @@ pub use cw_multi_test;
 pub mod coins;
 pub mod convert;
 pub mod modules;
+pub mod pair;
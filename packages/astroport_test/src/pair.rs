@@ -0,0 +1,172 @@
+use std::fmt::{Debug, Display};
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coin, Addr, Coin, Decimal, Uint128};
+use cw_multi_test::{AppResponse, BankSudo, Executor};
+
+use astroport::asset::{Asset, AssetInfo, PairInfo, MINIMUM_LIQUIDITY_AMOUNT};
+use astroport::pair::{ExecuteMsg, MinimumLiquidityResponse, QueryMsg};
+
+use crate::modules::stargate::StargateApp;
+
+/// Amount of each pool asset the attacker donates to the pair
+const DONATION: u128 = 1_000_000_000000;
+/// Amount of each pool asset the victim provides after the donation
+const VICTIM_DEPOSIT: u128 = 5_000_000_000000;
+/// Deposit which is rounded down to zero LP tokens after the donation
+const DUST_DEPOSIT: u128 = 100;
+
+/// Runs the first depositor (share inflation) attack against an empty pair with two native assets
+/// and checks that the [`MINIMUM_LIQUIDITY_AMOUNT`] lock-up makes it unprofitable.
+///
+/// * **initial_deposit** smallest amount of each asset which mints LP tokens on the first provision.
+///
+/// * **min_liquidity_err** pair error returned when the first provision doesn't exceed the locked amount.
+///
+/// * **too_small_err** pair error returned when a later provision is rounded down to zero LP tokens.
+pub fn check_first_depositor_donation_attack<E>(
+    app: &mut StargateApp,
+    pair_addr: &Addr,
+    initial_deposit: u128,
+    min_liquidity_err: E,
+    too_small_err: E,
+) where
+    E: Display + Debug + PartialEq + Send + Sync + 'static,
+{
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(pair_addr, &QueryMsg::Pair {})
+        .unwrap();
+    let denoms = pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| match asset_info {
+            AssetInfo::NativeToken { denom } => denom.clone(),
+            AssetInfo::Token { .. } => panic!("Only native pool assets are supported"),
+        })
+        .collect::<Vec<_>>();
+    let lp_token = pair_info.liquidity_token;
+
+    let resp: MinimumLiquidityResponse = app
+        .wrap()
+        .query_wasm_smart(pair_addr, &QueryMsg::MinimumLiquidity {})
+        .unwrap();
+    assert_eq!(resp, MinimumLiquidityResponse::new(pair_addr.clone()));
+
+    let attacker = Addr::unchecked("attacker");
+    let victim = Addr::unchecked("victim");
+    mint(app, &attacker, &denoms, initial_deposit + DONATION);
+    mint(app, &victim, &denoms, VICTIM_DEPOSIT + DUST_DEPOSIT);
+
+    // Initial provide must be greater than the locked amount
+    let err = provide(app, pair_addr, &attacker, &denoms, initial_deposit / 2).unwrap_err();
+    assert_eq!(err.downcast::<E>().unwrap(), min_liquidity_err);
+
+    // Attacker mints the smallest possible amount of LP tokens
+    provide(app, pair_addr, &attacker, &denoms, initial_deposit).unwrap();
+    let attacker_lp = app.wrap().query_balance(&attacker, &lp_token).unwrap();
+    assert!(!attacker_lp.amount.is_zero() && attacker_lp.amount.u128() < 10);
+    let locked_lp = app.wrap().query_balance(pair_addr, &lp_token).unwrap();
+    assert_eq!(locked_lp.amount, MINIMUM_LIQUIDITY_AMOUNT);
+
+    // Attacker inflates the LP token price by donating directly to the pair
+    app.send_tokens(
+        attacker.clone(),
+        pair_addr.clone(),
+        &funds(&denoms, DONATION),
+    )
+    .unwrap();
+
+    // Deposit which would be rounded down to zero LP tokens is rejected
+    let err = provide(app, pair_addr, &victim, &denoms, DUST_DEPOSIT).unwrap_err();
+    assert_eq!(err.downcast::<E>().unwrap(), too_small_err);
+
+    // Large deposit loses only a negligible rounding amount
+    provide(app, pair_addr, &victim, &denoms, VICTIM_DEPOSIT).unwrap();
+    let victim_lp = app.wrap().query_balance(&victim, &lp_token).unwrap();
+    withdraw(app, pair_addr, &victim, victim_lp).unwrap();
+    for denom in &denoms {
+        // Dust is left from the rejected provide
+        let balance = app
+            .wrap()
+            .query_balance(&victim, denom)
+            .unwrap()
+            .amount
+            .u128()
+            - DUST_DEPOSIT;
+        assert!(
+            balance >= VICTIM_DEPOSIT * 99 / 100,
+            "victim lost more than 1% of {VICTIM_DEPOSIT}{denom}: {balance}"
+        );
+    }
+
+    // Attacker can't withdraw more than they put into the pair
+    withdraw(app, pair_addr, &attacker, attacker_lp).unwrap();
+    for denom in &denoms {
+        let balance = app.wrap().query_balance(&attacker, denom).unwrap().amount;
+        assert!(balance < Uint128::new(initial_deposit + DONATION));
+    }
+}
+
+fn funds(denoms: &[String], amount: u128) -> Vec<Coin> {
+    let mut funds = denoms
+        .iter()
+        .map(|denom| coin(amount, denom))
+        .collect::<Vec<_>>();
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+    funds
+}
+
+fn mint(app: &mut StargateApp, recipient: &Addr, denoms: &[String], amount: u128) {
+    app.sudo(
+        BankSudo::Mint {
+            to_address: recipient.to_string(),
+            amount: funds(denoms, amount),
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+fn provide(
+    app: &mut StargateApp,
+    pair_addr: &Addr,
+    sender: &Addr,
+    denoms: &[String],
+    amount: u128,
+) -> AnyResult<AppResponse> {
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: denoms
+            .iter()
+            .map(|denom| Asset::native(denom, amount))
+            .collect(),
+        slippage_tolerance: Some(Decimal::percent(50)),
+        auto_stake: None,
+        receiver: None,
+        min_lp_to_receive: None,
+        staking_target: None,
+        deadline: None,
+    };
+
+    app.execute_contract(
+        sender.clone(),
+        pair_addr.clone(),
+        &msg,
+        &funds(denoms, amount),
+    )
+}
+
+fn withdraw(
+    app: &mut StargateApp,
+    pair_addr: &Addr,
+    sender: &Addr,
+    lp_tokens: Coin,
+) -> AnyResult<AppResponse> {
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        assets: vec![],
+        min_assets_to_receive: None,
+        deadline: None,
+    };
+
+    app.execute_contract(sender.clone(), pair_addr.clone(), &msg, &[lp_tokens])
+}
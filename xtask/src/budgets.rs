@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::workspace_root;
+
+/// Thresholds recorded in xtask/budgets.json.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Budgets {
+    pub wasm: WasmBudgets,
+    pub gas: GasBudgets,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WasmBudgets {
+    /// The chain upload limit in KiB. Exceeding it always fails the check
+    pub max_size_kib: u64,
+    /// Allowed growth over the recorded size in percent
+    pub max_regression_pct: u64,
+    /// Recorded sizes in bytes per artifact file name
+    pub sizes: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GasBudgets {
+    /// Allowed growth over the recorded gas in percent
+    pub max_regression_pct: u64,
+    /// Recorded gas per scenario name
+    pub scenarios: BTreeMap<String, u64>,
+}
+
+impl Budgets {
+    fn path() -> PathBuf {
+        workspace_root().join("xtask").join("budgets.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {path:?}"))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {path:?}"))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        fs::write(&path, content).with_context(|| format!("Failed to write {path:?}"))
+    }
+}
+
+/// Outcome of comparing a measurement with its recorded value.
+pub enum Verdict {
+    /// Nothing is recorded for this measurement yet
+    New,
+    Ok,
+    Regressed {
+        limit: u64,
+    },
+}
+
+pub fn compare(actual: u64, recorded: Option<u64>, max_regression_pct: u64) -> Verdict {
+    match recorded {
+        None => Verdict::New,
+        Some(recorded) => {
+            let limit = recorded + recorded * max_regression_pct / 100;
+            if actual > limit {
+                Verdict::Regressed { limit }
+            } else {
+                Verdict::Ok
+            }
+        }
+    }
+}
+
+/// Prints a measurement line and returns whether the measurement is within its budget.
+pub fn report(name: &str, actual: u64, recorded: Option<u64>, max_regression_pct: u64) -> bool {
+    match compare(actual, recorded, max_regression_pct) {
+        Verdict::New => {
+            println!("{name:<48} {actual:>12}  (not recorded, run with --update)");
+            true
+        }
+        Verdict::Ok => {
+            println!("{name:<48} {actual:>12}  (recorded {})", recorded.unwrap());
+            true
+        }
+        Verdict::Regressed { limit } => {
+            println!(
+                "{name:<48} {actual:>12}  REGRESSED: recorded {}, limit {limit}",
+                recorded.unwrap()
+            );
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert!(matches!(compare(100, None, 5), Verdict::New));
+        assert!(matches!(compare(90, Some(100), 5), Verdict::Ok));
+        assert!(matches!(compare(105, Some(100), 5), Verdict::Ok));
+        assert!(matches!(
+            compare(106, Some(100), 5),
+            Verdict::Regressed { limit: 105 }
+        ));
+    }
+}
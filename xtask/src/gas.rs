@@ -0,0 +1,32 @@
+use std::env;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use xtask::{workspace_root, UPDATE_ENV};
+
+/// Runs the gas budget suite from xtask/tests/gas. The suite needs the contracts and test utilities
+/// which are only dev-dependencies of xtask thus it is executed as an integration test.
+pub fn run(update: bool) -> Result<()> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut cmd = Command::new(cargo);
+    cmd.current_dir(workspace_root()).args([
+        "test",
+        "--package",
+        "xtask",
+        "--test",
+        "gas",
+        "--",
+        "--nocapture",
+    ]);
+    if update {
+        cmd.env(UPDATE_ENV, "1");
+    }
+
+    let status = cmd.status().context("Failed to run cargo test")?;
+    if !status.success() {
+        bail!("Gas budget suite failed");
+    }
+
+    Ok(())
+}
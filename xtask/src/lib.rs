@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+pub mod budgets;
+
+/// Environment variable which makes the gas budget suite record current measurements.
+pub const UPDATE_ENV: &str = "XTASK_UPDATE";
+
+/// Returns the workspace root directory.
+pub fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask must be located in the workspace root")
+        .to_path_buf()
+}
@@ -0,0 +1,33 @@
+use std::env;
+use std::process::exit;
+
+mod gas;
+mod wasm_size;
+
+const USAGE: &str = "Usage: cargo xtask <command> [--update]
+
+Commands:
+  wasm-size    Checks optimized wasm sizes in artifacts/ against the chain upload limit and recorded sizes
+  gas          Runs the gas budget suite against recorded gas budgets
+
+Options:
+  --update     Records current measurements in xtask/budgets.json";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let update = args.iter().any(|arg| arg == "--update");
+
+    let res = match args.first().map(String::as_str) {
+        Some("wasm-size") => wasm_size::run(update),
+        Some("gas") => gas::run(update),
+        _ => {
+            eprintln!("{USAGE}");
+            exit(2);
+        }
+    };
+
+    if let Err(err) = res {
+        eprintln!("Error: {err:#}");
+        exit(1);
+    }
+}
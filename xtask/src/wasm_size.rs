@@ -0,0 +1,63 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+
+use xtask::budgets::{report, Budgets};
+use xtask::workspace_root;
+
+/// Checks optimized contracts in artifacts/ produced by scripts/build_release.sh.
+/// Any artifact above the chain upload limit fails the check regardless of `update`.
+pub fn run(update: bool) -> Result<()> {
+    let mut budgets = Budgets::load()?;
+    let artifacts_dir = workspace_root().join("artifacts");
+
+    let mut artifacts = fs::read_dir(&artifacts_dir)
+        .with_context(|| format!("Failed to read {artifacts_dir:?}. Build artifacts first"))?
+        .map(|entry| {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            Ok((name, entry.metadata()?.len()))
+        })
+        .filter(|res: &Result<(String, u64)>| {
+            res.as_ref()
+                .map_or(true, |(name, _)| name.ends_with(".wasm"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    artifacts.sort();
+
+    if artifacts.is_empty() {
+        bail!("No wasm artifacts found in {artifacts_dir:?}");
+    }
+
+    let max_size = budgets.wasm.max_size_kib * 1024;
+    let mut oversized = vec![];
+    let mut regressed = vec![];
+    for (name, size) in &artifacts {
+        if *size > max_size {
+            println!("{name:<48} {size:>12}  EXCEEDS upload limit of {max_size} bytes");
+            oversized.push(name.clone());
+            continue;
+        }
+
+        let recorded = budgets.wasm.sizes.get(name).copied();
+        if !report(name, *size, recorded, budgets.wasm.max_regression_pct) {
+            regressed.push(name.clone());
+        }
+    }
+
+    if !oversized.is_empty() {
+        bail!("Artifacts exceed the chain upload limit: {oversized:?}");
+    }
+
+    if update {
+        budgets.wasm.sizes = artifacts.into_iter().collect();
+        budgets.save()?;
+        println!("Recorded wasm sizes updated");
+    } else if !regressed.is_empty() {
+        bail!(
+            "Artifact sizes regressed: {regressed:?}. Run with --update if the growth is expected"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,352 @@
+use std::env;
+
+use anyhow::{bail, Result};
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, to_json_binary, Addr, Coin, Decimal, Empty};
+use serde::Serialize;
+
+use astroport::asset::{native_asset, native_asset_info, PairInfo};
+use astroport::factory::{PairConfig, PairType};
+use astroport::pair::{ExecuteMsg, StablePoolParams};
+use astroport::pair_concentrated::ConcentratedPoolParams;
+use astroport_test::cw_multi_test::{
+    App, AppBuilder, BankKeeper, ContractWrapper, DistributionKeeper, Executor, FailingModule,
+    GovFailingModule, IbcFailingModule, StakeKeeper, WasmKeeper,
+};
+use astroport_test::modules::stargate::MockStargate;
+
+use storage::{GasMeter, GasMeteredStorage};
+use xtask::budgets::{report, Budgets};
+use xtask::UPDATE_ENV;
+
+mod storage;
+
+type GasApp = App<
+    BankKeeper,
+    MockApi,
+    GasMeteredStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    MockStargate,
+>;
+
+const OWNER: &str = "owner";
+const DENOMS: [&str; 2] = ["uusd", "uluna"];
+
+/// Runs every scenario and compares consumed gas with the recorded budgets.
+/// Current measurements are recorded instead if [`UPDATE_ENV`] is set.
+#[test]
+fn gas_budgets() -> Result<()> {
+    let update = env::var_os(UPDATE_ENV).is_some();
+    let mut budgets = Budgets::load()?;
+
+    let mut suite = Suite::new()?;
+    let mut measurements = vec![];
+    for (name, pair_type, init_params) in [
+        ("xyk", PairType::Xyk {}, None),
+        (
+            "stable",
+            PairType::Stable {},
+            Some(to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+                lp_token_metadata: None,
+                balance_caps: None,
+            })?),
+        ),
+        (
+            "concentrated",
+            PairType::Custom("concentrated".to_string()),
+            Some(to_json_binary(&pcl_params())?),
+        ),
+    ] {
+        measurements.extend(suite.pair_scenarios(name, pair_type, init_params)?);
+    }
+
+    let mut regressed = vec![];
+    for (name, gas) in &measurements {
+        let recorded = budgets.gas.scenarios.get(name).copied();
+        if !report(name, *gas, recorded, budgets.gas.max_regression_pct) {
+            regressed.push(name.clone());
+        }
+    }
+
+    if update {
+        budgets.gas.scenarios = measurements.into_iter().collect();
+        budgets.save()?;
+        println!("Recorded gas budgets updated");
+    } else if !regressed.is_empty() {
+        bail!(
+            "Gas consumption regressed: {regressed:?}. Run with --update if the growth is expected"
+        );
+    }
+
+    Ok(())
+}
+
+fn pcl_params() -> ConcentratedPoolParams {
+    ConcentratedPoolParams {
+        amp: Decimal::from_ratio(40u8, 1u8),
+        gamma: Decimal::from_ratio(145u8, 1_000_000u32),
+        mid_fee: Decimal::from_ratio(26u8, 10_000u16),
+        out_fee: Decimal::from_ratio(45u8, 10_000u16),
+        fee_gamma: Decimal::from_ratio(23u8, 100_000u32),
+        repeg_profit_threshold: Decimal::from_ratio(2u8, 1_000_000u32),
+        min_price_scale_delta: Decimal::from_ratio(146u8, 1_000_000u32),
+        price_scale: Decimal::one(),
+        ma_half_time: 600,
+        track_asset_balances: None,
+        fee_share: None,
+        lp_token_metadata: None,
+        balance_caps: None,
+        max_price_scale_change_per_hour: None,
+    }
+}
+
+struct Suite {
+    app: GasApp,
+    meter: GasMeter,
+    owner: Addr,
+    factory: Addr,
+}
+
+impl Suite {
+    fn new() -> Result<Self> {
+        let meter = GasMeter::default();
+        let owner = Addr::unchecked(OWNER);
+        let mut app = AppBuilder::new_custom()
+            .with_stargate(MockStargate::default())
+            .with_storage(GasMeteredStorage::new(meter.clone()))
+            .build(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &owner,
+                        DENOMS
+                            .iter()
+                            .map(|denom| coin(u128::MAX / 4, *denom))
+                            .collect(),
+                    )
+                    .unwrap()
+            });
+
+        let coin_registry_code_id = app.store_code(Box::new(ContractWrapper::new_with_empty(
+            astroport_native_coin_registry::contract::execute,
+            astroport_native_coin_registry::contract::instantiate,
+            astroport_native_coin_registry::contract::query,
+        )));
+        let coin_registry = app.instantiate_contract(
+            coin_registry_code_id,
+            owner.clone(),
+            &astroport::native_coin_registry::InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            "Coin registry",
+            None,
+        )?;
+        app.execute_contract(
+            owner.clone(),
+            coin_registry.clone(),
+            &astroport::native_coin_registry::ExecuteMsg::Add {
+                native_coins: DENOMS.iter().map(|denom| (denom.to_string(), 6)).collect(),
+            },
+            &[],
+        )?;
+
+        let xyk_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(
+                astroport_pair::contract::execute,
+                astroport_pair::contract::instantiate,
+                astroport_pair::contract::query,
+            )
+            .with_reply_empty(astroport_pair::contract::reply),
+        ));
+        let stable_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(
+                astroport_pair_stable::contract::execute,
+                astroport_pair_stable::contract::instantiate,
+                astroport_pair_stable::contract::query,
+            )
+            .with_reply_empty(astroport_pair_stable::contract::reply),
+        ));
+        let pcl_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(
+                astroport_pair_concentrated::contract::execute,
+                astroport_pair_concentrated::contract::instantiate,
+                astroport_pair_concentrated::queries::query,
+            )
+            .with_reply_empty(astroport_pair_concentrated::contract::reply),
+        ));
+        let factory_code_id = app.store_code(Box::new(
+            ContractWrapper::new_with_empty(
+                astroport_factory::contract::execute,
+                astroport_factory::contract::instantiate,
+                astroport_factory::contract::query,
+            )
+            .with_reply_empty(astroport_factory::contract::reply),
+        ));
+
+        let pair_config = |code_id: u64, pair_type: PairType, total_fee_bps: u16| PairConfig {
+            code_id,
+            pair_type,
+            total_fee_bps,
+            maker_fee_bps: 5000,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+            min_trade_notional: None,
+        };
+        let factory = app.instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &astroport::factory::InstantiateMsg {
+                pair_configs: vec![
+                    pair_config(xyk_code_id, PairType::Xyk {}, 30),
+                    pair_config(stable_code_id, PairType::Stable {}, 5),
+                    pair_config(pcl_code_id, PairType::Custom("concentrated".to_string()), 0),
+                ],
+                token_code_id: 0,
+                fee_address: Some("maker".to_string()),
+                generator_address: None,
+                owner: owner.to_string(),
+                whitelist_code_id: 0,
+                coin_registry_address: coin_registry.to_string(),
+                tracker_config: None,
+                ownership_timelock: None,
+            },
+            &[],
+            "Factory",
+            None,
+        )?;
+
+        Ok(Self {
+            app,
+            meter,
+            owner,
+            factory,
+        })
+    }
+
+    fn next_block(&mut self) {
+        self.app.update_block(|block| {
+            block.height += 1;
+            block.time = block.time.plus_seconds(5);
+        })
+    }
+
+    /// Executes the message and returns the gas consumed by it.
+    fn measure<T: Serialize + std::fmt::Debug>(
+        &mut self,
+        contract: &Addr,
+        msg: &T,
+        funds: &[Coin],
+    ) -> Result<u64> {
+        self.next_block();
+        let before = self.meter.consumed();
+        self.app
+            .execute_contract(self.owner.clone(), contract.clone(), msg, funds)?;
+        Ok(self.meter.consumed() - before)
+    }
+
+    /// Creates a pair and measures the main pool operations in a pool with existing liquidity.
+    fn pair_scenarios(
+        &mut self,
+        name: &str,
+        pair_type: PairType,
+        init_params: Option<cosmwasm_std::Binary>,
+    ) -> Result<Vec<(String, u64)>> {
+        let asset_infos = DENOMS
+            .iter()
+            .map(|denom| native_asset_info(denom.to_string()))
+            .collect::<Vec<_>>();
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.factory.clone(),
+            &astroport::factory::ExecuteMsg::CreatePair {
+                pair_type,
+                asset_infos: asset_infos.clone(),
+                init_params,
+            },
+            &[],
+        )?;
+        let pair_info: PairInfo = self.app.wrap().query_wasm_smart(
+            &self.factory,
+            &astroport::factory::QueryMsg::Pair { asset_infos },
+        )?;
+        let pair = pair_info.contract_addr;
+
+        let provide = |amount: u128| {
+            let msg = ExecuteMsg::ProvideLiquidity {
+                assets: DENOMS
+                    .iter()
+                    .map(|denom| native_asset(denom.to_string(), amount.into()))
+                    .collect(),
+                slippage_tolerance: Some(Decimal::percent(50)),
+                auto_stake: None,
+                receiver: None,
+                min_lp_to_receive: None,
+                staking_target: None,
+                deadline: None,
+            };
+            let funds = DENOMS
+                .iter()
+                .map(|denom| coin(amount, *denom))
+                .collect::<Vec<_>>();
+            (msg, funds)
+        };
+
+        let mut measurements = vec![];
+
+        let (msg, funds) = provide(1_000_000_000000);
+        self.measure(&pair, &msg, &funds)?;
+
+        let (msg, funds) = provide(1_000_000000);
+        measurements.push((
+            format!("{name}/provide_liquidity"),
+            self.measure(&pair, &msg, &funds)?,
+        ));
+
+        let swap = ExecuteMsg::Swap {
+            offer_asset: native_asset(DENOMS[0].to_string(), 1_000000u128.into()),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+            deadline: None,
+        };
+        measurements.push((
+            format!("{name}/swap"),
+            self.measure(&pair, &swap, &[coin(1_000000, DENOMS[0])])?,
+        ));
+
+        let lp_balance = self
+            .app
+            .wrap()
+            .query_balance(&self.owner, &pair_info.liquidity_token)?
+            .amount;
+        let withdraw = ExecuteMsg::WithdrawLiquidity {
+            assets: vec![],
+            min_assets_to_receive: None,
+            deadline: None,
+        };
+        measurements.push((
+            format!("{name}/withdraw_liquidity"),
+            self.measure(
+                &pair,
+                &withdraw,
+                &[coin(
+                    lp_balance.u128() / 100,
+                    pair_info.liquidity_token.as_str(),
+                )],
+            )?,
+        ));
+
+        Ok(measurements)
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{Order, Record, Storage};
+
+// Cosmos SDK KV store gas config
+// https://github.com/cosmos/cosmos-sdk/blob/47f46643affd7ec7978329c42bac47275ac7e1cc/store/types/gas.go#L199
+const READ_COST_FLAT: u64 = 1000;
+const READ_COST_PER_BYTE: u64 = 3;
+const WRITE_COST_FLAT: u64 = 2000;
+const WRITE_COST_PER_BYTE: u64 = 30;
+const DELETE_COST: u64 = 1000;
+const ITER_NEXT_COST_FLAT: u64 = 30;
+
+/// Shared counter of consumed gas.
+#[derive(Clone, Default)]
+pub struct GasMeter(Rc<Cell<u64>>);
+
+impl GasMeter {
+    pub fn consumed(&self) -> u64 {
+        self.0.get()
+    }
+
+    fn consume(&self, amount: u64) {
+        self.0.set(self.0.get() + amount)
+    }
+}
+
+/// In-memory storage which charges gas for every operation the same way the SDK KV store does.
+/// Wasm execution gas is not metered, thus budgets reflect storage access patterns only.
+#[derive(Default)]
+pub struct GasMeteredStorage {
+    inner: MockStorage,
+    meter: GasMeter,
+}
+
+impl GasMeteredStorage {
+    pub fn new(meter: GasMeter) -> Self {
+        Self {
+            inner: MockStorage::default(),
+            meter,
+        }
+    }
+}
+
+impl Storage for GasMeteredStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        let len = key.len() + value.as_ref().map_or(0, Vec::len);
+        self.meter
+            .consume(READ_COST_FLAT + READ_COST_PER_BYTE * len as u64);
+        value
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let meter = self.meter.clone();
+        Box::new(
+            self.inner
+                .range(start, end, order)
+                .inspect(move |(key, value)| {
+                    let len = key.len() + value.len();
+                    meter.consume(ITER_NEXT_COST_FLAT + READ_COST_PER_BYTE * len as u64)
+                }),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let len = key.len() + value.len();
+        self.meter
+            .consume(WRITE_COST_FLAT + WRITE_COST_PER_BYTE * len as u64);
+        self.inner.set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.meter.consume(DELETE_COST);
+        self.inner.remove(key)
+    }
+}